@@ -1,7 +1,52 @@
 fn main() {
     tauri_build::build();
-    
+
     // Embed version info from centralized config
     println!("cargo:rustc-env=CARGO_PKG_VERSION=2.7.0");
     println!("cargo:rustc-env=CARGO_PKG_NAME=TommyMemoryCleaner");
+
+    embed_event_messages();
+}
+
+/// Compile windows/events.man into message resources so Event Viewer can
+/// render the event descriptions. Needs mc.exe and rc.exe (Windows SDK) on
+/// PATH; without them the events still carry their structured fields.
+fn embed_event_messages() {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    println!("cargo:rerun-if-changed=windows/events.man");
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() != Ok("windows") {
+        return;
+    }
+
+    let out_dir = match std::env::var("OUT_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => return,
+    };
+    let rc_file = out_dir.join("events.rc");
+    let res_file = out_dir.join("events.res");
+
+    let compiled = Command::new("mc")
+        .args(["-um", "-h"])
+        .arg(&out_dir)
+        .arg("-r")
+        .arg(&out_dir)
+        .arg("windows/events.man")
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+        && Command::new("rc")
+            .args(["/nologo", "/fo"])
+            .arg(&res_file)
+            .arg(&rc_file)
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(false);
+
+    if compiled {
+        println!("cargo:rustc-link-arg-bins={}", res_file.display());
+    } else {
+        println!("cargo:warning=mc.exe/rc.exe not found, Event Log messages are not embedded");
+    }
 }
@@ -0,0 +1,72 @@
+//! Minimal client for the Tommy Memory Cleaner SDK pipe.
+//!
+//! The full path of the calling executable (for `cargo run`, the built
+//! `target\debug\examples\tmc_client.exe`) must be listed in
+//! `sdk_allowed_clients` and `sdk_pipe_enabled` must be true in config.json.
+//!
+//! Usage:
+//!   cargo run --example tmc_client -- ping
+//!   cargo run --example tmc_client -- memory_info
+//!   cargo run --example tmc_client -- last_result
+//!   cargo run --example tmc_client -- optimize [Normal|Balanced|Gaming]
+//!   cargo run --example tmc_client -- optimize --areas STANDBY_LIST|WORKING_SET
+
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+
+const PIPE_NAME: &str = r"\\.\pipe\tmc";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let request = match args.first().map(|s| s.as_str()) {
+        Some("ping") | None => serde_json::json!({ "id": 1, "command": "ping" }),
+        Some(cmd @ ("memory_info" | "last_result")) => {
+            serde_json::json!({ "id": 1, "command": cmd })
+        }
+        Some("optimize") => match (args.get(1).map(|s| s.as_str()), args.get(2)) {
+            (Some("--areas"), Some(areas)) => {
+                serde_json::json!({ "id": 1, "command": "optimize", "areas": areas })
+            }
+            (Some(profile), _) => {
+                serde_json::json!({ "id": 1, "command": "optimize", "profile": profile })
+            }
+            (None, _) => serde_json::json!({ "id": 1, "command": "optimize" }),
+        },
+        Some(other) => {
+            eprintln!(
+                "Unknown command: {} (use ping, memory_info, last_result or optimize)",
+                other
+            );
+            std::process::exit(2);
+        }
+    };
+
+    let mut pipe = match OpenOptions::new().read(true).write(true).open(PIPE_NAME) {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Cannot connect to {}: {} (is the SDK pipe enabled?)", PIPE_NAME, e);
+            std::process::exit(1);
+        }
+    };
+
+    // One request line, one response line
+    if let Err(e) = writeln!(pipe, "{}", request) {
+        eprintln!("Failed to send request: {}", e);
+        std::process::exit(1);
+    }
+
+    let mut response = String::new();
+    if let Err(e) = BufReader::new(pipe).read_line(&mut response) {
+        eprintln!("Failed to read response: {}", e);
+        std::process::exit(1);
+    }
+
+    let ok = serde_json::from_str::<serde_json::Value>(&response)
+        .ok()
+        .and_then(|v| v.get("ok").and_then(|ok| ok.as_bool()))
+        .unwrap_or(false);
+
+    print!("{}", response);
+    std::process::exit(if ok { 0 } else { 1 });
+}
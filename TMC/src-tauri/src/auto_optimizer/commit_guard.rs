@@ -0,0 +1,155 @@
+/// Commit charge warning
+///
+/// Applications crash with "out of memory" when the commit charge reaches the
+/// commit limit (RAM + page file), even with physical RAM to spare. While
+/// `commit_warning_percent` is set, the charge is sampled and a notification
+/// is shown once it crosses that share of the limit. The UI gets the same
+/// warning as an event and offers a modified page list flush; the toast links
+/// to the page file sizing advice, the only lasting fix.
+use crate::config::SharedConfig;
+use crate::engine::Engine;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const PAGEFILE_ADVICE_URL: &str =
+    "https://learn.microsoft.com/troubleshoot/windows-client/performance/how-to-determine-the-appropriate-page-file-size-for-64-bit-versions-of-windows";
+/// Payload: `CommitWarning`
+pub const EV_COMMIT_PRESSURE: &str = "commit-pressure";
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+/// The warning re-arms once the charge drops this many points below the threshold
+const REARM_MARGIN: u8 = 5;
+
+static MONITOR_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitWarning {
+    pub used_percent: u8,
+    pub used_bytes: u64,
+    pub limit_bytes: u64,
+    pub threshold: u8,
+}
+
+/// Fires once per crossing of the threshold
+#[derive(Debug)]
+pub struct CommitGuard {
+    armed: bool,
+}
+
+impl Default for CommitGuard {
+    fn default() -> Self {
+        Self { armed: true }
+    }
+}
+
+impl CommitGuard {
+    /// `true` when this sample should raise the warning
+    pub fn update(&mut self, used_percent: u8, threshold: u8) -> bool {
+        if used_percent >= threshold {
+            return std::mem::replace(&mut self.armed, false);
+        }
+        if used_percent < threshold.saturating_sub(REARM_MARGIN) {
+            self.armed = true;
+        }
+        false
+    }
+}
+
+/// Warning threshold, `None` while the warning is off
+fn read_threshold(cfg: &SharedConfig) -> Option<u8> {
+    Some(cfg.read().commit_warning_percent).filter(|p| *p > 0)
+}
+
+fn notify(app: &AppHandle, cfg: &SharedConfig, warning: &CommitWarning) {
+    let _ = app.emit(EV_COMMIT_PRESSURE, warning);
+
+    let (show, theme) = cfg.with(|c| (c.show_opt_notifications, c.theme.clone()));
+    if !show {
+        return;
+    }
+    let title = crate::i18n::tr("TMC • Commit charge high");
+    let body = crate::i18n::tr_args(
+        "Commit charge is at %s of the limit",
+        &[format!("{}%", warning.used_percent).into()],
+    );
+    let label = crate::i18n::tr("Page file advice");
+    if let Err(e) = crate::notifications::show_windows_notification_with_link(
+        app,
+        &title,
+        &body,
+        &theme,
+        Some((&label, PAGEFILE_ADVICE_URL)),
+    ) {
+        tracing::debug!("Failed to show commit charge notification: {}", e);
+    }
+}
+
+/// Start sampling the commit charge if the warning is enabled and not already running
+pub fn start_commit_monitor(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    if read_threshold(&cfg).is_none() {
+        return;
+    }
+    if MONITOR_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("Commit monitor already running");
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-commit-guard".into())
+        .spawn(move || {
+            let mut guard = CommitGuard::default();
+            // Re-read the threshold every sample so setting it to 0 stops the thread
+            while let Some(threshold) = read_threshold(&cfg) {
+                if let Ok(mem) = engine.memory() {
+                    let used_percent = mem.commit.used.percentage;
+                    if guard.update(used_percent, threshold) {
+                        tracing::warn!(
+                            "Commit charge at {}% of the limit ({} / {} bytes)",
+                            used_percent,
+                            mem.commit.used.bytes,
+                            mem.commit.total.bytes
+                        );
+                        notify(
+                            &app,
+                            &cfg,
+                            &CommitWarning {
+                                used_percent,
+                                used_bytes: mem.commit.used.bytes,
+                                limit_bytes: mem.commit.total.bytes,
+                                threshold,
+                            },
+                        );
+                    }
+                }
+                std::thread::sleep(SAMPLE_INTERVAL);
+            }
+            MONITOR_RUNNING.store(false, Ordering::SeqCst);
+            tracing::info!("Commit monitor stopped");
+        });
+
+    if let Err(e) = spawned {
+        MONITOR_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start commit monitor thread: {}", e);
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_guard_fires_once_per_crossing() {
+        let mut guard = CommitGuard::default();
+        assert!(!guard.update(80, 90));
+        assert!(guard.update(91, 90));
+        assert!(!guard.update(95, 90));
+        // Still within the re-arm margin
+        assert!(!guard.update(87, 90));
+        assert!(!guard.update(92, 90));
+        assert!(!guard.update(84, 90));
+        assert!(guard.update(90, 90));
+    }
+}
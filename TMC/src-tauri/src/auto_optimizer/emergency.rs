@@ -0,0 +1,263 @@
+/// Low memory emergency mode
+///
+/// A second, critical free memory threshold below `auto_opt_free_threshold`.
+/// When free physical memory drops below it the auto optimizer purges the
+/// standby list and trims the working sets right away as an `Emergency` run:
+/// the low memory cooldown, the idle wait, the locked session and battery
+/// settings, area cooldowns and the free RAM floor do not hold it back. If
+/// memory is still critical afterwards and `prompt_close_top` is set, the user
+/// is asked whether to close the process using the most memory.
+use crate::config::SharedConfig;
+use crate::engine::Engine;
+use crate::memory::types::{Areas, ProcessMemoryEntry, ProcessSortKey, Reason};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::AppHandle;
+
+/// What an emergency run frees: everything cached, then the working sets
+pub const EMERGENCY_AREAS: Areas = Areas::STANDBY_LIST.union(Areas::WORKING_SET);
+
+/// The trigger re-arms once free memory is this many points above the threshold
+const REARM_MARGIN: u8 = 3;
+/// Processes looked at when choosing the one to offer closing
+const CLOSE_CANDIDATES: usize = 10;
+
+/// A close prompt is on screen
+static PROMPT_OPEN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmergencyMode {
+    pub enabled: bool,
+    /// Free physical memory (percent) below which the emergency path runs
+    pub free_threshold: u8,
+    /// Offer to close the process using the most memory when the run was not enough
+    pub prompt_close_top: bool,
+}
+
+impl Default for EmergencyMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            free_threshold: 5,
+            prompt_close_top: false,
+        }
+    }
+}
+
+impl EmergencyMode {
+    pub fn validate(&mut self) {
+        self.free_threshold = self.free_threshold.clamp(1, 50);
+    }
+}
+
+/// Fires once per drop below the threshold
+#[derive(Debug)]
+pub struct EmergencyGuard {
+    armed: bool,
+}
+
+impl Default for EmergencyGuard {
+    fn default() -> Self {
+        Self { armed: true }
+    }
+}
+
+impl EmergencyGuard {
+    /// `true` when this sample should start an emergency run
+    pub fn update(&mut self, free_percent: u8, threshold: u8) -> bool {
+        if free_percent < threshold {
+            return std::mem::replace(&mut self.armed, false);
+        }
+        if free_percent >= threshold.saturating_add(REARM_MARGIN) {
+            self.armed = true;
+        }
+        false
+    }
+}
+
+/// Start an emergency run in the background, then prompt if memory is still critical
+pub fn spawn_emergency(app: &AppHandle, engine: &Engine, cfg: &SharedConfig, mode: EmergencyMode) {
+    let app = app.clone();
+    let engine = engine.clone();
+    let cfg = cfg.clone();
+
+    tauri::async_runtime::spawn(async move {
+        let result = crate::perform_optimization(
+            app.clone(),
+            engine.clone(),
+            cfg,
+            Reason::Emergency,
+            true,
+            Some(EMERGENCY_AREAS),
+            None,
+        )
+        .await;
+        if result.is_none() || !mode.prompt_close_top {
+            return;
+        }
+
+        let free_percent = match engine.memory() {
+            Ok(mem) => mem.physical.free.percentage,
+            Err(_) => return,
+        };
+        if free_percent >= mode.free_threshold {
+            return;
+        }
+        tracing::warn!(
+            "Memory still critical after the emergency run ({}% free)",
+            free_percent
+        );
+        let _ = tauri::async_runtime::spawn_blocking(prompt_close_top_consumer).await;
+    });
+}
+
+/// Process using the most private memory that may be closed
+fn top_consumer() -> Option<ProcessMemoryEntry> {
+    let own_pid = std::process::id();
+    let session = crate::system::sessions::current_session_id();
+    crate::memory::process_list::process_memory_list(ProcessSortKey::PrivateBytes, CLOSE_CANDIDATES)
+        .ok()?
+        .into_iter()
+        .find(|p| {
+            p.pid != own_pid
+                && !crate::memory::critical_processes::is_critical_process(&p.name)
+                && crate::memory::protection::protection_reason(p.pid, &p.name).is_none()
+                && !session
+                    .is_some_and(|s| crate::system::sessions::in_other_user_session(p.pid, s))
+        })
+}
+
+/// Ask whether to close the top memory consumer and close it on yes
+fn prompt_close_top_consumer() {
+    if PROMPT_OPEN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    if let Some(process) = top_consumer() {
+        let title = crate::i18n::tr("TMC • Memory critically low");
+        let body = crate::i18n::tr_args(
+            "%s is using %s. Close it to free memory? Unsaved work in it may be lost.",
+            &[
+                process.name.clone().into(),
+                format!("{:.1} GB", process.private_bytes as f64 / 1024f64.powi(3)).into(),
+            ],
+        );
+        if confirm(&title, &body) {
+            match close_process(process.pid) {
+                Ok(()) => tracing::warn!(
+                    "Closed {} (PID {}) to relieve critical memory",
+                    process.name,
+                    process.pid
+                ),
+                Err(e) => crate::logging::error_bus::report(
+                    crate::logging::error_bus::Subsystem::Scheduler,
+                    format!("Cannot close {}: {}", process.name, e),
+                ),
+            }
+        } else {
+            tracing::info!("Closing {} declined", process.name);
+        }
+    }
+    PROMPT_OPEN.store(false, Ordering::SeqCst);
+}
+
+/// Yes/No message box on top of every window
+#[cfg(windows)]
+fn confirm(title: &str, body: &str) -> bool {
+    use crate::utils::to_wide;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, IDYES, MB_ICONWARNING, MB_SETFOREGROUND, MB_TOPMOST, MB_YESNO,
+    };
+
+    let title = to_wide(title);
+    let body = to_wide(body);
+    let answer = unsafe {
+        MessageBoxW(
+            std::ptr::null_mut(),
+            body.as_ptr(),
+            title.as_ptr(),
+            MB_YESNO | MB_ICONWARNING | MB_TOPMOST | MB_SETFOREGROUND,
+        )
+    };
+    answer == IDYES
+}
+
+#[cfg(not(windows))]
+fn confirm(_title: &str, _body: &str) -> bool {
+    false
+}
+
+/// Ask the windows of a process to close, so it can save its work; a
+/// process without a visible window is terminated
+#[cfg(windows)]
+fn close_process(pid: u32) -> anyhow::Result<()> {
+    use windows_sys::Win32::Foundation::{CloseHandle, HWND, LPARAM};
+    use windows_sys::Win32::System::Threading::{OpenProcess, TerminateProcess, PROCESS_TERMINATE};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetWindowThreadProcessId, IsWindowVisible, PostMessageW, WM_CLOSE,
+    };
+
+    struct Search {
+        pid: u32,
+        closed: usize,
+    }
+
+    unsafe extern "system" fn close_window(hwnd: HWND, lparam: LPARAM) -> i32 {
+        let search = &mut *(lparam as *mut Search);
+        let mut owner = 0u32;
+        GetWindowThreadProcessId(hwnd, &mut owner);
+        if owner == search.pid
+            && IsWindowVisible(hwnd) != 0
+            && PostMessageW(hwnd, WM_CLOSE, 0, 0) != 0
+        {
+            search.closed += 1;
+        }
+        1
+    }
+
+    let mut search = Search { pid, closed: 0 };
+    unsafe {
+        EnumWindows(Some(close_window), &mut search as *mut Search as LPARAM);
+    }
+    if search.closed > 0 {
+        return Ok(());
+    }
+
+    unsafe {
+        let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+        if handle.is_null() {
+            anyhow::bail!("OpenProcess failed: {}", std::io::Error::last_os_error());
+        }
+        let terminated = TerminateProcess(handle, 1) != 0;
+        let error = std::io::Error::last_os_error();
+        CloseHandle(handle);
+        if !terminated {
+            anyhow::bail!("TerminateProcess failed: {}", error);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn close_process(_pid: u32) -> anyhow::Result<()> {
+    anyhow::bail!("Closing processes is only supported on Windows")
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emergency_guard_fires_once_per_drop() {
+        let mut guard = EmergencyGuard::default();
+        assert!(!guard.update(12, 5));
+        assert!(guard.update(4, 5));
+        assert!(!guard.update(3, 5));
+        // Still within the re-arm margin
+        assert!(!guard.update(6, 5));
+        assert!(!guard.update(4, 5));
+        assert!(!guard.update(8, 5));
+        assert!(guard.update(2, 5));
+    }
+}
@@ -0,0 +1,318 @@
+/// Automatic Gaming profile while a game runs
+///
+/// A game is a running process listed in `game_processes` or, with
+/// `detect_fullscreen`, any full-screen Direct3D application. While one is
+/// detected the Gaming profile is active; the previous profile comes back
+/// when the game is gone.
+///
+/// A crashed or hung game can leave its process or the full-screen state
+/// behind, so the 3D engines of the GPU are watched as a second signal: once
+/// they stay below `gpu_idle_percent` for `gpu_idle_minutes` the previous
+/// profile is restored as well, and the same game does not switch again
+/// until it has gone away.
+use super::gpu::GpuSampler;
+use crate::config::SharedConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const GAMING_PROFILE: &str = "Gaming";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameMode {
+    pub enabled: bool,
+    /// Executables that count as games (lowercase, without `.exe`)
+    pub game_processes: BTreeSet<String>,
+    /// Also treat any full-screen Direct3D application as a game
+    pub detect_fullscreen: bool,
+    /// Minutes of idle GPU after which the previous profile is restored (0 = off)
+    pub gpu_idle_minutes: u32,
+    /// 3D engine utilization below which the GPU counts as idle
+    pub gpu_idle_percent: u8,
+}
+
+impl Default for GameMode {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            game_processes: BTreeSet::new(),
+            detect_fullscreen: true,
+            gpu_idle_minutes: 3,
+            gpu_idle_percent: 5,
+        }
+    }
+}
+
+impl GameMode {
+    pub fn validate(&mut self) {
+        self.game_processes = self
+            .game_processes
+            .iter()
+            .map(|name| {
+                let name = name.trim().to_lowercase();
+                name.strip_suffix(".exe").unwrap_or(&name).to_string()
+            })
+            .filter(|name| !name.is_empty())
+            .collect();
+        self.gpu_idle_minutes = self.gpu_idle_minutes.min(60);
+        self.gpu_idle_percent = self.gpu_idle_percent.clamp(1, 50);
+    }
+
+    fn gpu_idle_after(&self) -> Option<Duration> {
+        (self.gpu_idle_minutes > 0).then(|| Duration::from_secs(self.gpu_idle_minutes as u64 * 60))
+    }
+}
+
+/// What the watcher does after a poll
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameModeAction {
+    None,
+    /// Switch to the Gaming profile
+    Enter,
+    /// Restore the previous profile
+    Revert(RevertReason),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RevertReason {
+    GameExited,
+    GpuIdle,
+}
+
+/// Switching state, kept between polls
+#[derive(Debug, Default)]
+pub struct GameModeState {
+    /// Profile to go back to, set while Gaming was activated automatically
+    previous: Option<String>,
+    /// When the GPU was last seen going idle
+    gpu_idle_since: Option<Instant>,
+    /// Reverted on an idle GPU while the game was still detected
+    held_off: bool,
+}
+
+impl GameModeState {
+    pub fn is_active(&self) -> bool {
+        self.previous.is_some()
+    }
+
+    /// Decide what to do with the latest signals
+    ///
+    /// `gpu_busy` is `None` when no utilization is available yet; an unknown
+    /// GPU never reverts.
+    pub fn update(
+        &mut self,
+        game_detected: bool,
+        gpu_busy: Option<bool>,
+        gpu_idle_after: Option<Duration>,
+        now: Instant,
+    ) -> GameModeAction {
+        if !self.is_active() {
+            if !game_detected {
+                self.held_off = false;
+            }
+            return if game_detected && !self.held_off {
+                GameModeAction::Enter
+            } else {
+                GameModeAction::None
+            };
+        }
+
+        if !game_detected {
+            return GameModeAction::Revert(RevertReason::GameExited);
+        }
+        match (gpu_busy, gpu_idle_after) {
+            (Some(false), Some(after)) => {
+                let since = *self.gpu_idle_since.get_or_insert(now);
+                if now.duration_since(since) >= after {
+                    self.held_off = true;
+                    return GameModeAction::Revert(RevertReason::GpuIdle);
+                }
+            }
+            _ => self.gpu_idle_since = None,
+        }
+        GameModeAction::None
+    }
+
+    pub fn entered(&mut self, previous: String) {
+        self.previous = Some(previous);
+        self.gpu_idle_since = None;
+    }
+
+    /// Forget the automatic switch, returns the profile to restore
+    pub fn take_previous(&mut self) -> Option<String> {
+        self.gpu_idle_since = None;
+        self.previous.take()
+    }
+}
+
+/// Whether a listed game or a full-screen Direct3D application is running
+fn game_detected(mode: &GameMode) -> bool {
+    if mode.detect_fullscreen && crate::system::game_mode::fullscreen_d3d_running() {
+        return true;
+    }
+    !mode.game_processes.is_empty()
+        && crate::memory::ops::process_list()
+            .iter()
+            .any(|(_, name)| mode.game_processes.contains(name))
+}
+
+/// Activate `name` and tell the tray and the UI
+fn switch_profile(app: &AppHandle, cfg: &SharedConfig, name: &str) -> bool {
+    match crate::commands::config::select_profile(cfg, name) {
+        Ok(_) => {
+            crate::ui::tray::refresh_tray_icon(app);
+            let _ = app.emit("config-changed", ());
+            true
+        }
+        Err(e) => {
+            tracing::warn!("Game mode: failed to activate profile '{}': {}", name, e);
+            false
+        }
+    }
+}
+
+/// Go back to the profile in use before the game, unless the user changed
+/// profile in the meantime
+fn restore_profile(app: &AppHandle, cfg: &SharedConfig, previous: &str) {
+    let current = cfg.with(|c| c.active_profile_name());
+    if !current.eq_ignore_ascii_case(GAMING_PROFILE) {
+        tracing::info!(
+            "Game mode: profile changed to '{}' meanwhile, keeping it",
+            current
+        );
+        return;
+    }
+    if switch_profile(app, cfg, previous) {
+        tracing::info!("Game mode: restored profile '{}'", previous);
+    }
+}
+
+/// Start the game mode watcher
+pub fn start_game_mode(app: AppHandle, cfg: SharedConfig) {
+    tauri::async_runtime::spawn(async move {
+        let mut state = GameModeState::default();
+        let mut gpu: Option<GpuSampler> = None;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let mode = cfg.with(|c| c.game_mode.clone());
+            if !mode.enabled {
+                if let Some(previous) = state.take_previous() {
+                    restore_profile(&app, &cfg, &previous);
+                }
+                gpu = None;
+                continue;
+            }
+
+            let detected = game_detected(&mode);
+            // The GPU only matters while the Gaming profile was switched on here
+            let gpu_busy = match (state.is_active(), mode.gpu_idle_after()) {
+                (true, Some(_)) => {
+                    let sampler = gpu.get_or_insert_with(GpuSampler::open);
+                    sampler
+                        .utilization()
+                        .map(|percent| percent >= mode.gpu_idle_percent as f64)
+                }
+                _ => {
+                    gpu = None;
+                    None
+                }
+            };
+
+            match state.update(detected, gpu_busy, mode.gpu_idle_after(), Instant::now()) {
+                GameModeAction::None => {}
+                GameModeAction::Enter => {
+                    let previous = cfg.with(|c| c.active_profile_name());
+                    if previous.eq_ignore_ascii_case(GAMING_PROFILE) {
+                        // Already on Gaming by choice, nothing to restore later
+                        continue;
+                    }
+                    if switch_profile(&app, &cfg, GAMING_PROFILE) {
+                        tracing::info!("Game mode: game detected, switched from '{}'", previous);
+                        state.entered(previous);
+                    }
+                }
+                GameModeAction::Revert(reason) => {
+                    match reason {
+                        RevertReason::GameExited => tracing::info!("Game mode: game exited"),
+                        RevertReason::GpuIdle => tracing::info!(
+                            "Game mode: GPU idle for {} min, game considered gone",
+                            mode.gpu_idle_minutes
+                        ),
+                    }
+                    if let Some(previous) = state.take_previous() {
+                        restore_profile(&app, &cfg, &previous);
+                    }
+                    gpu = None;
+                }
+            }
+        }
+    });
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverts_when_game_exits_or_gpu_stays_idle() {
+        let idle_after = Some(Duration::from_secs(180));
+        let t0 = Instant::now();
+        let mut state = GameModeState::default();
+
+        assert_eq!(
+            state.update(false, None, idle_after, t0),
+            GameModeAction::None
+        );
+        assert_eq!(
+            state.update(true, None, idle_after, t0),
+            GameModeAction::Enter
+        );
+        state.entered("Balanced".to_string());
+        assert_eq!(
+            state.update(false, Some(true), idle_after, t0),
+            GameModeAction::Revert(RevertReason::GameExited)
+        );
+        assert_eq!(state.take_previous().as_deref(), Some("Balanced"));
+
+        // Game still detected but the GPU went idle: revert once, then wait
+        // for the game to go away before switching again
+        state.entered("Normal".to_string());
+        let t1 = t0 + Duration::from_secs(60);
+        assert_eq!(
+            state.update(true, Some(false), idle_after, t1),
+            GameModeAction::None
+        );
+        assert_eq!(
+            state.update(true, Some(true), idle_after, t1 + Duration::from_secs(200)),
+            GameModeAction::None
+        );
+        let t2 = t1 + Duration::from_secs(300);
+        assert_eq!(
+            state.update(true, Some(false), idle_after, t2),
+            GameModeAction::None
+        );
+        assert_eq!(
+            state.update(true, Some(false), idle_after, t2 + Duration::from_secs(180)),
+            GameModeAction::Revert(RevertReason::GpuIdle)
+        );
+        assert_eq!(state.take_previous().as_deref(), Some("Normal"));
+        assert_eq!(
+            state.update(true, None, idle_after, t2),
+            GameModeAction::None
+        );
+        assert_eq!(
+            state.update(false, None, idle_after, t2),
+            GameModeAction::None
+        );
+        assert_eq!(
+            state.update(true, None, idle_after, t2),
+            GameModeAction::Enter
+        );
+    }
+}
@@ -0,0 +1,50 @@
+/// Gentle working set maintenance (experimental)
+///
+/// Emptying a working set makes the process fault every page back in at
+/// once, which shows up as stutter in games and other interactive apps. For
+/// the processes in `gentle_maintenance_processes` the auto optimizer instead
+/// runs a pass every `gentle_maintenance_interval_secs` that releases only a
+/// small share of each working set through soft limits and lowers the memory
+/// priority, so the pages that stay idle leave first when memory gets short.
+use crate::config::{Config, OtherSessionsPolicy};
+
+pub const DEFAULT_INTERVAL_SECS: u32 = 120;
+pub const MIN_INTERVAL_SECS: u32 = 30;
+pub const MAX_INTERVAL_SECS: u32 = 3600;
+/// Share of the working set released per pass
+const STEP_PERCENT: u8 = 10;
+/// Working sets under this size are left alone
+const MIN_WORKING_SET_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Run one maintenance pass over the configured processes
+pub fn run_pass(cfg: &Config) {
+    let names: Vec<String> = cfg
+        .gentle_maintenance_processes
+        .iter()
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+    let advised = match crate::memory::ops::gentle_working_set_advice(
+        &names,
+        &cfg.process_exclusion_list_lower(),
+        cfg.allow_system_process_trim,
+        STEP_PERCENT,
+        MIN_WORKING_SET_BYTES,
+        cfg.other_sessions_policy == OtherSessionsPolicy::SkipOtherSessions,
+    ) {
+        Ok(advised) => advised,
+        Err(e) => {
+            tracing::warn!("Gentle maintenance pass failed: {}", e);
+            return;
+        }
+    };
+
+    let released: u64 = advised
+        .iter()
+        .map(|p| p.before_bytes.saturating_sub(p.after_bytes))
+        .sum();
+    tracing::debug!(
+        "Gentle maintenance: {} processes advised, {} bytes released",
+        advised.len(),
+        released
+    );
+}
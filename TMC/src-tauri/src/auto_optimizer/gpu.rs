@@ -0,0 +1,133 @@
+/// GPU 3D engine utilization
+///
+/// Read from the `GPU Engine` performance counters, summed over every 3D
+/// engine instance. Rates need two samples, so the first reading after
+/// `GpuSampler::open` is `None`.
+#[cfg(windows)]
+use windows_sys::Win32::System::Performance::{PDH_HCOUNTER, PDH_HQUERY};
+
+#[cfg(windows)]
+const GPU_3D_COUNTER: &str = r"\GPU Engine(*engtype_3D)\Utilization Percentage";
+
+#[cfg(windows)]
+pub struct GpuSampler {
+    /// Query and counter, `None` if the counter is not available
+    handles: Option<(PDH_HQUERY, PDH_HCOUNTER)>,
+}
+
+// PDH handles are only used by the thread that owns the sampler at a time
+#[cfg(windows)]
+unsafe impl Send for GpuSampler {}
+
+#[cfg(windows)]
+impl GpuSampler {
+    pub fn open() -> Self {
+        use windows_sys::Win32::System::Performance::{
+            PdhAddEnglishCounterW, PdhCloseQuery, PdhCollectQueryData, PdhOpenQueryW,
+        };
+
+        let path: Vec<u16> = GPU_3D_COUNTER.encode_utf16().chain(Some(0)).collect();
+        unsafe {
+            let mut query: PDH_HQUERY = std::mem::zeroed();
+            if PdhOpenQueryW(std::ptr::null(), 0, &mut query) != 0 {
+                tracing::debug!("GPU utilization unavailable: cannot open a PDH query");
+                return Self { handles: None };
+            }
+            let mut counter: PDH_HCOUNTER = std::mem::zeroed();
+            if PdhAddEnglishCounterW(query, path.as_ptr(), 0, &mut counter) != 0 {
+                tracing::debug!("GPU utilization unavailable: no GPU Engine counters");
+                PdhCloseQuery(query);
+                return Self { handles: None };
+            }
+            // First sample, the rate is available from the next one
+            PdhCollectQueryData(query);
+            Self {
+                handles: Some((query, counter)),
+            }
+        }
+    }
+
+    /// Current 3D utilization in percent, `None` if it cannot be read
+    pub fn utilization(&mut self) -> Option<f64> {
+        use windows_sys::Win32::System::Performance::{
+            PdhCollectQueryData, PdhGetFormattedCounterArrayW, PDH_CSTATUS_NEW_DATA,
+            PDH_CSTATUS_VALID_DATA, PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE, PDH_FMT_NOCAP100,
+            PDH_MORE_DATA,
+        };
+
+        let (query, counter) = self.handles?;
+        unsafe {
+            if PdhCollectQueryData(query) != 0 {
+                return None;
+            }
+
+            let format = PDH_FMT_DOUBLE | PDH_FMT_NOCAP100;
+            let mut size = 0u32;
+            let mut count = 0u32;
+            let status = PdhGetFormattedCounterArrayW(
+                counter,
+                format,
+                &mut size,
+                &mut count,
+                std::ptr::null_mut(),
+            );
+            if status != PDH_MORE_DATA || size == 0 {
+                return None;
+            }
+
+            // Item array followed by the instance names, aligned for the items
+            let item_size = std::mem::size_of::<PDH_FMT_COUNTERVALUE_ITEM_W>();
+            let mut buffer: Vec<PDH_FMT_COUNTERVALUE_ITEM_W> =
+                Vec::with_capacity((size as usize).div_ceil(item_size));
+            if PdhGetFormattedCounterArrayW(
+                counter,
+                format,
+                &mut size,
+                &mut count,
+                buffer.as_mut_ptr(),
+            ) != 0
+            {
+                return None;
+            }
+            let items = std::slice::from_raw_parts(buffer.as_ptr(), count as usize);
+
+            // Invalid instances (a process that just exited) are skipped
+            let total: f64 = items
+                .iter()
+                .filter(|item| {
+                    matches!(
+                        item.FmtValue.CStatus,
+                        PDH_CSTATUS_VALID_DATA | PDH_CSTATUS_NEW_DATA
+                    )
+                })
+                .map(|item| item.FmtValue.Anonymous.doubleValue)
+                .sum();
+            Some(total.clamp(0.0, 100.0))
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for GpuSampler {
+    fn drop(&mut self) {
+        if let Some((query, _)) = self.handles.take() {
+            unsafe {
+                windows_sys::Win32::System::Performance::PdhCloseQuery(query);
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub struct GpuSampler;
+
+#[cfg(not(windows))]
+impl GpuSampler {
+    pub fn open() -> Self {
+        Self
+    }
+
+    pub fn utilization(&mut self) -> Option<f64> {
+        None
+    }
+}
@@ -0,0 +1,39 @@
+/// User idle detection
+///
+/// Automatic optimizations can be restricted to moments when the user is
+/// away, so trimming never causes stutter during active use or gaming. Idle
+/// time is the time since the last keyboard or mouse input of the session.
+use std::time::Duration;
+
+/// Time since the last user input, `None` if it cannot be determined
+#[cfg(windows)]
+pub fn idle_duration() -> Option<Duration> {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    unsafe {
+        if GetLastInputInfo(&mut info) == 0 {
+            return None;
+        }
+        // Both values are 32-bit tick counts; wrapping_sub handles the 49.7 day rollover
+        let idle_ms = GetTickCount().wrapping_sub(info.dwTime);
+        Some(Duration::from_millis(idle_ms as u64))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn idle_duration() -> Option<Duration> {
+    None
+}
+
+/// Whether the user has been idle for at least `minutes`
+///
+/// When idle time is unavailable the user is treated as active, so runs that
+/// are restricted to idle periods are deferred rather than forced.
+pub fn is_idle_for(minutes: u32) -> bool {
+    idle_duration().is_some_and(|idle| idle >= Duration::from_secs(minutes as u64 * 60))
+}
@@ -2,7 +2,18 @@
 ///
 /// This module provides scheduled memory optimization functionality,
 /// allowing the application to automatically clean memory at configured
-/// intervals to maintain system performance.
+/// intervals or cron-like rules to maintain system performance.
+pub mod commit_guard;
+pub mod emergency;
+pub mod game_mode;
+pub mod gentle_maintenance;
+pub mod gpu;
+pub mod idle;
+pub mod prediction;
+pub mod pressure;
+pub mod schedule;
 pub mod scheduler;
+pub mod session;
 
+pub use game_mode::start_game_mode;
 pub use scheduler::start_auto_optimizer;
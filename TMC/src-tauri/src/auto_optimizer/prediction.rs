@@ -0,0 +1,184 @@
+/// Memory pressure prediction
+///
+/// Instead of waiting for free RAM to drop below `auto_opt_free_threshold`,
+/// the auto optimizer samples free physical memory and free commit, fits a
+/// least-squares line over the latest samples and optimizes early when the
+/// trend crosses the threshold within the prediction window.
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PressurePrediction {
+    pub enabled: bool,
+    /// Seconds between memory samples
+    pub sample_interval_secs: u32,
+    /// Optimize when the threshold is predicted to be reached within this many minutes
+    pub window_minutes: u32,
+    /// 1 (steady trends only) to 10 (react to short spikes)
+    pub sensitivity: u8,
+}
+
+impl Default for PressurePrediction {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sample_interval_secs: 10,
+            window_minutes: 5,
+            sensitivity: 5,
+        }
+    }
+}
+
+impl PressurePrediction {
+    pub fn validate(&mut self) {
+        self.sample_interval_secs = self.sample_interval_secs.clamp(5, 60);
+        self.window_minutes = self.window_minutes.clamp(1, 60);
+        self.sensitivity = self.sensitivity.clamp(1, 10);
+    }
+
+    /// Samples used for the slope: high sensitivity looks at a shorter history
+    pub fn samples_for_fit(&self) -> usize {
+        (14 - self.sensitivity.clamp(1, 10) as usize).max(4)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    at_secs: f64,
+    free_percent: f64,
+    commit_free_percent: f64,
+}
+
+/// Outcome of a prediction
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forecast {
+    /// `RAM` or `Commit`
+    pub series: &'static str,
+    /// Percent per minute (negative when memory is shrinking)
+    pub slope_per_minute: f64,
+    pub minutes_to_threshold: f64,
+}
+
+/// Rolling window of memory samples
+#[derive(Debug, Clone)]
+pub struct PressureTrend {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl PressureTrend {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(2),
+        }
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(2);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn push(&mut self, at_secs: f64, free_percent: f64, commit_free_percent: f64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(Sample {
+            at_secs,
+            free_percent,
+            commit_free_percent,
+        });
+    }
+
+    /// Forget the history, e.g. after an optimization changed the baseline
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.samples.len() >= self.capacity
+    }
+
+    /// Least-squares slope in percent per minute
+    fn slope(&self, value: impl Fn(&Sample) -> f64) -> Option<f64> {
+        let n = self.samples.len() as f64;
+        if n < 2.0 {
+            return None;
+        }
+        let mean_t = self.samples.iter().map(|s| s.at_secs).sum::<f64>() / n;
+        let mean_v = self.samples.iter().map(&value).sum::<f64>() / n;
+
+        let (mut num, mut den) = (0.0, 0.0);
+        for s in &self.samples {
+            let dt = s.at_secs - mean_t;
+            num += dt * (value(s) - mean_v);
+            den += dt * dt;
+        }
+        if den <= f64::EPSILON {
+            return None;
+        }
+        Some(num / den * 60.0)
+    }
+
+    /// Earliest predicted crossing of `threshold_percent` by free RAM or free commit
+    pub fn forecast(&self, threshold_percent: f64) -> Option<Forecast> {
+        if !self.is_full() {
+            return None;
+        }
+        let last = self.samples.back()?;
+
+        let ram = self
+            .slope(|s| s.free_percent)
+            .map(|slope| ("RAM", slope, last.free_percent));
+        let commit = self
+            .slope(|s| s.commit_free_percent)
+            .map(|slope| ("Commit", slope, last.commit_free_percent));
+
+        [ram, commit]
+            .into_iter()
+            .flatten()
+            .filter(|(_, slope, current)| *slope < 0.0 && *current > threshold_percent)
+            .map(|(series, slope, current)| Forecast {
+                series,
+                slope_per_minute: slope,
+                minutes_to_threshold: (current - threshold_percent) / -slope,
+            })
+            .min_by(|a, b| a.minutes_to_threshold.total_cmp(&b.minutes_to_threshold))
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_forecast_falling_memory() {
+        let mut trend = PressureTrend::new(6);
+        // Free RAM drops 1% every 10 seconds (6%/min), commit is flat
+        for i in 0..6 {
+            trend.push(i as f64 * 10.0, 60.0 - i as f64, 70.0);
+        }
+        let forecast = trend.forecast(30.0).unwrap();
+        assert_eq!(forecast.series, "RAM");
+        assert!((forecast.slope_per_minute + 6.0).abs() < 1e-9);
+        // 55% now, 25 points above the threshold at 6%/min
+        assert!((forecast.minutes_to_threshold - 25.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_forecast_when_stable_or_incomplete() {
+        let mut trend = PressureTrend::new(4);
+        trend.push(0.0, 50.0, 50.0);
+        trend.push(10.0, 40.0, 50.0);
+        assert_eq!(trend.forecast(30.0), None);
+
+        for i in 0..4 {
+            trend.push(i as f64 * 10.0, 50.0 + i as f64, 50.0);
+        }
+        assert_eq!(trend.forecast(30.0), None);
+    }
+}
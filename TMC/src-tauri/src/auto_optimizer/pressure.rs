@@ -0,0 +1,126 @@
+/// Event-driven low memory detection
+///
+/// Windows signals a memory resource notification object when available
+/// physical memory runs low (`CreateMemoryResourceNotification`). A thread
+/// waits on it and wakes the auto-optimizer loop as soon as it is signaled,
+/// so the loop no longer has to read the memory status every 30 seconds to
+/// notice pressure. Where the object cannot be created the loop keeps
+/// polling as before.
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How often the end of a low memory state is checked; the object stays
+/// signaled while memory is low, so it cannot be waited on for that
+#[cfg(windows)]
+const CLEAR_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+static WAKE: Lazy<Notify> = Lazy::new(Notify::new);
+static DETECTOR_RUNNING: AtomicBool = AtomicBool::new(false);
+static LOW_MEMORY: AtomicBool = AtomicBool::new(false);
+
+/// Whether the detector thread is running (otherwise the loop polls)
+pub fn is_active() -> bool {
+    DETECTOR_RUNNING.load(Ordering::SeqCst)
+}
+
+/// Whether Windows currently reports low physical memory
+pub fn is_low_memory() -> bool {
+    LOW_MEMORY.load(Ordering::SeqCst)
+}
+
+/// Sleep for `timeout`, or less if low memory is signaled meanwhile
+///
+/// A signal that arrives while the loop is busy is kept and ends the next
+/// wait right away.
+pub async fn wait(timeout: Duration) {
+    let _ = tokio::time::timeout(timeout, WAKE.notified()).await;
+}
+
+/// Start the detector thread if it is not running yet
+///
+/// Returns false when the notification object is unavailable.
+#[cfg(windows)]
+pub fn start_pressure_detector() -> bool {
+    use windows_sys::Win32::System::Memory::{
+        CreateMemoryResourceNotification, LowMemoryResourceNotification,
+    };
+
+    if DETECTOR_RUNNING.swap(true, Ordering::SeqCst) {
+        return true;
+    }
+
+    let handle = unsafe { CreateMemoryResourceNotification(LowMemoryResourceNotification) };
+    if handle.is_null() {
+        DETECTOR_RUNNING.store(false, Ordering::SeqCst);
+        tracing::warn!("Memory resource notification unavailable, polling for low memory");
+        return false;
+    }
+
+    // HANDLE is a raw pointer, moved to the thread as an integer
+    let raw = handle as usize;
+    let spawned = std::thread::Builder::new()
+        .name("tmc-mem-pressure".into())
+        .spawn(move || detector_loop(raw as _));
+
+    match spawned {
+        Ok(_) => {
+            tracing::info!("Low memory detector started");
+            true
+        }
+        Err(e) => {
+            unsafe { windows_sys::Win32::Foundation::CloseHandle(raw as _) };
+            DETECTOR_RUNNING.store(false, Ordering::SeqCst);
+            tracing::error!("Failed to start low memory detector thread: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn start_pressure_detector() -> bool {
+    false
+}
+
+#[cfg(windows)]
+fn detector_loop(handle: windows_sys::Win32::Foundation::HANDLE) {
+    use windows_sys::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+    use windows_sys::Win32::System::Threading::{WaitForSingleObject, INFINITE};
+
+    loop {
+        let wait = unsafe { WaitForSingleObject(handle, INFINITE) };
+        if wait != WAIT_OBJECT_0 {
+            tracing::warn!(
+                "Waiting for low memory failed ({}), falling back to polling",
+                wait
+            );
+            break;
+        }
+
+        LOW_MEMORY.store(true, Ordering::SeqCst);
+        tracing::info!("Windows signaled low physical memory");
+        WAKE.notify_one();
+
+        while query_low_memory(handle).unwrap_or(false) {
+            std::thread::sleep(CLEAR_POLL_INTERVAL);
+        }
+        LOW_MEMORY.store(false, Ordering::SeqCst);
+        tracing::debug!("Low physical memory state ended");
+    }
+
+    LOW_MEMORY.store(false, Ordering::SeqCst);
+    unsafe { CloseHandle(handle) };
+    DETECTOR_RUNNING.store(false, Ordering::SeqCst);
+}
+
+#[cfg(windows)]
+fn query_low_memory(handle: windows_sys::Win32::Foundation::HANDLE) -> Option<bool> {
+    use windows_sys::Win32::System::Memory::QueryMemoryResourceNotification;
+
+    let mut state = 0;
+    if unsafe { QueryMemoryResourceNotification(handle, &mut state) } == 0 {
+        return None;
+    }
+    Some(state != 0)
+}
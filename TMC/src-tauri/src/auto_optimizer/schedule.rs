@@ -0,0 +1,321 @@
+/// Cron-like schedule rules for the auto optimizer
+///
+/// Rules are short strings evaluated in local time:
+///
+/// - `weekdays at 09:00`
+/// - `sat,sun at 10:30, 22:00`
+/// - `every 30m between 18:00-23:00`
+/// - `mon-fri every 2h`
+///
+/// Days: `daily` (default), `weekdays`, `weekends`, `mon`..`sun`, lists and
+/// ranges (`mon-fri`, `sat,sun`). Windows may wrap past midnight (`22:00-02:00`).
+use crate::config::Profile;
+use crate::memory::types::Areas;
+use chrono::{Datelike, Duration, NaiveDateTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+const MINUTES_PER_DAY: u16 = 24 * 60;
+/// Search horizon for the next run (a bit more than a week)
+const MAX_LOOKAHEAD_MINUTES: i64 = 8 * 24 * 60;
+pub const MAX_SCHEDULES: usize = 32;
+
+/// A schedule entry stored in the configuration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScheduleEntry {
+    pub name: String,
+    pub enabled: bool,
+    pub rule: String,
+    /// Profile whose areas are used when `areas` is not set
+    pub profile: Option<Profile>,
+    pub areas: Option<Areas>,
+}
+
+impl Default for ScheduleEntry {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            enabled: true,
+            rule: String::new(),
+            profile: None,
+            areas: None,
+        }
+    }
+}
+
+impl ScheduleEntry {
+    /// Areas to optimize, falling back to the given profile
+    pub fn effective_areas(&self, fallback: Profile) -> Areas {
+        match self.areas {
+            Some(areas) if !areas.is_empty() => areas,
+            _ => self.profile.unwrap_or(fallback).get_memory_areas(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Timing {
+    /// Fixed minutes of the day
+    At(Vec<u16>),
+    /// Every `interval` minutes inside `[start, end)`
+    Every { interval: u16, start: u16, end: u16 },
+}
+
+/// A parsed rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduleSpec {
+    /// Active days, Monday = 0
+    days: [bool; 7],
+    timing: Timing,
+}
+
+fn parse_day(token: &str) -> Option<usize> {
+    match token {
+        "mon" | "monday" => Some(0),
+        "tue" | "tuesday" => Some(1),
+        "wed" | "wednesday" => Some(2),
+        "thu" | "thursday" => Some(3),
+        "fri" | "friday" => Some(4),
+        "sat" | "saturday" => Some(5),
+        "sun" | "sunday" => Some(6),
+        _ => None,
+    }
+}
+
+fn parse_days(token: &str) -> Option<[bool; 7]> {
+    let mut days = [false; 7];
+    match token {
+        "daily" | "everyday" => return Some([true; 7]),
+        "weekdays" => return Some([true, true, true, true, true, false, false]),
+        "weekends" => return Some([false, false, false, false, false, true, true]),
+        _ => {}
+    }
+
+    for part in token.split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_day(from)?, parse_day(to)?);
+                let mut day = from;
+                loop {
+                    days[day] = true;
+                    if day == to {
+                        break;
+                    }
+                    day = (day + 1) % 7;
+                }
+            }
+            None => days[parse_day(part)?] = true,
+        }
+    }
+    Some(days)
+}
+
+fn parse_time(token: &str) -> Result<u16, String> {
+    let (h, m) = token
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid time '{}', expected HH:MM", token))?;
+    let h: u16 = h.parse().map_err(|_| format!("Invalid hour in '{}'", token))?;
+    let m: u16 = m.parse().map_err(|_| format!("Invalid minutes in '{}'", token))?;
+    if h > 23 || m > 59 {
+        return Err(format!("Time out of range: '{}'", token));
+    }
+    Ok(h * 60 + m)
+}
+
+fn parse_interval(token: &str) -> Result<u16, String> {
+    let (number, unit) = token.split_at(token.find(|c: char| !c.is_ascii_digit()).unwrap_or(token.len()));
+    let n: u16 = number
+        .parse()
+        .map_err(|_| format!("Invalid interval '{}'", token))?;
+    let minutes = match unit {
+        "m" | "min" | "mins" | "minutes" => n,
+        "h" | "hour" | "hours" => n.saturating_mul(60),
+        _ => return Err(format!("Invalid interval unit in '{}', use m or h", token)),
+    };
+    if !(5..=MINUTES_PER_DAY).contains(&minutes) {
+        return Err("Interval must be between 5 minutes and 24 hours".to_string());
+    }
+    Ok(minutes)
+}
+
+impl ScheduleSpec {
+    /// Parse a rule string
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        let normalized = rule.trim().to_lowercase().replace(", ", ",");
+        let tokens: Vec<&str> = normalized.split_whitespace().collect();
+        if tokens.is_empty() {
+            return Err("Empty schedule rule".to_string());
+        }
+
+        let (days, rest) = match tokens[0] {
+            "at" | "every" => ([true; 7], &tokens[..]),
+            first => (
+                parse_days(first).ok_or_else(|| format!("Invalid days '{}'", first))?,
+                &tokens[1..],
+            ),
+        };
+        if !days.iter().any(|d| *d) {
+            return Err("No days selected".to_string());
+        }
+
+        let timing = match rest {
+            ["at", times] => {
+                let mut minutes = times
+                    .split(',')
+                    .filter(|t| !t.is_empty())
+                    .map(parse_time)
+                    .collect::<Result<Vec<u16>, String>>()?;
+                minutes.sort_unstable();
+                minutes.dedup();
+                if minutes.is_empty() {
+                    return Err("Missing time after 'at'".to_string());
+                }
+                Timing::At(minutes)
+            }
+            ["every", interval] => Timing::Every {
+                interval: parse_interval(interval)?,
+                start: 0,
+                end: 0,
+            },
+            ["every", interval, "between", window] => {
+                let (start, end) = window
+                    .split_once('-')
+                    .ok_or_else(|| format!("Invalid window '{}', expected HH:MM-HH:MM", window))?;
+                let (start, end) = (parse_time(start)?, parse_time(end)?);
+                if start == end {
+                    return Err("Window start and end must differ".to_string());
+                }
+                Timing::Every {
+                    interval: parse_interval(interval)?,
+                    start,
+                    end,
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid rule '{}': use '<days> at HH:MM' or '<days> every <N>m|h [between HH:MM-HH:MM]'",
+                    rule.trim()
+                ))
+            }
+        };
+
+        Ok(Self { days, timing })
+    }
+
+    /// Whether the rule fires at the given weekday (Monday = 0) and minute of day
+    fn matches(&self, weekday: usize, minute: u16) -> bool {
+        if !self.days[weekday % 7] {
+            return false;
+        }
+        match &self.timing {
+            Timing::At(minutes) => minutes.contains(&minute),
+            Timing::Every {
+                interval,
+                start,
+                end,
+            } => {
+                // start == end (0-0) means the whole day
+                let in_window = if start == end {
+                    true
+                } else if start < end {
+                    minute >= *start && minute < *end
+                } else {
+                    minute >= *start || minute < *end
+                };
+                let offset = (minute + MINUTES_PER_DAY - start) % MINUTES_PER_DAY;
+                in_window && offset % interval == 0
+            }
+        }
+    }
+
+    /// First run strictly after `after` (minute precision)
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut t = after.with_second(0)?.with_nanosecond(0)?;
+        for _ in 0..MAX_LOOKAHEAD_MINUTES {
+            t += Duration::minutes(1);
+            let weekday = t.weekday().num_days_from_monday() as usize;
+            let minute = (t.hour() * 60 + t.minute()) as u16;
+            if self.matches(weekday, minute) {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    /// Next `count` runs after `after`
+    pub fn upcoming(&self, after: NaiveDateTime, count: usize) -> Vec<NaiveDateTime> {
+        let mut runs = Vec::with_capacity(count);
+        let mut cursor = after;
+        while runs.len() < count {
+            match self.next_after(cursor) {
+                Some(next) => {
+                    runs.push(next);
+                    cursor = next;
+                }
+                None => break,
+            }
+        }
+        runs
+    }
+}
+
+/// First enabled entry with a run in `(last, now]`
+pub fn due_entry<'a>(
+    entries: &'a [ScheduleEntry],
+    last: NaiveDateTime,
+    now: NaiveDateTime,
+) -> Option<&'a ScheduleEntry> {
+    entries.iter().filter(|e| e.enabled).find(|e| {
+        ScheduleSpec::parse(&e.rule)
+            .ok()
+            .and_then(|spec| spec.next_after(last))
+            .map_or(false, |next| next <= now)
+    })
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .unwrap()
+            .and_hms_opt(h, mi, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_weekdays_at() {
+        // 2024-06-07 is a Friday
+        let spec = ScheduleSpec::parse("weekdays at 09:00").unwrap();
+        assert_eq!(spec.next_after(at(2024, 6, 7, 8, 30)), Some(at(2024, 6, 7, 9, 0)));
+        // After Friday 09:00 the next run is Monday
+        assert_eq!(spec.next_after(at(2024, 6, 7, 9, 0)), Some(at(2024, 6, 10, 9, 0)));
+    }
+
+    #[test]
+    fn test_every_between_window() {
+        let spec = ScheduleSpec::parse("every 30m between 18:00-23:00").unwrap();
+        let runs = spec.upcoming(at(2024, 6, 7, 22, 10), 3);
+        assert_eq!(
+            runs,
+            vec![at(2024, 6, 7, 22, 30), at(2024, 6, 8, 18, 0), at(2024, 6, 8, 18, 30)]
+        );
+
+        let overnight = ScheduleSpec::parse("sat,sun every 2h between 22:00-02:00").unwrap();
+        assert_eq!(
+            overnight.upcoming(at(2024, 6, 8, 21, 0), 2),
+            vec![at(2024, 6, 8, 22, 0), at(2024, 6, 9, 0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(ScheduleSpec::parse("").is_err());
+        assert!(ScheduleSpec::parse("weekdays at 25:00").is_err());
+        assert!(ScheduleSpec::parse("every 1m").is_err());
+        assert!(ScheduleSpec::parse("someday at 10:00").is_err());
+    }
+}
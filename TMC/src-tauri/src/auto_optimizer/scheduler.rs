@@ -1,137 +1,531 @@
-use crate::config::Config;
+use super::emergency::{self, EmergencyGuard};
+use super::idle::is_idle_for;
+use super::prediction::{PressurePrediction, PressureTrend};
+use super::pressure;
+use super::schedule::{due_entry, ScheduleEntry};
+use super::session::{is_session_locked, SessionChange, SessionTracker};
+use crate::config::{LockedSessionPolicy, SharedConfig};
 use crate::engine::Engine;
-use crate::memory::types::Reason;
-use std::sync::{Arc, Mutex};
+use crate::logging::error_bus::{report, Subsystem};
+use crate::memory::types::{Areas, Reason};
+use crate::stats::usage::Feature;
+use crate::stats::Deferral;
+use crate::system::power::power_status;
+use crate::system::supervisor::BackgroundTask;
+use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::time::{Duration, Instant};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
+
+/// Longest pause between checks while polling for low memory
+const POLL_MAX_CHECK_INTERVAL: Duration = Duration::from_secs(120);
+/// Longest pause between checks while the low memory detector runs
+const EVENT_MAX_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+/// Minimum time between low memory (or predicted pressure) runs
+const LOW_MEMORY_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Start of the current low memory cooldown, shared with the UI
+static COOLDOWN_START: Lazy<parking_lot::Mutex<Option<Instant>>> =
+    Lazy::new(|| parking_lot::Mutex::new(None));
+
+/// Whether automatic optimizations run, and when the low memory trigger is armed again
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoOptimizerStatus {
+    pub enabled: bool,
+    /// Seconds left of the low memory cooldown, 0 when none is running
+    pub cooldown_remaining_secs: u64,
+}
+
+/// Status for the `auto_optimizer_enabled` in effect
+pub fn auto_optimizer_status(enabled: bool) -> AutoOptimizerStatus {
+    AutoOptimizerStatus {
+        enabled,
+        cooldown_remaining_secs: cooldown_remaining().as_secs(),
+    }
+}
+
+/// Pause or resume all automatic triggers and persist the choice
+///
+/// The triggers keep their configuration; the loop only skips them while
+/// paused. Used by the command, the tray menu and the hotkey.
+pub fn set_auto_optimizer_enabled(
+    app: &AppHandle,
+    cfg: &SharedConfig,
+    enabled: bool,
+) -> Result<AutoOptimizerStatus, String> {
+    {
+        let mut c = cfg.write();
+        if c.auto_optimizer_enabled != enabled {
+            c.auto_optimizer_enabled = enabled;
+            c.save().map_err(|e| e.to_string())?;
+            crate::stats::usage::record_feature(&c, Feature::AutomationToggle);
+        }
+    }
+    tracing::info!(
+        "Auto optimizer {}",
+        if enabled { "resumed" } else { "paused" }
+    );
+    let _ = app.emit("config-changed", ());
+    Ok(auto_optimizer_status(enabled))
+}
+
+fn start_cooldown() -> Instant {
+    let now = Instant::now();
+    *COOLDOWN_START.lock() = Some(now);
+    now
+}
+
+fn cooldown_remaining() -> Duration {
+    COOLDOWN_START
+        .lock()
+        .map(|start| LOW_MEMORY_COOLDOWN.saturating_sub(start.elapsed()))
+        .unwrap_or_default()
+}
 
 /// Start the auto-optimizer background task
-/// This function spawns an async task that periodically checks for:
+/// This function starts a supervised async task (restarted if it panics)
+/// that periodically checks for:
+/// - Schedule rules (cron-like, see `schedule.rs`)
 /// - Scheduled optimizations (time-based)
-/// - Low memory conditions (threshold-based)
-pub fn start_auto_optimizer(app: AppHandle, engine: Engine, cfg: Arc<Mutex<Config>>) {
-    tauri::async_runtime::spawn(async move {
-        let mut last_scheduled_opt = Instant::now();
-        let mut last_low_mem_opt = Instant::now();
-        let mut check_interval = Duration::from_secs(30);
+/// - Low memory conditions (threshold-based, and signaled by Windows through
+///   the detector in `pressure.rs`, which wakes the loop right away)
+/// - Predicted memory pressure (trend-based, see `prediction.rs`)
+/// - Critically low memory (`emergency_mode`, see `emergency.rs`): runs right
+///   away, ahead of the battery, lock, idle and cooldown settings
+/// - Gentle working set maintenance of selected processes, on its own
+///   interval (see `gentle_maintenance.rs`)
+///
+/// While the detector runs the loop checks memory less often; without it
+/// (the notification is unavailable) it polls every 30-120 seconds.
+///
+/// With `auto_opt_only_when_idle` every trigger waits until the user has
+/// been idle for `idle_minutes`; schedule rules that come due meanwhile are
+/// kept pending and run once the user is idle.
+///
+/// On battery the runs are skipped (`skip_auto_opt_on_battery`) or use the
+/// areas of `profile_on_battery` instead of the active profile.
+///
+/// While the session is locked `locked_session_policy` applies:
+/// - `RunWhileLocked`: nothing changes
+/// - `DeferToUnlock`: no run starts; schedule rules and the interval run once
+///   the session is unlocked, the low memory and predicted triggers are checked
+///   again. The first run after unlocking is recorded as "deferred (locked)"
+/// - `Skip`: no run starts; schedule rules and interval runs that come due are
+///   dropped, the low memory and predicted triggers are checked again after
+///   unlocking
+pub fn start_auto_optimizer(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    pressure::start_pressure_detector();
+    crate::system::supervisor::supervise(BackgroundTask::AutoOptimizer, move || {
+        auto_optimizer_loop(app.clone(), engine.clone(), cfg.clone())
+    });
+}
 
-        // Wait before starting checks
-        tokio::time::sleep(Duration::from_secs(10)).await;
+async fn auto_optimizer_loop(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    let mut last_scheduled_opt = Instant::now();
+    let mut last_low_mem_opt = start_cooldown();
+    let mut last_gentle_pass = Instant::now();
+    let mut check_interval = Duration::from_secs(30);
+    let mut last_rule_check = chrono::Local::now().naive_local();
+    let started = Instant::now();
+    let mut trend = PressureTrend::new(PressurePrediction::default().samples_for_fit());
+    let mut pending_rule: Option<ScheduleEntry> = None;
+    let mut session = SessionTracker::new();
+    // A trigger was held back by the lock (`DeferToUnlock`)
+    let mut held_while_locked = false;
+    let mut emergency_guard = EmergencyGuard::default();
 
-        loop {
-            tokio::time::sleep(check_interval).await;
+    // Wait before starting checks
+    tokio::time::sleep(Duration::from_secs(10)).await;
 
-            let conf = match cfg.lock() {
-                Ok(c) => c.clone(),
-                Err(_) => continue,
-            };
+    loop {
+        pressure::wait(check_interval).await;
 
-            let mut action_taken = false;
+        let conf = cfg.snapshot();
 
-            // SCHEDULED OPTIMIZATION
-            if conf.auto_opt_interval_hours > 0 {
-                let hours_passed = last_scheduled_opt.elapsed().as_secs() / 3600;
-                if hours_passed >= conf.auto_opt_interval_hours as u64 {
-                    tracing::info!(
-                        "Triggering scheduled optimization after {} hours",
-                        hours_passed
-                    );
+        // Paused by the user: keep the configuration, skip every trigger
+        if !conf.auto_optimizer_enabled {
+            last_rule_check = chrono::Local::now().naive_local();
+            pending_rule = None;
+            continue;
+        }
 
-                    // Log evento automatico
+        // EMERGENCY: critically low memory skips every other setting
+        let emergency_mode = conf.emergency_mode;
+        if emergency_mode.enabled {
+            if let Ok(mem) = engine.memory() {
+                let free_percent = mem.physical.free.percentage;
+                if emergency_guard.update(free_percent, emergency_mode.free_threshold) {
+                    tracing::warn!(
+                        "Triggering emergency optimization: {}% free < {}% emergency threshold",
+                        free_percent,
+                        emergency_mode.free_threshold
+                    );
                     crate::logging::event_viewer::log_auto_optimization_event(
-                        "Scheduled",
-                        conf.auto_opt_interval_hours as u8,
+                        "Emergency",
+                        emergency_mode.free_threshold,
                     );
+                    emergency::spawn_emergency(&app, &engine, &cfg, emergency_mode);
 
-                    let app_clone = app.clone();
-                    let engine_clone = engine.clone();
-                    let cfg_clone = cfg.clone();
-
-                    tauri::async_runtime::spawn(async move {
-                        // FIX: Use with_progress: true to update the UI during automatic optimizations
-                        // This prevents overlaps and correctly shows the status
-                        crate::perform_optimization(
-                            app_clone,
-                            engine_clone,
-                            cfg_clone,
-                            Reason::Schedule,
-                            true,
-                            None,
-                        )
-                        .await;
-                    });
-
-                    last_scheduled_opt = Instant::now();
-                    action_taken = true;
+                    // The regular low memory trigger waits for its cooldown
+                    last_low_mem_opt = start_cooldown();
+                    trend.clear();
+                    check_interval = Duration::from_secs(30);
+                    continue;
                 }
             }
+        }
+
+        let power = power_status();
+        if power.on_battery() && conf.skip_auto_opt_on_battery {
+            last_rule_check = chrono::Local::now().naive_local();
+            pending_rule = None;
+            continue;
+        }
+        let battery_areas = if power.on_battery() {
+            conf.battery_areas()
+        } else {
+            None
+        };
+
+        // Unknown lock state counts as unlocked, like before the policy existed
+        let policy = conf.locked_session_policy;
+        let locked = is_session_locked().unwrap_or(false);
+        match session.update(locked) {
+            Some(SessionChange::Locked) => {
+                tracing::info!("Session locked, automatic optimizations: {:?}", policy)
+            }
+            Some(SessionChange::Unlocked(after)) => {
+                tracing::info!("Session unlocked after {} min", after.as_secs() / 60)
+            }
+            None => {}
+        }
+        if policy != LockedSessionPolicy::DeferToUnlock {
+            held_while_locked = false;
+        }
+        let lock_ok = !locked || policy == LockedSessionPolicy::RunWhileLocked;
+        let skip_locked = locked && policy == LockedSessionPolicy::Skip;
+
+        let mut action_taken = false;
+        let idle_ok = !conf.auto_opt_only_when_idle || is_idle_for(conf.idle_minutes);
+        let run_ok = idle_ok && lock_ok;
+
+        // SCHEDULE RULES
+        let now = chrono::Local::now().naive_local();
+        if let Some(entry) = due_entry(&conf.schedules, last_rule_check, now) {
+            if skip_locked {
+                tracing::info!(
+                    "Schedule rule '{}' skipped, the session is locked",
+                    entry.rule
+                );
+            } else {
+                if !lock_ok && pending_rule.is_none() {
+                    tracing::info!(
+                        "Schedule rule '{}' deferred until the session is unlocked",
+                        entry.rule
+                    );
+                } else if !idle_ok && pending_rule.is_none() {
+                    tracing::info!(
+                        "Schedule rule '{}' deferred until the user is idle for {} min",
+                        entry.rule,
+                        conf.idle_minutes
+                    );
+                }
+                pending_rule = Some(entry.clone());
+            }
+        }
+        last_rule_check = now;
+        if !lock_ok && pending_rule.is_some() {
+            held_while_locked |= !skip_locked;
+        }
+        let due_rule = if run_ok { pending_rule.take() } else { None };
+        if let Some(entry) = due_rule {
+            let label = if entry.name.is_empty() {
+                entry.rule.clone()
+            } else {
+                entry.name.clone()
+            };
+            tracing::info!("Triggering schedule rule '{}' ({})", label, entry.rule);
+
+            crate::logging::event_viewer::log_auto_optimization_event(
+                &format!("Schedule rule: {}", label),
+                0,
+            );
+
+            let areas = match battery_areas {
+                Some(areas) if entry.profile.is_none() && entry.areas.is_none() => areas,
+                _ => entry.effective_areas(conf.profile),
+            };
+            spawn_optimization(
+                &app,
+                &engine,
+                &cfg,
+                Reason::Schedule,
+                Some(areas),
+                take_deferral(&mut held_while_locked),
+            );
+
+            last_scheduled_opt = Instant::now();
+            action_taken = true;
+        }
+
+        // SCHEDULED OPTIMIZATION
+        if conf.auto_opt_interval_hours > 0 && !action_taken && idle_ok {
+            let hours_passed = last_scheduled_opt.elapsed().as_secs() / 3600;
+            let due = hours_passed >= conf.auto_opt_interval_hours as u64;
+            if due && skip_locked {
+                tracing::info!("Scheduled optimization skipped, the session is locked");
+                last_scheduled_opt = Instant::now();
+            } else if due && !lock_ok {
+                // The elapsed time keeps growing, the run starts after unlocking
+                held_while_locked = true;
+            } else if due {
+                tracing::info!(
+                    "Triggering scheduled optimization after {} hours",
+                    hours_passed
+                );
+
+                // Log evento automatico
+                crate::logging::event_viewer::log_auto_optimization_event(
+                    "Scheduled",
+                    conf.auto_opt_interval_hours as u8,
+                );
 
-            // LOW MEMORY OPTIMIZATION (bug fix)
-            if conf.auto_opt_free_threshold > 0 && !action_taken {
-                // Check memory status
-                if let Ok(mem) = engine.memory() {
-                    let free_percent = mem.physical.free.percentage;
+                spawn_optimization(
+                    &app,
+                    &engine,
+                    &cfg,
+                    Reason::Schedule,
+                    battery_areas,
+                    take_deferral(&mut held_while_locked),
+                );
 
-                    // FIX: Correctly compare with threshold
-                    if free_percent < conf.auto_opt_free_threshold {
-                        // Verify 5-minute cooldown
-                        if last_low_mem_opt.elapsed() >= Duration::from_secs(300) {
+                last_scheduled_opt = Instant::now();
+                action_taken = true;
+            }
+        }
+
+        // LOW MEMORY OPTIMIZATION (bug fix)
+        if conf.auto_opt_free_threshold > 0 && !action_taken {
+            // Check memory status
+            let mem = engine.memory();
+            if let Err(e) = &mem {
+                tracing::warn!("Low memory check failed: {}", e);
+                report(
+                    Subsystem::Scheduler,
+                    format!("Cannot read memory status for the low memory trigger: {}", e),
+                );
+            }
+            if let Ok(mem) = mem {
+                let free_percent = mem.physical.free.percentage;
+                let signaled = pressure::is_low_memory();
+
+                // FIX: Correctly compare with threshold
+                if (free_percent < conf.auto_opt_free_threshold || signaled) && idle_ok {
+                    // Verify 5-minute cooldown
+                    if !lock_ok {
+                        tracing::debug!(
+                            "Low memory detected ({}% free) but the session is locked",
+                            free_percent
+                        );
+                        held_while_locked |= !skip_locked;
+                    } else if last_low_mem_opt.elapsed() >= LOW_MEMORY_COOLDOWN {
+                        if signaled {
+                            tracing::info!(
+                                "Triggering low memory optimization: signaled by Windows ({}% free)",
+                                free_percent
+                            );
+                        } else {
                             tracing::info!(
                                 "Triggering low memory optimization: {}% free < {}% threshold",
                                 free_percent,
                                 conf.auto_opt_free_threshold
                             );
+                        }
 
-                            // Log automatic event
-                            crate::logging::event_viewer::log_auto_optimization_event(
-                                "Low Memory",
-                                conf.auto_opt_free_threshold,
-                            );
+                        // Log automatic event
+                        crate::logging::event_viewer::log_auto_optimization_event(
+                            "Low Memory",
+                            conf.auto_opt_free_threshold,
+                        );
 
-                            let app_clone = app.clone();
-                            let engine_clone = engine.clone();
-                            let cfg_clone = cfg.clone();
-
-                            tauri::async_runtime::spawn(async move {
-                                // FIX: Use with_progress: true to update UI during automatic optimizations
-                                // This prevents overlaps and correctly shows status
-                                crate::perform_optimization(
-                                    app_clone,
-                                    engine_clone,
-                                    cfg_clone,
-                                    Reason::LowMemory,
-                                    true,
-                                    None,
-                                )
-                                .await;
-                            });
-
-                            last_low_mem_opt = Instant::now();
-                            action_taken = true;
-                        } else {
-                            let remaining = 300 - last_low_mem_opt.elapsed().as_secs();
-                            tracing::debug!(
-                                "Low memory detected ({}% free) but cooldown active ({}s remaining)",
-                                free_percent, remaining
-                            );
-                        }
+                        spawn_optimization(
+                            &app,
+                            &engine,
+                            &cfg,
+                            Reason::LowMemory,
+                            battery_areas,
+                            take_deferral(&mut held_while_locked),
+                        );
 
-                        // Increase check frequency when memory is low
-                        check_interval = Duration::from_secs(30);
+                        last_low_mem_opt = start_cooldown();
+                        action_taken = true;
                     } else {
-                        // Memory OK, reduce check frequency
-                        check_interval = Duration::from_secs(60);
+                        let remaining = cooldown_remaining().as_secs();
+                        tracing::debug!(
+                            "Low memory detected ({}% free) but cooldown active ({}s remaining)",
+                            free_percent,
+                            remaining
+                        );
                     }
+
+                    // Increase check frequency when memory is low
+                    check_interval = Duration::from_secs(30);
+                } else if !pressure::is_active() {
+                    // Memory OK, reduce check frequency
+                    check_interval = Duration::from_secs(60);
                 }
             }
+        }
 
-            // Adaptive interval
-            if !action_taken {
-                check_interval =
-                    (check_interval + Duration::from_secs(10)).min(Duration::from_secs(120));
-            } else {
-                check_interval = Duration::from_secs(30);
+        // PREDICTED MEMORY PRESSURE
+        let prediction = conf.pressure_prediction;
+        if prediction.enabled && conf.auto_opt_free_threshold > 0 {
+            if let Ok(mem) = engine.memory() {
+                trend.set_capacity(prediction.samples_for_fit());
+                trend.push(
+                    started.elapsed().as_secs_f64(),
+                    mem.physical.free.percentage as f64,
+                    mem.commit.free.percentage as f64,
+                );
+
+                if action_taken {
+                    // The baseline is about to change, start a fresh trend
+                    trend.clear();
+                } else if let Some(forecast) = trend.forecast(conf.auto_opt_free_threshold as f64) {
+                    tracing::debug!(
+                        "{} trend {:.2}%/min, threshold in {:.1} min",
+                        forecast.series,
+                        forecast.slope_per_minute,
+                        forecast.minutes_to_threshold
+                    );
+
+                    let in_window =
+                        forecast.minutes_to_threshold <= prediction.window_minutes as f64;
+                    if in_window && idle_ok && !lock_ok {
+                        held_while_locked |= !skip_locked;
+                    } else if in_window
+                        && last_low_mem_opt.elapsed() >= LOW_MEMORY_COOLDOWN
+                        && idle_ok
+                    {
+                        tracing::info!(
+                            "Triggering predicted pressure optimization: {} free {:.2}%/min, {}% threshold in {:.1} min",
+                            forecast.series,
+                            forecast.slope_per_minute,
+                            conf.auto_opt_free_threshold,
+                            forecast.minutes_to_threshold
+                        );
+
+                        crate::logging::event_viewer::log_auto_optimization_event(
+                            "Predicted Pressure",
+                            conf.auto_opt_free_threshold,
+                        );
+
+                        spawn_optimization(
+                            &app,
+                            &engine,
+                            &cfg,
+                            Reason::Predicted,
+                            battery_areas,
+                            take_deferral(&mut held_while_locked),
+                        );
+
+                        // Shares the low memory cooldown
+                        last_low_mem_opt = start_cooldown();
+                        action_taken = true;
+                        trend.clear();
+                    }
+                }
             }
+        } else {
+            trend.clear();
+        }
+
+        // GENTLE MAINTENANCE
+        let gentle_interval = Duration::from_secs(conf.gentle_maintenance_interval_secs as u64);
+        let gentle_enabled = !conf.gentle_maintenance_processes.is_empty();
+        if gentle_enabled
+            && lock_ok
+            && last_gentle_pass.elapsed() >= gentle_interval
+            && !crate::is_optimization_running()
+        {
+            last_gentle_pass = Instant::now();
+            let conf = conf.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                super::gentle_maintenance::run_pass(&conf)
+            });
+        }
+
+        // Adaptive interval, longer while the detector watches for low memory
+        let max_interval = if pressure::is_active() {
+            EVENT_MAX_CHECK_INTERVAL
+        } else {
+            POLL_MAX_CHECK_INTERVAL
+        };
+        if !action_taken {
+            check_interval = (check_interval + Duration::from_secs(10)).min(max_interval);
+        } else {
+            check_interval = Duration::from_secs(30);
+        }
+
+        // Schedule rules have minute precision
+        if conf.schedules.iter().any(|s| s.enabled) {
+            check_interval = check_interval.min(Duration::from_secs(30));
+        }
+
+        // Notice the unlock promptly
+        if locked && policy != LockedSessionPolicy::RunWhileLocked {
+            check_interval = check_interval.min(Duration::from_secs(30));
+        }
+
+        // Notice the start of an idle period promptly
+        if conf.auto_opt_only_when_idle && !idle_ok {
+            check_interval = check_interval.min(Duration::from_secs(30));
+        }
+
+        if gentle_enabled {
+            check_interval = check_interval.min(gentle_interval);
+        }
+
+        // Notice a drop to the emergency threshold promptly
+        if emergency_mode.enabled {
+            check_interval = check_interval.min(Duration::from_secs(30));
+        }
+
+        // The trend needs evenly spaced samples
+        if prediction.enabled {
+            check_interval =
+                check_interval.min(Duration::from_secs(prediction.sample_interval_secs as u64));
+        }
+    }
+}
+
+/// Deferral to record for the run about to start; a held trigger marks only one run
+fn take_deferral(held_while_locked: &mut bool) -> Option<Deferral> {
+    std::mem::take(held_while_locked).then_some(Deferral::Locked)
+}
+
+/// Start an automatic optimization in the background
+fn spawn_optimization(
+    app: &AppHandle,
+    engine: &Engine,
+    cfg: &SharedConfig,
+    reason: Reason,
+    areas: Option<Areas>,
+    deferred: Option<Deferral>,
+) {
+    let app = app.clone();
+    let engine = engine.clone();
+    let cfg = cfg.clone();
+
+    tauri::async_runtime::spawn(async move {
+        if let Some(deferral) = deferred {
+            crate::stats::history::defer_next(reason, deferral);
+        }
+        // with_progress updates the UI during automatic optimizations,
+        // this prevents overlaps and correctly shows the status
+        let result = crate::perform_optimization(app, engine, cfg, reason, true, areas, None).await;
+        if deferred.is_some() && result.is_none() {
+            crate::stats::history::clear_deferral();
         }
     });
 }
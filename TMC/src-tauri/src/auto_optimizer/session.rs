@@ -0,0 +1,110 @@
+/// Session lock tracking
+///
+/// `locked_session_policy` decides what automatic optimizations do while the
+/// workstation is locked. The scheduler samples the lock state every check
+/// and the tracker turns the samples into lock / unlock transitions.
+use std::time::{Duration, Instant};
+
+/// Whether the interactive session is locked, `None` if it cannot be determined
+#[cfg(windows)]
+pub fn is_session_locked() -> Option<bool> {
+    use windows_sys::Win32::System::RemoteDesktop::{
+        WTSFreeMemory, WTSQuerySessionInformationW, WTSSessionInfoEx, WTSINFOEXW,
+        WTS_CURRENT_SERVER_HANDLE, WTS_CURRENT_SESSION, WTS_SESSIONSTATE_LOCK,
+        WTS_SESSIONSTATE_UNLOCK,
+    };
+
+    let mut buffer: *mut u16 = std::ptr::null_mut();
+    let mut len = 0u32;
+    unsafe {
+        if WTSQuerySessionInformationW(
+            WTS_CURRENT_SERVER_HANDLE,
+            WTS_CURRENT_SESSION,
+            WTSSessionInfoEx,
+            &mut buffer,
+            &mut len,
+        ) == 0
+            || buffer.is_null()
+        {
+            return None;
+        }
+
+        let info = &*(buffer as *const WTSINFOEXW);
+        let state = if info.Level == 1 && len as usize >= std::mem::size_of::<WTSINFOEXW>() {
+            match info.Data.WTSInfoExLevel1.SessionFlags {
+                f if f == WTS_SESSIONSTATE_LOCK as i32 => Some(true),
+                f if f == WTS_SESSIONSTATE_UNLOCK as i32 => Some(false),
+                _ => None,
+            }
+        } else {
+            None
+        };
+        WTSFreeMemory(buffer as _);
+        state
+    }
+}
+
+#[cfg(not(windows))]
+pub fn is_session_locked() -> Option<bool> {
+    None
+}
+
+/// Lock state change noticed by `SessionTracker::update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionChange {
+    Locked,
+    /// Unlocked after being locked for the given time
+    Unlocked(Duration),
+}
+
+/// Last known lock state of the session
+#[derive(Debug, Default)]
+pub struct SessionTracker {
+    locked_since: Option<Instant>,
+}
+
+impl SessionTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the current lock state, returns the transition if it changed
+    pub fn update(&mut self, locked: bool) -> Option<SessionChange> {
+        match (self.locked_since, locked) {
+            (None, true) => {
+                self.locked_since = Some(Instant::now());
+                Some(SessionChange::Locked)
+            }
+            (Some(since), false) => {
+                self.locked_since = None;
+                Some(SessionChange::Unlocked(since.elapsed()))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.locked_since.is_some()
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_reports_transitions_once() {
+        let mut tracker = SessionTracker::new();
+        assert_eq!(tracker.update(false), None);
+        assert_eq!(tracker.update(true), Some(SessionChange::Locked));
+        assert_eq!(tracker.update(true), None);
+        assert!(tracker.is_locked());
+        assert!(matches!(
+            tracker.update(false),
+            Some(SessionChange::Unlocked(_))
+        ));
+        assert_eq!(tracker.update(false), None);
+        assert!(!tracker.is_locked());
+    }
+}
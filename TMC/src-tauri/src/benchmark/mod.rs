@@ -0,0 +1,154 @@
+/// RAM micro-benchmark
+///
+/// Optional (`benchmark_enabled`) before/after measurement around an
+/// optimization: a short synthetic workload allocates fresh memory and touches
+/// every page, timing each allocation and counting the page faults it causes.
+/// Comparing the two runs gives users a number that reflects how quickly the
+/// system hands out memory, not only how much of it is free.
+pub mod purge_impact;
+
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Size of each allocation, large enough to be served straight by the OS
+/// (VirtualAlloc) instead of being recycled by the heap
+const CHUNK_BYTES: usize = 1024 * 1024;
+/// Allocations per run (64 MB in total)
+const CHUNKS: usize = 64;
+const PAGE_BYTES: usize = 4096;
+
+/// Outcome of one workload run
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BenchmarkSample {
+    /// Median time to allocate and touch one 1 MB chunk, in microseconds
+    pub alloc_latency_us: f64,
+    /// Page faults taken by the workload
+    pub page_faults: u64,
+    pub page_faults_per_sec: f64,
+    pub duration_ms: f64,
+}
+
+/// Workload measured before and after the optimization
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct BenchmarkComparison {
+    pub before: BenchmarkSample,
+    pub after: BenchmarkSample,
+}
+
+impl BenchmarkComparison {
+    /// Change of the allocation latency in percent, negative when faster
+    pub fn latency_change_percent(&self) -> Option<f64> {
+        if self.before.alloc_latency_us <= 0.0 {
+            return None;
+        }
+        Some((self.after.alloc_latency_us / self.before.alloc_latency_us - 1.0) * 100.0)
+    }
+}
+
+/// Run the synthetic workload once
+///
+/// The memory is released before returning, so the workload does not skew
+/// the free memory measured by the engine.
+pub fn run_workload() -> BenchmarkSample {
+    let faults_before = page_fault_count();
+    let start = Instant::now();
+
+    let mut chunks: Vec<Vec<u8>> = Vec::with_capacity(CHUNKS);
+    let mut latencies: Vec<f64> = Vec::with_capacity(CHUNKS);
+    for _ in 0..CHUNKS {
+        let t0 = Instant::now();
+        let mut chunk = Vec::<u8>::with_capacity(CHUNK_BYTES);
+        // Touch one byte per page so every page is actually faulted in
+        for offset in (0..CHUNK_BYTES).step_by(PAGE_BYTES) {
+            chunk.spare_capacity_mut()[offset].write(1);
+        }
+        std::hint::black_box(&chunk);
+        latencies.push(t0.elapsed().as_secs_f64() * 1_000_000.0);
+        chunks.push(chunk);
+    }
+    drop(chunks);
+
+    let elapsed = start.elapsed().as_secs_f64();
+    let page_faults = page_fault_count().saturating_sub(faults_before);
+    BenchmarkSample {
+        alloc_latency_us: median(&mut latencies),
+        page_faults,
+        page_faults_per_sec: if elapsed > 0.0 {
+            page_faults as f64 / elapsed
+        } else {
+            0.0
+        },
+        duration_ms: elapsed * 1000.0,
+    }
+}
+
+fn median(values: &mut [f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    }
+}
+
+/// Page faults of the current process so far
+#[cfg(windows)]
+fn page_fault_count() -> u64 {
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    unsafe {
+        let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+        counters.cb = size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        if K32GetProcessMemoryInfo(GetCurrentProcess(), &mut counters, counters.cb) != 0 {
+            counters.PageFaultCount as u64
+        } else {
+            0
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn page_fault_count() -> u64 {
+    0
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(alloc_latency_us: f64) -> BenchmarkSample {
+        BenchmarkSample {
+            alloc_latency_us,
+            page_faults: 0,
+            page_faults_per_sec: 0.0,
+            duration_ms: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_latency_change_percent() {
+        let faster = BenchmarkComparison {
+            before: sample(200.0),
+            after: sample(150.0),
+        };
+        assert_eq!(faster.latency_change_percent(), Some(-25.0));
+
+        let unmeasured = BenchmarkComparison {
+            before: sample(0.0),
+            after: sample(150.0),
+        };
+        assert_eq!(unmeasured.latency_change_percent(), None);
+
+        let sample = run_workload();
+        assert!(sample.alloc_latency_us > 0.0);
+        assert!(sample.duration_ms >= sample.alloc_latency_us / 1000.0);
+    }
+}
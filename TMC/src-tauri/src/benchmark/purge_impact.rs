@@ -0,0 +1,207 @@
+/// Purge impact measurement
+///
+/// Purging the standby list frees memory that was caching files. What the
+/// system reads back from disk shortly after is the real cost of the purge.
+/// `observe` follows the standby list size and the system-wide hard faults
+/// (pages read from disk) for a while after a purge and sums it up as a
+/// score: the share of the purged bytes that had to be read back.
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Observation window bounds in seconds
+pub const MIN_WINDOW_SECS: u32 = 5;
+pub const MAX_WINDOW_SECS: u32 = 300;
+pub const DEFAULT_WINDOW_SECS: u32 = 60;
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Standby list and hard fault counter at one point in time
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StandbySnapshot {
+    pub standby_bytes: u64,
+    /// Pages read from disk since boot, `None` when it cannot be queried
+    pub page_reads: Option<u64>,
+}
+
+/// One second of the observation window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PurgeImpactSample {
+    pub secs: u32,
+    pub standby_bytes: u64,
+    pub hard_faults: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PurgeImpact {
+    pub window_secs: u32,
+    pub standby_before_bytes: u64,
+    pub standby_after_purge_bytes: u64,
+    pub standby_end_bytes: u64,
+    /// Growth of the standby list within the window
+    pub refilled_bytes: u64,
+    pub hard_faults: u64,
+    pub hard_faults_per_sec: f64,
+    /// Bytes read from disk within the window
+    pub read_back_bytes: u64,
+    /// 0 when nothing purged was read back, 100 when all of it was
+    pub score: u8,
+    #[serde(default)]
+    pub samples: Vec<PurgeImpactSample>,
+}
+
+impl PurgeImpact {
+    /// Sum up the window from the snapshot before the purge and the samples after it
+    pub fn from_samples(
+        before: StandbySnapshot,
+        after_purge: StandbySnapshot,
+        samples: Vec<PurgeImpactSample>,
+        page_size: u64,
+    ) -> Self {
+        let window_secs = samples.last().map(|s| s.secs).unwrap_or(0);
+        let standby_end_bytes = samples
+            .last()
+            .map(|s| s.standby_bytes)
+            .unwrap_or(after_purge.standby_bytes);
+        let hard_faults = samples.last().map(|s| s.hard_faults).unwrap_or(0);
+        let read_back_bytes = hard_faults.saturating_mul(page_size);
+        let purged = before
+            .standby_bytes
+            .saturating_sub(after_purge.standby_bytes);
+        let score = if purged == 0 {
+            0
+        } else {
+            (read_back_bytes as f64 / purged as f64 * 100.0).min(100.0) as u8
+        };
+        Self {
+            window_secs,
+            standby_before_bytes: before.standby_bytes,
+            standby_after_purge_bytes: after_purge.standby_bytes,
+            standby_end_bytes,
+            refilled_bytes: standby_end_bytes.saturating_sub(after_purge.standby_bytes),
+            hard_faults,
+            hard_faults_per_sec: if window_secs > 0 {
+                hard_faults as f64 / window_secs as f64
+            } else {
+                0.0
+            },
+            read_back_bytes,
+            score,
+            samples,
+        }
+    }
+}
+
+/// Standby list size and hard fault counter right now
+pub fn snapshot() -> StandbySnapshot {
+    StandbySnapshot {
+        standby_bytes: crate::memory::ops::standby_breakdown()
+            .map(|b| b.total_bytes)
+            .unwrap_or(0),
+        page_reads: page_read_count(),
+    }
+}
+
+/// Follow the standby list for `window_secs` after a purge
+///
+/// Blocks for the whole window, run it off the async runtime.
+pub fn observe(before: StandbySnapshot, window_secs: u32) -> PurgeImpact {
+    let window_secs = window_secs.clamp(MIN_WINDOW_SECS, MAX_WINDOW_SECS);
+    let after_purge = snapshot();
+    let start = Instant::now();
+    let mut samples = Vec::with_capacity(window_secs as usize);
+
+    for secs in 1..=window_secs {
+        let next = start + SAMPLE_INTERVAL * secs;
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+        let now = snapshot();
+        let hard_faults = match (after_purge.page_reads, now.page_reads) {
+            (Some(first), Some(current)) => current.saturating_sub(first),
+            _ => 0,
+        };
+        samples.push(PurgeImpactSample {
+            secs,
+            standby_bytes: now.standby_bytes,
+            hard_faults,
+        });
+    }
+
+    let impact = PurgeImpact::from_samples(
+        before,
+        after_purge,
+        samples,
+        crate::memory::ops::page_size(),
+    );
+    tracing::info!(
+        "Purge impact over {}s: score {}, {} hard faults, {} MB refilled",
+        impact.window_secs,
+        impact.score,
+        impact.hard_faults,
+        impact.refilled_bytes / (1024 * 1024)
+    );
+    impact
+}
+
+/// Pages read from disk system-wide since boot
+#[cfg(windows)]
+fn page_read_count() -> Option<u64> {
+    use ntapi::ntexapi::{
+        NtQuerySystemInformation, SystemPerformanceInformation, SYSTEM_PERFORMANCE_INFORMATION,
+    };
+
+    unsafe {
+        let mut info: SYSTEM_PERFORMANCE_INFORMATION = std::mem::zeroed();
+        let status = NtQuerySystemInformation(
+            SystemPerformanceInformation,
+            (&mut info as *mut SYSTEM_PERFORMANCE_INFORMATION) as _,
+            std::mem::size_of::<SYSTEM_PERFORMANCE_INFORMATION>() as u32,
+            std::ptr::null_mut(),
+        );
+        (status >= 0).then_some(info.PageReadCount as u64)
+    }
+}
+
+#[cfg(not(windows))]
+fn page_read_count() -> Option<u64> {
+    None
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_purge_impact_score() {
+        let before = StandbySnapshot {
+            standby_bytes: 1000 * MB,
+            page_reads: Some(0),
+        };
+        let after_purge = StandbySnapshot {
+            standby_bytes: 200 * MB,
+            page_reads: Some(0),
+        };
+        // 51200 pages of 4 KB = 200 MB read back out of 800 MB purged
+        let samples = vec![
+            PurgeImpactSample {
+                secs: 1,
+                standby_bytes: 250 * MB,
+                hard_faults: 25600,
+            },
+            PurgeImpactSample {
+                secs: 2,
+                standby_bytes: 400 * MB,
+                hard_faults: 51200,
+            },
+        ];
+        let impact = PurgeImpact::from_samples(before, after_purge, samples, 4096);
+        assert_eq!(impact.score, 25);
+        assert_eq!(impact.refilled_bytes, 200 * MB);
+        assert_eq!(impact.hard_faults_per_sec, 25600.0);
+
+        let nothing_purged = PurgeImpact::from_samples(before, before, Vec::new(), 4096);
+        assert_eq!(nothing_purged.score, 0);
+        assert_eq!(nothing_purged.window_secs, 0);
+    }
+}
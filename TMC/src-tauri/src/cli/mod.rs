@@ -2,7 +2,11 @@
 ///
 /// This module provides console-mode functionality for the application,
 /// allowing operation without a graphical user interface. It includes
-/// argument parsing and command execution for headless environments.
+/// argument parsing (`optimize`, `status`, `config` subcommands, the
+/// `--report-all-users` admin report and the legacy slash options) and
+/// headless command execution.
 pub mod parser;
+pub mod runner;
 
-pub use parser::run_console_mode;
+pub use parser::is_cli_invocation;
+pub use runner::run_console_mode;
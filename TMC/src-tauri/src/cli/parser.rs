@@ -1,123 +1,242 @@
-/// Command-line argument parser and console mode executor.
+/// Command-line argument parser.
 ///
-/// This module handles parsing of command-line arguments for memory optimization
-/// and executes the optimization in console mode without GUI. It supports both
-/// individual memory area selection and predefined profiles.
-use crate::config::{Config, Profile};
-use crate::engine::Engine;
-use crate::memory::types::{Areas, Reason};
-use std::sync::{Arc, Mutex};
+/// This module turns the raw command-line arguments into a `CliCommand`.
+/// It supports the subcommand syntax (`optimize`, `status`, `config`) as well
+/// as the legacy slash options (`/WorkingSet`, `/Profile:Gaming`, ...).
+use crate::config::preset_file::PRESET_FILE_EXTENSION;
+use crate::config::Profile;
+use crate::memory::types::Areas;
 
-#[cfg(not(windows))]
-use std::io;
+/// A parsed console command
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliCommand {
+    Help,
+    Optimize {
+        profile: Option<Profile>,
+        areas: Areas,
+        targets: Vec<String>,
+        /// Only estimate what would be freed
+        dry_run: bool,
+        json: bool,
+    },
+    Status {
+        json: bool,
+    },
+    ConfigGet {
+        key: String,
+        json: bool,
+    },
+    ConfigSet {
+        key: String,
+        value: String,
+        json: bool,
+    },
+    ConfigList {
+        json: bool,
+    },
+    /// Aggregate the history of every user profile (admin report)
+    ReportAllUsers {
+        json: bool,
+    },
+    /// Install (`true`) or remove the Event Log provider (installer hooks)
+    EventLog {
+        register: bool,
+        json: bool,
+    },
+    /// Import and apply a .tmcpreset file (file association)
+    OpenPreset {
+        path: String,
+        json: bool,
+    },
+}
 
-/// Runs the application in console mode with command-line arguments.
-///
-/// Parses the provided arguments to determine which memory areas to optimize
-/// or which profile to use, then executes the optimization and reports results.
-///
-/// # Arguments
+const SUBCOMMANDS: &[&str] = &["optimize", "status", "config", "help"];
+/// Console flags that act as a subcommand on their own
+const FLAG_SUBCOMMANDS: &[&str] = &[
+    "--report-all-users",
+    "--register-event-log",
+    "--unregister-event-log",
+];
+
+/// Returns true if the arguments request console mode instead of the GUI.
 ///
-/// * `args` - Slice of command-line arguments
-pub fn run_console_mode(args: &[String]) {
-    // Global function to write to console on Windows
-    #[cfg(windows)]
-    fn console_print(text: &str) {
-        unsafe {
-            use std::ptr;
-            use std::sync::atomic::{AtomicPtr, Ordering};
-            use windows_sys::Win32::System::Console::STD_OUTPUT_HANDLE;
-            use windows_sys::Win32::System::Console::{GetStdHandle, WriteConsoleW};
+/// GUI-only flags (e.g. `--startup-config` from the installer, `--minimized`)
+/// are not console invocations. A .tmcpreset file opened from Explorer is.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    match args.first().map(|a| a.as_str()) {
+        Some(first) => {
+            is_preset_path(first)
+                || SUBCOMMANDS.contains(&first.to_lowercase().as_str())
+                || first.starts_with('/')
+                || matches!(first, "-h" | "--help")
+                || FLAG_SUBCOMMANDS.contains(&first)
+        }
+        None => false,
+    }
+}
+
+fn is_preset_path(arg: &str) -> bool {
+    std::path::Path::new(arg)
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case(PRESET_FILE_EXTENSION))
+}
 
-            static CONSOLE_HANDLE: AtomicPtr<std::ffi::c_void> = AtomicPtr::new(ptr::null_mut());
+/// Parse a profile name (case-insensitive)
+pub fn parse_profile(name: &str) -> Result<Profile, String> {
+    match name.to_lowercase().as_str() {
+        "normal" => Ok(Profile::Normal),
+        "balanced" => Ok(Profile::Balanced),
+        "gaming" => Ok(Profile::Gaming),
+        _ => Err(format!(
+            "Invalid profile: {}. Use Normal, Balanced, or Gaming",
+            name
+        )),
+    }
+}
 
-            // Initialize console handle if not done yet
-            let handle = CONSOLE_HANDLE.load(Ordering::Relaxed);
-            if handle.is_null() {
-                use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
-                AttachConsole(ATTACH_PARENT_PROCESS);
-                let new_handle = GetStdHandle(STD_OUTPUT_HANDLE);
-                if new_handle as isize != 0 && new_handle as isize != !0 {
-                    CONSOLE_HANDLE.store(new_handle as *mut std::ffi::c_void, Ordering::Relaxed);
-                }
-            }
+/// Parse an areas string (`STANDBY_LIST|WORKING_SET`), rejecting unknown flags
+pub fn parse_areas(value: &str) -> Result<Areas, String> {
+    let mut result = Areas::empty();
+    for flag in value.split(|c| c == '|' || c == ',') {
+        let flag = flag.trim();
+        if flag.is_empty() {
+            continue;
+        }
+        match Areas::from_name(&flag.to_uppercase()) {
+            Some(area) => result |= area,
+            None => return Err(format!("Unknown memory area: {}", flag)),
+        }
+    }
+    Ok(result)
+}
+
+/// Parse the command-line arguments (without the executable name)
+pub fn parse_args(args: &[String]) -> Result<CliCommand, String> {
+    let first = match args.first() {
+        Some(f) => f.as_str(),
+        None => return Ok(CliCommand::Help),
+    };
+
+    let rest = &args[1..];
+    let json = rest.iter().any(|a| a == "--json");
+
+    if is_preset_path(first) {
+        reject_unknown_flags(rest, &["--json"])?;
+        return Ok(CliCommand::OpenPreset {
+            path: first.to_string(),
+            json,
+        });
+    }
+    if first.starts_with('/') || matches!(first, "-h" | "--help") {
+        return parse_legacy(args);
+    }
+
+    match first.to_lowercase().as_str() {
+        "help" => Ok(CliCommand::Help),
+        "status" => {
+            reject_unknown_flags(rest, &["--json"])?;
+            Ok(CliCommand::Status { json })
+        }
+        "optimize" => parse_optimize(rest, json),
+        "config" => parse_config(rest, json),
+        "--report-all-users" => {
+            reject_unknown_flags(rest, &["--json"])?;
+            Ok(CliCommand::ReportAllUsers { json })
+        }
+        "--register-event-log" | "--unregister-event-log" => {
+            reject_unknown_flags(rest, &["--json"])?;
+            Ok(CliCommand::EventLog {
+                register: first == "--register-event-log",
+                json,
+            })
+        }
+        other => Err(format!("Unknown command: {}", other)),
+    }
+}
+
+fn reject_unknown_flags(args: &[String], allowed: &[&str]) -> Result<(), String> {
+    match args.iter().find(|a| !allowed.contains(&a.as_str())) {
+        Some(unknown) => Err(format!("Unknown argument: {}", unknown)),
+        None => Ok(()),
+    }
+}
+
+fn parse_optimize(args: &[String], json: bool) -> Result<CliCommand, String> {
+    let mut profile = None;
+    let mut areas = Areas::empty();
+    let mut targets = Vec::new();
+    let mut dry_run = false;
 
-            // Write to console if handle is available
-            let handle = CONSOLE_HANDLE.load(Ordering::Relaxed);
-            if !handle.is_null() {
-                let wide_text: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
-                let mut written = 0u32;
-                WriteConsoleW(
-                    handle as windows_sys::Win32::Foundation::HANDLE,
-                    wide_text.as_ptr() as *const _,
-                    wide_text.len() as u32 - 1,
-                    &mut written,
-                    ptr::null_mut(),
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => {}
+            "--dry-run" => dry_run = true,
+            "--profile" | "-p" => {
+                let value = iter.next().ok_or("--profile requires a value")?;
+                profile = Some(parse_profile(value)?);
+            }
+            "--areas" | "-a" => {
+                let value = iter.next().ok_or("--areas requires a value")?;
+                areas |= parse_areas(value)?;
+            }
+            "--targets" | "-t" => {
+                let value = iter.next().ok_or("--targets requires a value")?;
+                targets.extend(
+                    value
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty()),
                 );
             }
+            other => return Err(format!("Unknown argument: {}", other)),
         }
     }
 
-    // Parse command-line arguments
+    Ok(CliCommand::Optimize {
+        profile,
+        areas,
+        targets,
+        dry_run,
+        json,
+    })
+}
+
+fn parse_config(args: &[String], json: bool) -> Result<CliCommand, String> {
+    let positional: Vec<&String> = args.iter().filter(|a| *a != "--json").collect();
+
+    match positional.first().map(|a| a.to_lowercase()).as_deref() {
+        Some("get") => match positional.get(1) {
+            Some(key) if positional.len() == 2 => Ok(CliCommand::ConfigGet {
+                key: key.to_string(),
+                json,
+            }),
+            _ => Err("Usage: config get <key>".to_string()),
+        },
+        Some("set") => match (positional.get(1), positional.get(2)) {
+            (Some(key), Some(value)) if positional.len() == 3 => Ok(CliCommand::ConfigSet {
+                key: key.to_string(),
+                value: value.to_string(),
+                json,
+            }),
+            _ => Err("Usage: config set <key> <value>".to_string()),
+        },
+        Some("list") | None => Ok(CliCommand::ConfigList { json }),
+        Some(other) => Err(format!("Unknown config action: {}", other)),
+    }
+}
+
+/// Legacy syntax: `/WorkingSet /StandbyList` or `/Profile:Balanced`
+fn parse_legacy(args: &[String]) -> Result<CliCommand, String> {
     let mut areas = Areas::empty();
-    let mut profile_mode = false;
-    let mut profile_name = String::new();
+    let mut profile = None;
 
     for arg in args {
         match arg.as_str() {
-            "/?" | "/help" | "-h" | "--help" => {
-                #[cfg(windows)]
-                {
-                    console_print("Tommy Memory Cleaner - Console Mode\n\n");
-                    console_print("Usage: TommyMemoryCleaner.exe [OPTIONS]\n\n");
-                    console_print("Options:\n");
-                    console_print("  /WorkingSet              Optimize Working Set\n");
-                    console_print("  /ModifiedPageList        Optimize Modified Page List\n");
-                    console_print("  /StandbyList             Optimize Standby List\n");
-                    console_print(
-                        "  /StandbyListLow          Optimize Low Priority Standby List\n",
-                    );
-                    console_print("  /SystemFileCache         Optimize System File Cache\n");
-                    console_print("  /CombinedPageList        Optimize Combined Page List\n");
-                    console_print("  /ModifiedFileCache       Optimize Modified File Cache\n");
-                    console_print("  /RegistryCache           Optimize Registry Cache\n");
-                    console_print("  /Profile:Normal          Use Normal profile\n");
-                    console_print("  /Profile:Balanced        Use Balanced profile\n");
-                    console_print("  /Profile:Gaming          Use Gaming profile\n");
-                    console_print("  /?                       Show this help\n\n");
-                    console_print("Examples:\n");
-                    console_print("  TommyMemoryCleaner.exe /WorkingSet /StandbyList\n");
-                    console_print("  TommyMemoryCleaner.exe /Profile:Balanced\n");
-                }
-                #[cfg(not(windows))]
-                {
-                    println!("Tommy Memory Cleaner - Console Mode");
-                    println!();
-                    println!("Usage: TommyMemoryCleaner.exe [OPTIONS]");
-                    println!();
-                    println!("Options:");
-                    println!("  /WorkingSet              Optimize Working Set");
-                    println!("  /ModifiedPageList        Optimize Modified Page List");
-                    println!("  /StandbyList             Optimize Standby List");
-                    println!("  /StandbyListLow          Optimize Low Priority Standby List");
-                    println!("  /SystemFileCache         Optimize System File Cache");
-                    println!("  /CombinedPageList        Optimize Combined Page List");
-                    println!("  /ModifiedFileCache       Optimize Modified File Cache");
-                    println!("  /RegistryCache           Optimize Registry Cache");
-                    println!("  /Profile:Normal          Use Normal profile");
-                    println!("  /Profile:Balanced        Use Balanced profile");
-                    println!("  /Profile:Gaming          Use Gaming profile");
-                    println!("  /?                       Show this help");
-                    println!();
-                    println!("Examples:");
-                    println!("  TommyMemoryCleaner.exe /WorkingSet /StandbyList");
-                    println!("  TommyMemoryCleaner.exe /Profile:Balanced");
-                }
-                return;
-            }
+            "/?" | "/help" | "-h" | "--help" => return Ok(CliCommand::Help),
             arg if arg.starts_with("/Profile:") => {
-                profile_mode = true;
-                profile_name = arg.strip_prefix("/Profile:").unwrap_or("").to_string();
+                profile = Some(parse_profile(arg.strip_prefix("/Profile:").unwrap_or(""))?);
             }
             "/WorkingSet" => areas |= Areas::WORKING_SET,
             "/ModifiedPageList" => areas |= Areas::MODIFIED_PAGE_LIST,
@@ -127,183 +246,92 @@ pub fn run_console_mode(args: &[String]) {
             "/CombinedPageList" => areas |= Areas::COMBINED_PAGE_LIST,
             "/ModifiedFileCache" => areas |= Areas::MODIFIED_FILE_CACHE,
             "/RegistryCache" => areas |= Areas::REGISTRY_CACHE,
-            _ => {
-                #[cfg(windows)]
-                {
-                    console_print(&format!("Unknown argument: {}\n", arg));
-                    console_print("Use /? for help\n");
-                }
-                #[cfg(not(windows))]
-                {
-                    eprintln!("Unknown argument: {}", arg);
-                    eprintln!("Use /? for help");
-                }
-                std::process::exit(1);
-            }
+            "/MemoryCompression" => areas |= Areas::MEMORY_COMPRESSION,
+            "/Json" | "/json" => {}
+            "/DryRun" => {}
+            other => return Err(format!("Unknown argument: {}", other)),
         }
     }
 
-    // If profile mode is specified, use the profile's areas
-    if profile_mode {
-        let profile = match profile_name.as_str() {
-            "Normal" => Profile::Normal,
-            "Balanced" => Profile::Balanced,
-            "Gaming" => Profile::Gaming,
-            _ => {
-                #[cfg(windows)]
-                {
-                    console_print(&format!(
-                        "Invalid profile: {}. Use Normal, Balanced, or Gaming\n",
-                        profile_name
-                    ));
-                }
-                #[cfg(not(windows))]
-                {
-                    eprintln!(
-                        "Invalid profile: {}. Use Normal, Balanced, or Gaming",
-                        profile_name
-                    );
-                }
-                std::process::exit(1);
-            }
-        };
-        areas = profile.get_memory_areas();
-        #[cfg(windows)]
-        {
-            console_print(&format!("Using profile: {:?}\n", profile));
-        }
-        #[cfg(not(windows))]
-        {
-            println!("Using profile: {:?}", profile);
-        }
-    }
+    Ok(CliCommand::Optimize {
+        profile,
+        areas,
+        targets: Vec::new(),
+        dry_run: args.iter().any(|a| a == "/DryRun"),
+        json: args.iter().any(|a| a.eq_ignore_ascii_case("/json")),
+    })
+}
 
-    // If no areas are specified, use Balanced profile by default
-    if areas.is_empty() {
-        areas = Profile::Balanced.get_memory_areas();
-        #[cfg(windows)]
-        {
-            console_print("No areas specified, using Balanced profile\n");
-        }
-        #[cfg(not(windows))]
-        {
-            println!("No areas specified, using Balanced profile");
-        }
-    }
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    #[cfg(windows)]
-    {
-        console_print(&format!(
-            "Optimizing memory areas: {:?}\n",
-            areas.get_names()
-        ));
-    }
-    #[cfg(not(windows))]
-    {
-        println!("Optimizing memory areas: {:?}", areas.get_names());
-        io::stdout().flush().unwrap();
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
     }
 
-    // Execute optimization synchronously in console mode
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    rt.block_on(async {
-        // Initialize privileges before optimization
-        if let Err(e) = crate::ensure_privileges_initialized() {
-            #[cfg(windows)]
-            {
-                console_print(&format!("Warning: Failed to initialize privileges: {}\n", e));
-            }
-            #[cfg(not(windows))]
-            {
-                eprintln!("Warning: Failed to initialize privileges: {}", e);
-            }
-        }
-        
-        // Initialize configuration
-        let cfg = match Config::load() {
-            Ok(c) => c,
-            Err(e) => {
-                #[cfg(windows)]
-                {
-                    console_print(&format!("Failed to load config: {}\n", e));
-                    console_print("Using default configuration\n");
-                }
-                #[cfg(not(windows))]
-                {
-                    eprintln!("Failed to load config: {}", e);
-                    eprintln!("Using default configuration");
-                }
-                Config::default()
+    #[test]
+    fn test_parse_optimize_subcommand() {
+        let cmd = parse_args(&args(&[
+            "optimize",
+            "--profile",
+            "gaming",
+            "--areas",
+            "STANDBY_LIST|WORKING_SET",
+            "--json",
+        ]))
+        .unwrap();
+        assert_eq!(
+            cmd,
+            CliCommand::Optimize {
+                profile: Some(Profile::Gaming),
+                areas: Areas::STANDBY_LIST | Areas::WORKING_SET,
+                targets: Vec::new(),
+                dry_run: false,
+                json: true,
             }
-        };
-
-        // Create Arc<Mutex<Config>> for the engine
-        let cfg_arc = Arc::new(Mutex::new(cfg));
-        let engine = Engine::new(cfg_arc.clone());
+        );
+    }
 
-        // Execute memory optimization with progress callback
-        let progress_callback = |current: u8, total: u8, area: String| {
-            #[cfg(windows)]
-            {
-                console_print(&format!("[{}/{}] Optimizing: {}\n", current + 1, total, area));
+    #[test]
+    fn test_parse_config_and_legacy() {
+        assert_eq!(
+            parse_args(&args(&["config", "set", "auto_opt_interval_hours", "2"])).unwrap(),
+            CliCommand::ConfigSet {
+                key: "auto_opt_interval_hours".to_string(),
+                value: "2".to_string(),
+                json: false,
             }
-            #[cfg(not(windows))]
-            {
-                println!("[{}/{}] Optimizing: {}", current + 1, total, area);
-                io::stdout().flush().unwrap();
-            }
-        };
-        
-        match engine.optimize(Reason::Manual, areas, Some(progress_callback)) {
-            Ok(result) => {
-                let freed_mb = result.freed_physical_bytes.abs() as f64 / 1024.0 / 1024.0;
-                #[cfg(windows)]
-                {
-                    console_print("Optimization completed successfully\n");
-                    console_print(&format!("Freed: {:.2} MB\n", freed_mb));
-                }
-                #[cfg(not(windows))]
-                {
-                    println!("Optimization completed successfully");
-                    println!("Freed: {:.2} MB", freed_mb);
-                }
-
-                // Display results for each optimized area
-                for area in result.areas {
-                    if let Some(error) = area.error {
-                        #[cfg(windows)]
-                        {
-                            console_print(&format!("  {}: FAILED - {}\n", area.name, error));
-                        }
-                        #[cfg(not(windows))]
-                        {
-                            eprintln!("  {}: FAILED - {}", area.name, error);
-                        }
-                    } else {
-                        #[cfg(windows)]
-                        {
-                            console_print(&format!("  {}: OK\n", area.name));
-                        }
-                        #[cfg(not(windows))]
-                        {
-                            println!("  {}: OK", area.name);
-                        }
-                    }
-                }
+        );
+        assert!(matches!(
+            parse_args(&args(&["/WorkingSet", "/Profile:Normal"])).unwrap(),
+            CliCommand::Optimize { profile: Some(Profile::Normal), .. }
+        ));
+        assert!(parse_args(&args(&["optimize", "--areas", "BOGUS"])).is_err());
+    }
 
-                std::process::exit(0);
+    #[test]
+    fn test_is_cli_invocation() {
+        assert!(is_cli_invocation(&args(&["status", "--json"])));
+        assert!(is_cli_invocation(&args(&["/StandbyList"])));
+        assert!(is_cli_invocation(&args(&["--report-all-users", "--json"])));
+        assert_eq!(
+            parse_args(&args(&["--unregister-event-log"])).unwrap(),
+            CliCommand::EventLog {
+                register: false,
+                json: false
             }
-            Err(e) => {
-                #[cfg(windows)]
-                {
-                    console_print(&format!("Optimization failed: {}\n", e));
-                }
-                #[cfg(not(windows))]
-                {
-                    eprintln!("Optimization failed: {}", e);
-                }
-                std::process::exit(1);
+        );
+        assert_eq!(
+            parse_args(&args(&["C:\\Downloads\\Tarkov.TMCPRESET"])).unwrap(),
+            CliCommand::OpenPreset {
+                path: "C:\\Downloads\\Tarkov.TMCPRESET".to_string(),
+                json: false
             }
-        }
-    });
+        );
+        assert!(!is_cli_invocation(&args(&["--startup-config"])));
+        assert!(!is_cli_invocation(&args(&["--minimized"])));
+        assert!(!is_cli_invocation(&[]));
+    }
 }
@@ -0,0 +1,599 @@
+/// Console mode executor.
+///
+/// Executes the parsed `CliCommand` headless: no webview, tray or window is
+/// created. With `--json` every command prints a single JSON document on
+/// stdout so the output can be consumed by scripts.
+use super::parser::{parse_args, CliCommand};
+use crate::config::{Config, ConfigService, Profile};
+use crate::engine::Engine;
+use crate::memory::types::{Areas, Reason};
+use crate::stats::usage::Trigger;
+use crate::ui::bridge::ProgressEvent;
+use serde_json::{json, Value};
+use std::io::Write;
+
+const EXIT_OK: i32 = 0;
+const EXIT_FAILURE: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+/// Attach to the parent console so stdout/stderr reach the terminal.
+///
+/// The release build uses the Windows subsystem and has no console of its own.
+/// When the output is redirected (pipes, files) the inherited handles are
+/// used as they are.
+#[cfg(windows)]
+fn attach_console() {
+    use windows_sys::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+fn out(text: &str) {
+    let mut stdout = std::io::stdout();
+    let _ = writeln!(stdout, "{}", text);
+    let _ = stdout.flush();
+}
+
+fn err(text: &str) {
+    let _ = writeln!(std::io::stderr(), "{}", text);
+}
+
+fn print_json(value: &Value) {
+    out(&serde_json::to_string_pretty(value).unwrap_or_else(|_| "{}".to_string()));
+}
+
+/// Report a failure either as JSON or as plain text
+fn fail(message: &str, json: bool, code: i32) -> i32 {
+    if json {
+        print_json(&json!({ "ok": false, "error": message }));
+    } else {
+        err(&format!("Error: {}", message));
+    }
+    code
+}
+
+const HELP_TEXT: &str = "Tommy Memory Cleaner - Console Mode
+
+Usage:
+  TommyMemoryCleaner.exe optimize [--profile <name>] [--areas <A|B>] [--targets <p1,p2>] [--dry-run] [--json]
+  TommyMemoryCleaner.exe status [--json]
+  TommyMemoryCleaner.exe config list [--json]
+  TommyMemoryCleaner.exe config get <key> [--json]
+  TommyMemoryCleaner.exe config set <key> <value> [--json]
+  TommyMemoryCleaner.exe --report-all-users [--json]   (run as administrator)
+  TommyMemoryCleaner.exe --register-event-log | --unregister-event-log [--json]   (run as administrator)
+  TommyMemoryCleaner.exe <file>.tmcpreset [--json]   (import and apply a preset)
+
+Areas:
+  WORKING_SET, MODIFIED_PAGE_LIST, STANDBY_LIST, STANDBY_LIST_LOW,
+  SYSTEM_FILE_CACHE, COMBINED_PAGE_LIST, MODIFIED_FILE_CACHE, REGISTRY_CACHE,
+  MEMORY_COMPRESSION
+
+Profiles:
+  Normal, Balanced, Gaming
+
+Legacy options:
+  /WorkingSet /ModifiedPageList /StandbyList /StandbyListLow /SystemFileCache
+  /CombinedPageList /ModifiedFileCache /RegistryCache /MemoryCompression
+  /Profile:<name> /DryRun /?
+
+Examples:
+  TommyMemoryCleaner.exe optimize --profile gaming --areas STANDBY_LIST|WORKING_SET
+  TommyMemoryCleaner.exe optimize --profile gaming --dry-run
+  TommyMemoryCleaner.exe status --json
+  TommyMemoryCleaner.exe config set auto_opt_interval_hours 2";
+
+/// Runs the application in console mode with command-line arguments.
+///
+/// Parses the arguments, executes the command and exits the process with
+/// 0 on success, 1 on failure and 2 on invalid usage.
+///
+/// # Arguments
+///
+/// * `args` - Slice of command-line arguments (without the executable name)
+pub fn run_console_mode(args: &[String]) {
+    attach_console();
+
+    let json = args.iter().any(|a| a == "--json" || a.eq_ignore_ascii_case("/json"));
+    let code = match parse_args(args) {
+        Ok(command) => execute(command),
+        Err(e) => {
+            let code = fail(&e, json, EXIT_USAGE);
+            if !json {
+                err("Use 'help' or /? for usage");
+            }
+            code
+        }
+    };
+
+    std::process::exit(code);
+}
+
+fn execute(command: CliCommand) -> i32 {
+    match command {
+        CliCommand::Help => {
+            out(HELP_TEXT);
+            EXIT_OK
+        }
+        CliCommand::Optimize {
+            profile,
+            areas,
+            targets,
+            dry_run,
+            json,
+        } => run_optimize(profile, areas, targets, dry_run, json),
+        CliCommand::Status { json } => run_status(json),
+        CliCommand::ConfigList { json } => run_config_list(json),
+        CliCommand::ConfigGet { key, json } => run_config_get(&key, json),
+        CliCommand::ConfigSet { key, value, json } => run_config_set(&key, &value, json),
+        CliCommand::ReportAllUsers { json } => run_report_all_users(json),
+        CliCommand::EventLog { register, json } => run_event_log(register, json),
+        CliCommand::OpenPreset { path, json } => run_open_preset(&path, json),
+    }
+}
+
+fn load_config(json: bool) -> Config {
+    match Config::load() {
+        Ok(c) => {
+            crate::i18n::set_language(&c.language);
+            c
+        }
+        Err(e) => {
+            if !json {
+                err(&format!("Failed to load config: {}", e));
+                err("Using default configuration");
+            }
+            Config::default()
+        }
+    }
+}
+
+fn run_optimize(
+    profile: Option<Profile>,
+    areas: Areas,
+    targets: Vec<String>,
+    dry_run: bool,
+    json: bool,
+) -> i32 {
+    // Explicit areas win over the profile; no areas at all means Balanced
+    let profile_used = profile.unwrap_or(Profile::Balanced);
+    let mut areas = if !areas.is_empty() {
+        areas
+    } else {
+        if profile.is_none() && !json {
+            out("No areas specified, using Balanced profile");
+        }
+        profile_used.get_memory_areas()
+    };
+
+    // Targets only make sense for the Working Set
+    if !targets.is_empty() {
+        areas = Areas::WORKING_SET;
+    }
+
+    if !json {
+        if let Some(p) = profile {
+            out(&format!("Using profile: {:?}", p));
+        }
+        out(&format!("Optimizing memory areas: {:?}", areas.get_names()));
+        if dry_run {
+            out("Dry run: nothing will be freed");
+        }
+    }
+
+    if let Err(e) = crate::ensure_privileges_initialized() {
+        if !json {
+            err(&format!("Warning: Failed to initialize privileges: {}", e));
+        }
+    }
+
+    let cfg_arc = ConfigService::new(load_config(json));
+    let engine = Engine::new(cfg_arc.clone());
+
+    let progress = |event: ProgressEvent| {
+        if let ProgressEvent::AreaStarted { index, total, area } = event {
+            if !json {
+                out(&format!("[{}/{}] Optimizing: {}", index, total, area));
+            }
+        }
+    };
+
+    let targets = if targets.is_empty() { None } else { Some(targets) };
+    match engine.optimize_with_targets(Reason::Manual, areas, targets, dry_run, Some(progress)) {
+        Ok(result) if result.dry_run => print_dry_run(&result, profile_used, areas, json),
+        Ok(result) => {
+            crate::stats::record_optimization(&result, &format!("{:?}", profile_used));
+            crate::stats::usage::record_run(&cfg_arc.read(), &result, Some(Trigger::Cli));
+
+            let failed = result.areas.iter().filter(|a| a.error.is_some()).count();
+            if json {
+                print_json(&json!({
+                    "ok": failed == 0,
+                    "profile": profile_used,
+                    "areas": areas.get_names(),
+                    "result": result,
+                }));
+            } else {
+                let freed_mb = result.freed_physical_bytes.abs() as f64 / 1024.0 / 1024.0;
+                out(&crate::i18n::tr_args(
+                    "Optimization completed in %d ms, freed %.2f MB",
+                    &[result.duration_ms.into(), freed_mb.into()],
+                ));
+                if let Some(bench) = &result.benchmark {
+                    out(&format!(
+                        "Allocation latency: {:.0} µs -> {:.0} µs, page faults: {:.0}/s -> {:.0}/s",
+                        bench.before.alloc_latency_us,
+                        bench.after.alloc_latency_us,
+                        bench.before.page_faults_per_sec,
+                        bench.after.page_faults_per_sec
+                    ));
+                }
+                for area in result.areas.iter().filter(|a| a.error.is_some()) {
+                    err(&format!(
+                        "  {} failed: {}",
+                        area.name,
+                        area.error.as_deref().unwrap_or("unknown error")
+                    ));
+                }
+            }
+
+            if failed == 0 {
+                EXIT_OK
+            } else {
+                EXIT_FAILURE
+            }
+        }
+        Err(e) => fail(&format!("Optimization failed: {}", e), json, EXIT_FAILURE),
+    }
+}
+
+fn print_dry_run(
+    result: &crate::engine::OptimizeResult,
+    profile: Profile,
+    areas: Areas,
+    json: bool,
+) -> i32 {
+    let to_mb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0;
+    if json {
+        print_json(&json!({
+            "ok": true,
+            "dry_run": true,
+            "profile": profile,
+            "areas": areas.get_names(),
+            "estimated_freed_bytes": result.estimated_freed_bytes(),
+            "result": result,
+        }));
+        return EXIT_OK;
+    }
+
+    for area in &result.areas {
+        let detail = match (&area.estimated_bytes, &area.skipped, &area.error) {
+            (_, Some(why), _) => format!("skipped ({})", why),
+            (_, _, Some(e)) => format!("unavailable ({})", e),
+            (Some(bytes), _, _) => format!("~{:.2} MB", to_mb(*bytes)),
+            (None, _, _) => "not measurable".to_string(),
+        };
+        out(&format!("  {:<28} {}", area.name, detail));
+    }
+    out(&crate::i18n::tr_args(
+        "Estimated reclaimable: %.2f MB",
+        &[to_mb(result.estimated_freed_bytes()).into()],
+    ));
+    EXIT_OK
+}
+
+fn run_status(json: bool) -> i32 {
+    let cfg = load_config(json);
+    let mem = match crate::memory::ops::memory_info() {
+        Ok(m) => m,
+        Err(e) => return fail(&format!("Failed to read memory info: {}", e), json, EXIT_FAILURE),
+    };
+
+    if json {
+        print_json(&json!({
+            "ok": true,
+            "version": crate::config::app_info::VERSION,
+            "elevated": crate::system::is_app_elevated(),
+            "memory": mem,
+            "profile": cfg.profile,
+            "memory_areas": cfg.memory_areas.get_names(),
+            "auto_opt_interval_hours": cfg.auto_opt_interval_hours,
+            "auto_opt_free_threshold": cfg.auto_opt_free_threshold,
+        }));
+    } else {
+        let to_gb = |bytes: u64| bytes as f64 / 1024.0 / 1024.0 / 1024.0;
+        out(&format!(
+            "Tommy Memory Cleaner {}",
+            crate::config::app_info::VERSION
+        ));
+        out(&format!(
+            "Physical: {:.2} GB free of {:.2} GB ({}% free)",
+            to_gb(mem.physical.free.bytes),
+            to_gb(mem.physical.total.bytes),
+            mem.physical.free.percentage
+        ));
+        out(&format!(
+            "Commit:   {:.2} GB free of {:.2} GB ({}% free)",
+            to_gb(mem.commit.free.bytes),
+            to_gb(mem.commit.total.bytes),
+            mem.commit.free.percentage
+        ));
+        if mem.hardware_reserved_bytes > 0 {
+            out(&format!(
+                "Installed: {:.2} GB, {:.2} GB reserved by hardware",
+                to_gb(mem.installed_bytes),
+                to_gb(mem.hardware_reserved_bytes)
+            ));
+        }
+        out(&format!("Load:     {}%", mem.load_percent));
+        if mem.game_mode_active {
+            out("Game Mode: active");
+        }
+        out(&format!("Profile:  {:?} ({})", cfg.profile, cfg.memory_areas));
+        out(&format!(
+            "Auto:     every {} h, below {}% free",
+            cfg.auto_opt_interval_hours, cfg.auto_opt_free_threshold
+        ));
+    }
+    EXIT_OK
+}
+
+fn run_report_all_users(json: bool) -> i32 {
+    let report = crate::stats::multi_user::build_machine_report();
+
+    if json {
+        print_json(&json!({ "ok": true, "report": report }));
+        return EXIT_OK;
+    }
+
+    let to_mb = |bytes: i64| bytes as f64 / 1024.0 / 1024.0;
+    if !report.elevated {
+        err("Warning: not running as administrator, other users' history may be unreadable");
+    }
+    out(&format!(
+        "Users: {} scanned, {} with history, {} unreadable",
+        report.users_scanned, report.users_with_data, report.users_unreadable
+    ));
+    out(&format!(
+        "Total: {} optimizations ({} automatic), freed {:.2} MB (avg {:.2} MB)",
+        report.total.optimizations,
+        report.total.automatic,
+        to_mb(report.total.freed_physical_bytes),
+        to_mb(report.total.average_freed_bytes)
+    ));
+    for user in &report.users {
+        match &user.summary {
+            Some(summary) => out(&format!(
+                "  {:<20} {:>6} runs  {:>10.2} MB freed",
+                user.user,
+                summary.optimizations,
+                to_mb(summary.freed_physical_bytes)
+            )),
+            None => out(&format!(
+                "  {:<20} {:?}{}",
+                user.user,
+                user.status,
+                user.error
+                    .as_deref()
+                    .map(|e| format!(" ({})", e))
+                    .unwrap_or_default()
+            )),
+        }
+    }
+    EXIT_OK
+}
+
+fn run_event_log(register: bool, json: bool) -> i32 {
+    use crate::logging::event_viewer::{register_provider, unregister_provider};
+
+    let result = if register {
+        register_provider()
+    } else {
+        unregister_provider()
+    };
+    match result {
+        Ok(()) => {
+            if json {
+                print_json(&json!({ "ok": true }));
+            } else if register {
+                out("Event Log provider registered");
+            } else {
+                out("Event Log provider removed");
+            }
+            EXIT_OK
+        }
+        Err(e) => fail(&e.to_string(), json, EXIT_FAILURE),
+    }
+}
+
+/// Import a preset opened from Explorer and make it the active profile
+///
+/// A running instance picks the saved configuration up through live reload.
+/// Without `--json` the outcome is shown in a message box, Explorer gives the
+/// process no console.
+fn run_open_preset(path: &str, json: bool) -> i32 {
+    let mut cfg = load_config(json);
+    let result =
+        crate::config::preset_file::import_preset(std::path::Path::new(path), &mut cfg, true)
+            .and_then(|report| {
+                cfg.validate();
+                cfg.save()?;
+                Ok(report)
+            });
+
+    match result {
+        Ok(report) => {
+            if json {
+                print_json(&json!({ "ok": true, "preset": report }));
+            } else {
+                let mut body = crate::i18n::tr_args(
+                    "Preset '%s' is now the active profile",
+                    &[report.name.clone().into()],
+                );
+                if !report.dropped_areas.is_empty() {
+                    body.push_str(&format!(
+                        "\n• Not supported on this PC: {}",
+                        report.dropped_areas.join(", ")
+                    ));
+                }
+                for warning in &report.warnings {
+                    body.push_str(&format!("\n• {}", warning));
+                }
+                message_box(&crate::i18n::tr("TMC • Preset applied"), &body, false);
+            }
+            EXIT_OK
+        }
+        Err(e) => {
+            if !json {
+                message_box(
+                    &crate::i18n::tr("TMC • Preset not applied"),
+                    &format!("{:#}", e),
+                    true,
+                );
+            }
+            fail(&format!("{:#}", e), json, EXIT_FAILURE)
+        }
+    }
+}
+
+#[cfg(windows)]
+fn message_box(title: &str, body: &str, error: bool) {
+    use crate::utils::to_wide;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        MessageBoxW, MB_ICONERROR, MB_ICONINFORMATION, MB_OK,
+    };
+
+    let title = to_wide(title);
+    let body = to_wide(body);
+    let icon = if error {
+        MB_ICONERROR
+    } else {
+        MB_ICONINFORMATION
+    };
+    unsafe {
+        MessageBoxW(
+            std::ptr::null_mut(),
+            body.as_ptr(),
+            title.as_ptr(),
+            MB_OK | icon,
+        );
+    }
+}
+
+#[cfg(not(windows))]
+fn message_box(title: &str, body: &str, _error: bool) {
+    out(&format!("{}\n{}", title, body));
+}
+
+/// Resolve a dotted key (`tray.warning_level`) inside the config JSON
+fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |v, part| v.get(part))
+}
+
+fn lookup_mut<'a>(value: &'a mut Value, key: &str) -> Option<&'a mut Value> {
+    key.split('.').try_fold(value, |v, part| v.get_mut(part))
+}
+
+fn config_value(cfg: &Config) -> Result<Value, String> {
+    serde_json::to_value(cfg).map_err(|e| format!("Failed to serialize config: {}", e))
+}
+
+fn run_config_list(json: bool) -> i32 {
+    let cfg = load_config(json);
+    match config_value(&cfg) {
+        Ok(value) => {
+            print_json(&value);
+            EXIT_OK
+        }
+        Err(e) => fail(&e, json, EXIT_FAILURE),
+    }
+}
+
+fn run_config_get(key: &str, json: bool) -> i32 {
+    let cfg = load_config(json);
+    let value = match config_value(&cfg) {
+        Ok(v) => v,
+        Err(e) => return fail(&e, json, EXIT_FAILURE),
+    };
+
+    match lookup(&value, key) {
+        Some(v) if json => {
+            print_json(&json!({ "ok": true, "key": key, "value": v }));
+            EXIT_OK
+        }
+        Some(Value::String(s)) => {
+            out(s);
+            EXIT_OK
+        }
+        Some(v) => {
+            out(&v.to_string());
+            EXIT_OK
+        }
+        None => fail(&format!("Unknown config key: {}", key), json, EXIT_USAGE),
+    }
+}
+
+fn run_config_set(key: &str, raw: &str, json: bool) -> i32 {
+    let mut cfg = load_config(json);
+    let mut value = match config_value(&cfg) {
+        Ok(v) => v,
+        Err(e) => return fail(&e, json, EXIT_FAILURE),
+    };
+
+    // Values are parsed as JSON (numbers, booleans, arrays); anything else is a string
+    let new_value = serde_json::from_str::<Value>(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+
+    match lookup_mut(&mut value, key) {
+        Some(slot) => *slot = new_value,
+        None => return fail(&format!("Unknown config key: {}", key), json, EXIT_USAGE),
+    }
+
+    let startup_settings = |c: &Config| (c.run_on_startup, c.startup_method, c.startup_delay_secs);
+    let previous_startup = startup_settings(&cfg);
+    cfg = match serde_json::from_value::<Config>(value) {
+        Ok(c) => c,
+        Err(e) => {
+            return fail(
+                &format!("Invalid value for {}: {}", key, e),
+                json,
+                EXIT_USAGE,
+            )
+        }
+    };
+    cfg.validate();
+
+    if startup_settings(&cfg) != previous_startup {
+        if let Err(e) = crate::system::startup::set_run_on_startup(
+            cfg.run_on_startup,
+            cfg.startup_method,
+            cfg.startup_delay_secs,
+        ) {
+            return fail(
+                &format!("Failed to update startup setting: {}", e),
+                json,
+                EXIT_FAILURE,
+            );
+        }
+    }
+
+    if let Err(e) = cfg.save() {
+        return fail(&format!("Failed to save config: {}", e), json, EXIT_FAILURE);
+    }
+
+    // Report the value after validation (it may have been clamped)
+    let stored = config_value(&cfg)
+        .ok()
+        .and_then(|v| lookup(&v, key).cloned())
+        .unwrap_or(Value::Null);
+
+    if json {
+        print_json(&json!({ "ok": true, "key": key, "value": stored }));
+    } else {
+        out(&format!("{} = {}", key, stored));
+    }
+    EXIT_OK
+}
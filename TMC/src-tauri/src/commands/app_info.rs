@@ -1,15 +1,18 @@
-use crate::config::app_info;
+use crate::config::{app_info, get_portable_detector};
 use serde_json::json;
 
 #[tauri::command]
 pub fn get_app_info() -> serde_json::Value {
+    let detector = get_portable_detector();
     json!({
         "name": app_info::get_app_name(),
         "version": app_info::get_version(),
         "versionFull": app_info::get_version_full(),
         "company": app_info::get_company_name(),
         "copyright": app_info::get_copyright(),
-        "description": app_info::FILE_DESCRIPTION
+        "description": app_info::FILE_DESCRIPTION,
+        "dataMode": detector.mode(),
+        "dataDir": detector.data_dir().to_string_lossy()
     })
 }
 
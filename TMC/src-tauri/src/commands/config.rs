@@ -3,16 +3,32 @@
 /// This module provides Tauri commands for managing application configuration,
 /// including loading, saving, and updating various settings such as profiles,
 /// memory areas, themes, and system preferences.
-use crate::config::{Config, Priority, Profile};
+use crate::auto_optimizer::schedule::{ScheduleEntry, ScheduleSpec, MAX_SCHEDULES};
+use crate::config::config_file::{ConfigFile, ConfigImportReport};
+use crate::config::migration::MigrationReport;
+use crate::config::preset_file::{PresetFile, PresetImportReport, PresetSummary};
+use crate::config::profile_file::{ProfileFile, ProfileImportReport};
+use crate::config::schema::ConfigValidationReport;
+use crate::config::custom_profile::MAX_CUSTOM_PROFILES;
+use crate::config::hotkey_binding::MAX_HOTKEY_BINDINGS;
+use crate::config::{
+    Config, CustomProfile, HotkeyBinding, LockedSessionPolicy, OtherSessionsPolicy, Priority,
+    Profile, SharedConfig, StartupMethod,
+};
 use crate::memory::types::Areas;
+use crate::stats::usage::Feature;
+use crate::system::recommendation::RecommendedConfig;
+use serde::Serialize;
 use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Exits the application gracefully.
 ///
 /// This command terminates the application process after logging the exit event.
 #[tauri::command]
-pub fn cmd_exit(_app: AppHandle) {
+pub fn cmd_exit(app: AppHandle) {
     tracing::info!("Exiting application...");
+    crate::stats::session::finish(&app, true);
+    crate::stats::timeline::save_session(&app.state::<crate::AppState>().cfg.read());
     std::process::exit(0);
 }
 
@@ -20,15 +36,10 @@ pub fn cmd_exit(_app: AppHandle) {
 ///
 /// # Returns
 ///
-/// Returns a clone of the current configuration or an error if the
-/// configuration lock is poisoned.
+/// Returns a snapshot of the current configuration.
 #[tauri::command]
 pub fn cmd_get_config(state: State<'_, crate::AppState>) -> Result<Config, String> {
-    state
-        .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())
-        .map(|c| c.clone())
+    Ok(state.cfg.snapshot())
 }
 
 /// Saves configuration changes from JSON data.
@@ -63,15 +74,16 @@ pub fn cmd_save_config(
         }
     }
 
-    let mut current_cfg = state
-        .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())?
-        .clone();
+    let mut current_cfg = state.cfg.snapshot();
 
-    let mut _need_menu_update = false;
+    let mut need_menu_update = false;
     let mut need_icon_update = false;
     let mut need_hotkey_update = false;
+    let mut need_sdk_pipe_start = false;
+    let mut need_widget_feed_start = false;
+    let mut need_http_api_start = false;
+    let mut need_update_checker_start = false;
+    let mut need_commit_monitor_start = false;
 
     if let Some(obj) = cfg_json.as_object() {
         // Profile handling
@@ -80,10 +92,54 @@ pub fn cmd_save_config(
                 current_cfg.profile = profile.clone();
                 current_cfg.memory_areas = profile.get_memory_areas();
                 current_cfg.run_priority = profile.get_priority();
+                current_cfg.active_custom_profile = None;
                 need_icon_update = true;
             }
         }
 
+        // Custom profiles - reject the whole save if the list is malformed
+        if let Some(v) = obj.get("custom_profiles") {
+            let profiles = serde_json::from_value::<Vec<CustomProfile>>(v.clone())
+                .map_err(|e| format!("Invalid custom profiles: {}", e))?;
+            if profiles.len() > MAX_CUSTOM_PROFILES {
+                return Err(format!(
+                    "Too many custom profiles (max {})",
+                    MAX_CUSTOM_PROFILES
+                ));
+            }
+            current_cfg.custom_profiles = profiles;
+            need_hotkey_update = true;
+        }
+
+        // Additional hotkey bindings - reject the whole save if the list is malformed
+        if let Some(v) = obj.get("hotkeys") {
+            let bindings = serde_json::from_value::<Vec<HotkeyBinding>>(v.clone())
+                .map_err(|e| format!("Invalid hotkey bindings: {}", e))?;
+            if bindings.len() > MAX_HOTKEY_BINDINGS {
+                return Err(format!(
+                    "Too many hotkey bindings (max {})",
+                    MAX_HOTKEY_BINDINGS
+                ));
+            }
+            current_cfg.hotkeys = bindings;
+            need_hotkey_update = true;
+        }
+
+        // Active custom profile (null returns to the built-in profile)
+        if let Some(v) = obj.get("active_custom_profile") {
+            if v.is_null() {
+                current_cfg.active_custom_profile = None;
+                current_cfg.memory_areas = current_cfg.profile.get_memory_areas();
+                current_cfg.run_priority = current_cfg.profile.get_priority();
+            } else if let Some(name) = v.as_str() {
+                if current_cfg.find_custom_profile(name).is_none() {
+                    return Err(format!("Unknown custom profile: {}", name));
+                }
+                current_cfg.select_profile(name);
+            }
+            need_icon_update = true;
+        }
+
         // Memory areas
         if let Some(v) = obj.get("memory_areas") {
             if let Some(areas_num) = v.as_u64() {
@@ -106,7 +162,8 @@ pub fn cmd_save_config(
             if let Some(s) = v.as_str() {
                 let old_language = current_cfg.language.clone();
                 current_cfg.language = s.to_string();
-                _need_menu_update = true;
+                crate::i18n::set_language(s);
+                need_menu_update = true;
 
                 // Emit event if language actually changed
                 if old_language != s.to_string() {
@@ -148,6 +205,7 @@ pub fn cmd_save_config(
             if let Ok(tray) = serde_json::from_value::<crate::config::TrayConfig>(v.clone()) {
                 current_cfg.tray = tray;
                 need_icon_update = true;
+                need_menu_update = true;
             }
         }
 
@@ -164,8 +222,9 @@ pub fn cmd_save_config(
 
         update_bool!(always_on_top);
         update_bool!(minimize_to_tray);
+        update_bool!(destroy_webview_on_hide);
+        update_bool!(start_minimized);
         update_bool!(show_opt_notifications);
-        update_bool!(auto_update);
         update_bool!(close_after_opt);
         update_bool!(request_elevation_on_startup);
         // Setup completed - important to prevent setup from opening multiple times
@@ -186,11 +245,40 @@ pub fn cmd_save_config(
                 current_cfg.is_windows_10 = b;
             }
         }
+        // Startup method and delay, applied together with run_on_startup
+        let previous_startup = (current_cfg.startup_method, current_cfg.startup_delay_secs);
+        if let Some(v) = obj.get("startup_method") {
+            if let Ok(method) = serde_json::from_value::<StartupMethod>(v.clone()) {
+                current_cfg.startup_method = method;
+            }
+        }
+        if let Some(v) = obj.get("startup_delay_secs") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.startup_delay_secs = n.min(600) as u32;
+            }
+        }
+        let startup_changed =
+            (current_cfg.startup_method, current_cfg.startup_delay_secs) != previous_startup;
+        if startup_changed && current_cfg.run_on_startup {
+            // Migrate to the new method; unlike the toggle below a failure is
+            // reported, the scheduled task needs administrator rights
+            crate::system::startup::set_run_on_startup(
+                true,
+                current_cfg.startup_method,
+                current_cfg.startup_delay_secs,
+            )
+            .map_err(|e| format!("Failed to change the startup method: {}", e))?;
+        }
+
         // Handle run_on_startup specially - it needs to call the system function
         if let Some(v) = obj.get("run_on_startup") {
             if let Some(b) = v.as_bool() {
                 // Execute operation and log any errors
-                if let Err(e) = crate::system::startup::set_run_on_startup(b) {
+                if let Err(e) = crate::system::startup::set_run_on_startup(
+                    b,
+                    current_cfg.startup_method,
+                    current_cfg.startup_delay_secs,
+                ) {
                     tracing::error!("Error enabling automatic startup (settings): {:?}", e);
                 }
                 // Force the boolean value chosen by user in config,
@@ -223,6 +311,128 @@ pub fn cmd_save_config(
             }
         }
 
+        if let Some(v) = obj.get("auto_opt_only_when_idle") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.auto_opt_only_when_idle = b;
+            }
+        }
+
+        if let Some(v) = obj.get("idle_minutes") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.idle_minutes = n.clamp(1, 240) as u32;
+            }
+        }
+
+        if let Some(v) = obj.get("locked_session_policy") {
+            if let Ok(policy) = serde_json::from_value::<LockedSessionPolicy>(v.clone()) {
+                current_cfg.locked_session_policy = policy;
+            }
+        }
+
+        if let Some(v) = obj.get("other_sessions_policy") {
+            if let Ok(policy) = serde_json::from_value::<OtherSessionsPolicy>(v.clone()) {
+                current_cfg.other_sessions_policy = policy;
+            }
+        }
+
+        if let Some(v) = obj.get("busy_process_cpu_percent") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.busy_process_cpu_percent = n.min(100) as u8;
+            }
+        }
+
+        if let Some(v) = obj.get("allow_system_process_trim") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.allow_system_process_trim = b;
+            }
+        }
+
+        if let Some(v) = obj.get("exclude_child_processes") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.exclude_child_processes = b;
+            }
+        }
+
+        if let Some(v) = obj.get("use_documented_apis_only") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.use_documented_apis_only = b;
+            }
+        }
+
+        if let Some(v) = obj.get("area_settle_ms") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.area_settle_ms = n.min(5000) as u32;
+            }
+        }
+
+        if let Some(v) = obj.get("area_verify_retries") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.area_verify_retries = n.min(3) as u8;
+            }
+        }
+
+        if let Some(v) = obj.get("measurement_delay_ms") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.measurement_delay_ms = n.min(10_000) as u32;
+            }
+        }
+
+        if let Some(v) = obj.get("measurement_samples") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.measurement_samples = n.clamp(1, 9) as u8;
+            }
+        }
+
+        if let Some(v) = obj.get("benchmark_enabled") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.benchmark_enabled = b;
+            }
+        }
+
+        if let Some(v) = obj.get("adaptive_area_selection") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.adaptive_area_selection = b;
+            }
+        }
+
+        // Power source
+        if let Some(v) = obj.get("skip_auto_opt_on_battery") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.skip_auto_opt_on_battery = b;
+            }
+        }
+
+        if let Some(v) = obj.get("profile_on_battery") {
+            if v.is_null() {
+                current_cfg.profile_on_battery = None;
+            } else if let Some(name) = v.as_str() {
+                if current_cfg.profile_areas(name).is_none() {
+                    return Err(format!("Unknown profile: {}", name));
+                }
+                current_cfg.profile_on_battery = Some(name.trim().to_string());
+            }
+        }
+
+        // Per-area cooldowns for automatic runs
+        if let Some(v) = obj.get("area_cooldowns") {
+            let cooldowns = serde_json::from_value::<Vec<crate::config::AreaCooldown>>(v.clone())
+                .map_err(|e| format!("Invalid area cooldowns: {}", e))?;
+            current_cfg.area_cooldowns = cooldowns;
+        }
+
+        // System File Cache limits per profile
+        if let Some(v) = obj.get("file_cache_limits") {
+            let limits = serde_json::from_value::<Vec<crate::config::FileCacheLimit>>(v.clone())
+                .map_err(|e| format!("Invalid file cache limits: {}", e))?;
+            current_cfg.file_cache_limits = limits;
+        }
+
+        if let Some(v) = obj.get("manual_cooldown_secs") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.manual_cooldown_secs = n.min(3600) as u32;
+            }
+        }
+
         if let Some(v) = obj.get("font_size") {
             if let Some(n) = v.as_f64() {
                 current_cfg.font_size = (n as f32).clamp(8.0, 24.0);
@@ -238,6 +448,250 @@ pub fn cmd_save_config(
             }
         }
 
+        // Process inclusions (targeted working set trimming)
+        if let Some(v) = obj.get("process_include_list") {
+            if let Ok(list) =
+                serde_json::from_value::<std::collections::BTreeSet<String>>(v.clone())
+            {
+                current_cfg.process_include_list = list;
+            }
+        }
+
+        if let Some(v) = obj.get("strict_nt_errors") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.strict_nt_errors = b;
+            }
+        }
+
+        // Processes kept small by gentle maintenance instead of full trims
+        if let Some(v) = obj.get("gentle_maintenance_processes") {
+            if let Ok(list) =
+                serde_json::from_value::<std::collections::BTreeSet<String>>(v.clone())
+            {
+                current_cfg.gentle_maintenance_processes = list;
+            }
+        }
+
+        if let Some(v) = obj.get("gentle_maintenance_interval_secs") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.gentle_maintenance_interval_secs = n.min(u32::MAX as u64) as u32;
+            }
+        }
+
+        if let Some(v) = obj.get("document_editor_processes") {
+            if let Ok(list) =
+                serde_json::from_value::<std::collections::BTreeSet<String>>(v.clone())
+            {
+                current_cfg.document_editor_processes = list;
+            }
+        }
+
+        if let Some(v) = obj.get("skip_foreground_process") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.skip_foreground_process = b;
+            }
+        }
+
+        if let Some(v) = obj.get("skip_audio_processes") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.skip_audio_processes = b;
+            }
+        }
+
+        // Schedule rules - reject the whole save if a rule is invalid
+        if let Some(v) = obj.get("schedules") {
+            let entries = serde_json::from_value::<Vec<ScheduleEntry>>(v.clone())
+                .map_err(|e| format!("Invalid schedules: {}", e))?;
+            if entries.len() > MAX_SCHEDULES {
+                return Err(format!("Too many schedules (max {})", MAX_SCHEDULES));
+            }
+            for entry in &entries {
+                ScheduleSpec::parse(&entry.rule)
+                    .map_err(|e| format!("Invalid schedule '{}': {}", entry.name, e))?;
+            }
+            current_cfg.schedules = entries;
+        }
+
+        // Free RAM floor (null restores the profile defaults)
+        if let Some(v) = obj.get("free_ram_floor") {
+            if v.is_null() {
+                current_cfg.free_ram_floor = None;
+            } else if let Ok(floor) =
+                serde_json::from_value::<crate::config::FreeRamFloor>(v.clone())
+            {
+                current_cfg.free_ram_floor = Some(floor);
+            }
+        }
+
+        // Working Set trim strategy (null restores the profile default)
+        if let Some(v) = obj.get("trim_strategy") {
+            if v.is_null() {
+                current_cfg.trim_strategy = None;
+            } else if let Ok(strategy) =
+                serde_json::from_value::<crate::memory::types::TrimStrategy>(v.clone())
+            {
+                current_cfg.trim_strategy = Some(strategy);
+            }
+        }
+
+        // Toast notification options
+        if let Some(v) = obj.get("toast") {
+            if let Ok(toast) = serde_json::from_value::<crate::notifications::ToastOptions>(v.clone())
+            {
+                current_cfg.toast = toast;
+            }
+        }
+        if let Some(v) = obj.get("accessibility") {
+            if let Ok(overrides) = serde_json::from_value(v.clone()) {
+                current_cfg.accessibility = overrides;
+                need_icon_update = true;
+            }
+        }
+        if let Some(v) = obj.get("notification_verbosity") {
+            if let Ok(verbosity) =
+                serde_json::from_value::<crate::notifications::ProfileNotifications>(v.clone())
+            {
+                current_cfg.notification_verbosity = verbosity;
+            }
+        }
+        if let Some(v) = obj.get("notify_nothing_freed") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.notify_nothing_freed = b;
+            }
+        }
+
+        if let Some(v) = obj.get("session_summary_notification") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.session_summary_notification = b;
+            }
+        }
+
+        if let Some(v) = obj.get("temp_cleaner") {
+            if let Ok(options) =
+                serde_json::from_value::<crate::storage::TempCleanerOptions>(v.clone())
+            {
+                current_cfg.temp_cleaner = options;
+            }
+        }
+
+        // Pause/resume of the automatic triggers
+        if let Some(v) = obj.get("auto_optimizer_enabled") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.auto_optimizer_enabled = b;
+            }
+        }
+
+        // Memory pressure prediction
+        if let Some(v) = obj.get("pressure_prediction") {
+            if let Ok(prediction) = serde_json::from_value::<
+                crate::auto_optimizer::prediction::PressurePrediction,
+            >(v.clone())
+            {
+                current_cfg.pressure_prediction = prediction;
+            }
+        }
+
+        // Automatic Gaming profile
+        if let Some(v) = obj.get("game_mode") {
+            if let Ok(game_mode) =
+                serde_json::from_value::<crate::auto_optimizer::game_mode::GameMode>(v.clone())
+            {
+                current_cfg.game_mode = game_mode;
+            }
+        }
+
+        // Low memory emergency mode
+        if let Some(v) = obj.get("emergency_mode") {
+            if let Ok(mode) =
+                serde_json::from_value::<crate::auto_optimizer::emergency::EmergencyMode>(v.clone())
+            {
+                current_cfg.emergency_mode = mode;
+            }
+        }
+
+        // Local SDK pipe
+        if let Some(v) = obj.get("sdk_pipe_enabled") {
+            if let Some(b) = v.as_bool() {
+                need_sdk_pipe_start = b && !current_cfg.sdk_pipe_enabled;
+                current_cfg.sdk_pipe_enabled = b;
+            }
+        }
+        if let Some(v) = obj.get("sdk_allowed_clients") {
+            if let Ok(list) =
+                serde_json::from_value::<std::collections::BTreeSet<String>>(v.clone())
+            {
+                current_cfg.sdk_allowed_clients = list;
+            }
+        }
+        if let Some(v) = obj.get("sdk_rate_limit_per_minute") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.sdk_rate_limit_per_minute = n.clamp(1, 60) as u32;
+            }
+        }
+
+        if let Some(v) = obj.get("auto_update") {
+            if let Some(b) = v.as_bool() {
+                need_update_checker_start = b && !current_cfg.auto_update;
+                current_cfg.auto_update = b;
+            }
+        }
+
+        if let Some(v) = obj.get("widget_feed_enabled") {
+            if let Some(b) = v.as_bool() {
+                need_widget_feed_start = b && !current_cfg.widget_feed_enabled;
+                current_cfg.widget_feed_enabled = b;
+            }
+        }
+        if let Some(v) = obj.get("widget_feed_interval_secs") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.widget_feed_interval_secs = n.clamp(1, 60) as u32;
+            }
+        }
+
+        // Local HTTP API
+        if let Some(v) = obj.get("http_api_enabled") {
+            if let Some(b) = v.as_bool() {
+                need_http_api_start = b && !current_cfg.http_api_enabled;
+                current_cfg.http_api_enabled = b;
+            }
+        }
+        if let Some(v) = obj.get("http_api_port") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.http_api_port =
+                    n.clamp(crate::ipc::http_api::MIN_PORT as u64, u16::MAX as u64) as u16;
+            }
+        }
+        if let Some(v) = obj.get("http_api_token") {
+            if let Some(s) = v.as_str() {
+                current_cfg.http_api_token = crate::ipc::http_api::sanitize_token(s);
+            }
+        }
+
+        if let Some(v) = obj.get("timeline_interval_secs") {
+            if let Some(n) = v.as_u64() {
+                current_cfg.timeline_interval_secs = n.clamp(1, 300) as u32;
+            }
+        }
+        if let Some(v) = obj.get("timeline_persist") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.timeline_persist = b;
+            }
+        }
+        if let Some(v) = obj.get("usage_metrics") {
+            if let Some(b) = v.as_bool() {
+                current_cfg.usage_metrics = b;
+            }
+        }
+
+        // Commit charge warning (0 = off)
+        if let Some(v) = obj.get("commit_warning_percent") {
+            if let Some(n) = v.as_u64() {
+                let percent = if n == 0 { 0 } else { n.clamp(50, 99) as u8 };
+                need_commit_monitor_start = percent > 0 && current_cfg.commit_warning_percent == 0;
+                current_cfg.commit_warning_percent = percent;
+            }
+        }
+
         // Priority
         if let Some(v) = obj.get("run_priority") {
             if let Ok(priority) = serde_json::from_value::<Priority>(v.clone()) {
@@ -249,41 +703,36 @@ pub fn cmd_save_config(
     // Validate and save
     current_cfg.validate();
 
-    // FIX #2: Release lock as soon as possible - save config with retry then release
-    {
-        let mut guard = state
-            .cfg
-            .lock()
-            .map_err(|_| "Config lock poisoned".to_string())?;
-        *guard = current_cfg.clone();
-
-        // Save with retry for better reliability
-        let save_result = guard.save();
-        match save_result {
-            Ok(_) => {
-                tracing::debug!("Config saved successfully");
-            }
-            Err(e) => {
-                tracing::warn!("Failed to save config: {:?}, retrying...", e);
-                // Retry once after a short delay
-                std::thread::sleep(std::time::Duration::from_millis(100));
-                guard.save().map_err(|e2| {
-                    tracing::error!("Failed to save config on retry: {:?}", e2);
-                    format!("Failed to save config: {}", e2)
-                })?;
-            }
+    // The edits above ran on a snapshot, only the swap and the save hold the lock
+    match state.cfg.update_and_save(|c| *c = current_cfg.clone()) {
+        Ok(_) => {
+            tracing::debug!("Config saved successfully");
+        }
+        Err(e) => {
+            tracing::warn!("Failed to save config: {:?}, retrying...", e);
+            // Retry once after a short delay
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            state.cfg.read().save().map_err(|e2| {
+                tracing::error!("Failed to save config on retry: {:?}", e2);
+                format!("Failed to save config: {}", e2)
+            })?;
         }
-        // Lock is automatically released here
     }
 
     // Update UI - all these operations happen AFTER the lock has been released
-    // Note: update_menu no longer exists, menu is managed via HTML
+    // The webview menu is managed via HTML, only the native one is rebuilt here
+
+    crate::memory::file_cache::apply_profile_limit(&current_cfg);
 
     if need_icon_update {
         // Force tray update
         crate::ui::tray::refresh_tray_icon(&app);
     }
 
+    if need_menu_update {
+        crate::ui::tray::apply_native_menu(&app);
+    }
+
     if need_hotkey_update {
         if let Err(e) =
             crate::register_global_hotkey_v2(&app, &current_cfg.hotkey, state.inner().cfg.clone())
@@ -292,18 +741,187 @@ pub fn cmd_save_config(
         }
     }
 
+    if need_sdk_pipe_start {
+        crate::ipc::start_pipe_server(
+            app.clone(),
+            state.engine.clone(),
+            state.inner().cfg.clone(),
+        );
+    }
+
+    if need_widget_feed_start {
+        crate::ipc::widget_feed::start_widget_feed(
+            state.engine.clone(),
+            state.inner().cfg.clone(),
+        );
+    }
+
+    if need_http_api_start {
+        crate::ipc::http_api::start_http_api(
+            app.clone(),
+            state.engine.clone(),
+            state.inner().cfg.clone(),
+        );
+    }
+
+    if need_update_checker_start {
+        crate::updater::start_update_checker(app.clone(), state.inner().cfg.clone());
+    }
+
+    if need_commit_monitor_start {
+        crate::auto_optimizer::commit_guard::start_commit_monitor(
+            app.clone(),
+            state.engine.clone(),
+            state.inner().cfg.clone(),
+        );
+    }
+
     // Emit config-changed event for tray menu
     let _ = app.emit("config-changed", ());
 
     Ok(())
 }
 
+/// Activates a built-in or custom profile by name.
+///
+/// Used by the tray menu and the profile hotkeys to switch profiles without
+/// sending the whole configuration.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `name` - `Normal`, `Balanced`, `Gaming` or the name of a custom profile
+///
+/// # Returns
+///
+/// Returns the name of the active profile, or an error string if no profile
+/// has that name or the configuration cannot be saved.
+#[tauri::command]
+pub fn cmd_select_profile(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    name: String,
+) -> Result<String, String> {
+    let active = select_profile(&state.cfg, &name)?;
+    crate::stats::usage::record_feature(&state.cfg.read(), Feature::ProfileSwitch);
+    crate::ui::tray::refresh_tray_icon(&app);
+    let _ = app.emit("config-changed", ());
+    Ok(active)
+}
+
+/// Activate a profile in the shared configuration and persist it
+pub fn select_profile(cfg: &SharedConfig, name: &str) -> Result<String, String> {
+    let mut guard = cfg.write();
+    if !guard.select_profile(name) {
+        return Err(format!("Unknown profile: {}", name));
+    }
+    guard
+        .save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+    tracing::info!("Active profile: {}", guard.active_profile_name());
+    crate::memory::file_cache::apply_profile_limit(&guard);
+    Ok(guard.active_profile_name())
+}
+
+/// Result of `cmd_toggle_area`
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaToggleResult {
+    /// Area set in use after the change
+    pub areas: Areas,
+    pub warnings: Vec<String>,
+}
+
+/// Enables or disables a single memory area.
+///
+/// Used by the area checkboxes of the main window instead of saving the whole
+/// areas string on every click. With a custom profile active the change
+/// applies to that profile.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `area` - Area flag name, e.g. `STANDBY_LIST`
+/// * `enabled` - Whether the area should be optimized
+///
+/// # Returns
+///
+/// Returns the resolved area set with validation warnings, or an error string
+/// if the area is unknown or unavailable, or the configuration cannot be saved.
+#[tauri::command]
+pub fn cmd_toggle_area(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    area: String,
+    enabled: bool,
+) -> Result<AreaToggleResult, String> {
+    let flag = Areas::from_name(&area.trim().to_uppercase())
+        .filter(|flag| flag.bits().count_ones() == 1)
+        .ok_or_else(|| format!("Unknown memory area: {}", area))?;
+
+    let (areas, warnings) = {
+        let mut cfg = state.cfg.write();
+        let result = cfg.toggle_area(flag, enabled)?;
+        cfg.save()
+            .map_err(|e| format!("Failed to save config: {}", e))?;
+        result
+    };
+    tracing::debug!("Area {} {}: {}", area, if enabled { "on" } else { "off" }, areas);
+
+    let _ = app.emit("config-changed", ());
+    Ok(AreaToggleResult { areas, warnings })
+}
+
+/// Records the user's confirmation of an aggressive optimization area.
+///
+/// `WORKING_SET` (all processes) and `SYSTEM_FILE_CACHE` are skipped by
+/// automatic runs until the user confirms them once; the confirmation is kept
+/// in the configuration.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `area` - Area flag name, e.g. `WORKING_SET`
+/// * `confirmed` - `false` withdraws an earlier confirmation
+///
+/// # Returns
+///
+/// Returns the confirmed areas, or an error string if the area is unknown,
+/// does not need a confirmation or the configuration cannot be saved.
+#[tauri::command]
+pub fn cmd_confirm_area(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    area: String,
+    confirmed: Option<bool>,
+) -> Result<Areas, String> {
+    let flag = Areas::from_name(&area.trim().to_uppercase())
+        .ok_or_else(|| format!("Unknown memory area: {}", area))?;
+    if flag.is_empty() || !Areas::AGGRESSIVE.contains(flag) {
+        return Err(format!("{} does not require confirmation", area));
+    }
+
+    let confirmed_areas = {
+        let mut cfg = state.cfg.write();
+        cfg.confirmed_areas.set(flag, confirmed.unwrap_or(true));
+        cfg.save()
+            .map_err(|e| format!("Failed to save config: {}", e))?;
+        cfg.confirmed_areas
+    };
+    tracing::info!("Confirmed areas for automatic runs: {}", confirmed_areas);
+
+    let _ = app.emit("config-changed", ());
+    Ok(confirmed_areas)
+}
+
 /// Completes the setup wizard with provided configuration.
 ///
 /// This command applies the initial configuration settings chosen during
 /// the first-time setup, including startup preferences, theme, language,
-/// and window behavior. It also handles the transition from setup window
-/// to the main application window.
+/// window behavior and the hardware recommendation when accepted. It also
+/// handles the transition from setup window to the main application window.
 ///
 /// # Arguments
 ///
@@ -321,10 +939,7 @@ pub fn cmd_complete_setup(
     state: State<'_, crate::AppState>,
     setup_data: serde_json::Value,
 ) -> Result<(), String> {
-    let mut cfg = state
-        .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())?;
+    let mut cfg = state.cfg.write();
 
     // Apply settings from setup
     if let Some(obj) = setup_data.as_object() {
@@ -343,7 +958,11 @@ pub fn cmd_complete_setup(
         if let Some(v) = obj.get("run_on_startup") {
             if let Some(b) = v.as_bool() {
                 // Execute operation and log any errors
-                if let Err(e) = crate::system::startup::set_run_on_startup(b) {
+                if let Err(e) = crate::system::startup::set_run_on_startup(
+                    b,
+                    cfg.startup_method,
+                    cfg.startup_delay_secs,
+                ) {
                     tracing::error!("Failed to set startup during setup: {:?}", e);
                 }
                 // Force the boolean value chosen by user in config,
@@ -383,6 +1002,15 @@ pub fn cmd_complete_setup(
         if let Some(v) = obj.get("language") {
             if let Some(s) = v.as_str() {
                 cfg.language = s.to_string();
+                crate::i18n::set_language(s);
+            }
+        }
+
+        // Hardware recommendation accepted in the wizard
+        if let Some(v) = obj.get("recommended") {
+            match serde_json::from_value::<RecommendedConfig>(v.clone()) {
+                Ok(recommended) => recommended.apply(&mut cfg),
+                Err(e) => tracing::warn!("Ignoring invalid recommended settings: {}", e),
             }
         }
     }
@@ -452,6 +1080,8 @@ pub fn cmd_complete_setup(
     };
     let language = cfg.language.clone();
     let always_on_top = cfg.always_on_top;
+    // The tray updater and the auto-optimizer started below read the config
+    drop(cfg);
 
     // Show the main window FIRST, THEN close setup
     // Ensure the main window exists, otherwise create it
@@ -461,21 +1091,8 @@ pub fn cmd_complete_setup(
     } else {
         tracing::info!("Main window not found, creating it...");
         // Create the main window if it doesn't exist
-        match tauri::WebviewWindowBuilder::new(
-            &app,
-            "main",
-            tauri::WebviewUrl::App("index.html".into()),
-        )
-        .title("Tommy Memory Cleaner")
-        .inner_size(500.0, 700.0)
-        .resizable(false)
-        .decorations(false)
-        .transparent(true)
-        .shadow(false)  // Disabilita shadow per Windows 10
-        .skip_taskbar(false)
-        .visible(false) // FIX: Create hidden to avoid flash
-        .build()
-        {
+        // FIX: Create hidden to avoid flash
+        match crate::commands::ui::build_main_window(&app, false) {
             Ok(window) => {
                 tracing::info!("Main window created successfully after setup");
                 Some(window)
@@ -591,6 +1208,8 @@ pub fn cmd_complete_setup(
             engine_for_auto,
             cfg_for_auto
         );
+
+        crate::auto_optimizer::start_game_mode(app.clone(), state.cfg.clone());
     }
 
     // DO NOT close setup here - let frontend close it after verifying
@@ -598,3 +1217,392 @@ pub fn cmd_complete_setup(
 
     Ok(())
 }
+
+/// Exports the current optimization profile to a shareable .tmcprofile file.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing the configuration
+/// * `path` - Destination file path (the .tmcprofile extension is added if missing)
+/// * `name` - Display name stored in the profile file
+/// * `exclusions` - Optional subset of the exclusion list to include (all if `None`)
+///
+/// # Returns
+///
+/// Returns the path of the written file, or an error string if the export fails.
+#[tauri::command]
+pub fn cmd_export_profile(
+    state: State<'_, crate::AppState>,
+    path: String,
+    name: String,
+    exclusions: Option<Vec<String>>,
+) -> Result<String, String> {
+    let profile = {
+        let cfg = state.cfg.read();
+        ProfileFile::from_config(&cfg, &name, exclusions.as_deref())
+    };
+
+    let written = profile
+        .save_to(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())?;
+    tracing::info!("✓ Profile '{}' exported to {}", profile.name, written.display());
+
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Imports a .tmcprofile file and applies it to the configuration.
+///
+/// Areas not supported on this Windows build are dropped, and imported
+/// exclusions are merged with the existing ones.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `path` - Path of the profile file to import
+///
+/// # Returns
+///
+/// Returns a `ProfileImportReport` describing what was applied and dropped,
+/// or an error string if the file is invalid.
+#[tauri::command]
+pub fn cmd_import_profile(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    path: String,
+) -> Result<ProfileImportReport, String> {
+    let mut profile =
+        ProfileFile::load_from(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+    let (dropped_areas, warnings) = profile.validate();
+
+    if !dropped_areas.is_empty() {
+        tracing::warn!(
+            "Profile '{}' contains areas unsupported on this system: {}",
+            profile.name,
+            dropped_areas.join(", ")
+        );
+    }
+
+    {
+        let mut guard = state.cfg.write();
+        profile.apply_to(&mut guard);
+        guard.validate();
+        guard
+            .save()
+            .map_err(|e| format!("Failed to save config: {}", e))?;
+        crate::memory::file_cache::apply_profile_limit(&guard);
+    }
+
+    tracing::info!("✓ Profile '{}' imported from {}", profile.name, path);
+
+    crate::ui::tray::refresh_tray_icon(&app);
+    let _ = app.emit("config-changed", ());
+
+    Ok(ProfileImportReport {
+        name: profile.name.clone(),
+        applied_areas: profile.memory_areas,
+        dropped_areas,
+        warnings,
+    })
+}
+
+/// Exports a custom profile, or the profile in use, to a shareable .tmcpreset file.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing the configuration
+/// * `path` - Destination file path (the .tmcpreset extension is added if missing)
+/// * `name` - Display name stored in the preset
+/// * `description` - Optional description stored in the preset
+/// * `profile` - Custom profile to export, the profile in use if `None`
+///
+/// # Returns
+///
+/// Returns the path of the written file, or an error string if the export fails.
+#[tauri::command]
+pub fn cmd_export_preset(
+    state: State<'_, crate::AppState>,
+    path: String,
+    name: String,
+    description: Option<String>,
+    profile: Option<String>,
+) -> Result<String, String> {
+    let mut preset = {
+        let cfg = state.cfg.read();
+        PresetFile::from_config(
+            &cfg,
+            &name,
+            description.as_deref().unwrap_or_default(),
+            profile.as_deref(),
+        )
+        .map_err(|e| e.to_string())?
+    };
+    preset.validate().map_err(|e| e.to_string())?;
+
+    let written = preset
+        .save_to(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())?;
+    tracing::info!(
+        "✓ Preset '{}' exported to {}",
+        preset.name,
+        written.display()
+    );
+
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Imports a .tmcpreset file into the preset folder and optionally applies it.
+///
+/// Areas not supported on this Windows build are dropped. Applying adds (or
+/// replaces) the custom profile named after the preset and activates it.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `path` - Path of the preset file to import
+/// * `apply` - Activate the preset after importing it
+///
+/// # Returns
+///
+/// Returns a `PresetImportReport` describing what was stored and dropped,
+/// or an error string if the file is invalid.
+#[tauri::command]
+pub fn cmd_import_preset(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    path: String,
+    apply: bool,
+) -> Result<PresetImportReport, String> {
+    let report = {
+        let mut guard = state.cfg.write();
+        let report = crate::config::preset_file::import_preset(
+            std::path::Path::new(&path),
+            &mut guard,
+            apply,
+        )
+        .map_err(|e| e.to_string())?;
+        if apply {
+            save_applied_preset(&mut guard)?;
+        }
+        report
+    };
+
+    tracing::info!("✓ Preset '{}' imported from {}", report.name, path);
+    if apply {
+        crate::ui::tray::refresh_tray_icon(&app);
+        let _ = app.emit("config-changed", ());
+    }
+
+    Ok(report)
+}
+
+/// Lists the presets stored in the data directory.
+///
+/// # Returns
+///
+/// Returns the stored presets sorted by name.
+#[tauri::command]
+pub fn cmd_list_presets() -> Vec<PresetSummary> {
+    crate::config::preset_file::list_presets()
+}
+
+/// Applies a stored preset as the active custom profile.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `name` - Name of the stored preset (case-insensitive)
+///
+/// # Returns
+///
+/// Returns `Ok(())` once the preset is active, or an error string if it is
+/// unknown or the custom profile limit is reached.
+#[tauri::command]
+pub fn cmd_apply_preset(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    name: String,
+) -> Result<(), String> {
+    let mut preset = crate::config::preset_file::stored_preset(&name).map_err(|e| e.to_string())?;
+    // Supported areas can differ from when the preset was stored
+    preset.validate().map_err(|e| e.to_string())?;
+
+    {
+        let mut guard = state.cfg.write();
+        preset.apply_to(&mut guard).map_err(|e| e.to_string())?;
+        save_applied_preset(&mut guard)?;
+    }
+
+    tracing::info!("✓ Preset '{}' applied", preset.name);
+    crate::ui::tray::refresh_tray_icon(&app);
+    let _ = app.emit("config-changed", ());
+
+    Ok(())
+}
+
+/// Deletes a stored preset; the custom profile created from it is kept.
+///
+/// # Arguments
+///
+/// * `name` - Name of the stored preset (case-insensitive)
+///
+/// # Returns
+///
+/// Returns `Ok(())` once the file is removed, or an error string otherwise.
+#[tauri::command]
+pub fn cmd_delete_preset(name: String) -> Result<(), String> {
+    crate::config::preset_file::delete_preset(&name).map_err(|e| e.to_string())?;
+    tracing::info!("Preset '{}' deleted", name);
+    Ok(())
+}
+
+fn save_applied_preset(cfg: &mut crate::config::Config) -> Result<(), String> {
+    cfg.validate();
+    cfg.save()
+        .map_err(|e| format!("Failed to save config: {}", e))?;
+    crate::memory::file_cache::apply_profile_limit(cfg);
+    Ok(())
+}
+
+/// Exports the whole configuration to a .tmcconfig settings file.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing the configuration
+/// * `path` - Destination file path (the .tmcconfig extension is added if missing)
+///
+/// # Returns
+///
+/// Returns the path of the written file, or an error string if the export fails.
+#[tauri::command]
+pub fn cmd_export_config(
+    state: State<'_, crate::AppState>,
+    path: String,
+) -> Result<String, String> {
+    let file = {
+        let cfg = state.cfg.read();
+        ConfigFile::from_config(&cfg).map_err(|e| e.to_string())?
+    };
+
+    let written = file
+        .save_to(std::path::Path::new(&path))
+        .map_err(|e| e.to_string())?;
+    tracing::info!("✓ Settings exported to {}", written.display());
+
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Imports a .tmcconfig settings file, replacing the current configuration.
+///
+/// The imported configuration is migrated and validated like on startup;
+/// areas not supported on this Windows build are dropped and the startup
+/// settings of this machine are kept.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `path` - Path of the settings file to import
+///
+/// # Returns
+///
+/// Returns a `ConfigImportReport` describing what was dropped, or an error
+/// string if the file is invalid.
+#[tauri::command]
+pub fn cmd_import_config(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    path: String,
+) -> Result<ConfigImportReport, String> {
+    let file = ConfigFile::load_from(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+
+    let (hotkey, report) = {
+        let mut guard = state.cfg.write();
+        let (imported, report) = file.into_config(&guard).map_err(|e| e.to_string())?;
+        *guard = imported;
+        guard
+            .save()
+            .map_err(|e| format!("Failed to save config: {}", e))?;
+        (guard.hotkey.clone(), report)
+    };
+
+    if !report.dropped_areas.is_empty() {
+        tracing::warn!(
+            "Imported settings contain areas unsupported on this system: {}",
+            report.dropped_areas.join(", ")
+        );
+    }
+    tracing::info!("✓ Settings imported from {}", path);
+
+    if let Err(e) = crate::register_global_hotkey_v2(&app, &hotkey, state.inner().cfg.clone()) {
+        tracing::error!("Failed to register hotkey: {}", e);
+    }
+    crate::ipc::start_pipe_server(app.clone(), state.engine.clone(), state.inner().cfg.clone());
+    crate::ipc::widget_feed::start_widget_feed(state.engine.clone(), state.inner().cfg.clone());
+    crate::ipc::http_api::start_http_api(
+        app.clone(),
+        state.engine.clone(),
+        state.inner().cfg.clone(),
+    );
+    crate::ui::tray::refresh_tray_icon(&app);
+    let _ = app.emit("config-changed", ());
+
+    Ok(report)
+}
+
+/// Settles a conflict between a hand-edited config.json and unsaved running settings.
+///
+/// # Arguments
+///
+/// * `app` - The application handle for emitting events
+/// * `state` - The application state containing the configuration
+/// * `use_file` - Apply the edited file when `true`, else save the running settings over it
+///
+/// # Returns
+///
+/// Returns whether a conflict was pending, or an error string if the running
+/// settings cannot be saved.
+#[tauri::command]
+pub fn cmd_resolve_config_conflict(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    use_file: bool,
+) -> Result<bool, String> {
+    crate::config::live_reload::resolve_conflict(&app, &state.cfg, use_file)
+}
+
+/// Validates a config.json (or a .tmcconfig settings file) against the config schema.
+///
+/// Nothing is loaded or changed; meant for files edited by hand or by scripts.
+///
+/// # Arguments
+///
+/// * `path` - Path of the file to check
+///
+/// # Returns
+///
+/// Returns the `ConfigValidationReport` with the errors and the unknown settings
+/// found, or an error string if the file cannot be read.
+#[tauri::command]
+pub async fn cmd_validate_config_file(path: String) -> Result<ConfigValidationReport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::config::schema::validate_config_file(std::path::Path::new(&path))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Returns the report of the last legacy data migration.
+///
+/// Used by diagnostics to show what was moved from older TMC versions.
+///
+/// # Returns
+///
+/// Returns the `MigrationReport`, or `None` if no legacy data was ever found.
+#[tauri::command]
+pub fn cmd_get_migration_report() -> Result<Option<MigrationReport>, String> {
+    Ok(crate::config::migration::last_report())
+}
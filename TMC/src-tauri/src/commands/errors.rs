@@ -0,0 +1,48 @@
+/// Recent error and log commands.
+///
+/// This module exposes the errors collected from background subsystems so the
+/// UI can show failures that would otherwise only reach the log, and the tail
+/// of the log file itself for bug reports.
+use crate::logging::error_bus::{self, RecentErrors};
+use crate::logging::file_log::{self, LogEntry, DEFAULT_RECENT_LOGS};
+
+/// Retrieves the recently reported errors, newest first.
+///
+/// # Arguments
+///
+/// * `mark_seen` - Clear the unseen badge (default `true`)
+///
+/// # Returns
+///
+/// Returns the errors and how many had not been seen before this call.
+#[tauri::command]
+pub fn cmd_get_recent_errors(mark_seen: Option<bool>) -> Result<RecentErrors, String> {
+    Ok(error_bus::recent_errors(mark_seen.unwrap_or(true)))
+}
+
+/// Removes all recorded errors.
+#[tauri::command]
+pub fn cmd_clear_recent_errors() -> Result<(), String> {
+    error_bus::clear();
+    Ok(())
+}
+
+/// Retrieves the last entries of the log file, newest first.
+///
+/// Meant for bug reports: the release build has no console output.
+///
+/// # Arguments
+///
+/// * `n` - Number of entries to return (default 200, at most 2000)
+///
+/// # Returns
+///
+/// Returns the entries; empty if nothing was logged to the file yet.
+#[tauri::command]
+pub async fn cmd_get_recent_logs(n: Option<usize>) -> Result<Vec<LogEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        file_log::recent_logs(n.unwrap_or(DEFAULT_RECENT_LOGS))
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
@@ -0,0 +1,105 @@
+/// Optimization history commands.
+///
+/// This module exposes the persistent optimization history recorded by the
+/// stats subsystem, with filtering and paging for charts in the UI.
+use crate::stats::adaptive::AreaEffectiveness;
+use crate::stats::session::{self, SessionReport};
+use crate::stats::timeline::{self, MemoryTimeline, TimelineRange};
+use crate::stats::usage::{self, UsageReport};
+use crate::stats::{HistoryPage, HistoryQuery};
+use tauri::State;
+
+/// Retrieves recorded optimizations, newest first.
+///
+/// # Arguments
+///
+/// * `query` - Optional filters (time range, reason, profile) and paging
+///
+/// # Returns
+///
+/// Returns a `HistoryPage` with the total number of matching records
+/// and the requested page.
+#[tauri::command]
+pub fn cmd_get_optimization_history(query: Option<HistoryQuery>) -> Result<HistoryPage, String> {
+    Ok(crate::stats::query_history(&query.unwrap_or_default()))
+}
+
+/// Retrieves the memory composition timeline, oldest first.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing the configuration
+/// * `range` - Optional time range and maximum number of points
+///
+/// # Returns
+///
+/// Returns the in use, modified, standby, free and compressed bytes of
+/// each sample, averaged down to the requested number of points.
+#[tauri::command]
+pub fn cmd_memory_timeline(
+    state: State<'_, crate::AppState>,
+    range: Option<TimelineRange>,
+) -> Result<MemoryTimeline, String> {
+    let interval_secs = state.cfg.read().timeline_interval_secs;
+    Ok(timeline::query_timeline(
+        &range.unwrap_or_default(),
+        interval_secs,
+    ))
+}
+
+/// Retrieves what adaptive area selection learned about each area.
+///
+/// # Returns
+///
+/// Returns one entry per area found in the history with its recent yield,
+/// the adaptive skips since it last ran and whether the next run skips it.
+#[tauri::command]
+pub fn cmd_get_area_effectiveness() -> Result<Vec<AreaEffectiveness>, String> {
+    Ok(crate::stats::adaptive::effectiveness())
+}
+
+/// Retrieves the summary of this app session and of the previous one.
+///
+/// # Returns
+///
+/// Returns the optimizations, the memory they freed and the RAM usage peak
+/// since the app started, and the summary written when it last exited.
+#[tauri::command]
+pub fn cmd_get_session_summary() -> Result<SessionReport, String> {
+    Ok(session::report())
+}
+
+/// Retrieves the anonymized summary of the local usage metrics.
+///
+/// # Returns
+///
+/// Returns the optimizations per reason with the average memory freed, the
+/// runs per trigger, feature use and area failures, without timestamps,
+/// paths or names.
+#[tauri::command]
+pub fn cmd_get_usage_report() -> Result<UsageReport, String> {
+    Ok(usage::usage_report())
+}
+
+/// Writes the usage report to a JSON file the user can attach to an issue.
+///
+/// # Arguments
+///
+/// * `path` - Destination file path (the .json extension is added if missing)
+///
+/// # Returns
+///
+/// Returns the path of the written file, or an error string if the export fails.
+#[tauri::command]
+pub fn cmd_export_usage_report(path: String) -> Result<String, String> {
+    let written = usage::export_report(std::path::Path::new(&path)).map_err(|e| e.to_string())?;
+    tracing::info!("✓ Usage report exported to {}", written.display());
+    Ok(written.to_string_lossy().to_string())
+}
+
+/// Clears the local usage metrics and deletes their file.
+#[tauri::command]
+pub fn cmd_reset_usage_metrics() -> Result<(), String> {
+    usage::reset();
+    Ok(())
+}
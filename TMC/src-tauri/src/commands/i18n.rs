@@ -1,103 +1,173 @@
-/// Internationalization (i18n) support module.
+/// Internationalization (i18n) commands.
 ///
-/// This module provides functionality for managing translations and localization
-/// in the application. It includes caching mechanisms for translations received
-/// from the frontend and utilities for retrieving translated strings.
-use parking_lot::RwLock;
-use serde::{Deserialize, Serialize};
+/// The translations themselves live in `crate::i18n`; these commands let the
+/// frontend switch the backend language and report incomplete language packs.
+use crate::i18n::BUNDLED_LANGUAGES;
+use serde::Serialize;
 use std::collections::HashMap;
-use std::sync::Arc;
-use tauri::State;
 
-/// Represents a cached translation set for a specific language.
+/// Switches the language used by backend strings (notifications, tray tooltip).
 ///
-/// This structure stores the language code and a mapping of translation keys
-/// to their corresponding translated strings.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TranslationCache {
-    /// The language code (e.g., "en", "it", "es")
-    pub language: String,
-    /// Mapping of translation keys to translated strings
-    pub translations: HashMap<String, String>,
-}
-
-impl Default for TranslationCache {
-    fn default() -> Self {
-        Self {
-            language: "en".to_string(),
-            translations: HashMap::new(),
-        }
-    }
-}
-
-/// Type alias for the thread-safe translation state.
-pub type TranslationState = Arc<RwLock<TranslationCache>>;
-
-/// Caches translations from the frontend for backend use.
-///
-/// This command receives translation data from the frontend and stores it
-/// in a thread-safe cache for later use by backend components.
+/// Override files are read again, so edits to them apply on the next switch.
 ///
 /// # Arguments
 ///
-/// * `app_state` - The application state containing the translation cache
-/// * `language` - The language code for the translations
-/// * `translations` - HashMap of translation keys to translated strings
+/// * `language` - The language code (e.g., "en", "it", "pt-BR")
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if translations are cached successfully,
-/// or an error string if the operation fails.
+/// Returns `Ok(())` once the language is set.
 #[tauri::command]
-pub fn cmd_set_translations(
-    app_state: State<'_, crate::AppState>,
-    language: String,
-    translations: HashMap<String, String>,
-) -> Result<(), String> {
-    tracing::info!(
-        "Received translations request for language: {} with {} keys",
-        language,
-        translations.len()
-    );
-
-    let mut cache = app_state.translations.write();
-    cache.language = language;
-    cache.translations = translations;
-    tracing::info!(
-        "Translations cached successfully for language: {}",
-        cache.language
-    );
+pub fn cmd_set_translations(language: String) -> Result<(), String> {
+    crate::i18n::reload();
+    crate::i18n::set_language(&language);
     Ok(())
 }
 
-/// Retrieves a cached translation for the given key.
+/// Translation keys looked up by backend code (notifications, tray tooltip, CLI).
 ///
-/// This function looks up the translation for the specified key in the
-/// current language's translation cache. If the key is not found, it returns
-/// the key itself and logs a warning.
-///
-/// # Arguments
-///
-/// * `state` - The translation state containing the cache
-/// * `key` - The translation key to look up
-///
-/// # Returns
+/// Keep this list in sync when adding new `i18n::tr` calls so the
+/// completeness checker can report them.
+pub const BACKEND_TRANSLATION_KEYS: &[&str] = &[
+    "TMC • Optimization completed",
+    "TMC • Scheduled optimization",
+    "TMC • Low memory optimization",
+    "TMC • Hotkey optimization",
+    "TMC • Predicted pressure optimization",
+    "TMC • Emergency optimization",
+    "TMC • Preset applied",
+    "TMC • Preset not applied",
+    "Preset '%s' is now the active profile",
+    "TMC • Memory critically low",
+    "%s is using %s. Close it to free memory? Unsaved work in it may be lost.",
+    "✅ Freed: %.1f MB\n🧠 Free RAM: %.2f GB\n🎯 Profile: %s",
+    "RAM: %d%",
+    "TMC • Update available",
+    "Version %s is available",
+    "TMC • Commit charge high",
+    "Commit charge is at %s of the limit",
+    "Page file advice",
+    "Last optimization: %s freed",
+    "%d areas failed",
+    "Used: %s • Free: %s",
+    "Standby: %s",
+    "Last optimization: %s",
+    "%s ago",
+    "%d min",
+    "%d h",
+    "%d d",
+    "⚡ Allocation: %s → %s µs (%s)",
+    "Optimization completed in %d ms, freed %.2f MB",
+    "Estimated reclaimable: %.2f MB",
+    "MB",
+    "GB",
+    "Normal",
+    "Balanced",
+    "Gaming",
+    "Open TMC",
+    "Optimize Memory",
+    "Exit",
+    "TMC • Session summary",
+    "%d optimizations, %.1f MB freed\nPeak RAM usage: %d%",
+];
+
+/// Missing keys for a single bundled language.
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingTranslations {
+    /// The language code (e.g., "it")
+    pub language: String,
+    /// Keys used by the backend that the language file does not define
+    pub missing_backend_keys: Vec<String>,
+    /// Keys defined in the English file that the language file does not define
+    pub missing_keys: Vec<String>,
+    /// Set if the language file could not be parsed
+    pub parse_error: Option<String>,
+}
+
+/// Checks every bundled language file against the backend keys and the English file.
 ///
-/// Returns the translated string if found, otherwise returns the key itself.
-pub fn get_translation(state: &TranslationState, key: &str) -> String {
-    let cache = state.read();
-    let translation = cache.translations.get(key).cloned().unwrap_or_else(|| {
+/// Only languages with at least one problem are returned.
+pub fn check_translations() -> Vec<MissingTranslations> {
+    let parse = |content: &str| serde_json::from_str::<HashMap<String, String>>(content);
+
+    let english: HashMap<String, String> = BUNDLED_LANGUAGES
+        .iter()
+        .find(|(lang, _)| *lang == "en")
+        .and_then(|(_, content)| parse(content).ok())
+        .unwrap_or_default();
+
+    let mut reports = Vec::new();
+    for (language, content) in BUNDLED_LANGUAGES {
+        let report = match parse(content) {
+            Ok(dict) => {
+                let missing_backend_keys: Vec<String> = BACKEND_TRANSLATION_KEYS
+                    .iter()
+                    .filter(|k| !dict.contains_key(**k))
+                    .map(|k| k.to_string())
+                    .collect();
+                let mut missing_keys: Vec<String> = english
+                    .keys()
+                    .filter(|k| !dict.contains_key(*k))
+                    .cloned()
+                    .collect();
+                missing_keys.sort();
+
+                MissingTranslations {
+                    language: language.to_string(),
+                    missing_backend_keys,
+                    missing_keys,
+                    parse_error: None,
+                }
+            }
+            Err(e) => MissingTranslations {
+                language: language.to_string(),
+                missing_backend_keys: Vec::new(),
+                missing_keys: Vec::new(),
+                parse_error: Some(e.to_string()),
+            },
+        };
+
+        if report.parse_error.is_some()
+            || !report.missing_backend_keys.is_empty()
+            || !report.missing_keys.is_empty()
+        {
+            reports.push(report);
+        }
+    }
+
+    reports
+}
+
+/// Logs missing translations for every bundled language (used at startup in debug builds).
+pub fn log_missing_translations() {
+    let reports = check_translations();
+    if reports.is_empty() {
+        tracing::info!("✓ All {} bundled language packs are complete", BUNDLED_LANGUAGES.len());
+        return;
+    }
+
+    for report in &reports {
+        if let Some(err) = &report.parse_error {
+            tracing::warn!("✗ Language pack '{}' cannot be parsed: {}", report.language, err);
+            continue;
+        }
         tracing::warn!(
-            "Translation not found for key: '{}' (language: {})",
-            key,
-            cache.language
+            "✗ Language pack '{}' is missing {} keys ({} used by backend): {:?}",
+            report.language,
+            report.missing_keys.len(),
+            report.missing_backend_keys.len(),
+            report.missing_backend_keys
         );
-        key.to_string()
-    });
-
-    if translation != key {
-        tracing::debug!("Found translation for '{}' -> '{}'", key, translation);
     }
+}
 
-    translation
+/// Returns the missing translation keys for each bundled language.
+///
+/// # Returns
+///
+/// Returns one entry per incomplete language pack; an empty list means
+/// every bundled language is complete.
+#[tauri::command]
+pub fn cmd_get_missing_translations() -> Result<Vec<MissingTranslations>, String> {
+    Ok(check_translations())
 }
@@ -4,8 +4,12 @@
 /// including memory information retrieval, process listing, and both synchronous
 /// and asynchronous memory optimization functionality.
 use crate::memory::types::{Areas, Reason};
+use crate::stats::usage::Feature;
 use std::time::Duration;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Maximum number of processes accepted by a single targeted optimization request
+const MAX_PROCESS_TARGETS: usize = 64;
 
 /// Retrieves current memory usage information.
 ///
@@ -20,6 +24,63 @@ pub fn cmd_memory_info(
     state.engine.memory().map_err(|e| e.to_string())
 }
 
+/// Retrieves the standby list size for each page priority (0-7).
+///
+/// Lets the UI show how much `STANDBY_LIST_LOW` (priority 0 only) frees
+/// compared to a full standby purge.
+///
+/// # Returns
+///
+/// Returns a `StandbyBreakdown` with bytes per priority, or an error string
+/// if the query fails (e.g. without administrator privileges).
+#[tauri::command]
+pub fn cmd_standby_breakdown() -> Result<crate::memory::types::StandbyBreakdown, String> {
+    crate::memory::ops::standby_breakdown().map_err(|e| e.to_string())
+}
+
+/// Retrieves memory compression store statistics.
+///
+/// Reports how many pages the store holds, how well they compress and the
+/// working set of the Memory Compression process, so the effect of the
+/// `MEMORY_COMPRESSION` area can be judged.
+///
+/// # Returns
+///
+/// Returns a `CompressionInfo`, or an error string if the query fails (e.g.
+/// without administrator privileges or with memory compression turned off).
+#[tauri::command]
+pub fn cmd_compression_info() -> Result<crate::memory::types::CompressionInfo, String> {
+    crate::memory::ops::compression_info().map_err(|e| e.to_string())
+}
+
+/// Retrieves the System File Cache size and its limits.
+///
+/// Shows how much memory the file cache holds and whether the limit of the
+/// active profile (`file_cache_limits` in the configuration) is in effect.
+///
+/// # Returns
+///
+/// Returns a `FileCacheInfo` with the current and peak size and the minimum
+/// and maximum working set, or an error string if the query fails.
+#[tauri::command]
+pub fn cmd_get_file_cache_info() -> Result<crate::memory::types::FileCacheInfo, String> {
+    crate::memory::ops::file_cache_info().map_err(|e| e.to_string())
+}
+
+/// Retrieves the result of the last finished optimization.
+///
+/// The same result is emitted as the `optimization-result` event when a run
+/// finishes; this lets a window opened later catch up.
+///
+/// # Returns
+///
+/// Returns the full `OptimizeResult` (per-area durations, errors and skip
+/// reasons), or `None` if nothing was optimized since the app started.
+#[tauri::command]
+pub fn cmd_get_last_result() -> Option<crate::engine::OptimizeResult> {
+    crate::last_optimization_result()
+}
+
 /// Retrieves a list of all running process names.
 ///
 /// # Returns
@@ -31,6 +92,89 @@ pub fn cmd_list_process_names() -> Result<Vec<String>, String> {
     Ok(crate::memory::ops::list_process_names())
 }
 
+/// Retrieves the processes using the most memory.
+///
+/// # Arguments
+///
+/// * `sort` - Column to rank by: `working_set` (default), `private_bytes` or `commit`
+/// * `limit` - Number of processes to return (default 20)
+/// * `icons` - Whether to attach each executable icon as a PNG data URL
+///
+/// # Returns
+///
+/// Returns the top processes, largest first, or an error string if the
+/// process list cannot be queried.
+#[tauri::command]
+pub async fn cmd_process_memory_list(
+    sort: Option<crate::memory::types::ProcessSortKey>,
+    limit: Option<usize>,
+    icons: Option<bool>,
+) -> Result<Vec<crate::memory::types::ProcessMemoryEntry>, String> {
+    use crate::memory::process_list::{process_memory_list, DEFAULT_PROCESS_LIMIT};
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut entries = process_memory_list(
+            sort.unwrap_or_default(),
+            limit.unwrap_or(DEFAULT_PROCESS_LIMIT),
+        )
+        .map_err(|e| e.to_string())?;
+
+        if icons.unwrap_or(false) {
+            for entry in entries.iter_mut() {
+                entry.icon = entry
+                    .path
+                    .as_deref()
+                    .and_then(crate::ui::exe_icons::exe_icon_data_url);
+            }
+        }
+        Ok(entries)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Trims the working set of a single process.
+///
+/// Critical system processes and processes in the exclusion list in effect
+/// are refused.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing configuration
+/// * `pid` - Process ID to trim
+///
+/// # Returns
+///
+/// Returns the working set of the process before and after the trim,
+/// or an error string explaining why the process was not trimmed.
+#[tauri::command]
+pub async fn cmd_trim_process(
+    state: State<'_, crate::AppState>,
+    pid: u32,
+) -> Result<crate::memory::types::SingleProcessTrim, String> {
+    {
+        let mut rl = state
+            .rate_limiter
+            .lock()
+            .map_err(|_| "Rate limiter lock poisoned".to_string())?;
+        if !rl.check_rate_limit("trim_process") {
+            return Err("Too many trim requests. Please wait before trying again.".to_string());
+        }
+    }
+
+    let (exclusions, allow_system_trim) = {
+        let cfg = state.cfg.read();
+        (cfg.process_exclusion_list_lower(), cfg.allow_system_process_trim)
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::memory::ops::trim_process(pid, &exclusions, allow_system_trim)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
 /// Retrieves a list of critical system processes.
 ///
 /// These processes should not be terminated during memory optimization
@@ -56,6 +200,11 @@ pub fn cmd_get_critical_processes() -> Result<Vec<String>, String> {
 /// * `state` - The application state containing the engine and configuration
 /// * `reason` - The reason for optimization (manual, scheduled, low memory)
 /// * `areas` - String representation of memory areas to optimize
+/// * `targets` - Optional PIDs or process names; when given, only the working set
+///   of the matching processes is trimmed
+/// * `dry_run` - Only estimate what each area would reclaim; the result is
+///   emitted as `tmc://dry_run_result` and nothing is recorded
+/// * `from_tray` - Started from the tray menu, only used by the usage metrics
 ///
 /// # Returns
 ///
@@ -67,6 +216,9 @@ pub fn cmd_optimize_async(
     state: State<'_, crate::AppState>,
     reason: Reason,
     areas: String,
+    targets: Option<Vec<String>>,
+    dry_run: Option<bool>,
+    from_tray: Option<bool>,
 ) -> Result<(), String> {
     // Rate limiting check to prevent excessive optimization requests
     {
@@ -97,6 +249,7 @@ pub fn cmd_optimize_async(
                 "STANDBY_LIST_LOW" => result |= Areas::STANDBY_LIST_LOW,
                 "SYSTEM_FILE_CACHE" => result |= Areas::SYSTEM_FILE_CACHE,
                 "WORKING_SET" => result |= Areas::WORKING_SET,
+                "MEMORY_COMPRESSION" => result |= Areas::MEMORY_COMPRESSION,
                 "" => {}
                 unknown => {
                     tracing::warn!(
@@ -110,6 +263,62 @@ pub fn cmd_optimize_async(
         result
     };
 
+    // Sanitize process targets (PIDs or names) for targeted working set trimming
+    let targets: Option<Vec<String>> = targets
+        .map(|list| {
+            list.iter()
+                .map(|t| crate::security::sanitize_process_name(t).trim().to_string())
+                .filter(|t| !t.is_empty() && !crate::security::contains_injection_patterns(t))
+                .take(MAX_PROCESS_TARGETS)
+                .collect::<Vec<String>>()
+        })
+        .filter(|list| !list.is_empty());
+
+    // Targeted mode only touches the working set of the selected processes
+    let areas_flags = if targets.is_some() {
+        Areas::WORKING_SET
+    } else {
+        areas_flags
+    };
+
+    {
+        let c = cfg.read();
+        if dry_run.unwrap_or(false) {
+            crate::stats::usage::record_feature(&c, Feature::DryRun);
+        }
+        if targets.is_some() {
+            crate::stats::usage::record_feature(&c, Feature::TargetedRun);
+        }
+    }
+
+    if dry_run.unwrap_or(false) {
+        if crate::is_optimization_running() {
+            return Err("An optimization is already running".to_string());
+        }
+        tauri::async_runtime::spawn_blocking(move || {
+            let progress_app = app.clone();
+            let result = engine.optimize_with_targets(
+                reason,
+                areas_flags,
+                targets,
+                true,
+                Some(move |event| crate::ui::bridge::emit_progress(&progress_app, event)),
+            );
+            match result {
+                Ok(res) => {
+                    let _ = app.emit(crate::ui::bridge::EV_DRY_RUN_RESULT, res);
+                }
+                Err(e) => tracing::warn!("Dry run failed: {}", e),
+            }
+            let _ = app.emit(crate::ui::bridge::EV_DONE, ());
+        });
+        return Ok(());
+    }
+
+    if from_tray.unwrap_or(false) {
+        crate::stats::usage::mark_next_from_tray();
+    }
+
     // Run optimization in background task to avoid blocking UI
     tauri::async_runtime::spawn(async move {
         crate::perform_optimization(
@@ -119,12 +328,13 @@ pub fn cmd_optimize_async(
             reason,
             true,
             Some(areas_flags),
+            targets,
         )
         .await;
 
         // Handle automatic window closing after optimization if configured
         if reason == Reason::Manual {
-            let should_close = cfg.lock().map(|c| c.close_after_opt).unwrap_or(false);
+            let should_close = cfg.with(|c| c.close_after_opt);
 
             if should_close {
                 tokio::time::sleep(Duration::from_secs(2)).await;
@@ -137,3 +347,82 @@ pub fn cmd_optimize_async(
 
     Ok(())
 }
+
+/// Purges the standby list and measures what it costs afterwards.
+///
+/// Runs a manual standby list optimization, then follows the standby list
+/// size and the system-wide hard faults for `duration_secs`. The measurement
+/// is stored with the run in the optimization history.
+///
+/// # Arguments
+///
+/// * `app` - The application handle
+/// * `state` - The application state containing the engine and configuration
+/// * `low_priority` - Purge only the low priority standby pages
+/// * `duration_secs` - Observation window, 5 to 300 seconds (default 60)
+///
+/// # Returns
+///
+/// Returns the `PurgeImpact` with its score, or an error string if another
+/// optimization is running or the purge failed.
+#[tauri::command]
+pub async fn cmd_measure_purge_impact(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    low_priority: Option<bool>,
+    duration_secs: Option<u32>,
+) -> Result<crate::benchmark::purge_impact::PurgeImpact, String> {
+    use crate::benchmark::purge_impact::{self, DEFAULT_WINDOW_SECS};
+
+    {
+        let mut rl = state
+            .rate_limiter
+            .lock()
+            .map_err(|_| "Rate limiter lock poisoned".to_string())?;
+        if !rl.check_rate_limit("purge_impact") {
+            return Err(
+                "Too many measurement requests. Please wait before trying again.".to_string(),
+            );
+        }
+    }
+    if crate::is_optimization_running() {
+        return Err("An optimization is already running".to_string());
+    }
+
+    let area = if low_priority.unwrap_or(false) {
+        Areas::STANDBY_LIST_LOW
+    } else {
+        Areas::STANDBY_LIST
+    };
+    let since = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let before = tauri::async_runtime::spawn_blocking(purge_impact::snapshot)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    crate::perform_optimization(
+        app,
+        state.engine.clone(),
+        state.cfg.clone(),
+        Reason::Manual,
+        true,
+        Some(area),
+        None,
+    )
+    .await
+    .ok_or_else(|| "The standby list purge did not run".to_string())?;
+
+    let window_secs = duration_secs.unwrap_or(DEFAULT_WINDOW_SECS);
+    let impact =
+        tauri::async_runtime::spawn_blocking(move || purge_impact::observe(before, window_secs))
+            .await
+            .map_err(|e| e.to_string())?;
+
+    if !crate::stats::attach_purge_impact(since, impact.clone()) {
+        tracing::warn!("No history record found for the purge impact measurement");
+    }
+    crate::stats::usage::record_feature(&state.cfg.read(), Feature::PurgeImpact);
+    Ok(impact)
+}
@@ -5,13 +5,17 @@
 /// system integration, theme handling, and UI management.
 pub mod app_info;
 pub mod config;
+pub mod errors;
+pub mod history;
 pub mod i18n;
 pub mod memory;
 pub mod memory_stats;
+pub mod schedule;
+pub mod storage;
 pub mod system;
 pub mod theme;
 pub mod ui;
+pub mod update;
 
 // Re-export commonly used functions for convenient access
-pub use i18n::{get_translation, TranslationState};
 pub use ui::{position_tray_menu, show_or_create_window};
@@ -0,0 +1,133 @@
+/// Schedule rule commands.
+///
+/// This module lets the UI preview when the cron-like schedule rules of the
+/// auto optimizer will fire, including rules that have not been saved yet,
+/// and pause or resume the auto optimizer as a whole.
+use crate::auto_optimizer::schedule::{ScheduleEntry, ScheduleSpec};
+use crate::auto_optimizer::scheduler::{self, AutoOptimizerStatus};
+use crate::config::Profile;
+use crate::memory::types::Areas;
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+const DEFAULT_PREVIEW_COUNT: usize = 10;
+const MAX_PREVIEW_COUNT: usize = 100;
+
+/// A single upcoming run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScheduledRun {
+    pub name: String,
+    pub rule: String,
+    /// Local time, `YYYY-MM-DDTHH:MM`
+    pub at: String,
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub areas: Areas,
+}
+
+/// Computes the next runs of the schedule rules.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing the configuration
+/// * `entries` - Optional rules to preview instead of the saved ones
+/// * `count` - Maximum number of runs to return (default 10, max 100)
+///
+/// # Returns
+///
+/// Returns the upcoming runs of all enabled rules merged in chronological
+/// order, or an error string if a rule is invalid.
+#[tauri::command]
+pub fn cmd_get_schedule_preview(
+    state: State<'_, crate::AppState>,
+    entries: Option<Vec<ScheduleEntry>>,
+    count: Option<usize>,
+) -> Result<Vec<ScheduledRun>, String> {
+    let count = count
+        .unwrap_or(DEFAULT_PREVIEW_COUNT)
+        .clamp(1, MAX_PREVIEW_COUNT);
+
+    let (entries, profile) = {
+        let cfg = state.cfg.read();
+        (
+            entries.unwrap_or_else(|| cfg.schedules.clone()),
+            cfg.profile,
+        )
+    };
+
+    preview(&entries, profile, count, chrono::Local::now())
+}
+
+/// Pauses or resumes all automatic optimizations.
+///
+/// Schedule rules, the interval, the low memory and predicted triggers and
+/// gentle maintenance keep their settings and are skipped while paused. The
+/// choice is saved in the configuration.
+///
+/// # Arguments
+///
+/// * `app` - The application handle, used to notify the windows
+/// * `state` - The application state containing the configuration
+/// * `enabled` - `false` to pause, `true` to resume
+///
+/// # Returns
+///
+/// Returns the new `AutoOptimizerStatus`, or an error string if the
+/// configuration could not be saved.
+#[tauri::command]
+pub fn cmd_set_auto_optimizer_enabled(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    enabled: bool,
+) -> Result<AutoOptimizerStatus, String> {
+    scheduler::set_auto_optimizer_enabled(&app, &state.cfg, enabled)
+}
+
+/// Retrieves whether automatic optimizations run.
+///
+/// # Returns
+///
+/// Returns the `AutoOptimizerStatus` with the seconds left of the current
+/// low memory cooldown.
+#[tauri::command]
+pub fn cmd_get_auto_optimizer_status(
+    state: State<'_, crate::AppState>,
+) -> Result<AutoOptimizerStatus, String> {
+    let enabled = state.cfg.read().auto_optimizer_enabled;
+    Ok(scheduler::auto_optimizer_status(enabled))
+}
+
+fn preview(
+    entries: &[ScheduleEntry],
+    profile: Profile,
+    count: usize,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<Vec<ScheduledRun>, String> {
+    use chrono::TimeZone;
+
+    let mut runs = Vec::new();
+    for entry in entries.iter().filter(|e| e.enabled) {
+        let spec = ScheduleSpec::parse(&entry.rule)
+            .map_err(|e| format!("Invalid schedule '{}': {}", entry.name, e))?;
+        let areas = entry.effective_areas(profile);
+
+        for at in spec.upcoming(now.naive_local(), count) {
+            // Skip times that do not exist locally (DST gap)
+            let timestamp = match chrono::Local.from_local_datetime(&at).earliest() {
+                Some(local) => local.timestamp(),
+                None => continue,
+            };
+            runs.push(ScheduledRun {
+                name: entry.name.clone(),
+                rule: entry.rule.clone(),
+                at: at.format("%Y-%m-%dT%H:%M").to_string(),
+                timestamp,
+                areas,
+            });
+        }
+    }
+
+    runs.sort_by_key(|r| r.timestamp);
+    runs.truncate(count);
+    Ok(runs)
+}
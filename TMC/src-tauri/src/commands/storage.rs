@@ -0,0 +1,88 @@
+/// Temp cleaner commands for the Tauri backend.
+///
+/// This module exposes the opt-in temporary file cleaner: a size scan that
+/// deletes nothing and a cleanup that reports progress through the same
+/// events as a memory optimization.
+use crate::storage::{self, StorageReport, TempCleanerOptions};
+use crate::ui::bridge::{emit_progress, EV_DONE, EV_STORAGE_CLEANED};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Emitter, State};
+
+/// Set while a cleanup is deleting files
+static CLEANING: AtomicBool = AtomicBool::new(false);
+
+/// Temp cleaner options, refused while the cleaner is disabled
+fn enabled_options(state: &State<'_, crate::AppState>) -> Result<TempCleanerOptions, String> {
+    let cfg = state.cfg.read();
+    if !cfg.temp_cleaner.enabled {
+        return Err("The temp cleaner is disabled in the settings".to_string());
+    }
+    Ok(cfg.temp_cleaner.clone())
+}
+
+/// Measures what a cleanup would delete, without deleting anything.
+///
+/// # Arguments
+///
+/// * `state` - The application state containing configuration
+///
+/// # Returns
+///
+/// Returns a `StorageReport` with the files and bytes per enabled location,
+/// or an error string if the temp cleaner is disabled.
+#[tauri::command]
+pub async fn cmd_scan_cleanable(
+    state: State<'_, crate::AppState>,
+) -> Result<StorageReport, String> {
+    let options = enabled_options(&state)?;
+    tauri::async_runtime::spawn_blocking(move || storage::scan(&options))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Deletes the temporary files of the enabled locations in the background.
+///
+/// Progress is emitted as `tmc://opt_progress` and `tmc://opt_done`, the
+/// final `StorageReport` as `tmc://storage_cleaned`.
+///
+/// # Arguments
+///
+/// * `app` - The application handle used to emit events
+/// * `state` - The application state containing configuration
+///
+/// # Returns
+///
+/// Returns `Ok(())` once the cleanup has started, or an error string if the
+/// temp cleaner is disabled or a cleanup or optimization is already running.
+#[tauri::command]
+pub fn cmd_clean_storage(app: AppHandle, state: State<'_, crate::AppState>) -> Result<(), String> {
+    let options = enabled_options(&state)?;
+    let (show_notification, theme) = {
+        let cfg = state.cfg.read();
+        (cfg.show_opt_notifications, cfg.theme.clone())
+    };
+
+    // Both report through the same progress footer
+    if crate::is_optimization_running() {
+        return Err("An optimization is already running".to_string());
+    }
+    if CLEANING.swap(true, Ordering::SeqCst) {
+        return Err("A cleanup is already running".to_string());
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let progress_app = app.clone();
+        let report = storage::clean(
+            &options,
+            Some(move |event| emit_progress(&progress_app, event)),
+        );
+        CLEANING.store(false, Ordering::SeqCst);
+
+        let _ = app.emit(EV_STORAGE_CLEANED, &report);
+        let _ = app.emit(EV_DONE, ());
+        if show_notification {
+            storage::notify_cleaned(&app, &report, &theme);
+        }
+    });
+    Ok(())
+}
@@ -1,4 +1,11 @@
 use crate::config::Priority;
+use crate::logging::startup_timing::{self, StartupRecord};
+use crate::system::diagnostics::{self, DiagnosticsReport};
+use crate::system::elevation::{self, ElevationStatus};
+use crate::system::recommendation::{self, RecommendedSettings};
+use crate::system::setup_probe::{self, SetupProbeResult, SetupProbeStep};
+use crate::system::supervisor::TaskHealth;
+use crate::system::tweaks::{self, SystemTweak, TweakStatus};
 use tauri::{AppHandle, State};
 
 /// System configuration commands for the Tommy Memory Cleaner application.
@@ -6,18 +13,33 @@ use tauri::{AppHandle, State};
 /// This module provides Tauri commands to manage system-level settings
 /// including process priority, startup behavior, and window properties.
 
-/// Restarts the application with elevated privileges.
+/// Relaunches the application as administrator through the UAC prompt.
+///
+/// The current instance exits once the elevated one has started.
+///
+/// # Returns
+///
+/// Returns an error string if TMC already runs elevated, an optimization is
+/// running, or the prompt was declined; this instance then keeps running.
 #[tauri::command]
-pub fn cmd_restart_with_elevation() -> Result<(), String> {
-    #[cfg(windows)]
-    {
-        crate::restart_with_elevation().map_err(|e| e.to_string())
+pub fn cmd_request_elevation() -> Result<(), String> {
+    if crate::system::is_app_elevated() {
+        return Err("Already running as administrator".to_string());
     }
-    
-    #[cfg(not(windows))]
-    {
-        Err("Elevation is only supported on Windows".to_string())
+    if crate::is_optimization_running() {
+        return Err("An optimization is already running".to_string());
     }
+    elevation::relaunch_as_admin().map_err(|e| e.to_string())
+}
+
+/// Reports whether TMC runs elevated and which areas are unavailable if not.
+///
+/// # Returns
+///
+/// Returns the `ElevationStatus` of this process.
+#[tauri::command]
+pub fn cmd_get_elevation_status() -> Result<ElevationStatus, String> {
+    Ok(elevation::status())
 }
 
 /// Manages the elevated task for silent admin startup.
@@ -47,6 +69,67 @@ pub fn cmd_manage_elevated_task(create: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// Runs the environment check of one setup wizard step.
+///
+/// # Arguments
+///
+/// * `app` - The application handle, used by the hotkey probe
+/// * `state` - The application state containing the configuration
+/// * `step` - The step to check (privileges, areas, notifications, hotkey, startup)
+///
+/// # Returns
+///
+/// Returns the outcome of the probe with a remediation key when it did not
+/// pass, or an error string if the configuration cannot be read.
+#[tauri::command]
+pub async fn cmd_setup_probe_step(
+    app: AppHandle,
+    state: State<'_, crate::AppState>,
+    step: SetupProbeStep,
+) -> Result<SetupProbeResult, String> {
+    let cfg = state.cfg.snapshot();
+    tauri::async_runtime::spawn_blocking(move || setup_probe::run_probe(&app, &cfg, step))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Recommends a profile and automatic optimization thresholds for this PC.
+///
+/// Detects the installed memory, a battery and a dedicated graphics card.
+///
+/// # Returns
+///
+/// Returns the detected hardware, the recommended settings and the i18n keys
+/// of the reasons the setup wizard shows, or an error string if the
+/// detection could not be run.
+#[tauri::command]
+pub async fn cmd_get_recommended_settings() -> Result<RecommendedSettings, String> {
+    tauri::async_runtime::spawn_blocking(recommendation::recommend_for_this_pc)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Runs the self-test and returns the report.
+///
+/// Checks elevation, each privilege, each memory area on the running
+/// Windows build, a no-op NtSetSystemInformation call, the notification
+/// pipeline and the tray icon.
+///
+/// # Arguments
+///
+/// * `app` - The application handle, used by the tray check
+///
+/// # Returns
+///
+/// Returns the report with one entry per check, or an error string if the
+/// checks could not be run.
+#[tauri::command]
+pub async fn cmd_run_diagnostics(app: AppHandle) -> Result<DiagnosticsReport, String> {
+    tauri::async_runtime::spawn_blocking(move || diagnostics::run_diagnostics(&app))
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Sets the application process priority.
 ///
 /// Updates both the current process priority and persists the setting
@@ -58,12 +141,10 @@ pub fn cmd_set_priority(
 ) -> Result<(), String> {
     crate::system::priority::set_priority(priority.clone()).map_err(|e| e.to_string())?;
 
-    let mut cfg = state
+    state
         .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())?;
-    cfg.run_priority = priority;
-    cfg.save().map_err(|e| e.to_string())
+        .update_and_save(|c| c.run_priority = priority)
+        .map_err(|e| e.to_string())
 }
 
 /// Configures the application to run automatically on system startup.
@@ -73,26 +154,29 @@ pub fn cmd_set_priority(
 /// configuration for consistency.
 #[tauri::command]
 pub fn cmd_run_on_startup(enable: bool, state: State<'_, crate::AppState>) -> Result<(), String> {
-    crate::system::startup::set_run_on_startup(enable).map_err(|e| {
+    let (method, delay_secs) = {
+        let cfg = state.cfg.read();
+        (cfg.startup_method, cfg.startup_delay_secs)
+    };
+
+    crate::system::startup::set_run_on_startup(enable, method, delay_secs).map_err(|e| {
         format!(
             "Failed to set startup: {}. Try running as administrator.",
             e
         )
     })?;
 
-    let is_enabled = crate::system::startup::is_startup_enabled();
+    let is_enabled = crate::system::startup::is_startup_enabled(method);
     if enable && !is_enabled {
         return Err(
             "Failed to enable startup. Please add the app manually to Windows startup.".to_string(),
         );
     }
 
-    let mut cfg = state
+    state
         .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())?;
-    cfg.run_on_startup = is_enabled;
-    cfg.save().map_err(|e| e.to_string())
+        .update_and_save(|c| c.run_on_startup = is_enabled)
+        .map_err(|e| e.to_string())
 }
 
 /// Controls the window's "always on top" behavior.
@@ -107,10 +191,70 @@ pub fn cmd_set_always_on_top(
 ) -> Result<(), String> {
     crate::system::window::set_always_on_top(&app, on)?;
 
-    let mut cfg = state
+    state
         .cfg
-        .lock()
-        .map_err(|_| "Config lock poisoned".to_string())?;
-    cfg.always_on_top = on;
-    cfg.save().map_err(|e| e.to_string())
+        .update_and_save(|c| c.always_on_top = on)
+        .map_err(|e| e.to_string())
+}
+
+/// Returns the current power source and battery state.
+///
+/// The auto optimizer uses the same information to skip runs or switch to
+/// the battery profile.
+#[tauri::command]
+pub fn cmd_power_status() -> Result<crate::system::power::PowerStatus, String> {
+    Ok(crate::system::power::power_status())
+}
+
+/// Returns the startup phase timings of the last launches, newest first.
+///
+/// Each record carries the app version, so a slower startup can be matched
+/// to the release that introduced it.
+#[tauri::command]
+pub fn cmd_get_startup_timings() -> Result<Vec<StartupRecord>, String> {
+    Ok(startup_timing::startup_timings())
+}
+
+/// Returns the health of the supervised background tasks.
+///
+/// # Returns
+///
+/// Returns one entry per task started so far (tray updater, auto optimizer)
+/// with its state, restart count and last failure.
+#[tauri::command]
+pub fn cmd_get_background_task_status() -> Result<Vec<TaskHealth>, String> {
+    Ok(crate::system::supervisor::task_status())
+}
+
+/// Returns the advanced system tweaks and their current state.
+///
+/// The state is read from the registry, so changes made outside TMC (regedit,
+/// group policy) are reported as they are.
+///
+/// # Returns
+///
+/// Returns one `TweakStatus` per tweak with its state, its warning and
+/// whether TMC has the rights to change it.
+#[tauri::command]
+pub fn cmd_get_system_tweaks() -> Result<Vec<TweakStatus>, String> {
+    Ok(SystemTweak::ALL.into_iter().map(tweaks::status).collect())
+}
+
+/// Enables or disables an advanced system tweak.
+///
+/// The change is machine-wide and takes effect after the next restart. The
+/// UI shows the tweak warning and asks for confirmation before calling this.
+///
+/// # Arguments
+///
+/// * `tweak` - The tweak to change, e.g. `clear_page_file_at_shutdown`
+/// * `enable` - Whether the tweak should be turned on
+///
+/// # Returns
+///
+/// Returns the state read back from the registry, or an error string if TMC
+/// is not running as administrator or the value could not be changed.
+#[tauri::command]
+pub fn cmd_set_system_tweak(tweak: SystemTweak, enable: bool) -> Result<TweakStatus, String> {
+    tweaks::set(tweak, enable).map_err(|e| e.to_string())
 }
@@ -7,55 +7,17 @@
 pub fn cmd_get_system_theme() -> Result<String, String> {
     #[cfg(windows)]
     {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
-        use std::ptr::null_mut;
-        use windows_sys::Win32::System::Registry::*;
-
-        let key_path: Vec<u16> =
-            OsStr::new(r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize")
-                .encode_wide()
-                .chain(std::iter::once(0))
-                .collect();
-
-        let mut hkey: HKEY = std::ptr::null_mut();
-        let value_name: Vec<u16> = OsStr::new("AppsUseLightTheme")
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
-
-        let result =
-            unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, key_path.as_ptr(), 0, KEY_READ, &mut hkey) };
-
-        // HKEY in windows-sys is isize, so compare with 0
-        if result == 0 && hkey != std::ptr::null_mut() {
-            let mut value_data: u32 = 0;
-            let mut value_type: u32 = 0;
-            let mut data_size: u32 = std::mem::size_of::<u32>() as u32;
-
-            let read_result = unsafe {
-                RegQueryValueExW(
-                    hkey,
-                    value_name.as_ptr(),
-                    null_mut(),
-                    &mut value_type,
-                    &mut value_data as *mut _ as *mut u8,
-                    &mut data_size,
-                )
-            };
-
-            unsafe {
-                RegCloseKey(hkey);
-            }
-
-            if read_result == 0 && value_type == REG_DWORD {
-                // Registry value: 0 = dark theme, 1 = light theme
-                return Ok(if value_data == 0 {
-                    "dark".to_string()
-                } else {
-                    "light".to_string()
-                });
-            }
+        // Registry value: 0 = dark theme, 1 = light theme
+        let light = crate::utils::read_user_dword(
+            r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "AppsUseLightTheme",
+        );
+        if let Some(light) = light {
+            return Ok(if light == 0 {
+                "dark".to_string()
+            } else {
+                "light".to_string()
+            });
         }
     }
 
@@ -72,22 +34,14 @@ pub fn cmd_get_system_theme() -> Result<String, String> {
 pub fn cmd_get_system_language() -> Result<String, String> {
     #[cfg(windows)]
     {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
         use std::ptr::null_mut;
         use windows_sys::Win32::System::Registry::*;
 
         // Read the language from Windows registry
-        let key_path: Vec<u16> = OsStr::new(r"Control Panel\International")
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
+        let key_path = crate::utils::to_wide(r"Control Panel\International");
 
         let mut hkey: HKEY = std::ptr::null_mut();
-        let value_name: Vec<u16> = OsStr::new("LocaleName")
-            .encode_wide()
-            .chain(std::iter::once(0))
-            .collect();
+        let value_name = crate::utils::to_wide("LocaleName");
 
         let result =
             unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, key_path.as_ptr(), 0, KEY_READ, &mut hkey) };
@@ -143,3 +97,20 @@ pub fn cmd_get_system_language() -> Result<String, String> {
     // Default to English if detection fails
     Ok("en".to_string())
 }
+
+/// Retrieves the Windows accessibility preferences.
+///
+/// Reads the high contrast mode and the "Show animations in Windows" setting
+/// and applies the `accessibility` overrides from the configuration.
+/// Changes are also emitted as `accessibility-changed`.
+///
+/// # Returns
+///
+/// Returns the `AccessibilityPrefs` in effect along with the detected values.
+#[tauri::command]
+pub fn cmd_get_accessibility_prefs(
+    state: tauri::State<'_, crate::AppState>,
+) -> Result<crate::ui::accessibility::AccessibilityPrefs, String> {
+    let overrides = state.cfg.read().accessibility;
+    Ok(crate::ui::accessibility::accessibility_prefs(&overrides))
+}
@@ -4,6 +4,9 @@
 /// displaying notifications, and positioning UI elements.
 use tauri::{AppHandle, Manager, State};
 
+/// Logical size of the tray menu window (scaled per monitor DPI)
+pub const TRAY_MENU_SIZE: (f64, f64) = (160.0, 200.0);
+
 /// Returns the window configuration values including border radius.
 ///
 /// This command exposes the window styling values to the frontend
@@ -25,15 +28,9 @@ pub fn cmd_get_window_config() -> Result<serde_json::Value, String> {
 pub fn cmd_get_platform() -> Result<String, String> {
     #[cfg(target_os = "windows")]
     {
-        if crate::os::is_windows_11() {
-            Ok("windows-11".to_string())
-        } else if crate::os::is_windows_10() {
-            Ok("windows-10".to_string())
-        } else {
-            Ok("windows".to_string())
-        }
+        Ok(crate::os::win_version().platform_name().to_string())
     }
-    
+
     #[cfg(not(target_os = "windows"))]
     Ok("other".to_string())
 }
@@ -48,6 +45,39 @@ pub fn cmd_update_tray_theme(app: AppHandle, theme: String) -> Result<(), String
     Ok(())
 }
 
+/// Returns a menu icon recolored for the current theme.
+///
+/// Icons are rendered from monochrome glyphs and cached per color, so the
+/// tray menu and widget follow the accent color without shipping variants.
+///
+/// # Arguments
+///
+/// * `name` - Icon name (`open`, `optimize`, `profile`, `exit`)
+/// * `color` - Hex color; defaults to the accent color of the active theme
+///
+/// # Returns
+///
+/// A base64 PNG data URL usable as an image source
+#[tauri::command]
+pub fn cmd_get_menu_icon(
+    state: State<'_, crate::AppState>,
+    name: String,
+    color: Option<String>,
+) -> Result<String, String> {
+    let color = match color {
+        Some(color) => color,
+        None => {
+            let cfg = state.cfg.read();
+            if cfg.theme == "light" {
+                cfg.main_color_hex_light.clone()
+            } else {
+                cfg.main_color_hex_dark.clone()
+            }
+        }
+    };
+    crate::ui::menu_icons::menu_icon_data_url(&name, &color)
+}
+
 /// Apply rounded corners to the current window
 #[tauri::command]
 pub fn cmd_apply_rounded_corners(app: AppHandle) -> Result<(), String> {
@@ -102,19 +132,69 @@ pub fn cmd_show_notification(
     state: State<'_, crate::AppState>,
 ) -> Result<(), String> {
     // Get the current theme from configuration
-    let theme = {
-        match state.cfg.try_lock() {
-            Ok(cfg_guard) => cfg_guard.theme.clone(),
-            Err(_) => {
-                tracing::debug!("Config lock busy in cmd_show_notification, using default theme");
-                "dark".to_string()
-            }
-        }
-    };
+    let theme = state.cfg.with(|c| c.theme.clone());
     // Use the notifications module function
     crate::notifications::show_windows_notification(&app, &title, &message, &theme)
 }
 
+/// Creates the main window from its declaration in tauri.conf.json.
+///
+/// The window is declared with `create: false`, so starting minimized to the
+/// tray never loads its webview. Every place that needs the window builds it
+/// here, with the sizes and limits of the declaration.
+pub fn build_main_window(app: &AppHandle, visible: bool) -> tauri::Result<tauri::WebviewWindow> {
+    let declared = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == "main")
+        .cloned();
+    let builder = match declared {
+        Some(window_config) => tauri::WebviewWindowBuilder::from_config(app, &window_config)?,
+        None => tauri::WebviewWindowBuilder::new(
+            app,
+            "main",
+            tauri::WebviewUrl::App("index.html".into()),
+        )
+        .title("Tommy Memory Cleaner")
+        .inner_size(500.0, 700.0)
+        .resizable(false)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false), // Disabilita shadow per Windows 10
+    };
+    let window = builder.skip_taskbar(false).visible(visible).build()?;
+    crate::ui::main_window::on_main_window_created();
+    Ok(window)
+}
+
+/// Hides the main window to the tray.
+///
+/// With `destroy_webview_on_hide` the window and its WebView are destroyed
+/// instead, and created again the next time the window is opened.
+///
+/// # Arguments
+///
+/// * `app` - The application handle
+/// * `ui_state` - Frontend state (e.g. the active tab) handed to the next
+///   main window
+#[tauri::command]
+pub fn cmd_hide_main_window(app: AppHandle, ui_state: Option<serde_json::Value>) {
+    crate::ui::main_window::hide_main_window(&app, ui_state);
+}
+
+/// Returns the UI state saved when the previous main window was hidden.
+///
+/// # Returns
+///
+/// Returns the state passed to `cmd_hide_main_window`, or `None` if there is
+/// none or it was already taken.
+#[tauri::command]
+pub fn cmd_take_main_window_state() -> Option<serde_json::Value> {
+    crate::ui::main_window::take_ui_state()
+}
+
 /// Helper function to show or create the main application window.
 ///
 /// This function is accessible from main.rs and handles both
@@ -154,20 +234,8 @@ pub fn show_or_create_window(app: &AppHandle) {
     } else {
         tracing::info!("Creating new main window...");
         tracing::info!("Window dimensions will be: 500x700");
-        let result = tauri::WebviewWindowBuilder::new(
-            app,
-            "main",
-            tauri::WebviewUrl::App("index.html".into())
-        )
-        .title("Tommy Memory Cleaner")
-        .inner_size(500.0, 700.0)
-        .resizable(false)
-        .decorations(false)
-        .transparent(true)
-        .shadow(false)  // Disabilita shadow per Windows 10
-        .skip_taskbar(false)  // Show in taskbar
-        .visible(true)  // Show window immediately for SetWindowRgn
-        .build();
+        // Show window immediately for SetWindowRgn
+        let result = build_main_window(app, true);
 
         match result {
             Ok(window) => {
@@ -189,6 +257,13 @@ pub fn show_or_create_window(app: &AppHandle) {
                 }
                 let _ = window.set_skip_taskbar(false);
                 let _ = window.set_focus();
+
+                // Always on top could not be applied at startup when the app started minimized
+                if let Some(state) = app.try_state::<crate::AppState>() {
+                    if state.cfg.with(|c| c.always_on_top) {
+                        let _ = window.set_always_on_top(true);
+                    }
+                }
             }
             Err(e) => {
                 tracing::error!("Failed to create window: {:?}", e);
@@ -198,230 +273,190 @@ pub fn show_or_create_window(app: &AppHandle) {
     }
 }
 
-/// Positions the tray menu relative to the system tray icon.
+/// Positions the tray menu next to the system tray icon.
 ///
-/// This function calculates the optimal position for the tray menu
-/// based on the cursor position and taskbar location.
+/// The monitor containing the icon supplies the DPI, bounds and work area;
+/// the placement itself is computed by `ui::menu_position::place_menu`.
 ///
 /// # Arguments
 ///
 /// * `window` - The tray menu window to position
-pub fn position_tray_menu(window: &tauri::WebviewWindow) {
-    // Get the menu dimensions
-    let menu_size = match window.outer_size() {
-        Ok(size) => size,
-        Err(e) => {
-            tracing::error!("Failed to get menu size: {:?}", e);
+/// * `anchor` - Click position on the tray icon in physical pixels, `None` to use the cursor
+pub fn position_tray_menu(window: &tauri::WebviewWindow, anchor: Option<(i32, i32)>) {
+    let anchor = match anchor.or_else(|| {
+        window
+            .cursor_position()
+            .ok()
+            .map(|p| (p.x as i32, p.y as i32))
+    }) {
+        Some(anchor) => anchor,
+        None => {
+            tracing::error!("Failed to get the tray icon or cursor position");
             return;
         }
     };
 
-    let menu_width = menu_size.width as i32;
-    let menu_height = menu_size.height as i32;
-
-    // FIX: Get cursor position FIRST (near tray icon)
-    let cursor_pos = match window.cursor_position() {
-        Ok(pos) => pos,
-        Err(_) => {
-            tracing::error!("Failed to get cursor position");
-            // Fallback: use primary monitor
-            if let Ok(Some(monitor)) = window.primary_monitor() {
-                let monitor_size = monitor.size();
-                let monitor_pos = monitor.position();
-                let fallback_pos = tauri::PhysicalPosition {
-                    x: (monitor_pos.x + monitor_size.width as i32 - 50) as f64,
-                    y: (monitor_pos.y + monitor_size.height as i32 - 50) as f64,
-                };
-                tracing::warn!("Using fallback cursor position: {:?}", fallback_pos);
-                fallback_pos
-            } else {
-                tracing::error!("Failed to get primary monitor for fallback");
-                return;
-            }
+    let monitor = match monitor_at(window, anchor) {
+        Some(m) => m,
+        None => {
+            tracing::error!("No monitor available for the tray menu");
+            return;
         }
     };
 
-    // FIX: Find monitor containing cursor (not the window's monitor)
-    let cursor_x = cursor_pos.x as i32;
-    let cursor_y = cursor_pos.y as i32;
-
-    let monitor = match window.available_monitors() {
-        Ok(monitors) => {
-            // Find monitor containing the cursor
-            let mut found_monitor = None;
-            for m in monitors {
-                let m_pos = m.position();
-                let m_size = m.size();
-
-                let m_left = m_pos.x;
-                let m_top = m_pos.y;
-                let m_right = m_pos.x + m_size.width as i32;
-                let m_bottom = m_pos.y + m_size.height as i32;
-
-                // Check if cursor is inside this monitor
-                if cursor_x >= m_left
-                    && cursor_x < m_right
-                    && cursor_y >= m_top
-                    && cursor_y < m_bottom
-                {
-                    // Log before moving m
-                    tracing::debug!(
-                        "Found monitor containing cursor: {}x{} at {:?}",
-                        m_size.width,
-                        m_size.height,
-                        m_pos
-                    );
-                    found_monitor = Some(m);
-                    break;
-                }
-            }
-
-            // If not found, use primary monitor as fallback
-            if let Some(m) = found_monitor {
-                m
-            } else {
-                tracing::warn!("Cursor not found in any monitor, using primary monitor");
-                if let Some(m) = window.primary_monitor().ok().flatten() {
-                    m
-                } else {
-                    tracing::error!("No primary monitor available");
-                    // Return current monitor as last resort
-                    if let Some(m) = window.current_monitor().ok().flatten() {
-                        m
-                    } else {
-                        tracing::error!("No current monitor available");
-                        // Return a default monitor position
-                        return;
-                    }
-                }
-            }
-        }
-        Err(e) => {
-            tracing::error!("Failed to get available monitors: {:?}", e);
-            // Fallback: use current_monitor or primary_monitor directly
-            if let Some(m) = window.current_monitor().ok().flatten() {
-                m
-            } else if let Some(m) = window.primary_monitor().ok().flatten() {
-                m
-            } else {
-                tracing::error!("No monitor available");
-                return;
-            }
-        }
-    };
+    // Size the menu for the DPI of the monitor hosting the tray
+    let menu_width = crate::ui::dpi::scale(TRAY_MENU_SIZE.0, monitor.dpi) as i32;
+    let menu_height = crate::ui::dpi::scale(TRAY_MENU_SIZE.1, monitor.dpi) as i32;
+    if let Err(e) = window.set_size(tauri::PhysicalSize {
+        width: menu_width as u32,
+        height: menu_height as u32,
+    }) {
+        tracing::warn!("Failed to scale tray menu: {:?}", e);
+    }
 
-    let monitor_size = monitor.size();
-    let monitor_pos = monitor.position();
+    let taskbar = get_taskbar_info();
+    let (x, y) = crate::ui::menu_position::place_menu(
+        &monitor,
+        taskbar.as_ref(),
+        anchor,
+        (menu_width, menu_height),
+    );
 
     tracing::debug!(
-        "Cursor position: {:?}, Using monitor: {}x{} at {:?}",
-        cursor_pos,
-        monitor_size.width,
-        monitor_size.height,
-        monitor_pos
+        "Tray menu: anchor {:?}, monitor {:?}, taskbar {:?}, menu {}x{} at ({}, {})",
+        anchor,
+        monitor,
+        taskbar,
+        menu_width,
+        menu_height,
+        x,
+        y
     );
 
-    // Determine taskbar position
-    let (final_x, final_y) = if let Some((
-        taskbar_left,
-        taskbar_top,
-        taskbar_right,
-        taskbar_bottom,
-    )) = get_taskbar_rect()
-    {
-        let taskbar_height = taskbar_bottom - taskbar_top;
-        let taskbar_width = taskbar_right - taskbar_left;
-        let is_taskbar_vertical = taskbar_width < taskbar_height;
-
-        tracing::debug!(
-            "Taskbar rect: ({}, {}, {}, {}), vertical: {}",
-            taskbar_left,
-            taskbar_top,
-            taskbar_right,
-            taskbar_bottom,
-            is_taskbar_vertical
-        );
-
-        let cursor_x = cursor_pos.x as i32;
-        let cursor_y = cursor_pos.y as i32;
-
-        if is_taskbar_vertical {
-            // Vertical taskbar (left or right)
-            if taskbar_left < monitor_pos.x + 100 {
-                // Taskbar on LEFT - menu to the right of tray
-                let x = taskbar_right + 5;
-                let y = (cursor_y - menu_height / 2).max(monitor_pos.y + 5);
-                (x, y)
-            } else {
-                // Taskbar on RIGHT - menu to the left of tray
-                let x = (taskbar_left - menu_width - 5).max(monitor_pos.x + 5);
-                let y = (cursor_y - menu_height / 2).max(monitor_pos.y + 5);
-                (x, y)
-            }
-        } else {
-            // Horizontal taskbar (top or bottom)
-            // Center menu horizontally relative to cursor
-            let x = (cursor_x - menu_width / 2)
-                .max(monitor_pos.x + 5)  // Not too far left
-                .min(monitor_pos.x + monitor_size.width as i32 - menu_width - 5); // Not too far right
-
-            if taskbar_top < monitor_pos.y + 100 {
-                // Taskbar on TOP - menu BELOW taskbar
-                let y = taskbar_bottom + 5;
-                (x, y)
-            } else {
-                // Taskbar on BOTTOM - menu ABOVE taskbar
-                let y = taskbar_top - menu_height - 5;
-                (x, y)
-            }
-        }
-    } else {
-        // Fallback: no taskbar info, use safe position
-        tracing::warn!("Could not get taskbar rect, using fallback positioning");
-        let x = (cursor_pos.x as i32 - menu_width / 2)
-            .max(monitor_pos.x + 5)
-            .min(monitor_pos.x + monitor_size.width as i32 - menu_width - 5);
-        let y =
-            (monitor_pos.y + monitor_size.height as i32 - menu_height - 80).max(monitor_pos.y + 5);
-        (x, y)
+    if let Err(e) = window.set_position(tauri::PhysicalPosition { x, y }) {
+        tracing::error!("Failed to set menu position: {:?}", e);
+    }
+}
+
+/// Monitor containing (or nearest to) a screen point, with its work area and DPI
+#[cfg(windows)]
+fn monitor_at(
+    _window: &tauri::WebviewWindow,
+    (x, y): (i32, i32),
+) -> Option<crate::ui::menu_position::Monitor> {
+    use crate::ui::menu_position::{Monitor, Rect};
+    use std::mem::zeroed;
+    use windows_sys::Win32::Foundation::{POINT, RECT};
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetMonitorInfoW, MonitorFromPoint, MONITORINFO, MONITOR_DEFAULTTONEAREST,
     };
+    use windows_sys::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
 
-    tracing::info!("Positioning tray menu at: ({}, {})", final_x, final_y);
+    fn rect(rc: RECT) -> Rect {
+        Rect::new(rc.left, rc.top, rc.right, rc.bottom)
+    }
 
-    // Apply the position
-    if let Err(e) = window.set_position(tauri::PhysicalPosition {
-        x: final_x,
-        y: final_y,
-    }) {
-        tracing::error!("Failed to set menu position: {:?}", e);
+    unsafe {
+        let hmonitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST);
+        if hmonitor.is_null() {
+            return None;
+        }
+        let mut info: MONITORINFO = zeroed();
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if GetMonitorInfoW(hmonitor, &mut info) == 0 {
+            return None;
+        }
+        let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+        let dpi = if GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) == 0
+            && dpi_x > 0
+        {
+            dpi_x
+        } else {
+            crate::ui::dpi::BASE_DPI
+        };
+        Some(Monitor {
+            bounds: rect(info.rcMonitor),
+            work_area: rect(info.rcWork),
+            dpi,
+        })
     }
 }
 
-/// Retrieves the Windows taskbar rectangle coordinates.
+/// Monitor containing a screen point, from the monitors Tauri reports
+#[cfg(not(windows))]
+fn monitor_at(
+    window: &tauri::WebviewWindow,
+    (x, y): (i32, i32),
+) -> Option<crate::ui::menu_position::Monitor> {
+    use crate::ui::menu_position::{Monitor, Rect};
+
+    let monitors = window.available_monitors().ok()?;
+    let bounds_of = |m: &tauri::Monitor| {
+        let (pos, size) = (m.position(), m.size());
+        Rect::new(
+            pos.x,
+            pos.y,
+            pos.x + size.width as i32,
+            pos.y + size.height as i32,
+        )
+    };
+    let monitor = monitors
+        .iter()
+        .find(|m| bounds_of(m).contains(x, y))
+        .or_else(|| monitors.first())?;
+    let bounds = bounds_of(monitor);
+    Some(Monitor {
+        bounds,
+        work_area: bounds,
+        dpi: (monitor.scale_factor() * crate::ui::dpi::BASE_DPI as f64).round() as u32,
+    })
+}
+
+/// Retrieves the taskbar rectangle, docking edge and auto-hide state.
 ///
-/// Returns (left, top, right, bottom) of the taskbar area.
 /// Only available on Windows.
 #[cfg(windows)]
-pub fn get_taskbar_rect() -> Option<(i32, i32, i32, i32)> {
+pub fn get_taskbar_info() -> Option<crate::ui::menu_position::Taskbar> {
+    use crate::ui::menu_position::{Rect, Taskbar, TaskbarEdge};
     use std::mem::zeroed;
-    use windows_sys::Win32::UI::Shell::{SHAppBarMessage, ABM_GETTASKBARPOS, APPBARDATA};
+    use windows_sys::Win32::UI::Shell::{
+        SHAppBarMessage, ABE_LEFT, ABE_RIGHT, ABE_TOP, ABM_GETSTATE, ABM_GETTASKBARPOS,
+        ABS_AUTOHIDE, APPBARDATA,
+    };
 
     unsafe {
         let mut app_bar_data: APPBARDATA = zeroed();
         app_bar_data.cbSize = std::mem::size_of::<APPBARDATA>() as u32;
 
-        let result = SHAppBarMessage(ABM_GETTASKBARPOS, &mut app_bar_data);
-        if result != 0 {
-            let rc = app_bar_data.rc;
-            Some((rc.left, rc.top, rc.right, rc.bottom))
-        } else {
-            None
+        if SHAppBarMessage(ABM_GETTASKBARPOS, &mut app_bar_data) == 0 {
+            return None;
         }
+        let rc = app_bar_data.rc;
+        let edge = match app_bar_data.uEdge {
+            ABE_LEFT => TaskbarEdge::Left,
+            ABE_TOP => TaskbarEdge::Top,
+            ABE_RIGHT => TaskbarEdge::Right,
+            _ => TaskbarEdge::Bottom,
+        };
+        let state = SHAppBarMessage(ABM_GETSTATE, &mut app_bar_data);
+
+        Some(Taskbar {
+            rect: Rect::new(rc.left, rc.top, rc.right, rc.bottom),
+            edge,
+            auto_hide: (state as u32 & ABS_AUTOHIDE) != 0,
+        })
     }
 }
 
 /// Stub implementation for non-Windows platforms.
 #[cfg(not(windows))]
-fn get_taskbar_rect() -> Option<(i32, i32, i32, i32)> {
+pub fn get_taskbar_info() -> Option<crate::ui::menu_position::Taskbar> {
     None
 }
+
+/// Retrieves the Windows taskbar rectangle coordinates.
+///
+/// Returns (left, top, right, bottom) of the taskbar area.
+pub fn get_taskbar_rect() -> Option<(i32, i32, i32, i32)> {
+    get_taskbar_info().map(|t| (t.rect.left, t.rect.top, t.rect.right, t.rect.bottom))
+}
@@ -0,0 +1,46 @@
+/// Application update commands.
+///
+/// Updates come from the GitHub releases of the project; the download is
+/// verified before anything is started.
+use crate::updater::{self, UpdateInfo};
+use tauri::AppHandle;
+
+/// Checks GitHub for a newer release.
+///
+/// # Returns
+///
+/// Returns the `UpdateInfo` for the latest release (`update_available` tells
+/// whether it is newer), or an error string if the check fails.
+#[tauri::command]
+pub async fn cmd_check_update() -> Result<UpdateInfo, String> {
+    tauri::async_runtime::spawn_blocking(updater::check_for_update)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("{:#}", e))
+}
+
+/// Downloads, verifies and starts the update found by the last check.
+///
+/// The installer (or, in portable mode, the portable zip) is checked against
+/// the release's signed SHA256SUMS, and its executable against the pinned
+/// signing certificate, before it runs. The application exits once the update
+/// has been started.
+///
+/// # Arguments
+///
+/// * `app` - The application handle, used to exit
+///
+/// # Returns
+///
+/// Returns an error string if there is no update, or if the download or its
+/// verification fails.
+#[tauri::command]
+pub async fn cmd_apply_update(app: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let update = updater::download_update()?;
+        updater::apply_update(&app, &update)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("{:#}", e))
+}
@@ -0,0 +1,87 @@
+/// Per-area cooldowns
+///
+/// Frequent low-memory triggers should not drop the file cache again and
+/// again. A cooldown makes automatic runs skip an area that already ran less
+/// than `minutes` ago; the last run of each area comes from the optimization
+/// history. Manual runs are never held back.
+use crate::memory::types::Areas;
+use serde::{Deserialize, Serialize};
+
+pub const MAX_AREA_COOLDOWNS: usize = 16;
+/// One week
+pub const MAX_COOLDOWN_MINUTES: u32 = 7 * 24 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AreaCooldown {
+    /// Areas the cooldown applies to, each on its own clock
+    pub area: Areas,
+    /// Minimum minutes between two runs of the area
+    pub minutes: u32,
+}
+
+impl AreaCooldown {
+    pub fn validate(&mut self) {
+        self.area &= Areas::all();
+        self.minutes = self.minutes.clamp(1, MAX_COOLDOWN_MINUTES);
+    }
+}
+
+/// Clamp the entries, drop the ones without a known area and cap the list
+pub fn validate_area_cooldowns(cooldowns: &mut Vec<AreaCooldown>) {
+    cooldowns.iter_mut().for_each(AreaCooldown::validate);
+    cooldowns.retain(|c| !c.area.is_empty());
+    if cooldowns.len() > MAX_AREA_COOLDOWNS {
+        tracing::warn!(
+            "Too many area cooldowns ({}), keeping the first {}",
+            cooldowns.len(),
+            MAX_AREA_COOLDOWNS
+        );
+        cooldowns.truncate(MAX_AREA_COOLDOWNS);
+    }
+}
+
+/// Cooldown in minutes for a single area, the longest one when entries overlap
+pub fn cooldown_minutes(cooldowns: &[AreaCooldown], area: Areas) -> Option<u32> {
+    cooldowns
+        .iter()
+        .filter(|c| !area.is_empty() && c.area.contains(area))
+        .map(|c| c.minutes)
+        .max()
+}
+
+/// Seconds until an area that last ran at `last_run` may run again, `None`
+/// when the cooldown is over
+pub fn remaining_secs(minutes: u32, last_run: Option<u64>, now: u64) -> Option<u64> {
+    let ready_at = last_run? + minutes as u64 * 60;
+    (now < ready_at).then(|| ready_at - now)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_lookup_and_remaining() {
+        let cooldowns = [
+            AreaCooldown {
+                area: Areas::SYSTEM_FILE_CACHE | Areas::STANDBY_LIST,
+                minutes: 60,
+            },
+            AreaCooldown {
+                area: Areas::SYSTEM_FILE_CACHE,
+                minutes: 360,
+            },
+        ];
+        assert_eq!(
+            cooldown_minutes(&cooldowns, Areas::SYSTEM_FILE_CACHE),
+            Some(360)
+        );
+        assert_eq!(cooldown_minutes(&cooldowns, Areas::STANDBY_LIST), Some(60));
+        assert_eq!(cooldown_minutes(&cooldowns, Areas::WORKING_SET), None);
+
+        assert_eq!(remaining_secs(60, None, 10_000), None);
+        assert_eq!(remaining_secs(60, Some(10_000), 10_600), Some(3000));
+        assert_eq!(remaining_secs(60, Some(10_000), 13_600), None);
+    }
+}
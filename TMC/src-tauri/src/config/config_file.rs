@@ -0,0 +1,180 @@
+/// Settings backup files (.tmcconfig)
+///
+/// A settings file holds the whole configuration (tray colors, custom
+/// profiles, exclusions, hotkeys, schedules, ...) so it can be moved to another
+/// machine or kept as a backup before trying experimental options. Settings
+/// that belong to the machine, like the startup task, are never imported.
+use super::Config;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CONFIG_FILE_EXTENSION: &str = "tmcconfig";
+const CONFIG_FORMAT_VERSION: u32 = 1;
+const MAX_CONFIG_FILE_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigFile {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    #[serde(default)]
+    pub app_version: String,
+    /// Unix timestamp (seconds) of the export
+    #[serde(default)]
+    pub exported_at: u64,
+    /// Kept as JSON so a file from another version is checked before parsing
+    pub config: serde_json::Value,
+}
+
+fn default_format_version() -> u32 {
+    CONFIG_FORMAT_VERSION
+}
+
+/// Outcome of a settings import, returned to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigImportReport {
+    /// Version of TMC that wrote the file
+    pub app_version: String,
+    pub exported_at: u64,
+    pub dropped_areas: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ConfigFile {
+    /// Build a settings file from the current configuration
+    pub fn from_config(cfg: &Config) -> Result<Self> {
+        let mut config = serde_json::to_value(cfg).context("Failed to serialize config")?;
+        // The HTTP API token is a secret of this machine
+        config["http_api_token"] = serde_json::Value::String(String::new());
+
+        Ok(Self {
+            format_version: CONFIG_FORMAT_VERSION,
+            app_version: super::app_info::VERSION.to_string(),
+            exported_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            config,
+        })
+    }
+
+    /// Write the file to disk, adding the .tmcconfig extension if missing
+    pub fn save_to(&self, path: &Path) -> Result<PathBuf> {
+        let path = with_config_extension(path);
+        let content =
+            serde_json::to_string_pretty(self).context("Failed to serialize settings file")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write settings to {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Read and parse a settings file (the configuration is checked by `into_config`)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Cannot access settings file {}", path.display()))?;
+        if metadata.len() > MAX_CONFIG_FILE_SIZE {
+            bail!("Settings file is too large ({} bytes)", metadata.len());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read settings file {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let file: Self = serde_json::from_str(content).context("Invalid settings file")?;
+        if file.format_version > CONFIG_FORMAT_VERSION {
+            bail!(
+                "Settings format version {} is newer than supported ({})",
+                file.format_version,
+                CONFIG_FORMAT_VERSION
+            );
+        }
+        Ok(file)
+    }
+
+    /// Turn the file into a configuration ready to replace `current`
+    ///
+    /// Older configurations are migrated, values are validated like on
+    /// startup and areas unsupported on this Windows build are dropped.
+    /// Machine-specific settings are taken from `current`.
+    pub fn into_config(self, current: &Config) -> Result<(Config, ConfigImportReport)> {
+        let mut cfg: Config = serde_json::from_value(self.config)
+            .context("Invalid configuration in settings file")?;
+        let mut warnings = Vec::new();
+
+        if cfg.config_version > super::default_config_version() {
+            warnings.push(format!(
+                "Settings were written by a newer version ({}), unknown options were ignored",
+                self.app_version
+            ));
+        }
+        cfg.migrate_if_needed();
+
+        // The startup task, the setup state and the HTTP API token belong to this machine
+        cfg.run_on_startup = current.run_on_startup;
+        cfg.request_elevation_on_startup = current.request_elevation_on_startup;
+        cfg.setup_completed = current.setup_completed;
+        cfg.http_api_token = current.http_api_token.clone();
+
+        // Capability downgrade
+        let supported = crate::os::supported_areas(cfg.memory_areas);
+        let dropped_areas: Vec<String> = (cfg.memory_areas & !supported)
+            .get_names()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        cfg.memory_areas = supported;
+
+        let before = (
+            cfg.custom_profiles.len(),
+            cfg.hotkeys.len(),
+            cfg.schedules.len(),
+        );
+        cfg.validate();
+        if cfg.custom_profiles.len() < before.0 {
+            warnings.push("Invalid custom profiles were ignored".to_string());
+        }
+        if cfg.hotkeys.len() < before.1 {
+            warnings.push("Invalid hotkeys were ignored".to_string());
+        }
+        if cfg.schedules.len() < before.2 {
+            warnings.push("Invalid schedule rules were ignored".to_string());
+        }
+
+        Ok((
+            cfg,
+            ConfigImportReport {
+                app_version: self.app_version,
+                exported_at: self.exported_at,
+                dropped_areas,
+                warnings,
+            },
+        ))
+    }
+}
+
+fn with_config_extension(path: &Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case(CONFIG_FILE_EXTENSION) => path.to_path_buf(),
+        _ => path.with_extension(CONFIG_FILE_EXTENSION),
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rejects_newer_format() {
+        let newer = r#"{"format_version": 2, "config": {}}"#;
+        assert!(ConfigFile::parse(newer).is_err());
+
+        let current = r#"{"format_version": 1, "config": {"language": "it"}}"#;
+        let file = ConfigFile::parse(current).unwrap();
+        assert_eq!(file.config["language"], "it");
+    }
+}
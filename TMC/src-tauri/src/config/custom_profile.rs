@@ -0,0 +1,105 @@
+/// User-defined optimization profiles
+///
+/// Besides the built-in Normal/Balanced/Gaming profiles the user can define
+/// named profiles with their own areas, process priority and (optionally) an
+/// exclusion list that replaces the global one while the profile is active.
+/// A profile can be bound to a global hotkey that activates it and optimizes.
+use super::Priority;
+use crate::memory::types::{Areas, TrimStrategy};
+use crate::security::{contains_injection_patterns, sanitize_hotkey, sanitize_string};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const MAX_CUSTOM_PROFILES: usize = 16;
+const MAX_NAME_LEN: usize = 32;
+/// Names reserved by the built-in profiles (compared case-insensitively)
+const BUILT_IN_NAMES: &[&str] = &["normal", "balanced", "gaming"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomProfile {
+    pub name: String,
+    pub areas: Areas,
+    #[serde(default)]
+    pub priority: Priority,
+    /// Replaces `process_exclusion_list` while active, `None` keeps the global list
+    #[serde(default)]
+    pub exclusions: Option<BTreeSet<String>>,
+    /// Global hotkey that activates the profile and optimizes
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Working Set trim strategy, `None` keeps the global one
+    #[serde(default)]
+    pub trim_strategy: Option<TrimStrategy>,
+}
+
+impl CustomProfile {
+    pub fn is_named(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name.trim())
+    }
+
+    pub fn exclusion_list_lower(&self) -> Option<Vec<String>> {
+        self.exclusions.as_ref().map(|list| {
+            list.iter()
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+    }
+}
+
+pub fn is_built_in_name(name: &str) -> bool {
+    BUILT_IN_NAMES.contains(&name.trim().to_lowercase().as_str())
+}
+
+/// Sanitize the profile list, dropping entries that cannot be used
+///
+/// Profiles without a usable name, with a name taken by a built-in or an
+/// earlier profile, or without any supported area are removed. Hotkeys that
+/// are empty or already used by an earlier profile are cleared.
+pub fn validate_custom_profiles(
+    profiles: &mut Vec<CustomProfile>,
+    sanitize_exclusions: impl Fn(&BTreeSet<String>) -> BTreeSet<String>,
+) {
+    profiles.truncate(MAX_CUSTOM_PROFILES);
+
+    let mut names = BTreeSet::new();
+    let mut hotkeys = BTreeSet::new();
+    profiles.retain_mut(|profile| {
+        profile.name = sanitize_string(profile.name.trim(), MAX_NAME_LEN)
+            .trim()
+            .to_string();
+        if profile.name.is_empty()
+            || contains_injection_patterns(&profile.name)
+            || is_built_in_name(&profile.name)
+            || !names.insert(profile.name.to_lowercase())
+        {
+            tracing::warn!(
+                "Dropping custom profile with invalid name '{}'",
+                profile.name
+            );
+            return false;
+        }
+
+        profile.areas = crate::os::supported_areas(Areas::from_bits_truncate(profile.areas.bits()));
+        if profile.areas.is_empty() {
+            tracing::warn!(
+                "Dropping custom profile '{}': no supported areas",
+                profile.name
+            );
+            return false;
+        }
+
+        if let Some(list) = profile.exclusions.as_mut() {
+            *list = sanitize_exclusions(list);
+        }
+
+        profile.hotkey = profile
+            .hotkey
+            .as_deref()
+            .filter(|h| !contains_injection_patterns(h))
+            .map(sanitize_hotkey)
+            .filter(|h| !h.trim().is_empty() && hotkeys.insert(h.to_uppercase()));
+
+        true
+    });
+}
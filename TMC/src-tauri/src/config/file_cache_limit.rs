@@ -0,0 +1,112 @@
+/// Persistent System File Cache limits
+///
+/// Purging the System File Cache only helps until the cache grows back. A
+/// limit keeps the cache working set between `min_mb` and `max_mb` while the
+/// profile it belongs to is active. Windows drops the limit at reboot, so it
+/// is applied again at startup. With `hard` the maximum is enforced; without
+/// it the memory manager only uses it as a trimming target.
+use crate::security::sanitize_string;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const MAX_FILE_CACHE_LIMITS: usize = 16;
+/// Lower bound for `max_mb`, a smaller cache makes every file access hit the disk
+pub const MIN_LIMIT_MB: u32 = 64;
+/// 1 TB
+pub const MAX_LIMIT_MB: u32 = 1024 * 1024;
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileCacheLimit {
+    /// Built-in or custom profile the limit applies to
+    pub profile: String,
+    pub min_mb: u32,
+    pub max_mb: u32,
+    /// Enforce `max_mb` instead of using it as a trimming target
+    #[serde(default)]
+    pub hard: bool,
+}
+
+impl FileCacheLimit {
+    pub fn validate(&mut self) {
+        self.profile = sanitize_string(self.profile.trim(), 32).trim().to_string();
+        self.max_mb = self.max_mb.clamp(MIN_LIMIT_MB, MAX_LIMIT_MB);
+        self.min_mb = self.min_mb.min(self.max_mb);
+    }
+
+    pub fn min_bytes(&self) -> u64 {
+        self.min_mb as u64 * BYTES_PER_MB
+    }
+
+    pub fn max_bytes(&self) -> u64 {
+        self.max_mb as u64 * BYTES_PER_MB
+    }
+
+    /// Same limit, whatever profile it belongs to
+    pub fn same_limit(&self, other: &FileCacheLimit) -> bool {
+        (self.min_mb, self.max_mb, self.hard) == (other.min_mb, other.max_mb, other.hard)
+    }
+}
+
+/// Clamp the entries and drop the ones for unknown or already limited profiles
+pub fn validate_file_cache_limits(
+    limits: &mut Vec<FileCacheLimit>,
+    is_known_profile: impl Fn(&str) -> bool,
+) {
+    let mut seen = BTreeSet::new();
+    limits.retain_mut(|limit| {
+        limit.validate();
+        if !is_known_profile(&limit.profile) || !seen.insert(limit.profile.to_lowercase()) {
+            tracing::warn!(
+                "Dropping file cache limit for '{}': unknown profile or already limited",
+                limit.profile
+            );
+            return false;
+        }
+        true
+    });
+    if limits.len() > MAX_FILE_CACHE_LIMITS {
+        tracing::warn!(
+            "Too many file cache limits ({}), keeping the first {}",
+            limits.len(),
+            MAX_FILE_CACHE_LIMITS
+        );
+        limits.truncate(MAX_FILE_CACHE_LIMITS);
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(profile: &str, min_mb: u32, max_mb: u32) -> FileCacheLimit {
+        FileCacheLimit {
+            profile: profile.to_string(),
+            min_mb,
+            max_mb,
+            hard: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_file_cache_limits() {
+        let mut limits = vec![
+            limit("Gaming", 512, 16),
+            limit("gaming", 0, 1024),
+            limit("Nope", 0, 1024),
+            limit(" Balanced ", 0, u32::MAX),
+        ];
+        validate_file_cache_limits(&mut limits, |name| name != "Nope");
+
+        assert_eq!(
+            limits,
+            vec![
+                limit("Gaming", MIN_LIMIT_MB, MIN_LIMIT_MB),
+                limit("Balanced", 0, MAX_LIMIT_MB),
+            ]
+        );
+        assert_eq!(limits[0].max_bytes(), 64 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,77 @@
+/// Global hotkeys bound to actions
+///
+/// Besides the main optimization hotkey (`Config::hotkey`) the user can bind
+/// additional shortcuts to other actions. Custom profile hotkeys are exposed
+/// through the same model as `Optimize` bindings with a profile.
+use crate::security::{contains_injection_patterns, sanitize_hotkey, sanitize_string};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const MAX_HOTKEY_BINDINGS: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HotkeyAction {
+    /// Optimize, activating the named profile first (`None` keeps the current one)
+    Optimize {
+        #[serde(default)]
+        profile: Option<String>,
+    },
+    /// Pause or resume all automatic optimizations
+    ToggleAutoOptimizer,
+    /// Show the main window, or hide it when it is visible
+    ToggleWindow,
+    /// Trim the working set of the process owning the foreground window
+    TrimForeground,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyBinding {
+    pub hotkey: String,
+    pub action: HotkeyAction,
+}
+
+impl HotkeyBinding {
+    pub fn new(hotkey: impl Into<String>, action: HotkeyAction) -> Self {
+        Self {
+            hotkey: hotkey.into(),
+            action,
+        }
+    }
+}
+
+/// Sanitize the binding list
+///
+/// Bindings with an unusable hotkey, or with keys already taken by `reserved`
+/// (e.g. the main hotkey) or by an earlier binding, are dropped.
+pub fn validate_hotkey_bindings(bindings: &mut Vec<HotkeyBinding>, reserved: &[&str]) {
+    bindings.truncate(MAX_HOTKEY_BINDINGS);
+
+    let mut seen: BTreeSet<String> = reserved.iter().map(|h| h.to_uppercase()).collect();
+    bindings.retain_mut(|binding| {
+        if contains_injection_patterns(&binding.hotkey) {
+            tracing::warn!("Dropping hotkey binding with invalid keys");
+            return false;
+        }
+        binding.hotkey = sanitize_hotkey(&binding.hotkey);
+        if binding.hotkey.trim().is_empty() || !seen.insert(binding.hotkey.to_uppercase()) {
+            tracing::warn!(
+                "Dropping hotkey binding '{}': empty or already in use",
+                binding.hotkey
+            );
+            return false;
+        }
+
+        if let HotkeyAction::Optimize {
+            profile: Some(name),
+        } = &mut binding.action
+        {
+            *name = sanitize_string(name.trim(), 32).trim().to_string();
+            if name.is_empty() {
+                binding.action = HotkeyAction::Optimize { profile: None };
+            }
+        }
+
+        true
+    });
+}
@@ -0,0 +1,166 @@
+/// Crash-resistant config persistence
+///
+/// A save never overwrites `config.json` in place. The new content is written
+/// and fsynced to a sidecar journal, the current file is kept as the
+/// last-known-good copy while it still parses, and only then the journal is
+/// swapped over the main file. After a hard power loss at any point, `read`
+/// finds either a complete journal, an intact main file or the last-known-good
+/// copy, instead of falling back to defaults.
+use serde::de::DeserializeOwned;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Where the loaded configuration came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Main,
+    /// A save was interrupted after the journal was synced
+    Journal,
+    /// The main file was corrupt
+    LastGood,
+}
+
+pub fn journal_path(path: &Path) -> PathBuf {
+    path.with_extension("json.journal")
+}
+
+pub fn last_good_path(path: &Path) -> PathBuf {
+    path.with_extension("json.lastgood")
+}
+
+/// Copy of a corrupt main file, kept for troubleshooting
+pub fn corrupt_path(path: &Path) -> PathBuf {
+    path.with_extension("json.corrupt")
+}
+
+/// Durably replace `path` with `content`
+pub fn write<T: DeserializeOwned>(path: &Path, content: &str) -> io::Result<()> {
+    let journal = journal_path(path);
+    {
+        let mut file = File::create(&journal)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+    }
+
+    // A corrupt main file must not replace a good fallback copy
+    if parse_file::<T>(path).is_some() {
+        if let Err(e) = copy_synced(path, &last_good_path(path)) {
+            tracing::warn!("Failed to update last-known-good config: {}", e);
+        }
+    }
+
+    replace(&journal, path)
+}
+
+/// Read `path`, recovering from an interrupted save or a corrupt file.
+/// Returns `None` when no usable copy exists.
+pub fn read<T: DeserializeOwned>(path: &Path) -> Option<(T, Source)> {
+    let journal = journal_path(path);
+    if journal.exists() {
+        match parse_file::<T>(&journal) {
+            Some(value) => {
+                // The journal is complete and newer than the main file: finish the swap
+                if let Err(e) = replace(&journal, path) {
+                    tracing::warn!("Failed to complete interrupted config save: {}", e);
+                }
+                return Some((value, Source::Journal));
+            }
+            None => {
+                // Interrupted while writing the journal, the main file was not touched
+                tracing::warn!(
+                    "Discarding incomplete config journal: {}",
+                    journal.display()
+                );
+                let _ = fs::remove_file(&journal);
+            }
+        }
+    }
+
+    if let Some(value) = parse_file::<T>(path) {
+        return Some((value, Source::Main));
+    }
+
+    if path.exists() {
+        let _ = fs::copy(path, corrupt_path(path));
+    }
+    parse_file::<T>(&last_good_path(path)).map(|value| (value, Source::LastGood))
+}
+
+fn parse_file<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn copy_synced(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from, to)?;
+    File::options().write(true).open(to)?.sync_all()
+}
+
+/// Move `from` over `to`, flushed to disk before returning
+#[cfg(windows)]
+fn replace(from: &Path, to: &Path) -> io::Result<()> {
+    use windows_sys::Win32::Storage::FileSystem::{
+        MoveFileExW, MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH,
+    };
+
+    let from_w = crate::to_wide(&from.to_string_lossy());
+    let to_w = crate::to_wide(&to.to_string_lossy());
+    let ok = unsafe {
+        MoveFileExW(
+            from_w.as_ptr(),
+            to_w.as_ptr(),
+            MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+        )
+    };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn replace(from: &Path, to: &Path) -> io::Result<()> {
+    fs::rename(from, to)?;
+    if let Some(parent) = to.parent() {
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recovers_from_journal_and_last_good() {
+        let dir = std::env::temp_dir().join(format!("tmc_journal_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.json");
+
+        write::<serde_json::Value>(&path, r#"{"v":1}"#).unwrap();
+        write::<serde_json::Value>(&path, r#"{"v":2}"#).unwrap();
+        let (value, source) = read::<serde_json::Value>(&path).unwrap();
+        assert_eq!((value["v"].as_u64(), source), (Some(2), Source::Main));
+
+        // Truncated journal: ignored, main file wins
+        fs::write(journal_path(&path), r#"{"v":"#).unwrap();
+        let (value, source) = read::<serde_json::Value>(&path).unwrap();
+        assert_eq!((value["v"].as_u64(), source), (Some(2), Source::Main));
+        assert!(!journal_path(&path).exists());
+
+        // Complete journal: newer than the main file
+        fs::write(journal_path(&path), r#"{"v":3}"#).unwrap();
+        let (value, source) = read::<serde_json::Value>(&path).unwrap();
+        assert_eq!((value["v"].as_u64(), source), (Some(3), Source::Journal));
+        assert_eq!(fs::read_to_string(&path).unwrap(), r#"{"v":3}"#);
+
+        // Corrupt main file: last-known-good copy from the previous save
+        fs::write(&path, "\0\0\0\0").unwrap();
+        let (value, source) = read::<serde_json::Value>(&path).unwrap();
+        assert_eq!((value["v"].as_u64(), source), (Some(1), Source::LastGood));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
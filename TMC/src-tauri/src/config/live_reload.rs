@@ -0,0 +1,182 @@
+/// Live reload of config.json edited while TMC runs
+///
+/// A background thread polls the modification time of config.json. When the
+/// file changes and its content is not what TMC itself wrote last, it is
+/// migrated and validated like on startup and replaces the running
+/// configuration: tray, hotkeys and background tasks are refreshed and
+/// `config-changed` is emitted. A file that does not parse is reported and
+/// left alone until the next edit.
+///
+/// If the running configuration has changes that never reached the disk,
+/// neither side is dropped silently: the edited file is held back, the UI
+/// gets `config-conflict` and the user picks one with `resolve_conflict`.
+use super::{Config, SharedConfig};
+use crate::logging::error_bus::{report, Subsystem};
+use crate::ui::bridge::EV_CONFIG_CONFLICT;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Hash of the content TMC last wrote to or applied from config.json
+static LAST_SYNCED: Mutex<Option<u64>> = Mutex::new(None);
+/// Edited configuration held back by a conflict
+static PENDING: Lazy<Mutex<Option<Config>>> = Lazy::new(|| Mutex::new(None));
+static WATCHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Record `content` as written by TMC, so the watcher does not reload it
+pub fn mark_synced(content: &str) {
+    *LAST_SYNCED.lock() = Some(content_hash(content));
+}
+
+/// Whether the running configuration differs from what was last synced
+fn has_unsaved_changes(cfg: &Config) -> bool {
+    let synced = *LAST_SYNCED.lock();
+    match serde_json::to_string_pretty(cfg) {
+        Ok(content) => synced != Some(content_hash(&content)),
+        Err(_) => false,
+    }
+}
+
+/// Start watching config.json; does nothing if the watcher already runs
+pub fn start_watcher(app: AppHandle, cfg: SharedConfig) {
+    if WATCHER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-config-watch".into())
+        .spawn(move || {
+            let path = super::config_path();
+            let stamp = |p: &std::path::Path| -> Option<(SystemTime, u64)> {
+                let meta = std::fs::metadata(p).ok()?;
+                Some((meta.modified().ok()?, meta.len()))
+            };
+            let mut seen = stamp(&path);
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+                let current = stamp(&path);
+                if current.is_none() || current == seen {
+                    continue;
+                }
+                seen = current;
+
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        tracing::debug!("Config watcher could not read the file: {}", e);
+                        continue;
+                    }
+                };
+                if *LAST_SYNCED.lock() == Some(content_hash(&content)) {
+                    continue;
+                }
+                on_external_edit(&app, &cfg, &content);
+            }
+        });
+
+    if let Err(e) = spawned {
+        WATCHER_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start config watcher thread: {}", e);
+    }
+}
+
+fn on_external_edit(app: &AppHandle, cfg: &SharedConfig, content: &str) {
+    let mut edited: Config = match serde_json::from_str(content) {
+        Ok(edited) => edited,
+        Err(e) => {
+            let msg = format!(
+                "config.json was edited but is not valid, keeping the running settings: {}",
+                e
+            );
+            tracing::warn!("{}", msg);
+            report(Subsystem::Config, msg);
+            return;
+        }
+    };
+    edited.migrate_if_needed();
+    edited.validate();
+
+    if has_unsaved_changes(&cfg.read()) {
+        tracing::warn!("config.json was edited while the running settings have unsaved changes");
+        *PENDING.lock() = Some(edited);
+        let _ = app.emit(EV_CONFIG_CONFLICT, ());
+        return;
+    }
+
+    tracing::info!("config.json was edited externally, applying it");
+    apply(app, cfg, edited);
+}
+
+/// Settle a conflict: `use_file` applies the edited file, otherwise the
+/// running configuration is saved over it. Returns `false` if there was none.
+pub fn resolve_conflict(
+    app: &AppHandle,
+    cfg: &SharedConfig,
+    use_file: bool,
+) -> Result<bool, String> {
+    let edited = match PENDING.lock().take() {
+        Some(edited) => edited,
+        None => return Ok(false),
+    };
+    if use_file {
+        apply(app, cfg, edited);
+    } else {
+        cfg.read()
+            .save()
+            .map_err(|e| format!("Failed to save config: {}", e))?;
+    }
+    Ok(true)
+}
+
+/// Replace the running configuration and refresh everything that reads it once
+fn apply(app: &AppHandle, cfg: &SharedConfig, edited: Config) {
+    let (previous, current) = {
+        let mut guard = cfg.write();
+        let previous = std::mem::replace(&mut *guard, edited);
+        // Written back in normalized form, which also marks it as synced
+        if let Err(e) = guard.save() {
+            tracing::warn!("Failed to save the reloaded config: {}", e);
+        }
+        (previous, guard.clone())
+    };
+
+    if current.language != previous.language {
+        crate::i18n::set_language(&current.language);
+        let _ = app.emit("language-changed", current.language.clone());
+    }
+    if current.always_on_top != previous.always_on_top {
+        let _ = crate::system::window::set_always_on_top(app, current.always_on_top);
+    }
+    crate::memory::file_cache::apply_profile_limit(&current);
+    if let Err(e) = crate::register_global_hotkey_v2(app, &current.hotkey, cfg.clone()) {
+        tracing::error!("Failed to register hotkey: {}", e);
+    }
+
+    // Background tasks started by a setting that was off; each one stops on its own
+    if let Some(state) = app.try_state::<crate::AppState>() {
+        crate::ipc::start_pipe_server(app.clone(), state.engine.clone(), cfg.clone());
+        crate::ipc::widget_feed::start_widget_feed(state.engine.clone(), cfg.clone());
+        crate::ipc::http_api::start_http_api(app.clone(), state.engine.clone(), cfg.clone());
+        crate::auto_optimizer::commit_guard::start_commit_monitor(
+            app.clone(),
+            state.engine.clone(),
+            cfg.clone(),
+        );
+    }
+    crate::updater::start_update_checker(app.clone(), cfg.clone());
+
+    crate::ui::tray::refresh_tray_icon(app);
+    let _ = app.emit("config-changed", ());
+}
@@ -0,0 +1,491 @@
+/// Startup migration of data left behind by older TMC versions
+///
+/// Older releases kept `config.json` next to the executable, stored the
+/// history as a single JSON array (`history.json`) and converted the
+/// notification icon into loose files. `run_startup_migration` moves all of it
+/// into the current data directory and formats. Every legacy file is copied to
+/// `<data_dir>/legacy_backup/<timestamp>/` before it is touched.
+use crate::memory::types::Reason;
+use crate::stats::OptimizationRecord;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+
+const BACKUP_DIR_NAME: &str = "legacy_backup";
+const REPORT_FILE_NAME: &str = "migration_report.json";
+const LEGACY_HISTORY_FILE: &str = "history.json";
+const LEGACY_ICON_FILES: [&str; 2] = ["icon.png", "icon.ico"];
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationStatus {
+    Migrated,
+    Skipped,
+    Failed,
+}
+
+/// One legacy file found at startup
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationItem {
+    /// `config`, `history` or `icon`
+    pub kind: String,
+    pub source: String,
+    pub destination: String,
+    pub status: MigrationStatus,
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Summary of the last migration that found legacy data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MigrationReport {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+    pub backup_dir: Option<String>,
+    pub items: Vec<MigrationItem>,
+}
+
+impl MigrationReport {
+    fn count(&self, status: MigrationStatus) -> usize {
+        self.items.iter().filter(|i| i.status == status).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{} migrated, {} skipped, {} failed",
+            self.count(MigrationStatus::Migrated),
+            self.count(MigrationStatus::Skipped),
+            self.count(MigrationStatus::Failed)
+        )
+    }
+}
+
+static LAST_REPORT: Lazy<Mutex<Option<MigrationReport>>> = Lazy::new(|| Mutex::new(None));
+static MIGRATION: Once = Once::new();
+
+/// Report of the most recent migration, from this run or a previous one
+pub fn last_report() -> Option<MigrationReport> {
+    if let Some(report) = LAST_REPORT.lock().clone() {
+        return Some(report);
+    }
+    let path = super::get_portable_detector()
+        .data_dir()
+        .join(REPORT_FILE_NAME);
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Detect and migrate legacy data (runs once per process)
+pub fn run_startup_migration() {
+    MIGRATION.call_once(|| {
+        let data_dir = super::get_portable_detector().data_dir().clone();
+        let mut migrator = Migrator::new(data_dir);
+        migrator.migrate_config();
+        migrator.migrate_history();
+        migrator.migrate_icons();
+        migrator.finish();
+    });
+}
+
+struct Migrator {
+    data_dir: PathBuf,
+    /// Created on the first backup
+    backup_dir: Option<PathBuf>,
+    report: MigrationReport,
+}
+
+impl Migrator {
+    fn new(data_dir: PathBuf) -> Self {
+        Self {
+            data_dir,
+            backup_dir: None,
+            report: MigrationReport::default(),
+        }
+    }
+
+    /// Directories older versions wrote to, excluding the current data dir
+    fn legacy_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if let Some(exe_dir) = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+        {
+            dirs.push(exe_dir);
+        }
+        #[cfg(windows)]
+        if let Ok(appdata) = std::env::var("APPDATA") {
+            dirs.push(PathBuf::from(appdata).join("TommyMemoryCleaner"));
+        }
+        dirs.retain(|d| *d != self.data_dir);
+        dirs.dedup();
+        dirs
+    }
+
+    fn record(
+        &mut self,
+        kind: &str,
+        source: &Path,
+        destination: &Path,
+        status: MigrationStatus,
+        detail: Option<String>,
+    ) {
+        self.report.items.push(MigrationItem {
+            kind: kind.to_string(),
+            source: source.display().to_string(),
+            destination: destination.display().to_string(),
+            status,
+            detail,
+        });
+    }
+
+    /// Copy a legacy file into the backup folder before touching it
+    fn backup(&mut self, source: &Path) -> std::io::Result<()> {
+        let backup_dir = match &self.backup_dir {
+            Some(dir) => dir.clone(),
+            None => {
+                let dir = self
+                    .data_dir
+                    .join(BACKUP_DIR_NAME)
+                    .join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+                fs::create_dir_all(&dir)?;
+                self.backup_dir = Some(dir.clone());
+                dir
+            }
+        };
+
+        // Files from different legacy folders may share a name
+        let file_name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let mut target = backup_dir.join(&file_name);
+        let mut n = 1;
+        while target.exists() {
+            target = backup_dir.join(format!("{}.{}", file_name, n));
+            n += 1;
+        }
+        fs::copy(source, target).map(|_| ())
+    }
+
+    /// `config.json` next to the executable (the roaming one belongs to the installer)
+    fn migrate_config(&mut self) {
+        let destination = self.data_dir.join("config.json");
+        let Some(exe_dir) = std::env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(Path::to_path_buf))
+        else {
+            return;
+        };
+        let source = exe_dir.join("config.json");
+        if !source.exists() || source == destination {
+            return;
+        }
+        if destination.exists() {
+            tracing::debug!(
+                "Legacy config {} ignored, {} already exists",
+                source.display(),
+                destination.display()
+            );
+            return;
+        }
+
+        if let Err(e) = self.backup(&source) {
+            let detail = format!("Backup failed: {}", e);
+            self.record(
+                "config",
+                &source,
+                &destination,
+                MigrationStatus::Failed,
+                Some(detail),
+            );
+            return;
+        }
+
+        // Older field layouts are upgraded by Config::load (config_version)
+        match fs::copy(&source, &destination) {
+            Ok(_) => {
+                if let Err(e) = fs::rename(&source, exe_dir.join("config.json.old")) {
+                    tracing::debug!("Failed to rename old config for backup: {}", e);
+                }
+                self.record(
+                    "config",
+                    &source,
+                    &destination,
+                    MigrationStatus::Migrated,
+                    None,
+                );
+            }
+            Err(e) => {
+                self.record(
+                    "config",
+                    &source,
+                    &destination,
+                    MigrationStatus::Failed,
+                    Some(e.to_string()),
+                );
+            }
+        }
+    }
+
+    /// `history.json` (JSON array) converted to the JSON Lines history
+    fn migrate_history(&mut self) {
+        let destination = self.data_dir.join("optimization_history.jsonl");
+        let mut dirs = self.legacy_dirs();
+        dirs.push(self.data_dir.clone());
+
+        for dir in dirs {
+            let source = dir.join(LEGACY_HISTORY_FILE);
+            let content = match fs::read_to_string(&source) {
+                Ok(c) => c,
+                Err(_) => continue,
+            };
+
+            if let Err(e) = self.backup(&source) {
+                let detail = format!("Backup failed: {}", e);
+                self.record(
+                    "history",
+                    &source,
+                    &destination,
+                    MigrationStatus::Failed,
+                    Some(detail),
+                );
+                continue;
+            }
+
+            let records = match parse_legacy_history(&content) {
+                Ok(r) => r,
+                Err(e) => {
+                    self.record(
+                        "history",
+                        &source,
+                        &destination,
+                        MigrationStatus::Failed,
+                        Some(e),
+                    );
+                    continue;
+                }
+            };
+
+            match crate::stats::import_records(records) {
+                Ok(count) => {
+                    if let Err(e) = fs::rename(&source, source.with_extension("json.migrated")) {
+                        tracing::debug!("Failed to rename legacy history: {}", e);
+                    }
+                    self.record(
+                        "history",
+                        &source,
+                        &destination,
+                        MigrationStatus::Migrated,
+                        Some(format!("{} records", count)),
+                    );
+                }
+                Err(e) => {
+                    self.record(
+                        "history",
+                        &source,
+                        &destination,
+                        MigrationStatus::Failed,
+                        Some(e.to_string()),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Notification icons converted by older versions (roaming AppData, or the
+    /// data dir root before the icon cache). They are derived from the embedded
+    /// icon, so they are backed up and removed; `ui::icon_cache` regenerates them.
+    /// Icons shipped next to the exe are left alone.
+    fn migrate_icons(&mut self) {
+        let destination = self.data_dir.join("icons");
+        let mut dirs = vec![self.data_dir.clone()];
+        #[cfg(windows)]
+        if let Ok(appdata) = std::env::var("APPDATA") {
+            dirs.push(PathBuf::from(appdata).join("TommyMemoryCleaner"));
+        }
+        dirs.dedup();
+
+        for dir in dirs {
+            for name in LEGACY_ICON_FILES {
+                let source = dir.join(name);
+                if !source.exists() {
+                    continue;
+                }
+
+                let result = self.backup(&source).and_then(|_| fs::remove_file(&source));
+                match result {
+                    Ok(_) => self.record(
+                        "icon",
+                        &source,
+                        &destination,
+                        MigrationStatus::Migrated,
+                        Some("Replaced by the icon cache".to_string()),
+                    ),
+                    Err(e) => self.record(
+                        "icon",
+                        &source,
+                        &destination,
+                        MigrationStatus::Failed,
+                        Some(e.to_string()),
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Log the report and keep it for diagnostics
+    fn finish(mut self) {
+        if self.report.items.is_empty() {
+            tracing::debug!("No legacy data to migrate");
+            return;
+        }
+
+        self.report.timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self.report.backup_dir = self.backup_dir.map(|d| d.display().to_string());
+
+        for item in &self.report.items {
+            match item.status {
+                MigrationStatus::Failed => tracing::warn!(
+                    "✗ Legacy {} {} not migrated: {}",
+                    item.kind,
+                    item.source,
+                    item.detail.as_deref().unwrap_or("unknown error")
+                ),
+                _ => tracing::info!(
+                    "Legacy {} {} -> {} ({:?})",
+                    item.kind,
+                    item.source,
+                    item.destination,
+                    item.status
+                ),
+            }
+        }
+        tracing::info!("✓ Legacy data migration: {}", self.report.summary());
+
+        match serde_json::to_string_pretty(&self.report) {
+            Ok(json) => {
+                if let Err(e) = fs::write(self.data_dir.join(REPORT_FILE_NAME), json) {
+                    tracing::debug!("Failed to save migration report: {}", e);
+                }
+            }
+            Err(e) => tracing::debug!("Failed to serialize migration report: {}", e),
+        }
+        *LAST_REPORT.lock() = Some(self.report);
+    }
+}
+
+fn field<'a>(entry: &'a Value, names: &[&str]) -> Option<&'a Value> {
+    names.iter().find_map(|n| entry.get(*n))
+}
+
+/// Timestamps were stored as seconds, milliseconds or RFC 3339 strings
+fn legacy_timestamp(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => {
+            let n = n.as_u64()?;
+            Some(if n > 100_000_000_000 { n / 1000 } else { n })
+        }
+        Value::String(s) => chrono::DateTime::parse_from_rfc3339(s)
+            .ok()
+            .and_then(|d| u64::try_from(d.timestamp()).ok()),
+        _ => None,
+    }
+}
+
+/// Convert a legacy `history.json` (array, or `{"history": [...]}`) to records
+pub fn parse_legacy_history(content: &str) -> Result<Vec<OptimizationRecord>, String> {
+    let root: Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid legacy history: {}", e))?;
+    let entries = match &root {
+        Value::Array(entries) => entries,
+        Value::Object(obj) => match obj.get("history") {
+            Some(Value::Array(entries)) => entries,
+            _ => return Err("Legacy history has no 'history' array".to_string()),
+        },
+        _ => return Err("Unsupported legacy history format".to_string()),
+    };
+
+    let records = entries
+        .iter()
+        .filter_map(|entry| {
+            let timestamp =
+                field(entry, &["timestamp", "time", "date"]).and_then(legacy_timestamp)?;
+            let freed_physical_bytes = match field(entry, &["freed_physical_bytes", "freed_bytes"])
+                .and_then(Value::as_i64)
+            {
+                Some(bytes) => bytes,
+                None => {
+                    (field(entry, &["freed_mb"]).and_then(Value::as_f64)? * BYTES_PER_MB) as i64
+                }
+            };
+            let reason = field(entry, &["reason"])
+                .and_then(|r| serde_json::from_value::<Reason>(r.clone()).ok())
+                .unwrap_or(Reason::Manual);
+            let profile = field(entry, &["profile"])
+                .and_then(Value::as_str)
+                .unwrap_or("Unknown")
+                .to_string();
+
+            Some(OptimizationRecord {
+                timestamp,
+                reason,
+                profile,
+                areas: Vec::new(),
+                freed_physical_bytes,
+                freed_commit_bytes: field(entry, &["freed_commit_bytes"])
+                    .and_then(Value::as_i64)
+                    .unwrap_or(0),
+                duration_ms: field(entry, &["duration_ms"])
+                    .and_then(Value::as_u64)
+                    .unwrap_or(0) as u128,
+                deferred: None,
+                purge_impact: None,
+            })
+        })
+        .collect::<Vec<_>>();
+
+    if records.is_empty() && !entries.is_empty() {
+        return Err("No readable records in legacy history".to_string());
+    }
+    Ok(records)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_legacy_history() {
+        let records = parse_legacy_history(
+            r#"[
+                {"timestamp": 1700000000, "freed_mb": 512, "profile": "Gaming", "reason": "Schedule"},
+                {"date": "2023-11-14T22:13:20Z", "freed_bytes": 1048576},
+                {"timestamp": 1700000000000, "freed_mb": 1.5},
+                {"note": "no timestamp"}
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].freed_physical_bytes, 512 * 1024 * 1024);
+        assert_eq!(records[0].reason, Reason::Schedule);
+        assert_eq!(records[1].timestamp, 1_700_000_000);
+        assert_eq!(records[1].profile, "Unknown");
+        assert_eq!(records[2].timestamp, 1_700_000_000);
+
+        assert!(parse_legacy_history(r#"{"history": []}"#)
+            .unwrap()
+            .is_empty());
+        assert!(parse_legacy_history(r#"[{"foo": 1}]"#).is_err());
+    }
+}
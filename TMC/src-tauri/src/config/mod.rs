@@ -3,21 +3,66 @@
 /// Handles loading, saving, and validating application configuration
 /// with support for portable installations and proper data directory handling.
 pub mod app_info;
-
-use crate::memory::types::Areas;
+pub mod area_cooldown;
+pub mod config_file;
+pub mod custom_profile;
+pub mod file_cache_limit;
+pub mod hotkey_binding;
+pub mod journal;
+pub mod live_reload;
+pub mod migration;
+pub mod preset_file;
+pub mod profile_file;
+pub mod ram_floor;
+pub mod schema;
+pub mod service;
+
+use crate::auto_optimizer::emergency::EmergencyMode;
+use crate::auto_optimizer::game_mode::GameMode;
+use crate::auto_optimizer::gentle_maintenance;
+use crate::auto_optimizer::prediction::PressurePrediction;
+use crate::auto_optimizer::schedule::{ScheduleEntry, ScheduleSpec, MAX_SCHEDULES};
+use crate::logging::error_bus::{report, Subsystem};
+use crate::memory::types::{Areas, TrimStrategy};
+use crate::notifications::{NotificationVerbosity, ProfileNotifications, ToastOptions};
 use crate::security::{
     contains_injection_patterns, is_valid_hex_color, sanitize_hotkey, sanitize_process_name,
+    sanitize_string,
 };
+use crate::storage::TempCleanerOptions;
+use crate::ui::accessibility::AccessibilityOverrides;
+pub use area_cooldown::AreaCooldown;
+pub use custom_profile::CustomProfile;
+pub use file_cache_limit::FileCacheLimit;
+pub use hotkey_binding::{HotkeyAction, HotkeyBinding};
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::{collections::BTreeSet, fs, io, path::PathBuf};
 
 // ========== PORTABLE DETECTION ==========
+/// Marker file next to the executable that enables portable mode
+pub const PORTABLE_FLAG_FILE: &str = "portable.flag";
+/// Command line switch that enables portable mode
+pub const PORTABLE_ARG: &str = "--portable";
+/// Data folder next to the executable used in portable mode
+const PORTABLE_DATA_DIR: &str = "data";
+
+/// Where configuration, logs, stats and cached icons are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataMode {
+    /// `%LOCALAPPDATA%\TommyMemoryCleaner`
+    AppData,
+    /// `data\` beside the executable, nothing is written to the user profile
+    Portable,
+}
+
 /// Detects portable installation and manages data directories
 #[derive(Debug, Clone)]
 pub struct PortableDetector {
     is_portable: bool,
+    mode: DataMode,
     exe_path: PathBuf,
     data_dir: PathBuf,
 }
@@ -27,33 +72,30 @@ impl PortableDetector {
     pub fn new() -> io::Result<Self> {
         let exe_path = std::env::current_exe()?;
 
-        // The program is always "portable" (can be moved anywhere)
-        // but data is ALWAYS saved in AppData for centralization
-        let is_portable = true; // The program is portable (can be moved)
-
-        // ALWAYS use AppData for data, regardless of exe location
-        let data_dir = {
-            #[cfg(windows)]
-            {
-                use std::env;
-                // Try LOCALAPPDATA first, then APPDATA
-                env::var("LOCALAPPDATA")
-                    .or_else(|_| env::var("APPDATA"))
-                    .map(PathBuf::from)
-                    .unwrap_or_else(|_| {
-                        // Fallback to user directory
-                        dirs::config_dir().unwrap_or_else(|| PathBuf::from("."))
-                    })
-                    .join("TommyMemoryCleaner")
-            }
-
-            #[cfg(not(windows))]
-            {
-                dirs::config_dir()
-                    .unwrap_or_else(|| PathBuf::from("."))
-                    .join("TommyMemoryCleaner")
+        // The program is always "portable" (can be moved anywhere);
+        // only the data location depends on the mode
+        let is_portable = true;
+
+        let mut mode = DataMode::AppData;
+        let mut data_dir = appdata_dir();
+
+        if let Some(exe_dir) = exe_path.parent() {
+            if portable_requested(exe_dir) {
+                let portable_dir = exe_dir.join(PORTABLE_DATA_DIR);
+                // A read-only location (e.g. a locked USB stick) falls back to AppData
+                match fs::create_dir_all(&portable_dir) {
+                    Ok(()) => {
+                        mode = DataMode::Portable;
+                        data_dir = portable_dir;
+                    }
+                    Err(e) => tracing::warn!(
+                        "Portable mode requested but {} is not writable ({}), using AppData",
+                        portable_dir.display(),
+                        e
+                    ),
+                }
             }
-        };
+        }
 
         // Create directory if it doesn't exist
         if !data_dir.exists() {
@@ -61,14 +103,11 @@ impl PortableDetector {
         }
 
         // Log where we save the data
-        tracing::info!("Data directory: {}", data_dir.display());
-        tracing::info!(
-            "Portable executable: {} (can be moved anywhere, data saved in AppData)",
-            is_portable
-        );
+        tracing::info!("Data directory: {} ({:?} mode)", data_dir.display(), mode);
 
         Ok(Self {
             is_portable,
+            mode,
             exe_path,
             data_dir,
         })
@@ -78,6 +117,15 @@ impl PortableDetector {
         self.is_portable
     }
 
+    pub fn mode(&self) -> DataMode {
+        self.mode
+    }
+
+    /// True if data is stored beside the executable
+    pub fn is_portable_mode(&self) -> bool {
+        self.mode == DataMode::Portable
+    }
+
     pub fn config_path(&self) -> PathBuf {
         self.data_dir.join("config.json")
     }
@@ -91,12 +139,42 @@ impl PortableDetector {
     }
 }
 
+/// Portable mode is on if `portable.flag` sits next to the exe or `--portable` was passed
+fn portable_requested(exe_dir: &std::path::Path) -> bool {
+    exe_dir.join(PORTABLE_FLAG_FILE).is_file() || std::env::args().any(|a| a == PORTABLE_ARG)
+}
+
+/// Per-user data directory used outside portable mode
+fn appdata_dir() -> PathBuf {
+    #[cfg(windows)]
+    {
+        use std::env;
+        // Try LOCALAPPDATA first, then APPDATA
+        env::var("LOCALAPPDATA")
+            .or_else(|_| env::var("APPDATA"))
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| {
+                // Fallback to user directory
+                dirs::config_dir().unwrap_or_else(|| PathBuf::from("."))
+            })
+            .join("TommyMemoryCleaner")
+    }
+
+    #[cfg(not(windows))]
+    {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("TommyMemoryCleaner")
+    }
+}
+
 static PORTABLE: Lazy<RwLock<PortableDetector>> = Lazy::new(|| match PortableDetector::new() {
     Ok(detector) => RwLock::new(detector),
     Err(e) => {
         eprintln!("Failed to initialize portable detector: {}", e);
         RwLock::new(PortableDetector {
             is_portable: false,
+            mode: DataMode::AppData,
             exe_path: std::env::current_exe().unwrap_or_else(|err| {
                 tracing::error!("Failed to get exe path: {}, using fallback", err);
                 PathBuf::from(".")
@@ -129,6 +207,54 @@ impl Default for Priority {
     }
 }
 
+/// What automatic optimizations do while the workstation is locked
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum LockedSessionPolicy {
+    /// Triggers fire as usual
+    RunWhileLocked,
+    /// Triggers are held and the first run after unlocking is marked deferred
+    DeferToUnlock,
+    /// Triggers that come due while locked are dropped
+    Skip,
+}
+
+impl Default for LockedSessionPolicy {
+    fn default() -> Self {
+        Self::RunWhileLocked
+    }
+}
+
+/// Whether the Working Set trim reaches the processes of other logged on users
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OtherSessionsPolicy {
+    /// Every session is trimmed, as on a single-user machine
+    TrimAll,
+    /// Processes in other user sessions (fast user switching, RDP) are skipped
+    SkipOtherSessions,
+}
+
+impl Default for OtherSessionsPolicy {
+    fn default() -> Self {
+        Self::TrimAll
+    }
+}
+
+/// How the app is started at logon
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum StartupMethod {
+    /// Run registry key (a Startup folder shortcut in portable mode), no elevation
+    RunKey,
+    /// Task Scheduler task with highest privileges, elevated without a UAC prompt
+    ScheduledTask,
+}
+
+impl Default for StartupMethod {
+    fn default() -> Self {
+        Self::RunKey
+    }
+}
+
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "PascalCase")]
 pub enum Profile {
@@ -180,6 +306,11 @@ impl Profile {
                     areas |= Areas::MODIFIED_FILE_CACHE;
                 }
                 
+                // Store di compressione se disponibile
+                if crate::os::has_memory_compression() {
+                    areas |= Areas::MEMORY_COMPRESSION;
+                }
+                
                 areas
             }
             Profile::Gaming => {
@@ -205,6 +336,9 @@ impl Profile {
                 if crate::os::has_modified_file_cache() {
                     areas |= Areas::MODIFIED_FILE_CACHE;
                 }
+                if crate::os::has_memory_compression() {
+                    areas |= Areas::MEMORY_COMPRESSION;
+                }
                 
                 tracing::info!(
                     "Gaming profile areas: {:?} ({} areas)",
@@ -223,12 +357,40 @@ impl Profile {
             Profile::Gaming => Priority::High,
         }
     }
+
+    pub fn trim_strategy(&self) -> TrimStrategy {
+        match self {
+            Profile::Normal => TrimStrategy::Gentle,
+            Profile::Balanced => TrimStrategy::Standard,
+            Profile::Gaming => TrimStrategy::Aggressive,
+        }
+    }
 }
 
 // ========== TRAY CONFIG ==========
+/// What the tray icon draws
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TrayDisplayMode {
+    /// RAM usage percentage as text
+    Percent,
+    /// Bar graph of RAM usage over the last few minutes
+    Sparkline,
+    /// Application icon, no live data
+    IconOnly,
+}
+
+impl Default for TrayDisplayMode {
+    fn default() -> Self {
+        Self::Percent
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TrayConfig {
     pub show_mem_usage: bool,
+    #[serde(default)]
+    pub display_mode: TrayDisplayMode,
     pub text_color_hex: String,
     pub background_color_hex: String,
     pub transparent_bg: bool,
@@ -236,12 +398,20 @@ pub struct TrayConfig {
     pub warning_color_hex: String,
     pub danger_level: u8,
     pub danger_color_hex: String,
+    /// Text and background follow the Windows taskbar theme and accent color
+    #[serde(default)]
+    pub follow_system: bool,
+    /// Right-click opens a native menu with an optimize entry per built-in
+    /// profile instead of the webview menu
+    #[serde(default)]
+    pub native_menu: bool,
 }
 
 impl Default for TrayConfig {
     fn default() -> Self {
         Self {
             show_mem_usage: true,
+            display_mode: TrayDisplayMode::Percent,
             text_color_hex: "#FFFFFF".to_string(),
             background_color_hex: "#2d8a3d".to_string(), // Original green but slightly less bright
             transparent_bg: false,
@@ -249,11 +419,22 @@ impl Default for TrayConfig {
             warning_color_hex: "#d97706".to_string(), // Original orange but slightly less bright
             danger_level: 90,
             danger_color_hex: "#b91c1c".to_string(), // Original red but slightly less bright
+            follow_system: false,
+            native_menu: false,
         }
     }
 }
 
 impl TrayConfig {
+    /// Display mode, with `show_mem_usage` turned off meaning the plain icon
+    pub fn effective_display_mode(&self) -> TrayDisplayMode {
+        if self.show_mem_usage {
+            self.display_mode
+        } else {
+            TrayDisplayMode::IconOnly
+        }
+    }
+
     fn validate(&mut self) {
         // If colors are still old defaults (including "cold" ones), update to new balanced ones
         // Complete list of all old colors to update
@@ -342,10 +523,67 @@ impl TrayConfig {
 pub struct Config {
     pub always_on_top: bool,
     pub minimize_to_tray: bool,
+    /// Destroy the main window and its WebView when hidden to the tray
+    #[serde(default)]
+    pub destroy_webview_on_hide: bool,
     pub close_after_opt: bool,
     pub compact_mode: bool,
     pub auto_opt_interval_hours: u32,
     pub auto_opt_free_threshold: u8,
+    /// Automatic optimizations wait until the user has been idle for `idle_minutes`
+    #[serde(default)]
+    pub auto_opt_only_when_idle: bool,
+    #[serde(default = "default_idle_minutes")]
+    pub idle_minutes: u32,
+    /// Behaviour of automatic optimizations while the session is locked
+    #[serde(default)]
+    pub locked_session_policy: LockedSessionPolicy,
+    /// Working Set trims of processes in other user sessions
+    #[serde(default)]
+    pub other_sessions_policy: OtherSessionsPolicy,
+    /// Working Set trim skips processes above this CPU usage (percent, 0 = off)
+    #[serde(default)]
+    pub busy_process_cpu_percent: u8,
+    /// Expert override: let the Working Set trim touch system-account, protected
+    /// and critical-flagged processes (the hardcoded critical list still applies)
+    #[serde(default)]
+    pub allow_system_process_trim: bool,
+    /// Excluding a process also excludes every process it started, down the tree
+    #[serde(default)]
+    pub exclude_child_processes: bool,
+    /// Run every area through documented APIs only: no SYSTEM token
+    /// impersonation and no syscall number resolution (security software friendly)
+    #[serde(default)]
+    pub use_documented_apis_only: bool,
+    /// Milliseconds to wait after each area before measuring what it freed
+    #[serde(default = "default_area_settle_ms")]
+    pub area_settle_ms: u32,
+    /// Runs again a memory list area that reported success but left its list
+    /// unchanged, at most this many times (0 = no verification)
+    #[serde(default = "default_area_verify_retries")]
+    pub area_verify_retries: u8,
+    /// Milliseconds to wait after the last area before measuring the memory
+    /// freed by the whole run
+    #[serde(default = "default_measurement_delay_ms")]
+    pub measurement_delay_ms: u32,
+    /// Memory samples taken for that measurement, the median one counts
+    #[serde(default = "default_measurement_samples")]
+    pub measurement_samples: u8,
+    /// Time a short allocation workload before and after each optimization
+    #[serde(default)]
+    pub benchmark_enabled: bool,
+    /// Skip areas that freed nothing in their recent runs, probing them now and then
+    #[serde(default)]
+    pub adaptive_area_selection: bool,
+    /// Editors the full Working Set trim skips while they have open documents
+    #[serde(default = "default_document_editor_processes")]
+    pub document_editor_processes: BTreeSet<String>,
+    /// The full Working Set trim skips the process of the foreground window
+    #[serde(default = "default_skip_foreground_process")]
+    pub skip_foreground_process: bool,
+    /// The full Working Set trim skips processes that are playing audio
+    #[serde(default = "default_skip_audio_processes")]
+    pub skip_audio_processes: bool,
     pub auto_update: bool,
     pub font_size: f32,
     pub language: String,
@@ -360,10 +598,45 @@ pub struct Config {
     pub memory_areas: Areas,
     pub hotkey: String,
     pub process_exclusion_list: BTreeSet<String>,
+    /// When not empty, Working Set optimization only trims these processes
+    #[serde(default)]
+    pub process_include_list: BTreeSet<String>,
+    /// Report an NT call that failed inside an area as the area error, even
+    /// when a fallback call succeeded after it
+    #[serde(default)]
+    pub strict_nt_errors: bool,
+    /// Processes whose working set is released gradually instead of trimmed (experimental)
+    #[serde(default)]
+    pub gentle_maintenance_processes: BTreeSet<String>,
+    /// Seconds between two gentle maintenance passes
+    #[serde(default = "default_gentle_maintenance_interval_secs")]
+    pub gentle_maintenance_interval_secs: u32,
     pub run_priority: Priority,
     pub run_on_startup: bool,
+    #[serde(default)]
+    pub startup_method: StartupMethod,
+    /// Delay after logon before the scheduled task starts the app (seconds)
+    #[serde(default = "default_startup_delay_secs")]
+    pub startup_delay_secs: u32,
+    /// Start to the tray without creating the main window
+    #[serde(default)]
+    pub start_minimized: bool,
     pub show_opt_notifications: bool,
+    /// Notification verbosity per built-in profile; `show_opt_notifications`
+    /// still turns notifications of automatic runs off altogether
+    #[serde(default)]
+    pub notification_verbosity: ProfileNotifications,
+    /// Say that memory was already optimal when a run frees less than 1 MB,
+    /// instead of reporting a near-zero amount
+    #[serde(default = "default_notify_nothing_freed")]
+    pub notify_nothing_freed: bool,
+    /// Show the optimizations, memory freed and RAM peak of the session on exit
+    #[serde(default)]
+    pub session_summary_notification: bool,
     pub tray: TrayConfig,
+    /// Forced high contrast and reduced motion, detected from Windows when unset
+    #[serde(default)]
+    pub accessibility: AccessibilityOverrides,
     #[serde(default)]
     pub request_elevation_on_startup: bool,
 
@@ -381,6 +654,203 @@ pub struct Config {
     
     #[serde(default)]
     pub is_windows_10: bool,
+
+    /// Local SDK named pipe for third-party tools (off by default)
+    #[serde(default)]
+    pub sdk_pipe_enabled: bool,
+    /// Full paths of the executables allowed to talk to the SDK pipe
+    #[serde(default)]
+    pub sdk_allowed_clients: BTreeSet<String>,
+    /// Maximum optimization requests per client per minute
+    #[serde(default = "default_sdk_rate_limit_per_minute")]
+    pub sdk_rate_limit_per_minute: u32,
+    /// Keep widget.json in the data directory updated for widget tools (off by default)
+    #[serde(default)]
+    pub widget_feed_enabled: bool,
+    /// Seconds between widget.json updates
+    #[serde(default = "default_widget_feed_interval_secs")]
+    pub widget_feed_interval_secs: u32,
+    /// Local HTTP API for dashboards on 127.0.0.1 (off by default)
+    #[serde(default)]
+    pub http_api_enabled: bool,
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
+    /// Bearer token required by every HTTP API request, generated when empty
+    #[serde(default)]
+    pub http_api_token: String,
+    /// Seconds between memory composition samples for the timeline
+    #[serde(default = "default_timeline_interval_secs")]
+    pub timeline_interval_secs: u32,
+    /// Save the memory timeline and reload it at the next start
+    #[serde(default)]
+    pub timeline_persist: bool,
+    /// Count optimizations and feature use in usage_metrics.json (never sent anywhere)
+    #[serde(default = "default_usage_metrics")]
+    pub usage_metrics: bool,
+    /// Warn when the commit charge reaches this percentage of the commit limit, 0 = off
+    #[serde(default = "default_commit_warning_percent")]
+    pub commit_warning_percent: u8,
+
+    /// Free RAM floor for automatic runs, `None` uses the profile defaults (disabled)
+    #[serde(default)]
+    pub free_ram_floor: Option<FreeRamFloor>,
+
+    /// Working Set trim strategy, `None` uses the profile default
+    #[serde(default)]
+    pub trim_strategy: Option<TrimStrategy>,
+
+    /// Cron-like schedule rules, evaluated alongside `auto_opt_interval_hours`
+    #[serde(default)]
+    pub schedules: Vec<ScheduleEntry>,
+
+    /// Trend-based trigger that optimizes before `auto_opt_free_threshold` is reached
+    #[serde(default)]
+    pub pressure_prediction: PressurePrediction,
+
+    /// Gaming profile while a game runs, reverted when it exits or the GPU stays idle
+    #[serde(default)]
+    pub game_mode: GameMode,
+
+    /// Immediate standby purge and working set trim below a critical free memory threshold
+    #[serde(default)]
+    pub emergency_mode: EmergencyMode,
+
+    /// Duration, scenario and sound of toast notifications
+    #[serde(default)]
+    pub toast: ToastOptions,
+
+    /// Opt-in cleaner for temporary files and the shader cache
+    #[serde(default)]
+    pub temp_cleaner: TempCleanerOptions,
+
+    /// User-defined profiles, selectable alongside the built-in ones
+    #[serde(default)]
+    pub custom_profiles: Vec<CustomProfile>,
+    /// Name of the custom profile in use, `None` when a built-in profile is active
+    #[serde(default)]
+    pub active_custom_profile: Option<String>,
+
+    /// Aggressive areas the user has confirmed; automatic runs skip the others.
+    /// Configs written before this setting existed keep their current behavior.
+    #[serde(default = "legacy_confirmed_areas")]
+    pub confirmed_areas: Areas,
+
+    /// Additional global hotkeys, each bound to an action
+    #[serde(default)]
+    pub hotkeys: Vec<HotkeyBinding>,
+    /// Automatic optimizations run only while enabled (toggled by hotkey)
+    #[serde(default = "default_auto_optimizer_enabled")]
+    pub auto_optimizer_enabled: bool,
+
+    /// Automatic optimizations do not run while on battery
+    #[serde(default)]
+    pub skip_auto_opt_on_battery: bool,
+    /// Built-in or custom profile whose areas automatic runs use on battery
+    #[serde(default)]
+    pub profile_on_battery: Option<String>,
+
+    /// Minimum time between automatic runs of an area
+    #[serde(default)]
+    pub area_cooldowns: Vec<AreaCooldown>,
+    /// Seconds after a manual or hotkey run before the next one is accepted, 0 = off
+    #[serde(default = "default_manual_cooldown_secs")]
+    pub manual_cooldown_secs: u32,
+    /// System File Cache size limits, applied while their profile is active
+    #[serde(default)]
+    pub file_cache_limits: Vec<FileCacheLimit>,
+}
+
+fn default_auto_optimizer_enabled() -> bool {
+    true
+}
+
+fn default_usage_metrics() -> bool {
+    true
+}
+
+fn default_manual_cooldown_secs() -> u32 {
+    30
+}
+
+fn default_startup_delay_secs() -> u32 {
+    30
+}
+
+fn legacy_confirmed_areas() -> Areas {
+    Areas::AGGRESSIVE
+}
+
+/// Built-in profile by name (case-insensitive)
+fn built_in_profile(name: &str) -> Option<Profile> {
+    match name.trim().to_lowercase().as_str() {
+        "normal" => Some(Profile::Normal),
+        "balanced" => Some(Profile::Balanced),
+        "gaming" => Some(Profile::Gaming),
+        _ => None,
+    }
+}
+
+fn default_document_editor_processes() -> BTreeSet<String> {
+    crate::memory::document_guard::DEFAULT_DOCUMENT_EDITORS
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_skip_foreground_process() -> bool {
+    true
+}
+
+fn default_skip_audio_processes() -> bool {
+    true
+}
+
+fn default_notify_nothing_freed() -> bool {
+    true
+}
+
+fn default_idle_minutes() -> u32 {
+    5
+}
+
+fn default_sdk_rate_limit_per_minute() -> u32 {
+    2
+}
+
+fn default_widget_feed_interval_secs() -> u32 {
+    2
+}
+
+fn default_http_api_port() -> u16 {
+    crate::ipc::http_api::DEFAULT_PORT
+}
+
+fn default_timeline_interval_secs() -> u32 {
+    5
+}
+
+fn default_commit_warning_percent() -> u8 {
+    90
+}
+
+fn default_gentle_maintenance_interval_secs() -> u32 {
+    gentle_maintenance::DEFAULT_INTERVAL_SECS
+}
+
+fn default_area_settle_ms() -> u32 {
+    250
+}
+
+fn default_area_verify_retries() -> u8 {
+    1
+}
+
+fn default_measurement_delay_ms() -> u32 {
+    800
+}
+
+fn default_measurement_samples() -> u8 {
+    3
 }
 
 fn default_setup_completed() -> bool {
@@ -388,7 +858,7 @@ fn default_setup_completed() -> bool {
 }
 
 fn default_config_version() -> u32 {
-    2
+    4
 }
 
 fn default_main_color_light() -> String {
@@ -415,11 +885,29 @@ impl Default for Config {
         Self {
             always_on_top: false,
             minimize_to_tray: true,
+            destroy_webview_on_hide: false,
             close_after_opt: false,
             compact_mode: false,
             auto_opt_interval_hours: 1,
             auto_opt_free_threshold: 30,
-            auto_update: true,
+            auto_opt_only_when_idle: false,
+            idle_minutes: default_idle_minutes(),
+            locked_session_policy: LockedSessionPolicy::default(),
+            other_sessions_policy: OtherSessionsPolicy::default(),
+            busy_process_cpu_percent: 0,
+            allow_system_process_trim: false,
+            exclude_child_processes: false,
+            use_documented_apis_only: false,
+            area_settle_ms: default_area_settle_ms(),
+            area_verify_retries: default_area_verify_retries(),
+            measurement_delay_ms: default_measurement_delay_ms(),
+            measurement_samples: default_measurement_samples(),
+            benchmark_enabled: false,
+            adaptive_area_selection: false,
+            document_editor_processes: default_document_editor_processes(),
+            skip_foreground_process: default_skip_foreground_process(),
+            skip_audio_processes: default_skip_audio_processes(),
+            auto_update: false,
             font_size: 13.0,
             language: "en".to_string(),
             theme: "dark".to_string(),
@@ -430,16 +918,57 @@ impl Default for Config {
             memory_areas: default_areas,
             hotkey: "Ctrl+Alt+N".to_string(),
             process_exclusion_list: exclusions,
+            process_include_list: BTreeSet::new(),
+            strict_nt_errors: false,
+            gentle_maintenance_processes: BTreeSet::new(),
+            gentle_maintenance_interval_secs: default_gentle_maintenance_interval_secs(),
             run_priority: default_priority,
             run_on_startup: true,
+            startup_method: StartupMethod::default(),
+            startup_delay_secs: default_startup_delay_secs(),
+            start_minimized: false,
             show_opt_notifications: true,
+            notification_verbosity: ProfileNotifications::default(),
+            notify_nothing_freed: default_notify_nothing_freed(),
+            session_summary_notification: false,
             tray: TrayConfig::default(),
+            accessibility: AccessibilityOverrides::default(),
             request_elevation_on_startup: true,
             is_portable_install: false,
             config_version: default_config_version(),
             setup_completed: false,
             platform_detected: false,
             is_windows_10: false,
+            sdk_pipe_enabled: false,
+            sdk_allowed_clients: BTreeSet::new(),
+            sdk_rate_limit_per_minute: default_sdk_rate_limit_per_minute(),
+            widget_feed_enabled: false,
+            widget_feed_interval_secs: default_widget_feed_interval_secs(),
+            http_api_enabled: false,
+            http_api_port: default_http_api_port(),
+            http_api_token: String::new(),
+            timeline_interval_secs: default_timeline_interval_secs(),
+            timeline_persist: false,
+            usage_metrics: default_usage_metrics(),
+            commit_warning_percent: default_commit_warning_percent(),
+            free_ram_floor: None,
+            trim_strategy: None,
+            schedules: Vec::new(),
+            pressure_prediction: PressurePrediction::default(),
+            game_mode: GameMode::default(),
+            emergency_mode: EmergencyMode::default(),
+            toast: ToastOptions::default(),
+            temp_cleaner: TempCleanerOptions::default(),
+            custom_profiles: Vec::new(),
+            active_custom_profile: None,
+            confirmed_areas: Areas::empty(),
+            hotkeys: Vec::new(),
+            auto_optimizer_enabled: default_auto_optimizer_enabled(),
+            skip_auto_opt_on_battery: false,
+            profile_on_battery: None,
+            area_cooldowns: Vec::new(),
+            manual_cooldown_secs: default_manual_cooldown_secs(),
+            file_cache_limits: Vec::new(),
         }
     }
 }
@@ -479,6 +1008,8 @@ impl Config {
             self.auto_opt_free_threshold = 100;
         }
         // 0 is valid (disables auto-opt for low memory)
+        self.idle_minutes = self.idle_minutes.clamp(1, 240);
+        self.busy_process_cpu_percent = self.busy_process_cpu_percent.min(100);
         self.font_size = self.font_size.clamp(8.0, 24.0);
 
         const VALID_LANGUAGES: &[&str] = &["en", "it", "es", "fr", "pt", "de", "ar", "ja", "zh"];
@@ -502,19 +1033,343 @@ impl Config {
         }
 
         self.tray.validate();
+        if let Some(floor) = self.free_ram_floor.as_mut() {
+            floor.validate();
+        }
+        self.validate_schedules();
+        self.pressure_prediction.validate();
+        self.game_mode.validate();
+        self.emergency_mode.validate();
+        self.temp_cleaner.validate();
+        self.validate_custom_profiles();
+        self.validate_hotkey_bindings();
+        area_cooldown::validate_area_cooldowns(&mut self.area_cooldowns);
+        let mut file_cache_limits = std::mem::take(&mut self.file_cache_limits);
+        file_cache_limit::validate_file_cache_limits(&mut file_cache_limits, |name| {
+            self.profile_areas(name).is_some()
+        });
+        self.file_cache_limits = file_cache_limits;
+        if let Some(name) = &self.profile_on_battery {
+            if self.profile_areas(name).is_none() {
+                tracing::warn!("Battery profile '{}' no longer exists", name);
+                self.profile_on_battery = None;
+            }
+        }
+        self.confirmed_areas &= Areas::AGGRESSIVE;
+
+        // Security: Sanitize process exclusion and inclusion lists
+        self.process_exclusion_list =
+            Self::sanitize_process_set(&self.process_exclusion_list, "exclusion");
+        self.process_include_list =
+            Self::sanitize_process_set(&self.process_include_list, "inclusion");
+        self.gentle_maintenance_processes =
+            Self::sanitize_process_set(&self.gentle_maintenance_processes, "gentle maintenance");
+        self.gentle_maintenance_interval_secs = self.gentle_maintenance_interval_secs.clamp(
+            gentle_maintenance::MIN_INTERVAL_SECS,
+            gentle_maintenance::MAX_INTERVAL_SECS,
+        );
+        self.sdk_allowed_clients = Self::sanitize_client_paths(&self.sdk_allowed_clients);
+        self.document_editor_processes =
+            Self::sanitize_process_set(&self.document_editor_processes, "document editor");
+        self.sdk_rate_limit_per_minute = self.sdk_rate_limit_per_minute.clamp(1, 60);
+        self.widget_feed_interval_secs = self.widget_feed_interval_secs.clamp(1, 60);
+        self.http_api_port = self.http_api_port.max(crate::ipc::http_api::MIN_PORT);
+        self.http_api_token = crate::ipc::http_api::sanitize_token(&self.http_api_token);
+        self.timeline_interval_secs = self.timeline_interval_secs.clamp(1, 300);
+        if self.commit_warning_percent != 0 {
+            self.commit_warning_percent = self.commit_warning_percent.clamp(50, 99);
+        }
+        self.area_settle_ms = self.area_settle_ms.min(5000);
+        self.area_verify_retries = self.area_verify_retries.min(3);
+        self.measurement_delay_ms = self.measurement_delay_ms.min(10_000);
+        self.measurement_samples = self.measurement_samples.clamp(1, 9);
+        self.manual_cooldown_secs = self.manual_cooldown_secs.min(3600);
+        self.startup_delay_secs = self.startup_delay_secs.min(600);
 
-        // Security: Sanitize process exclusion list
-        let mut seen = BTreeSet::new();
-        self.process_exclusion_list = self
-            .process_exclusion_list
+        self.is_portable_install = PORTABLE.read().is_portable_mode();
+
+        // Platform flags come from the cached version service
+        #[cfg(windows)]
+        {
+            self.is_windows_10 = crate::os::win_version().is_windows_10();
+            self.platform_detected = true;
+        }
+
+        if self.memory_areas.is_empty() {
+            self.memory_areas = self.effective_areas();
+        }
+
+        // NOTE: run_priority is now independent from profile, so don't override it
+        // The user can set it manually and it won't be changed by profile changes
+    }
+
+    /// Drop schedule entries whose rule cannot be parsed
+    fn validate_schedules(&mut self) {
+        self.schedules.truncate(MAX_SCHEDULES);
+        self.schedules.retain_mut(|entry| {
+            entry.name = sanitize_string(&entry.name, 64);
+            match ScheduleSpec::parse(&entry.rule) {
+                Ok(_) => true,
+                Err(e) => {
+                    tracing::warn!("Dropping invalid schedule rule '{}': {}", entry.rule, e);
+                    false
+                }
+            }
+        });
+    }
+
+    fn validate_custom_profiles(&mut self) {
+        custom_profile::validate_custom_profiles(&mut self.custom_profiles, |list| {
+            Self::sanitize_process_set(list, "profile exclusion")
+        });
+
+        // The main hotkey wins over a profile hotkey bound to the same keys
+        let main_hotkey = self.hotkey.to_uppercase();
+        for profile in &mut self.custom_profiles {
+            if profile.hotkey.as_deref().map(str::to_uppercase) == Some(main_hotkey.clone()) {
+                tracing::warn!("Hotkey of profile '{}' conflicts with the main hotkey", profile.name);
+                profile.hotkey = None;
+            }
+        }
+
+        if let Some(name) = &self.active_custom_profile {
+            match self.custom_profiles.iter().find(|p| p.is_named(name)) {
+                Some(profile) => self.active_custom_profile = Some(profile.name.clone()),
+                None => {
+                    tracing::warn!("Active custom profile '{}' no longer exists", name);
+                    self.active_custom_profile = None;
+                }
+            }
+        }
+    }
+
+    fn validate_hotkey_bindings(&mut self) {
+        // The main hotkey and profile hotkeys win over an additional binding
+        let reserved: Vec<&str> = std::iter::once(self.hotkey.as_str())
+            .chain(self.custom_profiles.iter().filter_map(|p| p.hotkey.as_deref()))
+            .collect();
+        hotkey_binding::validate_hotkey_bindings(&mut self.hotkeys, &reserved);
+
+        let profiles = &self.custom_profiles;
+        self.hotkeys.retain(|binding| match &binding.action {
+            HotkeyAction::Optimize {
+                profile: Some(name),
+            } if !custom_profile::is_built_in_name(name)
+                && !profiles.iter().any(|p| p.is_named(name)) =>
+            {
+                tracing::warn!(
+                    "Dropping hotkey {}: unknown profile '{}'",
+                    binding.hotkey,
+                    name
+                );
+                false
+            }
+            _ => true,
+        });
+    }
+
+    /// Every hotkey to register: the main hotkey, custom profile hotkeys and
+    /// the additional bindings, in that order
+    pub fn hotkey_bindings(&self) -> Vec<HotkeyBinding> {
+        let mut bindings = Vec::with_capacity(1 + self.custom_profiles.len() + self.hotkeys.len());
+        if !self.hotkey.trim().is_empty() {
+            bindings.push(HotkeyBinding::new(
+                self.hotkey.clone(),
+                HotkeyAction::Optimize { profile: None },
+            ));
+        }
+        for profile in &self.custom_profiles {
+            if let Some(hotkey) = &profile.hotkey {
+                bindings.push(HotkeyBinding::new(
+                    hotkey.clone(),
+                    HotkeyAction::Optimize {
+                        profile: Some(profile.name.clone()),
+                    },
+                ));
+            }
+        }
+        bindings.extend(self.hotkeys.iter().cloned());
+        bindings
+    }
+
+    pub fn find_custom_profile(&self, name: &str) -> Option<&CustomProfile> {
+        self.custom_profiles.iter().find(|p| p.is_named(name))
+    }
+
+    /// Custom profile in use, if any
+    pub fn active_custom(&self) -> Option<&CustomProfile> {
+        self.active_custom_profile
+            .as_deref()
+            .and_then(|name| self.find_custom_profile(name))
+    }
+
+    /// Display name of the profile in use
+    pub fn active_profile_name(&self) -> String {
+        match self.active_custom() {
+            Some(custom) => custom.name.clone(),
+            None => format!("{:?}", self.profile),
+        }
+    }
+
+    /// Areas of the profile in use (custom or built-in)
+    pub fn effective_areas(&self) -> Areas {
+        match self.active_custom() {
+            Some(custom) => custom.areas,
+            None => self.profile.get_memory_areas(),
+        }
+    }
+
+    /// Activate a built-in or custom profile by name (case-insensitive)
+    ///
+    /// Updates the areas and the process priority like a profile change from
+    /// the UI. Returns false if no profile has that name.
+    pub fn select_profile(&mut self, name: &str) -> bool {
+        if let Some(custom) = self.find_custom_profile(name).cloned() {
+            self.active_custom_profile = Some(custom.name);
+            self.memory_areas = custom.areas;
+            self.run_priority = custom.priority;
+            return true;
+        }
+
+        let built_in = match built_in_profile(name) {
+            Some(profile) => profile,
+            None => return false,
+        };
+        self.active_custom_profile = None;
+        self.profile = built_in;
+        self.memory_areas = built_in.get_memory_areas();
+        self.run_priority = built_in.get_priority();
+        true
+    }
+
+    /// Enable or disable a single area in the area set in use
+    ///
+    /// With a custom profile active the flag is changed in that profile,
+    /// otherwise in `memory_areas`. Returns the resolved set and warnings
+    /// about the result, or an error if the change cannot be applied.
+    pub fn toggle_area(
+        &mut self,
+        area: Areas,
+        enabled: bool,
+    ) -> Result<(Areas, Vec<String>), String> {
+        let mut warnings = Vec::new();
+        if enabled && !crate::os::supported_areas(area).contains(area) {
+            return Err(format!("{} is not available on this Windows version", area));
+        }
+
+        let current = match self.active_custom() {
+            Some(custom) => custom.areas,
+            None => self.memory_areas,
+        };
+        let mut areas = current;
+        areas.set(area, enabled);
+        let areas = crate::os::supported_areas(areas);
+
+        match self.active_custom_profile.clone() {
+            Some(name) => {
+                if areas.is_empty() {
+                    return Err(format!("Profile '{}' needs at least one area", name));
+                }
+                if let Some(custom) = self
+                    .custom_profiles
+                    .iter_mut()
+                    .find(|p| p.is_named(&name))
+                {
+                    custom.areas = areas;
+                }
+            }
+            None if areas.is_empty() => warnings
+                .push("No areas selected: optimization will not free any memory".to_string()),
+            None => {}
+        }
+        self.memory_areas = areas;
+
+        if areas.contains(Areas::STANDBY_LIST | Areas::STANDBY_LIST_LOW) {
+            warnings.push(
+                "Low Priority Standby is already included in the Standby List".to_string(),
+            );
+        }
+        let unconfirmed = self.unconfirmed_aggressive(areas, false);
+        if !unconfirmed.is_empty() {
+            warnings.push(format!(
+                "Automatic optimizations skip {} until confirmed",
+                unconfirmed
+            ));
+        }
+
+        Ok((areas, warnings))
+    }
+
+    /// Aggressive areas in `areas` that automatic runs must not touch yet
+    ///
+    /// The Working Set only counts as aggressive when it trims every process,
+    /// i.e. when no include list restricts it.
+    pub fn unconfirmed_aggressive(&self, areas: Areas, targeted: bool) -> Areas {
+        let mut aggressive = Areas::AGGRESSIVE;
+        if targeted || !self.process_include_list.is_empty() {
+            aggressive.remove(Areas::WORKING_SET);
+        }
+        areas & aggressive & !self.confirmed_areas
+    }
+
+    /// Areas of a built-in or custom profile, `None` if no profile has that name
+    pub fn profile_areas(&self, name: &str) -> Option<Areas> {
+        match self.find_custom_profile(name) {
+            Some(custom) => Some(custom.areas),
+            None => built_in_profile(name).map(|p| p.get_memory_areas()),
+        }
+    }
+
+    /// Areas automatic runs use on battery, `None` to keep the active profile
+    pub fn battery_areas(&self) -> Option<Areas> {
+        self.profile_on_battery
+            .as_deref()
+            .and_then(|name| self.profile_areas(name))
+    }
+
+    /// Free RAM floor in effect (custom settings or the profile defaults)
+    pub fn effective_free_ram_floor(&self) -> FreeRamFloor {
+        self.free_ram_floor
+            .unwrap_or_else(|| FreeRamFloor::for_profile(self.profile))
+    }
+
+    /// Working Set trim strategy in effect: the active custom profile's, the
+    /// global setting, then the built-in profile default
+    pub fn effective_trim_strategy(&self) -> TrimStrategy {
+        self.active_custom()
+            .and_then(|custom| custom.trim_strategy)
+            .or(self.trim_strategy)
+            .unwrap_or_else(|| self.profile.trim_strategy())
+    }
+
+    /// Notification verbosity of the profile in use, custom profiles notify in detail
+    pub fn effective_notification_verbosity(&self) -> NotificationVerbosity {
+        if self.active_custom().is_some() {
+            return NotificationVerbosity::Detailed;
+        }
+        self.notification_verbosity.for_profile(self.profile)
+    }
+
+    /// System File Cache limit of the profile in use, if one is configured
+    pub fn effective_file_cache_limit(&self) -> Option<&FileCacheLimit> {
+        let name = self.active_profile_name().to_lowercase();
+        self.file_cache_limits
             .iter()
+            .find(|limit| limit.profile.to_lowercase() == name)
+    }
+
+    /// Sanitize a process name list, dropping empty entries, injection attempts
+    /// and case-insensitive duplicates
+    fn sanitize_process_set(list: &BTreeSet<String>, kind: &str) -> BTreeSet<String> {
+        let mut seen = BTreeSet::new();
+        list.iter()
             .filter_map(|s| {
                 let sanitized = sanitize_process_name(s);
                 let trimmed = sanitized.trim();
                 if !trimmed.is_empty() {
                     // Check for injection patterns
                     if contains_injection_patterns(trimmed) {
-                        tracing::warn!("Potential injection in process exclusion: {}", trimmed);
+                        tracing::warn!("Potential injection in process {}: {}", kind, trimmed);
                         None
                     } else {
                         let lower = trimmed.to_lowercase();
@@ -528,16 +1383,27 @@ impl Config {
                     None
                 }
             })
-            .collect();
-
-        self.is_portable_install = PORTABLE.read().is_portable();
-
-        if self.memory_areas.is_empty() {
-            self.memory_areas = self.profile.get_memory_areas();
-        }
+            .collect()
+    }
 
-        // NOTE: run_priority is now independent from profile, so don't override it
-        // The user can set it manually and it won't be changed by profile changes
+    /// Keep the SDK clients given as full executable paths, dropping bare
+    /// names and case-insensitive duplicates
+    fn sanitize_client_paths(list: &BTreeSet<String>) -> BTreeSet<String> {
+        let mut seen = BTreeSet::new();
+        list.iter()
+            .filter_map(|s| {
+                let trimmed = s.trim();
+                if !crate::ipc::protocol::is_valid_client_path(trimmed) {
+                    tracing::warn!(
+                        "Ignoring SDK client '{}': expected the full path of an executable",
+                        trimmed
+                    );
+                    return None;
+                }
+                seen.insert(crate::ipc::protocol::normalize_client_path(trimmed))
+                    .then(|| trimmed.to_string())
+            })
+            .collect()
     }
 
     fn load_installer_settings() -> Option<serde_json::Value> {
@@ -563,53 +1429,31 @@ impl Config {
     pub fn load() -> io::Result<Self> {
         let path = config_path();
 
-        // Try to migrate from old location if needed
-        if !path.exists() {
-            // Check if there's a config in exe directory (old location)
-            if let Ok(exe_path) = std::env::current_exe() {
-                if let Some(exe_dir) = exe_path.parent() {
-                    let old_config = exe_dir.join("config.json");
-                    if old_config.exists() && old_config != path {
-                        tracing::info!(
-                            "Migrating config from {} to {}",
-                            old_config.display(),
-                            path.display()
-                        );
-
-                        // Copy old config to new location
-                        if let Err(e) = fs::copy(&old_config, &path) {
-                            tracing::warn!("Failed to migrate config: {}", e);
-                        } else {
-                            // Rename old one for backup
-                            if let Err(e) = fs::rename(&old_config, exe_dir.join("config.json.old"))
-                            {
-                                tracing::debug!("Failed to rename old config for backup: {}", e);
-                            }
-                        }
-                    }
+        let mut cfg = if let Some((mut c, source)) = journal::read::<Self>(&path) {
+            match source {
+                journal::Source::Main => {}
+                journal::Source::Journal => {
+                    tracing::info!("Recovered config from an interrupted save");
                 }
-            }
-        }
-
-        let mut cfg = if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => match serde_json::from_str::<Self>(&content) {
-                    Ok(mut c) => {
-                        c.migrate_if_needed();
-                        c
-                    }
-                    Err(e) => {
-                        eprintln!("Failed to parse config: {}. Using defaults.", e);
-                        let backup_path = path.with_extension("json.bak");
-                        let _ = fs::copy(&path, backup_path);
-                        Self::default()
-                    }
-                },
-                Err(e) => {
-                    eprintln!("Failed to read config: {}. Using defaults.", e);
-                    Self::default()
+                journal::Source::LastGood => {
+                    let msg = format!(
+                        "Config file was corrupt, restored the last-known-good copy (corrupt file kept as {})",
+                        journal::corrupt_path(&path).display()
+                    );
+                    tracing::warn!("{}", msg);
+                    report(Subsystem::Config, msg);
                 }
             }
+            c.migrate_if_needed();
+            c
+        } else if path.exists() {
+            let msg = format!(
+                "Config file was corrupt and no last-known-good copy exists, using defaults (corrupt file kept as {})",
+                journal::corrupt_path(&path).display()
+            );
+            tracing::error!("{}", msg);
+            report(Subsystem::Config, msg);
+            Self::default()
         } else {
             let mut default = Self::default();
             // FIX: Prova a caricare tutte le impostazioni dall'installer se esiste
@@ -728,83 +1572,33 @@ impl Config {
             }
         };
 
-        // Fallback 4: Atomic save with retry and backup
-        let temp_path = path.with_extension("tmp");
-        let backup_path = path.with_extension("json.bak");
-
-        // Create backup of existing file if present
-        if path.exists() {
-            if let Err(e) = fs::copy(&path, &backup_path) {
-                tracing::warn!("Failed to create backup: {:?}", e);
-                // Don't block save if backup fails
-            }
-        }
-
-        // Retry up to 3 times to write temporary file
-        let mut last_error = None;
-        for attempt in 1..=3 {
-            match fs::write(&temp_path, &content) {
-                Ok(_) => break,
-                Err(e) => {
-                    let error_msg = format!("{}", e);
-                    last_error = Some(e);
-                    tracing::warn!(
-                        "Failed to write temp config (attempt {}): {}",
-                        attempt,
-                        error_msg
-                    );
-                    if attempt < 3 {
-                        std::thread::sleep(std::time::Duration::from_millis(50 * attempt as u64));
-                    }
-                }
-            }
-        }
-
-        if let Some(e) = last_error.take() {
-            tracing::error!(
-                "Failed to write config after retries, restoring from backup if available"
-            );
-            // Restore backup if available
-            if backup_path.exists() && path.exists() {
-                let _ = fs::copy(&backup_path, &path);
-            }
-            return Err(e);
-        }
-
-        // Fallback 5: Atomic rename with retry
-        for attempt in 1..=3 {
-            match fs::rename(&temp_path, &path) {
-                Ok(_) => {
+        // Fallback 4: Journaled write with retry (journal + fsync, then swap)
+        let mut attempt = 1;
+        loop {
+            match journal::write::<Self>(&path, &content) {
+                Ok(()) => {
+                    live_reload::mark_synced(&content);
                     tracing::debug!("Config saved successfully to: {}", path.display());
-                    // Remove old backup if everything is ok
-                    if backup_path.exists() {
-                        let _ = fs::remove_file(&backup_path);
-                    }
                     return Ok(());
                 }
+                Err(e) if attempt < 3 => {
+                    tracing::warn!("Failed to write config (attempt {}): {}", attempt, e);
+                    std::thread::sleep(std::time::Duration::from_millis(50 * attempt as u64));
+                    attempt += 1;
+                }
                 Err(e) => {
-                    tracing::warn!(
-                        "Failed to rename temp config (attempt {}): {:?}",
-                        attempt,
-                        e
-                    );
-                    if attempt < 3 {
-                        std::thread::sleep(std::time::Duration::from_millis(50 * attempt as u64));
-                    } else {
-                        // Ultimo tentativo fallito, ripristina backup
-                        if backup_path.exists() && path.exists() {
-                            let _ = fs::copy(&backup_path, &path);
-                        }
-                        return Err(e);
-                    }
+                    tracing::error!("Failed to write config after retries: {}", e);
+                    return Err(e);
                 }
             }
         }
-
-        Ok(())
     }
 
+    /// Exclusions in effect: the active custom profile may replace the global list
     pub fn process_exclusion_list_lower(&self) -> Vec<String> {
+        if let Some(list) = self.active_custom().and_then(|p| p.exclusion_list_lower()) {
+            return list;
+        }
         self.process_exclusion_list
             .iter()
             .map(|s| s.trim().to_lowercase())
@@ -812,10 +1606,41 @@ impl Config {
             .collect()
     }
 
+    pub fn process_include_list_lower(&self) -> Vec<String> {
+        self.process_include_list
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    pub fn document_editor_processes_lower(&self) -> Vec<String> {
+        self.document_editor_processes
+            .iter()
+            .map(|s| s.trim().to_lowercase())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
+
+    /// Processes in use that the full Working Set trim leaves alone
+    pub fn activity_guard(&self) -> crate::memory::activity::ActivityGuard {
+        crate::memory::activity::ActivityGuard {
+            document_editors: self.document_editor_processes_lower(),
+            foreground: self.skip_foreground_process,
+            audio: self.skip_audio_processes,
+        }
+    }
+
     fn migrate_if_needed(&mut self) {
         if self.config_version < 2 {
             self.migrate_v1_to_v2();
         }
+        if self.config_version < 3 {
+            self.migrate_v2_to_v3();
+        }
+        if self.config_version < 4 {
+            self.migrate_v3_to_v4();
+        }
     }
 
     fn migrate_v1_to_v2(&mut self) {
@@ -827,4 +1652,27 @@ impl Config {
 
         self.config_version = 2;
     }
+
+    fn migrate_v2_to_v3(&mut self) {
+        // auto_update used to default to on while doing nothing; update
+        // checks are opt-in now that they exist
+        self.auto_update = false;
+
+        self.config_version = 3;
+    }
+
+    fn migrate_v3_to_v4(&mut self) {
+        // The Modified File Cache area used to trim the memory compression
+        // store as well, keep doing that through the new area
+        if self.memory_areas.contains(Areas::MODIFIED_FILE_CACHE) {
+            self.memory_areas |= Areas::MEMORY_COMPRESSION;
+        }
+        for profile in &mut self.custom_profiles {
+            if profile.areas.contains(Areas::MODIFIED_FILE_CACHE) {
+                profile.areas |= Areas::MEMORY_COMPRESSION;
+            }
+        }
+
+        self.config_version = 4;
+    }
 }
@@ -0,0 +1,430 @@
+/// Shareable optimization presets (.tmcpreset)
+///
+/// A preset is a named set of memory areas, Working Set trim strategy and
+/// exclusions meant to be shared ("Tarkov preset", "DaVinci Resolve preset").
+/// Imported presets are kept in the `presets` folder of the data directory;
+/// applying one turns it into a custom profile of the same name and activates
+/// it. Double-clicking a .tmcpreset file imports and applies it (see
+/// `cli::runner`).
+use super::{Config, CustomProfile};
+use crate::memory::types::{Areas, TrimStrategy};
+use crate::security::{contains_injection_patterns, sanitize_process_name, sanitize_string};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PRESET_FILE_EXTENSION: &str = "tmcpreset";
+const PRESET_FORMAT_VERSION: u32 = 1;
+const MAX_PRESET_FILE_SIZE: u64 = 64 * 1024;
+/// Same limit as custom profile names, the preset becomes one
+const MAX_PRESET_NAME_LEN: usize = 32;
+const MAX_PRESET_DESCRIPTION_LEN: usize = 256;
+const MAX_PRESET_EXCLUSIONS: usize = 256;
+const MAX_STORED_PRESETS: usize = 100;
+const PRESETS_DIR: &str = "presets";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetFile {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub areas: Areas,
+    /// `None` keeps the global trim strategy
+    #[serde(default)]
+    pub trim_strategy: Option<TrimStrategy>,
+    /// Replaces the global exclusion list while the preset is active
+    #[serde(default)]
+    pub exclusions: BTreeSet<String>,
+    #[serde(default)]
+    pub app_version: String,
+}
+
+fn default_format_version() -> u32 {
+    PRESET_FORMAT_VERSION
+}
+
+/// A preset stored in the data directory, as listed to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetSummary {
+    pub name: String,
+    pub description: String,
+    pub areas: Areas,
+    pub trim_strategy: Option<TrimStrategy>,
+    pub exclusions: usize,
+}
+
+/// Outcome of a preset import, returned to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetImportReport {
+    pub name: String,
+    pub applied_areas: Areas,
+    pub dropped_areas: Vec<String>,
+    pub warnings: Vec<String>,
+    /// The preset is now the active custom profile
+    pub applied: bool,
+}
+
+impl PresetFile {
+    /// Build a preset from a custom profile, or from the profile in use when
+    /// `profile` is `None`
+    pub fn from_config(
+        cfg: &Config,
+        name: &str,
+        description: &str,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        let source = match profile {
+            Some(profile) => Some(
+                cfg.find_custom_profile(profile)
+                    .with_context(|| format!("Unknown custom profile '{}'", profile))?,
+            ),
+            None => cfg.active_custom(),
+        };
+
+        let (areas, trim_strategy, exclusions) = match source {
+            Some(custom) => (
+                custom.areas,
+                custom.trim_strategy.or(cfg.trim_strategy),
+                custom
+                    .exclusions
+                    .clone()
+                    .unwrap_or_else(|| cfg.process_exclusion_list.clone()),
+            ),
+            None => (
+                cfg.memory_areas,
+                Some(cfg.effective_trim_strategy()),
+                cfg.process_exclusion_list.clone(),
+            ),
+        };
+
+        Ok(Self {
+            format_version: PRESET_FORMAT_VERSION,
+            name: sanitize_string(name.trim(), MAX_PRESET_NAME_LEN),
+            description: sanitize_string(description, MAX_PRESET_DESCRIPTION_LEN),
+            areas,
+            trim_strategy,
+            exclusions,
+            app_version: super::app_info::VERSION.to_string(),
+        })
+    }
+
+    /// Write the preset to disk, adding the .tmcpreset extension if missing
+    pub fn save_to(&self, path: &Path) -> Result<PathBuf> {
+        let path = with_preset_extension(path);
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize preset")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write preset to {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Read and parse a preset file (validation is done separately)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Cannot access preset file {}", path.display()))?;
+        if metadata.len() > MAX_PRESET_FILE_SIZE {
+            bail!("Preset file is too large ({} bytes)", metadata.len());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read preset file {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    fn parse(content: &str) -> Result<Self> {
+        let preset: Self = serde_json::from_str(content).context("Invalid preset file")?;
+        if preset.format_version > PRESET_FORMAT_VERSION {
+            bail!(
+                "Preset format version {} is newer than supported ({})",
+                preset.format_version,
+                PRESET_FORMAT_VERSION
+            );
+        }
+        Ok(preset)
+    }
+
+    /// Sanitize imported values and drop areas unsupported on this Windows build
+    ///
+    /// Returns the names of the dropped areas and a list of warnings, or an
+    /// error if no usable area is left.
+    pub fn validate(&mut self) -> Result<(Vec<String>, Vec<String>)> {
+        let mut warnings = Vec::new();
+
+        self.name = sanitize_string(self.name.trim(), MAX_PRESET_NAME_LEN)
+            .trim()
+            .to_string();
+        if self.name.is_empty() || contains_injection_patterns(&self.name) {
+            self.name = "Imported preset".to_string();
+        } else if super::custom_profile::is_built_in_name(&self.name) {
+            // Built-in profile names cannot be reused by a custom profile
+            warnings.push(format!("Renamed to '{} preset'", self.name));
+            self.name = format!("{} preset", self.name);
+        }
+        self.description = sanitize_string(&self.description, MAX_PRESET_DESCRIPTION_LEN);
+
+        // Unknown bits are silently discarded
+        let known = Areas::from_bits_truncate(self.areas.bits());
+        if known != self.areas {
+            warnings.push("Unknown memory areas were ignored".to_string());
+        }
+
+        // Capability downgrade
+        let supported = crate::os::supported_areas(known);
+        let dropped_names: Vec<String> = (known & !supported)
+            .get_names()
+            .iter()
+            .map(|n| n.to_string())
+            .collect();
+        self.areas = supported;
+        if self.areas.is_empty() {
+            bail!(
+                "Preset '{}' has no area supported on this system",
+                self.name
+            );
+        }
+
+        let before = self.exclusions.len();
+        // Process names are lowercased, case variants collapse into one entry
+        self.exclusions = self
+            .exclusions
+            .iter()
+            .map(|p| sanitize_process_name(p))
+            .filter(|p| !p.is_empty() && !contains_injection_patterns(p))
+            .take(MAX_PRESET_EXCLUSIONS)
+            .collect();
+        if self.exclusions.len() < before {
+            warnings.push(format!(
+                "{} invalid exclusions were ignored",
+                before - self.exclusions.len()
+            ));
+        }
+
+        Ok((dropped_names, warnings))
+    }
+
+    /// Custom profile for the preset; an existing profile of the same name
+    /// keeps its hotkey and process priority
+    pub fn to_custom_profile(&self, existing: Option<&CustomProfile>) -> CustomProfile {
+        CustomProfile {
+            name: self.name.clone(),
+            areas: self.areas,
+            priority: existing.map(|p| p.priority).unwrap_or_default(),
+            exclusions: Some(self.exclusions.clone()),
+            hotkey: existing.and_then(|p| p.hotkey.clone()),
+            trim_strategy: self.trim_strategy,
+        }
+    }
+
+    /// Add or replace the custom profile of the (validated) preset and activate it
+    pub fn apply_to(&self, cfg: &mut Config) -> Result<()> {
+        let profile = self.to_custom_profile(cfg.find_custom_profile(&self.name));
+        match cfg
+            .custom_profiles
+            .iter_mut()
+            .find(|p| p.is_named(&self.name))
+        {
+            Some(existing) => *existing = profile,
+            None => {
+                if cfg.custom_profiles.len() >= super::custom_profile::MAX_CUSTOM_PROFILES {
+                    bail!(
+                        "At most {} custom profiles are allowed, delete one to apply '{}'",
+                        super::custom_profile::MAX_CUSTOM_PROFILES,
+                        self.name
+                    );
+                }
+                cfg.custom_profiles.push(profile);
+            }
+        }
+        cfg.select_profile(&self.name);
+        Ok(())
+    }
+
+    /// Keep the (validated) preset in the data directory, replacing one of the same name
+    pub fn store(&self) -> Result<PathBuf> {
+        let dir = presets_dir();
+        fs::create_dir_all(&dir).with_context(|| format!("Cannot create {}", dir.display()))?;
+
+        let existing = find_stored(&self.name);
+        if existing.is_none() && stored_paths().len() >= MAX_STORED_PRESETS {
+            bail!("At most {} presets can be stored", MAX_STORED_PRESETS);
+        }
+        let path = existing
+            .map(|(path, _)| path)
+            .unwrap_or_else(|| unique_path(&dir, &file_stem(&self.name)));
+        self.save_to(&path)
+    }
+
+    fn summary(&self) -> PresetSummary {
+        PresetSummary {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            areas: self.areas,
+            trim_strategy: self.trim_strategy,
+            exclusions: self.exclusions.len(),
+        }
+    }
+}
+
+/// Validate a preset file, store it and apply it when `apply` is set
+pub fn import_preset(path: &Path, cfg: &mut Config, apply: bool) -> Result<PresetImportReport> {
+    let mut preset = PresetFile::load_from(path)?;
+    let (dropped_areas, warnings) = preset.validate()?;
+    if !dropped_areas.is_empty() {
+        tracing::warn!(
+            "Preset '{}' contains areas unsupported on this system: {}",
+            preset.name,
+            dropped_areas.join(", ")
+        );
+    }
+
+    let stored = preset.store()?;
+    tracing::info!("✓ Preset '{}' stored in {}", preset.name, stored.display());
+    if apply {
+        preset.apply_to(cfg)?;
+    }
+
+    Ok(PresetImportReport {
+        name: preset.name,
+        applied_areas: preset.areas,
+        dropped_areas,
+        warnings,
+        applied: apply,
+    })
+}
+
+/// Stored presets, sorted by name
+pub fn list_presets() -> Vec<PresetSummary> {
+    let mut presets: Vec<PresetSummary> = stored_paths()
+        .iter()
+        .filter_map(|path| load_stored(path).map(|p| p.summary()))
+        .collect();
+    presets.sort_by_key(|p| p.name.to_lowercase());
+    presets
+}
+
+/// Stored preset by name (case-insensitive)
+pub fn stored_preset(name: &str) -> Result<PresetFile> {
+    find_stored(name)
+        .map(|(_, preset)| preset)
+        .with_context(|| format!("Unknown preset '{}'", name))
+}
+
+/// Remove a stored preset; a custom profile created from it is kept
+pub fn delete_preset(name: &str) -> Result<()> {
+    let (path, _) = find_stored(name).with_context(|| format!("Unknown preset '{}'", name))?;
+    fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))
+}
+
+fn presets_dir() -> PathBuf {
+    super::get_portable_detector().data_dir().join(PRESETS_DIR)
+}
+
+fn stored_paths() -> Vec<PathBuf> {
+    let entries = match fs::read_dir(presets_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| e.eq_ignore_ascii_case(PRESET_FILE_EXTENSION))
+        })
+        .collect()
+}
+
+/// A stored file that no longer parses is skipped
+fn load_stored(path: &Path) -> Option<PresetFile> {
+    match PresetFile::load_from(path) {
+        Ok(preset) => Some(preset),
+        Err(e) => {
+            tracing::warn!("Skipping preset {}: {:#}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn find_stored(name: &str) -> Option<(PathBuf, PresetFile)> {
+    stored_paths().into_iter().find_map(|path| {
+        load_stored(&path)
+            .filter(|p| p.name.eq_ignore_ascii_case(name.trim()))
+            .map(|p| (path, p))
+    })
+}
+
+/// File name for a preset name, never leaving the presets folder
+fn file_stem(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    match cleaned.trim_matches('_') {
+        "" => "preset".to_string(),
+        stem => stem.to_string(),
+    }
+}
+
+/// Names differing only in punctuation map to the same stem
+fn unique_path(dir: &Path, stem: &str) -> PathBuf {
+    let mut path = dir.join(format!("{}.{}", stem, PRESET_FILE_EXTENSION));
+    let mut n = 2;
+    while path.exists() {
+        path = dir.join(format!("{}-{}.{}", stem, n, PRESET_FILE_EXTENSION));
+        n += 1;
+    }
+    path
+}
+
+fn with_preset_extension(path: &Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case(PRESET_FILE_EXTENSION) => path.to_path_buf(),
+        _ => path.with_extension(PRESET_FILE_EXTENSION),
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_validate() {
+        let newer = r#"{"format_version": 2, "name": "x", "areas": 128}"#;
+        assert!(PresetFile::parse(newer).is_err());
+
+        let content = r#"{
+            "name": "Gaming",
+            "areas": 128,
+            "trim_strategy": "gentle",
+            "exclusions": ["Resolve.exe", "resolve.exe", ""]
+        }"#;
+        let mut preset = PresetFile::parse(content).unwrap();
+        let (_, warnings) = preset.validate().unwrap();
+        assert_eq!(preset.name, "Gaming preset");
+        assert_eq!(preset.trim_strategy, Some(TrimStrategy::Gentle));
+        assert_eq!(preset.exclusions.len(), 1);
+        assert_eq!(warnings.len(), 2);
+
+        let profile = preset.to_custom_profile(None);
+        assert_eq!(profile.exclusions, Some(preset.exclusions.clone()));
+        assert_eq!(profile.hotkey, None);
+    }
+
+    #[test]
+    fn test_file_stem_stays_in_folder() {
+        assert_eq!(file_stem("Tarkov preset"), "Tarkov_preset");
+        assert_eq!(file_stem("../../evil"), "evil");
+        assert_eq!(file_stem("..."), "preset");
+    }
+}
@@ -0,0 +1,200 @@
+/// Shareable profile files (.tmcprofile)
+///
+/// A profile file is a small JSON document containing the optimization settings
+/// of a profile (areas, intensity, schedule overrides and a subset of exclusions)
+/// that can be exported and imported on another machine.
+use super::{Config, Profile};
+use crate::memory::types::Areas;
+use crate::security::{contains_injection_patterns, sanitize_process_name, sanitize_string};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const PROFILE_FILE_EXTENSION: &str = "tmcprofile";
+const PROFILE_FORMAT_VERSION: u32 = 1;
+const MAX_PROFILE_FILE_SIZE: u64 = 64 * 1024;
+const MAX_PROFILE_NAME_LEN: usize = 64;
+const MAX_PROFILE_EXCLUSIONS: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileFile {
+    #[serde(default = "default_format_version")]
+    pub format_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Intensity of the profile (Normal/Balanced/Gaming)
+    pub base_profile: Profile,
+    pub memory_areas: Areas,
+    #[serde(default)]
+    pub auto_opt_interval_hours: Option<u32>,
+    #[serde(default)]
+    pub auto_opt_free_threshold: Option<u8>,
+    #[serde(default)]
+    pub process_exclusion_list: BTreeSet<String>,
+    #[serde(default)]
+    pub app_version: String,
+}
+
+fn default_format_version() -> u32 {
+    PROFILE_FORMAT_VERSION
+}
+
+/// Outcome of a profile import, returned to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileImportReport {
+    pub name: String,
+    pub applied_areas: Areas,
+    pub dropped_areas: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl ProfileFile {
+    /// Build a profile file from the current configuration
+    ///
+    /// If `exclusions` is provided only those entries (that are also in the
+    /// configured exclusion list) are exported.
+    pub fn from_config(cfg: &Config, name: &str, exclusions: Option<&[String]>) -> Self {
+        let process_exclusion_list = match exclusions {
+            Some(subset) => {
+                let wanted: BTreeSet<String> = subset.iter().map(|s| s.to_lowercase()).collect();
+                cfg.process_exclusion_list
+                    .iter()
+                    .filter(|p| wanted.contains(&p.to_lowercase()))
+                    .cloned()
+                    .collect()
+            }
+            None => cfg.process_exclusion_list.clone(),
+        };
+
+        Self {
+            format_version: PROFILE_FORMAT_VERSION,
+            name: sanitize_string(name, MAX_PROFILE_NAME_LEN),
+            description: String::new(),
+            base_profile: cfg.profile,
+            memory_areas: cfg.memory_areas,
+            auto_opt_interval_hours: Some(cfg.auto_opt_interval_hours),
+            auto_opt_free_threshold: Some(cfg.auto_opt_free_threshold),
+            process_exclusion_list,
+            app_version: super::app_info::VERSION.to_string(),
+        }
+    }
+
+    /// Write the profile to disk, adding the .tmcprofile extension if missing
+    pub fn save_to(&self, path: &Path) -> Result<PathBuf> {
+        let path = with_profile_extension(path);
+        let content = serde_json::to_string_pretty(self).context("Failed to serialize profile")?;
+        fs::write(&path, content)
+            .with_context(|| format!("Failed to write profile to {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Read and parse a profile file (validation is done separately)
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let metadata = fs::metadata(path)
+            .with_context(|| format!("Cannot access profile file {}", path.display()))?;
+        if metadata.len() > MAX_PROFILE_FILE_SIZE {
+            bail!("Profile file is too large ({} bytes)", metadata.len());
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read profile file {}", path.display()))?;
+        let profile: Self = serde_json::from_str(&content).context("Invalid profile file")?;
+
+        if profile.format_version > PROFILE_FORMAT_VERSION {
+            bail!(
+                "Profile format version {} is newer than supported ({})",
+                profile.format_version,
+                PROFILE_FORMAT_VERSION
+            );
+        }
+
+        Ok(profile)
+    }
+
+    /// Sanitize imported values and drop areas unsupported on this Windows build
+    ///
+    /// Returns the names of the dropped areas and a list of warnings.
+    pub fn validate(&mut self) -> (Vec<String>, Vec<String>) {
+        let mut warnings = Vec::new();
+
+        self.name = sanitize_string(&self.name, MAX_PROFILE_NAME_LEN);
+        if self.name.trim().is_empty() || contains_injection_patterns(&self.name) {
+            self.name = "Imported profile".to_string();
+        }
+        self.description = sanitize_string(&self.description, 256);
+
+        // Unknown bits are silently discarded
+        let known = Areas::from_bits_truncate(self.memory_areas.bits());
+        if known != self.memory_areas {
+            warnings.push("Unknown memory areas were ignored".to_string());
+        }
+
+        // Capability downgrade
+        let supported = crate::os::supported_areas(known);
+        let dropped = known & !supported;
+        let dropped_names: Vec<String> =
+            dropped.get_names().iter().map(|n| n.to_string()).collect();
+        self.memory_areas = supported;
+        if self.memory_areas.is_empty() {
+            warnings.push("No supported areas left, using profile defaults".to_string());
+            self.memory_areas = self.base_profile.get_memory_areas();
+        }
+
+        if let Some(hours) = self.auto_opt_interval_hours {
+            if hours > 24 {
+                warnings.push(format!("Interval of {} hours clamped to 24", hours));
+                self.auto_opt_interval_hours = Some(24);
+            }
+        }
+        if let Some(threshold) = self.auto_opt_free_threshold {
+            if threshold > 100 {
+                self.auto_opt_free_threshold = Some(100);
+            }
+        }
+
+        let before = self.process_exclusion_list.len();
+        self.process_exclusion_list = self
+            .process_exclusion_list
+            .iter()
+            .map(|p| sanitize_process_name(p))
+            .filter(|p| !p.is_empty() && !contains_injection_patterns(p))
+            .take(MAX_PROFILE_EXCLUSIONS)
+            .collect();
+        if self.process_exclusion_list.len() < before {
+            warnings.push(format!(
+                "{} invalid exclusions were ignored",
+                before - self.process_exclusion_list.len()
+            ));
+        }
+
+        (dropped_names, warnings)
+    }
+
+    /// Apply the (validated) profile to the configuration
+    ///
+    /// Exclusions are merged with the existing ones, never removed.
+    pub fn apply_to(&self, cfg: &mut Config) {
+        cfg.profile = self.base_profile;
+        cfg.active_custom_profile = None;
+        cfg.memory_areas = self.memory_areas;
+        cfg.run_priority = self.base_profile.get_priority();
+        if let Some(hours) = self.auto_opt_interval_hours {
+            cfg.auto_opt_interval_hours = hours;
+        }
+        if let Some(threshold) = self.auto_opt_free_threshold {
+            cfg.auto_opt_free_threshold = threshold;
+        }
+        cfg.process_exclusion_list
+            .extend(self.process_exclusion_list.iter().cloned());
+    }
+}
+
+fn with_profile_extension(path: &Path) -> PathBuf {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case(PROFILE_FILE_EXTENSION) => path.to_path_buf(),
+        _ => path.with_extension(PROFILE_FILE_EXTENSION),
+    }
+}
@@ -0,0 +1,86 @@
+/// Free RAM floor protection
+///
+/// Automatic optimizations should not evict caches when the system already has
+/// plenty of free memory. The floor is off unless `enabled` is set, and the
+/// engine evaluates it between areas: the first area always runs.
+use super::Profile;
+use serde::{Deserialize, Serialize};
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreeRamFloor {
+    pub enabled: bool,
+    /// Never trim working sets while free RAM is above this percentage (0 = off)
+    pub skip_working_set_above_percent: u8,
+    /// Stop purging further areas once free RAM exceeds this many GB (0 = off)
+    pub stop_above_free_gb: f32,
+}
+
+impl Default for FreeRamFloor {
+    fn default() -> Self {
+        Self::for_profile(Profile::Balanced)
+    }
+}
+
+/// What the engine should do with the next area
+#[derive(Debug, Clone, PartialEq)]
+pub enum FloorDecision {
+    Proceed,
+    /// Skip this area only
+    Skip(String),
+    /// Skip this and all remaining areas
+    Stop(String),
+}
+
+impl FreeRamFloor {
+    /// Thresholds tuned per profile (lighter profiles back off earlier), disabled
+    pub fn for_profile(profile: Profile) -> Self {
+        let (percent, gb) = match profile {
+            Profile::Normal => (40, 4.0),
+            Profile::Balanced => (50, 6.0),
+            Profile::Gaming => (60, 8.0),
+        };
+        Self {
+            enabled: false,
+            skip_working_set_above_percent: percent,
+            stop_above_free_gb: gb,
+        }
+    }
+
+    pub fn validate(&mut self) {
+        self.skip_working_set_above_percent = self.skip_working_set_above_percent.min(100);
+        if !self.stop_above_free_gb.is_finite() || self.stop_above_free_gb < 0.0 {
+            self.stop_above_free_gb = 0.0;
+        }
+        self.stop_above_free_gb = self.stop_above_free_gb.min(1024.0);
+    }
+
+    /// Evaluate the floor against the current free memory
+    pub fn check(&self, is_working_set: bool, free_percent: u8, free_bytes: u64) -> FloorDecision {
+        if !self.enabled {
+            return FloorDecision::Proceed;
+        }
+
+        let free_gb = free_bytes as f64 / BYTES_PER_GB;
+        if self.stop_above_free_gb > 0.0 && free_gb > self.stop_above_free_gb as f64 {
+            return FloorDecision::Stop(format!(
+                "Free RAM {:.1} GB above floor of {:.1} GB",
+                free_gb, self.stop_above_free_gb
+            ));
+        }
+
+        if is_working_set
+            && self.skip_working_set_above_percent > 0
+            && free_percent > self.skip_working_set_above_percent
+        {
+            return FloorDecision::Skip(format!(
+                "Free RAM {}% above {}%",
+                free_percent, self.skip_working_set_above_percent
+            ));
+        }
+
+        FloorDecision::Proceed
+    }
+}
@@ -0,0 +1,378 @@
+/// JSON Schema of config.json
+///
+/// The schema (`config.schema.json`, draft 2020-12) is embedded in the binary
+/// and written to the data directory on startup, so editors and scripts can
+/// check a hand-edited config.json before the app silently clamps or drops
+/// bad values. `validate_config_file` checks a file against it with a small
+/// built-in validator covering the keywords the schema uses; `oneOf` is
+/// treated like `anyOf` because its branches are exclusive.
+///
+/// Unknown settings are reported as warnings, not errors: the app ignores
+/// them when loading.
+use crate::auto_optimizer::schedule::ScheduleSpec;
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const CONFIG_SCHEMA: &str = include_str!("config.schema.json");
+pub const SCHEMA_FILE_NAME: &str = "config.schema.json";
+const MAX_VALIDATED_FILE_SIZE: u64 = 1024 * 1024;
+
+static SCHEMA: Lazy<Value> = Lazy::new(|| serde_json::from_str(CONFIG_SCHEMA).unwrap_or_default());
+
+/// A problem found in a config file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaIssue {
+    /// JSON Pointer to the offending value, empty for the whole document
+    pub path: String,
+    pub message: String,
+}
+
+/// Outcome of a config file validation, returned to the UI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigValidationReport {
+    pub valid: bool,
+    pub errors: Vec<SchemaIssue>,
+    /// Unknown settings, ignored by the app
+    pub warnings: Vec<SchemaIssue>,
+}
+
+/// Write the schema to the data directory if it is missing or outdated
+pub fn install_schema() {
+    let path = schema_path();
+    if fs::read_to_string(&path).is_ok_and(|current| current == CONFIG_SCHEMA) {
+        return;
+    }
+    match fs::write(&path, CONFIG_SCHEMA) {
+        Ok(()) => tracing::debug!("Config schema written to {}", path.display()),
+        Err(e) => tracing::warn!("Failed to write config schema to {}: {}", path.display(), e),
+    }
+}
+
+pub fn schema_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(SCHEMA_FILE_NAME)
+}
+
+/// Validate a config.json, or the configuration inside a .tmcconfig file
+pub fn validate_config_file(path: &Path) -> Result<ConfigValidationReport> {
+    let metadata =
+        fs::metadata(path).with_context(|| format!("Cannot access {}", path.display()))?;
+    if metadata.len() > MAX_VALIDATED_FILE_SIZE {
+        bail!("File is too large ({} bytes)", metadata.len());
+    }
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(validate_config_str(&content))
+}
+
+pub fn validate_config_str(content: &str) -> ConfigValidationReport {
+    match serde_json::from_str::<Value>(content) {
+        Ok(value) => match value.get("config") {
+            // Settings file written by "Export settings"
+            Some(inner) if value.get("format_version").is_some() => {
+                let mut report = validate_config_value(inner);
+                for issue in report.errors.iter_mut().chain(report.warnings.iter_mut()) {
+                    issue.path = format!("/config{}", issue.path);
+                }
+                report
+            }
+            _ => validate_config_value(&value),
+        },
+        Err(e) => ConfigValidationReport {
+            valid: false,
+            errors: vec![SchemaIssue {
+                path: String::new(),
+                message: format!("Invalid JSON: {}", e),
+            }],
+            warnings: Vec::new(),
+        },
+    }
+}
+
+pub fn validate_config_value(value: &Value) -> ConfigValidationReport {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    check(&SCHEMA, &SCHEMA, value, "", &mut errors, &mut warnings);
+
+    // Schedule rules have their own grammar
+    if let Some(schedules) = value.get("schedules").and_then(Value::as_array) {
+        for (i, entry) in schedules.iter().enumerate() {
+            if let Some(rule) = entry.get("rule").and_then(Value::as_str) {
+                if let Err(e) = ScheduleSpec::parse(rule) {
+                    errors.push(issue(&format!("/schedules/{}/rule", i), e.to_string()));
+                }
+            }
+        }
+    }
+
+    // Anything the schema cannot express, e.g. unknown area names
+    if errors.is_empty() {
+        if let Err(e) = serde_json::from_value::<super::Config>(value.clone()) {
+            errors.push(issue("", e.to_string()));
+        }
+    }
+
+    ConfigValidationReport {
+        valid: errors.is_empty(),
+        errors,
+        warnings,
+    }
+}
+
+fn issue(path: &str, message: impl Into<String>) -> SchemaIssue {
+    SchemaIssue {
+        path: path.to_string(),
+        message: message.into(),
+    }
+}
+
+/// JSON Pointer of a child, escaping `~` and `/`
+fn child_path(path: &str, key: &str) -> String {
+    format!("{}/{}", path, key.replace('~', "~0").replace('/', "~1"))
+}
+
+fn resolve<'a>(root: &'a Value, reference: &str) -> Option<&'a Value> {
+    let name = reference.strip_prefix("#/$defs/")?;
+    root.get("$defs")?.get(name)
+}
+
+fn type_matches(value: &Value, ty: &str) -> bool {
+    match ty {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        _ => true,
+    }
+}
+
+fn check(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<SchemaIssue>,
+    warnings: &mut Vec<SchemaIssue>,
+) {
+    let schema = match schema.as_object() {
+        Some(schema) => schema,
+        None => return,
+    };
+
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        match resolve(root, target) {
+            Some(resolved) => check(root, resolved, value, path, errors, warnings),
+            None => errors.push(issue(
+                path,
+                format!("Unresolved schema reference {}", target),
+            )),
+        }
+    }
+
+    if let Some(ty) = schema.get("type") {
+        let allowed: Vec<&str> = match ty {
+            Value::String(s) => vec![s.as_str()],
+            Value::Array(list) => list.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| type_matches(value, t)) {
+            errors.push(issue(path, format!("Expected {}", allowed.join(" or "))));
+            return;
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            errors.push(issue(path, format!("Expected {}", expected)));
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            let list: Vec<String> = options.iter().map(Value::to_string).collect();
+            errors.push(issue(path, format!("Expected one of {}", list.join(", "))));
+        }
+    }
+
+    for keyword in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(keyword).and_then(Value::as_array) {
+            check_branches(root, branches, value, path, errors, warnings);
+        }
+    }
+
+    match value {
+        Value::Number(n) => check_number(schema, n.as_f64().unwrap_or_default(), path, errors),
+        Value::String(s) => check_length(schema, s.chars().count(), path, errors),
+        Value::Array(items) => check_array(root, schema, items, path, errors, warnings),
+        Value::Object(map) => check_object(root, schema, map, path, errors, warnings),
+        _ => {}
+    }
+}
+
+/// Passes if one branch matches, otherwise reports the closest branch
+fn check_branches(
+    root: &Value,
+    branches: &[Value],
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<SchemaIssue>,
+    warnings: &mut Vec<SchemaIssue>,
+) {
+    let mut closest: Option<(Vec<SchemaIssue>, Vec<SchemaIssue>)> = None;
+    for branch in branches {
+        let (mut branch_errors, mut branch_warnings) = (Vec::new(), Vec::new());
+        check(
+            root,
+            branch,
+            value,
+            path,
+            &mut branch_errors,
+            &mut branch_warnings,
+        );
+        if branch_errors.is_empty() {
+            warnings.append(&mut branch_warnings);
+            return;
+        }
+        let closer = match &closest {
+            Some((e, _)) => branch_errors.len() < e.len(),
+            None => true,
+        };
+        if closer {
+            closest = Some((branch_errors, branch_warnings));
+        }
+    }
+    if let Some((mut branch_errors, _)) = closest {
+        errors.append(&mut branch_errors);
+    }
+}
+
+fn check_number(schema: &Map<String, Value>, n: f64, path: &str, errors: &mut Vec<SchemaIssue>) {
+    if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+        if n < min {
+            errors.push(issue(path, format!("Must be at least {}", min)));
+        }
+    }
+    if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+        if n > max {
+            errors.push(issue(path, format!("Must be at most {}", max)));
+        }
+    }
+}
+
+fn check_length(
+    schema: &Map<String, Value>,
+    len: usize,
+    path: &str,
+    errors: &mut Vec<SchemaIssue>,
+) {
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        if (len as u64) < min {
+            let message = if min == 1 {
+                "Must not be empty".to_string()
+            } else {
+                format!("Must be at least {} characters", min)
+            };
+            errors.push(issue(path, message));
+        }
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        if len as u64 > max {
+            errors.push(issue(path, format!("Must be at most {} characters", max)));
+        }
+    }
+}
+
+fn check_array(
+    root: &Value,
+    schema: &Map<String, Value>,
+    items: &[Value],
+    path: &str,
+    errors: &mut Vec<SchemaIssue>,
+    warnings: &mut Vec<SchemaIssue>,
+) {
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if items.len() as u64 > max {
+            errors.push(issue(path, format!("At most {} entries are allowed", max)));
+        }
+    }
+    if schema.get("uniqueItems").and_then(Value::as_bool) == Some(true) {
+        for (i, item) in items.iter().enumerate() {
+            if items[..i].contains(item) {
+                errors.push(issue(&child_path(path, &i.to_string()), "Duplicate entry"));
+            }
+        }
+    }
+    if let Some(item_schema) = schema.get("items") {
+        for (i, item) in items.iter().enumerate() {
+            let item_path = child_path(path, &i.to_string());
+            check(root, item_schema, item, &item_path, errors, warnings);
+        }
+    }
+}
+
+fn check_object(
+    root: &Value,
+    schema: &Map<String, Value>,
+    map: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<SchemaIssue>,
+    warnings: &mut Vec<SchemaIssue>,
+) {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for key in required.iter().filter_map(Value::as_str) {
+            if !map.contains_key(key) {
+                errors.push(issue(path, format!("Missing required setting \"{}\"", key)));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let closed = schema.get("additionalProperties").and_then(Value::as_bool) == Some(false);
+    for (key, child) in map {
+        let key_path = child_path(path, key);
+        match properties.and_then(|p| p.get(key)) {
+            Some(child_schema) => check(root, child_schema, child, &key_path, errors, warnings),
+            None if closed => warnings.push(issue(&key_path, "Unknown setting, ignored")),
+            None => {}
+        }
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_matches_schema() {
+        assert!(SCHEMA.is_object(), "embedded schema must parse");
+        let value = serde_json::to_value(crate::config::Config::default()).unwrap();
+        let report = validate_config_value(&value);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    }
+
+    #[test]
+    fn test_reports_precise_paths() {
+        let mut value = serde_json::to_value(crate::config::Config::default()).unwrap();
+        value["tray"]["warning_level"] = serde_json::json!(120);
+        value["theme"] = serde_json::json!("blue");
+        value["hotkeys"] = serde_json::json!([{ "hotkey": "Ctrl+K", "action": { "type": "fly" } }]);
+        value["old_setting"] = serde_json::json!(true);
+
+        let report = validate_config_value(&value);
+        assert!(!report.valid);
+        let paths: Vec<&str> = report.errors.iter().map(|e| e.path.as_str()).collect();
+        assert!(paths.contains(&"/tray/warning_level"));
+        assert!(paths.contains(&"/theme"));
+        assert!(paths.contains(&"/hotkeys/0/action/type"));
+        assert_eq!(report.warnings[0].path, "/old_setting");
+    }
+}
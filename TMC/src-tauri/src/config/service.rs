@@ -0,0 +1,85 @@
+/// Shared configuration access
+///
+/// Every subsystem reads the same `Config` through a `SharedConfig`. Reads
+/// take a read lock and never wait on each other, so the tray updater, the
+/// notifications and the auto-optimizer no longer skip a cycle or fall back
+/// to default settings while another reader holds the config. Writers go
+/// through `update`/`update_and_save`, which change and save the config under
+/// one write lock so no other write can land in between.
+///
+/// Guards must not be held across an `.await` or a slow call; take a
+/// `snapshot` when the values are needed for longer.
+use super::Config;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::sync::Arc;
+
+pub type SharedConfig = Arc<ConfigService>;
+
+#[derive(Debug, Default)]
+pub struct ConfigService {
+    inner: RwLock<Config>,
+}
+
+impl ConfigService {
+    pub fn new(cfg: Config) -> SharedConfig {
+        Arc::new(Self {
+            inner: RwLock::new(cfg),
+        })
+    }
+
+    /// Read access for a few field reads
+    pub fn read(&self) -> RwLockReadGuard<'_, Config> {
+        self.inner.read()
+    }
+
+    /// Run `read` on the configuration under the read lock
+    pub fn with<R>(&self, read: impl FnOnce(&Config) -> R) -> R {
+        read(&self.inner.read())
+    }
+
+    /// Copy of the whole configuration
+    pub fn snapshot(&self) -> Config {
+        self.inner.read().clone()
+    }
+
+    /// Write access for changes spread over several steps; prefer `update`
+    pub fn write(&self) -> RwLockWriteGuard<'_, Config> {
+        self.inner.write()
+    }
+
+    /// Change the configuration in memory
+    pub fn update<R>(&self, change: impl FnOnce(&mut Config) -> R) -> R {
+        change(&mut self.inner.write())
+    }
+
+    /// Change the configuration and save it before releasing the lock
+    pub fn update_and_save<R>(&self, change: impl FnOnce(&mut Config) -> R) -> anyhow::Result<R> {
+        let mut cfg = self.inner.write();
+        let result = change(&mut cfg);
+        cfg.save()?;
+        Ok(result)
+    }
+
+    /// Swap in a whole new configuration (import, live reload)
+    pub fn replace(&self, cfg: Config) {
+        *self.inner.write() = cfg;
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_do_not_block_each_other() {
+        let shared = ConfigService::new(Config::default());
+        let first = shared.read();
+        let second = shared.read();
+        assert_eq!(first.profile, second.profile);
+        drop((first, second));
+
+        shared.update(|c| c.minimize_to_tray = false);
+        assert!(!shared.snapshot().minimize_to_tray);
+    }
+}
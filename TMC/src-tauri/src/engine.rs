@@ -2,18 +2,65 @@
 ///
 /// This module contains the core engine responsible for performing
 /// memory optimization operations on Windows systems.
-use crate::config::Config;
+use crate::benchmark::{self, BenchmarkComparison};
+use crate::config::area_cooldown::{cooldown_minutes, remaining_secs};
+use crate::config::ram_floor::FloorDecision;
+use crate::config::{OtherSessionsPolicy, SharedConfig};
 use crate::logging::event_viewer::{log_error_event, log_optimization_event};
 use crate::memory::ops::{
-    memory_info, optimize_combined_page_list, optimize_modified_page_list_with_stealth, optimize_registry_cache,
-    optimize_standby_list_with_stealth, optimize_system_file_cache, optimize_working_set_with_stealth,
+    flush_modified_list_documented, memory_info, optimize_combined_page_list,
+    optimize_modified_page_list_with_stealth, optimize_registry_cache,
+    optimize_registry_cache_documented, optimize_standby_list_with_stealth,
+    optimize_system_file_cache, optimize_working_set_targeted, optimize_working_set_with_stealth,
+    purge_standby_list_documented, standby_breakdown, system_file_cache_size, working_set_estimate,
+    working_set_progress,
 };
 use crate::memory::advanced::trim_memory_compression_store;
-use crate::memory::types::{Areas, MemoryInfo, Reason};
+use crate::memory::error::MemoryOpError;
+use crate::memory::process_tree::implicitly_excluded;
+use crate::memory::strict::{reset_swallowed, take_swallowed};
+use crate::memory::types::{
+    Areas, MemoryInfo, ProcessTrimResult, Reason, StandbyBreakdown, TrimStrategy,
+};
 use crate::os;
+use crate::stats::adaptive::ADAPTIVE_SKIP_REASON;
+use crate::system::elevation::NOT_ELEVATED_REASON;
+use crate::ui::bridge::{overall_percent, ProgressEvent};
 use serde::{Deserialize, Serialize};
-use std::sync::{mpsc, Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// End of the last user-triggered run, for `manual_cooldown_secs`
+static LAST_MANUAL_RUN: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// A manual or hotkey run was refused because of `manual_cooldown_secs`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Throttled {
+    pub remaining_secs: u64,
+}
+
+impl std::fmt::Display for Throttled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Optimization throttled, try again in {}s",
+            self.remaining_secs
+        )
+    }
+}
+
+impl std::error::Error for Throttled {}
+
+/// API path an area runs through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiPath {
+    /// Documented Windows APIs only
+    Documented,
+    /// The advanced module (SYSTEM token, resolved syscalls) first, with the
+    /// documented APIs as fallback
+    Advanced,
+}
 
 /// Result of optimizing a specific memory area
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,27 +68,137 @@ pub struct OptimizeAreaResult {
     pub name: String,
     pub duration_ms: u128,
     pub error: Option<String>,
+    /// Structured cause of `error` when it is known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<MemoryOpError>,
+    /// Why the area was not optimized (free RAM floor, cooldown), `None` if it ran
+    #[serde(default)]
+    pub skipped: Option<String>,
+    /// Dry run only: bytes the area is expected to reclaim, `None` when it
+    /// cannot be estimated (combined pages, registry, modified file cache)
+    #[serde(default)]
+    pub estimated_bytes: Option<u64>,
+    /// Working Set only: outcome per process when processes are trimmed one by one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub processes: Vec<ProcessTrimResult>,
+    /// API path the area ran through, `None` when it did not run
+    #[serde(default)]
+    pub api_path: Option<ApiPath>,
+    /// Working Set only: trim strategy used, `None` when it did not run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trim_strategy: Option<TrimStrategy>,
+    /// Free physical memory gained across this area, measured after
+    /// `area_settle_ms`; `None` when it did not run or could not be measured
+    #[serde(default)]
+    pub freed_bytes: Option<i64>,
+    /// Times the area ran again because it reported success but left its
+    /// memory list as it was, see `area_verify_retries`
+    #[serde(default)]
+    pub retries: u8,
 }
 
+impl OptimizeAreaResult {
+    /// An area that did not run, with the reason shown to the user
+    pub fn skipped(name: &str, reason: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            duration_ms: 0,
+            error: None,
+            error_kind: None,
+            skipped: Some(reason.into()),
+            estimated_bytes: None,
+            processes: Vec::new(),
+            api_path: None,
+            trim_strategy: None,
+            freed_bytes: None,
+            retries: 0,
+        }
+    }
+}
+
+/// Below this a run is reported as having found nothing to free
+pub const NOTHING_FREED_BYTES: i64 = 1024 * 1024;
+
+/// Areas whose memory list is measured before and after, see `area_verify_retries`
+const VERIFIED_AREAS: [&str; 4] = [
+    "StandbyList",
+    "StandbyListLowPriority",
+    "ModifiedPageList",
+    "SystemFileCache",
+];
+/// A list smaller than this has too little in it to tell a failed purge apart
+const VERIFY_MIN_BYTES: u64 = 16 * 1024 * 1024;
+/// Pause between the memory samples taken after a run
+const SAMPLE_INTERVAL_MS: u32 = 100;
+
+/// How the memory after a run was measured, to reproduce a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MeasurementInfo {
+    /// Wait after the last area before the first sample, `measurement_delay_ms`
+    pub delay_ms: u32,
+    /// `memory_info()` samples per measurement, the median one counts
+    pub samples: u8,
+    pub sample_interval_ms: u32,
+    /// Measurements taken again because less than 1 MB had come free
+    pub retries: u32,
+}
+
+// FIX #10: Timeout per operazioni di ottimizzazione (30 secondi per operazione)
+const OPERATION_TIMEOUT: Duration = Duration::from_secs(30);
+// How often a running area is polled for its own progress
+const PROGRESS_POLL: Duration = Duration::from_millis(100);
+
 /// Complete optimization result with all areas
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OptimizeResult {
     pub reason: Reason,
     pub duration_ms: u128,
+    /// Raw difference in free physical memory over the whole run, including
+    /// whatever other processes allocated or released meanwhile
     pub freed_physical_bytes: i64,
     pub freed_commit_bytes: i64,
+    /// Sum of the per-area `freed_bytes`, without the time between areas
+    #[serde(default)]
+    pub attributed_freed_bytes: i64,
     pub areas: Vec<OptimizeAreaResult>,
+    /// Nothing was optimized; see `OptimizeAreaResult::estimated_bytes`
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Allocation workload timed around the run, with `benchmark_enabled`
+    #[serde(default)]
+    pub benchmark: Option<BenchmarkComparison>,
+    /// Processes skipped only because an excluded process started them,
+    /// with `exclude_child_processes`
+    #[serde(default)]
+    pub implicitly_excluded: usize,
+    /// `None` for dry runs, which measure nothing
+    #[serde(default)]
+    pub measurement: Option<MeasurementInfo>,
+}
+
+impl OptimizeResult {
+    /// Sum of the per-area estimates of a dry run
+    pub fn estimated_freed_bytes(&self) -> u64 {
+        self.areas.iter().filter_map(|a| a.estimated_bytes).sum()
+    }
+
+    /// Every area ran or was skipped without errors and less than
+    /// `NOTHING_FREED_BYTES` came free: memory was already optimal
+    pub fn freed_nothing(&self) -> bool {
+        self.freed_physical_bytes < NOTHING_FREED_BYTES
+            && self.areas.iter().all(|a| a.error.is_none())
+    }
 }
 
 /// Main memory optimization engine
 #[derive(Clone)]
 pub struct Engine {
-    pub cfg: Arc<Mutex<Config>>,
+    pub cfg: SharedConfig,
 }
 
 impl Engine {
     /// Create a new engine instance with configuration
-    pub fn new(cfg: Arc<Mutex<Config>>) -> Self {
+    pub fn new(cfg: SharedConfig) -> Self {
         Self { cfg }
     }
 
@@ -57,27 +214,99 @@ impl Engine {
     /// - Optimizes each specified memory area
     /// - Reports progress through callback
     /// - Returns detailed results
+    ///
+    /// With `dry_run` the availability checks still run but no memory list
+    /// is touched: each area reports an estimate of what it would reclaim.
     pub fn optimize<F>(
         &self,
         reason: Reason,
         areas: Areas,
+        dry_run: bool,
+        progress: Option<F>,
+    ) -> anyhow::Result<OptimizeResult>
+    where
+        F: FnMut(ProgressEvent),
+    {
+        self.optimize_with_targets(reason, areas, None, dry_run, progress)
+    }
+
+    /// Perform memory optimization, restricting the Working Set trim to specific processes
+    ///
+    /// `targets` contains PIDs or process names. When `None`, the configured
+    /// `process_include_list` is used (an empty list means all processes).
+    pub fn optimize_with_targets<F>(
+        &self,
+        reason: Reason,
+        areas: Areas,
+        targets: Option<Vec<String>>,
+        dry_run: bool,
         mut progress: Option<F>,
     ) -> anyhow::Result<OptimizeResult>
     where
-        F: FnMut(u8, u8, String),
+        F: FnMut(ProgressEvent),
     {
         // Pre-acquire all necessary privileges BEFORE starting
         tracing::info!(
-            "Starting optimization with reason: {:?}, areas: {:?}",
+            "Starting optimization with reason: {:?}, areas: {:?}, dry run: {}",
             reason,
-            areas
+            areas,
+            dry_run
         );
 
+        // Back-to-back manual runs have nothing left to free; automatic runs
+        // and targeted trims are not held back
+        let cooldown_applies = !dry_run
+            && reason.is_user_triggered()
+            && !targets.as_ref().is_some_and(|t| !t.is_empty());
+        if cooldown_applies {
+            let cooldown = self
+                .cfg
+                .with(|c| Duration::from_secs(c.manual_cooldown_secs as u64));
+            let elapsed = LAST_MANUAL_RUN
+                .lock()
+                .ok()
+                .and_then(|last| last.map(|t| t.elapsed()));
+            if let Some(elapsed) = elapsed.filter(|e| *e < cooldown) {
+                let remaining_secs = (cooldown - elapsed).as_secs_f64().ceil() as u64;
+                tracing::info!(
+                    "{} optimization throttled, {}s of cooldown left",
+                    reason,
+                    remaining_secs
+                );
+                return Err(Throttled { remaining_secs }.into());
+            }
+        }
+
+        // Documented-only mode never enters the advanced module
+        let (documented_only, include_list_empty, trim_strategy, settle, verify_retries) =
+            self.cfg.with(|c| {
+                (
+                    c.use_documented_apis_only,
+                    c.process_include_list.is_empty(),
+                    c.effective_trim_strategy(),
+                    Duration::from_millis(c.area_settle_ms as u64),
+                    c.area_verify_retries,
+                )
+            });
+        if documented_only {
+            tracing::info!("Using documented APIs only");
+        }
+
+        // Decided by the startup probe; without it the list areas use the documented APIs
+        let advanced_syscalls = crate::memory::syscall_probe::advanced_syscalls_enabled();
+
         // Check if we should use indirect syscalls for advanced memory areas
         // These areas benefit from stealth: Combined Page List, Modified Page List, Standby List
-        let use_indirect_syscalls = areas.intersects(
-            Areas::COMBINED_PAGE_LIST | Areas::MODIFIED_PAGE_LIST | Areas::STANDBY_LIST
-        );
+        let use_indirect_syscalls = !documented_only
+            && advanced_syscalls
+            && areas.intersects(
+                Areas::COMBINED_PAGE_LIST | Areas::MODIFIED_PAGE_LIST | Areas::STANDBY_LIST,
+            );
+        // Same rule as `execute_optimization`: an empty target list trims everything
+        let targeted = match &targets {
+            Some(t) => !t.is_empty(),
+            None => !include_list_empty,
+        };
         
         tracing::debug!("use_indirect_syscalls = {}", use_indirect_syscalls);
         
@@ -85,20 +314,27 @@ impl Engine {
             tracing::info!("Advanced memory areas detected - using indirect syscalls for stealth");
         }
 
+        // Without elevation the privileges cannot be acquired: the other
+        // areas are skipped and only the user's own working sets are trimmed
+        let unavailable = crate::system::elevation::unavailable_areas();
+        let elevated = unavailable.is_empty();
+
         // Acquire privileges in advance for all areas with retry
         let mut required_privs = vec![];
-        if areas.contains(Areas::WORKING_SET) {
+        if areas.contains(Areas::WORKING_SET) && elevated {
             required_privs.push("SeDebugPrivilege");
         }
-        if areas.contains(Areas::SYSTEM_FILE_CACHE) {
+        if areas.contains(Areas::SYSTEM_FILE_CACHE) && elevated {
             required_privs.push("SeIncreaseQuotaPrivilege");
         }
-        if areas.intersects(
-            Areas::MODIFIED_PAGE_LIST
-                | Areas::STANDBY_LIST
-                | Areas::STANDBY_LIST_LOW
-                | Areas::COMBINED_PAGE_LIST,
-        ) {
+        if elevated
+            && areas.intersects(
+                Areas::MODIFIED_PAGE_LIST
+                    | Areas::STANDBY_LIST
+                    | Areas::STANDBY_LIST_LOW
+                    | Areas::COMBINED_PAGE_LIST,
+            )
+        {
             required_privs.push("SeProfileSingleProcessPrivilege");
         }
 
@@ -153,31 +389,7 @@ impl Engine {
         );
 
         // Valida le aree disponibili per questa versione di Windows
-        let mut validated_areas = Areas::empty();
-        if areas.contains(Areas::STANDBY_LIST) && os::has_standby_list() {
-            validated_areas |= Areas::STANDBY_LIST;
-        }
-        if areas.contains(Areas::STANDBY_LIST_LOW) && os::has_standby_list_low() {
-            validated_areas |= Areas::STANDBY_LIST_LOW;
-        }
-        if areas.contains(Areas::MODIFIED_PAGE_LIST) && os::has_modified_page_list() {
-            validated_areas |= Areas::MODIFIED_PAGE_LIST;
-        }
-        if areas.contains(Areas::REGISTRY_CACHE) && os::has_registry_cache() {
-            validated_areas |= Areas::REGISTRY_CACHE;
-        }
-        if areas.contains(Areas::SYSTEM_FILE_CACHE) && os::has_system_file_cache() {
-            validated_areas |= Areas::SYSTEM_FILE_CACHE;
-        }
-        if areas.contains(Areas::COMBINED_PAGE_LIST) && os::has_combined_page_list() {
-            validated_areas |= Areas::COMBINED_PAGE_LIST;
-        }
-        if areas.contains(Areas::WORKING_SET) && os::has_working_set() {
-            validated_areas |= Areas::WORKING_SET;
-        }
-        if areas.contains(Areas::MODIFIED_FILE_CACHE) && os::has_modified_file_cache() {
-            validated_areas |= Areas::MODIFIED_FILE_CACHE;
-        }
+        let validated_areas = os::supported_areas(areas);
 
         if validated_areas != areas {
             let missing = areas & !validated_areas;
@@ -189,9 +401,21 @@ impl Engine {
 
         let areas = validated_areas;
 
+        // The workload runs before the initial delay so its memory is back
+        // to the OS when the "before" snapshot is taken
+        let benchmark_before = if dry_run {
+            None
+        } else {
+            self.cfg
+                .with(|c| c.benchmark_enabled)
+                .then(benchmark::run_workload)
+        };
+
         // FIX: Aggiungi un delay iniziale più lungo per stabilizzare il sistema
         // Questo è particolarmente importante al primo avvio
-        std::thread::sleep(std::time::Duration::from_millis(300));
+        if !dry_run {
+            std::thread::sleep(std::time::Duration::from_millis(300));
+        }
 
         // Ottieni memoria PRIMA dell'ottimizzazione
         let before = self.memory()?;
@@ -232,29 +456,165 @@ impl Engine {
         if areas.contains(Areas::REGISTRY_CACHE) {
             area_operations.push(("RegistryCache", "Registry Cache"));
         }
+        if areas.contains(Areas::MEMORY_COMPRESSION) {
+            area_operations.push(("MemoryCompression", "Memory Compression"));
+        }
 
         // Validazione per evitare overflow: len() potrebbe essere > 255
-        let total = area_operations
-            .len()
-            .try_into()
-            .ok()
-            .and_then(|n: u8| n.checked_add(1))
-            .unwrap_or(u8::MAX);
+        let area_count = area_operations.len();
+        let total = u8::try_from(area_count).unwrap_or(u8::MAX);
         let mut idx: u8 = 0;
+        // Results already reported as finished areas
+        let mut reported = 0;
         let mut results = Vec::with_capacity(area_operations.len());
+        let mut attributed_freed_bytes: i64 = 0;
         let mut errors = Vec::new();
         let start_all = Instant::now();
 
-        // FIX #10: Timeout per operazioni di ottimizzazione (30 secondi per operazione)
-        const OPERATION_TIMEOUT: Duration = Duration::from_secs(30);
+        // Free RAM floor: only automatic runs back off when memory is already healthy
+        let floor = if reason.respects_safeguards() {
+            Some(self.cfg.with(|c| c.effective_free_ram_floor()))
+        } else {
+            None
+        };
+        let mut stop_reason: Option<String> = None;
+
+        // Aggressive areas are never used unattended before the user confirmed them;
+        // turning emergency mode on counts as confirming its standby and working set purge
+        let unconfirmed = if reason.respects_safeguards() {
+            let targeted = targets.as_ref().is_some_and(|t| !t.is_empty());
+            self.cfg.with(|c| c.unconfirmed_aggressive(areas, targeted))
+        } else {
+            Areas::empty()
+        };
+
+        // Areas that keep freeing nothing are left out, targeted trims excepted
+        let adaptive = !dry_run && !targeted && self.cfg.with(|c| c.adaptive_area_selection);
+
+        // Area cooldowns hold back automatic runs only, emergency runs excepted
+        let cooldowns = if reason.respects_safeguards() {
+            self.cfg.with(|c| c.area_cooldowns.clone())
+        } else {
+            Vec::new()
+        };
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        // Dry run: memory lists are queried once, lazily
+        let mut standby: Option<StandbyBreakdown> = None;
 
         // Esegui ottimizzazioni
         for (operation_name, display_name) in &area_operations {
             idx = idx.saturating_add(1);
             area_names.push(display_name.to_string());
 
+            // Every path below pushes exactly one result for the previous area
+            report_finished(&mut progress, &results, &mut reported, area_count);
             if let Some(cb) = progress.as_mut() {
-                cb(idx, total, display_name.to_string());
+                cb(ProgressEvent::AreaStarted {
+                    index: idx,
+                    total,
+                    area: display_name.to_string(),
+                });
+            }
+
+            // The store trim needs the SYSTEM token, there is no documented equivalent
+            if documented_only && *operation_name == "MemoryCompression" {
+                tracing::info!("Skipping {}: needs the advanced path", display_name);
+                results.push(OptimizeAreaResult::skipped(
+                    display_name,
+                    "Not available with documented APIs only",
+                ));
+                continue;
+            }
+
+            if unavailable.intersects(area_flag(operation_name)) {
+                tracing::info!("Skipping {}: not running as administrator", display_name);
+                results.push(OptimizeAreaResult::skipped(
+                    display_name,
+                    NOT_ELEVATED_REASON,
+                ));
+                continue;
+            }
+
+            if unconfirmed.intersects(area_flag(operation_name)) {
+                tracing::info!("Skipping {}: not confirmed for automatic runs", display_name);
+                results.push(OptimizeAreaResult::skipped(
+                    display_name,
+                    "Requires confirmation",
+                ));
+                continue;
+            }
+
+            if let Some(minutes) = cooldown_minutes(&cooldowns, area_flag(operation_name)) {
+                let last_run = crate::stats::last_area_run(display_name);
+                if let Some(left) = remaining_secs(minutes, last_run, now) {
+                    let why = format!("Cooldown, {} min left", left.div_ceil(60));
+                    tracing::info!("Skipping {}: {}", display_name, why);
+                    results.push(OptimizeAreaResult::skipped(display_name, why));
+                    continue;
+                }
+            }
+
+            if adaptive && crate::stats::adaptive::should_skip(display_name) {
+                tracing::info!("Skipping {}: {}", display_name, ADAPTIVE_SKIP_REASON);
+                results.push(OptimizeAreaResult::skipped(
+                    display_name,
+                    ADAPTIVE_SKIP_REASON,
+                ));
+                continue;
+            }
+
+            // Valuta la soglia di RAM libera tra un'area e l'altra, mai prima della prima
+            if stop_reason.is_none() && idx > 1 {
+                if let (Some(floor), Ok(mem)) = (floor.as_ref(), self.memory()) {
+                    match floor.check(
+                        *operation_name == "WorkingSet",
+                        mem.physical.free.percentage,
+                        mem.physical.free.bytes,
+                    ) {
+                        FloorDecision::Proceed => {}
+                        FloorDecision::Skip(why) => {
+                            tracing::info!("Skipping {}: {}", display_name, why);
+                            results.push(OptimizeAreaResult::skipped(display_name, why));
+                            continue;
+                        }
+                        FloorDecision::Stop(why) => {
+                            tracing::info!("Stopping optimization at {}: {}", display_name, why);
+                            stop_reason = Some(why);
+                        }
+                    }
+                }
+            }
+            if let Some(why) = &stop_reason {
+                results.push(OptimizeAreaResult::skipped(display_name, why.as_str()));
+                continue;
+            }
+
+            if dry_run {
+                let t0 = Instant::now();
+                let estimate =
+                    self.estimate_area(operation_name, areas, targets.as_deref(), &mut standby);
+                let (estimated_bytes, error, error_kind) = match estimate {
+                    Ok(bytes) => (bytes, None, None),
+                    Err(e) => (None, Some(e.to_string()), MemoryOpError::find(&e)),
+                };
+                results.push(OptimizeAreaResult {
+                    name: display_name.to_string(),
+                    duration_ms: t0.elapsed().as_millis(),
+                    error,
+                    error_kind,
+                    skipped: None,
+                    estimated_bytes,
+                    processes: Vec::new(),
+                    api_path: None,
+                    trim_strategy: None,
+                    freed_bytes: None,
+                    retries: 0,
+                });
+                continue;
             }
 
             // FIX: Aumenta il delay tra operazioni per il primo run
@@ -263,80 +623,129 @@ impl Engine {
             }
 
             let t0 = Instant::now();
+            let free_before = self.memory().ok().map(|m| m.physical.free.bytes);
+            // Only the standard strategy can empty all working sets at once
+            let per_process = targeted || trim_strategy != TrimStrategy::Standard;
+            let path = api_path(
+                operation_name,
+                documented_only,
+                advanced_syscalls,
+                use_indirect_syscalls,
+                per_process,
+            );
+            let area_strategy = (*operation_name == "WorkingSet").then_some(trim_strategy);
+            // Size of the list the area empties, to catch a success that freed nothing
+            let list_before = if verify_retries > 0 {
+                self.list_size(operation_name, areas)
+            } else {
+                None
+            };
 
-            // FIX #10: Esegui l'operazione con timeout usando un thread separato
-            let operation_name_clone = operation_name.to_string();
-            let cfg_clone = self.cfg.clone();
-            let use_indirect_syscalls_clone = use_indirect_syscalls;
-
-            let (tx, rx) = mpsc::channel();
-            let handle = std::thread::spawn(move || {
-                // Ricrea l'engine per eseguire l'operazione
-                let engine = Engine { cfg: cfg_clone };
-                let result = engine.execute_optimization(&operation_name_clone, use_indirect_syscalls_clone);
-                let _ = tx.send(result);
-            });
+            let mut res = run_with_timeout(
+                &self.cfg,
+                operation_name,
+                display_name,
+                use_indirect_syscalls,
+                path,
+                trim_strategy,
+                targets.as_deref(),
+                &mut progress,
+                (results.len(), area_count),
+            );
 
-            // Attendi il risultato con timeout
-            let res = match rx.recv_timeout(OPERATION_TIMEOUT) {
-                Ok(result) => {
-                    // Aspetta che il thread finisca (dovrebbe essere già finito)
-                    if let Err(e) = handle.join() {
-                        tracing::warn!(
-                            "Thread panicked during operation {}: {:?}",
-                            display_name,
-                            e
-                        );
+            // A success status does not always mean the list was emptied
+            // (transient NTSTATUS races): run the area again while it is unchanged,
+            // and report it as failed if the last retry did not empty it either
+            let mut retries = 0;
+            if let (true, Some(mut before)) = (res.is_ok(), list_before) {
+                loop {
+                    let after = match self.list_size(operation_name, areas) {
+                        Some(after) if list_unchanged(before, after) => after,
+                        _ => break,
+                    };
+                    if retries == verify_retries {
+                        res = Err(MemoryOpError::ListUnchanged { retries }.into());
+                        break;
                     }
-                    result
-                }
-                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    retries += 1;
                     tracing::warn!(
-                        "Operation {} timed out after {:?}",
+                        "{} reported success but its list is still {:.1} MB, retrying ({}/{})",
                         display_name,
-                        OPERATION_TIMEOUT
+                        after as f64 / 1024.0 / 1024.0,
+                        retries,
+                        verify_retries
                     );
-                    // Il thread potrebbe ancora essere in esecuzione, ma non possiamo aspettarlo indefinitamente
-                    // Nota: Non possiamo fare join qui perché il thread è ancora in esecuzione e potrebbe bloccarci
-                    // Il thread continuerà in background ma terminerà naturalmente quando completa l'operazione
-                    Err(anyhow::anyhow!(
-                        "Operation timed out after {:?}",
-                        OPERATION_TIMEOUT
-                    ))
-                }
-                Err(mpsc::RecvTimeoutError::Disconnected) => {
-                    // Il thread è crashato o è stato terminato
-                    if let Err(e) = handle.join() {
-                        tracing::warn!(
-                            "Thread panicked during operation {} (disconnected): {:?}",
-                            display_name,
-                            e
-                        );
+                    let retry = run_with_timeout(
+                        &self.cfg,
+                        operation_name,
+                        display_name,
+                        use_indirect_syscalls,
+                        path,
+                        trim_strategy,
+                        targets.as_deref(),
+                        &mut progress,
+                        (results.len(), area_count),
+                    );
+                    if let Err(e) = retry {
+                        res = Err(e.context(format!("Retry {} of {}", retries, verify_retries)));
+                        break;
                     }
-                    Err(anyhow::anyhow!("Operation thread disconnected"))
+                    before = after;
                 }
-            };
+            }
 
             let dur = t0.elapsed().as_millis();
 
+            // Attribute the change in free memory to this area once it has settled
+            if !settle.is_zero() {
+                std::thread::sleep(settle);
+            }
+            let freed_bytes = free_before.zip(self.memory().ok()).map(|(before, after)| {
+                (after.physical.free.bytes as i64).saturating_sub(before as i64)
+            });
+            attributed_freed_bytes =
+                attributed_freed_bytes.saturating_add(freed_bytes.unwrap_or(0));
+
             match res {
-                Ok(_) => {
+                Ok(processes) => {
                     successful_areas += 1;
                     results.push(OptimizeAreaResult {
                         name: display_name.to_string(),
                         duration_ms: dur,
                         error: None,
+                        error_kind: None,
+                        skipped: None,
+                        estimated_bytes: None,
+                        processes,
+                        api_path: Some(path),
+                        trim_strategy: area_strategy,
+                        freed_bytes,
+                        retries,
                     });
                     tracing::debug!("Successfully optimized: {} in {}ms", display_name, dur);
                 }
                 Err(e) => {
-                    let error_msg = e.to_string();
+                    // A known cause says what to do about it, NT failures keep the call too
+                    let error_kind = MemoryOpError::find(&e);
+                    let error_msg = match &error_kind {
+                        Some(MemoryOpError::NtStatus { .. }) => format!("{:#}", e),
+                        Some(kind) => kind.to_string(),
+                        None => e.to_string(),
+                    };
                     tracing::warn!("Area {} optimization warning: {}", display_name, error_msg);
 
                     results.push(OptimizeAreaResult {
                         name: display_name.to_string(),
                         duration_ms: dur,
                         error: Some(error_msg.clone()),
+                        error_kind,
+                        skipped: None,
+                        estimated_bytes: None,
+                        processes: Vec::new(),
+                        api_path: Some(path),
+                        trim_strategy: area_strategy,
+                        freed_bytes,
+                        retries,
                     });
 
                     if *operation_name == "WorkingSet" || *operation_name == "SystemFileCache" {
@@ -347,15 +756,39 @@ impl Engine {
         }
 
         // Notifica completamento
+        report_finished(&mut progress, &results, &mut reported, area_count);
         if let Some(cb) = progress.as_mut() {
-            cb(total, total, "Completed".to_string());
+            cb(ProgressEvent::Completed);
         }
 
-        // FIX: Aumenta il delay di stabilizzazione dopo l'ottimizzazione
-        std::thread::sleep(std::time::Duration::from_millis(800));
+        if dry_run {
+            let result = OptimizeResult {
+                reason,
+                duration_ms: start_all.elapsed().as_millis(),
+                freed_physical_bytes: 0,
+                freed_commit_bytes: 0,
+                attributed_freed_bytes: 0,
+                areas: results,
+                dry_run: true,
+                benchmark: None,
+                implicitly_excluded: 0,
+                measurement: None,
+            };
+            tracing::info!(
+                "Dry run completed: an estimated {:.2} MB could be reclaimed",
+                result.estimated_freed_bytes() as f64 / 1024.0 / 1024.0
+            );
+            return Ok(result);
+        }
+
+        // Slow systems keep releasing pages for a while after the last area
+        let (delay_ms, samples) = self
+            .cfg
+            .with(|c| (c.measurement_delay_ms, c.measurement_samples.max(1)));
+        std::thread::sleep(Duration::from_millis(delay_ms as u64));
 
         // Ottieni memoria DOPO con retry e validazione
-        let mut after = self.memory()?;
+        let mut after = self.measure(samples)?;
         let mut retry_count = 0;
         const MAX_RETRIES: u32 = 3;
 
@@ -383,8 +816,29 @@ impl Engine {
                 retry_count
             );
             std::thread::sleep(std::time::Duration::from_millis(500 * retry_count as u64));
-            after = self.memory()?;
+            after = self.measure(samples)?;
         }
+        let measurement = MeasurementInfo {
+            delay_ms,
+            samples,
+            sample_interval_ms: SAMPLE_INTERVAL_MS,
+            retries: retry_count,
+        };
+
+        let benchmark = benchmark_before.map(|before| {
+            let comparison = BenchmarkComparison {
+                before,
+                after: benchmark::run_workload(),
+            };
+            tracing::info!(
+                "Benchmark: allocation {:.0} µs -> {:.0} µs, page faults {:.0}/s -> {:.0}/s",
+                comparison.before.alloc_latency_us,
+                comparison.after.alloc_latency_us,
+                comparison.before.page_faults_per_sec,
+                comparison.after.page_faults_per_sec
+            );
+            comparison
+        });
 
         // FIX #16: Usa saturating_sub per evitare problemi con overflow/underflow
         // Inoltre, valida che i valori siano in un range sicuro prima del cast per evitare overflow
@@ -415,7 +869,9 @@ impl Engine {
         let freed_commit_mb = freed_commit as f64 / 1024.0 / 1024.0;
 
         // Verifica che almeno una area sia stata ottimizzata con successo
-        let has_successful_area = results.iter().any(|r| r.error.is_none());
+        let has_successful_area = results
+            .iter()
+            .any(|r| r.error.is_none() && r.skipped.is_none());
 
         // Se non abbiamo liberato memoria E non abbiamo aree di successo, potrebbe essere un problema
         if freed_phys.abs() < 1024 * 1024 && !has_successful_area && successful_areas == 0 {
@@ -432,22 +888,20 @@ impl Engine {
 
         // Log nell'Event Viewer solo se abbiamo liberato memoria significativa o abbiamo aree di successo
         if freed_phys.abs() > 1024 * 1024 || has_successful_area {
-            let freed_mb = freed_phys as f64 / 1024.0 / 1024.0;
-            let profile_name = self
-                .cfg
-                .lock()
-                .map(|c| format!("{:?}", c.profile))
-                .unwrap_or_else(|_| "Unknown".to_string());
+            let profile_name = self.cfg.with(|c| c.active_profile_name());
 
             let mode = match reason {
                 Reason::Manual => "Manual",
                 Reason::Schedule => "Scheduled",
                 Reason::LowMemory => "Low Memory Auto",
                 Reason::Hotkey => "Hotkey",
+                Reason::External => "External",
+                Reason::Predicted => "Predicted Pressure Auto",
+                Reason::Emergency => "Emergency Auto",
             };
 
             log_optimization_event(
-                freed_mb.abs(),
+                freed_phys,
                 &profile_name,
                 mode,
                 &area_names.join(", "),
@@ -456,34 +910,197 @@ impl Engine {
             );
         }
 
+        if cooldown_applies {
+            if let Ok(mut last) = LAST_MANUAL_RUN.lock() {
+                *last = Some(Instant::now());
+            }
+        }
+
+        let implicitly_excluded: usize = results
+            .iter()
+            .map(|a| implicitly_excluded(&a.processes))
+            .sum();
+        if implicitly_excluded > 0 {
+            tracing::info!(
+                "{} processes left alone because an excluded process started them",
+                implicitly_excluded
+            );
+        }
+
         Ok(OptimizeResult {
             reason,
             duration_ms: duration,
             freed_physical_bytes: freed_phys,
             freed_commit_bytes: freed_commit,
+            attributed_freed_bytes,
             areas: results,
+            dry_run: false,
+            benchmark,
+            implicitly_excluded,
+            measurement: Some(measurement),
         })
     }
 
-    fn execute_optimization(&self, operation_name: &str, use_indirect_syscalls: bool) -> anyhow::Result<()> {
+    /// Take `samples` memory readings `SAMPLE_INTERVAL_MS` apart and keep the
+    /// median by free physical memory, so one reading caught during a burst
+    /// of allocations does not skew the result
+    fn measure(&self, samples: u8) -> anyhow::Result<MemoryInfo> {
+        let mut readings = Vec::with_capacity(samples as usize);
+        for i in 0..samples.max(1) {
+            if i > 0 {
+                std::thread::sleep(Duration::from_millis(SAMPLE_INTERVAL_MS as u64));
+            }
+            readings.push(self.memory()?);
+        }
+        readings.sort_by_key(|m| m.physical.free.bytes);
+        Ok(readings[readings.len() / 2])
+    }
+
+    /// Estimate what an area would reclaim, without optimizing it
+    ///
+    /// Standby and modified estimates come from SystemMemoryListInformation
+    /// and are upper bounds: pages in use again by the time of a real run are
+    /// not released.
+    fn estimate_area(
+        &self,
+        operation_name: &str,
+        areas: Areas,
+        targets: Option<&[String]>,
+        standby: &mut Option<StandbyBreakdown>,
+    ) -> anyhow::Result<Option<u64>> {
+        let mut breakdown = || -> anyhow::Result<StandbyBreakdown> {
+            if standby.is_none() {
+                *standby = Some(standby_breakdown()?);
+            }
+            standby.ok_or_else(|| anyhow::anyhow!("Memory list information unavailable"))
+        };
+
         match operation_name {
             "WorkingSet" => {
-                let excl = self
-                    .cfg
-                    .lock()
-                    .map(|c| c.process_exclusion_list_lower())
-                    .unwrap_or_default();
-                
-                // Use stealth mode for Working Set when indirect syscalls are enabled
-                if use_indirect_syscalls {
-                    tracing::debug!("Using stealth mode for Working Set optimization");
-                }
-                
-                optimize_working_set_with_stealth(&excl, use_indirect_syscalls)
+                let (
+                    excl,
+                    include,
+                    allow_system_trim,
+                    guard,
+                    skip_other_sessions,
+                    exclude_children,
+                ) = self.cfg.with(|c| {
+                    (
+                        c.process_exclusion_list_lower(),
+                        c.process_include_list_lower(),
+                        c.allow_system_process_trim,
+                        c.activity_guard(),
+                        c.other_sessions_policy == OtherSessionsPolicy::SkipOtherSessions,
+                        c.exclude_child_processes,
+                    )
+                });
+                let targets = targets.map(|t| t.to_vec()).unwrap_or(include);
+                Ok(Some(working_set_estimate(
+                    &targets,
+                    &excl,
+                    allow_system_trim,
+                    &guard,
+                    skip_other_sessions,
+                    exclude_children,
+                )))
+            }
+            "SystemFileCache" => Ok(Some(system_file_cache_size()?)),
+            "ModifiedPageList" => Ok(Some(breakdown()?.modified_bytes)),
+            "StandbyList" => Ok(Some(breakdown()?.total_bytes)),
+            // Priority 0 is already part of the full standby purge
+            "StandbyListLowPriority" if areas.contains(Areas::STANDBY_LIST) => Ok(Some(0)),
+            "StandbyListLowPriority" => Ok(Some(breakdown()?.low_priority_bytes)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Bytes in the memory list an area empties, `None` for areas that
+    /// are not verified or when the list cannot be queried
+    fn list_size(&self, operation_name: &str, areas: Areas) -> Option<u64> {
+        if !VERIFIED_AREAS.contains(&operation_name) {
+            return None;
+        }
+        self.estimate_area(operation_name, areas, None, &mut None)
+            .ok()
+            .flatten()
+    }
+
+    /// Run one area; the Working Set reports per-process results
+    fn execute_optimization(
+        &self,
+        operation_name: &str,
+        use_indirect_syscalls: bool,
+        path: ApiPath,
+        trim_strategy: TrimStrategy,
+        targets: Option<&[String]>,
+    ) -> anyhow::Result<Vec<ProcessTrimResult>> {
+        if operation_name == "WorkingSet" {
+            let (
+                excl,
+                include,
+                busy_cpu_percent,
+                allow_system_trim,
+                guard,
+                skip_other_sessions,
+                exclude_children,
+            ) = self.cfg.with(|c| {
+                (
+                    c.process_exclusion_list_lower(),
+                    c.process_include_list_lower(),
+                    c.busy_process_cpu_percent,
+                    c.allow_system_process_trim,
+                    c.activity_guard(),
+                    c.other_sessions_policy == OtherSessionsPolicy::SkipOtherSessions,
+                    c.exclude_child_processes,
+                )
+            });
+
+            // Explicit targets from the caller take precedence over the configured include list
+            let targets = targets.map(|t| t.to_vec()).unwrap_or(include);
+            if !targets.is_empty() {
+                tracing::debug!("Trimming working set of {} targeted processes", targets.len());
+                return optimize_working_set_targeted(
+                    &targets,
+                    &excl,
+                    busy_cpu_percent,
+                    allow_system_trim,
+                    trim_strategy,
+                    skip_other_sessions,
+                    exclude_children,
+                );
             }
+
+            // Use stealth mode for Working Set when indirect syscalls are enabled
+            if use_indirect_syscalls {
+                tracing::debug!("Using stealth mode for Working Set optimization");
+            }
+
+            return optimize_working_set_with_stealth(
+                &excl,
+                use_indirect_syscalls,
+                busy_cpu_percent,
+                allow_system_trim,
+                &guard,
+                trim_strategy,
+                skip_other_sessions,
+                exclude_children,
+            );
+        }
+
+        let strict = self.cfg.with(|c| c.strict_nt_errors);
+        reset_swallowed();
+        let documented = path == ApiPath::Documented;
+        let result = match operation_name {
+            "ModifiedPageList" if documented => flush_modified_list_documented(),
+            "StandbyList" if documented => purge_standby_list_documented(false),
+            "StandbyListLowPriority" if documented => purge_standby_list_documented(true),
+            "RegistryCache" if documented => optimize_registry_cache_documented(),
             "SystemFileCache" => {
                 // System cache optimization
-                optimize_system_file_cache()
+                let result = optimize_system_file_cache();
+                // The purge sets its own limits, put the profile limit back
+                crate::memory::file_cache::reapply_after_purge(&self.cfg.read());
+                result
             }
             "ModifiedPageList" => {
                 // Use the optimized modified page list function with stealth support
@@ -495,16 +1112,200 @@ impl Engine {
             "StandbyListLowPriority" => optimize_standby_list_with_stealth(true, use_indirect_syscalls),
             "CombinedPageList" => optimize_combined_page_list(),
             "RegistryCache" => optimize_registry_cache(),
-            "ModifiedFileCache" => {
-                // Always trim memory compression store
-                tracing::warn!("Using memory compression store trim");
-                let _ = trim_memory_compression_store();
-                crate::memory::volumes::flush_modified_file_cache_all()
-            }
+            "ModifiedFileCache" => crate::memory::volumes::flush_modified_file_cache_all(),
+            "MemoryCompression" => trim_memory_compression_store(),
             _ => {
                 tracing::warn!("Unknown optimization operation: {}", operation_name);
                 Ok(())
             }
+        };
+        // A fallback may have succeeded after a failed NT call
+        match take_swallowed() {
+            Some(e) if strict && result.is_ok() => Err(e),
+            _ => result.map(|_| Vec::new()),
         }
     }
 }
+
+/// API path of an engine operation
+///
+/// A Working Set trim that opens the processes one by one (`per_process`)
+/// never uses the advanced module. When the startup probe disabled the
+/// syscall path (`advanced_syscalls`) the list areas use the documented APIs;
+/// the Memory Compression trim has no documented equivalent and keeps the
+/// advanced module, which then skips the resolver.
+fn api_path(
+    operation_name: &str,
+    documented_only: bool,
+    advanced_syscalls: bool,
+    use_indirect_syscalls: bool,
+    per_process: bool,
+) -> ApiPath {
+    if documented_only {
+        return ApiPath::Documented;
+    }
+    match operation_name {
+        "WorkingSet" if use_indirect_syscalls && !per_process => ApiPath::Advanced,
+        "ModifiedPageList" | "StandbyList" | "StandbyListLowPriority" | "RegistryCache"
+            if advanced_syscalls =>
+        {
+            ApiPath::Advanced
+        }
+        "MemoryCompression" => ApiPath::Advanced,
+        _ => ApiPath::Documented,
+    }
+}
+
+/// The list still holds (nearly) everything it held before the area ran
+fn list_unchanged(before: u64, after: u64) -> bool {
+    before >= VERIFY_MIN_BYTES && before.saturating_sub(after) < NOTHING_FREED_BYTES as u64
+}
+
+/// Run one area on its own thread, giving up after `OPERATION_TIMEOUT`
+///
+/// While it runs the Working Set reports its progress through `progress`;
+/// `done` of `total` areas are finished before this one.
+#[allow(clippy::too_many_arguments)]
+fn run_with_timeout<F>(
+    cfg: &SharedConfig,
+    operation_name: &str,
+    display_name: &str,
+    use_indirect_syscalls: bool,
+    path: ApiPath,
+    trim_strategy: TrimStrategy,
+    targets: Option<&[String]>,
+    progress: &mut Option<F>,
+    (done, total): (usize, usize),
+) -> anyhow::Result<Vec<ProcessTrimResult>>
+where
+    F: FnMut(ProgressEvent),
+{
+    // FIX #10: Esegui l'operazione con timeout usando un thread separato
+    let operation_name_clone = operation_name.to_string();
+    let cfg_clone = cfg.clone();
+    let targets_clone = targets.map(|t| t.to_vec());
+
+    let (tx, rx) = mpsc::channel();
+    let handle = std::thread::spawn(move || {
+        // Ricrea l'engine per eseguire l'operazione
+        let engine = Engine { cfg: cfg_clone };
+        let result = engine.execute_optimization(
+            &operation_name_clone,
+            use_indirect_syscalls,
+            path,
+            trim_strategy,
+            targets_clone.as_deref(),
+        );
+        let _ = tx.send(result);
+    });
+
+    // Attendi il risultato con timeout
+    let deadline = Instant::now() + OPERATION_TIMEOUT;
+    let mut area_percent = 0;
+    loop {
+        let wait = deadline
+            .saturating_duration_since(Instant::now())
+            .min(PROGRESS_POLL);
+        match rx.recv_timeout(wait) {
+            Ok(result) => {
+                // Aspetta che il thread finisca (dovrebbe essere già finito)
+                if let Err(e) = handle.join() {
+                    tracing::warn!("Thread panicked during operation {}: {:?}", display_name, e);
+                }
+                return result;
+            }
+            // Only the per-process Working Set loop reports its own progress
+            Err(mpsc::RecvTimeoutError::Timeout) if Instant::now() < deadline => {
+                let percent = if operation_name == "WorkingSet" {
+                    working_set_progress()
+                } else {
+                    0
+                };
+                if percent == area_percent {
+                    continue;
+                }
+                area_percent = percent;
+                if let Some(cb) = progress.as_mut() {
+                    cb(ProgressEvent::AreaPercent {
+                        area: display_name.to_string(),
+                        percent,
+                    });
+                    cb(ProgressEvent::Overall {
+                        percent: overall_percent(done, total, percent),
+                    });
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                tracing::warn!(
+                    "Operation {} timed out after {:?}",
+                    display_name,
+                    OPERATION_TIMEOUT
+                );
+                // Il thread potrebbe ancora essere in esecuzione, ma non possiamo aspettarlo indefinitamente
+                // Nota: Non possiamo fare join qui perché il thread è ancora in esecuzione e potrebbe bloccarci
+                // Il thread continuerà in background ma terminerà naturalmente quando completa l'operazione
+                return Err(MemoryOpError::Timeout {
+                    secs: OPERATION_TIMEOUT.as_secs(),
+                }
+                .into());
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                // Il thread è crashato o è stato terminato
+                if let Err(e) = handle.join() {
+                    tracing::warn!(
+                        "Thread panicked during operation {} (disconnected): {:?}",
+                        display_name,
+                        e
+                    );
+                }
+                return Err(anyhow::anyhow!("Operation thread disconnected"));
+            }
+        }
+    }
+}
+
+/// Area flag handled by an engine operation
+fn area_flag(operation_name: &str) -> Areas {
+    match operation_name {
+        "WorkingSet" => Areas::WORKING_SET,
+        "SystemFileCache" => Areas::SYSTEM_FILE_CACHE,
+        "ModifiedPageList" => Areas::MODIFIED_PAGE_LIST,
+        "StandbyList" => Areas::STANDBY_LIST,
+        "StandbyListLowPriority" => Areas::STANDBY_LIST_LOW,
+        "CombinedPageList" => Areas::COMBINED_PAGE_LIST,
+        "RegistryCache" => Areas::REGISTRY_CACHE,
+        "ModifiedFileCache" => Areas::MODIFIED_FILE_CACHE,
+        "MemoryCompression" => Areas::MEMORY_COMPRESSION,
+        _ => Areas::empty(),
+    }
+}
+
+/// Report the areas pushed to `results` since the last call as finished
+fn report_finished<F>(
+    progress: &mut Option<F>,
+    results: &[OptimizeAreaResult],
+    reported: &mut usize,
+    total: usize,
+) where
+    F: FnMut(ProgressEvent),
+{
+    let cb = match progress.as_mut() {
+        Some(cb) => cb,
+        None => return,
+    };
+    if *reported == results.len() {
+        return;
+    }
+    for area in &results[*reported..] {
+        cb(ProgressEvent::AreaFinished {
+            area: area.name.clone(),
+            freed_bytes: area.freed_bytes,
+            skipped: area.skipped.is_some(),
+            failed: area.error.is_some(),
+        });
+    }
+    *reported = results.len();
+    cb(ProgressEvent::Overall {
+        percent: overall_percent(results.len(), total, 0),
+    });
+}
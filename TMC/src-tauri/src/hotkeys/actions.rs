@@ -0,0 +1,77 @@
+//! Hotkey Action Dispatch
+//!
+//! Runs the action bound to a pressed global shortcut. Actions are looked up
+//! in the registry kept by the manager, so a press always matches the
+//! bindings that are currently registered.
+
+use crate::config::HotkeyAction;
+use crate::memory::types::{Areas, Reason};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Run the action bound to a pressed shortcut
+pub async fn run_action(app: AppHandle, action: HotkeyAction) {
+    let (cfg, engine) = match app.try_state::<crate::AppState>() {
+        Some(state) => (state.cfg.clone(), state.engine.clone()),
+        None => return,
+    };
+
+    match action {
+        HotkeyAction::Optimize { profile } => {
+            // A profile hotkey activates its profile first
+            if let Some(name) = profile {
+                match crate::commands::config::select_profile(&cfg, &name) {
+                    Ok(_) => {
+                        crate::ui::tray::refresh_tray_icon(&app);
+                        let _ = app.emit("config-changed", ());
+                    }
+                    Err(e) => tracing::warn!("Failed to activate profile '{}': {}", name, e),
+                }
+            }
+
+            crate::perform_optimization(app, engine, cfg, Reason::Hotkey, true, None, None).await;
+        }
+        HotkeyAction::ToggleAutoOptimizer => {
+            let enabled = !cfg.read().auto_optimizer_enabled;
+            if let Err(e) =
+                crate::auto_optimizer::scheduler::set_auto_optimizer_enabled(&app, &cfg, enabled)
+            {
+                tracing::warn!("Failed to toggle the auto optimizer by hotkey: {}", e);
+            }
+        }
+        HotkeyAction::ToggleWindow => {
+            let visible = app
+                .get_webview_window("main")
+                .and_then(|w| w.is_visible().ok())
+                .unwrap_or(false);
+            if visible {
+                crate::ui::main_window::hide_main_window(&app, None);
+            } else {
+                crate::commands::ui::show_or_create_window(&app);
+            }
+        }
+        HotkeyAction::TrimForeground => {
+            let pid = match crate::memory::ops::get_foreground_process_pid() {
+                Some(pid) => pid,
+                None => {
+                    tracing::debug!("No foreground window to trim");
+                    return;
+                }
+            };
+            if pid == std::process::id() {
+                tracing::debug!("Foreground window belongs to TMC, nothing to trim");
+                return;
+            }
+
+            crate::perform_optimization(
+                app,
+                engine,
+                cfg,
+                Reason::Hotkey,
+                true,
+                Some(Areas::WORKING_SET),
+                Some(vec![pid.to_string()]),
+            )
+            .await;
+        }
+    }
+}
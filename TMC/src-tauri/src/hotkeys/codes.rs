@@ -2,14 +2,15 @@
 ///
 /// This module provides functionality to convert string representations
 /// of keyboard keys to their corresponding Code enum values used by
-/// the global shortcut plugin.
-use tauri_plugin_global_shortcut::Code;
+/// the global shortcut plugin, and to reject combinations that Windows
+/// reserves for itself.
+use tauri_plugin_global_shortcut::{Code, Modifiers};
 
 /// Converts a string representation of a key to a Code enum value.
 ///
-/// This function parses keyboard key strings (e.g., "A", "F1", "0")
-/// into the corresponding Code enum variants used for global shortcuts.
-/// The comparison is case-insensitive.
+/// This function parses keyboard key strings (e.g., "A", "F1", "0",
+/// "Numpad5", "PageUp", "MediaPlayPause") into the corresponding Code enum
+/// variants used for global shortcuts. The comparison is case-insensitive.
 ///
 /// # Arguments
 ///
@@ -69,6 +70,118 @@ pub fn code_from_str(s: &str) -> Result<Code, String> {
         "F10" => Ok(Code::F10),
         "F11" => Ok(Code::F11),
         "F12" => Ok(Code::F12),
+        "NUMPAD0" => Ok(Code::Numpad0),
+        "NUMPAD1" => Ok(Code::Numpad1),
+        "NUMPAD2" => Ok(Code::Numpad2),
+        "NUMPAD3" => Ok(Code::Numpad3),
+        "NUMPAD4" => Ok(Code::Numpad4),
+        "NUMPAD5" => Ok(Code::Numpad5),
+        "NUMPAD6" => Ok(Code::Numpad6),
+        "NUMPAD7" => Ok(Code::Numpad7),
+        "NUMPAD8" => Ok(Code::Numpad8),
+        "NUMPAD9" => Ok(Code::Numpad9),
+        "NUMPADADD" => Ok(Code::NumpadAdd),
+        "NUMPADSUBTRACT" => Ok(Code::NumpadSubtract),
+        "NUMPADMULTIPLY" => Ok(Code::NumpadMultiply),
+        "NUMPADDIVIDE" => Ok(Code::NumpadDivide),
+        "NUMPADDECIMAL" => Ok(Code::NumpadDecimal),
+        "NUMPADENTER" => Ok(Code::NumpadEnter),
+        "UP" | "ARROWUP" => Ok(Code::ArrowUp),
+        "DOWN" | "ARROWDOWN" => Ok(Code::ArrowDown),
+        "LEFT" | "ARROWLEFT" => Ok(Code::ArrowLeft),
+        "RIGHT" | "ARROWRIGHT" => Ok(Code::ArrowRight),
+        "HOME" => Ok(Code::Home),
+        "END" => Ok(Code::End),
+        "PAGEUP" => Ok(Code::PageUp),
+        "PAGEDOWN" => Ok(Code::PageDown),
+        "INSERT" | "INS" => Ok(Code::Insert),
+        "DELETE" | "DEL" => Ok(Code::Delete),
+        "SPACE" => Ok(Code::Space),
+        "TAB" => Ok(Code::Tab),
+        "ENTER" => Ok(Code::Enter),
+        "ESCAPE" | "ESC" => Ok(Code::Escape),
+        "BACKSPACE" => Ok(Code::Backspace),
+        "MEDIAPLAYPAUSE" => Ok(Code::MediaPlayPause),
+        "MEDIASTOP" => Ok(Code::MediaStop),
+        "MEDIANEXT" | "MEDIATRACKNEXT" => Ok(Code::MediaTrackNext),
+        "MEDIAPREV" | "MEDIATRACKPREVIOUS" => Ok(Code::MediaTrackPrevious),
+        "VOLUMEUP" | "AUDIOVOLUMEUP" => Ok(Code::AudioVolumeUp),
+        "VOLUMEDOWN" | "AUDIOVOLUMEDOWN" => Ok(Code::AudioVolumeDown),
+        "VOLUMEMUTE" | "AUDIOVOLUMEMUTE" => Ok(Code::AudioVolumeMute),
         _ => Err(format!("Unsupported key: {}", s)),
     }
 }
+
+/// Combinations Windows handles before any application sees them, with what
+/// they do
+const SYSTEM_HOTKEYS: &[(Modifiers, Code, &str)] = &[
+    (
+        Modifiers::CONTROL.union(Modifiers::ALT),
+        Code::Delete,
+        "Ctrl+Alt+Del opens the Windows security screen",
+    ),
+    (
+        Modifiers::CONTROL.union(Modifiers::SHIFT),
+        Code::Escape,
+        "Ctrl+Shift+Esc opens Task Manager",
+    ),
+    (
+        Modifiers::CONTROL,
+        Code::Escape,
+        "Ctrl+Esc opens the Start menu",
+    ),
+    (Modifiers::ALT, Code::Tab, "Alt+Tab switches windows"),
+    (Modifiers::ALT, Code::F4, "Alt+F4 closes the active window"),
+    (
+        Modifiers::ALT,
+        Code::Escape,
+        "Alt+Esc cycles through windows",
+    ),
+    (Modifiers::SUPER, Code::KeyL, "Win+L locks the computer"),
+    (Modifiers::SUPER, Code::KeyD, "Win+D shows the desktop"),
+    (Modifiers::SUPER, Code::KeyE, "Win+E opens File Explorer"),
+    (Modifiers::SUPER, Code::KeyR, "Win+R opens the Run dialog"),
+    (
+        Modifiers::SUPER,
+        Code::KeyX,
+        "Win+X opens the Quick Link menu",
+    ),
+    (Modifiers::SUPER, Code::KeyI, "Win+I opens Settings"),
+    (Modifiers::SUPER, Code::Tab, "Win+Tab opens Task View"),
+];
+
+/// Checks a combination against the hotkeys reserved by Windows.
+///
+/// # Returns
+///
+/// Returns `Ok(())` if the combination is free, or an error string naming
+/// what Windows uses it for.
+pub fn check_system_conflict(modifiers: Modifiers, code: Code) -> Result<(), String> {
+    match SYSTEM_HOTKEYS
+        .iter()
+        .find(|(mods, key, _)| *mods == modifiers && *key == code)
+    {
+        Some((_, _, what)) => Err(format!("This hotkey is reserved by Windows: {}", what)),
+        None => Ok(()),
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extended_keys_and_system_conflicts() {
+        assert_eq!(code_from_str("numpad7"), Ok(Code::Numpad7));
+        assert_eq!(code_from_str("PageDown"), Ok(Code::PageDown));
+        assert_eq!(code_from_str("MediaPlayPause"), Ok(Code::MediaPlayPause));
+        assert!(code_from_str("F13").is_err());
+
+        let ctrl_alt = Modifiers::CONTROL | Modifiers::ALT;
+        assert!(check_system_conflict(ctrl_alt, Code::Delete).is_err());
+        assert!(check_system_conflict(Modifiers::SUPER, Code::KeyL).is_err());
+        assert!(check_system_conflict(ctrl_alt, Code::KeyN).is_ok());
+        assert!(check_system_conflict(ctrl_alt | Modifiers::SHIFT, Code::Delete).is_ok());
+    }
+}
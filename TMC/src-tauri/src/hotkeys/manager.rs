@@ -5,9 +5,11 @@
 //! convert string representations of shortcuts into hardware-level key codes
 //! and modifier bitflags.
 
-use crate::config::Config;
-use crate::hotkeys::codes::code_from_str;
-use std::sync::{Arc, Mutex};
+use crate::config::{HotkeyAction, HotkeyBinding, SharedConfig};
+use crate::hotkeys::codes::{check_system_conflict, code_from_str};
+use crate::logging::error_bus::{report, Subsystem};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
 use tauri::AppHandle;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut};
 
@@ -37,36 +39,155 @@ pub fn parse_hotkey_for_v2(hotkey: &str) -> Result<(Modifiers, String), String>
     Ok((mods, key))
 }
 
-/// Configures and registers a global hotkey within the Tauri application context.
+/// Resolves a hotkey string into a shortcut understood by the plugin.
 ///
-/// This function ensures that any previously registered shortcuts are cleared
-/// before attempting to register the new hotkey to prevent conflicts.
+/// Combinations reserved by Windows are rejected with the reason.
+fn shortcut_from_str(hotkey: &str) -> Result<Shortcut, String> {
+    let (modifiers, key) = parse_hotkey_for_v2(hotkey)?;
+    let code = code_from_str(&key)?;
+    check_system_conflict(modifiers, code)?;
+    Ok(Shortcut::new(Some(modifiers), code))
+}
+
+/// Shortcuts currently registered by the app, by shortcut id
+static REGISTERED: Lazy<parking_lot::Mutex<HashMap<u32, RegisteredBinding>>> =
+    Lazy::new(|| parking_lot::Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone)]
+struct RegisteredBinding {
+    shortcut: Shortcut,
+    binding: HotkeyBinding,
+}
+
+/// Configures and registers the global hotkeys within the Tauri application context.
+///
+/// `hotkey` is the main optimization hotkey; the hotkeys of custom profiles
+/// and the additional bindings from the configuration are registered as well.
+/// Registration is done per binding: shortcuts that are no longer configured
+/// are unregistered, new ones registered and unchanged ones left alone. A
+/// secondary binding that cannot be registered is logged and skipped; only a
+/// failure of the main hotkey is returned as an error.
 pub fn register_global_hotkey_v2(
     app: &AppHandle,
     hotkey: &str,
-    _cfg: Arc<Mutex<Config>>,
+    cfg: SharedConfig,
 ) -> Result<(), String> {
-    // Clear previous registrations to ensure a clean state
-    app.global_shortcut()
-        .unregister_all()
-        .map_err(|e| e.to_string())?;
+    let mut bindings = cfg.with(|c| c.hotkey_bindings());
+    // The caller may pass a hotkey that is not saved yet
+    if let Some(main) = bindings
+        .iter_mut()
+        .find(|b| b.action == HotkeyAction::Optimize { profile: None })
+    {
+        main.hotkey = hotkey.to_string();
+    }
 
-    // Deconstruct hotkey string and resolve hardware key code
-    let (modifiers, key) = parse_hotkey_for_v2(hotkey)?;
-    let code = code_from_str(&key)?;
+    // Resolve every binding first; the first binding wins a duplicate shortcut
+    let mut wanted: HashMap<u32, RegisteredBinding> = HashMap::new();
+    let mut main_error = None;
+    for binding in bindings {
+        match shortcut_from_str(&binding.hotkey) {
+            Ok(shortcut) => {
+                wanted
+                    .entry(shortcut.id())
+                    .or_insert(RegisteredBinding { shortcut, binding });
+            }
+            Err(e) if binding.hotkey == hotkey => main_error = Some(e),
+            Err(e) => {
+                tracing::warn!("Invalid hotkey {}: {}", binding.hotkey, e);
+                report(
+                    Subsystem::Hotkeys,
+                    format!("Invalid hotkey {}: {}", binding.hotkey, e),
+                );
+            }
+        }
+    }
+    if let Some(e) = main_error {
+        return Err(e);
+    }
 
-    // Initialize the shortcut structure
-    let shortcut = Shortcut::new(Some(modifiers), code);
+    let mut registered = REGISTERED.lock();
 
-    // Final registration with the operating system via Tauri plugin
+    // Unregister bindings that are gone
+    let stale: Vec<u32> = registered
+        .keys()
+        .filter(|id| !wanted.contains_key(id))
+        .copied()
+        .collect();
+    for id in stale {
+        if let Some(old) = registered.remove(&id) {
+            match app.global_shortcut().unregister(old.shortcut) {
+                Ok(()) => tracing::info!("Hotkey {} unregistered", old.binding.hotkey),
+                Err(e) => tracing::warn!("Failed to unregister hotkey {}: {}", old.binding.hotkey, e),
+            }
+        }
+    }
+
+    // Register new bindings, update the action of existing ones
+    let mut result = Ok(());
+    for (id, entry) in wanted {
+        if let Some(existing) = registered.get_mut(&id) {
+            existing.binding = entry.binding;
+            continue;
+        }
+        match app.global_shortcut().register(entry.shortcut) {
+            Ok(()) => {
+                tracing::info!(
+                    "Global hotkey successfully registered: {} ({:?})",
+                    entry.binding.hotkey,
+                    entry.binding.action
+                );
+                registered.insert(id, entry);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to register hotkey {} ({:?}): {}",
+                    entry.binding.hotkey,
+                    entry.binding.action,
+                    e
+                );
+                report(
+                    Subsystem::Hotkeys,
+                    format!(
+                        "Hotkey {} could not be registered, it may be in use by another application: {}",
+                        entry.binding.hotkey, e
+                    ),
+                );
+                if entry.binding.hotkey == hotkey {
+                    result = Err(e.to_string());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Checks that a hotkey can be used: it must parse and be either registered
+/// by the app already or free to register.
+///
+/// A free hotkey is registered and released right away.
+pub fn probe_hotkey(app: &AppHandle, hotkey: &str) -> Result<(), String> {
+    let shortcut = shortcut_from_str(hotkey)?;
+    if REGISTERED.lock().contains_key(&shortcut.id()) {
+        return Ok(());
+    }
     app.global_shortcut()
         .register(shortcut)
         .map_err(|e| e.to_string())?;
-
-    tracing::info!("Global hotkey successfully registered: {}", hotkey);
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        tracing::warn!("Failed to release probed hotkey {}: {}", hotkey, e);
+    }
     Ok(())
 }
 
+/// Returns the action bound to a pressed shortcut, if any.
+pub fn action_for_shortcut(shortcut_id: u32) -> Option<HotkeyAction> {
+    REGISTERED
+        .lock()
+        .get(&shortcut_id)
+        .map(|entry| entry.binding.action.clone())
+}
+
 /// Tauri IPC command to dynamically update the global hotkey from the frontend.
 ///
 /// Accesses the application state to retrieve configuration before triggering
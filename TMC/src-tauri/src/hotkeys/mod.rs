@@ -4,6 +4,7 @@
 //! both the internal registration functions and the Tauri-exposed commands.
 //! It serves as the public interface for the application's hotkey system.
 
+pub mod actions;
 pub mod codes;
 pub mod manager;
 
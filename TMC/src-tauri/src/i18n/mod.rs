@@ -0,0 +1,340 @@
+/// Backend translations
+///
+/// Notifications, the tray tooltip and the CLI translate through this module.
+/// The language packs of the frontend are embedded at build time and a
+/// `<language>.json` file in the `translations` folder of the data directory
+/// overrides single keys, so a translation can be fixed or completed without
+/// a rebuild. Lookups fall back from a regional language ("pt-BR") to its
+/// base language ("pt"), then to English, then to the key itself.
+///
+/// Templates use printf-style placeholders (`%s`, `%d`, `%.1f`) filled in
+/// order by `tr_args`; `%%` is a literal percent sign.
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub const OVERRIDE_DIR_NAME: &str = "translations";
+pub const FALLBACK_LANGUAGE: &str = "en";
+
+/// Language files bundled with the frontend
+pub const BUNDLED_LANGUAGES: &[(&str, &str)] = &[
+    ("en", include_str!("../../../ui/src/i18n/en.json")),
+    ("it", include_str!("../../../ui/src/i18n/it.json")),
+    ("es", include_str!("../../../ui/src/i18n/es.json")),
+    ("fr", include_str!("../../../ui/src/i18n/fr.json")),
+    ("pt", include_str!("../../../ui/src/i18n/pt.json")),
+    ("de", include_str!("../../../ui/src/i18n/de.json")),
+    ("ar", include_str!("../../../ui/src/i18n/ar.json")),
+    ("ja", include_str!("../../../ui/src/i18n/ja.json")),
+    ("zh", include_str!("../../../ui/src/i18n/zh.json")),
+];
+
+type Catalog = HashMap<String, String>;
+
+static LANGUAGE: Lazy<RwLock<String>> = Lazy::new(|| RwLock::new(FALLBACK_LANGUAGE.to_string()));
+/// Parsed catalogs by language, loaded on first use
+static CATALOGS: Lazy<RwLock<HashMap<String, Option<Arc<Catalog>>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// A value for a template placeholder
+#[derive(Debug, Clone, PartialEq)]
+pub enum Arg {
+    Text(String),
+    Int(i64),
+    Float(f64),
+}
+
+impl From<&str> for Arg {
+    fn from(s: &str) -> Self {
+        Arg::Text(s.to_string())
+    }
+}
+
+impl From<String> for Arg {
+    fn from(s: String) -> Self {
+        Arg::Text(s)
+    }
+}
+
+impl From<&String> for Arg {
+    fn from(s: &String) -> Self {
+        Arg::Text(s.clone())
+    }
+}
+
+impl From<i64> for Arg {
+    fn from(n: i64) -> Self {
+        Arg::Int(n)
+    }
+}
+
+impl From<u64> for Arg {
+    fn from(n: u64) -> Self {
+        Arg::Int(n.min(i64::MAX as u64) as i64)
+    }
+}
+
+impl From<u32> for Arg {
+    fn from(n: u32) -> Self {
+        Arg::Int(n as i64)
+    }
+}
+
+impl From<u8> for Arg {
+    fn from(n: u8) -> Self {
+        Arg::Int(n as i64)
+    }
+}
+
+impl From<usize> for Arg {
+    fn from(n: usize) -> Self {
+        Arg::Int(n.min(i64::MAX as usize) as i64)
+    }
+}
+
+impl From<u128> for Arg {
+    fn from(n: u128) -> Self {
+        Arg::Int(n.min(i64::MAX as u128) as i64)
+    }
+}
+
+impl From<f64> for Arg {
+    fn from(n: f64) -> Self {
+        Arg::Float(n)
+    }
+}
+
+/// Change the language used by `tr` and `tr_args`
+pub fn set_language(language: &str) {
+    let language = language.trim();
+    let language = if language.is_empty() {
+        FALLBACK_LANGUAGE
+    } else {
+        language
+    };
+    let mut current = LANGUAGE.write();
+    if *current != language {
+        tracing::info!("Backend language set to {}", language);
+        *current = language.to_string();
+    }
+}
+
+pub fn language() -> String {
+    LANGUAGE.read().clone()
+}
+
+/// Drop the loaded catalogs so override files are read again
+pub fn reload() {
+    CATALOGS.write().clear();
+}
+
+/// Translate `key` into the current language
+pub fn tr(key: &str) -> String {
+    tr_in(&language(), key)
+}
+
+/// Translate `key` into the current language and fill its placeholders
+pub fn tr_args(key: &str, args: &[Arg]) -> String {
+    let language = language();
+    format(&language, &tr_in(&language, key), args)
+}
+
+/// Translate `key` into `language`, following the fallback chain
+pub fn tr_in(language: &str, key: &str) -> String {
+    for lang in fallback_chain(language) {
+        let found = catalog(&lang).and_then(|c| c.get(key).filter(|v| !v.is_empty()).cloned());
+        if let Some(text) = found {
+            return text;
+        }
+    }
+    tracing::debug!("Translation not found for key '{}' ({})", key, language);
+    key.to_string()
+}
+
+/// "pt-BR" -> ["pt-BR", "pt", "en"]
+fn fallback_chain(language: &str) -> Vec<String> {
+    let mut chain = vec![language.to_string()];
+    if let Some((base, _)) = language.split_once(['-', '_']) {
+        chain.push(base.to_string());
+    }
+    chain.push(FALLBACK_LANGUAGE.to_string());
+    chain.dedup();
+    chain
+}
+
+fn catalog(language: &str) -> Option<Arc<Catalog>> {
+    if let Some(cached) = CATALOGS.read().get(language) {
+        return cached.clone();
+    }
+    let loaded = load_catalog(language).map(Arc::new);
+    CATALOGS
+        .write()
+        .insert(language.to_string(), loaded.clone());
+    loaded
+}
+
+fn override_path(language: &str) -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(OVERRIDE_DIR_NAME)
+        .join(format!("{}.json", language))
+}
+
+/// Bundled pack merged with the override file, `None` if neither exists
+fn load_catalog(language: &str) -> Option<Catalog> {
+    let mut catalog = BUNDLED_LANGUAGES
+        .iter()
+        .find(|(lang, _)| *lang == language)
+        .and_then(
+            |(_, content)| match serde_json::from_str::<Catalog>(content) {
+                Ok(c) => Some(c),
+                Err(e) => {
+                    tracing::warn!(
+                        "Bundled language pack '{}' cannot be parsed: {}",
+                        language,
+                        e
+                    );
+                    None
+                }
+            },
+        );
+
+    // Only plain language codes can name a file
+    if language
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        let path = override_path(language);
+        if let Ok(content) = fs::read_to_string(&path) {
+            match serde_json::from_str::<Catalog>(&content) {
+                Ok(overrides) => {
+                    tracing::info!(
+                        "Loaded {} translation overrides from {}",
+                        overrides.len(),
+                        path.display()
+                    );
+                    catalog.get_or_insert_with(Catalog::new).extend(overrides);
+                }
+                Err(e) => tracing::warn!("Ignoring {}: {}", path.display(), e),
+            }
+        }
+    }
+    catalog
+}
+
+/// Languages that write 1,5 instead of 1.5
+pub fn uses_decimal_comma(language: &str) -> bool {
+    let base = language.split(['-', '_']).next().unwrap_or(language);
+    matches!(base, "it" | "es" | "fr" | "pt" | "de")
+}
+
+/// `value` with `precision` decimals in the current language
+pub fn decimal(value: f64, precision: usize) -> String {
+    format_float(&language(), value, precision)
+}
+
+fn format_float(language: &str, value: f64, precision: usize) -> String {
+    let text = std::format!("{:.*}", precision, value);
+    if uses_decimal_comma(language) {
+        text.replace('.', ",")
+    } else {
+        text
+    }
+}
+
+/// Fill the placeholders of `template` in order
+///
+/// Placeholders without a matching argument and unknown sequences are kept
+/// as they are.
+pub fn format(language: &str, template: &str, args: &[Arg]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        let spec = &rest[pos + 1..];
+
+        if let Some(after) = spec.strip_prefix('%') {
+            out.push('%');
+            rest = after;
+            continue;
+        }
+
+        // Optional precision, then the conversion
+        let (precision, conv) = match spec.strip_prefix('.') {
+            Some(p) => {
+                let digits = p.chars().take_while(|c| c.is_ascii_digit()).count();
+                (p[..digits].parse::<usize>().ok(), &p[digits..])
+            }
+            None => (None, spec),
+        };
+        let kind = conv.chars().next().filter(|c| matches!(c, 's' | 'd' | 'f'));
+        let arg = match kind {
+            Some(_) => args.next(),
+            None => None,
+        };
+
+        match (kind, arg) {
+            (Some(kind), Some(arg)) => {
+                out.push_str(&render(language, kind, precision, arg));
+                rest = &conv[1..];
+            }
+            _ => {
+                out.push('%');
+                rest = spec;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn render(language: &str, kind: char, precision: Option<usize>, arg: &Arg) -> String {
+    match (kind, arg) {
+        (_, Arg::Text(s)) => s.clone(),
+        ('f', Arg::Int(n)) => format_float(language, *n as f64, precision.unwrap_or(6)),
+        (_, Arg::Int(n)) => n.to_string(),
+        ('d', Arg::Float(v)) => std::format!("{:.0}", v),
+        ('f', Arg::Float(v)) => format_float(language, *v, precision.unwrap_or(6)),
+        (_, Arg::Float(v)) => match precision {
+            Some(p) => format_float(language, *v, p),
+            None => format_float(language, *v, 1),
+        },
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_placeholders() {
+        assert_eq!(
+            format(
+                "en",
+                "✅ Freed: %.1f MB\n🎯 Profile: %s",
+                &[512.26.into(), "Gaming".into()]
+            ),
+            "✅ Freed: 512.3 MB\n🎯 Profile: Gaming"
+        );
+        // Trailing percent sign and missing arguments stay as they are
+        assert_eq!(format("en", "RAM: %d%", &[42u8.into()]), "RAM: 42%");
+        assert_eq!(format("en", "%s of %s", &["1".into()]), "1 of %s");
+        assert_eq!(format("en", "100%% %x", &[]), "100% %x");
+        assert_eq!(format("it", "%.2f GB", &[1.5.into()]), "1,50 GB");
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        assert_eq!(fallback_chain("pt-BR"), vec!["pt-BR", "pt", "en"]);
+        assert_eq!(fallback_chain("en"), vec!["en"]);
+        assert_eq!(tr_in("it-CH", "Balanced"), tr_in("it", "Balanced"));
+        assert_eq!(tr_in("xx", "Balanced"), tr_in("en", "Balanced"));
+        assert_eq!(tr_in("en", "No such key"), "No such key");
+    }
+}
@@ -0,0 +1,489 @@
+/// Local HTTP API for dashboards
+///
+/// Home-lab dashboards (Grafana, Home Assistant) poll HTTP far more easily
+/// than they talk to a named pipe. When enabled, a small HTTP/1.1 listener on
+/// 127.0.0.1 serves `GET /memory`, `GET /history` and `POST /optimize`, plus
+/// `GET /metrics` in the Prometheus text format, each authenticated with the
+/// bearer token from the config. Like the SDK pipe it
+/// runs on a dedicated thread with blocking I/O and serves one client at a
+/// time, so it never blocks the Tauri runtime and at most one optimization it
+/// requested is in flight. See docs/http-api.md.
+use super::protocol::resolve_areas;
+use crate::config::SharedConfig;
+use crate::engine::Engine;
+use crate::logging::error_bus::{report, Subsystem};
+use crate::memory::types::Reason;
+use crate::security::RateLimiter;
+use crate::stats::HistoryQuery;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+pub const DEFAULT_PORT: u16 = 9735;
+/// Lowest accepted `http_api_port`, well-known ports are left alone
+pub const MIN_PORT: u16 = 1024;
+/// Requests (headers and body) larger than this are rejected
+const MAX_REQUEST_SIZE: usize = 8192;
+const MAX_TOKEN_LEN: usize = 128;
+/// Optimizations `POST /optimize` may start per minute
+const OPTIMIZATIONS_PER_MINUTE: usize = 6;
+/// How often the listener checks for a new connection and for config changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// A client that stops sending or reading is dropped after this
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+
+static SERVER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Keep only characters that can travel in an `Authorization` header
+pub fn sanitize_token(token: &str) -> String {
+    token
+        .chars()
+        .filter(|c| c.is_ascii_graphic())
+        .take(MAX_TOKEN_LEN)
+        .collect()
+}
+
+fn generate_token() -> String {
+    use rand::Rng;
+    let bytes: [u8; 24] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Port and token, `None` while the API is disabled
+///
+/// An empty token is replaced by a generated one and saved, so the API is
+/// never reachable without authentication.
+fn read_settings(cfg: &SharedConfig) -> Option<(u16, String)> {
+    let (port, token) = cfg.with(|c| {
+        c.http_api_enabled
+            .then(|| (c.http_api_port, c.http_api_token.clone()))
+    })?;
+    if !token.is_empty() {
+        return Some((port, token));
+    }
+
+    let token = generate_token();
+    match cfg.update_and_save(|c| c.http_api_token = token.clone()) {
+        Ok(()) => tracing::info!("Generated an HTTP API token, see http_api_token in config.json"),
+        Err(e) => tracing::warn!("Failed to save the generated HTTP API token: {}", e),
+    }
+    Some((port, token))
+}
+
+/// Start the HTTP API if it is enabled and not already running
+pub fn start_http_api(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    if read_settings(&cfg).is_none() {
+        return;
+    }
+    if SERVER_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("HTTP API already running");
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-http-api".into())
+        .spawn(move || {
+            let _guard = scopeguard::guard((), |_| {
+                SERVER_RUNNING.store(false, Ordering::SeqCst);
+            });
+            server_loop(app, engine, cfg);
+            tracing::info!("HTTP API stopped");
+        });
+
+    if let Err(e) = spawned {
+        SERVER_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("✗ Failed to start HTTP API: {}", e);
+    }
+}
+
+fn bind(port: u16) -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))?;
+    // Polled, so disabling the API or changing the port takes effect without a client
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+fn server_loop(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    let mut limiter = RateLimiter::new(OPTIMIZATIONS_PER_MINUTE, Duration::from_secs(60));
+
+    // Binds again after a port change, returns once the API is disabled
+    while let Some((port, _)) = read_settings(&cfg) {
+        let listener = match bind(port) {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!("Failed to listen on 127.0.0.1:{}: {}", port, e);
+                report(
+                    Subsystem::Ipc,
+                    format!("HTTP API cannot listen on 127.0.0.1:{} ({})", port, e),
+                );
+                std::thread::sleep(Duration::from_secs(5));
+                continue;
+            }
+        };
+        tracing::info!("✓ HTTP API listening on http://127.0.0.1:{}", port);
+
+        loop {
+            let token = match read_settings(&cfg) {
+                Some((current_port, token)) if current_port == port => token,
+                _ => break,
+            };
+            match listener.accept() {
+                Ok((stream, _)) => serve(stream, &token, &mut limiter, &app, &engine, &cfg),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+                Err(e) => {
+                    tracing::debug!("HTTP API: accept failed: {}", e);
+                    std::thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    }
+}
+
+fn serve(
+    mut stream: TcpStream,
+    token: &str,
+    limiter: &mut RateLimiter,
+    app: &AppHandle,
+    engine: &Engine,
+    cfg: &SharedConfig,
+) {
+    // Accepted sockets inherit non-blocking mode from the listener on Windows
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+
+    let response = match read_request(&mut stream).and_then(|raw| parse_request(&raw)) {
+        Some(request) => handle_request(&request, token, limiter, app, engine, cfg),
+        None => HttpResponse::error(400, "Malformed, incomplete or too large request"),
+    };
+    if let Err(e) = stream.write_all(&response.to_bytes()) {
+        tracing::debug!(
+            "HTTP API: client disconnected before the response was sent: {}",
+            e
+        );
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    bearer: Option<String>,
+    body: String,
+}
+
+struct HttpResponse {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/json",
+            body: body.to_string(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            ..Self::ok(json!({ "error": message.into() }))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            status_text(self.status),
+            self.content_type,
+            self.body.len()
+        );
+        if self.status == 401 {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        head.push_str("\r\n");
+        head.push_str(&self.body);
+        head.into_bytes()
+    }
+}
+
+fn status_text(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        429 => "Too Many Requests",
+        _ => "Internal Server Error",
+    }
+}
+
+fn to_response<T: serde::Serialize>(value: &T) -> HttpResponse {
+    match serde_json::to_value(value) {
+        Ok(value) => HttpResponse::ok(value),
+        Err(e) => HttpResponse::error(500, e.to_string()),
+    }
+}
+
+fn handle_request(
+    request: &HttpRequest,
+    token: &str,
+    limiter: &mut RateLimiter,
+    app: &AppHandle,
+    engine: &Engine,
+    cfg: &SharedConfig,
+) -> HttpResponse {
+    if !token_matches(request.bearer.as_deref(), token) {
+        return HttpResponse::error(401, "Missing or wrong bearer token");
+    }
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/memory") => match engine.memory() {
+            Ok(info) => to_response(&info),
+            Err(e) => HttpResponse::error(500, e.to_string()),
+        },
+        ("GET", "/history") => match history_query(&request.query) {
+            Ok(query) => to_response(&crate::stats::query_history(&query)),
+            Err(e) => HttpResponse::error(400, e),
+        },
+        ("GET", "/metrics") => {
+            let gauges = crate::stats::prometheus::Gauges::read(engine);
+            HttpResponse {
+                status: 200,
+                content_type: crate::stats::prometheus::CONTENT_TYPE,
+                body: crate::stats::prometheus::render(&gauges),
+            }
+        }
+        ("POST", "/optimize") => optimize(&request.body, limiter, app, engine, cfg),
+        (_, "/memory" | "/history" | "/metrics" | "/optimize") => {
+            HttpResponse::error(405, format!("{} is not supported here", request.method))
+        }
+        _ => HttpResponse::error(404, format!("Unknown endpoint {}", request.path)),
+    }
+}
+
+/// Body of `POST /optimize`, the same fields as the SDK `optimize` command
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptimizeRequest {
+    #[serde(default)]
+    profile: Option<String>,
+    #[serde(default)]
+    areas: Option<String>,
+}
+
+fn optimize(
+    body: &str,
+    limiter: &mut RateLimiter,
+    app: &AppHandle,
+    engine: &Engine,
+    cfg: &SharedConfig,
+) -> HttpResponse {
+    let request = if body.trim().is_empty() {
+        OptimizeRequest::default()
+    } else {
+        match serde_json::from_str::<OptimizeRequest>(body) {
+            Ok(request) => request,
+            Err(e) => return HttpResponse::error(400, format!("Invalid request: {}", e)),
+        }
+    };
+    let areas = match resolve_areas(request.profile, request.areas) {
+        Ok(areas) => areas,
+        Err(e) => return HttpResponse::error(400, e),
+    };
+
+    if crate::is_optimization_running() {
+        return HttpResponse::error(409, "Another optimization is running");
+    }
+    if !limiter.check_rate_limit("http") {
+        return HttpResponse::error(
+            429,
+            format!(
+                "Rate limit exceeded ({} optimizations per minute)",
+                OPTIMIZATIONS_PER_MINUTE
+            ),
+        );
+    }
+
+    tracing::info!("HTTP API: optimization requested (areas: {:?})", areas);
+    let result = tauri::async_runtime::block_on(crate::perform_optimization(
+        app.clone(),
+        engine.clone(),
+        cfg.clone(),
+        Reason::External,
+        true,
+        areas,
+        None,
+    ));
+
+    match result {
+        Some(res) => to_response(&res),
+        None => HttpResponse::error(500, "Optimization did not complete"),
+    }
+}
+
+/// `GET /history?limit=20&offset=0&since=<unix>&until=<unix>&reason=Schedule`
+fn history_query(params: &HashMap<String, String>) -> Result<HistoryQuery, String> {
+    fn number<T: std::str::FromStr>(
+        params: &HashMap<String, String>,
+        name: &str,
+    ) -> Result<Option<T>, String> {
+        params
+            .get(name)
+            .map(|v| v.parse().map_err(|_| format!("Invalid {}: {}", name, v)))
+            .transpose()
+    }
+
+    Ok(HistoryQuery {
+        since: number(params, "since")?,
+        until: number(params, "until")?,
+        reason: params
+            .get("reason")
+            .map(|v| {
+                serde_json::from_value::<Reason>(Value::String(v.clone()))
+                    .map_err(|_| format!("Invalid reason: {}", v))
+            })
+            .transpose()?,
+        profile: None,
+        offset: number(params, "offset")?.unwrap_or(0),
+        limit: number(params, "limit")?,
+    })
+}
+
+/// Compare in constant time, an empty configured token matches nothing
+fn token_matches(given: Option<&str>, token: &str) -> bool {
+    match given {
+        Some(given) if !token.is_empty() && given.len() == token.len() => {
+            given
+                .bytes()
+                .zip(token.bytes())
+                .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+                == 0
+        }
+        _ => false,
+    }
+}
+
+/// Value of the first header called `name` (case-insensitive)
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.split("\r\n")
+        .skip(1)
+        .filter_map(|line| line.split_once(':'))
+        .find(|(n, _)| n.trim().eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+}
+
+/// Read the headers and the `Content-Length` body (bounded by MAX_REQUEST_SIZE)
+fn read_request(stream: &mut impl Read) -> Option<String> {
+    let mut data = Vec::with_capacity(512);
+    let mut buf = [0u8; 1024];
+
+    loop {
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            let head = std::str::from_utf8(&data[..end]).ok()?;
+            let length = match header(head, "content-length") {
+                Some(v) => v.parse::<usize>().ok()?,
+                None => 0,
+            };
+            let total = end + 4 + length;
+            if total > MAX_REQUEST_SIZE {
+                return None;
+            }
+            if data.len() >= total {
+                data.truncate(total);
+                break;
+            }
+        }
+
+        let read = stream.read(&mut buf).ok()?;
+        if read == 0 {
+            return None;
+        }
+        data.extend_from_slice(&buf[..read]);
+        if data.len() > MAX_REQUEST_SIZE {
+            return None;
+        }
+    }
+
+    String::from_utf8(data).ok()
+}
+
+fn parse_request(raw: &str) -> Option<HttpRequest> {
+    let (head, body) = raw.split_once("\r\n\r\n")?;
+    let mut request_line = head.split("\r\n").next()?.split(' ');
+    let method = request_line.next()?.to_ascii_uppercase();
+    let target = request_line.next()?;
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    Some(HttpRequest {
+        method,
+        path: path.trim_end_matches('/').to_ascii_lowercase(),
+        query: query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| (k.to_ascii_lowercase(), v.to_string()))
+            .collect(),
+        bearer: header(head, "authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|t| t.trim().to_string()),
+        body: body.to_string(),
+    })
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_and_parse_request() {
+        let raw = b"POST /optimize/?x=1 HTTP/1.1\r\nHost: 127.0.0.1\r\nauthorization: Bearer abc\r\nContent-Length: 20\r\n\r\n{\"profile\":\"gaming\"}trailing";
+        let text = read_request(&mut &raw[..]).unwrap();
+        let request = parse_request(&text).unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/optimize");
+        assert_eq!(request.query.get("x").map(String::as_str), Some("1"));
+        assert_eq!(request.bearer.as_deref(), Some("abc"));
+        assert_eq!(request.body, r#"{"profile":"gaming"}"#);
+
+        // Incomplete or oversized requests are refused
+        assert!(read_request(&mut &b"GET /memory HTTP/1.1\r\n"[..]).is_none());
+        let huge = format!(
+            "POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n",
+            MAX_REQUEST_SIZE
+        );
+        assert!(read_request(&mut huge.as_bytes()).is_none());
+    }
+
+    #[test]
+    fn test_token_and_history_query() {
+        assert!(token_matches(Some("secret"), "secret"));
+        assert!(!token_matches(Some("secreT"), "secret"));
+        assert!(!token_matches(None, "secret"));
+        assert!(!token_matches(Some(""), ""));
+        assert_eq!(sanitize_token(" to ken\n"), "token");
+
+        let params: HashMap<String, String> = [("limit", "5"), ("reason", "Schedule")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let query = history_query(&params).unwrap();
+        assert_eq!(query.limit, Some(5));
+        assert_eq!(query.reason, Some(Reason::Schedule));
+
+        let bad: HashMap<String, String> = [("since".to_string(), "yesterday".to_string())].into();
+        assert!(history_query(&bad).is_err());
+    }
+}
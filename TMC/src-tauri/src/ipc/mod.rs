@@ -0,0 +1,29 @@
+/// Local IPC for third-party tools
+///
+/// The SDK pipe lets other applications (game launchers, streaming suites,
+/// scripts) request an optimization and receive the result. The wire format
+/// is documented in docs/sdk-pipe-protocol.md and a minimal client lives in
+/// examples/tmc_client.rs.
+///
+/// The widget feed is the read-only alternative for widget tools: a JSON file
+/// in the data directory, documented in docs/widget-feed.md.
+///
+/// The HTTP API serves dashboards on 127.0.0.1, documented in docs/http-api.md.
+pub mod http_api;
+pub mod protocol;
+pub mod widget_feed;
+
+#[cfg(windows)]
+mod pipe_server;
+
+#[cfg(windows)]
+pub use pipe_server::start_pipe_server;
+
+#[cfg(not(windows))]
+pub fn start_pipe_server(
+    _app: tauri::AppHandle,
+    _engine: crate::engine::Engine,
+    _cfg: crate::config::SharedConfig,
+) {
+    tracing::debug!("SDK pipe is only available on Windows");
+}
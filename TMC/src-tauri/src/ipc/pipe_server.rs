@@ -0,0 +1,400 @@
+/// Named pipe server for the local SDK
+///
+/// Runs on a dedicated thread with blocking I/O and serves one client at a
+/// time, so at most one SDK optimization can be in flight; optimizations also
+/// wait for none to be running elsewhere in the app. Remote clients are
+/// rejected and every client is identified by the full path of the executable
+/// behind the pipe connection, not by anything it claims in the request. The
+/// pipe is created with an explicit DACL and as the first instance of its
+/// name, so another process cannot open or squat it first.
+use super::protocol::{
+    is_client_allowed, normalize_client_path, parse_request, resolve_areas, ErrorCode, SdkCommand,
+    SdkResponse, MAX_REQUEST_SIZE, PIPE_NAME,
+};
+use crate::config::SharedConfig;
+use crate::engine::Engine;
+use crate::logging::error_bus::{report, Subsystem};
+use crate::memory::types::Reason;
+use crate::security::RateLimiter;
+use std::ptr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+use windows_sys::Win32::Foundation::{
+    CloseHandle, GetLastError, LocalFree, ERROR_ACCESS_DENIED, ERROR_PIPE_CONNECTED, HANDLE,
+    INVALID_HANDLE_VALUE,
+};
+use windows_sys::Win32::Security::Authorization::{
+    ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows_sys::Win32::Security::{PSECURITY_DESCRIPTOR, SECURITY_ATTRIBUTES};
+use windows_sys::Win32::Storage::FileSystem::{
+    FlushFileBuffers, ReadFile, WriteFile, FILE_FLAG_FIRST_PIPE_INSTANCE, PIPE_ACCESS_DUPLEX,
+};
+use windows_sys::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, GetNamedPipeClientProcessId,
+    PIPE_READMODE_BYTE, PIPE_REJECT_REMOTE_CLIENTS, PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+
+const PIPE_BUFFER_SIZE: u32 = 4096;
+/// SYSTEM and administrators get full access, interactive users read/write
+/// only (so they cannot change the DACL); the medium integrity label with
+/// no-write-up keeps low integrity and sandboxed processes out
+const PIPE_SDDL: &str = "D:P(A;;GA;;;SY)(A;;GA;;;BA)(A;;GRGW;;;IU)S:(ML;;NW;;;ME)";
+
+static SERVER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Snapshot of the SDK settings, read before each connection
+struct SdkSettings {
+    enabled: bool,
+    allowed_clients: std::collections::BTreeSet<String>,
+    rate_limit_per_minute: u32,
+}
+
+fn read_settings(cfg: &SharedConfig) -> SdkSettings {
+    cfg.with(|c| SdkSettings {
+        enabled: c.sdk_pipe_enabled,
+        allowed_clients: c.sdk_allowed_clients.clone(),
+        rate_limit_per_minute: c.sdk_rate_limit_per_minute,
+    })
+}
+
+/// Start the SDK pipe server if it is enabled and not already running
+pub fn start_pipe_server(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    if !read_settings(&cfg).enabled {
+        return;
+    }
+    if SERVER_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("SDK pipe server already running");
+        return;
+    }
+
+    let spawn_result = std::thread::Builder::new()
+        .name("tmc-sdk-pipe".to_string())
+        .spawn(move || {
+            let _guard = scopeguard::guard((), |_| {
+                SERVER_RUNNING.store(false, Ordering::SeqCst);
+            });
+            server_loop(app, engine, cfg);
+        });
+
+    match spawn_result {
+        Ok(_) => tracing::info!("✓ SDK pipe server listening on {}", PIPE_NAME),
+        Err(e) => {
+            SERVER_RUNNING.store(false, Ordering::SeqCst);
+            tracing::error!("✗ Failed to start SDK pipe server: {}", e);
+        }
+    }
+}
+
+struct PipeGuard(HANDLE);
+
+impl Drop for PipeGuard {
+    fn drop(&mut self) {
+        unsafe {
+            FlushFileBuffers(self.0);
+            DisconnectNamedPipe(self.0);
+            CloseHandle(self.0);
+        }
+    }
+}
+
+fn server_loop(app: AppHandle, engine: Engine, cfg: SharedConfig) {
+    let pipe_name = crate::to_wide(PIPE_NAME);
+    let mut limiter_rate = 0u32;
+    let mut limiter = RateLimiter::new(1, Duration::from_secs(60));
+    // Never fall back to the default DACL
+    let security = match PipeSecurity::new() {
+        Some(security) => security,
+        None => return,
+    };
+
+    loop {
+        let pipe = unsafe {
+            CreateNamedPipeW(
+                pipe_name.as_ptr(),
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE,
+                PIPE_TYPE_BYTE | PIPE_READMODE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                1,
+                PIPE_BUFFER_SIZE,
+                PIPE_BUFFER_SIZE,
+                0,
+                &security.attributes,
+            )
+        };
+        if pipe == INVALID_HANDLE_VALUE {
+            let error = unsafe { GetLastError() };
+            let message = if error == ERROR_ACCESS_DENIED {
+                format!("SDK pipe {} is already in use by another process", PIPE_NAME)
+            } else {
+                format!("Failed to create SDK pipe {} (error {})", PIPE_NAME, error)
+            };
+            tracing::warn!("{}", message);
+            report(Subsystem::Ipc, message);
+            std::thread::sleep(Duration::from_secs(5));
+            continue;
+        }
+        let guard = PipeGuard(pipe);
+
+        let connected = unsafe {
+            ConnectNamedPipe(pipe, ptr::null_mut()) != 0 || GetLastError() == ERROR_PIPE_CONNECTED
+        };
+        if !connected {
+            continue;
+        }
+
+        // Settings may change while we wait for a client
+        let settings = read_settings(&cfg);
+        if !settings.enabled {
+            write_response(
+                pipe,
+                &SdkResponse::failure(None, ErrorCode::Disabled, "SDK pipe is disabled"),
+            );
+            drop(guard);
+            tracing::info!("SDK pipe disabled, stopping server");
+            return;
+        }
+
+        if settings.rate_limit_per_minute != limiter_rate {
+            limiter_rate = settings.rate_limit_per_minute;
+            limiter = RateLimiter::new(limiter_rate as usize, Duration::from_secs(60));
+        }
+
+        let response = handle_client(pipe, &settings, &mut limiter, &app, &engine, &cfg);
+        write_response(pipe, &response);
+    }
+}
+
+fn handle_client(
+    pipe: HANDLE,
+    settings: &SdkSettings,
+    limiter: &mut RateLimiter,
+    app: &AppHandle,
+    engine: &Engine,
+    cfg: &SharedConfig,
+) -> SdkResponse {
+    let client = client_image_path(pipe).unwrap_or_default();
+    if !is_client_allowed(&client, &settings.allowed_clients) {
+        tracing::warn!("SDK pipe: rejected client '{}'", client);
+        return SdkResponse::failure(
+            None,
+            ErrorCode::NotAllowed,
+            "Client is not in the SDK allowlist",
+        );
+    }
+
+    let raw = match read_request(pipe) {
+        Some(r) => r,
+        None => {
+            return SdkResponse::failure(None, ErrorCode::BadRequest, "Request too large or empty")
+        }
+    };
+    let request = match parse_request(&raw) {
+        Ok(r) => r,
+        Err(response) => return response,
+    };
+
+    match request.command {
+        SdkCommand::Ping => SdkResponse::success(
+            request.id,
+            serde_json::json!({
+                "app_version": crate::config::app_info::VERSION,
+                "protocol_version": super::protocol::PROTOCOL_VERSION,
+            }),
+        ),
+        SdkCommand::MemoryInfo => match engine.memory() {
+            Ok(info) => to_response(request.id, &info),
+            Err(e) => SdkResponse::failure(request.id, ErrorCode::Failed, e.to_string()),
+        },
+        SdkCommand::LastResult => to_response(request.id, &crate::stats::last_record()),
+        SdkCommand::Optimize { profile, areas } => {
+            let areas = match resolve_areas(profile, areas) {
+                Ok(areas) => areas,
+                Err(e) => return SdkResponse::failure(request.id, ErrorCode::BadRequest, e),
+            };
+
+            if crate::is_optimization_running() {
+                return SdkResponse::failure(
+                    request.id,
+                    ErrorCode::Busy,
+                    "Another optimization is running",
+                );
+            }
+
+            let client_key = normalize_client_path(&client);
+            if !limiter.check_rate_limit(&client_key) {
+                return SdkResponse::failure(
+                    request.id,
+                    ErrorCode::RateLimited,
+                    format!(
+                        "Rate limit exceeded ({} optimizations per minute)",
+                        settings.rate_limit_per_minute
+                    ),
+                );
+            }
+
+            tracing::info!(
+                "SDK pipe: optimization requested by '{}' (areas: {:?})",
+                client_key,
+                areas
+            );
+            let result = tauri::async_runtime::block_on(crate::perform_optimization(
+                app.clone(),
+                engine.clone(),
+                cfg.clone(),
+                Reason::External,
+                true,
+                areas,
+                None,
+            ));
+
+            match result {
+                Some(res) => to_response(request.id, &res),
+                None => SdkResponse::failure(
+                    request.id,
+                    ErrorCode::Failed,
+                    "Optimization did not complete",
+                ),
+            }
+        }
+    }
+}
+
+fn to_response<T: serde::Serialize>(id: Option<serde_json::Value>, value: &T) -> SdkResponse {
+    match serde_json::to_value(value) {
+        Ok(value) => SdkResponse::success(id, value),
+        Err(e) => SdkResponse::failure(id, ErrorCode::Failed, e.to_string()),
+    }
+}
+
+/// Read one newline-terminated request (bounded by MAX_REQUEST_SIZE)
+fn read_request(pipe: HANDLE) -> Option<String> {
+    let mut data = Vec::with_capacity(256);
+    let mut buf = [0u8; 512];
+
+    loop {
+        let mut read = 0u32;
+        let ok = unsafe {
+            ReadFile(
+                pipe,
+                buf.as_mut_ptr(),
+                buf.len() as u32,
+                &mut read,
+                ptr::null_mut(),
+            )
+        };
+        if ok == 0 || read == 0 {
+            break;
+        }
+        data.extend_from_slice(&buf[..read as usize]);
+        if data.len() > MAX_REQUEST_SIZE {
+            return None;
+        }
+        if data.contains(&b'\n') {
+            break;
+        }
+    }
+
+    let text = String::from_utf8(data).ok()?;
+    let line = text.lines().next()?.trim().to_string();
+    if line.is_empty() {
+        None
+    } else {
+        Some(line)
+    }
+}
+
+fn write_response(pipe: HANDLE, response: &SdkResponse) {
+    let line = response.to_line();
+    let bytes = line.as_bytes();
+    let mut offset = 0usize;
+    while offset < bytes.len() {
+        let mut written = 0u32;
+        let ok = unsafe {
+            WriteFile(
+                pipe,
+                bytes[offset..].as_ptr(),
+                (bytes.len() - offset) as u32,
+                &mut written,
+                ptr::null_mut(),
+            )
+        };
+        if ok == 0 || written == 0 {
+            tracing::debug!("SDK pipe: client disconnected before the response was sent");
+            return;
+        }
+        offset += written as usize;
+    }
+}
+
+/// Full executable path of the process connected to the pipe
+fn client_image_path(pipe: HANDLE) -> Option<String> {
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let mut pid = 0u32;
+        if GetNamedPipeClientProcessId(pipe, &mut pid) == 0 || pid == 0 {
+            return None;
+        }
+
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buf.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        if ok == 0 {
+            return None;
+        }
+
+        Some(String::from_utf16_lossy(&buf[..len as usize]))
+    }
+}
+
+/// Security attributes built from `PIPE_SDDL`, freed on drop
+struct PipeSecurity {
+    attributes: SECURITY_ATTRIBUTES,
+}
+
+impl PipeSecurity {
+    fn new() -> Option<Self> {
+        let sddl = crate::to_wide(PIPE_SDDL);
+        let mut descriptor: PSECURITY_DESCRIPTOR = ptr::null_mut();
+        let ok = unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                ptr::null_mut(),
+            )
+        };
+        if ok == 0 || descriptor.is_null() {
+            tracing::error!(
+                "Failed to build the SDK pipe security descriptor (error {})",
+                unsafe { GetLastError() }
+            );
+            return None;
+        }
+        Some(Self {
+            attributes: SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor,
+                bInheritHandle: 0,
+            },
+        })
+    }
+}
+
+impl Drop for PipeSecurity {
+    fn drop(&mut self) {
+        unsafe {
+            LocalFree(self.attributes.lpSecurityDescriptor);
+        }
+    }
+}
@@ -0,0 +1,255 @@
+/// SDK pipe protocol (version 1)
+///
+/// Each connection carries a single request: one JSON object terminated by a
+/// newline. The server answers with one JSON object terminated by a newline
+/// and closes the connection. See docs/sdk-pipe-protocol.md for examples.
+use crate::memory::types::Areas;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+pub const PIPE_NAME: &str = r"\\.\pipe\tmc";
+pub const PROTOCOL_VERSION: u32 = 1;
+/// Requests larger than this are rejected
+pub const MAX_REQUEST_SIZE: usize = 4096;
+
+/// A request sent by a client
+#[derive(Debug, Deserialize)]
+pub struct SdkRequest {
+    /// Opaque value echoed back in the response
+    #[serde(default)]
+    pub id: Option<Value>,
+    #[serde(flatten)]
+    pub command: SdkCommand,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum SdkCommand {
+    /// Check that the server is reachable and the client is allowed
+    Ping,
+    /// Current physical, commit and page file usage
+    MemoryInfo,
+    /// Run an optimization; without a profile or areas the configured ones are used
+    Optimize {
+        #[serde(default)]
+        profile: Option<String>,
+        /// Areas string (`STANDBY_LIST|WORKING_SET`), exclusive with `profile`
+        #[serde(default)]
+        areas: Option<String>,
+    },
+    /// The most recent recorded optimization, whatever triggered it
+    LastResult,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    BadRequest,
+    NotAllowed,
+    RateLimited,
+    Busy,
+    Disabled,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SdkError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// Response sent back to the client
+#[derive(Debug, Clone, Serialize)]
+pub struct SdkResponse {
+    pub version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SdkError>,
+}
+
+impl SdkResponse {
+    pub fn success(id: Option<Value>, result: Value) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            id,
+            ok: true,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn failure(id: Option<Value>, code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            version: PROTOCOL_VERSION,
+            id,
+            ok: false,
+            result: None,
+            error: Some(SdkError {
+                code,
+                message: message.into(),
+            }),
+        }
+    }
+
+    /// Serialize as a single newline-terminated line
+    pub fn to_line(&self) -> String {
+        let mut line = serde_json::to_string(self).unwrap_or_else(|_| {
+            r#"{"version":1,"ok":false,"error":{"code":"failed","message":"serialization error"}}"#
+                .to_string()
+        });
+        line.push('\n');
+        line
+    }
+}
+
+/// Parse a raw request line
+pub fn parse_request(raw: &str) -> Result<SdkRequest, SdkResponse> {
+    serde_json::from_str::<SdkRequest>(raw.trim()).map_err(|e| {
+        SdkResponse::failure(None, ErrorCode::BadRequest, format!("Invalid request: {}", e))
+    })
+}
+
+/// Areas for an `optimize` request; `None` runs the configured profile
+///
+/// Shared with the HTTP API, which takes the same two fields.
+pub fn resolve_areas(
+    profile: Option<String>,
+    areas: Option<String>,
+) -> Result<Option<Areas>, String> {
+    match (profile, areas) {
+        (Some(_), Some(_)) => Err("Use either profile or areas, not both".to_string()),
+        (Some(profile), None) => {
+            crate::cli::parser::parse_profile(&profile).map(|p| Some(p.get_memory_areas()))
+        }
+        (None, Some(areas)) => match crate::cli::parser::parse_areas(&areas)? {
+            a if a.is_empty() => Err("No memory areas given".to_string()),
+            a => Ok(Some(a)),
+        },
+        (None, None) => Ok(None),
+    }
+}
+
+/// Normalize an executable path for allowlist comparisons
+/// (`C:/Tools/OBS64.exe` -> `c:\tools\obs64.exe`)
+pub fn normalize_client_path(path: &str) -> String {
+    path.trim().replace('/', "\\").to_lowercase()
+}
+
+/// Whether `path` is the absolute path of an executable
+///
+/// A bare file name is not an identity: any program can be copied or
+/// renamed to `obs64.exe`, so the allowlist only holds full paths.
+pub fn is_valid_client_path(path: &str) -> bool {
+    let path = normalize_client_path(path);
+    let bytes = path.as_bytes();
+    let drive = bytes.len() > 3 && bytes[0].is_ascii_alphabetic() && &bytes[1..3] == b":\\";
+    let unc = path.starts_with("\\\\");
+    (drive || unc)
+        && path.ends_with(".exe")
+        && !path.split('\\').any(|part| part == "..")
+        && !path
+            .chars()
+            .any(|c| c.is_control() || matches!(c, '"' | '*' | '?' | '<' | '>' | '|'))
+}
+
+/// Check the full path of a client executable against the configured allowlist
+///
+/// An empty allowlist denies every client.
+pub fn is_client_allowed(client: &str, allowlist: &BTreeSet<String>) -> bool {
+    let client = normalize_client_path(client);
+    is_valid_client_path(&client)
+        && allowlist.iter().any(|allowed| {
+            is_valid_client_path(allowed) && normalize_client_path(allowed) == client
+        })
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request() {
+        let req = parse_request(r#"{"id": 7, "command": "optimize", "profile": "gaming"}"#).unwrap();
+        assert_eq!(req.id, Some(Value::from(7)));
+        assert_eq!(
+            req.command,
+            SdkCommand::Optimize {
+                profile: Some("gaming".to_string()),
+                areas: None,
+            }
+        );
+        assert_eq!(parse_request(r#"{"command":"ping"}"#).unwrap().command, SdkCommand::Ping);
+        assert_eq!(
+            parse_request(r#"{"command":"memory_info"}"#).unwrap().command,
+            SdkCommand::MemoryInfo
+        );
+        assert_eq!(
+            parse_request(r#"{"command":"last_result"}"#).unwrap().command,
+            SdkCommand::LastResult
+        );
+        assert_eq!(
+            parse_request(r#"{"command":"optimize","areas":"STANDBY_LIST|WORKING_SET"}"#)
+                .unwrap()
+                .command,
+            SdkCommand::Optimize {
+                profile: None,
+                areas: Some("STANDBY_LIST|WORKING_SET".to_string()),
+            }
+        );
+
+        let err = parse_request(r#"{"command":"format_c"}"#).unwrap_err();
+        assert_eq!(err.error.unwrap().code, ErrorCode::BadRequest);
+    }
+
+    #[test]
+    fn test_resolve_areas() {
+        assert_eq!(resolve_areas(None, None), Ok(None));
+        assert_eq!(
+            resolve_areas(None, Some("STANDBY_LIST|WORKING_SET".to_string())),
+            Ok(Some(Areas::STANDBY_LIST | Areas::WORKING_SET))
+        );
+        assert!(resolve_areas(Some("gaming".to_string()), None)
+            .unwrap()
+            .is_some());
+        assert!(
+            resolve_areas(Some("gaming".to_string()), Some("WORKING_SET".to_string())).is_err()
+        );
+        assert!(resolve_areas(Some("turbo".to_string()), None).is_err());
+    }
+
+    #[test]
+    fn test_client_allowlist() {
+        let allowlist: BTreeSet<String> = [
+            r"C:\Program Files (x86)\Steam\Steam.exe".to_string(),
+            "C:/Tools/obs64.exe".to_string(),
+            "notepad.exe".to_string(),
+        ]
+        .into();
+        assert!(is_client_allowed(
+            r"c:\program files (x86)\steam\steam.exe",
+            &allowlist
+        ));
+        assert!(is_client_allowed(r"C:\Tools\OBS64.EXE", &allowlist));
+        // Same name elsewhere, and bare names, are not an identity
+        assert!(!is_client_allowed(
+            r"C:\Users\me\Downloads\obs64.exe",
+            &allowlist
+        ));
+        assert!(!is_client_allowed(r"C:\Windows\notepad.exe", &allowlist));
+        assert!(!is_client_allowed("notepad.exe", &allowlist));
+        assert!(!is_client_allowed(
+            r"C:\Tools\..\Tools\obs64.exe",
+            &allowlist
+        ));
+        assert!(!is_client_allowed(
+            r"C:\Program Files (x86)\Steam\Steam.exe",
+            &BTreeSet::new()
+        ));
+    }
+}
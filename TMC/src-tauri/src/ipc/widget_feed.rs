@@ -0,0 +1,146 @@
+/// Widget data feed
+///
+/// Widget tools (Rainmeter, Zebar, ...) cannot talk to the SDK pipe easily, so
+/// when enabled TMC keeps `widget.json` in the data directory up to date with
+/// the current memory usage and the last optimization. The file is replaced
+/// atomically, readers never see a half-written document. The schema is
+/// documented in docs/widget-feed.md.
+use crate::config::SharedConfig;
+use crate::engine::Engine;
+use crate::memory::types::{MemoryInfo, Reason};
+use serde::Serialize;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const WIDGET_FEED_FILE_NAME: &str = "widget.json";
+/// Bumped on breaking changes of the document layout
+pub const WIDGET_FEED_SCHEMA: u32 = 1;
+
+static FEED_RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Serialize)]
+pub struct WidgetFeed {
+    pub schema: u32,
+    pub app_version: &'static str,
+    /// Unix timestamp (seconds) of this snapshot
+    pub updated: u64,
+    /// Seconds until the next snapshot, readers can treat older files as stale
+    pub interval_secs: u32,
+    pub optimizing: bool,
+    pub profile: String,
+    pub memory: Option<WidgetMemory>,
+    pub last_clean: Option<WidgetLastClean>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WidgetMemory {
+    pub load_percent: u32,
+    pub physical_total_bytes: u64,
+    pub physical_used_bytes: u64,
+    pub physical_free_bytes: u64,
+    pub commit_total_bytes: u64,
+    pub commit_used_bytes: u64,
+}
+
+impl From<&MemoryInfo> for WidgetMemory {
+    fn from(info: &MemoryInfo) -> Self {
+        Self {
+            load_percent: info.load_percent,
+            physical_total_bytes: info.physical.total.bytes,
+            physical_used_bytes: info.physical.used.bytes,
+            physical_free_bytes: info.physical.free.bytes,
+            commit_total_bytes: info.commit.total.bytes,
+            commit_used_bytes: info.commit.used.bytes,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct WidgetLastClean {
+    pub timestamp: u64,
+    pub reason: Reason,
+    pub profile: String,
+    pub freed_physical_bytes: i64,
+    pub duration_ms: u128,
+}
+
+fn feed_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(WIDGET_FEED_FILE_NAME)
+}
+
+/// Feed settings, `None` while the feed is disabled
+fn read_settings(cfg: &SharedConfig) -> Option<(u32, String)> {
+    cfg.with(|c| {
+        c.widget_feed_enabled
+            .then(|| (c.widget_feed_interval_secs, c.active_profile_name()))
+    })
+}
+
+fn snapshot(engine: &Engine, interval_secs: u32, profile: String) -> WidgetFeed {
+    WidgetFeed {
+        schema: WIDGET_FEED_SCHEMA,
+        app_version: env!("CARGO_PKG_VERSION"),
+        updated: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        interval_secs,
+        optimizing: crate::is_optimization_running(),
+        profile,
+        memory: engine.memory().ok().as_ref().map(WidgetMemory::from),
+        last_clean: crate::stats::last_record().map(|r| WidgetLastClean {
+            timestamp: r.timestamp,
+            reason: r.reason,
+            profile: r.profile,
+            freed_physical_bytes: r.freed_physical_bytes,
+            duration_ms: r.duration_ms,
+        }),
+    }
+}
+
+fn write_feed(feed: &WidgetFeed) -> std::io::Result<()> {
+    let path = feed_path();
+    let temp_path = path.with_extension("json.tmp");
+    let json = serde_json::to_string_pretty(feed)?;
+    fs::write(&temp_path, json)?;
+    fs::rename(&temp_path, &path)
+}
+
+/// Start updating the widget feed if it is enabled and not already running
+pub fn start_widget_feed(engine: Engine, cfg: SharedConfig) {
+    if read_settings(&cfg).is_none() {
+        return;
+    }
+    if FEED_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("Widget feed already running");
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-widget-feed".into())
+        .spawn(move || {
+            tracing::info!("Widget feed started: {}", feed_path().display());
+            // Re-read the settings every tick so disabling the feed stops the thread
+            while let Some((interval_secs, profile)) = read_settings(&cfg) {
+                if let Err(e) = write_feed(&snapshot(&engine, interval_secs, profile)) {
+                    // A widget may hold the file open for a moment, retry next tick
+                    tracing::debug!("Failed to update widget feed: {}", e);
+                }
+                std::thread::sleep(Duration::from_secs(interval_secs as u64));
+            }
+
+            // Stale data is worse than none
+            let _ = fs::remove_file(feed_path());
+            FEED_RUNNING.store(false, Ordering::SeqCst);
+            tracing::info!("Widget feed stopped");
+        });
+
+    if let Err(e) = spawned {
+        FEED_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start widget feed thread: {}", e);
+    }
+}
@@ -0,0 +1,229 @@
+/// Recent errors reported by the background subsystems
+///
+/// Failures in hotkeys, tray, engine, scheduler, notifications or the SDK pipe
+/// usually happen without a window to show them in, so they only reached the
+/// log. Subsystems report them here as well: the last `MAX_RECENT_ERRORS` are
+/// kept in `recent_errors.json` in the data directory and the UI is told how
+/// many it has not seen yet through the `errors-changed` event.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter};
+
+pub const ERRORS_FILE_NAME: &str = "recent_errors.json";
+pub const EV_ERRORS_CHANGED: &str = "errors-changed";
+const MAX_RECENT_ERRORS: usize = 100;
+const MAX_MESSAGE_LEN: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Hotkeys,
+    Tray,
+    Engine,
+    Scheduler,
+    Notifications,
+    Ipc,
+    Config,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentError {
+    /// Increases with every report, including folded repeats
+    #[serde(default)]
+    pub seq: u64,
+    /// Unix timestamp (seconds) of the last occurrence
+    pub timestamp: u64,
+    pub subsystem: Subsystem,
+    pub message: String,
+    /// Consecutive occurrences folded into this entry
+    #[serde(default = "default_count")]
+    pub count: u32,
+}
+
+fn default_count() -> u32 {
+    1
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ErrorLog {
+    #[serde(default)]
+    next_seq: u64,
+    /// Errors up to this sequence number have been shown to the user
+    #[serde(default)]
+    last_seen: u64,
+    #[serde(default)]
+    errors: VecDeque<RecentError>,
+}
+
+impl ErrorLog {
+    fn unseen(&self) -> usize {
+        self.errors
+            .iter()
+            .filter(|e| e.seq > self.last_seen)
+            .count()
+    }
+}
+
+/// Errors and the number not seen yet, newest first
+#[derive(Debug, Clone, Serialize)]
+pub struct RecentErrors {
+    pub unseen: usize,
+    pub errors: Vec<RecentError>,
+}
+
+static LOG: Lazy<Mutex<ErrorLog>> = Lazy::new(|| Mutex::new(load()));
+static APP: Lazy<Mutex<Option<AppHandle>>> = Lazy::new(|| Mutex::new(None));
+
+fn errors_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(ERRORS_FILE_NAME)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load() -> ErrorLog {
+    fs::read_to_string(errors_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save(log: &ErrorLog) {
+    let result = serde_json::to_string_pretty(log)
+        .map_err(|e| e.to_string())
+        .and_then(|json| fs::write(errors_path(), json).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        tracing::debug!("Failed to persist recent errors: {}", e);
+    }
+}
+
+fn emit_changed(unseen: usize) {
+    if let Some(app) = APP.lock().as_ref() {
+        let _ = app.emit(EV_ERRORS_CHANGED, unseen);
+    }
+}
+
+/// Connect the bus to the app so reports reach the UI
+pub fn init(app: AppHandle) {
+    *APP.lock() = Some(app);
+    let unseen = LOG.lock().unseen();
+    if unseen > 0 {
+        emit_changed(unseen);
+    }
+}
+
+fn push(log: &mut ErrorLog, subsystem: Subsystem, message: String, now: u64) {
+    log.next_seq += 1;
+    let seq = log.next_seq;
+
+    // A failure repeating in a loop (e.g. the pipe server) stays one entry
+    match log.errors.back_mut() {
+        Some(last) if last.subsystem == subsystem && last.message == message => {
+            last.count = last.count.saturating_add(1);
+            last.seq = seq;
+            last.timestamp = now;
+        }
+        _ => {
+            log.errors.push_back(RecentError {
+                seq,
+                timestamp: now,
+                subsystem,
+                message,
+                count: 1,
+            });
+            while log.errors.len() > MAX_RECENT_ERRORS {
+                log.errors.pop_front();
+            }
+        }
+    }
+}
+
+/// Record an error from a subsystem
+///
+/// Callers keep logging through tracing; this only makes the failure visible
+/// in the UI.
+pub fn report(subsystem: Subsystem, message: impl Into<String>) {
+    let mut message = message.into();
+    if message.len() > MAX_MESSAGE_LEN {
+        let mut end = MAX_MESSAGE_LEN;
+        while !message.is_char_boundary(end) {
+            end -= 1;
+        }
+        message.truncate(end);
+    }
+
+    let unseen = {
+        let mut log = LOG.lock();
+        push(&mut log, subsystem, message, unix_now());
+        save(&log);
+        log.unseen()
+    };
+    emit_changed(unseen);
+}
+
+/// Recent errors, optionally marking them as seen
+pub fn recent_errors(mark_seen: bool) -> RecentErrors {
+    let mut log = LOG.lock();
+    let result = RecentErrors {
+        unseen: log.unseen(),
+        errors: log.errors.iter().rev().cloned().collect(),
+    };
+
+    if mark_seen && result.unseen > 0 {
+        log.last_seen = log.next_seq;
+        save(&log);
+        drop(log);
+        emit_changed(0);
+    }
+    result
+}
+
+/// Remove all recorded errors
+pub fn clear() {
+    {
+        let mut log = LOG.lock();
+        *log = ErrorLog::default();
+        save(&log);
+    }
+    emit_changed(0);
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_errors_are_folded_and_bounded() {
+        let mut log = ErrorLog::default();
+        push(&mut log, Subsystem::Ipc, "pipe".to_string(), 1);
+        push(&mut log, Subsystem::Ipc, "pipe".to_string(), 2);
+        assert_eq!(log.errors.len(), 1);
+        assert_eq!(log.errors[0].count, 2);
+        assert_eq!(log.errors[0].timestamp, 2);
+
+        for i in 0..(MAX_RECENT_ERRORS as u64 + 10) {
+            push(&mut log, Subsystem::Engine, format!("error {}", i), 10 + i);
+        }
+        assert_eq!(log.errors.len(), MAX_RECENT_ERRORS);
+        assert_eq!(log.unseen(), MAX_RECENT_ERRORS);
+
+        log.last_seen = log.next_seq;
+        assert_eq!(log.unseen(), 0);
+        // A repeat of a seen error counts as new
+        let last = log.errors.back().unwrap().message.clone();
+        push(&mut log, Subsystem::Engine, last, 500);
+        assert_eq!(log.unseen(), 1);
+    }
+}
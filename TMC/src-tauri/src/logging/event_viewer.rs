@@ -1,11 +1,25 @@
 // src-tauri/src/logging/event_viewer.rs
 
+//
+// Events go to two places:
+// - the "Tommy-Memory-Cleaner/Operational" channel (Applications and Services
+//   Logs) through the manifest-based ETW provider in windows/events.man, with
+//   structured fields; the manifest is installed with wevtutil on first run
+//   (as administrator) or by the installer and removed on uninstall
+// - the classic Application log, only while the provider is not installed
+
+use crate::utils::to_wide;
 use anyhow::Result;
 use once_cell::sync::Lazy;
 use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
+use windows_sys::core::GUID;
 use windows_sys::Win32::Foundation::{GetLastError, HANDLE};
+use windows_sys::Win32::System::Diagnostics::Etw::{
+    EventRegister, EventUnregister, EventWrite, EVENT_DATA_DESCRIPTOR, EVENT_DESCRIPTOR,
+};
 use windows_sys::Win32::System::EventLog::*;
 use windows_sys::Win32::System::Registry::*;
 
@@ -17,9 +31,210 @@ const REGISTRY_PATH: &str =
 const EVENT_ID_STARTUP: u32 = 100;
 const EVENT_ID_SHUTDOWN: u32 = 200;
 const EVENT_ID_OPTIMIZATION: u32 = 1000;
+const EVENT_ID_OPTIMIZATION_WARNINGS: u32 = 1001;
 const EVENT_ID_AUTO_OPTIMIZATION: u32 = 1100;
 const EVENT_ID_ERROR: u32 = 2000;
 
+// ========== ETW PROVIDER ==========
+/// Provider GUID, must match windows/events.man
+const PROVIDER_GUID: GUID = GUID::from_u128(0x24543396_220c_4cb4_8dff_287c1a0407bd);
+const PROVIDER_MANIFEST: &str = include_str!("../../windows/events.man");
+const PUBLISHER_KEY: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\WINEVT\Publishers\{24543396-220c-4cb4-8dff-287c1a0407bd}";
+/// Channel value of "Tommy-Memory-Cleaner/Operational" in the manifest
+const CHANNEL_OPERATIONAL: u8 = 16;
+
+const LEVEL_ERROR: u8 = 2;
+const LEVEL_WARNING: u8 = 3;
+const LEVEL_INFORMATIONAL: u8 = 4;
+
+// Se il manifest è installato gli eventi vanno solo nel canale dedicato
+static PROVIDER_INSTALLED: Lazy<AtomicBool> =
+    Lazy::new(|| AtomicBool::new(registered_resource_file().is_some()));
+
+/// Registration handle of the ETW provider
+struct EtwProvider {
+    handle: i64,
+}
+
+impl EtwProvider {
+    fn register() -> Option<Self> {
+        let mut handle = 0i64;
+        let status = unsafe { EventRegister(&PROVIDER_GUID, None, null_mut(), &mut handle) };
+        if status == 0 {
+            Some(Self { handle })
+        } else {
+            tracing::debug!("EventRegister failed: {}", status);
+            None
+        }
+    }
+
+    fn write(&self, event_id: u32, level: u8, fields: &[EtwField]) {
+        let descriptor = EVENT_DESCRIPTOR {
+            Id: event_id as u16,
+            Version: 0,
+            Channel: CHANNEL_OPERATIONAL,
+            Level: level,
+            Opcode: 0,
+            Task: 0,
+            Keyword: 0,
+        };
+        let data: Vec<EVENT_DATA_DESCRIPTOR> = fields.iter().map(EtwField::descriptor).collect();
+        let status =
+            unsafe { EventWrite(self.handle, &descriptor, data.len() as u32, data.as_ptr()) };
+        if status != 0 {
+            tracing::debug!("EventWrite({}) failed: {}", event_id, status);
+        }
+    }
+}
+
+impl Drop for EtwProvider {
+    fn drop(&mut self) {
+        unsafe {
+            EventUnregister(self.handle);
+        }
+    }
+}
+
+static ETW_PROVIDER: Lazy<Option<EtwProvider>> = Lazy::new(EtwProvider::register);
+
+/// A template field, in the order and type declared in the manifest
+enum EtwField {
+    /// win:UnicodeString, NUL terminated
+    Str(Vec<u16>),
+    /// win:Int64 / win:UInt64
+    Int(u64),
+    /// win:UInt32
+    U32(u32),
+    /// win:UInt8
+    U8(u8),
+}
+
+impl EtwField {
+    fn str(s: &str) -> Self {
+        EtwField::Str(to_wide(s))
+    }
+
+    fn descriptor(&self) -> EVENT_DATA_DESCRIPTOR {
+        let (ptr, size) = match self {
+            EtwField::Str(wide) => (wide.as_ptr() as u64, (wide.len() * 2) as u32),
+            EtwField::Int(v) => (v as *const u64 as u64, 8),
+            EtwField::U32(v) => (v as *const u32 as u64, 4),
+            EtwField::U8(v) => (v as *const u8 as u64, 1),
+        };
+        let mut descriptor: EVENT_DATA_DESCRIPTOR = unsafe { std::mem::zeroed() };
+        descriptor.Ptr = ptr;
+        descriptor.Size = size;
+        descriptor
+    }
+}
+
+fn write_etw(event_id: u32, level: u8, fields: &[EtwField]) {
+    if let Some(provider) = ETW_PROVIDER.as_ref() {
+        provider.write(event_id, level, fields);
+    }
+}
+
+/// Resource file the installed manifest points to, `None` if not installed
+fn registered_resource_file() -> Option<String> {
+    unsafe {
+        let mut hkey: HKEY = null_mut();
+        let path = to_wide(PUBLISHER_KEY);
+        if RegOpenKeyExW(HKEY_LOCAL_MACHINE, path.as_ptr(), 0, KEY_READ, &mut hkey) != 0 {
+            return None;
+        }
+
+        let value_name = to_wide("ResourceFileName");
+        let mut buffer = [0u16; 1024];
+        let mut size = (buffer.len() * 2) as u32;
+        let mut value_type = 0u32;
+        let result = RegQueryValueExW(
+            hkey,
+            value_name.as_ptr(),
+            null_mut(),
+            &mut value_type,
+            buffer.as_mut_ptr() as *mut u8,
+            &mut size,
+        );
+        RegCloseKey(hkey);
+
+        if result != 0 {
+            // Chiave presente ma senza valore: il provider è comunque installato
+            return Some(String::new());
+        }
+        let len = (size as usize / 2).min(buffer.len());
+        let value = String::from_utf16_lossy(&buffer[..len]);
+        Some(value.trim_end_matches('\0').to_string())
+    }
+}
+
+/// Run `wevtutil <command> <manifest> <options>` with the manifest in a temporary file
+fn run_wevtutil(command: &str, options: &[String]) -> Result<()> {
+    use std::os::windows::process::CommandExt;
+
+    let manifest_path = std::env::temp_dir().join("TommyMemoryCleaner.events.man");
+    std::fs::write(&manifest_path, PROVIDER_MANIFEST)?;
+
+    let output = std::process::Command::new("wevtutil")
+        .arg(command)
+        .arg(&manifest_path)
+        .args(options)
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .output();
+    let _ = std::fs::remove_file(&manifest_path);
+
+    let output = output?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "wevtutil {} failed: {}",
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Install the event manifest for the current executable (requires administrator)
+///
+/// Re-registers when the executable moved since the last registration.
+pub fn register_provider() -> Result<()> {
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_string_lossy();
+    if registered_resource_file().is_some_and(|f| f.eq_ignore_ascii_case(&exe)) {
+        PROVIDER_INSTALLED.store(true, Ordering::Relaxed);
+        return Ok(());
+    }
+
+    // Un'installazione precedente con un altro percorso va rimossa prima
+    let _ = run_wevtutil("um", &[]);
+    run_wevtutil("im", &[format!("/rf:{}", exe), format!("/mf:{}", exe)])?;
+
+    PROVIDER_INSTALLED.store(true, Ordering::Relaxed);
+    tracing::info!("Event log provider registered for {}", exe);
+    Ok(())
+}
+
+/// Remove the event manifest and the classic event source (uninstall cleanup)
+pub fn unregister_provider() -> Result<()> {
+    let result = run_wevtutil("um", &[]);
+    PROVIDER_INSTALLED.store(false, Ordering::Relaxed);
+
+    unsafe {
+        let path = to_wide(REGISTRY_PATH);
+        RegDeleteKeyW(HKEY_LOCAL_MACHINE, path.as_ptr());
+    }
+
+    result
+}
+
+/// First-run registration, in the background so startup is not delayed
+pub fn ensure_provider_registered() {
+    std::thread::spawn(|| {
+        if let Err(e) = register_provider() {
+            tracing::debug!("Event log provider not registered (OK if not admin): {}", e);
+        }
+    });
+}
+
 // Wrapper thread-safe per HANDLE
 struct SafeHandle {
     handle: *mut std::ffi::c_void,
@@ -234,17 +449,6 @@ static EVENT_LOGGER: Lazy<Arc<Mutex<Option<EventLoggerInner>>>> =
         }
     });
 
-// Helper per convertire stringhe in wide strings Windows
-fn to_wide(s: &str) -> Vec<u16> {
-    use std::ffi::OsStr;
-    use std::os::windows::ffi::OsStrExt;
-
-    OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 // Funzione helper per ottenere timestamp formattato
 fn get_timestamp() -> String {
     use std::time::SystemTime;
@@ -299,6 +503,11 @@ pub fn log_startup_event(version: &str, config_loaded: bool) {
         get_timestamp()
     );
 
+    write_etw(
+        EVENT_ID_STARTUP,
+        LEVEL_INFORMATIONAL,
+        &[EtwField::str(version), EtwField::U32(std::process::id())],
+    );
     write_log(EVENTLOG_INFORMATION_TYPE, EVENT_ID_STARTUP, &message);
 }
 
@@ -313,12 +522,17 @@ pub fn log_shutdown_event() {
         get_timestamp()
     );
 
+    write_etw(
+        EVENT_ID_SHUTDOWN,
+        LEVEL_INFORMATIONAL,
+        &[EtwField::U32(std::process::id())],
+    );
     write_log(EVENTLOG_INFORMATION_TYPE, EVENT_ID_SHUTDOWN, &message);
 }
 
 /// Log di un'ottimizzazione completata
 pub fn log_optimization_event(
-    memory_freed_mb: f64,
+    freed_bytes: i64,
     profile: &str,
     mode: &str,
     areas: &str,
@@ -345,7 +559,7 @@ pub fn log_optimization_event(
         {}",
         profile,
         mode,
-        memory_freed_mb,
+        freed_bytes as f64 / 1024.0 / 1024.0,
         duration_ms,
         areas,
         if success {
@@ -361,6 +575,23 @@ pub fn log_optimization_event(
         }
     );
 
+    let (etw_id, etw_level) = if success {
+        (EVENT_ID_OPTIMIZATION, LEVEL_INFORMATIONAL)
+    } else {
+        (EVENT_ID_OPTIMIZATION_WARNINGS, LEVEL_WARNING)
+    };
+    write_etw(
+        etw_id,
+        etw_level,
+        &[
+            EtwField::Int(freed_bytes as u64),
+            EtwField::str(mode),
+            EtwField::str(profile),
+            EtwField::Int(duration_ms.min(u64::MAX as u128) as u64),
+            EtwField::str(areas),
+            EtwField::str(&errors.join("\n")),
+        ],
+    );
     write_log(event_type, EVENT_ID_OPTIMIZATION, &message);
 }
 
@@ -377,6 +608,11 @@ pub fn log_auto_optimization_event(reason: &str, threshold: u8) {
         get_timestamp()
     );
 
+    write_etw(
+        EVENT_ID_AUTO_OPTIMIZATION,
+        LEVEL_INFORMATIONAL,
+        &[EtwField::str(reason), EtwField::U8(threshold)],
+    );
     write_log(
         EVENTLOG_INFORMATION_TYPE,
         EVENT_ID_AUTO_OPTIMIZATION,
@@ -395,11 +631,17 @@ pub fn log_error_event(error: &str) {
         get_timestamp()
     );
 
+    write_etw(EVENT_ID_ERROR, LEVEL_ERROR, &[EtwField::str(error)]);
     write_log(EVENTLOG_ERROR_TYPE, EVENT_ID_ERROR, &message);
 }
 
-// Funzione helper interna per scrivere i log
+// Funzione helper interna per scrivere i log nel registro Application
 fn write_log(event_type: u16, event_id: u32, message: &str) {
+    // Con il provider installato gli eventi sono già nel canale dedicato
+    if PROVIDER_INSTALLED.load(Ordering::Relaxed) {
+        return;
+    }
+
     // FIX: Non crashare se il logging fallisce - usa catch_unwind
     let result = std::panic::catch_unwind(|| {
         if let Ok(guard) = EVENT_LOGGER.lock() {
@@ -435,6 +677,15 @@ mod tests {
         assert!(handle.is_valid());
     }
 
+    #[test]
+    fn test_etw_field_descriptors() {
+        // UnicodeString includes the NUL terminator
+        let field = EtwField::str("ab");
+        assert_eq!(field.descriptor().Size, 6);
+        assert_eq!(EtwField::Int(1).descriptor().Size, 8);
+        assert_eq!(EtwField::U8(1).descriptor().Size, 1);
+    }
+
     #[test]
     fn test_timestamp() {
         let ts = get_timestamp();
@@ -0,0 +1,278 @@
+/// Rolling JSON Lines log file
+///
+/// The release build has no console, so every event at INFO and above (DEBUG
+/// in debug builds) is also appended as one JSON object per line to
+/// `logs/tmc.jsonl` in the data directory. The file is rotated when it passes
+/// `MAX_LOG_BYTES` or the day changes; rotated files are named after their day
+/// and only the newest `MAX_ROTATED_FILES` are kept.
+use chrono::{DateTime, Local, NaiveDate, SecondsFormat};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+pub const LOG_DIR_NAME: &str = "logs";
+pub const LOG_FILE_NAME: &str = "tmc.jsonl";
+const ROTATED_PREFIX: &str = "tmc-";
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: usize = 7;
+pub const DEFAULT_RECENT_LOGS: usize = 200;
+pub const MAX_RECENT_LOGS: usize = 2000;
+
+/// One line of the log file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogEntry {
+    /// Local time, RFC 3339 with milliseconds
+    pub ts: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields of the event other than the message
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    pub fields: Map<String, Value>,
+}
+
+struct ActiveFile {
+    file: File,
+    day: NaiveDate,
+    size: u64,
+}
+
+/// Tracing layer appending events to the log file
+///
+/// Nothing on the write path may log through `tracing`: the event would come
+/// back to this layer while the file lock is held.
+pub struct FileLogLayer {
+    dir: PathBuf,
+    active: Mutex<Option<ActiveFile>>,
+}
+
+/// Directory of the log files
+pub fn log_dir() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(LOG_DIR_NAME)
+}
+
+impl FileLogLayer {
+    /// Layer writing to `log_dir()`, `None` if the directory cannot be created
+    pub fn new() -> Option<Self> {
+        let dir = log_dir();
+        fs::create_dir_all(&dir).ok()?;
+        Some(Self {
+            dir,
+            active: Mutex::new(None),
+        })
+    }
+
+    fn write_entry(&self, entry: &LogEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(_) => return,
+        };
+        let today = Local::now().date_naive();
+
+        let mut active = self.active.lock();
+        if let Some(current) = active.as_ref() {
+            if current.day != today || current.size >= MAX_LOG_BYTES {
+                let day = current.day;
+                // Close the file before renaming it
+                *active = None;
+                rotate(&self.dir, day);
+            }
+        }
+        if active.is_none() {
+            *active = open_active(&self.dir, today);
+        }
+        if let Some(current) = active.as_mut() {
+            if writeln!(current.file, "{}", line).is_ok() {
+                current.size += line.len() as u64 + 1;
+            }
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for FileLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let meta = event.metadata();
+        self.write_entry(&LogEntry {
+            ts: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: meta.level().to_string(),
+            target: meta.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+/// Collects the message and the other fields of an event as JSON values
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        if field.name() == "message" {
+            self.message = match value {
+                Value::String(s) => s,
+                other => other.to_string(),
+            };
+        } else {
+            self.fields.insert(field.name().to_string(), value);
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        // The formatted message arrives here as fmt::Arguments
+        self.insert(field, Value::from(format!("{:?}", value)));
+    }
+}
+
+fn file_day(path: &Path) -> Option<NaiveDate> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some(DateTime::<Local>::from(modified).date_naive())
+}
+
+/// Open the active file, rotating a leftover from an earlier day or a full one
+fn open_active(dir: &Path, today: NaiveDate) -> Option<ActiveFile> {
+    let path = dir.join(LOG_FILE_NAME);
+    if let Ok(meta) = fs::metadata(&path) {
+        let day = file_day(&path).unwrap_or(today);
+        if day != today || meta.len() >= MAX_LOG_BYTES {
+            rotate(dir, day);
+        }
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .ok()?;
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    Some(ActiveFile {
+        file,
+        day: today,
+        size,
+    })
+}
+
+/// Rename the active file after its day and drop the oldest rotated files
+fn rotate(dir: &Path, day: NaiveDate) {
+    let stem = format!("{}{}", ROTATED_PREFIX, day.format("%Y-%m-%d"));
+    let mut target = dir.join(format!("{}.jsonl", stem));
+    let mut n = 1;
+    while target.exists() {
+        target = dir.join(format!("{}.{}.jsonl", stem, n));
+        n += 1;
+    }
+    if fs::rename(dir.join(LOG_FILE_NAME), &target).is_err() {
+        return;
+    }
+
+    let rotated = rotated_files(dir);
+    for old in rotated.iter().skip(MAX_ROTATED_FILES) {
+        let _ = fs::remove_file(old);
+    }
+}
+
+/// Rotated log files, newest first
+fn rotated_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with(ROTATED_PREFIX) && n.ends_with(".jsonl"))
+                })
+                .filter_map(|p| Some((fs::metadata(&p).and_then(|m| m.modified()).ok()?, p)))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files.into_iter().map(|(_, p)| p).collect()
+}
+
+/// Parse log lines, skipping corrupted or truncated ones
+pub fn parse_log(content: &str) -> Vec<LogEntry> {
+    content
+        .lines()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect()
+}
+
+/// The last `limit` log entries, newest first
+///
+/// Continues into the rotated files when the active file has fewer entries.
+pub fn recent_logs(limit: usize) -> Vec<LogEntry> {
+    let dir = log_dir();
+    let limit = limit.clamp(1, MAX_RECENT_LOGS);
+    let mut entries = Vec::new();
+
+    let files = std::iter::once(dir.join(LOG_FILE_NAME)).chain(rotated_files(&dir));
+    for path in files {
+        let content = match fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(_) => continue,
+        };
+        let needed = limit - entries.len();
+        entries.extend(parse_log(&content).into_iter().rev().take(needed));
+        if entries.len() >= limit {
+            break;
+        }
+    }
+    entries
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_skips_truncated_lines() {
+        let content = concat!(
+            r#"{"ts":"2026-10-16T09:00:00.000+02:00","level":"INFO","target":"tmc","message":"a"}"#,
+            "\n",
+            r#"{"ts":"2026-10-16T09:00:01.000+02:00","level":"WARN","target":"tmc","#,
+            "\n",
+        );
+        let entries = parse_log(content);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "a");
+        assert!(entries[0].fields.is_empty());
+    }
+}
@@ -1,6 +1,10 @@
+pub mod error_bus;
 pub mod event_viewer;
+pub mod file_log;
+pub mod startup_timing;
 
 use std::sync::Once;
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::prelude::*;
 
 static INIT: Once = Once::new();
@@ -11,7 +15,17 @@ pub fn init() {
             .with_target(false)
             .with_ansi(cfg!(debug_assertions));
 
-        let subscriber = tracing_subscriber::registry().with(fmt_layer);
+        // The windows_subsystem build has no console, the file keeps the log
+        let file_level = if cfg!(debug_assertions) {
+            LevelFilter::DEBUG
+        } else {
+            LevelFilter::INFO
+        };
+        let file_layer = file_log::FileLogLayer::new().map(|l| l.with_filter(file_level));
+
+        let subscriber = tracing_subscriber::registry()
+            .with(fmt_layer)
+            .with(file_layer);
 
         let _ = tracing::subscriber::set_global_default(subscriber);
 
@@ -0,0 +1,150 @@
+/// Startup phase timing
+///
+/// Each launch measures how long TMC spends in its startup phases, from the
+/// first line of `main` until the main webview finished loading. The last
+/// launches are kept in `startup_timings.json` together with the app version,
+/// so a slower startup can be traced back to the release that introduced it.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+pub const STARTUP_TIMINGS_FILE_NAME: &str = "startup_timings.json";
+const MAX_STARTUP_RECORDS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    PrivilegeInit,
+    ConfigLoad,
+    WindowCreate,
+    TrayBuild,
+    WebviewReady,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    /// Milliseconds from process start to the beginning of the phase
+    pub start_ms: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupRecord {
+    pub version: String,
+    /// Unix timestamp (seconds) of the launch
+    pub timestamp: u64,
+    /// Milliseconds from process start until the webview was ready
+    pub total_ms: u64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+struct Recorder {
+    started: Instant,
+    phases: Vec<PhaseTiming>,
+    finished: bool,
+}
+
+static RECORDER: Lazy<Mutex<Recorder>> = Lazy::new(|| {
+    Mutex::new(Recorder {
+        started: Instant::now(),
+        phases: Vec::new(),
+        finished: false,
+    })
+});
+
+fn timings_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(STARTUP_TIMINGS_FILE_NAME)
+}
+
+fn millis_between(from: Instant, to: Instant) -> u64 {
+    to.saturating_duration_since(from).as_millis() as u64
+}
+
+/// Start the clock; call first thing in `main`
+pub fn begin() {
+    Lazy::force(&RECORDER);
+}
+
+/// Run `f` and record it as `phase`
+pub fn measure<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+    record(phase, start, Instant::now());
+    result
+}
+
+/// Record a phase that started at `start` and ends now
+pub fn record_since(phase: Phase, start: Instant) {
+    record(phase, start, Instant::now());
+}
+
+fn record(phase: Phase, start: Instant, end: Instant) {
+    let mut recorder = RECORDER.lock();
+    if recorder.finished {
+        return;
+    }
+    let started = recorder.started;
+    recorder.phases.push(PhaseTiming {
+        phase,
+        start_ms: millis_between(started, start),
+        duration_ms: millis_between(start, end),
+    });
+}
+
+/// Close the measurement once the main webview is ready (the tray when
+/// starting minimized) and persist it
+pub fn finish() {
+    let record = {
+        let mut recorder = RECORDER.lock();
+        if recorder.finished {
+            return;
+        }
+        recorder.finished = true;
+        StartupRecord {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            total_ms: millis_between(recorder.started, Instant::now()),
+            phases: recorder.phases.clone(),
+        }
+    };
+
+    tracing::info!(
+        "Startup completed in {} ms ({})",
+        record.total_ms,
+        record
+            .phases
+            .iter()
+            .map(|p| format!("{:?}: {} ms", p.phase, p.duration_ms))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut records = startup_timings();
+    records.insert(0, record);
+    records.truncate(MAX_STARTUP_RECORDS);
+    match serde_json::to_string_pretty(&records) {
+        Ok(json) => {
+            if let Err(e) = fs::write(timings_path(), json) {
+                tracing::debug!("Failed to save startup timings: {}", e);
+            }
+        }
+        Err(e) => tracing::debug!("Failed to serialize startup timings: {}", e),
+    }
+}
+
+/// Recorded launches, newest first
+pub fn startup_timings() -> Vec<StartupRecord> {
+    fs::read_to_string(timings_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
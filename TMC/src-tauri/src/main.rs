@@ -15,33 +15,50 @@
 /// - Security checks
 mod antivirus;
 mod auto_optimizer;
+mod benchmark;
 mod cli;
 mod commands;
 mod config;
 mod engine;
 mod hotkeys;
+mod i18n;
+mod ipc;
 mod logging;
 mod memory;
 mod notifications;
 mod os;
 mod security;
+mod stats;
+mod storage;
 mod system;
 mod ui;
+mod updater;
+mod utils;
 
-use crate::auto_optimizer::start_auto_optimizer;
-use crate::cli::run_console_mode;
+use crate::auto_optimizer::{start_auto_optimizer, start_game_mode};
+use crate::cli::{is_cli_invocation, run_console_mode};
 use crate::commands::{position_tray_menu, show_or_create_window};
-use crate::config::{Config, Profile};
-use crate::engine::Engine;
+use crate::config::{Config, ConfigService, Profile, SharedConfig};
+use crate::engine::{Engine, OptimizeResult, Throttled};
 use crate::hotkeys::{cmd_register_hotkey, register_global_hotkey_v2};
+use crate::logging::error_bus::{report, Subsystem};
+use crate::logging::startup_timing::{self, Phase};
 use crate::memory::types::{Areas, Reason};
-use crate::notifications::{register_app_for_notifications, show_windows_notification};
-use crate::ui::bridge::{emit_progress, EV_DONE};
+use crate::notifications::{
+    register_app_for_notifications, set_app_user_model_id, show_windows_notification,
+    NotificationVerbosity,
+};
+use crate::ui::bridge::{
+    emit_progress, ProgressEvent, EV_DONE, EV_NOTHING_FREED, EV_OPTIMIZATION_RESULT,
+    EV_OPTIMIZATION_THROTTLED,
+};
+#[cfg(windows)]
+use crate::utils::to_wide;
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::webview::WebviewWindowBuilder;
 use tauri::WebviewUrl;
 use tauri::{AppHandle, Emitter, Manager};
@@ -56,6 +73,8 @@ static OPTIMIZATION_RUNNING: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(fal
 static PRIVILEGES_INITIALIZED: Lazy<RwLock<bool>> = Lazy::new(|| RwLock::new(false));
 /// Tracks if first optimization has been completed
 static FIRST_OPTIMIZATION_DONE: Lazy<AtomicBool> = Lazy::new(|| AtomicBool::new(false));
+/// Result of the last finished optimization (not persisted)
+static LAST_RESULT: Lazy<RwLock<Option<OptimizeResult>>> = Lazy::new(|| RwLock::new(None));
 /// Stores the tray icon ID for updates
 pub(crate) static TRAY_ICON_ID: Lazy<std::sync::Mutex<Option<String>>> =
     Lazy::new(|| std::sync::Mutex::new(None));
@@ -63,61 +82,12 @@ pub(crate) static TRAY_ICON_ID: Lazy<std::sync::Mutex<Option<String>>> =
 /// Application state shared across Tauri commands
 #[derive(Clone)]
 struct AppState {
-    cfg: Arc<Mutex<Config>>,
+    cfg: SharedConfig,
     engine: Engine,
-    translations: crate::commands::TranslationState,
     rate_limiter: Arc<Mutex<crate::security::RateLimiter>>,
 }
 
-// ============= WINDOWS HELPERS =============
-#[cfg(windows)]
-/// Convert UTF-8 string to Windows wide string (UTF-16)
-fn to_wide(s: &str) -> Vec<u16> {
-    use std::os::windows::ffi::OsStrExt;
-    std::ffi::OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 // ============= PRIVILEGE MANAGEMENT =============
-/// Restart the application with elevated privileges
-#[cfg(windows)]
-fn restart_with_elevation() -> Result<(), Box<dyn std::error::Error>> {
-    use std::env;
-    use windows_sys::Win32::UI::Shell::ShellExecuteW;
-    use windows_sys::Win32::Foundation::GetLastError;
-    
-    let current_exe = env::current_exe()?;
-    let exe_path = current_exe.to_string_lossy();
-    
-    tracing::info!("Restarting application with elevated privileges...");
-    
-    // Keep the wide string alive for the duration of the call
-    let runas = to_wide("runas");
-    let exe_wide = exe_path.encode_utf16().chain(std::iter::once(0)).collect::<Vec<_>>();
-    
-    let result = unsafe {
-        ShellExecuteW(
-            std::ptr::null_mut(), // HWND null
-            runas.as_ptr(),
-            exe_wide.as_ptr(),
-            std::ptr::null_mut(),
-            std::ptr::null_mut(),
-            1, // SW_SHOWNORMAL
-        )
-    };
-    
-    // ShellExecuteW returns HINSTANCE (HANDLE) which is isize in windows-sys
-    if (result as isize) <= 32 {
-        let error_code = unsafe { GetLastError() };
-        tracing::error!("Failed to restart with elevation. ShellExecuteW returned: {:?}, GetLastError: {}", result as isize, error_code);
-        Err(format!("Failed to restart with elevation (code: {:?}, error: {})", result as isize, error_code).into())
-    } else {
-        std::process::exit(0);
-    }
-}
-
 /// Initialize required Windows privileges for memory optimization
 ///
 /// This function ensures the process has the necessary privileges
@@ -193,6 +163,7 @@ fn parse_areas_string(areas_str: &str) -> Areas {
             "STANDBY_LIST_LOW" => result |= Areas::STANDBY_LIST_LOW,
             "SYSTEM_FILE_CACHE" => result |= Areas::SYSTEM_FILE_CACHE,
             "WORKING_SET" => result |= Areas::WORKING_SET,
+            "MEMORY_COMPRESSION" => result |= Areas::MEMORY_COMPRESSION,
             "" => {} // Ignore empty strings
             unknown => {
                 tracing::warn!(
@@ -212,6 +183,16 @@ fn parse_areas_string(areas_str: &str) -> Areas {
 // code_from_str moved to hotkeys/codes.rs
 
 // ============= OPTIMIZATION LOGIC =============
+/// Returns true while an optimization is in progress
+pub(crate) fn is_optimization_running() -> bool {
+    OPTIMIZATION_RUNNING.load(Ordering::SeqCst)
+}
+
+/// Result of the last finished optimization since the app started
+pub(crate) fn last_optimization_result() -> Option<OptimizeResult> {
+    LAST_RESULT.read().clone()
+}
+
 /// Perform memory optimization with specified parameters
 ///
 /// This is the core optimization function that:
@@ -219,21 +200,24 @@ fn parse_areas_string(areas_str: &str) -> Areas {
 /// - Ensures proper privileges are acquired
 /// - Executes optimization with progress updates
 /// - Handles cleanup and error recovery
+///
+/// Returns the result, or `None` if another optimization was running or it failed.
 async fn perform_optimization(
     app: AppHandle,
     engine: Engine,
-    cfg: Arc<Mutex<Config>>,
+    cfg: SharedConfig,
     reason: Reason,
     with_progress: bool,
     areas_override: Option<Areas>,
-) {
+    process_targets: Option<Vec<String>>,
+) -> Option<OptimizeResult> {
     // Check if optimization is already running
     if OPTIMIZATION_RUNNING
         .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
         .is_err()
     {
         tracing::info!("Optimization already running, skipping");
-        return;
+        return None;
     }
 
     // Use scopeguard to ensure flag is always released
@@ -297,157 +281,207 @@ async fn perform_optimization(
         tracing::info!("First optimization setup complete, proceeding with optimization");
     }
 
-    let (areas, _show_notif, profile, _language) = {
-        match cfg.lock() {
-            Ok(c) => {
-                // If areas_override is specified, use it, otherwise use areas from profile
-                let areas = if let Some(override_areas) = areas_override {
-                    override_areas
-                } else {
-                    // This is important because available areas can change or have been saved
-                    // with a previous version of Windows
-                    c.profile.get_memory_areas()
-                };
-                tracing::info!(
-                    "Profile: {:?}, Areas: {:?} ({} areas, override: {})",
-                    c.profile,
-                    areas,
-                    areas.bits().count_ones(),
-                    areas_override.is_some()
-                );
-                (
-                    areas,
-                    c.show_opt_notifications || reason == Reason::Manual,
-                    c.profile.clone(),
-                    c.language.clone(),
-                )
-            }
-            Err(_) => (
-                areas_override.unwrap_or(Areas::WORKING_SET),
-                true,
-                Profile::Balanced,
-                "en".to_string(),
-            ),
-        }
+    let (areas, _show_notif, profile, custom_profile, _language) = {
+        let c = cfg.read();
+        // If areas_override is specified, use it, otherwise use areas from profile
+        let areas = if let Some(override_areas) = areas_override {
+            override_areas
+        } else {
+            // This is important because available areas can change or have been saved
+            // with a previous version of Windows
+            c.effective_areas()
+        };
+        tracing::info!(
+            "Profile: {}, Areas: {:?} ({} areas, override: {})",
+            c.active_profile_name(),
+            areas,
+            areas.bits().count_ones(),
+            areas_override.is_some()
+        );
+        (
+            areas,
+            // An emergency run is always worth telling about
+            c.show_opt_notifications || matches!(reason, Reason::Manual | Reason::Emergency),
+            c.profile.clone(),
+            c.active_custom().map(|p| p.name.clone()),
+            c.language.clone(),
+        )
     };
 
     // Execute optimization
     let _before = engine.memory().ok();
 
     let result = if with_progress {
-        engine.optimize(
+        engine.optimize_with_targets(
             reason,
             areas,
-            Some(|v, t, s: String| emit_progress(&app, v, t, &s)),
+            process_targets,
+            false,
+            Some(|event| emit_progress(&app, event)),
         )
     } else {
-        engine.optimize::<fn(u8, u8, String)>(reason, areas, None)
+        engine.optimize_with_targets::<fn(ProgressEvent)>(
+            reason,
+            areas,
+            process_targets,
+            false,
+            None,
+        )
     };
 
+    // Record the run in the persistent optimization history
+    match &result {
+        Ok(res) => {
+            let profile_label = custom_profile
+                .clone()
+                .unwrap_or_else(|| format!("{:?}", profile));
+            crate::stats::record_optimization(res, &profile_label);
+            crate::stats::prometheus::record(res);
+            crate::stats::usage::record_run(&cfg.read(), res, None);
+            crate::stats::session::record_run(res);
+            *LAST_RESULT.write() = Some(res.clone());
+            crate::ui::tray::note_optimization();
+            let _ = app.emit(EV_OPTIMIZATION_RESULT, res);
+
+            for area in res.areas.iter() {
+                if let Some(err) = &area.error {
+                    report(Subsystem::Engine, format!("{} failed: {}", area.name, err));
+                }
+            }
+        }
+        Err(e) => match e.downcast_ref::<Throttled>() {
+            Some(throttled) => {
+                let _ = app.emit(EV_OPTIMIZATION_THROTTLED, throttled);
+            }
+            None => report(Subsystem::Engine, format!("Optimization failed: {}", e)),
+        },
+    }
+    let outcome = result.as_ref().ok().cloned();
+
     // Delay for metrics stabilization
     tokio::time::sleep(Duration::from_millis(300)).await;
 
     let after = engine.memory().ok();
 
     if with_progress {
+        // Lets the UI say "Already optimal" instead of "Done"
+        if let Some(res) = outcome.as_ref().filter(|r| r.freed_nothing()) {
+            let _ = app.emit(
+                EV_NOTHING_FREED,
+                serde_json::json!({
+                    "freed_physical_mb": res.freed_physical_bytes as f64 / 1024.0 / 1024.0
+                }),
+            );
+        }
         let _ = app.emit(EV_DONE, ());
     }
 
-    // FIX: Verify notification setting (reload from disk to be sure)
-    let show_notif = {
-        // Force reload config to pick up changes from Setup
-        match crate::config::Config::load() {
-            Ok(loaded) => loaded.show_opt_notifications,
-            Err(_) => {
-                // Fallback to memory if load fails
-                if let Ok(guard) = cfg.lock() {
-                    guard.show_opt_notifications
-                } else {
-                    true
-                }
-            }
-        }
-    };
+    // Setup writes the shared config and live reload brings in external edits;
+    // loading from disk here would mark a pending edit as synced and drop it
+    let (show_notif, verbosity, notify_nothing_freed) = cfg.with(|c| {
+        (
+            c.show_opt_notifications,
+            c.effective_notification_verbosity(),
+            c.notify_nothing_freed,
+        )
+    });
 
     // Debug log to verify logic
-    tracing::info!("Notification check: show_settings={}, reason={:?}", show_notif, reason);
+    tracing::info!(
+        "Notification check: show_settings={}, verbosity={:?}, reason={:?}",
+        show_notif,
+        verbosity,
+        reason
+    );
 
     // Check if notifications are globally disabled for this reason
     if !show_notif && reason != Reason::Manual {
         tracing::debug!("Notifications disabled in config, suppressing");
         // Only suppress if NOT manual (user clicked Optimize Now)
-        return; 
+        return outcome;
+    } else if verbosity == NotificationVerbosity::Silent {
+        // The tray tooltip already carries the last result, refresh it right away
+        tracing::debug!("Silent notifications for this profile, updating the tray only");
+        if let Ok(res) = &result {
+            // Memory stats in the UI still count silent runs
+            let freed_mb = res.freed_physical_bytes.abs() as f64 / 1024.0 / 1024.0;
+            let _ = app.emit(
+                "optimization-completed",
+                serde_json::json!({ "freed_physical_mb": freed_mb }),
+            );
+        }
+        crate::ui::tray::refresh_tray_icon(&app);
+        return outcome;
     } else if show_notif || reason == Reason::Manual {
         if let (Ok(res), Some(aft)) = (result, after) {
             let freed_mb = res.freed_physical_bytes.abs() as f64 / 1024.0 / 1024.0;
             let free_gb = aft.physical.free.bytes as f64 / 1024.0 / 1024.0 / 1024.0;
 
             // Verify that at least one area was successfully optimized
-            let has_successful_area = res.areas.iter().any(|a| a.error.is_none());
+            let has_successful_area = res
+                .areas
+                .iter()
+                .any(|a| a.error.is_none() && a.skipped.is_none());
+            // Memory was already optimal: say so rather than "Freed: 0.0 MB" or nothing
+            let nothing_freed = notify_nothing_freed && res.freed_nothing();
 
             // Show notification only if:
             // 1. We freed at least 1MB OR
-            // 2. We have at least one successfully optimized area (even if little memory freed)
-            if freed_mb > 1.0 || has_successful_area {
-                // Use cached translations from frontend
+            // 2. We have at least one successfully optimized area (even if little memory freed) OR
+            // 3. There was nothing to free and the user wants to know
+            if freed_mb > 1.0 || has_successful_area || nothing_freed {
                 let title_key = match reason {
                     Reason::Manual => "TMC • Optimization completed",
                     Reason::Schedule => "TMC • Scheduled optimization",
                     Reason::LowMemory => "TMC • Low memory optimization",
                     Reason::Hotkey => "TMC • Hotkey optimization",
+                    Reason::External => "TMC • Optimization completed",
+                    Reason::Predicted => "TMC • Predicted pressure optimization",
+                    Reason::Emergency => "TMC • Emergency optimization",
                 };
+                let title = crate::i18n::tr(title_key);
 
-                let title = {
-                    let state = app.state::<AppState>();
-                    crate::commands::get_translation(&state.translations, title_key)
-                };
-
-                // Format notification body using translations
                 let profile_key = match profile {
                     Profile::Normal => "Normal",
                     Profile::Balanced => "Balanced",
                     Profile::Gaming => "Gaming",
                 };
 
-                let profile_name = {
-                    let state = app.state::<AppState>();
-                    crate::commands::get_translation(&state.translations, profile_key)
+                // Custom profile names are shown as the user typed them
+                let profile_name = match &custom_profile {
+                    Some(name) => name.clone(),
+                    None => crate::i18n::tr(profile_key),
                 };
 
-                let body_template = {
-                    let state = app.state::<AppState>();
-                    crate::commands::get_translation(
-                        &state.translations,
-                        "✅ Freed: %.1f MB\n🧠 Free RAM: %.2f GB\n🎯 Profile: %s",
+                let body = if nothing_freed {
+                    crate::notifications::get_nothing_freed_body(
+                        free_gb,
+                        &profile_name,
+                        verbosity == NotificationVerbosity::Detailed,
+                    )
+                } else if verbosity == NotificationVerbosity::Detailed {
+                    crate::notifications::get_notification_body(
+                        freed_mb.abs(),
+                        free_gb,
+                        &profile_name,
+                        res.benchmark.as_ref(),
                     )
+                } else {
+                    crate::notifications::get_notification_summary(freed_mb.abs())
                 };
 
-                let body = body_template
-                    .replace("%.1f", &format!("{:.1}", freed_mb.abs()))
-                    .replace("%.2f", &format!("{:.2}", free_gb))
-                    .replace("%s", &profile_name);
-
                 // Emit event to frontend for memory stats tracking
                 let event_result = app.emit("optimization-completed", serde_json::json!({
                     "freed_physical_mb": freed_mb.abs()
                 }));
                 tracing::debug!("Emitted optimization-completed event with {} MB freed, result: {:?}", freed_mb.abs(), event_result);
                 // Get current theme from configuration
-                let theme = {
-                    let state = app.state::<AppState>();
-                    let theme_result = match state.cfg.try_lock() {
-                        Ok(cfg_guard) => cfg_guard.theme.clone(),
-                        Err(_) => {
-                            tracing::debug!("Config lock busy when getting theme for notification, using default");
-                            "dark".to_string()
-                        }
-                    };
-                    theme_result
-                };
+                let theme = app.state::<AppState>().cfg.with(|c| c.theme.clone());
                 tracing::info!(
-                    "Attempting to show notification - freed: {:.2} MB, has_successful_area: {}",
+                    "Attempting to show notification - freed: {:.2} MB, has_successful_area: {}, nothing_freed: {}",
                     freed_mb,
-                    has_successful_area
+                    has_successful_area,
+                    nothing_freed
                 );
                 match show_windows_notification(&app, &title, &body, &theme) {
                     Ok(_) => tracing::info!("✓ Notification sent successfully"),
@@ -460,6 +494,7 @@ async fn perform_optimization(
     }
 
     // The flag is automatically released by the guard
+    outcome
 }
 
 // ============= TAURI COMMANDS =============
@@ -472,7 +507,9 @@ async fn perform_optimization(
 
 // ============= TRAY MENU MANAGEMENT (ROBUST) =============
 /// Show tray menu with retry and robust fallbacks
-async fn show_tray_menu_with_retry(app: &AppHandle) {
+///
+/// `anchor` is the click position on the tray icon (physical pixels).
+async fn show_tray_menu_with_retry(app: &AppHandle, anchor: Option<(i32, i32)>) {
     const MAX_RETRIES: u32 = 3;
     const RETRY_DELAY_MS: u64 = 100;
 
@@ -516,10 +553,7 @@ async fn show_tray_menu_with_retry(app: &AppHandle) {
             }
 
             // Posiziona prima di mostrare (evita lampeggio)
-            position_tray_menu(&menu_win);
-
-            // Piccolo delay per assicurarsi che il posizionamento sia completato
-            tokio::time::sleep(Duration::from_millis(50)).await;
+            position_tray_menu(&menu_win, anchor);
 
             // Mostra il menu con retry
             match menu_win.show() {
@@ -577,7 +611,10 @@ async fn show_tray_menu_with_retry(app: &AppHandle) {
                 "tray_menu",
                 WebviewUrl::App("tray.html".into())
             )
-            .inner_size(160.0, 120.0)
+            .inner_size(
+                crate::commands::ui::TRAY_MENU_SIZE.0,
+                crate::commands::ui::TRAY_MENU_SIZE.1,
+            )
             .skip_taskbar(true)
             .decorations(false)
             .transparent(true)
@@ -608,10 +645,7 @@ async fn show_tray_menu_with_retry(app: &AppHandle) {
                     });
 
                     // Posiziona prima di mostrare
-                    position_tray_menu(&menu_win);
-
-                    // Piccolo delay per assicurarsi che il posizionamento sia completato
-                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    position_tray_menu(&menu_win, anchor);
 
                     // Mostra la finestra
                     match menu_win.show() {
@@ -680,6 +714,10 @@ async fn show_tray_menu_with_retry(app: &AppHandle) {
     }
 
     tracing::error!("Failed to show tray menu after {} attempts", MAX_RETRIES);
+    report(
+        Subsystem::Tray,
+        format!("Tray menu could not be shown after {} attempts", MAX_RETRIES),
+    );
 }
 
 // ============= WEBVIEW2 CHECK =============
@@ -745,15 +783,29 @@ fn check_webview2() {
 
 // ============= MAIN ENTRY POINT =============
 fn main() {
+    // Start measuring startup phases before anything else
+    startup_timing::begin();
+
     // Initialize logging
     logging::init();
 
-    // Console mode: check if there are command line arguments
-    let args: Vec<String> = std::env::args().skip(1).collect();
-    if !args.is_empty() {
+    // Move data left behind by older versions before anything reads it
+    config::migration::run_startup_migration();
+
+    // Console mode: subcommands or legacy /Options (GUI flags like --startup-config are ignored,
+    // --portable is valid in both modes and only picks the data directory)
+    let args: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|a| a != config::PORTABLE_ARG)
+        .collect();
+    if is_cli_invocation(&args) {
         return run_console_mode(&args);
     }
 
+    // Language pack completeness check (diagnostics only)
+    #[cfg(debug_assertions)]
+    crate::commands::i18n::log_missing_translations();
+
     // WebView2 check (Windows only)
     #[cfg(windows)]
     check_webview2();
@@ -762,42 +814,18 @@ fn main() {
     // This forces Windows to use the registered DisplayName instead of AppUserModelID
     // IMPORTANT: This function MUST be called before any other Windows API
     // that might use AppUserModelID (like shell notifications, jump lists, etc.)
-    #[cfg(windows)]
-    {
-        use std::ffi::OsStr;
-        use std::os::windows::ffi::OsStrExt;
-        use windows_sys::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+    set_app_user_model_id();
 
-        let app_id = "TommyMemoryCleaner";
-        let app_id_wide: Vec<u16> = OsStr::new(app_id).encode_wide().chain(Some(0)).collect();
-
-        unsafe {
-            // SetCurrentProcessExplicitAppUserModelID returns HRESULT:
-            // S_OK (0) = success
-            // Other values = error
-            let result = SetCurrentProcessExplicitAppUserModelID(app_id_wide.as_ptr());
-            if result == 0 {
-                tracing::info!("✓ AppUserModelID set explicitly: {}", app_id);
-                eprintln!("[TMC] AppUserModelID set explicitly: {}", app_id);
-            } else {
-                // Log error but don't block the app (some Windows versions might not support it)
-                tracing::warn!(
-                    "✗ Failed to set AppUserModelID explicitly: HRESULT 0x{:08X}",
-                    result
-                );
-                tracing::debug!(
-                    "This may cause notifications to show AppID instead of DisplayName"
-                );
-                eprintln!(
-                    "[TMC] ERROR: Failed to set AppUserModelID explicitly: HRESULT 0x{:08X}",
-                    result
-                );
-            }
-        }
-    }
+    // Install the dedicated Event Log channel on first run (administrator only,
+    // no-op once registered for this executable)
+    logging::event_viewer::ensure_provider_registered();
 
     // Register app for Windows Toast notifications BEFORE everything else
     // This is critical to correctly show name and icon in notifications
+    // Icons are converted in the background and cached in the data dir
+    crate::ui::icon_cache::start_icon_cache();
+    // Keep the config schema next to config.json for editors and scripts
+    crate::config::schema::install_schema();
     #[cfg(windows)]
     {
         register_app_for_notifications();
@@ -824,11 +852,15 @@ fn main() {
                             }
                         }
                         
-                        // If not elevated, run via task scheduler
+                        // If not elevated, run via task scheduler, else ask through UAC
                         if !is_elevated {
                             tracing::info!("Running via elevated task...");
                             if let Err(e) = run_via_elevated_task() {
                                 tracing::error!("Failed to run via elevated task: {}", e);
+                                // Only returns if the relaunch did not happen
+                                if let Err(e) = crate::system::elevation::relaunch_as_admin() {
+                                    tracing::warn!("Continuing without elevation: {}", e);
+                                }
                             }
                         }
                     }
@@ -839,7 +871,10 @@ fn main() {
         if is_elevated {
             tracing::info!("Application running with elevated privileges");
         } else {
-            tracing::warn!("Application running without elevated privileges - some features may be limited");
+            tracing::warn!(
+                "Application running without elevated privileges, unavailable areas: {:?}",
+                crate::system::elevation::unavailable_areas().get_names()
+            );
         }
     }
     
@@ -852,6 +887,7 @@ fn main() {
     // Initialize privileges at startup with retry
     // IMPORTANT: Privileges must be acquired BEFORE first optimization
     // Some privileges might require elevated privileges, but we try anyway
+    let privileges_start = Instant::now();
     let mut retry_count = 0;
     let max_retries = 3;
     while retry_count < max_retries {
@@ -886,6 +922,8 @@ fn main() {
         }
     }
 
+    startup_timing::record_since(Phase::PrivilegeInit, privileges_start);
+
     // Register app as trusted to reduce antivirus false positives
     #[cfg(windows)]
     if let Err(e) = antivirus::whitelist::register_as_trusted() {
@@ -893,10 +931,17 @@ fn main() {
     }
 
     // Load configuration
-    let cfg = Arc::new(Mutex::new(Config::load().unwrap_or_else(|e| {
-        tracing::warn!("Failed to load config: {}, using defaults", e);
-        Config::default()
-    })));
+    let cfg = ConfigService::new(startup_timing::measure(Phase::ConfigLoad, || {
+        Config::load().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load config: {}, using defaults", e);
+            Config::default()
+        })
+    }));
+    {
+        let c = cfg.read();
+        crate::i18n::set_language(&c.language);
+        crate::memory::file_cache::apply_profile_limit(&c);
+    }
     let engine = Engine::new(cfg.clone());
     let rate_limiter = crate::security::RateLimiter::new(
         100,                                // max 100 requests
@@ -905,7 +950,6 @@ fn main() {
     let state = AppState {
         cfg: cfg.clone(),
         engine: engine.clone(),
-        translations: crate::commands::TranslationState::default(),
         rate_limiter: Arc::new(Mutex::new(rate_limiter)),
     };
 
@@ -919,31 +963,23 @@ fn main() {
     }
 
     // Build Tauri v2 app
+    let builder_start = Instant::now();
     tauri::Builder::default()
         .plugin(tauri_plugin_global_shortcut::Builder::new()
             .with_handler(move |app, shortcut, event| {
                 if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
                     tracing::info!("Hotkey pressed: {}", shortcut.id());
 
-                    // Trigger optimization when hotkey is pressed
-                    let app_clone = app.clone();
-                    tauri::async_runtime::spawn(async move {
-                        // Get current configuration
-                        if let Some(state) = app_clone.try_state::<crate::AppState>() {
-                            let cfg = state.cfg.clone();
-                            let engine = state.engine.clone();
-
-                            // Perform optimization with hotkey reason
-                            crate::perform_optimization(
-                                app_clone,
-                                engine,
-                                cfg,
-                                crate::memory::types::Reason::Hotkey,
-                                true,
-                                None
-                            ).await;
+                    // Run the action bound to the pressed shortcut
+                    match crate::hotkeys::manager::action_for_shortcut(shortcut.id()) {
+                        Some(action) => {
+                            tauri::async_runtime::spawn(crate::hotkeys::actions::run_action(
+                                app.clone(),
+                                action,
+                            ));
                         }
-                    });
+                        None => tracing::warn!("No action bound to hotkey {}", shortcut.id()),
+                    }
                 }
             })
             .build())
@@ -959,50 +995,111 @@ fn main() {
             commands::config::cmd_exit,
             commands::config::cmd_get_config,
             commands::config::cmd_save_config,
+            commands::config::cmd_select_profile,
+            commands::config::cmd_confirm_area,
+            commands::config::cmd_toggle_area,
             commands::config::cmd_complete_setup,
+            commands::system::cmd_setup_probe_step,
+            commands::system::cmd_get_recommended_settings,
+            commands::system::cmd_run_diagnostics,
+            commands::config::cmd_export_profile,
+            commands::config::cmd_import_profile,
+            commands::config::cmd_export_preset,
+            commands::config::cmd_import_preset,
+            commands::config::cmd_list_presets,
+            commands::config::cmd_apply_preset,
+            commands::config::cmd_delete_preset,
+            commands::config::cmd_export_config,
+            commands::config::cmd_import_config,
+            commands::config::cmd_resolve_config_conflict,
+            commands::config::cmd_validate_config_file,
+            commands::update::cmd_check_update,
+            commands::update::cmd_apply_update,
+            commands::config::cmd_get_migration_report,
             // Commands from memory module
             commands::memory::cmd_memory_info,
+            commands::memory::cmd_standby_breakdown,
+            commands::memory::cmd_compression_info,
+            commands::memory::cmd_get_file_cache_info,
+            commands::memory::cmd_get_last_result,
             commands::memory::cmd_list_process_names,
+            commands::memory::cmd_process_memory_list,
+            commands::memory::cmd_trim_process,
             commands::memory::cmd_get_critical_processes,
             commands::memory::cmd_optimize_async,
+            commands::memory::cmd_measure_purge_impact,
             // Commands from memory_stats module
             commands::memory_stats::get_memory_stats,
             commands::memory_stats::save_memory_stats,
+            // Commands from history module
+            commands::history::cmd_get_optimization_history,
+            commands::history::cmd_memory_timeline,
+            commands::history::cmd_get_area_effectiveness,
+            commands::history::cmd_get_usage_report,
+            commands::history::cmd_get_session_summary,
+            commands::history::cmd_export_usage_report,
+            commands::history::cmd_reset_usage_metrics,
+            // Commands from errors module
+            commands::errors::cmd_get_recent_errors,
+            commands::errors::cmd_clear_recent_errors,
+            commands::errors::cmd_get_recent_logs,
+            // Commands from schedule module
+            commands::schedule::cmd_get_schedule_preview,
+            commands::schedule::cmd_set_auto_optimizer_enabled,
+            commands::schedule::cmd_get_auto_optimizer_status,
             // Commands from system module
             commands::system::cmd_run_on_startup,
             commands::system::cmd_set_always_on_top,
             commands::system::cmd_set_priority,
-            commands::system::cmd_restart_with_elevation,
+            commands::system::cmd_request_elevation,
+            commands::system::cmd_get_elevation_status,
             commands::system::cmd_manage_elevated_task,
+            commands::system::cmd_power_status,
+            commands::system::cmd_get_startup_timings,
+            commands::system::cmd_get_background_task_status,
+            commands::system::cmd_get_system_tweaks,
+            commands::system::cmd_set_system_tweak,
+            // Commands from storage module
+            commands::storage::cmd_scan_cleanable,
+            commands::storage::cmd_clean_storage,
             // Commands from theme module
             commands::theme::cmd_get_system_theme,
+            commands::theme::cmd_get_accessibility_prefs,
             commands::theme::cmd_get_system_language,
             // Commands from ui module
             commands::ui::cmd_show_or_create_window,
+            commands::ui::cmd_hide_main_window,
+            commands::ui::cmd_take_main_window_state,
             commands::ui::cmd_show_notification,
             commands::ui::cmd_get_window_config,
             commands::ui::cmd_get_platform,
             commands::ui::cmd_apply_rounded_corners,
             commands::ui::cmd_update_tray_theme,
+            commands::ui::cmd_get_menu_icon,
             // Commands from i18n module
             commands::i18n::cmd_set_translations,
+            commands::i18n::cmd_get_missing_translations,
             // Commands from hotkeys module
             cmd_register_hotkey
         ])
+        .on_page_load(move |webview, payload| {
+            if webview.label() == "main"
+                && matches!(payload.event(), tauri::webview::PageLoadEvent::Finished)
+            {
+                startup_timing::record_since(Phase::WebviewReady, builder_start);
+                startup_timing::finish();
+            }
+        })
         .setup(move |app| {
             let app_handle = app.handle();
 
             // Initial log
             tracing::info!("Application setup started");
+            crate::logging::error_bus::init(app_handle.clone());
+            crate::stats::session::start(app_handle);
 
             // Check if this is first run - if so, don't show main window yet
-            let is_first_run = {
-                if let Ok(cfg) = state.cfg.try_lock() {
-                    !cfg.setup_completed
-                } else {
-                    false
-                }
-            };
+            let is_first_run = !state.cfg.with(|c| c.setup_completed);
 
             // Only show main window if setup is already completed
             if !is_first_run {
@@ -1019,6 +1116,7 @@ fn main() {
 
             // Build tray icon - handle errors without crashing
             // NOTE: During first run (setup), we build the tray but delay activation
+            let tray_start = Instant::now();
             let mut tray_builder = match ui::tray::build(app_handle) {
                 Ok(builder) => {
                     tracing::info!("Tray icon builder created successfully");
@@ -1040,11 +1138,9 @@ fn main() {
                     // Check if setup is now completed by looking for main window
                     let app = tray.app_handle();
                     if let Some(state) = app.try_state::<AppState>() {
-                        if let Ok(cfg) = state.cfg.try_lock() {
-                            if !cfg.setup_completed {
-                                tracing::debug!("Ignoring tray click during setup");
-                                return;
-                            }
+                        if !state.cfg.with(|c| c.setup_completed) {
+                            tracing::debug!("Ignoring tray click during setup");
+                            return;
                         }
                     }
                 }
@@ -1070,24 +1166,39 @@ fn main() {
                     tauri::tray::TrayIconEvent::Click {
                         button: tauri::tray::MouseButton::Right,
                         button_state: tauri::tray::MouseButtonState::Up,
+                        position,
                         ..
                     } => {
                         let app_handle = tray.app_handle();
                         tracing::info!("Right click on tray icon detected");
 
+                        // With `tray.native_menu` Windows shows the native menu instead
+                        if app_handle
+                            .try_state::<AppState>()
+                            .is_some_and(|s| s.cfg.with(|c| c.tray.native_menu))
+                        {
+                            return;
+                        }
+
                         // Usa async runtime per gestire l'apertura in modo non bloccante
                         let app_clone = app_handle.clone();
                         tauri::async_runtime::spawn(async move {
-                            show_tray_menu_with_retry(&app_clone).await;
+                            let anchor = (position.x as i32, position.y as i32);
+                            show_tray_menu_with_retry(&app_clone, Some(anchor)).await;
                         });
                     }
                     _ => {}
                 }
             });
 
+            tray_builder = tray_builder
+                .show_menu_on_left_click(false)
+                .on_menu_event(|app, event| ui::tray::handle_menu_event(app, event.id().as_ref()));
+
             let tray = match tray_builder.build(app) {
                 Ok(t) => {
                     tracing::info!("Tray icon built successfully");
+                    startup_timing::record_since(Phase::TrayBuild, tray_start);
                     t
                 }
                 Err(e) => {
@@ -1101,6 +1212,7 @@ fn main() {
             if let Ok(mut id) = TRAY_ICON_ID.lock() {
                 *id = Some(tray_id.clone());
             }
+            ui::tray::apply_native_menu(app_handle);
 
             // FIX: Rinomina variabili non usate con _ per rimuovere warning
             let _cfg_for_setup = cfg.clone();
@@ -1108,14 +1220,20 @@ fn main() {
             // FIX: Controlla se è stato chiamato con --startup-config dall'installer
             let args: Vec<String> = std::env::args().collect();
             let is_startup_config = args.iter().any(|a| a == "--startup-config");
+            // Start to the tray: the main window and its webview are created on first use
+            let start_minimized = args.iter().any(|a| a == "--minimized")
+                || _cfg_for_setup.with(|c| c.start_minimized);
 
             if is_startup_config {
                 // Configura startup se richiesto dall'installer
-                let _ = crate::system::startup::set_run_on_startup(true);
-                if let Ok(mut c) = _cfg_for_setup.lock() {
+                let _ = _cfg_for_setup.update_and_save(|c| {
+                    let _ = crate::system::startup::set_run_on_startup(
+                        true,
+                        c.startup_method,
+                        c.startup_delay_secs,
+                    );
                     c.run_on_startup = true;
-                    let _ = c.save();
-                }
+                });
                 std::process::exit(0);
             }
 
@@ -1128,10 +1246,7 @@ fn main() {
                     return Ok(());
                 }
 
-                let cfg_guard = _cfg_for_setup.lock();
-                let should_show = cfg_guard.as_ref()
-                    .map(|c| !c.setup_completed)
-                    .unwrap_or(true);
+                let should_show = !_cfg_for_setup.with(|c| c.setup_completed);
 
                 // Fallback 2: also check if config file exists
                 // If file exists but setup_completed is false, it might be an issue
@@ -1225,6 +1340,10 @@ fn main() {
                         }
                     }
                 }
+            } else if start_minimized {
+                tracing::info!("Starting minimized to the tray, main window created on first use");
+                // No webview will report ready, the tray is the end of the startup
+                startup_timing::finish();
             } else {
                 // Mostra finestra all'avvio - usa app_handle invece di app
                 tracing::info!("Checking main window visibility...");
@@ -1259,8 +1378,10 @@ fn main() {
                     }
                 } else {
                     // Se la finestra non esiste, creala
-                    tracing::warn!("Main window not found, creating it...");
-                    show_or_create_window(&app_handle);
+                    tracing::info!("Creating main window...");
+                    startup_timing::measure(Phase::WindowCreate, || {
+                        show_or_create_window(&app_handle)
+                    });
                     // Verifica che sia stata creata
                     if let Some(window) = app_handle.get_webview_window("main") {
                         tracing::info!("Window created successfully");
@@ -1276,10 +1397,17 @@ fn main() {
             // Aggiorna menu tray (Tauri v2 - gestito dal builder)
 
             // Applica configurazioni iniziali
-            if let Ok(c) = _cfg_for_setup.lock() {
+            {
+                let c = _cfg_for_setup.snapshot();
                 // Startup
-                if c.run_on_startup && !crate::system::startup::is_startup_enabled() {
-                    let _ = crate::system::startup::set_run_on_startup(true);
+                if c.run_on_startup
+                    && !crate::system::startup::is_startup_enabled(c.startup_method)
+                {
+                    let _ = crate::system::startup::set_run_on_startup(
+                        true,
+                        c.startup_method,
+                        c.startup_delay_secs,
+                    );
                 }
 
                 // Registra l'app per Windows Toast notifications (richiesto per applicazioni non confezionate)
@@ -1317,13 +1445,45 @@ fn main() {
                     engine_for_auto,
                     cfg.clone()
                 );
+
+                start_game_mode(app_handle.clone(), cfg.clone());
             } else {
                 tracing::info!("First run: background processes delayed until setup completion");
             }
 
+            // Local SDK pipe (only when enabled in config)
+            crate::ipc::start_pipe_server(app_handle.clone(), state.engine.clone(), cfg.clone());
+
+            // Memory composition timeline for the UI charts
+            crate::stats::timeline::start_timeline_sampler(cfg.clone());
+
+            // Widget feed file (only when enabled in config)
+            crate::ipc::widget_feed::start_widget_feed(state.engine.clone(), cfg.clone());
+
+            // Local HTTP API for dashboards (only when enabled in config)
+            crate::ipc::http_api::start_http_api(app_handle.clone(), state.engine.clone(), cfg.clone());
+
+            // Daily update check (only when auto_update is enabled)
+            crate::updater::start_update_checker(app_handle.clone(), cfg.clone());
+
+            // Commit charge warning (only when commit_warning_percent is set)
+            crate::auto_optimizer::commit_guard::start_commit_monitor(
+                app_handle.clone(),
+                state.engine.clone(),
+                cfg.clone(),
+            );
+
+            // Pick up hand edits of config.json
+            crate::config::live_reload::start_watcher(app_handle.clone(), cfg.clone());
+
             Ok(())
         })
         .on_window_event(|app, event| {
+            if let tauri::WindowEvent::ScaleFactorChanged { scale_factor, .. } = event {
+                crate::ui::dpi::handle_scale_factor_changed(app, *scale_factor);
+                return;
+            }
+
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 // In Tauri v2, we get the window from app parameter using the window from event
                 // But we need to check which window emitted the event
@@ -1341,21 +1501,20 @@ fn main() {
 
                 // Handle main window close
                 if let Some(main_window) = app.get_webview_window("main") {
-                    if let Ok(cfg) = main_window.app_handle().state::<AppState>().cfg.lock() {
-                        if cfg.minimize_to_tray {
-                            if let Err(e) = main_window.hide() {
-                                tracing::warn!("Failed to hide window: {}", e);
-                            }
-                            api.prevent_close();
-                        } else {
-                            // If not minimizing to tray, close app and log shutdown
-                            crate::logging::shutdown();
-                        }
+                    let cfg = main_window.app_handle().state::<AppState>().cfg.snapshot();
+                    if cfg.minimize_to_tray {
+                        api.prevent_close();
+                        crate::ui::main_window::hide_main_window(main_window.app_handle(), None);
+                    } else {
+                        // If not minimizing to tray, close app and log shutdown
+                        crate::stats::session::finish(main_window.app_handle(), true);
+                        crate::stats::timeline::save_session(&cfg);
+                        crate::logging::shutdown();
                     }
                 }
             }
         })
-        .run(tauri::generate_context!())
+        .build(tauri::generate_context!())
         .map_err(|e| {
             tracing::error!("Failed to run TMC application: {:?}", e);
             eprintln!("FATAL ERROR: Failed to run TMC application: {:?}", e);
@@ -1364,5 +1523,17 @@ fn main() {
         .unwrap_or_else(|e| {
             eprintln!("FATAL: Application failed to start: {:?}", e);
             std::process::exit(1);
+        })
+        .run(|app, event| match event {
+            // Destroying the main window on hide can leave no windows, stay in the tray
+            tauri::RunEvent::ExitRequested {
+                code: None, api, ..
+            } => {
+                if crate::ui::main_window::keep_running_without_window() {
+                    api.prevent_exit();
+                }
+            }
+            tauri::RunEvent::Exit => crate::stats::session::finish(app, true),
+            _ => {}
         });
 }
@@ -0,0 +1,185 @@
+/// Processes the user is working with
+///
+/// A full Working Set trim of the game in the foreground or of the player
+/// that is making sound shows up as stutter a moment later, while their pages
+/// fault back in. Before a full trim the foreground process, the processes
+/// with an active audio session (`IAudioSessionManager2`, on every active
+/// playback device) and the editors with open documents are collected here
+/// and skipped, with the reason in the per-process result. The foreground
+/// and audio heuristics can be turned off in the config
+/// (`skip_foreground_process`, `skip_audio_processes`).
+use crate::memory::document_guard::open_document_sessions;
+use std::collections::{HashMap, HashSet};
+
+pub const FOREGROUND_REASON: &str = "Foreground window";
+pub const AUDIO_REASON: &str = "Playing audio";
+pub const OPEN_DOCUMENTS_REASON: &str = "Open documents";
+
+/// Which processes in use the full trim leaves alone
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActivityGuard {
+    /// Editors skipped while they have an open document session
+    pub document_editors: Vec<String>,
+    /// Skip the process of the foreground window
+    pub foreground: bool,
+    /// Skip processes with an active audio session
+    pub audio: bool,
+}
+
+/// Processes in use right now, with the reason each one is skipped
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ActiveProcesses {
+    reasons: HashMap<u32, &'static str>,
+}
+
+impl ActivityGuard {
+    /// Look for processes in use among `processes` (from `process_list()`)
+    pub fn detect(&self, processes: &[(u32, String)]) -> ActiveProcesses {
+        let foreground = if self.foreground {
+            crate::memory::ops::get_foreground_process_pid()
+        } else {
+            None
+        };
+        let audio = if self.audio {
+            audio_session_pids()
+        } else {
+            HashSet::new()
+        };
+        let open_documents = open_document_sessions(processes, &self.document_editors);
+        ActiveProcesses::new(foreground, &audio, &open_documents)
+    }
+}
+
+impl ActiveProcesses {
+    /// The foreground window wins over audio, audio over open documents
+    fn new(foreground: Option<u32>, audio: &HashSet<u32>, open_documents: &HashSet<u32>) -> Self {
+        let mut reasons = HashMap::new();
+        for &pid in open_documents {
+            reasons.insert(pid, OPEN_DOCUMENTS_REASON);
+        }
+        for &pid in audio {
+            reasons.insert(pid, AUDIO_REASON);
+        }
+        if let Some(pid) = foreground {
+            reasons.insert(pid, FOREGROUND_REASON);
+        }
+        Self { reasons }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reasons.is_empty()
+    }
+
+    /// Why `pid` must not be trimmed, `None` if it can be
+    pub fn skip_reason(&self, pid: u32) -> Option<&'static str> {
+        self.reasons.get(&pid).copied()
+    }
+}
+
+/// Processes with an active audio session on any playback device
+#[cfg(windows)]
+fn audio_session_pids() -> HashSet<u32> {
+    match active_audio_sessions() {
+        Ok(pids) => {
+            if !pids.is_empty() {
+                tracing::debug!("Processes playing audio: {:?}", pids);
+            }
+            pids
+        }
+        Err(e) => {
+            tracing::debug!("Audio sessions unavailable: {}", e);
+            HashSet::new()
+        }
+    }
+}
+
+#[cfg(windows)]
+fn active_audio_sessions() -> windows::core::Result<HashSet<u32>> {
+    use windows::Win32::Media::Audio::{
+        eRender, IMMDeviceEnumerator, MMDeviceEnumerator, DEVICE_STATE_ACTIVE,
+    };
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+    };
+
+    // The trim runs on a worker thread; COM is set up for this call only.
+    // A thread already in another apartment can still use it.
+    let initialized = unsafe { CoInitializeEx(None, COINIT_MULTITHREADED) }.is_ok();
+    let _uninit = scopeguard::guard(initialized, |initialized| {
+        if initialized {
+            unsafe { CoUninitialize() };
+        }
+    });
+
+    let mut pids = HashSet::new();
+    unsafe {
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL)?;
+        let devices = enumerator.EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)?;
+        for index in 0..devices.GetCount()? {
+            // One device failing must not hide the sessions of the others
+            if let Err(e) = devices
+                .Item(index)
+                .and_then(|device| add_active_sessions(&device, &mut pids))
+            {
+                tracing::debug!("Skipping audio device {}: {}", index, e);
+            }
+        }
+    }
+    Ok(pids)
+}
+
+#[cfg(windows)]
+unsafe fn add_active_sessions(
+    device: &windows::Win32::Media::Audio::IMMDevice,
+    pids: &mut HashSet<u32>,
+) -> windows::core::Result<()> {
+    use windows::core::Interface;
+    use windows::Win32::Media::Audio::{
+        AudioSessionStateActive, IAudioSessionControl2, IAudioSessionManager2,
+    };
+    use windows::Win32::System::Com::CLSCTX_ALL;
+
+    let manager: IAudioSessionManager2 = device.Activate(CLSCTX_ALL, None)?;
+    let sessions = manager.GetSessionEnumerator()?;
+    for index in 0..sessions.GetCount()? {
+        let control: IAudioSessionControl2 = sessions.GetSession(index)?.cast()?;
+        if control.GetState()? != AudioSessionStateActive {
+            continue;
+        }
+        // PID 0 is the system sounds session
+        match control.GetProcessId() {
+            Ok(pid) if pid != 0 => {
+                pids.insert(pid);
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn audio_session_pids() -> HashSet<u32> {
+    HashSet::new()
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skip_reason_precedence() {
+        let audio: HashSet<u32> = [10, 20].into_iter().collect();
+        let documents: HashSet<u32> = [20, 30].into_iter().collect();
+        let active = ActiveProcesses::new(Some(10), &audio, &documents);
+
+        assert_eq!(active.skip_reason(10), Some(FOREGROUND_REASON));
+        assert_eq!(active.skip_reason(20), Some(AUDIO_REASON));
+        assert_eq!(active.skip_reason(30), Some(OPEN_DOCUMENTS_REASON));
+        assert_eq!(active.skip_reason(40), None);
+
+        let none = ActiveProcesses::new(None, &HashSet::new(), &HashSet::new());
+        assert!(none.is_empty());
+    }
+}
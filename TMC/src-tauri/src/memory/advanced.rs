@@ -12,6 +12,7 @@
 /// Requires Administrator privileges and may trigger security software.
 
 use anyhow::{Result, bail, Context};
+use crate::memory::strict::note_swallowed;
 use std::{ptr, mem, ffi::CString, sync::OnceLock};
 use windows_sys::Win32::{
     Foundation::{HANDLE, CloseHandle},
@@ -152,7 +153,7 @@ impl Drop for TokenImpersonationGuard {
 }
 
 /// Stealth EmptyWorkingSet using indirect syscalls
-pub fn empty_working_set_stealth(exclusions: &[String]) -> Result<()> {
+pub fn empty_working_set_stealth(exclusions: &[String], allow_system_trim: bool) -> Result<()> {
     tracing::debug!("Using stealth mode for Working Set optimization with indirect syscalls");
     
     // First try indirect syscall approach
@@ -176,6 +177,13 @@ pub fn empty_working_set_stealth(exclusions: &[String]) -> Result<()> {
         if crate::memory::critical_processes::is_critical_process(&name) {
             continue;
         }
+
+        // Skip system-account, protected and critical-flagged processes
+        if !allow_system_trim
+            && crate::memory::protection::protection_reason(pid, &name).is_some()
+        {
+            continue;
+        }
         
         unsafe {
             // Use PROCESS_ALL_ACCESS if available, otherwise minimum required permissions
@@ -300,8 +308,16 @@ struct SyscallResolver {
 }
 
 impl SyscallResolver {
-    /// Initialize by finding ntdll in memory with proper error handling
+    /// Initialize unless the startup probe disabled the advanced syscall path
     fn new() -> Result<Self> {
+        if let Some(reason) = super::syscall_probe::disabled_reason() {
+            bail!("Advanced syscall path disabled: {}", reason);
+        }
+        Self::locate()
+    }
+
+    /// Initialize by finding ntdll in memory with proper error handling
+    fn locate() -> Result<Self> {
         unsafe {
             let ntdll_name = CString::new("ntdll.dll")?;
             let h_ntdll = windows_sys::Win32::System::LibraryLoader::GetModuleHandleA(
@@ -460,6 +476,53 @@ impl SyscallResolver {
         let base = self.ntdll_base as usize;
         addr_val >= base && addr_val + size <= base + self.ntdll_size
     }
+
+    /// Whether the stub of an export starts with something other than
+    /// `mov r10, rcx; mov eax, SSN`, `None` when ntdll does not export it
+    unsafe fn is_hooked(&self, func_name: &str) -> Option<bool> {
+        let func_name_cstr = CString::new(func_name).ok()?;
+        let func_addr = GetProcAddress(self.ntdll_base as _, func_name_cstr.as_ptr() as _)? as *const u8;
+        if !self.is_within_bounds(func_addr, SYSCALL_STUB_SIZE) {
+            return Some(true);
+        }
+        Some(self.extract_ssn_direct(func_addr).is_none())
+    }
+}
+
+/// Hook check of ntdll exports for the startup probe, as `(name, hooked)`
+/// for the exports that exist on this Windows version
+pub(super) fn syscall_stubs<'a>(names: &[&'a str]) -> Result<Vec<(&'a str, bool)>> {
+    let resolver = SyscallResolver::locate()?;
+    Ok(names
+        .iter()
+        .filter_map(|name| unsafe { resolver.is_hooked(name) }.map(|hooked| (*name, hooked)))
+        .collect())
+}
+
+/// Issue NtYieldExecution as a direct syscall for the startup probe; it
+/// takes no arguments and changes nothing
+pub(super) fn noop_direct_syscall() -> Result<i32> {
+    let resolver = SyscallResolver::locate()?;
+    unsafe {
+        let ssn = resolver
+            .get_ssn("NtYieldExecution")
+            .ok_or_else(|| anyhow::anyhow!("Could not resolve NtYieldExecution"))?;
+        Ok(execute_noop_syscall(ssn))
+    }
+}
+
+/// Direct syscall without arguments
+unsafe fn execute_noop_syscall(ssn: u32) -> i32 {
+    let mut status: i32;
+    std::arch::asm!(
+        "syscall",
+        in("eax") ssn,
+        lateout("rax") status,
+        lateout("rcx") _,
+        lateout("r11") _,
+        options(nostack)
+    );
+    status
 }
 
 /// Safe token impersonation with automatic cleanup
@@ -690,6 +753,7 @@ unsafe fn try_trim_with_resolver() -> Result<()> {
         Ok(())
     } else {
         tracing::warn!("Direct syscall failed (0x{:08X}), trying indirect syscall...", status as u32);
+        note_swallowed(status, "Compression store trim (direct syscall)");
         
         // Fallback to indirect syscall for better stealth
         match execute_indirect_syscall(
@@ -704,6 +768,7 @@ unsafe fn try_trim_with_resolver() -> Result<()> {
             }
             Ok(indirect_status) => {
                 tracing::warn!("Indirect syscall also failed (0x{:08X})", indirect_status as u32);
+                note_swallowed(indirect_status, "Compression store trim (indirect syscall)");
                 Err(anyhow::anyhow!("Both direct and indirect syscalls failed"))
             }
             Err(e) => {
@@ -730,9 +795,12 @@ unsafe fn try_trim_direct_nt() -> Result<()> {
         if status == 0 {
             tracing::info!("✓ Memory Compression Store trimmed via direct NT call (cmd={})", cmd);
             return Ok(());
-        } else if status as u32 != 0xC000000D {
-            // If it's not STATUS_INVALID_PARAMETER, it might be a different issue
-            tracing::warn!("Direct NT call returned NTSTATUS: 0x{:08X} (cmd={})", status as u32, cmd);
+        } else {
+            if status as u32 != 0xC000000D {
+                // If it's not STATUS_INVALID_PARAMETER, it might be a different issue
+                tracing::warn!("Direct NT call returned NTSTATUS: 0x{:08X} (cmd={})", status as u32, cmd);
+            }
+            note_swallowed(status, &format!("Compression store trim (NT call, cmd={})", cmd));
         }
     }
     
@@ -798,6 +866,7 @@ unsafe fn try_standby_with_resolver(low_priority: bool) -> Result<()> {
         tracing::info!("✓ Advanced standby list purge successful");
         Ok(())
     } else {
+        note_swallowed(status, &format!("Standby list purge (direct syscall, cmd={})", cmd));
         Err(anyhow::anyhow!("Direct NT call failed with all commands"))
     }
 }
@@ -858,6 +927,7 @@ pub fn aggressive_modified_page_flush_stealth() -> Result<()> {
         }
         Ok(status) => {
             tracing::warn!("Indirect syscall failed (0x{:08X}), trying direct syscall", status as u32);
+            note_swallowed(status, "Modified page list flush (indirect syscall)");
             
             // Fallback to direct syscall
             let direct_status = unsafe { execute_direct_syscall(
@@ -872,6 +942,7 @@ pub fn aggressive_modified_page_flush_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Modified page list flush (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
@@ -894,6 +965,7 @@ pub fn aggressive_modified_page_flush_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Modified page list flush (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
@@ -957,6 +1029,7 @@ unsafe fn try_modified_with_resolver() -> Result<()> {
         Ok(())
     } else {
         tracing::warn!("Advanced flush returned NTSTATUS: 0x{:08X}", status as u32);
+        note_swallowed(status, "Modified page list flush (direct syscall)");
         Err(anyhow::anyhow!("Advanced approach failed"))
     }
 }
@@ -977,9 +1050,12 @@ unsafe fn try_modified_direct_nt() -> Result<()> {
         if status == 0 {
             tracing::info!("✓ Modified page list flushed via direct NT call (cmd={})", cmd);
             return Ok(());
-        } else if status as u32 != 0xC000000D {
-            // If it's not STATUS_INVALID_PARAMETER, it might be a different issue
-            tracing::warn!("Direct NT call returned NTSTATUS: 0x{:08X} (cmd={})", status as u32, cmd);
+        } else {
+            if status as u32 != 0xC000000D {
+                // If it's not STATUS_INVALID_PARAMETER, it might be a different issue
+                tracing::warn!("Direct NT call returned NTSTATUS: 0x{:08X} (cmd={})", status as u32, cmd);
+            }
+            note_swallowed(status, &format!("Modified page list flush (NT call, cmd={})", cmd));
         }
     }
     
@@ -989,6 +1065,8 @@ unsafe fn try_modified_direct_nt() -> Result<()> {
 /// Initialize advanced optimization features
 pub fn init_advanced_features() -> Result<()> {
     tracing::info!("Initializing production-ready advanced memory optimization features");
+    // Decide on the syscall path before the first optimization selects its API paths
+    super::syscall_probe::probe();
     Ok(())
 }
 
@@ -1049,6 +1127,7 @@ unsafe fn try_registry_with_resolver() -> Result<()> {
         Ok(())
     } else {
         tracing::warn!("Advanced registry optimization returned NTSTATUS: 0x{:08X}", status as u32);
+        note_swallowed(status, "Registry cache optimization (resolver path)");
         Err(anyhow::anyhow!("Advanced approach failed"))
     }
 }
@@ -1073,6 +1152,7 @@ unsafe fn try_registry_direct_nt() -> Result<()> {
         Ok(())
     } else {
         tracing::warn!("Direct NT call returned NTSTATUS: 0x{:08X}", status as u32);
+        note_swallowed(status, "Registry cache optimization (NT call)");
         Err(anyhow::anyhow!("Direct NT call failed"))
     }
 }
@@ -1140,6 +1220,7 @@ pub fn purge_standby_list_stealth() -> Result<()> {
         }
         Ok(status) => {
             tracing::warn!("Indirect syscall failed (0x{:08X}), trying direct syscall", status as u32);
+            note_swallowed(status, "Standby list purge (indirect syscall)");
             
             // Fallback to direct syscall
             let direct_status = unsafe { execute_direct_syscall(
@@ -1154,6 +1235,7 @@ pub fn purge_standby_list_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Standby list purge (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
@@ -1176,6 +1258,7 @@ pub fn purge_standby_list_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Standby list purge (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
@@ -1208,6 +1291,7 @@ pub fn purge_standby_list_low_priority_stealth() -> Result<()> {
         }
         Ok(status) => {
             tracing::warn!("Indirect syscall failed (0x{:08X}), trying direct syscall", status as u32);
+            note_swallowed(status, "Low priority standby list purge (indirect syscall)");
             
             // Fallback to direct syscall
             let direct_status = unsafe { execute_direct_syscall(
@@ -1222,6 +1306,7 @@ pub fn purge_standby_list_low_priority_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Low priority standby list purge (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
@@ -1244,6 +1329,7 @@ pub fn purge_standby_list_low_priority_stealth() -> Result<()> {
                 Ok(())
             } else {
                 tracing::warn!("Direct syscall failed, using standard API");
+                note_swallowed(direct_status, "Low priority standby list purge (direct syscall)");
                 crate::memory::ops::nt_call_u32(
                     crate::memory::ops::SYS_MEMORY_LIST_INFORMATION,
                     cmd
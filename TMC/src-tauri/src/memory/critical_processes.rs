@@ -179,8 +179,11 @@ pub fn is_critical_process(process_name: &str) -> bool {
         .trim_end_matches(".sys")
         .trim_end_matches(".dll");
 
-    // Exact check
-    if CRITICAL_PROCESSES.contains(clean_name) {
+    // Exact check (the list mixes bare names and names with their extension)
+    if ["", ".exe", ".sys", ".dll"]
+        .iter()
+        .any(|ext| CRITICAL_PROCESSES.contains(&format!("{}{}", clean_name, ext)))
+    {
         return true;
     }
 
@@ -218,3 +221,19 @@ pub fn is_critical_process(process_name: &str) -> bool {
 pub fn get_critical_processes_list() -> Vec<String> {
     CRITICAL_PROCESSES.iter().cloned().collect()
 }
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listed_names_match_with_or_without_extension() {
+        // process_list() reports names without ".exe"
+        assert!(is_critical_process("lsass"));
+        assert!(is_critical_process("csrss.exe"));
+        assert!(is_critical_process("Memory Compression"));
+        assert!(is_critical_process("svchost"));
+        assert!(!is_critical_process("notepad"));
+    }
+}
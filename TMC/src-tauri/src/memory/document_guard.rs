@@ -0,0 +1,164 @@
+/// Document editor guard for the full Working Set trim
+///
+/// Trimming an editor while it saves makes the save stall on page faults, and
+/// users read that as the app hanging or losing their work. Before a full
+/// Working Set trim the running processes named in `document_editor_processes`
+/// are registered with the Restart Manager; editors it reports with a running
+/// main window, i.e. an open document session, are skipped.
+///
+/// The Restart Manager cannot tell a saved document from an unsaved one, so
+/// every open document session counts as possibly unsaved.
+use std::collections::HashSet;
+
+/// Editors guarded by default (process names without ".exe")
+pub const DEFAULT_DOCUMENT_EDITORS: [&str; 12] = [
+    "winword",
+    "excel",
+    "powerpnt",
+    "onenote",
+    "visio",
+    "notepad",
+    "notepad++",
+    "wordpad",
+    "soffice.bin",
+    "code",
+    "devenv",
+    "photoshop",
+];
+
+/// PIDs of the editors in `processes` that have an open document session
+///
+/// `processes` comes from `process_list()`, `editors` may carry ".exe".
+pub fn open_document_sessions(processes: &[(u32, String)], editors: &[String]) -> HashSet<u32> {
+    let editors: HashSet<String> = editors
+        .iter()
+        .map(|e| {
+            let lower = e.trim().to_lowercase();
+            lower.strip_suffix(".exe").unwrap_or(&lower).to_string()
+        })
+        .filter(|e| !e.is_empty())
+        .collect();
+    if editors.is_empty() {
+        return HashSet::new();
+    }
+
+    let pids: Vec<u32> = processes
+        .iter()
+        .filter(|(_, name)| editors.contains(name))
+        .map(|(pid, _)| *pid)
+        .collect();
+    if pids.is_empty() {
+        return HashSet::new();
+    }
+
+    let sessions = main_window_sessions(&pids);
+    if !sessions.is_empty() {
+        tracing::debug!("Editors with open documents: {:?}", sessions);
+    }
+    sessions
+}
+
+/// Processes the Restart Manager reports as running main-window applications
+#[cfg(windows)]
+fn main_window_sessions(pids: &[u32]) -> HashSet<u32> {
+    use windows_sys::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
+    use windows_sys::Win32::System::RestartManager::{
+        RmEndSession, RmGetList, RmMainWindow, RmRegisterResources, RmStartSession,
+        RmStatusRunning, CCH_RM_SESSION_KEY, RM_PROCESS_INFO, RM_UNIQUE_PROCESS,
+    };
+
+    let processes: Vec<RM_UNIQUE_PROCESS> =
+        pids.iter().filter_map(|&p| unique_process(p)).collect();
+    if processes.is_empty() {
+        return HashSet::new();
+    }
+
+    unsafe {
+        let mut session = 0u32;
+        let mut key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        if RmStartSession(&mut session, 0, key.as_mut_ptr()) != ERROR_SUCCESS {
+            return HashSet::new();
+        }
+        let _end = scopeguard::guard(session, |s| {
+            RmEndSession(s);
+        });
+
+        if RmRegisterResources(
+            session,
+            0,
+            std::ptr::null(),
+            processes.len() as u32,
+            processes.as_ptr(),
+            0,
+            std::ptr::null(),
+        ) != ERROR_SUCCESS
+        {
+            return HashSet::new();
+        }
+
+        // The list can grow between the calls, retry a few times
+        let mut infos: Vec<RM_PROCESS_INFO> = vec![std::mem::zeroed(); processes.len()];
+        for _ in 0..3 {
+            let mut needed = 0u32;
+            let mut count = infos.len() as u32;
+            let mut reasons = 0u32;
+            let status = RmGetList(
+                session,
+                &mut needed,
+                &mut count,
+                infos.as_mut_ptr(),
+                &mut reasons,
+            );
+            if status == ERROR_MORE_DATA {
+                infos.resize(needed as usize, std::mem::zeroed());
+                continue;
+            }
+            if status != ERROR_SUCCESS {
+                return HashSet::new();
+            }
+            return infos[..count as usize]
+                .iter()
+                .filter(|i| {
+                    i.ApplicationType == RmMainWindow && i.AppStatus & RmStatusRunning as u32 != 0
+                })
+                .map(|i| i.Process.dwProcessId)
+                .collect();
+        }
+        HashSet::new()
+    }
+}
+
+#[cfg(not(windows))]
+fn main_window_sessions(_pids: &[u32]) -> HashSet<u32> {
+    HashSet::new()
+}
+
+/// PID and start time, how the Restart Manager identifies a process
+#[cfg(windows)]
+fn unique_process(
+    pid: u32,
+) -> Option<windows_sys::Win32::System::RestartManager::RM_UNIQUE_PROCESS> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::RestartManager::RM_UNIQUE_PROCESS;
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let h = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if h.is_null() {
+            return None;
+        }
+        let empty = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+        let ok = GetProcessTimes(h, &mut created, &mut exited, &mut kernel, &mut user) != 0;
+        CloseHandle(h);
+        ok.then_some(RM_UNIQUE_PROCESS {
+            dwProcessId: pid,
+            ProcessStartTime: created,
+        })
+    }
+}
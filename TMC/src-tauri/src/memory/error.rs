@@ -0,0 +1,117 @@
+/// Structured errors of the memory operations
+///
+/// The operations return `anyhow::Result` like the rest of the app; where the
+/// cause is known a `MemoryOpError` is put at the root of the error chain.
+/// The engine finds it with `MemoryOpError::find` and hands it to the UI in
+/// `OptimizeAreaResult::error_kind`, next to the plain message.
+use serde::{Deserialize, Serialize};
+
+const STATUS_NOT_IMPLEMENTED: u32 = 0xC000_0002;
+const STATUS_INVALID_INFO_CLASS: u32 = 0xC000_0003;
+const STATUS_ACCESS_DENIED: u32 = 0xC000_0022;
+const STATUS_PRIVILEGE_NOT_HELD: u32 = 0xC000_0061;
+const STATUS_NOT_SUPPORTED: u32 = 0xC000_00BB;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MemoryOpError {
+    /// A privilege could not be enabled in the process token
+    PrivilegeMissing { privilege: String },
+    /// The Windows build does not implement the request
+    UnsupportedOsBuild { build: u32 },
+    /// An NT call failed with this NTSTATUS
+    NtStatus { status: u32 },
+    /// The process could not be opened
+    AccessDenied { pid: u32 },
+    /// The area did not finish in time and was left running
+    Timeout { secs: u64 },
+    /// The area reported success but its memory list was still full after the retries
+    ListUnchanged { retries: u8 },
+}
+
+impl MemoryOpError {
+    /// Error for a failed NT call, telling unsupported builds apart
+    pub fn from_nt_status(status: i32) -> Self {
+        match status as u32 {
+            STATUS_NOT_IMPLEMENTED | STATUS_INVALID_INFO_CLASS | STATUS_NOT_SUPPORTED => {
+                Self::UnsupportedOsBuild {
+                    build: crate::os::win_version().version.build,
+                }
+            }
+            status => Self::NtStatus { status },
+        }
+    }
+
+    /// The structured cause anywhere in the chain of `error`
+    pub fn find(error: &anyhow::Error) -> Option<Self> {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<MemoryOpError>())
+            .cloned()
+    }
+}
+
+impl std::fmt::Display for MemoryOpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PrivilegeMissing { privilege } => {
+                write!(f, "Missing {}, run TMC as administrator", privilege)
+            }
+            Self::UnsupportedOsBuild { build } => {
+                write!(f, "Not supported on Windows build {}", build)
+            }
+            Self::NtStatus { status } => match *status {
+                STATUS_ACCESS_DENIED => write!(
+                    f,
+                    "Access denied (0x{:08X}), security software may be blocking it",
+                    status
+                ),
+                STATUS_PRIVILEGE_NOT_HELD => {
+                    write!(f, "Privilege not held (0x{:08X})", status)
+                }
+                _ => write!(f, "NTSTATUS 0x{:08X}", status),
+            },
+            Self::AccessDenied { pid } => write!(f, "Access denied to process {}", pid),
+            Self::Timeout { secs } => write!(f, "Timed out after {}s", secs),
+            Self::ListUnchanged { retries } => write!(
+                f,
+                "Reported success but the list was not emptied ({} retries)",
+                retries
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MemoryOpError {}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_in_context_chain() {
+        let error = anyhow::Error::new(MemoryOpError::PrivilegeMissing {
+            privilege: "SeProfileSingleProcessPrivilege".to_string(),
+        })
+        .context("ensuring privilege SeProfileSingleProcessPrivilege");
+        assert_eq!(
+            MemoryOpError::find(&error),
+            Some(MemoryOpError::PrivilegeMissing {
+                privilege: "SeProfileSingleProcessPrivilege".to_string(),
+            })
+        );
+        assert_eq!(MemoryOpError::find(&anyhow::anyhow!("plain")), None);
+
+        assert_eq!(
+            MemoryOpError::from_nt_status(0xC000_0022_u32 as i32),
+            MemoryOpError::NtStatus {
+                status: STATUS_ACCESS_DENIED
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(MemoryOpError::AccessDenied { pid: 4 }).ok(),
+            Some(serde_json::json!({ "kind": "access_denied", "pid": 4 }))
+        );
+    }
+}
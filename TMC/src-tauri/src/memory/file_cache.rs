@@ -0,0 +1,67 @@
+/// Keeps the System File Cache limit of the active profile in effect
+///
+/// Windows forgets the limit at reboot and the SystemFileCache purge replaces
+/// it with its own dynamic limits, so it is applied at startup, whenever the
+/// configuration or the profile changes, and again after each purge.
+use crate::config::{Config, FileCacheLimit};
+use crate::logging::error_bus::{report, Subsystem};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+/// Limit set by this app, `None` when the cache is not limited by us
+static APPLIED: Lazy<Mutex<Option<FileCacheLimit>>> = Lazy::new(|| Mutex::new(None));
+
+/// Set or lift the limit so it matches the active profile
+pub fn apply_profile_limit(cfg: &Config) {
+    let wanted = cfg.effective_file_cache_limit().cloned();
+    let mut applied = APPLIED.lock();
+    let unchanged = match (&*applied, &wanted) {
+        (Some(a), Some(w)) => a.same_limit(w),
+        (None, None) => true,
+        _ => false,
+    };
+    if unchanged {
+        return;
+    }
+
+    let result = match &wanted {
+        Some(limit) => {
+            tracing::info!(
+                "Limiting the file cache to {}-{}MB ({}) for profile '{}'",
+                limit.min_mb,
+                limit.max_mb,
+                if limit.hard { "hard" } else { "soft" },
+                limit.profile
+            );
+            crate::memory::ops::set_file_cache_limit(
+                limit.min_bytes(),
+                limit.max_bytes(),
+                limit.hard,
+            )
+        }
+        None => {
+            tracing::info!("Lifting the file cache limit");
+            crate::memory::ops::clear_file_cache_limit()
+        }
+    };
+
+    match result {
+        Ok(()) => *applied = wanted,
+        Err(e) => {
+            tracing::warn!("Failed to update the file cache limit: {}", e);
+            report(
+                Subsystem::Engine,
+                format!("Failed to update the file cache limit: {}", e),
+            );
+        }
+    }
+}
+
+/// Restore the profile limit after the SystemFileCache purge replaced it
+pub fn reapply_after_purge(cfg: &Config) {
+    if cfg.effective_file_cache_limit().is_none() {
+        return;
+    }
+    *APPLIED.lock() = None;
+    apply_profile_limit(cfg);
+}
@@ -1,6 +1,16 @@
+pub mod activity;
 pub mod advanced;
 pub mod critical_processes;
+pub mod document_guard;
+pub mod error;
+pub mod file_cache;
 pub mod ops;
 pub mod privileges;
+pub mod process_cpu;
+pub mod process_list;
+pub mod process_tree;
+pub mod protection;
+pub mod strict;
+pub mod syscall_probe;
 pub mod types;
 pub mod volumes;
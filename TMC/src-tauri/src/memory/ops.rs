@@ -18,11 +18,21 @@
 // All these APIs are officially documented by Microsoft and used by legitimate software.
 // Antivirus false positives are common for unsigned software that uses system APIs.
 
+use crate::memory::activity::{ActiveProcesses, ActivityGuard};
+use crate::memory::error::MemoryOpError;
 use crate::memory::privileges::ensure_privileges;
-use crate::memory::types::{mk_stats, MemoryInfo};
+use crate::memory::process_cpu::{sample_cpu_usage, CPU_SAMPLE_WINDOW};
+use crate::memory::strict::note_swallowed;
+use crate::memory::types::{
+    mk_stats, CompressionInfo, FileCacheInfo, MemoryInfo, ProcessTrimResult, SingleProcessTrim,
+    StandbyBreakdown, TrimStrategy,
+};
 use anyhow::{bail, Result};
 use std::{ffi::OsString, mem, os::windows::ffi::OsStringExt, ptr};
-use windows_sys::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+use windows_sys::Win32::System::SystemInformation::{
+    GetPhysicallyInstalledSystemMemory, GetSystemInfo, GlobalMemoryStatusEx, MEMORYSTATUSEX,
+    SYSTEM_INFO,
+};
 
 use windows_sys::Win32::Foundation::{CloseHandle, GetLastError, HANDLE, INVALID_HANDLE_VALUE};
 use windows_sys::Win32::System::ProcessStatus::K32EmptyWorkingSet;
@@ -30,24 +40,104 @@ use windows_sys::Win32::System::Threading::{
     OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_SET_QUOTA,
 };
 
-use ntapi::ntexapi::NtSetSystemInformation;
-use windows_sys::Win32::System::Memory::SetSystemFileCacheSize;
+use ntapi::ntexapi::{NtQuerySystemInformation, NtSetSystemInformation};
+use windows_sys::Win32::System::Memory::{GetSystemFileCacheSize, SetSystemFileCacheSize};
 
 use crate::memory::critical_processes::is_critical_process;
+use crate::memory::process_tree::{ExcludedChildren, EXCLUDED_PARENT_REASON};
+use crate::memory::protection::protection_reason;
+use crate::system::elevation::NOT_ELEVATED_REASON;
 use once_cell::sync::Lazy;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use parking_lot::RwLock;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::{Duration, Instant};
 
 pub const SYS_MEMORY_LIST_INFORMATION: u32 = 80;
 const SYS_COMBINE_PHYSICAL_MEMORY_INFORMATION: u32 = 101;
+const SYS_FILE_CACHE_INFORMATION: u32 = 21;
+const SYS_STORE_INFORMATION: u32 = 109;
+
+/// SystemStoreInformation request for the memory compression store statistics
+const STORE_INFORMATION_VERSION: u32 = 1;
+const STORE_MEM_COMPRESSION_INFO_REQUEST: u32 = 22;
+const STORE_MEM_COMPRESSION_INFO_VERSION: u32 = 3;
 
 const MEM_EMPTY_WORKING_SETS: u32 = 2;
+const MEM_FLUSH_MODIFIED_LIST: u32 = 3;
+const MEM_PURGE_STANDBY_LIST: u32 = 4;
+const MEM_PURGE_LOW_PRIORITY_STANDBY_LIST: u32 = 5;
+/// First invalid memory list command (MemoryCommandMax)
+const MEM_COMMAND_MAX: u32 = 6;
+const STATUS_INVALID_PARAMETER: i32 = 0xC000000Du32 as i32;
+const SYS_REGISTRY_RECONCILIATION_INFORMATION: u32 = 155;
+
+/// Hard working set cap of the aggressive trim strategy
+const HARD_TRIM_MIN_BYTES: usize = 256 * 1024;
+const HARD_TRIM_MAX_BYTES: usize = 1024 * 1024;
+
+/// SetSystemFileCacheSize / GetSystemFileCacheSize flags
+const FILE_CACHE_MAX_HARD_ENABLE: u32 = 0x1;
+const FILE_CACHE_MAX_HARD_DISABLE: u32 = 0x2;
+const FILE_CACHE_MIN_HARD_ENABLE: u32 = 0x4;
+const FILE_CACHE_MIN_HARD_DISABLE: u32 = 0x8;
 
 const SE_DEBUG_NAME: &str = "SeDebugPrivilege";
 const SE_INC_QUOTA_NAME: &str = "SeIncreaseQuotaPrivilege";
 const SE_PROFILE_SINGLE_PROCESS_NAME: &str = "SeProfileSingleProcessPrivilege";
 
+/// Output of SystemMemoryListInformation queries (all counts are in pages)
+#[repr(C)]
+#[derive(Default)]
+struct SYSTEM_MEMORY_LIST_INFORMATION {
+    zero_page_count: usize,
+    free_page_count: usize,
+    modified_page_count: usize,
+    modified_no_write_page_count: usize,
+    bad_page_count: usize,
+    page_count_by_priority: [usize; 8],
+    repurposed_pages_by_priority: [usize; 8],
+    modified_page_count_page_file: usize,
+}
+
+/// Output of SystemFileCacheInformation queries
+#[repr(C)]
+#[derive(Default)]
+struct SYSTEM_FILECACHE_INFORMATION {
+    current_size: usize,
+    peak_size: usize,
+    page_fault_count: u32,
+    minimum_working_set: usize,
+    maximum_working_set: usize,
+    current_size_incl_transition: usize,
+    peak_size_incl_transition: usize,
+    transition_repurpose_count: u32,
+    flags: u32,
+}
+
+/// SystemStoreInformation envelope (undocumented, layout from the NT headers)
+#[repr(C)]
+struct SYSTEM_STORE_INFORMATION {
+    /// Version in bits 0-7, store information class in bits 8-31
+    version_and_class: u32,
+    data: *mut std::ffi::c_void,
+    length: u32,
+}
+
+/// Memory compression store statistics
+#[repr(C)]
+#[derive(Default)]
+struct SM_MEM_COMPRESSION_INFO_REQUEST {
+    /// Version in bits 0-7
+    version: u32,
+    compression_pid: u32,
+    /// In pages
+    working_set_size: u32,
+    total_data_compressed: usize,
+    total_compressed_size: usize,
+    total_unique_data_compressed: usize,
+}
+
 #[repr(C)]
 struct MEMORY_COMBINE_INFORMATION_EX {
     handle: usize,
@@ -68,6 +158,14 @@ static PROCESS_CACHE: Lazy<RwLock<ProcessCache>> = Lazy::new(|| {
     })
 });
 
+/// Percent of the candidates of the running Working Set trim already handled
+static WORKING_SET_PROGRESS: AtomicU8 = AtomicU8::new(0);
+
+/// Progress of the running per-process Working Set trim, polled by the engine
+pub fn working_set_progress() -> u8 {
+    WORKING_SET_PROGRESS.load(Ordering::Relaxed)
+}
+
 /// Get Global Memory Status Extended
 fn gmse() -> Result<MEMORYSTATUSEX> {
     unsafe {
@@ -80,8 +178,22 @@ fn gmse() -> Result<MEMORYSTATUSEX> {
     }
 }
 
+/// RAM installed in the slots (SMBIOS), read once; `None` when the firmware does not report it
+static INSTALLED_MEMORY: Lazy<Option<u64>> = Lazy::new(|| {
+    let mut kilobytes = 0u64;
+    if unsafe { GetPhysicallyInstalledSystemMemory(&mut kilobytes) } == 0 || kilobytes == 0 {
+        tracing::debug!("Installed memory not reported by the firmware");
+        return None;
+    }
+    Some(kilobytes.saturating_mul(1024))
+});
+
 /// Get current memory information
 /// Returns physical and commit memory statistics
+///
+/// Physical percentages are computed over the usable RAM (installed minus
+/// what firmware and devices reserve), so thresholds and the tray agree with
+/// the total Windows shows; `load_percent` is the load Windows reports.
 pub fn memory_info() -> Result<MemoryInfo> {
     let st = gmse()?;
     let phys_free = st.ullAvailPhys;
@@ -89,14 +201,243 @@ pub fn memory_info() -> Result<MemoryInfo> {
     let load = st.dwMemoryLoad as u32;
     let commit_free = st.ullAvailPageFile;
     let commit_total = st.ullTotalPageFile;
+    let installed = INSTALLED_MEMORY.unwrap_or(phys_total).max(phys_total);
 
     Ok(MemoryInfo {
-        physical: mk_stats(phys_free as u64, phys_total as u64, Some(load as u8)),
+        physical: mk_stats(phys_free as u64, phys_total as u64, None),
         commit: mk_stats(commit_free as u64, commit_total as u64, None),
         load_percent: load,
+        installed_bytes: installed,
+        hardware_reserved_bytes: installed - phys_total,
+        game_mode_active: crate::system::game_mode::game_mode_active(),
     })
 }
 
+pub(crate) fn page_size() -> u64 {
+    unsafe {
+        let mut info: SYSTEM_INFO = std::mem::zeroed();
+        GetSystemInfo(&mut info);
+        if info.dwPageSize > 0 {
+            info.dwPageSize as u64
+        } else {
+            4096
+        }
+    }
+}
+
+/// Query the standby list size per page priority
+///
+/// `STANDBY_LIST_LOW` only purges priority 0, while `STANDBY_LIST` purges all
+/// eight priorities; the breakdown shows how much each of them would free.
+pub fn standby_breakdown() -> Result<StandbyBreakdown> {
+    ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+
+    let mut info = SYSTEM_MEMORY_LIST_INFORMATION::default();
+    let status = unsafe {
+        NtQuerySystemInformation(
+            SYS_MEMORY_LIST_INFORMATION,
+            (&mut info as *mut SYSTEM_MEMORY_LIST_INFORMATION) as _,
+            size_of::<SYSTEM_MEMORY_LIST_INFORMATION>() as u32,
+            ptr::null_mut(),
+        )
+    };
+    if status < 0 {
+        return Err(nt_error(
+            status,
+            format!(
+                "NtQuerySystemInformation(class={}) failed: 0x{:08X}",
+                SYS_MEMORY_LIST_INFORMATION, status as u32
+            ),
+        ));
+    }
+
+    Ok(StandbyBreakdown::from_page_counts(
+        page_size(),
+        info.page_count_by_priority.map(|pages| pages as u64),
+        (info.modified_page_count + info.modified_no_write_page_count) as u64,
+        info.free_page_count as u64,
+        info.zero_page_count as u64,
+    ))
+}
+
+/// Query the memory compression store
+///
+/// Uses the same undocumented SystemStoreInformation request as Task
+/// Manager's "Compressed" value. Fails on Windows versions without a
+/// compression store or when memory compression is turned off.
+pub fn compression_info() -> Result<CompressionInfo> {
+    ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+
+    let mut request = SM_MEM_COMPRESSION_INFO_REQUEST {
+        version: STORE_MEM_COMPRESSION_INFO_VERSION,
+        ..Default::default()
+    };
+    let mut store = SYSTEM_STORE_INFORMATION {
+        version_and_class: STORE_INFORMATION_VERSION | (STORE_MEM_COMPRESSION_INFO_REQUEST << 8),
+        data: (&mut request as *mut SM_MEM_COMPRESSION_INFO_REQUEST) as _,
+        length: size_of::<SM_MEM_COMPRESSION_INFO_REQUEST>() as u32,
+    };
+    let status = unsafe {
+        NtQuerySystemInformation(
+            SYS_STORE_INFORMATION,
+            (&mut store as *mut SYSTEM_STORE_INFORMATION) as _,
+            size_of::<SYSTEM_STORE_INFORMATION>() as u32,
+            ptr::null_mut(),
+        )
+    };
+    if status < 0 {
+        return Err(nt_error(
+            status,
+            format!(
+                "NtQuerySystemInformation(class={}) failed: 0x{:08X}",
+                SYS_STORE_INFORMATION, status as u32
+            ),
+        ));
+    }
+
+    Ok(CompressionInfo::from_store(
+        page_size(),
+        request.compression_pid,
+        request.working_set_size as u64,
+        request.total_data_compressed as u64,
+        request.total_compressed_size as u64,
+    ))
+}
+
+/// Current size in bytes of the system file cache working set
+pub fn system_file_cache_size() -> Result<u64> {
+    let mut info = SYSTEM_FILECACHE_INFORMATION::default();
+    let status = unsafe {
+        NtQuerySystemInformation(
+            SYS_FILE_CACHE_INFORMATION,
+            (&mut info as *mut SYSTEM_FILECACHE_INFORMATION) as _,
+            size_of::<SYSTEM_FILECACHE_INFORMATION>() as u32,
+            ptr::null_mut(),
+        )
+    };
+    if status < 0 {
+        return Err(nt_error(
+            status,
+            format!(
+                "NtQuerySystemInformation(class={}) failed: 0x{:08X}",
+                SYS_FILE_CACHE_INFORMATION, status as u32
+            ),
+        ));
+    }
+    Ok(info.current_size as u64)
+}
+
+/// Size of the system file cache working set and the limits in effect
+pub fn file_cache_info() -> Result<FileCacheInfo> {
+    let mut info = SYSTEM_FILECACHE_INFORMATION::default();
+    let status = unsafe {
+        NtQuerySystemInformation(
+            SYS_FILE_CACHE_INFORMATION,
+            (&mut info as *mut SYSTEM_FILECACHE_INFORMATION) as _,
+            size_of::<SYSTEM_FILECACHE_INFORMATION>() as u32,
+            ptr::null_mut(),
+        )
+    };
+    if status < 0 {
+        return Err(nt_error(
+            status,
+            format!(
+                "NtQuerySystemInformation(class={}) failed: 0x{:08X}",
+                SYS_FILE_CACHE_INFORMATION, status as u32
+            ),
+        ));
+    }
+
+    let (mut min_size, mut max_size, mut flags) = (0usize, 0usize, 0u32);
+    if unsafe { GetSystemFileCacheSize(&mut min_size, &mut max_size, &mut flags) } == 0 {
+        let error = unsafe { GetLastError() };
+        bail!("GetSystemFileCacheSize failed: {}", error);
+    }
+
+    Ok(FileCacheInfo {
+        current_bytes: info.current_size as u64,
+        peak_bytes: info.peak_size as u64,
+        min_limit_bytes: min_size as u64,
+        max_limit_bytes: max_size as u64,
+        hard_max: flags & FILE_CACHE_MAX_HARD_ENABLE != 0,
+        hard_min: flags & FILE_CACHE_MIN_HARD_ENABLE != 0,
+    })
+}
+
+/// Limit the system file cache working set to `min_bytes..=max_bytes`
+///
+/// With `hard` the maximum is enforced, otherwise the memory manager only
+/// trims the cache towards it when memory runs short. The limit lasts until
+/// it is changed again or Windows restarts.
+pub fn set_file_cache_limit(min_bytes: u64, max_bytes: u64, hard: bool) -> Result<()> {
+    ensure_privileges(&[SE_INC_QUOTA_NAME])?;
+    let flags = if hard {
+        FILE_CACHE_MAX_HARD_ENABLE | FILE_CACHE_MIN_HARD_DISABLE
+    } else {
+        FILE_CACHE_MAX_HARD_DISABLE | FILE_CACHE_MIN_HARD_DISABLE
+    };
+    if unsafe { SetSystemFileCacheSize(min_bytes as usize, max_bytes as usize, flags) } == 0 {
+        bail!(
+            "SetSystemFileCacheSize({}MB, {}MB) failed: {}",
+            min_bytes / (1024 * 1024),
+            max_bytes / (1024 * 1024),
+            unsafe { GetLastError() }
+        );
+    }
+    Ok(())
+}
+
+/// Stop enforcing the file cache limits, the cache grows freely again
+pub fn clear_file_cache_limit() -> Result<()> {
+    ensure_privileges(&[SE_INC_QUOTA_NAME])?;
+    let current = file_cache_info()?;
+    let flags = FILE_CACHE_MAX_HARD_DISABLE | FILE_CACHE_MIN_HARD_DISABLE;
+    let ok = unsafe {
+        SetSystemFileCacheSize(
+            current.min_limit_bytes as usize,
+            current.max_limit_bytes as usize,
+            flags,
+        )
+    };
+    if ok == 0 {
+        let error = unsafe { GetLastError() };
+        bail!("SetSystemFileCacheSize failed: {}", error);
+    }
+    Ok(())
+}
+
+/// Call NtSetSystemInformation with an out-of-range memory list command
+///
+/// Nothing is changed: STATUS_INVALID_PARAMETER means the call reached the
+/// memory manager, while a hook from security software or a missing privilege
+/// shows up as STATUS_ACCESS_DENIED / STATUS_PRIVILEGE_NOT_HELD.
+pub fn probe_memory_list_command() -> Result<()> {
+    ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+
+    let mut cmd = MEM_COMMAND_MAX;
+    let status = unsafe {
+        NtSetSystemInformation(
+            SYS_MEMORY_LIST_INFORMATION,
+            (&mut cmd as *mut u32) as _,
+            size_of::<u32>() as u32,
+        )
+    };
+    if status >= 0 {
+        Ok(())
+    } else if status == STATUS_INVALID_PARAMETER {
+        note_swallowed(status, "Memory list probe");
+        Ok(())
+    } else {
+        Err(nt_error(
+            status,
+            format!(
+                "NtSetSystemInformation(class={}) failed: 0x{:08X}",
+                SYS_MEMORY_LIST_INFORMATION, status as u32
+            ),
+        ))
+    }
+}
+
 /// Make NT system call with u32 command
 pub fn nt_call_u32(class: u32, command: u32) -> Result<()> {
     // FIX: Retry logic for antivirus compatibility
@@ -124,6 +465,7 @@ pub fn nt_call_u32(class: u32, command: u32) -> Result<()> {
                     // STATUS_UNSUCCESSFUL (0xC0000001)
                     if attempt < MAX_RETRIES {
                         tracing::debug!("NtSetSystemInformation blocked (possible antivirus), retrying (attempt {})...", attempt);
+                        note_swallowed(status, &retry_call(class, command, attempt));
                         std::thread::sleep(std::time::Duration::from_millis(100 * attempt as u64));
                         continue;
                     }
@@ -135,6 +477,7 @@ pub fn nt_call_u32(class: u32, command: u32) -> Result<()> {
                             "NtSetSystemInformation access denied, retrying (attempt {})...",
                             attempt
                         );
+                        note_swallowed(status, &retry_call(class, command, attempt));
                         std::thread::sleep(std::time::Duration::from_millis(100 * attempt as u64));
                         continue;
                     }
@@ -154,7 +497,20 @@ pub fn nt_call_u32(class: u32, command: u32) -> Result<()> {
         class, command, MAX_RETRIES, last_error
     );
     tracing::warn!("{}", error_msg);
-    Err(anyhow::anyhow!("{}", error_msg))
+    Err(nt_error(last_error, error_msg))
+}
+
+/// Error of a failed NT call, with the NTSTATUS as structured cause
+fn nt_error(status: i32, message: String) -> anyhow::Error {
+    anyhow::Error::new(MemoryOpError::from_nt_status(status)).context(message)
+}
+
+/// Name of a retried `nt_call_u32` attempt, for `note_swallowed`
+fn retry_call(class: u32, command: u32, attempt: u32) -> String {
+    format!(
+        "NtSetSystemInformation(class={}, cmd={}) attempt {}",
+        class, command, attempt
+    )
 }
 
 pub fn optimize_standby_list(low_priority: bool) -> Result<()> {
@@ -255,7 +611,7 @@ pub fn optimize_modified_page_list_with_stealth(use_stealth: bool) -> Result<()>
                 }
                 Err(e) => {
                     tracing::warn!("⚠ Advanced modified page flush failed ({}), using standard API", e);
-                    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, 3) // MEM_FLUSH_MODIFIED_LIST equivalent
+                    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, MEM_FLUSH_MODIFIED_LIST)
                 }
             }
         } else {
@@ -267,7 +623,7 @@ pub fn optimize_modified_page_list_with_stealth(use_stealth: bool) -> Result<()>
                 }
                 Err(e) => {
                     tracing::warn!("⚠ Advanced modified page flush failed ({}), using standard API", e);
-                    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, 3) // MEM_FLUSH_MODIFIED_LIST equivalent
+                    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, MEM_FLUSH_MODIFIED_LIST)
                 }
             }
         }
@@ -285,22 +641,50 @@ pub fn optimize_registry_cache() -> Result<()> {
             }
             Err(e) => {
                 tracing::warn!("⚠ Advanced registry optimization failed ({}), using standard API", e);
-                unsafe {
-                    let status = ntapi::ntexapi::NtSetSystemInformation(
-                        155, // SYS_REGISTRY_RECONCILIATION_INFORMATION
-                        ptr::null_mut(),
-                        0,
-                    );
-                    if status < 0 {
-                        tracing::warn!("Registry cache optimization not available: 0x{:x}", status);
-                    }
-                    Ok(())
-                }
+                reconcile_registry();
+                Ok(())
             }
         }
     })
 }
 
+/// Write the registry hives back to disk
+fn reconcile_registry() {
+    let status = unsafe {
+        NtSetSystemInformation(SYS_REGISTRY_RECONCILIATION_INFORMATION, ptr::null_mut(), 0)
+    };
+    if status < 0 {
+        tracing::warn!("Registry cache optimization not available: 0x{:x}", status);
+        note_swallowed(status, "Registry reconciliation");
+    }
+}
+
+// Documented-only path (`use_documented_apis_only`): plain NtSetSystemInformation
+// calls, never the advanced module
+
+/// Purge the standby list, or only its low priority pages
+pub fn purge_standby_list_documented(low_priority: bool) -> Result<()> {
+    ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+    let command = if low_priority {
+        MEM_PURGE_LOW_PRIORITY_STANDBY_LIST
+    } else {
+        MEM_PURGE_STANDBY_LIST
+    };
+    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, command)
+}
+
+/// Write the modified page list to disk
+pub fn flush_modified_list_documented() -> Result<()> {
+    ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+    nt_call_u32(SYS_MEMORY_LIST_INFORMATION, MEM_FLUSH_MODIFIED_LIST)
+}
+
+/// Write the registry hives back to disk
+pub fn optimize_registry_cache_documented() -> Result<()> {
+    reconcile_registry();
+    Ok(())
+}
+
 pub fn optimize_system_file_cache() -> Result<()> {
     ensure_privileges(&[SE_INC_QUOTA_NAME])?;
     crate::antivirus::whitelist::safe_memory_operation(|| -> Result<(), anyhow::Error> {
@@ -491,94 +875,696 @@ fn empty_ws_process(pid: u32) -> bool {
     false
 }
 
+/// Trim one process with the configured strategy
+fn trim_with_strategy(pid: u32, strategy: TrimStrategy) -> bool {
+    match strategy {
+        TrimStrategy::Gentle => lower_memory_priority(pid),
+        TrimStrategy::Standard => empty_ws_process(pid),
+        TrimStrategy::Aggressive => hard_trim_process(pid),
+    }
+}
+
+/// Gentle strategy: nothing is trimmed now, the pages of the process are the
+/// first the memory manager repurposes once memory runs short
+fn lower_memory_priority(pid: u32) -> bool {
+    use windows_sys::Win32::System::Threading::{
+        ProcessMemoryPriority, SetProcessInformation, MEMORY_PRIORITY_INFORMATION,
+        MEMORY_PRIORITY_LOW, PROCESS_SET_INFORMATION,
+    };
+
+    unsafe {
+        let h: HANDLE = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if h.is_null() {
+            return false;
+        }
+        let info = MEMORY_PRIORITY_INFORMATION {
+            MemoryPriority: MEMORY_PRIORITY_LOW,
+        };
+        let ok = SetProcessInformation(
+            h,
+            ProcessMemoryPriority,
+            &info as *const _ as *const _,
+            size_of::<MEMORY_PRIORITY_INFORMATION>() as u32,
+        ) != 0;
+        CloseHandle(h);
+        ok
+    }
+}
+
+/// Aggressive strategy: force the working set under a hard maximum, then put
+/// the original limits back so the process can grow again
+fn hard_trim_process(pid: u32) -> bool {
+    use windows_sys::Win32::System::Memory::{
+        GetProcessWorkingSetSizeEx, SetProcessWorkingSetSizeEx, QUOTA_LIMITS_HARDWS_MAX_ENABLE,
+        QUOTA_LIMITS_HARDWS_MIN_DISABLE,
+    };
+
+    unsafe {
+        let h: HANDLE = OpenProcess(PROCESS_SET_QUOTA | PROCESS_QUERY_INFORMATION, 0, pid);
+        if h.is_null() {
+            return false;
+        }
+        let (mut min, mut max, mut flags) = (0usize, 0usize, 0u32);
+        if GetProcessWorkingSetSizeEx(h, &mut min, &mut max, &mut flags) == 0 {
+            CloseHandle(h);
+            return false;
+        }
+        let capped = SetProcessWorkingSetSizeEx(
+            h,
+            HARD_TRIM_MIN_BYTES,
+            HARD_TRIM_MAX_BYTES,
+            QUOTA_LIMITS_HARDWS_MIN_DISABLE | QUOTA_LIMITS_HARDWS_MAX_ENABLE,
+        ) != 0;
+        // Restore even when the cap failed, the process must keep its own limits
+        if SetProcessWorkingSetSizeEx(h, min, max, flags) == 0 {
+            tracing::warn!("Failed to restore the working set limits of PID {}", pid);
+        }
+        CloseHandle(h);
+        capped
+    }
+}
+
+/// Soft working set limits just under `target_bytes`, then the original limits back
+///
+/// With soft limits the memory manager releases the least recently used pages
+/// down to the target but does not hold the process there, so it can fault
+/// its pages back in gradually instead of all at once after a full trim.
+fn advise_working_set(pid: u32, target_bytes: usize) -> bool {
+    use windows_sys::Win32::System::Memory::{
+        GetProcessWorkingSetSizeEx, SetProcessWorkingSetSizeEx, QUOTA_LIMITS_HARDWS_MAX_DISABLE,
+        QUOTA_LIMITS_HARDWS_MIN_DISABLE,
+    };
+
+    unsafe {
+        let h: HANDLE = OpenProcess(PROCESS_SET_QUOTA | PROCESS_QUERY_INFORMATION, 0, pid);
+        if h.is_null() {
+            return false;
+        }
+        let (mut min, mut max, mut flags) = (0usize, 0usize, 0u32);
+        if GetProcessWorkingSetSizeEx(h, &mut min, &mut max, &mut flags) == 0 {
+            CloseHandle(h);
+            return false;
+        }
+        let advised = SetProcessWorkingSetSizeEx(
+            h,
+            min.min(target_bytes),
+            target_bytes,
+            QUOTA_LIMITS_HARDWS_MIN_DISABLE | QUOTA_LIMITS_HARDWS_MAX_DISABLE,
+        ) != 0;
+        if SetProcessWorkingSetSizeEx(h, min, max, flags) == 0 {
+            tracing::warn!("Failed to restore the working set limits of PID {}", pid);
+        }
+        CloseHandle(h);
+        advised
+    }
+}
+
+/// Process of another logged on user, left alone when `own_session` is set
+fn in_skipped_session(pid: u32, own_session: Option<u32>) -> bool {
+    own_session.is_some_and(|current| crate::system::sessions::in_other_user_session(pid, current))
+}
+
+/// Protection verdict for a process, unless the expert override allows system trims
+fn protected_reason(pid: u32, name: &str, allow_system_trim: bool) -> Option<&'static str> {
+    if allow_system_trim {
+        None
+    } else {
+        protection_reason(pid, name)
+    }
+}
+
+/// Descendants of the excluded processes, when `exclude_children` is set
+fn excluded_children(exclusions: &[String], exclude_children: bool) -> ExcludedChildren {
+    if exclusions.is_empty() {
+        return ExcludedChildren::default();
+    }
+    let excluded: HashSet<String> = exclusions
+        .iter()
+        .map(|e| normalize_process_target(e))
+        .collect();
+    ExcludedChildren::detect(exclude_children, |name| excluded.contains(name))
+}
+
 /// Optimize working set with optional stealth mode
-pub fn optimize_working_set_with_stealth(exclusions: &[String], use_stealth: bool) -> Result<()> {
-    ensure_privileges(&[SE_DEBUG_NAME])?;
+///
+/// With `busy_cpu_percent` > 0 processes above that CPU usage are skipped;
+/// this needs the per-process path, so stealth mode is not used then. The
+/// same goes for any `strategy` other than the standard one.
+/// Unless `allow_system_trim` is set, system-account, protected and
+/// critical-flagged processes are never trimmed. The processes in use that
+/// `guard` selects (foreground window, audio, editors with open documents)
+/// are skipped, which needs the per-process path as well. With
+/// `skip_other_sessions` the processes of other logged on users are skipped,
+/// again per process. With `exclude_children` the descendants of excluded
+/// processes are skipped too, per process once any are running.
+#[allow(clippy::too_many_arguments)]
+pub fn optimize_working_set_with_stealth(
+    exclusions: &[String],
+    use_stealth: bool,
+    busy_cpu_percent: u8,
+    allow_system_trim: bool,
+    guard: &ActivityGuard,
+    strategy: TrimStrategy,
+    skip_other_sessions: bool,
+    exclude_children: bool,
+) -> Result<Vec<ProcessTrimResult>> {
+    let all_processes = ensure_debug_privilege()?;
+    WORKING_SET_PROGRESS.store(0, Ordering::Relaxed);
+
+    let active = guard.detect(&process_list());
+    let own_session = crate::system::sessions::trim_session_filter(skip_other_sessions);
+    let children = excluded_children(exclusions, exclude_children);
     
     crate::antivirus::whitelist::safe_memory_operation(|| {
-        if use_stealth {
+        if use_stealth
+            && all_processes
+            && busy_cpu_percent == 0
+            && active.is_empty()
+            && own_session.is_none()
+            && children.is_empty()
+            && strategy == TrimStrategy::Standard
+        {
             // Try stealth optimization for working set
-            match crate::memory::advanced::empty_working_set_stealth(exclusions) {
+            match crate::memory::advanced::empty_working_set_stealth(exclusions, allow_system_trim) {
                 Ok(_) => {
                     tracing::info!("✓ Working Set optimization successful (stealth mode with indirect syscalls)");
-                    Ok(())
+                    Ok(Vec::new())
                 }
                 Err(e) => {
                     tracing::warn!("⚠ Stealth Working Set optimization failed ({}), using standard API", e);
                     // Fallback to standard implementation
-                    optimize_working_set_standard(exclusions)
+                    optimize_working_set_standard(
+                        exclusions,
+                        busy_cpu_percent,
+                        allow_system_trim,
+                        &active,
+                        &children,
+                        strategy,
+                        own_session,
+                    )
                 }
             }
         } else {
             // Use standard implementation
-            optimize_working_set_standard(exclusions)
+            optimize_working_set_standard(
+                exclusions,
+                busy_cpu_percent,
+                allow_system_trim,
+                &active,
+                &children,
+                strategy,
+                own_session,
+            )
         }
     })
 }
 
+/// CPU usage of the candidate processes, sampled only when busy processes are skipped
+fn sample_candidates(candidates: &[(u32, String)], busy_cpu_percent: u8) -> HashMap<u32, f32> {
+    if busy_cpu_percent == 0 {
+        return HashMap::new();
+    }
+    let pids: Vec<u32> = candidates.iter().map(|(pid, _)| *pid).collect();
+    sample_cpu_usage(&pids, CPU_SAMPLE_WINDOW)
+}
+
+/// Trim one process unless it is above the CPU threshold
+fn trim_unless_busy(
+    pid: u32,
+    name: &str,
+    cpu: &HashMap<u32, f32>,
+    busy_cpu_percent: u8,
+    strategy: TrimStrategy,
+) -> ProcessTrimResult {
+    let cpu_percent = cpu.get(&pid).copied();
+    let mut result = match cpu_percent {
+        Some(pct) if busy_cpu_percent > 0 && pct > busy_cpu_percent as f32 => {
+            tracing::debug!("Skipping busy process {} (PID: {}, {:.0}% CPU)", name, pid, pct);
+            ProcessTrimResult::skipped(pid, name, format!("Busy ({:.0}% CPU)", pct))
+        }
+        _ => ProcessTrimResult::attempted(pid, name, trim_with_strategy(pid, strategy)),
+    };
+    result.cpu_percent = cpu_percent;
+    result
+}
+
+/// SeDebugPrivilege for a Working Set trim
+///
+/// Without elevation the privilege cannot be held and only the user's own
+/// processes can be opened; the trim then goes on over those, one by one.
+/// Returns whether every process is within reach.
+fn ensure_debug_privilege() -> Result<bool> {
+    match ensure_privileges(&[SE_DEBUG_NAME]) {
+        Ok(()) => Ok(true),
+        Err(e) if !crate::system::is_app_elevated() => {
+            tracing::info!("Not elevated, trimming own processes only: {:#}", e);
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Trim the candidates in order, publishing the share done for `working_set_progress`
+///
+/// Without `all_processes` the ones that cannot be opened are reported as
+/// skipped rather than failed.
+fn trim_candidates(
+    candidates: &[(u32, String)],
+    busy_cpu_percent: u8,
+    strategy: TrimStrategy,
+    all_processes: bool,
+) -> Vec<ProcessTrimResult> {
+    let cpu = sample_candidates(candidates, busy_cpu_percent);
+    let mut results = Vec::with_capacity(candidates.len());
+    for (done, (pid, name)) in candidates.iter().enumerate() {
+        let mut result = trim_unless_busy(*pid, name, &cpu, busy_cpu_percent, strategy);
+        if !all_processes && !result.trimmed && result.skipped.is_none() {
+            result.skipped = Some(NOT_ELEVATED_REASON.to_string());
+        }
+        results.push(result);
+        let percent = (done + 1) * 100 / candidates.len();
+        WORKING_SET_PROGRESS.store(percent as u8, Ordering::Relaxed);
+    }
+    results
+}
+
 /// Standard working set optimization without stealth
-fn optimize_working_set_standard(exclusions: &[String]) -> Result<()> {
+///
+/// `own_session` is set when the processes of other user sessions are skipped.
+fn optimize_working_set_standard(
+    exclusions: &[String],
+    busy_cpu_percent: u8,
+    allow_system_trim: bool,
+    active: &ActiveProcesses,
+    children: &ExcludedChildren,
+    strategy: TrimStrategy,
+    own_session: Option<u32>,
+) -> Result<Vec<ProcessTrimResult>> {
     // IMPORTANT: Always acquire SE_DEBUG_NAME to allow access to all processes
     // Even if we use the global method, SE_DEBUG_NAME ensures it works on all processes
-    ensure_privileges(&[SE_DEBUG_NAME, SE_PROFILE_SINGLE_PROCESS_NAME])?;
+    let all_processes = ensure_debug_privilege()?;
+    if all_processes {
+        ensure_privileges(&[SE_PROFILE_SINGLE_PROCESS_NAME])?;
+    }
 
-    // Get foreground window PID to exclude it (prevents FPS drops in games)
-    let foreground_pid = get_foreground_process_pid();
-    
     // Convert exclusions to lowercase for comparison
     let exclusions_lower: Vec<String> = exclusions.iter().map(|s| s.to_lowercase()).collect();
 
     // If there are no custom exclusions, use fast global optimization
     // This method requires SE_DEBUG_NAME to work correctly on system processes
-    if exclusions_lower.is_empty() {
+    // It trims every process, so it is only allowed with the expert override
+    // and empties the working sets, so only with the standard strategy
+    if exclusions_lower.is_empty()
+        && all_processes
+        && busy_cpu_percent == 0
+        && allow_system_trim
+        && active.is_empty()
+        && own_session.is_none()
+        && strategy == TrimStrategy::Standard
+    {
         return crate::antivirus::whitelist::safe_memory_operation(|| {
             nt_call_u32(SYS_MEMORY_LIST_INFORMATION, MEM_EMPTY_WORKING_SETS)
-        });
+        })
+        .map(|_| Vec::new());
     }
 
     // Create HashSet for user exclusions
     let user_exclusions: HashSet<&str> = exclusions_lower.iter().map(|s| s.as_str()).collect();
 
-    let processes = process_list();
-    let mut success_count = 0;
-    let mut skip_count = 0;
-    let mut critical_skip = 0;
-    let mut foreground_skip = 0;
+    let mut results = Vec::new();
+    let mut candidates = Vec::new();
 
-    for (pid, name) in processes {
-        // FIRST check if it's the foreground process
-        if Some(pid) == foreground_pid {
-            tracing::debug!("Skipping foreground process {} (PID: {})", name, pid);
-            foreground_skip += 1;
+    for (pid, name) in process_list() {
+        // FIRST check if the user is working with it (prevents stutter in games and music)
+        if let Some(reason) = active.skip_reason(pid) {
+            tracing::debug!(
+                "Skipping process in use {} (PID: {}): {}",
+                name,
+                pid,
+                reason
+            );
+            results.push(ProcessTrimResult::skipped(pid, &name, reason));
             continue;
         }
 
         // THEN check if it's a critical process
         if is_critical_process(&name) {
-            critical_skip += 1;
+            results.push(ProcessTrimResult::skipped(pid, &name, "Critical process"));
+            continue;
+        }
+
+        // THEN check what Windows itself says about the process
+        if let Some(reason) = protected_reason(pid, &name, allow_system_trim) {
+            results.push(ProcessTrimResult::skipped(pid, &name, reason));
             continue;
         }
 
         // THEN check user exclusions
         if user_exclusions.contains(name.as_str()) {
-            skip_count += 1;
+            results.push(ProcessTrimResult::skipped(pid, &name, "Excluded"));
             continue;
         }
 
-        if empty_ws_process(pid) {
-            success_count += 1;
+        // AND the processes an excluded one started
+        if let Some(parent) = children.excluded_ancestor(pid) {
+            tracing::debug!("Skipping {} (PID: {}), started by {}", name, pid, parent);
+            results.push(ProcessTrimResult::skipped(
+                pid,
+                &name,
+                EXCLUDED_PARENT_REASON,
+            ));
+            continue;
+        }
+
+        // THEN leave the processes of other logged on users alone
+        if in_skipped_session(pid, own_session) {
+            results.push(ProcessTrimResult::skipped(pid, &name, "Other user session"));
+            continue;
         }
+
+        candidates.push((pid, name));
     }
 
+    // FINALLY skip processes that are busy right now
+    results.extend(trim_candidates(
+        &candidates,
+        busy_cpu_percent,
+        strategy,
+        all_processes,
+    ));
+
     tracing::debug!(
-        "Working set optimization: {} cleaned, {} user excluded, {} critical protected, {} foreground protected",
-        success_count,
-        skip_count,
-        critical_skip,
-        foreground_skip
+        "Working set optimization: {} cleaned, {} skipped, {} failed",
+        results.iter().filter(|r| r.trimmed).count(),
+        results.iter().filter(|r| r.skipped.is_some()).count(),
+        results.iter().filter(|r| !r.trimmed && r.skipped.is_none()).count()
     );
 
-    Ok(())
+    Ok(results)
+}
+
+/// Normalize a process target to the form used by `process_list()` (lowercase, no ".exe")
+fn normalize_process_target(target: &str) -> String {
+    let lower = target.trim().to_lowercase();
+    lower.strip_suffix(".exe").unwrap_or(&lower).to_string()
+}
+
+/// Trim the working set of selected processes only
+///
+/// Each target can be a PID or a process name (with or without ".exe").
+/// Critical processes and user exclusions are always skipped, even when targeted explicitly.
+/// So are the processes of other user sessions with `skip_other_sessions`, and
+/// the descendants of excluded processes with `exclude_children`.
+pub fn optimize_working_set_targeted(
+    targets: &[String],
+    exclusions: &[String],
+    busy_cpu_percent: u8,
+    allow_system_trim: bool,
+    strategy: TrimStrategy,
+    skip_other_sessions: bool,
+    exclude_children: bool,
+) -> Result<Vec<ProcessTrimResult>> {
+    let all_processes = ensure_debug_privilege()?;
+    WORKING_SET_PROGRESS.store(0, Ordering::Relaxed);
+
+    let mut target_pids: HashSet<u32> = HashSet::new();
+    let mut target_names: HashSet<String> = HashSet::new();
+    for target in targets {
+        let trimmed = target.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        match trimmed.parse::<u32>() {
+            Ok(pid) => {
+                target_pids.insert(pid);
+            }
+            Err(_) => {
+                target_names.insert(normalize_process_target(trimmed));
+            }
+        }
+    }
+
+    if target_pids.is_empty() && target_names.is_empty() {
+        bail!("No valid process targets specified");
+    }
+
+    let user_exclusions: HashSet<String> = exclusions
+        .iter()
+        .map(|s| normalize_process_target(s))
+        .collect();
+    let own_session = crate::system::sessions::trim_session_filter(skip_other_sessions);
+    let children = excluded_children(exclusions, exclude_children);
+
+    let mut matched = 0;
+    let mut results = Vec::new();
+    let mut candidates = Vec::new();
+
+    for (pid, name) in process_list() {
+        if !target_pids.contains(&pid) && !target_names.contains(&name) {
+            continue;
+        }
+        matched += 1;
+
+        if is_critical_process(&name) {
+            tracing::debug!("Skipping critical process {} (PID: {})", name, pid);
+            results.push(ProcessTrimResult::skipped(pid, &name, "Critical process"));
+            continue;
+        }
+
+        if let Some(reason) = protected_reason(pid, &name, allow_system_trim) {
+            tracing::debug!("Skipping {} process {} (PID: {})", reason, name, pid);
+            results.push(ProcessTrimResult::skipped(pid, &name, reason));
+            continue;
+        }
+
+        if user_exclusions.contains(&name) {
+            results.push(ProcessTrimResult::skipped(pid, &name, "Excluded"));
+            continue;
+        }
+
+        if children.excluded_ancestor(pid).is_some() {
+            results.push(ProcessTrimResult::skipped(
+                pid,
+                &name,
+                EXCLUDED_PARENT_REASON,
+            ));
+            continue;
+        }
+
+        if in_skipped_session(pid, own_session) {
+            results.push(ProcessTrimResult::skipped(pid, &name, "Other user session"));
+            continue;
+        }
+
+        candidates.push((pid, name));
+    }
+
+    results.extend(trim_candidates(
+        &candidates,
+        busy_cpu_percent,
+        strategy,
+        all_processes,
+    ));
+
+    tracing::info!(
+        "Targeted working set optimization: {} matched, {} cleaned, {} skipped",
+        matched,
+        results.iter().filter(|r| r.trimmed).count(),
+        results.iter().filter(|r| r.skipped.is_some()).count()
+    );
+
+    if matched == 0 {
+        bail!("No running process matches the requested targets");
+    }
+
+    Ok(results)
+}
+
+/// Release a share of the working set of the named processes, without a full trim
+///
+/// Each matching process above `min_bytes` is advised down by `step_percent`
+/// of its working set (never below `min_bytes`) and gets a low memory
+/// priority, see `advise_working_set`. Critical, protected and excluded
+/// processes are skipped like in a targeted trim. Returns the processes that
+/// were advised, with their working set before and after.
+pub fn gentle_working_set_advice(
+    names: &[String],
+    exclusions: &[String],
+    allow_system_trim: bool,
+    step_percent: u8,
+    min_bytes: u64,
+    skip_other_sessions: bool,
+) -> Result<Vec<SingleProcessTrim>> {
+    let names: HashSet<String> = names.iter().map(|n| normalize_process_target(n)).collect();
+    let exclusions: HashSet<String> = exclusions
+        .iter()
+        .map(|e| normalize_process_target(e))
+        .collect();
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    ensure_debug_privilege()?;
+
+    let step = step_percent.min(50) as u64;
+    let own_session = crate::system::sessions::trim_session_filter(skip_other_sessions);
+    let mut advised = Vec::new();
+    for (pid, name) in process_list() {
+        if !names.contains(&name)
+            || exclusions.contains(&name)
+            || is_critical_process(&name)
+            || protected_reason(pid, &name, allow_system_trim).is_some()
+            || in_skipped_session(pid, own_session)
+        {
+            continue;
+        }
+        let before_bytes = match process_working_set(pid) {
+            Some(bytes) if bytes > min_bytes => bytes,
+            _ => continue,
+        };
+        let target = (before_bytes - before_bytes * step / 100).max(min_bytes);
+        if !advise_working_set(pid, target as usize) {
+            tracing::debug!("Working set advice refused by {} (PID: {})", name, pid);
+            continue;
+        }
+        lower_memory_priority(pid);
+        let after_bytes = process_working_set(pid).unwrap_or(before_bytes);
+        advised.push(SingleProcessTrim {
+            pid,
+            name,
+            before_bytes,
+            after_bytes,
+        });
+    }
+    Ok(advised)
+}
+
+/// Sum of the working sets a Working Set trim would touch, without trimming
+///
+/// Applies the same filters as the real trim: critical and excluded
+/// processes are skipped, and `targets` (PIDs or names) restricts the set
+/// when not empty. Processes that cannot be opened are not counted. Like the
+/// full trim, an untargeted estimate skips the processes in use `guard` selects.
+pub fn working_set_estimate(
+    targets: &[String],
+    exclusions: &[String],
+    allow_system_trim: bool,
+    guard: &ActivityGuard,
+    skip_other_sessions: bool,
+    exclude_children: bool,
+) -> u64 {
+    let target_pids: HashSet<u32> = targets.iter().filter_map(|t| t.trim().parse().ok()).collect();
+    let target_names: HashSet<String> = targets
+        .iter()
+        .filter(|t| t.trim().parse::<u32>().is_err())
+        .map(|t| normalize_process_target(t))
+        .collect();
+    let user_exclusions: HashSet<String> = exclusions
+        .iter()
+        .map(|s| normalize_process_target(s))
+        .collect();
+    // Targeted trims do not protect the processes in use
+    let processes = process_list();
+    let active = if targets.is_empty() {
+        guard.detect(&processes)
+    } else {
+        ActiveProcesses::default()
+    };
+    let own_session = crate::system::sessions::trim_session_filter(skip_other_sessions);
+    let children = excluded_children(exclusions, exclude_children);
+
+    let mut total = 0u64;
+    for (pid, name) in processes {
+        if !targets.is_empty() && !target_pids.contains(&pid) && !target_names.contains(&name) {
+            continue;
+        }
+        if active.skip_reason(pid).is_some()
+            || is_critical_process(&name)
+            || user_exclusions.contains(&name)
+            || children.excluded_ancestor(pid).is_some()
+            || protected_reason(pid, &name, allow_system_trim).is_some()
+            || in_skipped_session(pid, own_session)
+        {
+            continue;
+        }
+
+        total += process_working_set(pid).unwrap_or(0);
+    }
+    total
+}
+
+/// Current Working Set of a process, `None` if it cannot be opened
+fn process_working_set(pid: u32) -> Option<u64> {
+    use windows_sys::Win32::System::ProcessStatus::{
+        K32GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    };
+    use windows_sys::Win32::System::Threading::PROCESS_QUERY_LIMITED_INFORMATION;
+
+    unsafe {
+        let h: HANDLE = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if h.is_null() {
+            return None;
+        }
+        let mut counters: PROCESS_MEMORY_COUNTERS = std::mem::zeroed();
+        counters.cb = size_of::<PROCESS_MEMORY_COUNTERS>() as u32;
+        let ok = K32GetProcessMemoryInfo(h, &mut counters, counters.cb) != 0;
+        CloseHandle(h);
+        ok.then_some(counters.WorkingSetSize as u64)
+    }
+}
+
+/// Trim the Working Set of a single process right now
+///
+/// Unlike a targeted optimization this fails loudly: a critical or excluded
+/// process is an error, so the UI can tell the user why nothing was freed.
+pub fn trim_process(
+    pid: u32,
+    exclusions: &[String],
+    allow_system_trim: bool,
+) -> Result<SingleProcessTrim> {
+    let name = match process_list().into_iter().find(|(p, _)| *p == pid) {
+        Some((_, name)) => name,
+        None => bail!("Process {} is not running", pid),
+    };
+
+    if is_critical_process(&name) {
+        bail!("{} is a critical system process and cannot be trimmed", name);
+    }
+    if let Some(reason) = protected_reason(pid, &name, allow_system_trim) {
+        bail!("{} cannot be trimmed: {}", name, reason.to_lowercase());
+    }
+    if exclusions.iter().any(|e| normalize_process_target(e) == name) {
+        bail!("{} is in the process exclusion list", name);
+    }
+
+    ensure_debug_privilege()?;
+
+    let before_bytes = match process_working_set(pid) {
+        Some(bytes) => bytes,
+        None => {
+            return Err(anyhow::Error::new(MemoryOpError::AccessDenied { pid })
+                .context(format!("Cannot open process {} (PID: {})", name, pid)))
+        }
+    };
+    if !empty_ws_process(pid) {
+        bail!("Failed to trim the working set of {} (PID: {})", name, pid);
+    }
+    let after_bytes = process_working_set(pid).unwrap_or(0);
+
+    let result = SingleProcessTrim {
+        pid,
+        name,
+        before_bytes,
+        after_bytes,
+    };
+    tracing::info!(
+        "Trimmed {} (PID: {}): {} -> {} bytes",
+        result.name,
+        pid,
+        before_bytes,
+        after_bytes
+    );
+    Ok(result)
 }
 
 pub fn optimize_combined_page_list() -> Result<()> {
@@ -613,6 +1599,7 @@ pub fn optimize_combined_page_list() -> Result<()> {
             );
 
             if status < 0 {
+                note_swallowed(status, "Memory combining");
                 // Check for Windows 11 24H2+ compatibility issue
                 if status as u32 == 0xC0000003 {
                     // STATUS_INVALID_INFO_CLASS - Windows 11 24H2+ changed the API
@@ -639,7 +1626,7 @@ pub fn optimize_combined_page_list() -> Result<()> {
 
 /// Get the PID of the foreground window process
 #[cfg(target_os = "windows")]
-fn get_foreground_process_pid() -> Option<u32> {
+pub fn get_foreground_process_pid() -> Option<u32> {
     use windows_sys::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId};
     
     unsafe {
@@ -655,7 +1642,7 @@ fn get_foreground_process_pid() -> Option<u32> {
 }
 
 #[cfg(not(target_os = "windows"))]
-fn get_foreground_process_pid() -> Option<u32> {
+pub fn get_foreground_process_pid() -> Option<u32> {
     None
 }
 
@@ -1,3 +1,5 @@
+use crate::memory::error::MemoryOpError;
+use crate::utils::to_wide;
 use anyhow::{bail, Context, Result};
 use std::ptr::null_mut;
 use windows_sys::Win32::{
@@ -14,14 +16,6 @@ extern "system" {
         -> i32;
 }
 
-fn to_wide(s: &str) -> Vec<u16> {
-    use std::os::windows::ffi::OsStrExt;
-    std::ffi::OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 pub fn ensure_privilege(name: &str) -> Result<()> {
     unsafe {
         let process: HANDLE = GetCurrentProcess();
@@ -50,7 +44,11 @@ pub fn ensure_privilege(name: &str) -> Result<()> {
         let last = GetLastError();
         CloseHandle(token);
         if ok == 0 || last != 0 {
-            bail!("AdjustTokenPrivileges({name}) failed: {}", last);
+            // ERROR_NOT_ALL_ASSIGNED: the token does not hold the privilege at all
+            return Err(anyhow::Error::new(MemoryOpError::PrivilegeMissing {
+                privilege: name.to_string(),
+            })
+            .context(format!("AdjustTokenPrivileges({name}) failed: {}", last)));
         }
     }
     Ok(())
@@ -0,0 +1,137 @@
+/// Per-process memory usage
+///
+/// A single NtQuerySystemInformation(SystemProcessInformation) call returns
+/// the counters of every process, without opening them one by one or spawning
+/// tasklist. Only the processes that make it into the requested top list are
+/// opened afterwards to resolve their image path.
+use crate::memory::types::{ProcessMemoryEntry, ProcessSortKey};
+use anyhow::Result;
+
+pub const DEFAULT_PROCESS_LIMIT: usize = 20;
+pub const MAX_PROCESS_LIMIT: usize = 500;
+
+/// Top `limit` processes by `sort`, largest first
+pub fn process_memory_list(sort: ProcessSortKey, limit: usize) -> Result<Vec<ProcessMemoryEntry>> {
+    let mut entries = query_processes()?;
+    entries.sort_by(|a, b| {
+        b.sort_value(sort)
+            .cmp(&a.sort_value(sort))
+            .then(a.pid.cmp(&b.pid))
+    });
+    entries.truncate(limit.clamp(1, MAX_PROCESS_LIMIT));
+
+    for entry in entries.iter_mut() {
+        entry.path = process_image_path(entry.pid);
+    }
+    Ok(entries)
+}
+
+#[cfg(windows)]
+fn query_processes() -> Result<Vec<ProcessMemoryEntry>> {
+    use anyhow::bail;
+    use ntapi::ntexapi::{NtQuerySystemInformation, SYSTEM_PROCESS_INFORMATION};
+
+    const SYS_PROCESS_INFORMATION: u32 = 5;
+    const STATUS_INFO_LENGTH_MISMATCH: i32 = 0xC000_0004_u32 as i32;
+
+    // u64 storage keeps the entries 8-byte aligned
+    let mut buffer: Vec<u64> = vec![0; 512 * 1024 / 8];
+    let mut status = STATUS_INFO_LENGTH_MISMATCH;
+    for _ in 0..5 {
+        let mut needed = 0u32;
+        status = unsafe {
+            NtQuerySystemInformation(
+                SYS_PROCESS_INFORMATION,
+                buffer.as_mut_ptr() as _,
+                (buffer.len() * 8) as u32,
+                &mut needed,
+            )
+        };
+        if status != STATUS_INFO_LENGTH_MISMATCH {
+            break;
+        }
+        // Processes may start between two calls, leave some headroom
+        buffer = vec![0; (needed as usize + 64 * 1024) / 8 + 1];
+    }
+    if status < 0 {
+        bail!(
+            "NtQuerySystemInformation(class={}) failed: 0x{:08X}",
+            SYS_PROCESS_INFORMATION,
+            status as u32
+        );
+    }
+
+    let base = buffer.as_ptr() as *const u8;
+    let mut entries = Vec::with_capacity(256);
+    let mut offset = 0usize;
+    loop {
+        let info = unsafe { &*(base.add(offset) as *const SYSTEM_PROCESS_INFORMATION) };
+        let pid = info.UniqueProcessId as usize as u32;
+
+        // PID 0 is the idle process, it owns no memory
+        if pid != 0 {
+            let name = if info.ImageName.Buffer.is_null() || info.ImageName.Length == 0 {
+                format!("PID {}", pid)
+            } else {
+                let chars = unsafe {
+                    std::slice::from_raw_parts(
+                        info.ImageName.Buffer,
+                        info.ImageName.Length as usize / 2,
+                    )
+                };
+                String::from_utf16_lossy(chars)
+            };
+
+            entries.push(ProcessMemoryEntry {
+                pid,
+                name,
+                path: None,
+                working_set_bytes: info.WorkingSetSize as u64,
+                private_bytes: info.PrivatePageCount as u64,
+                commit_bytes: info.PagefileUsage as u64,
+                icon: None,
+            });
+        }
+
+        if info.NextEntryOffset == 0 {
+            break;
+        }
+        offset += info.NextEntryOffset as usize;
+    }
+
+    Ok(entries)
+}
+
+#[cfg(not(windows))]
+fn query_processes() -> Result<Vec<ProcessMemoryEntry>> {
+    Ok(Vec::new())
+}
+
+/// Full path of the executable of a process
+#[cfg(windows)]
+pub fn process_image_path(pid: u32) -> Option<String> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if process.is_null() {
+            return None;
+        }
+
+        let mut buf = [0u16; 1024];
+        let mut len = buf.len() as u32;
+        let ok =
+            QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buf.as_mut_ptr(), &mut len);
+        CloseHandle(process);
+        (ok != 0).then(|| String::from_utf16_lossy(&buf[..len as usize]))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn process_image_path(_pid: u32) -> Option<String> {
+    None
+}
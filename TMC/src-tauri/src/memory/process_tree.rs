@@ -0,0 +1,226 @@
+/// Exclusions that follow the process tree
+///
+/// With `exclude_child_processes` a process started by an excluded one is
+/// excluded as well, all the way down: excluding steam.exe also keeps the
+/// games it launched out of the Working Set trim. The parent links come from
+/// a fresh Toolhelp snapshot taken once per optimization run. Windows reuses
+/// the PID of a process that exited, so a parent link only counts when the
+/// parent started before the child.
+use crate::memory::types::ProcessTrimResult;
+use std::collections::{HashMap, HashSet};
+
+pub const EXCLUDED_PARENT_REASON: &str = "Child of an excluded process";
+
+/// Ancestors followed at most; deeper trees are not seen in practice
+const MAX_DEPTH: usize = 64;
+
+/// A process with its parent, as the snapshot reports it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessNode {
+    pub pid: u32,
+    pub parent_pid: u32,
+    /// Lowercase, without ".exe", like `process_list()`
+    pub name: String,
+    /// Creation time (FILETIME ticks), `None` when the process cannot be opened
+    pub created: Option<u64>,
+}
+
+/// Processes excluded only because an ancestor is, with that ancestor's name
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExcludedChildren {
+    ancestors: HashMap<u32, String>,
+}
+
+impl ExcludedChildren {
+    /// Walk the current process tree below the processes `is_excluded` selects
+    ///
+    /// Empty when `enabled` is off, so callers can always ask it.
+    pub fn detect(enabled: bool, is_excluded: impl Fn(&str) -> bool) -> Self {
+        if !enabled {
+            return Self::default();
+        }
+        let children = Self::from_nodes(&process_tree(), is_excluded);
+        if !children.is_empty() {
+            tracing::debug!(
+                "{} processes excluded through an excluded parent",
+                children.ancestors.len()
+            );
+        }
+        children
+    }
+
+    /// Propagate the exclusions of `nodes` down to their descendants
+    pub fn from_nodes(nodes: &[ProcessNode], is_excluded: impl Fn(&str) -> bool) -> Self {
+        let by_pid: HashMap<u32, &ProcessNode> = nodes.iter().map(|n| (n.pid, n)).collect();
+        let mut ancestors = HashMap::new();
+
+        for node in nodes {
+            if is_excluded(&node.name) {
+                continue;
+            }
+            let mut seen = HashSet::from([node.pid]);
+            let mut child = node;
+            for _ in 0..MAX_DEPTH {
+                let parent = match by_pid.get(&child.parent_pid) {
+                    Some(parent) if seen.insert(parent.pid) && started_before(parent, child) => {
+                        *parent
+                    }
+                    _ => break,
+                };
+                if is_excluded(&parent.name) {
+                    ancestors.insert(node.pid, parent.name.clone());
+                    break;
+                }
+                child = parent;
+            }
+        }
+        Self { ancestors }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ancestors.is_empty()
+    }
+
+    /// Name of the excluded ancestor of `pid`, `None` if it has none
+    pub fn excluded_ancestor(&self, pid: u32) -> Option<&str> {
+        self.ancestors.get(&pid).map(String::as_str)
+    }
+}
+
+/// The parent link is genuine, not a reused PID; unknown times are trusted
+fn started_before(parent: &ProcessNode, child: &ProcessNode) -> bool {
+    match (parent.created, child.created) {
+        (Some(parent), Some(child)) => parent <= child,
+        _ => true,
+    }
+}
+
+/// Processes a trim left alone only because of an excluded ancestor
+pub fn implicitly_excluded(results: &[ProcessTrimResult]) -> usize {
+    results
+        .iter()
+        .filter(|r| r.skipped.as_deref() == Some(EXCLUDED_PARENT_REASON))
+        .count()
+}
+
+/// Every running process with its parent
+#[cfg(windows)]
+fn process_tree() -> Vec<ProcessNode> {
+    use std::ffi::OsString;
+    use std::os::windows::ffi::OsStringExt;
+    use windows_sys::Win32::Foundation::{CloseHandle, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    };
+
+    let mut out = Vec::with_capacity(256);
+    unsafe {
+        let snap = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0);
+        if snap == INVALID_HANDLE_VALUE {
+            return out;
+        }
+        let mut pe: PROCESSENTRY32W = std::mem::zeroed();
+        pe.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
+
+        if Process32FirstW(snap, &mut pe) != 0 {
+            loop {
+                let len = pe
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(pe.szExeFile.len());
+                if len > 0 {
+                    let name = OsString::from_wide(&pe.szExeFile[..len])
+                        .to_string_lossy()
+                        .to_lowercase()
+                        .replace(".exe", "");
+                    out.push(ProcessNode {
+                        pid: pe.th32ProcessID,
+                        parent_pid: pe.th32ParentProcessID,
+                        name,
+                        created: creation_time(pe.th32ProcessID),
+                    });
+                }
+                if Process32NextW(snap, &mut pe) == 0 {
+                    break;
+                }
+            }
+        }
+        CloseHandle(snap);
+    }
+    out
+}
+
+#[cfg(not(windows))]
+fn process_tree() -> Vec<ProcessNode> {
+    Vec::new()
+}
+
+/// When a process started, in FILETIME ticks
+#[cfg(windows)]
+fn creation_time(pid: u32) -> Option<u64> {
+    use windows_sys::Win32::Foundation::{CloseHandle, FILETIME};
+    use windows_sys::Win32::System::Threading::{
+        GetProcessTimes, OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let h = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if h.is_null() {
+            return None;
+        }
+        let empty = FILETIME {
+            dwLowDateTime: 0,
+            dwHighDateTime: 0,
+        };
+        let (mut created, mut exited, mut kernel, mut user) = (empty, empty, empty, empty);
+        let ok = GetProcessTimes(h, &mut created, &mut exited, &mut kernel, &mut user) != 0;
+        CloseHandle(h);
+        ok.then_some(((created.dwHighDateTime as u64) << 32) | created.dwLowDateTime as u64)
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(pid: u32, parent_pid: u32, name: &str, created: u64) -> ProcessNode {
+        ProcessNode {
+            pid,
+            parent_pid,
+            name: name.to_string(),
+            created: Some(created),
+        }
+    }
+
+    #[test]
+    fn test_exclusion_follows_the_tree() {
+        let nodes = vec![
+            node(4, 0, "system", 0),
+            node(100, 4, "explorer", 10),
+            node(200, 100, "steam", 20),
+            node(300, 200, "steamwebhelper", 30),
+            node(400, 200, "game", 40),
+            node(500, 400, "crashhandler", 50),
+            node(600, 100, "chrome", 60),
+            // PID 200 reused: this process is older than the current steam
+            node(700, 200, "orphan", 5),
+        ];
+        let children = ExcludedChildren::from_nodes(&nodes, |n| n == "steam");
+
+        assert_eq!(children.excluded_ancestor(300), Some("steam"));
+        assert_eq!(children.excluded_ancestor(400), Some("steam"));
+        assert_eq!(children.excluded_ancestor(500), Some("steam"));
+        assert_eq!(children.excluded_ancestor(200), None);
+        assert_eq!(children.excluded_ancestor(600), None);
+        assert_eq!(children.excluded_ancestor(700), None);
+    }
+
+    #[test]
+    fn test_parent_cycle_terminates() {
+        let nodes = vec![node(1, 2, "a", 0), node(2, 1, "b", 0)];
+        assert!(ExcludedChildren::from_nodes(&nodes, |n| n == "c").is_empty());
+    }
+}
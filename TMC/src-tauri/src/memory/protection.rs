@@ -0,0 +1,152 @@
+/// Heuristic protection for the Working Set trim
+///
+/// The hardcoded list in `critical_processes` only knows names. This layer
+/// asks Windows about the process itself, so services and protected processes
+/// are left alone even when their name is unknown: processes running as
+/// LocalSystem / LocalService / NetworkService, protected processes (PP/PPL,
+/// e.g. lsass with RunAsPPL) and processes flagged critical (their exit
+/// bugchecks the system, e.g. csrss).
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+
+/// Bound for the verdict cache, cleared when exceeded
+const MAX_CACHED_VERDICTS: usize = 2048;
+
+/// Verdict by (PID, name): a reused PID with the same name is rare enough
+static VERDICTS: Lazy<RwLock<HashMap<(u32, String), Option<&'static str>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Why the process must not be trimmed, `None` if it may be
+pub fn protection_reason(pid: u32, name: &str) -> Option<&'static str> {
+    // Idle and System have no user-mode working set to trim
+    if pid == 0 || pid == 4 {
+        return Some("System process");
+    }
+
+    let key = (pid, name.to_string());
+    if let Some(verdict) = VERDICTS.read().get(&key) {
+        return *verdict;
+    }
+
+    let verdict = query_protection(pid);
+    let mut verdicts = VERDICTS.write();
+    if verdicts.len() >= MAX_CACHED_VERDICTS {
+        verdicts.clear();
+    }
+    verdicts.insert(key, verdict);
+    verdict
+}
+
+#[cfg(windows)]
+fn query_protection(pid: u32) -> Option<&'static str> {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    unsafe {
+        let limited = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+        if limited.is_null() {
+            // Not even limited access: nothing a trim could do either
+            return None;
+        }
+        let reason = if is_protected(limited) {
+            Some("Protected process")
+        } else if runs_as_service_account(limited) {
+            Some("System process")
+        } else {
+            None
+        };
+        CloseHandle(limited);
+        if reason.is_some() {
+            return reason;
+        }
+
+        // The break-on-termination flag needs full query access
+        let full = OpenProcess(PROCESS_QUERY_INFORMATION, 0, pid);
+        if full.is_null() {
+            return None;
+        }
+        let critical = is_flagged_critical(full);
+        CloseHandle(full);
+        critical.then_some("Critical flag")
+    }
+}
+
+#[cfg(not(windows))]
+fn query_protection(_pid: u32) -> Option<&'static str> {
+    None
+}
+
+/// PS_PROTECTION of the process is not zero (PP or PPL)
+#[cfg(windows)]
+unsafe fn is_protected(process: windows_sys::Win32::Foundation::HANDLE) -> bool {
+    use ntapi::ntpsapi::NtQueryInformationProcess;
+
+    const PROCESS_PROTECTION_INFORMATION: u32 = 61;
+
+    let mut protection = 0u8;
+    let status = NtQueryInformationProcess(
+        process as _,
+        PROCESS_PROTECTION_INFORMATION,
+        &mut protection as *mut u8 as _,
+        1,
+        std::ptr::null_mut(),
+    );
+    status >= 0 && protection != 0
+}
+
+/// Process exit would bugcheck the system (RtlSetProcessIsCritical)
+#[cfg(windows)]
+unsafe fn is_flagged_critical(process: windows_sys::Win32::Foundation::HANDLE) -> bool {
+    use ntapi::ntpsapi::NtQueryInformationProcess;
+
+    const PROCESS_BREAK_ON_TERMINATION: u32 = 29;
+
+    let mut flag = 0u32;
+    let status = NtQueryInformationProcess(
+        process as _,
+        PROCESS_BREAK_ON_TERMINATION,
+        &mut flag as *mut u32 as _,
+        std::mem::size_of::<u32>() as u32,
+        std::ptr::null_mut(),
+    );
+    status >= 0 && flag != 0
+}
+
+/// Token user is LocalSystem, LocalService or NetworkService
+#[cfg(windows)]
+unsafe fn runs_as_service_account(process: windows_sys::Win32::Foundation::HANDLE) -> bool {
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::Security::{
+        GetTokenInformation, IsWellKnownSid, TokenUser, WinLocalServiceSid, WinLocalSystemSid,
+        WinNetworkServiceSid, TOKEN_QUERY, TOKEN_USER,
+    };
+    use windows_sys::Win32::System::Threading::OpenProcessToken;
+
+    let mut token: HANDLE = std::ptr::null_mut();
+    if OpenProcessToken(process, TOKEN_QUERY, &mut token) == 0 {
+        return false;
+    }
+
+    // TOKEN_USER is followed by the SID it points to; u64 storage keeps it aligned
+    let mut buffer = [0u64; 16];
+    let mut len = 0u32;
+    let ok = GetTokenInformation(
+        token,
+        TokenUser,
+        buffer.as_mut_ptr() as _,
+        std::mem::size_of_val(&buffer) as u32,
+        &mut len,
+    );
+    CloseHandle(token);
+    if ok == 0 {
+        return false;
+    }
+
+    let sid = (*(buffer.as_ptr() as *const TOKEN_USER)).User.Sid;
+    [WinLocalSystemSid, WinLocalServiceSid, WinNetworkServiceSid]
+        .into_iter()
+        .any(|kind| IsWellKnownSid(sid, kind) != 0)
+}
@@ -0,0 +1,87 @@
+/// Strict NT error reporting
+///
+/// The advanced paths fall back from one call to the next and a later
+/// success hides the NTSTATUS of the calls that failed before it. Those are
+/// noted per thread with `note_swallowed`; with `strict_nt_errors` the engine
+/// reports all of them as the error of the area.
+use crate::memory::error::MemoryOpError;
+use std::cell::RefCell;
+
+thread_local! {
+    /// NT calls that failed on this thread without failing their operation
+    static SWALLOWED: RefCell<Vec<(i32, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Note an NT call that failed while its operation went on with a fallback
+pub fn note_swallowed(status: i32, call: &str) {
+    SWALLOWED.with(|s| s.borrow_mut().push((status, call.to_string())));
+}
+
+pub fn reset_swallowed() {
+    SWALLOWED.with(|s| s.borrow_mut().clear());
+}
+
+/// Every failure noted on this thread since `reset_swallowed`, as one error
+pub fn take_swallowed() -> Option<anyhow::Error> {
+    let failures = SWALLOWED.with(|s| std::mem::take(&mut *s.borrow_mut()));
+    if failures.is_empty() {
+        return None;
+    }
+    let message = failures
+        .iter()
+        .map(|(status, call)| format!("{} failed: {}", call, describe_status(*status)))
+        .collect::<Vec<_>>()
+        .join("; ");
+    // The first failure is the structured cause, the message lists them all
+    let cause = MemoryOpError::from_nt_status(failures[0].0);
+    Some(anyhow::Error::new(cause).context(message))
+}
+
+/// Hex code of an NTSTATUS with its name when it is a well-known one
+///
+/// The name stays in English whatever the UI language, so it can be
+/// searched and read the same way in every support request.
+pub fn describe_status(status: i32) -> String {
+    let status = status as u32;
+    let name = match status {
+        0xC000_0001 => "STATUS_UNSUCCESSFUL",
+        0xC000_0002 => "STATUS_NOT_IMPLEMENTED",
+        0xC000_0003 => "STATUS_INVALID_INFO_CLASS",
+        0xC000_0004 => "STATUS_INFO_LENGTH_MISMATCH",
+        0xC000_000D => "STATUS_INVALID_PARAMETER",
+        0xC000_0022 => "STATUS_ACCESS_DENIED",
+        0xC000_0061 => "STATUS_PRIVILEGE_NOT_HELD",
+        0xC000_009A => "STATUS_INSUFFICIENT_RESOURCES",
+        0xC000_00BB => "STATUS_NOT_SUPPORTED",
+        _ => return format!("0x{:08X}", status),
+    };
+    format!("0x{:08X} ({})", status, name)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_swallowed_failure_is_kept() {
+        reset_swallowed();
+        assert!(take_swallowed().is_none());
+
+        note_swallowed(0xC000_0022_u32 as i32, "Direct syscall");
+        note_swallowed(0x8000_0005_u32 as i32, "Indirect syscall");
+        let error = take_swallowed().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Direct syscall failed: 0xC0000022 (STATUS_ACCESS_DENIED); \
+             Indirect syscall failed: 0x80000005"
+        );
+        assert_eq!(
+            MemoryOpError::find(&error),
+            Some(MemoryOpError::NtStatus {
+                status: 0xC000_0022
+            })
+        );
+        assert!(take_swallowed().is_none());
+    }
+}
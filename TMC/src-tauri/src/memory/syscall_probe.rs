@@ -0,0 +1,238 @@
+/// Runtime check of the advanced syscall path
+///
+/// `memory::advanced` issues syscalls from x64 inline assembly with syscall
+/// numbers read from the ntdll stubs. That cannot work for an x64 build
+/// emulated on ARM64 Windows, and security software that hooks ntdll may
+/// flag or kill a process issuing syscalls of its own. The probe runs once at
+/// startup, before the first optimization: it checks the CPU, how many ntdll
+/// stubs are hooked and whether a harmless syscall returns. When any check
+/// fails the resolver refuses to run, the engine picks the documented APIs
+/// for the affected areas and the diagnostics report shows the reason.
+use std::sync::OnceLock;
+
+/// ntdll exports commonly hooked by security software
+const WATCHED_EXPORTS: [&str; 12] = [
+    "NtSetSystemInformation",
+    "NtQuerySystemInformation",
+    "NtSetInformationProcess",
+    "NtOpenProcess",
+    "NtOpenProcessToken",
+    "NtAdjustPrivilegesToken",
+    "NtAllocateVirtualMemory",
+    "NtProtectVirtualMemory",
+    "NtWriteVirtualMemory",
+    "NtReadVirtualMemory",
+    "NtCreateThreadEx",
+    "NtQueueApcThread",
+];
+
+/// Every memory list command of the advanced path goes through this export
+const CRITICAL_EXPORT: &str = "NtSetSystemInformation";
+
+/// Share of hooked watched exports from which the path is disabled
+const HOOK_DENSITY_LIMIT_PERCENT: usize = 25;
+
+const IMAGE_FILE_MACHINE_AMD64: u16 = 0x8664;
+const IMAGE_FILE_MACHINE_ARM64: u16 = 0xAA64;
+
+const STATUS_SUCCESS: i32 = 0;
+const STATUS_NO_YIELD_PERFORMED: i32 = 0x4000_0024;
+
+static CAPABILITY: OnceLock<SyscallCapability> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct SyscallCapability {
+    pub enabled: bool,
+    /// What was checked, or why the path was disabled (not translated)
+    pub detail: String,
+    /// Watched ntdll exports whose stub is hooked
+    pub hooked: Vec<String>,
+}
+
+impl SyscallCapability {
+    fn disabled(detail: impl Into<String>, hooked: Vec<String>) -> Self {
+        Self {
+            enabled: false,
+            detail: detail.into(),
+            hooked,
+        }
+    }
+}
+
+/// Result of the probe, run on first use
+pub fn probe() -> &'static SyscallCapability {
+    CAPABILITY.get_or_init(|| {
+        let capability = run_probe();
+        if capability.enabled {
+            tracing::info!("Advanced syscall path enabled: {}", capability.detail);
+        } else {
+            tracing::warn!(
+                "Advanced syscall path disabled, using documented APIs: {}",
+                capability.detail
+            );
+        }
+        if !capability.hooked.is_empty() {
+            tracing::info!("Hooked ntdll exports: {}", capability.hooked.join(", "));
+        }
+        capability
+    })
+}
+
+pub fn advanced_syscalls_enabled() -> bool {
+    probe().enabled
+}
+
+/// Why the advanced syscall path is disabled, `None` when it can be used
+pub fn disabled_reason() -> Option<&'static str> {
+    let capability = probe();
+    (!capability.enabled).then_some(capability.detail.as_str())
+}
+
+fn run_probe() -> SyscallCapability {
+    if !cfg!(target_arch = "x86_64") {
+        return SyscallCapability::disabled("Not an x64 build", Vec::new());
+    }
+    if let Some(machine) = native_machine() {
+        if machine != IMAGE_FILE_MACHINE_AMD64 {
+            let cpu = match machine {
+                IMAGE_FILE_MACHINE_ARM64 => "ARM64".to_string(),
+                other => format!("0x{:04X}", other),
+            };
+            return SyscallCapability::disabled(
+                format!("x64 emulation on an {} CPU", cpu),
+                Vec::new(),
+            );
+        }
+    }
+
+    let stubs = match super::advanced::syscall_stubs(&WATCHED_EXPORTS) {
+        Ok(stubs) => stubs,
+        Err(e) => {
+            return SyscallCapability::disabled(
+                format!("ntdll could not be inspected: {:#}", e),
+                Vec::new(),
+            )
+        }
+    };
+    let hooked: Vec<String> = stubs
+        .iter()
+        .filter(|(_, hooked)| *hooked)
+        .map(|(name, _)| name.to_string())
+        .collect();
+    if let Some(reason) = hook_verdict(&stubs) {
+        return SyscallCapability::disabled(reason, hooked);
+    }
+
+    match super::advanced::noop_direct_syscall() {
+        Ok(STATUS_SUCCESS) | Ok(STATUS_NO_YIELD_PERFORMED) => SyscallCapability {
+            enabled: true,
+            detail: format!(
+                "x64, {} of {} ntdll stubs hooked, test syscall passed",
+                hooked.len(),
+                stubs.len()
+            ),
+            hooked,
+        },
+        Ok(status) => SyscallCapability::disabled(
+            format!("Test syscall returned NTSTATUS 0x{:08X}", status as u32),
+            hooked,
+        ),
+        Err(e) => SyscallCapability::disabled(format!("Test syscall failed: {:#}", e), hooked),
+    }
+}
+
+/// Why the hooked stubs rule out the advanced path, `None` when they do not
+fn hook_verdict(stubs: &[(&str, bool)]) -> Option<String> {
+    if stubs.is_empty() {
+        return Some("No ntdll syscall stub could be read".to_string());
+    }
+    if stubs
+        .iter()
+        .any(|(name, hooked)| *name == CRITICAL_EXPORT && *hooked)
+    {
+        return Some(format!(
+            "{} is hooked by security software",
+            CRITICAL_EXPORT
+        ));
+    }
+    let hooked = stubs.iter().filter(|(_, hooked)| *hooked).count();
+    if hooked * 100 >= stubs.len() * HOOK_DENSITY_LIMIT_PERCENT {
+        return Some(format!(
+            "{} of {} ntdll syscall stubs are hooked by security software",
+            hooked,
+            stubs.len()
+        ));
+    }
+    None
+}
+
+/// Native CPU of the machine, which differs from AMD64 for an x64 build
+/// emulated on ARM64; `None` before Windows 10 1511, which has no emulation
+#[cfg(windows)]
+fn native_machine() -> Option<u16> {
+    use windows_sys::Win32::Foundation::HANDLE;
+    use windows_sys::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+    use windows_sys::Win32::System::Threading::GetCurrentProcess;
+
+    type IsWow64Process2Fn = unsafe extern "system" fn(
+        process: HANDLE,
+        process_machine: *mut u16,
+        native_machine: *mut u16,
+    ) -> i32;
+
+    unsafe {
+        let kernel32 = GetModuleHandleA(b"kernel32.dll\0".as_ptr());
+        if kernel32.is_null() {
+            return None;
+        }
+        let func = GetProcAddress(kernel32, b"IsWow64Process2\0".as_ptr())?;
+        let is_wow64_process2: IsWow64Process2Fn = std::mem::transmute(func);
+
+        let mut process_machine = 0u16;
+        let mut native_machine = 0u16;
+        if is_wow64_process2(
+            GetCurrentProcess(),
+            &mut process_machine,
+            &mut native_machine,
+        ) == 0
+        {
+            return None;
+        }
+        Some(native_machine)
+    }
+}
+
+#[cfg(not(windows))]
+fn native_machine() -> Option<u16> {
+    None
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hook_verdict() {
+        let clean: Vec<(&str, bool)> = WATCHED_EXPORTS.iter().map(|n| (*n, false)).collect();
+        assert_eq!(hook_verdict(&clean), None);
+
+        // A couple of hooks elsewhere leave the path enabled
+        let mut few = clean.clone();
+        few[3].1 = true;
+        few[4].1 = true;
+        assert_eq!(hook_verdict(&few), None);
+
+        let mut critical = clean.clone();
+        critical[0].1 = true;
+        assert!(hook_verdict(&critical).unwrap().contains(CRITICAL_EXPORT));
+
+        let mut dense = clean;
+        for stub in dense.iter_mut().skip(1).take(3) {
+            stub.1 = true;
+        }
+        assert!(hook_verdict(&dense).unwrap().starts_with("3 of 12"));
+
+        assert!(hook_verdict(&[]).is_some());
+    }
+}
@@ -15,6 +15,7 @@ bitflags::bitflags! {
         const STANDBY_LIST_LOW    = 1 << 5;
         const SYSTEM_FILE_CACHE   = 1 << 6;
         const WORKING_SET         = 1 << 7;
+        const MEMORY_COMPRESSION  = 1 << 8;
 
         // Presets
         const BASIC = Self::WORKING_SET.bits()
@@ -28,11 +29,18 @@ bitflags::bitflags! {
                    | Self::COMBINED_PAGE_LIST.bits()
                    | Self::MODIFIED_FILE_CACHE.bits()
                    | Self::REGISTRY_CACHE.bits()
-                   | Self::STANDBY_LIST_LOW.bits();
+                   | Self::STANDBY_LIST_LOW.bits()
+                   | Self::MEMORY_COMPRESSION.bits();
     }
 }
 
 impl Areas {
+    /// Areas that need a one-time confirmation before automatic runs may use them
+    pub const AGGRESSIVE: Areas = Areas::WORKING_SET.union(Areas::SYSTEM_FILE_CACHE);
+    /// Areas that need administrator rights; without them only the user's
+    /// own working sets can be trimmed
+    pub const ELEVATED: Areas = Areas::all().difference(Areas::WORKING_SET);
+
     /// Get human-readable names for the areas
     pub fn get_names(&self) -> Vec<&'static str> {
         let mut names = Vec::new();
@@ -61,6 +69,9 @@ impl Areas {
         if self.contains(Areas::REGISTRY_CACHE) {
             names.push("Registry Cache");
         }
+        if self.contains(Areas::MEMORY_COMPRESSION) {
+            names.push("Memory Compression");
+        }
 
         names
     }
@@ -84,6 +95,33 @@ pub enum Reason {
     Manual,
     Schedule,
     Hotkey,
+    /// Requested by a third-party tool through the SDK pipe
+    External,
+    /// Memory pressure trend predicted the low-memory threshold would be crossed
+    Predicted,
+    /// Free memory dropped below the emergency threshold
+    Emergency,
+}
+
+impl Reason {
+    /// Runs started by the auto optimizer rather than by the user
+    pub fn is_automatic(&self) -> bool {
+        matches!(
+            self,
+            Reason::Schedule | Reason::LowMemory | Reason::Predicted | Reason::Emergency
+        )
+    }
+
+    /// Automatic runs held back by the free RAM floor, the area cooldowns and
+    /// the confirmation of aggressive areas; an emergency run is not
+    pub fn respects_safeguards(&self) -> bool {
+        self.is_automatic() && *self != Reason::Emergency
+    }
+
+    /// Runs the user asked for directly (button, tray menu, hotkey)
+    pub fn is_user_triggered(&self) -> bool {
+        matches!(self, Reason::Manual | Reason::Hotkey)
+    }
 }
 
 impl fmt::Display for Reason {
@@ -93,6 +131,9 @@ impl fmt::Display for Reason {
             Reason::Manual => write!(f, "Manual"),
             Reason::Schedule => write!(f, "Scheduled"),
             Reason::Hotkey => write!(f, "Hotkey"),
+            Reason::External => write!(f, "External"),
+            Reason::Predicted => write!(f, "Predicted"),
+            Reason::Emergency => write!(f, "Emergency"),
         }
     }
 }
@@ -198,9 +239,222 @@ impl MemoryStats {
 // ========== MEMORY INFO ==========
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct MemoryInfo {
+    /// Percentages are relative to the usable RAM (`physical.total`)
     pub physical: MemoryStats,
     pub commit: MemoryStats,
     pub load_percent: u32,
+    /// RAM installed in the slots, `physical.total` when the firmware does not report it
+    #[serde(default)]
+    pub installed_bytes: u64,
+    /// Installed RAM reserved by the firmware and devices (e.g. integrated graphics)
+    #[serde(default)]
+    pub hardware_reserved_bytes: u64,
+    /// Windows Game Mode is engaged for a running game
+    #[serde(default)]
+    pub game_mode_active: bool,
+}
+
+// ========== STANDBY BREAKDOWN ==========
+/// Standby list size per page priority (0-7), from SystemMemoryListInformation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct StandbyBreakdown {
+    /// Bytes on the standby list for each priority, index = priority
+    pub by_priority: [u64; 8],
+    pub total_bytes: u64,
+    /// Bytes released by `STANDBY_LIST_LOW` (priority 0 only)
+    pub low_priority_bytes: u64,
+    pub modified_bytes: u64,
+    pub free_bytes: u64,
+    pub zero_bytes: u64,
+    pub page_size: u64,
+}
+
+impl StandbyBreakdown {
+    pub fn from_page_counts(
+        page_size: u64,
+        standby_pages: [u64; 8],
+        modified_pages: u64,
+        free_pages: u64,
+        zero_pages: u64,
+    ) -> Self {
+        let by_priority = standby_pages.map(|pages| pages.saturating_mul(page_size));
+        Self {
+            by_priority,
+            total_bytes: by_priority.iter().sum(),
+            low_priority_bytes: by_priority[0],
+            modified_bytes: modified_pages.saturating_mul(page_size),
+            free_bytes: free_pages.saturating_mul(page_size),
+            zero_bytes: zero_pages.saturating_mul(page_size),
+            page_size,
+        }
+    }
+}
+
+// ========== COMPRESSION STORE ==========
+/// Memory compression store usage, from SystemStoreInformation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CompressionInfo {
+    /// Pages currently held compressed in the store
+    pub compressed_pages: u64,
+    /// Size of that data before compression
+    pub uncompressed_bytes: u64,
+    /// Size of that data in the store
+    pub compressed_bytes: u64,
+    /// `uncompressed_bytes / compressed_bytes`, 0 while the store is empty
+    pub compression_ratio: f64,
+    /// PID of the Memory Compression process
+    pub process_id: u32,
+    /// Working set of the Memory Compression process
+    pub working_set_bytes: u64,
+}
+
+impl CompressionInfo {
+    pub fn from_store(
+        page_size: u64,
+        process_id: u32,
+        working_set_pages: u64,
+        uncompressed_bytes: u64,
+        compressed_bytes: u64,
+    ) -> Self {
+        let compression_ratio = if compressed_bytes > 0 {
+            uncompressed_bytes as f64 / compressed_bytes as f64
+        } else {
+            0.0
+        };
+        Self {
+            compressed_pages: uncompressed_bytes / page_size.max(1),
+            uncompressed_bytes,
+            compressed_bytes,
+            compression_ratio,
+            process_id,
+            working_set_bytes: working_set_pages.saturating_mul(page_size),
+        }
+    }
+}
+
+// ========== FILE CACHE ==========
+/// System File Cache working set and its limits
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct FileCacheInfo {
+    pub current_bytes: u64,
+    pub peak_bytes: u64,
+    /// Minimum working set the memory manager keeps for the cache
+    pub min_limit_bytes: u64,
+    /// Maximum working set of the cache
+    pub max_limit_bytes: u64,
+    /// The maximum is enforced, not only a trimming target
+    pub hard_max: bool,
+    /// The minimum is enforced
+    pub hard_min: bool,
+}
+
+// ========== TRIM STRATEGY ==========
+/// How the Working Set trim treats each process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrimStrategy {
+    /// Lower the memory priority only, pages leave first when memory runs short
+    Gentle,
+    /// Empty the working set (EmptyWorkingSet)
+    Standard,
+    /// Cap the working set with a hard maximum, then restore the original limits
+    Aggressive,
+}
+
+impl Default for TrimStrategy {
+    fn default() -> Self {
+        Self::Standard
+    }
+}
+
+// ========== PROCESS TRIM RESULT ==========
+/// Outcome of the Working Set trim for a single process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessTrimResult {
+    pub pid: u32,
+    pub name: String,
+    pub trimmed: bool,
+    /// Why the process was left alone, `None` when a trim was attempted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
+    /// CPU usage sampled before the trim (only when busy processes are skipped)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<f32>,
+}
+
+impl ProcessTrimResult {
+    pub fn skipped(pid: u32, name: &str, reason: impl Into<String>) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            trimmed: false,
+            skipped: Some(reason.into()),
+            cpu_percent: None,
+        }
+    }
+
+    pub fn attempted(pid: u32, name: &str, trimmed: bool) -> Self {
+        Self {
+            pid,
+            name: name.to_string(),
+            trimmed,
+            skipped: None,
+            cpu_percent: None,
+        }
+    }
+}
+
+// ========== SINGLE PROCESS TRIM ==========
+/// Working Set of one process around a "trim now" request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleProcessTrim {
+    pub pid: u32,
+    pub name: String,
+    pub before_bytes: u64,
+    pub after_bytes: u64,
+}
+
+impl SingleProcessTrim {
+    pub fn freed_bytes(&self) -> u64 {
+        self.before_bytes.saturating_sub(self.after_bytes)
+    }
+}
+
+// ========== PROCESS MEMORY LIST ==========
+/// Column used to rank processes by memory usage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessSortKey {
+    #[default]
+    WorkingSet,
+    PrivateBytes,
+    Commit,
+}
+
+/// Memory usage of a single process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessMemoryEntry {
+    pub pid: u32,
+    pub name: String,
+    /// Full image path, `None` when the process cannot be opened
+    #[serde(default)]
+    pub path: Option<String>,
+    pub working_set_bytes: u64,
+    pub private_bytes: u64,
+    pub commit_bytes: u64,
+    /// Executable icon as PNG data URL (only when requested)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl ProcessMemoryEntry {
+    pub fn sort_value(&self, key: ProcessSortKey) -> u64 {
+        match key {
+            ProcessSortKey::WorkingSet => self.working_set_bytes,
+            ProcessSortKey::PrivateBytes => self.private_bytes,
+            ProcessSortKey::Commit => self.commit_bytes,
+        }
+    }
 }
 
 // ========== HELPER FUNCTIONS (STILL USED) ==========
@@ -248,4 +502,15 @@ mod tests {
         assert_eq!(stats.used.percentage, 50);
         assert_eq!(stats.free.percentage, 50);
     }
+
+    #[test]
+    fn test_compression_info_from_store() {
+        let info = CompressionInfo::from_store(4096, 1234, 256, 3 * 4096 * 1000, 4096 * 1000);
+        assert_eq!(info.compressed_pages, 3000);
+        assert_eq!(info.compression_ratio, 3.0);
+        assert_eq!(info.working_set_bytes, 256 * 4096);
+
+        let empty = CompressionInfo::from_store(4096, 0, 0, 0, 0);
+        assert_eq!(empty.compression_ratio, 0.0);
+    }
 }
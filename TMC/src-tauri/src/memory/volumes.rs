@@ -1,3 +1,4 @@
+use crate::utils::to_wide;
 use anyhow::Result;
 use std::ptr::null_mut;
 use windows_sys::Win32::{
@@ -23,14 +24,6 @@ extern "system" {
     ) -> i32;
 }
 
-fn to_wide(s: &str) -> Vec<u16> {
-    use std::os::windows::ffi::OsStrExt;
-    std::ffi::OsStr::new(s)
-        .encode_wide()
-        .chain(std::iter::once(0))
-        .collect()
-}
-
 const FSCTL_DISCARD_VOLUME_CACHE: u32 = 0x00090054;
 const FSCTL_RESET_WRITE_ORDER: u32 = 0x000900F8;
 const FSCTL_LOCK_VOLUME: u32 = 0x00090018;
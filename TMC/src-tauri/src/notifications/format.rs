@@ -0,0 +1,195 @@
+/// Per-language formatting of notification text
+///
+/// The translated templates are written for left-to-right toasts. For
+/// right-to-left languages each line is embedded in an RTL run so the Latin
+/// numbers and units inside Arabic text keep their order, units (MB, GB) are
+/// replaced with their translation and, with `native_digits`, Arabic text
+/// uses Arabic-Indic digits.
+use super::options::ToastOptions;
+use crate::benchmark::BenchmarkComparison;
+
+/// Right-to-left embedding and pop directional formatting
+const RLE: char = '\u{202B}';
+const PDF: char = '\u{202C}';
+/// Units replaced by their translation, as whole words only
+const UNITS: [&str; 2] = ["MB", "GB"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    Ltr,
+    Rtl,
+}
+
+impl TextDirection {
+    pub fn for_language(language: &str) -> Self {
+        let base = language.split(['-', '_']).next().unwrap_or(language);
+        match base {
+            "ar" | "he" | "fa" | "ur" => TextDirection::Rtl,
+            _ => TextDirection::Ltr,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationFormat {
+    pub language: String,
+    pub direction: TextDirection,
+    /// Arabic-Indic digits instead of 0-9 (Arabic only)
+    pub native_digits: bool,
+}
+
+impl Default for NotificationFormat {
+    fn default() -> Self {
+        Self::new(crate::i18n::FALLBACK_LANGUAGE, false)
+    }
+}
+
+impl NotificationFormat {
+    pub fn new(language: &str, native_digits: bool) -> Self {
+        Self {
+            language: language.to_string(),
+            direction: TextDirection::for_language(language),
+            native_digits,
+        }
+    }
+
+    /// Format for the current backend language and the toast options
+    pub fn current(options: &ToastOptions) -> Self {
+        Self::new(&crate::i18n::language(), options.native_digits)
+    }
+
+    /// Apply units, digits and direction to a translated text
+    pub fn apply(&self, text: &str) -> String {
+        let text = self.localize_units(text);
+        let text = self.localize_digits(&text);
+        match self.direction {
+            TextDirection::Ltr => text,
+            TextDirection::Rtl => text
+                .split('\n')
+                .map(|line| format!("{}{}{}", RLE, line, PDF))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+
+    fn localize_units(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for unit in UNITS {
+            let translated = crate::i18n::tr_in(&self.language, unit);
+            if translated != unit {
+                text = replace_word(&text, unit, &translated);
+            }
+        }
+        text
+    }
+
+    fn localize_digits(&self, text: &str) -> String {
+        let arabic = self.language.split(['-', '_']).next() == Some("ar");
+        if !self.native_digits || !arabic {
+            return text.to_string();
+        }
+        let chars: Vec<char> = text.chars().collect();
+        let is_digit = |i: usize| chars.get(i).is_some_and(|c| c.is_ascii_digit());
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| match c {
+                '0'..='9' => char::from_u32(0x0660 + (c as u32 - '0' as u32)).unwrap_or(c),
+                // Arabic decimal separator and percent sign, only around digits
+                '.' | ',' if i > 0 && is_digit(i - 1) && is_digit(i + 1) => '\u{066B}',
+                '%' if i > 0 && is_digit(i - 1) => '\u{066A}',
+                _ => c,
+            })
+            .collect()
+    }
+}
+
+/// Replace `word` where it is not part of a longer word
+fn replace_word(text: &str, word: &str, with: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find(word) {
+        let before = rest[..pos].chars().next_back();
+        let after = rest[pos + word.len()..].chars().next();
+        let standalone =
+            !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric);
+        out.push_str(&rest[..pos]);
+        out.push_str(if standalone { with } else { word });
+        rest = &rest[pos + word.len()..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Body of the notification shown after an optimization, in the current language
+pub fn get_notification_body(
+    freed_mb: f64,
+    free_gb: f64,
+    profile_name: &str,
+    benchmark: Option<&BenchmarkComparison>,
+) -> String {
+    let mut body = crate::i18n::tr_args(
+        "✅ Freed: %.1f MB\n🧠 Free RAM: %.2f GB\n🎯 Profile: %s",
+        &[freed_mb.into(), free_gb.into(), profile_name.into()],
+    );
+
+    if let Some(bench) = benchmark {
+        let change = bench
+            .latency_change_percent()
+            .map(|p| format!("{:+.0}%", p))
+            .unwrap_or_else(|| "-".to_string());
+        body.push('\n');
+        body.push_str(&crate::i18n::tr_args(
+            "⚡ Allocation: %s → %s µs (%s)",
+            &[
+                format!("{:.0}", bench.before.alloc_latency_us).into(),
+                format!("{:.0}", bench.after.alloc_latency_us).into(),
+                change.into(),
+            ],
+        ));
+    }
+    body
+}
+
+/// One-line body for profiles that only want the freed memory
+pub fn get_notification_summary(freed_mb: f64) -> String {
+    crate::i18n::tr_args("✅ Freed: %.1f MB", &[freed_mb.into()])
+}
+
+/// Body for a run that found nothing to free, so it does not read as "Freed: 0.0 MB"
+pub fn get_nothing_freed_body(free_gb: f64, profile_name: &str, detailed: bool) -> String {
+    if detailed {
+        crate::i18n::tr_args(
+            "✅ Memory was already optimal, nothing to free\n🧠 Free RAM: %.2f GB\n🎯 Profile: %s",
+            &[free_gb.into(), profile_name.into()],
+        )
+    } else {
+        crate::i18n::tr("✅ Memory was already optimal, nothing to free")
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rtl_digits_and_units() {
+        let ltr = NotificationFormat::new("en", true);
+        assert_eq!(
+            ltr.apply("Freed: 12.5 MB\nRAM: 40%"),
+            "Freed: 12.5 MB\nRAM: 40%"
+        );
+
+        let rtl = NotificationFormat::new("ar", true);
+        let text = rtl.apply("تم تحرير: 12.5 MB\n40%");
+        let lines: Vec<&str> = text.split('\n').collect();
+        assert!(lines.iter().all(|l| l.starts_with(RLE) && l.ends_with(PDF)));
+        assert!(text.contains("١٢٫٥"));
+        assert!(text.contains("٤٠٪"));
+        assert!(!text.contains("MB"));
+
+        // Units inside longer words are left alone
+        assert_eq!(replace_word("MBR 5 MB", "MB", "Mo"), "MBR 5 Mo");
+    }
+}
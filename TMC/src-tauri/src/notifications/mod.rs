@@ -1,4 +1,9 @@
+pub mod format;
+pub mod options;
+pub mod quiet;
 pub mod windows;
 
 // Re-export functions for easier access
+pub use format::{get_nothing_freed_body, get_notification_body, get_notification_summary};
+pub use options::{NotificationVerbosity, ProfileNotifications, ToastOptions};
 pub use windows::*;
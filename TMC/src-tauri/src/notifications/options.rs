@@ -0,0 +1,94 @@
+/// User options for toast notifications
+use crate::config::Profile;
+use serde::{Deserialize, Serialize};
+
+/// How long Windows keeps the toast on screen (about 7s or 25s)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastDuration {
+    #[default]
+    Short,
+    Long,
+}
+
+/// `Reminder` keeps the toast on screen until it is dismissed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ToastScenario {
+    #[default]
+    Default,
+    Reminder,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToastOptions {
+    pub duration: ToastDuration,
+    pub scenario: ToastScenario,
+    /// Show the toast without playing a sound
+    pub silent: bool,
+    /// Arabic-Indic digits in Arabic notifications
+    pub native_digits: bool,
+    /// Show toasts right away during Focus Assist or fullscreen apps instead
+    /// of holding them back until the quiet time ends
+    pub show_when_busy: bool,
+}
+
+impl ToastOptions {
+    pub fn duration_attr(&self) -> &'static str {
+        match self.duration {
+            ToastDuration::Short => "short",
+            ToastDuration::Long => "long",
+        }
+    }
+
+    pub fn scenario_attr(&self) -> &'static str {
+        match self.scenario {
+            ToastScenario::Default => "default",
+            ToastScenario::Reminder => "reminder",
+        }
+    }
+}
+
+/// How much an optimization tells the user once it completes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationVerbosity {
+    /// No toast, only the last result in the tray tooltip
+    Silent,
+    /// Toast with the freed memory alone
+    Summary,
+    /// Toast with freed and free memory, profile and benchmark
+    Detailed,
+}
+
+/// Notification verbosity of each built-in profile
+///
+/// Custom profiles notify in detail, as all profiles did before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileNotifications {
+    pub normal: NotificationVerbosity,
+    pub balanced: NotificationVerbosity,
+    pub gaming: NotificationVerbosity,
+}
+
+impl Default for ProfileNotifications {
+    fn default() -> Self {
+        Self {
+            normal: NotificationVerbosity::Detailed,
+            balanced: NotificationVerbosity::Summary,
+            gaming: NotificationVerbosity::Silent,
+        }
+    }
+}
+
+impl ProfileNotifications {
+    pub fn for_profile(&self, profile: Profile) -> NotificationVerbosity {
+        match profile {
+            Profile::Normal => self.normal,
+            Profile::Balanced => self.balanced,
+            Profile::Gaming => self.gaming,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+/// Quiet time detection and deferred toasts
+///
+/// A toast popping up over a fullscreen game or a presentation is exactly
+/// what the user does not want. While Focus Assist is on or a fullscreen app
+/// is in the foreground, toasts are queued instead of shown. A background
+/// thread waits for the quiet time to end and then shows the single queued
+/// toast as it was, or one summary toast for several.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// Toasts kept while quiet, the oldest are dropped beyond this
+const MAX_DEFERRED: usize = 20;
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Why toasts are held back right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuietReason {
+    /// Focus Assist (quiet hours), set by the user or automatically
+    FocusAssist,
+    /// A fullscreen Direct3D game or app owns the foreground
+    Fullscreen,
+    /// A Windows Store app runs in fullscreen or tablet mode
+    StoreApp,
+    /// Windows presentation mode
+    Presentation,
+    /// The quiet hour Windows keeps after a new user first signs in
+    FirstSignIn,
+}
+
+impl QuietReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QuietReason::FocusAssist => "Focus Assist",
+            QuietReason::Fullscreen => "Fullscreen app",
+            QuietReason::StoreApp => "Fullscreen Store app",
+            QuietReason::Presentation => "Presentation mode",
+            QuietReason::FirstSignIn => "First sign-in quiet time",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DeferredToast {
+    title: String,
+    body: String,
+    theme: String,
+    link: Option<(String, String)>,
+}
+
+static DEFERRED: Lazy<Mutex<Vec<DeferredToast>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static FLUSHER_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Queue a toast until the quiet time ends
+pub fn defer(app: &AppHandle, title: &str, body: &str, theme: &str, link: Option<(&str, &str)>) {
+    {
+        let mut queue = DEFERRED.lock();
+        if queue.len() == MAX_DEFERRED {
+            queue.remove(0);
+        }
+        queue.push(DeferredToast {
+            title: title.to_string(),
+            body: body.to_string(),
+            theme: theme.to_string(),
+            link: link.map(|(label, url)| (label.to_string(), url.to_string())),
+        });
+    }
+
+    if FLUSHER_RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    let spawned = std::thread::Builder::new()
+        .name("tmc-toast-defer".into())
+        .spawn(move || loop {
+            std::thread::sleep(POLL_INTERVAL);
+            if quiet_reason(documented_apis_only(&app)).is_some() {
+                continue;
+            }
+            // Released before taking the queue: a toast queued from now on
+            // starts its own flusher instead of being left behind
+            FLUSHER_RUNNING.store(false, Ordering::SeqCst);
+            let queued = std::mem::take(&mut *DEFERRED.lock());
+            show_deferred(&app, queued);
+            break;
+        });
+
+    if let Err(e) = spawned {
+        FLUSHER_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start deferred toast thread: {}", e);
+    }
+}
+
+/// Show what was queued: a single toast as it was, several as one summary
+fn show_deferred(app: &AppHandle, mut queued: Vec<DeferredToast>) {
+    let result = match queued.len() {
+        0 => return,
+        1 => {
+            let toast = queued.remove(0);
+            super::windows::show_now(
+                app,
+                &toast.title,
+                &toast.body,
+                &toast.theme,
+                toast
+                    .link
+                    .as_ref()
+                    .map(|(label, url)| (label.as_str(), url.as_str())),
+            )
+        }
+        _ => {
+            let theme = queued.last().map(|t| t.theme.clone()).unwrap_or_default();
+            let title = crate::i18n::tr("TMC • While you were busy");
+            let body = summary_body(&queued);
+            super::windows::show_now(app, &title, &body, &theme, None)
+        }
+    };
+    if let Err(e) = result {
+        tracing::warn!("Failed to show deferred notifications: {}", e);
+    }
+}
+
+/// One line per distinct title, in the order they first arrived
+fn summary_body(queued: &[DeferredToast]) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for toast in queued {
+        match counts.iter_mut().find(|(title, _)| *title == toast.title) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&toast.title, 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(title, count)| match count {
+            1 => title.to_string(),
+            n => format!("{} ×{}", title, n),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// `use_documented_apis_only` from the config
+pub(super) fn documented_apis_only(app: &AppHandle) -> bool {
+    use tauri::Manager;
+
+    app.try_state::<crate::AppState>()
+        .map(|state| state.cfg.read().use_documented_apis_only)
+        .unwrap_or(false)
+}
+
+/// Whether toasts should wait, and why
+///
+/// Focus Assist is only exposed through the undocumented WNF state of the
+/// shell, so it is not checked with `documented_only`. The notification state
+/// of the shell never stands for Focus Assist: `QUNS_QUIET_TIME` is the quiet
+/// time after a new user's first sign-in.
+#[cfg(windows)]
+pub fn quiet_reason(documented_only: bool) -> Option<QuietReason> {
+    use windows_sys::Win32::UI::Shell::{
+        QUNS_APP, QUNS_BUSY, QUNS_PRESENTATION_MODE, QUNS_QUIET_TIME, QUNS_RUNNING_D3D_FULL_SCREEN,
+    };
+
+    if !documented_only && focus_assist_active() {
+        return Some(QuietReason::FocusAssist);
+    }
+
+    match crate::system::game_mode::notification_state()? {
+        QUNS_BUSY | QUNS_RUNNING_D3D_FULL_SCREEN => Some(QuietReason::Fullscreen),
+        QUNS_APP => Some(QuietReason::StoreApp),
+        QUNS_PRESENTATION_MODE => Some(QuietReason::Presentation),
+        QUNS_QUIET_TIME => Some(QuietReason::FirstSignIn),
+        _ => None,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn quiet_reason(_documented_only: bool) -> Option<QuietReason> {
+    None
+}
+
+/// Focus Assist profile from WNF: 0 off, 1 priority only, 2 alarms only
+#[cfg(windows)]
+fn focus_assist_active() -> bool {
+    use windows_sys::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+
+    /// WNF_SHEL_QUIETHOURS_ACTIVE_PROFILE_CHANGED
+    const WNF_QUIET_HOURS: u64 = 0x0D83_063E_A3BF_1C75;
+
+    type NtQueryWnfStateDataFn = unsafe extern "system" fn(
+        state_name: *const u64,
+        type_id: *const std::ffi::c_void,
+        explicit_scope: *const std::ffi::c_void,
+        change_stamp: *mut u32,
+        buffer: *mut std::ffi::c_void,
+        buffer_size: *mut u32,
+    ) -> i32;
+
+    unsafe {
+        let ntdll = GetModuleHandleA(b"ntdll.dll\0".as_ptr());
+        if ntdll.is_null() {
+            return false;
+        }
+        let func = match GetProcAddress(ntdll, b"NtQueryWnfStateData\0".as_ptr()) {
+            Some(func) => func,
+            None => return false,
+        };
+        let query: NtQueryWnfStateDataFn = std::mem::transmute(func);
+
+        let mut change_stamp = 0u32;
+        let mut profile = 0u32;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = query(
+            &WNF_QUIET_HOURS,
+            std::ptr::null(),
+            std::ptr::null(),
+            &mut change_stamp,
+            &mut profile as *mut u32 as *mut _,
+            &mut size,
+        );
+        status >= 0 && size > 0 && profile != 0
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn toast(title: &str) -> DeferredToast {
+        DeferredToast {
+            title: title.to_string(),
+            body: String::new(),
+            theme: "dark".to_string(),
+            link: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_body_groups_titles() {
+        let queued = vec![
+            toast("Scheduled optimization"),
+            toast("Low memory optimization"),
+            toast("Scheduled optimization"),
+        ];
+        assert_eq!(
+            summary_body(&queued),
+            "Scheduled optimization ×2\nLow memory optimization"
+        );
+    }
+}
@@ -1,110 +1,141 @@
-#[cfg(windows)]
-use std::os::windows::process::CommandExt;
-#[cfg(windows)]
+use super::format::NotificationFormat;
+use super::options::{ToastOptions, ToastScenario};
 use tauri::AppHandle;
 
-// Helper per convertire ICO in PNG ad alta risoluzione
 #[cfg(windows)]
-fn convert_ico_to_highres_png(ico_data: &[u8]) -> Result<Vec<u8>, String> {
-    // Carica l'ICO usando image::load_from_memory che gestisce automaticamente il formato
-    let img =
-        image::load_from_memory(ico_data).map_err(|e| format!("Failed to load ICO: {}", e))?;
+use crate::utils::to_wide;
 
-    // Converti in RGBA8
-    let rgba_img = img.to_rgba8();
+/// AppUserModelID registered by `register_app_for_notifications`
+#[cfg(windows)]
+const APP_ID: &str = "TommyMemoryCleaner";
 
-    // Resize a 256x256 (alta risoluzione per Windows Toast)
-    let resized =
-        image::imageops::resize(&rgba_img, 256, 256, image::imageops::FilterType::Lanczos3);
+/// Registry key holding the display name and icon of `APP_ID`
+#[cfg(windows)]
+fn app_id_key() -> String {
+    format!(r"Software\Classes\AppUserModelId\{}", APP_ID)
+}
 
-    // Codifica come PNG usando DynamicImage::save (API image 0.25)
-    // Converti RgbaImage in DynamicImage per poter usare save
-    let dynamic_img = image::DynamicImage::ImageRgba8(resized);
+/// Per-user notification settings of `APP_ID`
+#[cfg(windows)]
+fn app_settings_key() -> String {
+    format!(
+        r"Software\Microsoft\Windows\CurrentVersion\Notifications\Settings\{}",
+        APP_ID
+    )
+}
 
-    // Salva in un buffer in memoria usando il metodo save_with_format
-    let mut png_data = Vec::new();
-    {
-        let mut cursor = std::io::Cursor::new(&mut png_data);
-        dynamic_img
-            .write_to(&mut cursor, image::ImageFormat::Png)
-            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+/// Escape text for use inside the toast XML
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
     }
-
-    Ok(png_data)
+    escaped
 }
 
-// Helper per ottenere il percorso dell'icona PNG ad alta risoluzione accessibile
-// Windows Toast funziona meglio con PNG ad alta risoluzione (128x128 o più grande) invece di ICO
-#[cfg(windows)]
-fn ensure_notification_icon_available() -> Option<std::path::PathBuf> {
-    use std::fs;
-
-    // Prova prima a leggere PNG 128x128 dalla directory runtime (se distribuito con l'app)
-    // Altrimenti usa ICO embedded e convertilo in PNG usando la libreria image
-    let (icon_data, icon_ext) = {
-        let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
-
-        // Prova a leggere PNG dalla directory runtime (se l'app è distribuita con le icone)
-        if let Ok(png_data) = fs::read(exe_dir.join("icons").join("128x128.png")) {
-            (png_data, "png")
-        } else if let Ok(png_data) = fs::read(exe_dir.join("128x128.png")) {
-            (png_data, "png")
-        } else if let Ok(png_data) = fs::read(exe_dir.join("icons").join("icon.png")) {
-            (png_data, "png")
-        } else if let Ok(png_data) = fs::read(exe_dir.join("icon.png")) {
-            (png_data, "png")
-        } else {
-            // Fallback: converti ICO embedded in PNG 256x256 ad alta risoluzione
-            // Questo risolve il problema della sgranatura
-            match convert_ico_to_highres_png(include_bytes!("../../icons/icon.ico")) {
-                Ok(png_data) => {
-                    tracing::debug!(
-                        "Converted ICO to high-res PNG (256x256) for better notification quality"
-                    );
-                    (png_data, "png")
-                }
-                Err(e) => {
-                    tracing::warn!("Failed to convert ICO to PNG, using ICO: {}", e);
-                    (include_bytes!("../../icons/icon.ico").to_vec(), "ico")
-                }
-            }
+/// Convert a local path to a file:/// URI accepted by Windows Toast
+fn path_to_file_uri(path: &str) -> String {
+    let mut encoded = String::new();
+    for ch in path.replace('\\', "/").chars() {
+        match ch {
+            ' ' | '!' | '#' | '$' | '%' | '&' | '\'' | '(' | ')' | '*' | '+' | ',' | ';' | '='
+            | '?' | '@' | '[' | ']' => encoded.push_str(&format!("%{:02X}", ch as u32)),
+            // Keep the drive letter colon (C:/...)
+            ':' if encoded.len() == 1 => encoded.push(ch),
+            ':' => encoded.push_str("%3A"),
+            _ => encoded.push(ch),
         }
+    }
+    format!("file:///{}", encoded)
+}
+
+/// Build the ToastGeneric XML payload
+///
+/// `link` adds a button (label, URL) that opens the URL in the browser.
+/// Title and body are formatted for the language of `format`.
+fn build_toast_xml(
+    title: &str,
+    body: &str,
+    icon_uri: Option<&str>,
+    options: &ToastOptions,
+    link: Option<(&str, &str)>,
+    format: &NotificationFormat,
+) -> String {
+    let image = icon_uri
+        .map(|uri| {
+            format!(
+                r#"<image placement="appLogoOverride" hint-crop="circle" src="{}"/>"#,
+                escape_xml(uri)
+            )
+        })
+        .unwrap_or_default();
+
+    let mut buttons = String::new();
+    if let Some((label, url)) = link {
+        buttons.push_str(&format!(
+            r#"<action activationType="protocol" arguments="{}" content="{}"/>"#,
+            escape_xml(url),
+            escape_xml(label)
+        ));
+    }
+    // Windows ignores the reminder scenario on toasts without a button
+    if options.scenario == ToastScenario::Reminder {
+        buttons.push_str(r#"<action activationType="system" arguments="dismiss" content=""/>"#);
+    }
+    let actions = if buttons.is_empty() {
+        String::new()
+    } else {
+        format!("<actions>{}</actions>", buttons)
     };
 
-    // Prova a salvare l'icona nella directory dati dell'app
-    let icon_path = {
-        let detector = crate::config::get_portable_detector();
-        detector.data_dir().join(format!("icon.{}", icon_ext))
+    let audio = if options.silent {
+        r#"<audio silent="true"/>"#
+    } else {
+        r#"<audio src="ms-winsoundevent:Notification.Default"/>"#
     };
 
-    // Crea la directory se non esiste
-    if let Some(parent) = icon_path.parent() {
-        if let Err(e) = fs::create_dir_all(parent) {
-            tracing::warn!("Failed to create icon directory: {}", e);
-            return None;
-        }
-    }
+    format!(
+        r#"<toast launch="app-defined-string" duration="{}" scenario="{}"><visual lang="{}"><binding template="ToastGeneric"><text hint-maxLines="1">{}</text><text>{}</text>{}</binding></visual>{}{}</toast>"#,
+        options.duration_attr(),
+        options.scenario_attr(),
+        escape_xml(&format.language),
+        escape_xml(&format.apply(title)),
+        escape_xml(&format.apply(body)),
+        image,
+        actions,
+        audio
+    )
+}
 
-    // Copia l'icona solo se non esiste o se è stata modificata
-    // Controlla se il file esiste e ha la stessa dimensione
-    let needs_copy = match fs::metadata(&icon_path) {
-        Ok(meta) => meta.len() != icon_data.len() as u64,
-        Err(_) => true, // File non esiste, devi copiarlo
-    };
+/// Toast options from the config
+#[cfg(windows)]
+fn current_toast_options(app: &AppHandle) -> ToastOptions {
+    use tauri::Manager;
 
-    if needs_copy {
-        if let Err(e) = fs::write(&icon_path, &icon_data) {
-            tracing::warn!("Failed to write notification icon: {}", e);
-            return None;
-        }
-        tracing::debug!(
-            "Notification icon (format: {}) copied to: {}",
-            icon_ext,
-            icon_path.display()
-        );
-    }
+    app.try_state::<crate::AppState>()
+        .map(|state| state.cfg.read().toast)
+        .unwrap_or_default()
+}
 
-    Some(icon_path)
+/// Show a toast through the WinRT ToastNotificationManager (no external processes)
+#[cfg(windows)]
+fn show_winrt_toast(xml: &str) -> windows::core::Result<()> {
+    use windows::core::HSTRING;
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::UI::Notifications::{ToastNotification, ToastNotificationManager};
+
+    let document = XmlDocument::new()?;
+    document.LoadXml(&HSTRING::from(xml))?;
+    let toast = ToastNotification::CreateToastNotification(&document)?;
+    let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))?;
+    notifier.Show(&toast)
 }
 
 /// Show Windows notification with proper icon and theme
@@ -114,6 +145,44 @@ pub fn show_windows_notification(
     title: &str,
     body: &str,
     theme: &str,
+) -> Result<(), String> {
+    show_windows_notification_with_link(app, title, body, theme, None)
+}
+
+/// Show Windows notification with a button opening `link` (label, URL)
+///
+/// The Tauri fallback cannot show buttons, the link is dropped there.
+#[cfg(windows)]
+pub fn show_windows_notification_with_link(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    theme: &str,
+    link: Option<(&str, &str)>,
+) -> Result<(), String> {
+    if !current_toast_options(app).show_when_busy {
+        let documented_only = super::quiet::documented_apis_only(app);
+        if let Some(reason) = super::quiet::quiet_reason(documented_only) {
+            tracing::info!(
+                "{} active, deferring notification '{}'",
+                reason.as_str(),
+                title
+            );
+            super::quiet::defer(app, title, body, theme, link);
+            return Ok(());
+        }
+    }
+    show_now(app, title, body, theme, link)
+}
+
+/// Show a toast right away, even during quiet time
+#[cfg(windows)]
+pub(super) fn show_now(
+    app: &AppHandle,
+    title: &str,
+    body: &str,
+    theme: &str,
+    link: Option<(&str, &str)>,
 ) -> Result<(), String> {
     tracing::info!(
         "Attempting to show notification - Title: '{}', Body: '{}', Theme: {}",
@@ -122,257 +191,60 @@ pub fn show_windows_notification(
         theme
     );
 
-    // NUOVO APPROCCIO: Usa direttamente PowerShell con XML Toast template che include l'icona esplicitamente
-    // Questo garantisce che l'icona venga mostrata correttamente
-    #[cfg(windows)]
-    {
-        // Prova prima a usare un file .ico dedicato per migliori risultati
-        let icon_path_opt = ensure_notification_icon_available();
-
-        // Helper per fare URL encoding del percorso (necessario per spazi e caratteri speciali)
-        let encode_uri = |path: &str| -> String {
-            // Converti backslash a forward slash e poi applica percent-encoding
-            let path_normalized = path.replace("\\", "/");
-            // Per file:/// locali, dobbiamo fare percent-encoding solo dei caratteri speciali, non di tutto
-            // Windows Toast accetta percorsi diretti, ma per sicurezza codifichiamo spazi e caratteri speciali
-            let mut encoded = String::new();
-            for ch in path_normalized.chars() {
-                match ch {
-                    ' ' => encoded.push_str("%20"),
-                    '!' => encoded.push_str("%21"),
-                    '#' => encoded.push_str("%23"),
-                    '$' => encoded.push_str("%24"),
-                    '%' => encoded.push_str("%25"),
-                    '&' => encoded.push_str("%26"),
-                    '\'' => encoded.push_str("%27"),
-                    '(' => encoded.push_str("%28"),
-                    ')' => encoded.push_str("%29"),
-                    '*' => encoded.push_str("%2A"),
-                    '+' => encoded.push_str("%2B"),
-                    ',' => encoded.push_str("%2C"),
-                    ':' => encoded.push_str("%3A"),
-                    ';' => encoded.push_str("%3B"),
-                    '=' => encoded.push_str("%3D"),
-                    '?' => encoded.push_str("%3F"),
-                    '@' => encoded.push_str("%40"),
-                    '[' => encoded.push_str("%5B"),
-                    ']' => encoded.push_str("%5D"),
-                    _ => encoded.push(ch),
-                }
-            }
-            format!("file:///{}", encoded)
-        };
-
-        let icon_uri = if let Some(icon_path) = icon_path_opt {
-            // Usa il file .ico dedicato - converto il percorso in formato file:/// per Windows Toast
-            let icon_path_str = icon_path.to_string_lossy().to_string();
-            // Windows Toast richiede il formato file:/// con forward slashes e percent-encoding per spazi
-            encode_uri(&icon_path_str)
-        } else {
-            // Fallback: usa l'exe stesso
-            let exe_path = std::env::current_exe()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_string();
-            encode_uri(&exe_path)
-        };
-
-        // Crea un XML Toast template personalizzato con l'icona
-        let xml_template = format!(
-            r#"<toast launch="app-defined-string" scenario="default">
-<visual>
-<binding template="ToastGeneric">
-<text hint-maxLines="1">{}</text>
-<text>{}</text>
-<image placement="appLogoOverride" hint-crop="circle" src="{}"/>
-</binding>
-</visual>
-<audio src="ms-winsoundevent:Notification.Default" />
-</toast>"#,
-            title, body, icon_uri
-        );
-
-        // Salva l'XML in un file temporaneo
-        let temp_dir = std::env::temp_dir();
-        let xml_path = temp_dir.join("tmc_notification.xml");
-        if let Err(e) = std::fs::write(&xml_path, &xml_template) {
-            tracing::warn!("Failed to write notification XML: {}", e);
-        } else {
-            // Esegui PowerShell per mostrare la notifica
-            let app_id = "TommyMemoryCleaner";
-            let ps_script = format!(
-                r#"
-[Windows.UI.Notifications.ToastNotificationManager, Windows.UI.Notifications, ContentType = WindowsRuntime] | Out-Null
-[Windows.Data.Xml.Dom.XmlDocument, Windows.Data.Xml.Dom.XmlDocument, ContentType = WindowsRuntime] | Out-Null
-
-try {{
-    $appId = '{}'
-    $regPath = 'HKCU:\Software\Classes\AppUserModelId\' + $appId
-    $displayName = 'Tommy Memory Cleaner'
-    
-    # Forza la registrazione del DisplayName prima di ogni notifica
-    # Questo assicura che Windows usi il nome corretto anche se la cache è stata invalidata
-    if (-not (Test-Path $regPath)) {{
-        New-Item -Path $regPath -Force | Out-Null
-    }}
-    Set-ItemProperty -Path $regPath -Name DisplayName -Value $displayName -Type String -Force | Out-Null
-    Write-Output "DisplayName forced to: $displayName"
-    
-    # Carica e mostra la notifica
-    $xml = New-Object Windows.Data.Xml.Dom.XmlDocument
-    $xml.LoadXml([System.IO.File]::ReadAllText('{}'))
-    
-    $toast = [Windows.UI.Notifications.ToastNotification]::new($xml)
-    
-    # Crea il notifier - Windows dovrebbe usare automaticamente il DisplayName se registrato
-    $notifier = [Windows.UI.Notifications.ToastNotificationManager]::CreateToastNotifier($appId)
-    $notifier.Show($toast)
-    
-    Write-Output "Toast notification shown successfully with DisplayName: $displayName"
-}} catch {{
-    Write-Error "Failed to show toast: $_"
-    exit 1
-}}
-"#,
-                app_id,
-                xml_path.to_string_lossy().replace("'", "''")
+    // PNG ad alta risoluzione dalla cache delle icone (fallback: l'exe stesso)
+    let icon_path = crate::ui::icon_cache::notification_icon()
+        .and_then(|p| p.to_str().map(|s| s.to_string()))
+        .or_else(|| {
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe_path| exe_path.to_str().map(|s| s.to_string()))
+        });
+    let icon_uri = icon_path.as_deref().map(path_to_file_uri);
+
+    // WinRT toast built in-process
+    let options = current_toast_options(app);
+    let format = NotificationFormat::current(&options);
+    let xml = build_toast_xml(title, body, icon_uri.as_deref(), &options, link, &format);
+    match show_winrt_toast(&xml) {
+        Ok(()) => {
+            tracing::info!("✓ Windows Toast notification shown successfully");
+            return Ok(());
+        }
+        Err(e) => {
+            tracing::warn!(
+                "✗ WinRT Toast notification failed: {} (0x{:08X}), trying fallback",
+                e.message(),
+                e.code().0
             );
-
-            match std::process::Command::new("powershell")
-                .arg("-NoProfile")
-                .arg("-NonInteractive")
-                .arg("-ExecutionPolicy")
-                .arg("Bypass")
-                .arg("-Command")
-                .arg(&ps_script)
-                .creation_flags(0x08000000) // CREATE_NO_WINDOW
-                .output()
-            {
-                Ok(output) => {
-                    // Pulisci file temporaneo
-                    let _ = std::fs::remove_file(&xml_path);
-                    if output.status.success() {
-                        tracing::info!(
-                            "✓ Windows Toast notification shown successfully with icon: {}",
-                            icon_uri
-                        );
-                        return Ok(());
-                    } else {
-                        let error = String::from_utf8_lossy(&output.stderr);
-                        tracing::warn!(
-                            "✗ PowerShell Toast notification failed: {}, trying fallback",
-                            error
-                        );
-                    }
-                }
-                Err(e) => {
-                    let _ = std::fs::remove_file(&xml_path);
-                    tracing::warn!(
-                        "✗ Failed to execute PowerShell Toast notification: {}, trying fallback",
-                        e
-                    );
-                }
-            }
         }
     }
 
     // Fallback: Usa Tauri API notification
     tracing::debug!("Trying Tauri API notification as fallback...");
-    #[cfg(windows)]
-    let icon_path = ensure_notification_icon_available()
-        .and_then(|p| p.to_str().map(|s| s.to_string()))
-        .or_else(|| {
-            std::env::current_exe().ok().and_then(|exe_path| {
-                tracing::debug!("Using embedded icon from exe: {}", exe_path.display());
-                exe_path.to_str().map(|s| s.to_string())
-            })
-        })
-        .unwrap_or_else(|| {
-            tracing::warn!("Cannot get icon path, notification may fail");
-            String::new()
-        });
-
-    #[cfg(not(windows))]
-    let icon_path = String::new();
-
-    if !icon_path.is_empty() {
-        use tauri_plugin_notification::NotificationExt;
-        match app
-            .notification()
-            .builder()
-            .title(title)
-            .body(body)
-            .icon(icon_path)
-            .show()
-        {
-            Ok(_) => {
-                tracing::info!("✓ Tauri API notification shown successfully");
-                return Ok(());
-            }
-            Err(e) => {
-                tracing::warn!("✗ Tauri API notification failed: {}", e);
-            }
-        }
+    use tauri_plugin_notification::NotificationExt;
+    let mut builder = app
+        .notification()
+        .builder()
+        .title(format.apply(title))
+        .body(format.apply(body));
+    if let Some(path) = icon_path {
+        builder = builder.icon(path);
     }
-
-    // Ultimo fallback: PowerShell Balloon
-    #[cfg(windows)]
-    {
-        tracing::debug!("Trying PowerShell balloon notification as last fallback...");
-        let title_clone = title.to_string();
-        let body_clone = body.to_string();
-        let ps_script = format!(
-            r#"
-try {{
-    Add-Type -AssemblyName System.Windows.Forms -ErrorAction Stop
-    $notification = New-Object System.Windows.Forms.NotifyIcon
-    $notification.Icon = [System.Drawing.SystemIcons]::Information
-    $notification.BalloonTipTitle = '{}'
-    $notification.BalloonTipText = '{}'
-    $notification.Visible = $true
-    $notification.ShowBalloonTip(5000)
-    Start-Sleep -Seconds 6
-    $notification.Dispose()
-    Write-Output "Notification shown successfully"
-}} catch {{
-    Write-Error "Failed to show notification: $_"
-    exit 1
-}}
-"#,
-            title_clone
-                .replace("'", "''")
-                .replace("\n", " ")
-                .replace("\r", " "),
-            body_clone
-                .replace("'", "''")
-                .replace("\n", " ")
-                .replace("\r", " ")
-        );
-
-        match std::process::Command::new("powershell")
-            .arg("-NoProfile")
-            .arg("-NonInteractive")
-            .arg("-Command")
-            .arg(&ps_script)
-            .creation_flags(0x08000000)
-            .output()
-        {
-            Ok(output) => {
-                if output.status.success() {
-                    tracing::info!("✓ PowerShell balloon notification shown successfully");
-                    return Ok(());
-                } else {
-                    let error = String::from_utf8_lossy(&output.stderr);
-                    tracing::error!("✗ PowerShell notification failed: {}", error);
-                }
-            }
-            Err(e) => {
-                tracing::error!("✗ Failed to execute PowerShell notification: {}", e);
-            }
+    match builder.show() {
+        Ok(_) => {
+            tracing::info!("✓ Tauri API notification shown successfully");
+            Ok(())
+        }
+        Err(e) => {
+            tracing::error!("✗ Tauri API notification failed: {}", e);
+            let message = format!("All notification methods failed: {}", e);
+            crate::logging::error_bus::report(
+                crate::logging::error_bus::Subsystem::Notifications,
+                message.clone(),
+            );
+            Err(message)
         }
     }
-
-    Err("All notification methods failed".to_string())
 }
 
 #[cfg(not(windows))]
@@ -385,14 +257,120 @@ pub fn show_windows_notification(
     Ok(())
 }
 
+#[cfg(not(windows))]
+pub fn show_windows_notification_with_link(
+    _app: &AppHandle,
+    _title: &str,
+    _body: &str,
+    _theme: &str,
+    _link: Option<(&str, &str)>,
+) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub(super) fn show_now(
+    _app: &AppHandle,
+    _title: &str,
+    _body: &str,
+    _theme: &str,
+    _link: Option<(&str, &str)>,
+) -> Result<(), String> {
+    Ok(())
+}
+
+/// Why Windows would hide toasts from the app, `None` when they are allowed
+///
+/// Reads the per-user switches behind Settings > System > Notifications: the
+/// global toggle and the toggle of the app.
+#[cfg(windows)]
+pub fn notifications_blocked() -> Option<&'static str> {
+    use crate::utils::read_user_dword as read_dword;
+
+    // A missing value means the default, which is enabled
+    if read_dword(
+        r"Software\Microsoft\Windows\CurrentVersion\PushNotifications",
+        "ToastEnabled",
+    ) == Some(0)
+    {
+        return Some("Notifications are turned off in Windows");
+    }
+    if read_dword(&app_settings_key(), "Enabled") == Some(0) {
+        return Some("Notifications are turned off for Tommy Memory Cleaner");
+    }
+    None
+}
+
+#[cfg(not(windows))]
+pub fn notifications_blocked() -> Option<&'static str> {
+    None
+}
+
+/// Create the toast notifier for our AppUserModelID and read its setting,
+/// without showing anything
+#[cfg(windows)]
+pub fn probe_toast_notifier() -> Result<(), String> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::{NotificationSetting, ToastNotificationManager};
+
+    let notifier = ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(APP_ID))
+        .map_err(|e| format!("Toast notifier unavailable: {}", e))?;
+    let setting = notifier
+        .Setting()
+        .map_err(|e| format!("Toast notifier setting unavailable: {}", e))?;
+    match setting {
+        NotificationSetting::Enabled => Ok(()),
+        NotificationSetting::DisabledForApplication => {
+            Err("Disabled for Tommy Memory Cleaner".to_string())
+        }
+        NotificationSetting::DisabledForUser => Err("Disabled for the user".to_string()),
+        NotificationSetting::DisabledByGroupPolicy => Err("Disabled by group policy".to_string()),
+        NotificationSetting::DisabledByManifest => Err("Disabled by the app manifest".to_string()),
+        other => Err(format!("Notification setting {}", other.0)),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn probe_toast_notifier() -> Result<(), String> {
+    Ok(())
+}
+
+/// Set `APP_ID` as the AppUserModelID of this process
+///
+/// Must run before any other Windows API that uses it (shell notifications,
+/// jump lists), otherwise toasts show the id instead of the display name.
+#[cfg(windows)]
+pub fn set_app_user_model_id() {
+    use windows_sys::Win32::UI::Shell::SetCurrentProcessExplicitAppUserModelID;
+
+    let app_id_wide = to_wide(APP_ID);
+    // HRESULT, S_OK (0) on success
+    let result = unsafe { SetCurrentProcessExplicitAppUserModelID(app_id_wide.as_ptr()) };
+    if result == 0 {
+        tracing::info!("✓ AppUserModelID set explicitly: {}", APP_ID);
+        eprintln!("[TMC] AppUserModelID set explicitly: {}", APP_ID);
+    } else {
+        // Log error but don't block the app (some Windows versions might not support it)
+        tracing::warn!(
+            "✗ Failed to set AppUserModelID explicitly: HRESULT 0x{:08X}",
+            result
+        );
+        tracing::debug!("This may cause notifications to show AppID instead of DisplayName");
+        eprintln!(
+            "[TMC] ERROR: Failed to set AppUserModelID explicitly: HRESULT 0x{:08X}",
+            result
+        );
+    }
+}
+
+#[cfg(not(windows))]
+pub fn set_app_user_model_id() {}
+
 /// Register the app for Windows Toast notifications
 #[cfg(windows)]
 pub fn register_app_for_notifications() {
-    use std::ffi::OsStr;
-    use std::os::windows::ffi::OsStrExt;
     use windows_sys::Win32::System::Registry::{RegSetValueExW, HKEY_CURRENT_USER, REG_SZ};
 
-    let _app_id = "TommyMemoryCleaner";
     // Usa to_string_lossy() per gestire correttamente i percorsi con caratteri Unicode
     let exe_path = std::env::current_exe()
         .unwrap_or_default()
@@ -406,8 +384,8 @@ pub fn register_app_for_notifications() {
 
     // Registra AppUserModelID nel registro con DisplayName e IconUri
     // IMPORTANTE: Windows richiede che questa registrazione avvenga PRIMA di qualsiasi notifica
-    // USIAMO "TommyMemoryCleaner" come AppUserModelID per mostrare un nome user-friendly nelle notifiche
-    let key_path = r"Software\Classes\AppUserModelId\TommyMemoryCleaner";
+    // USIAMO APP_ID come AppUserModelID per mostrare un nome user-friendly nelle notifiche
+    let key_path = app_id_key();
     let display_name = "Tommy Memory Cleaner";
 
     // Elimina ricorsivamente la chiave esistente per forzare la ricreazione (utile se è stata modificata)
@@ -417,7 +395,7 @@ pub fn register_app_for_notifications() {
             RegCloseKey, RegDeleteKeyW, RegOpenKeyExW, KEY_ALL_ACCESS,
         };
         // Prova prima ad aprire la chiave per verificare se esiste
-        let key_path_wide: Vec<u16> = OsStr::new(key_path).encode_wide().chain(Some(0)).collect();
+        let key_path_wide = to_wide(&key_path);
         let mut hkey_test: windows_sys::Win32::Foundation::HANDLE = std::ptr::null_mut();
         let open_result = RegOpenKeyExW(
             HKEY_CURRENT_USER,
@@ -441,18 +419,15 @@ pub fn register_app_for_notifications() {
         }
     }
 
-    // Prova a usare un file .ico dedicato per migliori risultati con Windows Toast
-    // Fallback all'exe se non riesce
-    let icon_path = ensure_notification_icon_available()
+    // PNG dalla cache delle icone per migliori risultati con Windows Toast
+    // Fallback all'exe se la cache non è ancora pronta (viene ri-registrata dopo)
+    let icon_path = crate::ui::icon_cache::notification_icon()
         .and_then(|p| p.to_str().map(|s| s.to_string()))
         .unwrap_or_else(|| exe_path.clone());
 
     // Converti stringhe a wide strings
-    let key_path_wide: Vec<u16> = OsStr::new(key_path).encode_wide().chain(Some(0)).collect();
-    let display_name_wide: Vec<u16> = OsStr::new(display_name)
-        .encode_wide()
-        .chain(Some(0))
-        .collect();
+    let key_path_wide = to_wide(&key_path);
+    let display_name_wide = to_wide(display_name);
 
     unsafe {
         // Crea la chiave se non esiste e imposta i valori
@@ -471,10 +446,7 @@ pub fn register_app_for_notifications() {
 
         if result == 0 {
             // Imposta DisplayName
-            let display_name_value: Vec<u16> = OsStr::new("DisplayName")
-                .encode_wide()
-                .chain(Some(0))
-                .collect();
+            let display_name_value = to_wide("DisplayName");
             RegSetValueExW(
                 hkey,
                 display_name_value.as_ptr(),
@@ -485,12 +457,8 @@ pub fn register_app_for_notifications() {
             );
 
             // Imposta IconUri
-            let icon_uri_value: Vec<u16> =
-                OsStr::new("IconUri").encode_wide().chain(Some(0)).collect();
-            let icon_path_wide: Vec<u16> = OsStr::new(&icon_path)
-                .encode_wide()
-                .chain(Some(0))
-                .collect();
+            let icon_uri_value = to_wide("IconUri");
+            let icon_path_wide = to_wide(&icon_path);
             RegSetValueExW(
                 hkey,
                 icon_uri_value.as_ptr(),
@@ -512,3 +480,80 @@ pub fn register_app_for_notifications() {
 pub fn register_app_for_notifications() {
     // No-op on non-Windows platforms
 }
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notifications::options::ToastDuration;
+
+    #[test]
+    fn test_toast_xml_escapes_text() {
+        let xml = build_toast_xml(
+            "A & B",
+            "<script>'x'</script>",
+            None,
+            &ToastOptions::default(),
+            None,
+            &NotificationFormat::default(),
+        );
+        assert!(xml.contains("<text hint-maxLines=\"1\">A &amp; B</text>"));
+        assert!(xml.contains("&lt;script&gt;&apos;x&apos;&lt;/script&gt;"));
+        assert!(!xml.contains("<image"));
+        assert!(xml.contains(r#"duration="short" scenario="default""#));
+        assert!(!xml.contains("<actions>"));
+    }
+
+    #[test]
+    fn test_toast_xml_options() {
+        let options = ToastOptions {
+            duration: ToastDuration::Long,
+            scenario: ToastScenario::Reminder,
+            silent: true,
+            ..Default::default()
+        };
+        let xml = build_toast_xml(
+            "Title",
+            "Body",
+            None,
+            &options,
+            None,
+            &NotificationFormat::default(),
+        );
+        assert!(xml.contains(r#"duration="long" scenario="reminder""#));
+        assert!(xml.contains(r#"arguments="dismiss""#));
+        assert!(xml.contains(r#"<audio silent="true"/>"#));
+    }
+
+    #[test]
+    fn test_toast_xml_link_button() {
+        let link = Some(("Page file advice", "https://example.com/?a=1&b=2"));
+        let xml = build_toast_xml(
+            "Title",
+            "Body",
+            None,
+            &ToastOptions::default(),
+            link,
+            &NotificationFormat::default(),
+        );
+        assert!(xml.contains(
+            r#"<actions><action activationType="protocol" arguments="https://example.com/?a=1&amp;b=2" content="Page file advice"/></actions>"#
+        ));
+    }
+
+    #[test]
+    fn test_path_to_file_uri() {
+        assert_eq!(
+            path_to_file_uri(r"C:\Users\Mario Rossi\icon.png"),
+            "file:///C:/Users/Mario%20Rossi/icon.png"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_registry_keys_use_app_id() {
+        // The toast notifier, the registration and the settings check must agree
+        assert!(app_id_key().ends_with(r"\AppUserModelId\TommyMemoryCleaner"));
+        assert!(app_settings_key().ends_with(r"\Notifications\Settings\TommyMemoryCleaner"));
+    }
+}
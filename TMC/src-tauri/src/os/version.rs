@@ -0,0 +1,232 @@
+/// Cached Windows version service
+///
+/// The version is detected once (RtlGetVersion with GetVersionExW fallback)
+/// and a build-derived capability matrix is computed from it. All version
+/// checks in the app (memory areas, UI platform, window decorations,
+/// config platform flags) go through this service.
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+/// First Windows 11 build
+const WIN11_FIRST_BUILD: u32 = 22000;
+/// Windows 10 1803
+const WIN10_1803_BUILD: u32 = 17134;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub build: u32,
+}
+
+/// Features available on the running Windows build
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Capabilities {
+    pub standby_list_low: bool,
+    pub combined_page_list: bool,
+    pub modified_file_cache: bool,
+    pub memory_compression: bool,
+    pub native_rounded_corners: bool,
+}
+
+impl Capabilities {
+    /// Build the capability matrix for a given version
+    pub fn for_version(ver: &OsVersion) -> Self {
+        let win10_plus = ver.major >= 10;
+        let win10_1803_plus =
+            ver.major > 10 || (ver.major == 10 && ver.build >= WIN10_1803_BUILD);
+        let win11 = ver.major == 10 && ver.minor == 0 && ver.build >= WIN11_FIRST_BUILD;
+
+        Self {
+            standby_list_low: win10_plus,
+            combined_page_list: win10_1803_plus,
+            modified_file_cache: win10_1803_plus,
+            memory_compression: win10_plus,
+            native_rounded_corners: win11,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WinVersion {
+    pub version: OsVersion,
+    pub capabilities: Capabilities,
+}
+
+impl WinVersion {
+    fn new(version: OsVersion) -> Self {
+        Self {
+            version,
+            capabilities: Capabilities::for_version(&version),
+        }
+    }
+
+    /// Windows 11 (build >= 22000)
+    pub fn is_windows_11(&self) -> bool {
+        self.version.major == 10 && self.version.minor == 0 && self.version.build >= WIN11_FIRST_BUILD
+    }
+
+    /// Windows 10 (major 10, build < 22000)
+    pub fn is_windows_10(&self) -> bool {
+        self.version.major == 10 && self.version.minor == 0 && self.version.build < WIN11_FIRST_BUILD
+    }
+
+    /// Platform identifier used by the frontend for styling
+    pub fn platform_name(&self) -> &'static str {
+        if self.is_windows_11() {
+            "windows-11"
+        } else if self.is_windows_10() {
+            "windows-10"
+        } else {
+            "windows"
+        }
+    }
+}
+
+static WIN_VERSION: Lazy<WinVersion> = Lazy::new(|| {
+    let ver = WinVersion::new(detect_windows_version());
+    tracing::info!(
+        "Windows {}.{}.{} detected, capabilities: {:?}",
+        ver.version.major,
+        ver.version.minor,
+        ver.version.build,
+        ver.capabilities
+    );
+    ver
+});
+
+/// Get the cached Windows version (detected on first call)
+pub fn win_version() -> &'static WinVersion {
+    &WIN_VERSION
+}
+
+#[cfg(windows)]
+fn detect_windows_version() -> OsVersion {
+    // FIX: GetVersionExW è deprecato e può restituire informazioni errate su Windows 8+
+    // Usa RtlGetVersion che è più affidabile
+    unsafe {
+        #[repr(C)]
+        struct RTL_OSVERSIONINFOEXW {
+            dw_os_version_info_size: u32,
+            dw_major_version: u32,
+            dw_minor_version: u32,
+            dw_build_number: u32,
+            dw_platform_id: u32,
+            sz_csd_version: [u16; 128],
+            w_service_pack_major: u16,
+            w_service_pack_minor: u16,
+            w_suite_mask: u16,
+            w_product_type: u8,
+            w_reserved: u8,
+        }
+
+        type RtlGetVersionFn = unsafe extern "system" fn(*mut RTL_OSVERSIONINFOEXW) -> i32;
+
+        // Carica ntdll.dll e ottieni RtlGetVersion
+        use windows_sys::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+
+        let ntdll_name = b"ntdll.dll\0";
+        let ntdll = GetModuleHandleA(ntdll_name.as_ptr());
+        // GetModuleHandleA restituisce un HMODULE che è isize in windows-sys
+        // Compare with 0 instead of null_mut()
+        if ntdll != std::ptr::null_mut() {
+            let rtl_get_version_name = b"RtlGetVersion\0";
+            if let Some(rtl_get_version) = GetProcAddress(ntdll, rtl_get_version_name.as_ptr()) {
+                let rtl_get_version_fn: RtlGetVersionFn = std::mem::transmute(rtl_get_version);
+                let mut os_info: RTL_OSVERSIONINFOEXW = std::mem::zeroed();
+                os_info.dw_os_version_info_size =
+                    std::mem::size_of::<RTL_OSVERSIONINFOEXW>() as u32;
+
+                if rtl_get_version_fn(&mut os_info) == 0 {
+                    tracing::info!(
+                        "RtlGetVersion: Windows {}.{}.{}",
+                        os_info.dw_major_version,
+                        os_info.dw_minor_version,
+                        os_info.dw_build_number
+                    );
+                    return OsVersion {
+                        major: os_info.dw_major_version,
+                        minor: os_info.dw_minor_version,
+                        build: os_info.dw_build_number,
+                    };
+                }
+            }
+        }
+
+        // Fallback a GetVersionExW se RtlGetVersion non è disponibile
+        use windows_sys::Win32::System::SystemInformation::{GetVersionExW, OSVERSIONINFOEXW};
+        let mut os_info: OSVERSIONINFOEXW = std::mem::zeroed();
+        os_info.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOEXW>() as u32;
+
+        if GetVersionExW(&mut os_info as *mut _ as *mut _) != 0 {
+            tracing::warn!(
+                "GetVersionExW (may be inaccurate): Windows {}.{}.{}",
+                os_info.dwMajorVersion,
+                os_info.dwMinorVersion,
+                os_info.dwBuildNumber
+            );
+            // Se GetVersionExW restituisce 6.2, probabilmente è Windows 10/11
+            // Assumiamo Windows 10 come default sicuro
+            if os_info.dwMajorVersion == 6 && os_info.dwMinorVersion == 2 {
+                tracing::warn!("GetVersionExW returned 6.2 (Windows 8), assuming Windows 10+");
+                OsVersion {
+                    major: 10,
+                    minor: 0,
+                    build: 19041,
+                }
+            } else {
+                OsVersion {
+                    major: os_info.dwMajorVersion,
+                    minor: os_info.dwMinorVersion,
+                    build: os_info.dwBuildNumber,
+                }
+            }
+        } else {
+            // Default a Windows 10 se non riusciamo a rilevare
+            tracing::warn!("Failed to detect Windows version, defaulting to Windows 10");
+            OsVersion {
+                major: 10,
+                minor: 0,
+                build: 19041,
+            }
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn detect_windows_version() -> OsVersion {
+    OsVersion {
+        major: 0,
+        minor: 0,
+        build: 0,
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ver(major: u32, build: u32) -> OsVersion {
+        OsVersion {
+            major,
+            minor: 0,
+            build,
+        }
+    }
+
+    #[test]
+    fn test_capabilities_windows_10_old() {
+        let caps = Capabilities::for_version(&ver(10, 16299));
+        assert!(caps.standby_list_low);
+        assert!(!caps.combined_page_list);
+        assert!(!caps.native_rounded_corners);
+    }
+
+    #[test]
+    fn test_platform_name() {
+        assert_eq!(WinVersion::new(ver(10, 22631)).platform_name(), "windows-11");
+        assert_eq!(WinVersion::new(ver(10, 19045)).platform_name(), "windows-10");
+        assert_eq!(WinVersion::new(ver(6, 9600)).platform_name(), "windows");
+    }
+}
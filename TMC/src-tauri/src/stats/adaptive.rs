@@ -0,0 +1,153 @@
+/// Adaptive area selection
+///
+/// With `adaptive_area_selection` the engine skips areas that freed next to
+/// nothing in each of their last `WINDOW` runs, and runs them again after
+/// `PROBE_EVERY` skips in a row to notice when they become useful again.
+/// Everything is derived from the optimization history, so the learned state
+/// survives restarts without a file of its own.
+use crate::stats::history::{with_history, OptimizationRecord};
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+/// Runs looked at per area
+const WINDOW: usize = 5;
+/// Below this an area counts as having freed nothing
+const MIN_USEFUL_BYTES: i64 = 1024 * 1024;
+/// Adaptive skips in a row before the area is probed again
+const PROBE_EVERY: u32 = 10;
+/// `skipped` reason of an adaptive skip, also used to count them in the history
+pub const ADAPTIVE_SKIP_REASON: &str = "Freed nothing in recent runs";
+
+/// What the history says about one area
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AreaEffectiveness {
+    pub name: String,
+    /// Measured runs considered, at most `WINDOW`
+    pub recent_runs: usize,
+    pub avg_freed_bytes: i64,
+    /// Bytes freed per second of work over those runs
+    pub bytes_per_sec: f64,
+    /// Adaptive skips since the area last ran
+    pub adaptive_skips: u32,
+    /// The next run skips this area
+    pub skip_next: bool,
+}
+
+/// Assess one area (by display name) from the records, oldest first
+pub fn assess(records: &[OptimizationRecord], name: &str) -> AreaEffectiveness {
+    let mut runs: Vec<(i64, u128)> = Vec::with_capacity(WINDOW);
+    let mut adaptive_skips = 0;
+
+    for area in records
+        .iter()
+        .rev()
+        .filter_map(|r| r.areas.iter().find(|a| a.name == name))
+    {
+        if area.skipped.as_deref() == Some(ADAPTIVE_SKIP_REASON) {
+            if runs.is_empty() {
+                adaptive_skips += 1;
+            }
+            continue;
+        }
+        // Other skips, failures and unmeasured runs say nothing about the yield
+        if area.skipped.is_some() || area.error.is_some() {
+            continue;
+        }
+        if let Some(freed) = area.freed_bytes {
+            runs.push((freed, area.duration_ms));
+            if runs.len() == WINDOW {
+                break;
+            }
+        }
+    }
+
+    let total_freed: i64 = runs.iter().map(|(freed, _)| *freed).sum();
+    let total_ms: u128 = runs.iter().map(|(_, ms)| *ms).sum();
+    let ineffective =
+        runs.len() == WINDOW && runs.iter().all(|(freed, _)| *freed < MIN_USEFUL_BYTES);
+
+    AreaEffectiveness {
+        name: name.to_string(),
+        recent_runs: runs.len(),
+        avg_freed_bytes: if runs.is_empty() {
+            0
+        } else {
+            total_freed / runs.len() as i64
+        },
+        bytes_per_sec: if total_ms > 0 {
+            total_freed as f64 / (total_ms as f64 / 1000.0)
+        } else {
+            0.0
+        },
+        adaptive_skips,
+        skip_next: ineffective && adaptive_skips < PROBE_EVERY,
+    }
+}
+
+/// Whether the next run should skip the area
+pub fn should_skip(name: &str) -> bool {
+    with_history(|records| assess(records, name).skip_next)
+}
+
+/// Learned state of every area found in the history
+pub fn effectiveness() -> Vec<AreaEffectiveness> {
+    with_history(|records| {
+        let names: BTreeSet<&str> = records
+            .iter()
+            .flat_map(|r| r.areas.iter().map(|a| a.name.as_str()))
+            .collect();
+        names
+            .into_iter()
+            .map(|name| assess(records, name))
+            .collect()
+    })
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::types::Reason;
+    use crate::stats::history::AreaRecord;
+
+    fn record(freed: Option<i64>, skipped: Option<&str>) -> OptimizationRecord {
+        OptimizationRecord {
+            timestamp: 0,
+            reason: Reason::Manual,
+            profile: "Balanced".to_string(),
+            areas: vec![AreaRecord {
+                name: "Registry Cache".to_string(),
+                duration_ms: 50,
+                freed_bytes: freed,
+                error: None,
+                skipped: skipped.map(str::to_string),
+            }],
+            freed_physical_bytes: 0,
+            freed_commit_bytes: 0,
+            duration_ms: 50,
+            deferred: None,
+            purge_impact: None,
+        }
+    }
+
+    #[test]
+    fn test_skip_after_window_and_probe_again() {
+        let mut records: Vec<_> = (0..WINDOW).map(|_| record(Some(4096), None)).collect();
+        let state = assess(&records, "Registry Cache");
+        assert_eq!(state.recent_runs, WINDOW);
+        assert!(state.skip_next);
+
+        // One useful run in the window keeps the area
+        records[0] = record(Some(64 * 1024 * 1024), None);
+        assert!(!assess(&records, "Registry Cache").skip_next);
+        records[0] = record(Some(0), None);
+
+        // Probed again after PROBE_EVERY adaptive skips
+        for _ in 0..PROBE_EVERY {
+            records.push(record(None, Some(ADAPTIVE_SKIP_REASON)));
+        }
+        let state = assess(&records, "Registry Cache");
+        assert_eq!(state.adaptive_skips, PROBE_EVERY);
+        assert!(!state.skip_next);
+    }
+}
@@ -0,0 +1,404 @@
+/// Optimization history stored as JSON Lines in the data directory
+///
+/// Each optimization appends one line to `optimization_history.jsonl`.
+/// Records are kept in memory after the first access and the file is
+/// compacted to `COMPACT_TO` records when it grows past `MAX_RECORDS`, so the
+/// rewrite happens once every thousand runs instead of on every run.
+use crate::benchmark::purge_impact::PurgeImpact;
+use crate::engine::OptimizeResult;
+use crate::memory::types::Reason;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const HISTORY_FILE_NAME: &str = "optimization_history.jsonl";
+const MAX_RECORDS: usize = 5000;
+/// Records kept when the file is compacted
+const COMPACT_TO: usize = 4000;
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1000;
+
+/// Result of a single memory area within a recorded optimization
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaRecord {
+    pub name: String,
+    pub duration_ms: u128,
+    /// Bytes attributed to this area, `None` when the engine did not measure it
+    #[serde(default)]
+    pub freed_bytes: Option<i64>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Set when the area was skipped (free RAM floor, cooldown)
+    #[serde(default)]
+    pub skipped: Option<String>,
+}
+
+/// Why an automatic run happened later than its trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Deferral {
+    /// Held until the session was unlocked (`LockedSessionPolicy::DeferToUnlock`)
+    Locked,
+}
+
+impl std::fmt::Display for Deferral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Deferral::Locked => write!(f, "deferred (locked)"),
+        }
+    }
+}
+
+/// A single optimization run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizationRecord {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+    pub reason: Reason,
+    pub profile: String,
+    pub areas: Vec<AreaRecord>,
+    pub freed_physical_bytes: i64,
+    pub freed_commit_bytes: i64,
+    pub duration_ms: u128,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deferred: Option<Deferral>,
+    /// Refill cost measured after a standby list purge (`cmd_measure_purge_impact`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub purge_impact: Option<PurgeImpact>,
+}
+
+/// Filter and paging options for `query_history`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Only records at or after this unix timestamp
+    pub since: Option<u64>,
+    /// Only records at or before this unix timestamp
+    pub until: Option<u64>,
+    pub reason: Option<Reason>,
+    pub profile: Option<String>,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// A page of history records (newest first)
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryPage {
+    pub total: usize,
+    pub offset: usize,
+    pub records: Vec<OptimizationRecord>,
+}
+
+static HISTORY: Lazy<Mutex<Vec<OptimizationRecord>>> = Lazy::new(|| Mutex::new(load_history()));
+
+/// Deferral for the next recorded run with the given reason, set by the scheduler
+static PENDING_DEFERRAL: Lazy<Mutex<Option<(Reason, Deferral)>>> = Lazy::new(|| Mutex::new(None));
+
+fn history_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(HISTORY_FILE_NAME)
+}
+
+fn load_history() -> Vec<OptimizationRecord> {
+    let path = history_path();
+    let content = match fs::read_to_string(&path) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
+
+    let mut records = parse_history(&content);
+    if records.len() > MAX_RECORDS {
+        let excess = records.len() - MAX_RECORDS;
+        records.drain(..excess);
+    }
+
+    tracing::debug!("Loaded {} optimization history records", records.len());
+    records
+}
+
+/// Parse the JSON Lines history format, skipping corrupted lines
+pub fn parse_history(content: &str) -> Vec<OptimizationRecord> {
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .filter_map(|l| match serde_json::from_str(l) {
+            Ok(r) => Some(r),
+            Err(e) => {
+                tracing::debug!("Skipping corrupted history line: {}", e);
+                None
+            }
+        })
+        .collect()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Rewrite the history file with the given records
+fn rewrite_history(records: &[OptimizationRecord]) -> std::io::Result<()> {
+    let path = history_path();
+    let temp_path = path.with_extension("tmp");
+    let mut content = String::new();
+    for record in records {
+        if let Ok(line) = serde_json::to_string(record) {
+            content.push_str(&line);
+            content.push('\n');
+        }
+    }
+    fs::write(&temp_path, content)?;
+    fs::rename(&temp_path, &path)
+}
+
+fn append_history(record: &OptimizationRecord) -> std::io::Result<()> {
+    let line = serde_json::to_string(record)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path())?;
+    writeln!(file, "{}", line)
+}
+
+/// Mark the next recorded run with `reason` as deferred
+///
+/// The scheduler calls this right before starting a held-back run and
+/// `clear_deferral` if the run did not happen, so the mark never sticks to
+/// an unrelated run.
+pub fn defer_next(reason: Reason, deferral: Deferral) {
+    *PENDING_DEFERRAL.lock() = Some((reason, deferral));
+}
+
+pub fn clear_deferral() {
+    PENDING_DEFERRAL.lock().take();
+}
+
+fn take_deferral(reason: Reason) -> Option<Deferral> {
+    let mut pending = PENDING_DEFERRAL.lock();
+    match *pending {
+        Some((r, deferral)) if r == reason => {
+            *pending = None;
+            Some(deferral)
+        }
+        _ => None,
+    }
+}
+
+/// Record a completed optimization
+pub fn record_optimization(result: &OptimizeResult, profile: &str) {
+    let record = OptimizationRecord {
+        timestamp: unix_now(),
+        reason: result.reason,
+        profile: profile.to_string(),
+        areas: result
+            .areas
+            .iter()
+            .map(|a| AreaRecord {
+                name: a.name.clone(),
+                duration_ms: a.duration_ms,
+                freed_bytes: a.freed_bytes,
+                error: a.error.clone(),
+                skipped: a.skipped.clone(),
+            })
+            .collect(),
+        freed_physical_bytes: result.freed_physical_bytes,
+        freed_commit_bytes: result.freed_commit_bytes,
+        duration_ms: result.duration_ms,
+        deferred: take_deferral(result.reason),
+        purge_impact: None,
+    };
+
+    let mut history = HISTORY.lock();
+    history.push(record.clone());
+
+    let write_result = if history.len() > MAX_RECORDS {
+        let excess = history.len() - COMPACT_TO;
+        history.drain(..excess);
+        rewrite_history(&history)
+    } else {
+        append_history(&record)
+    };
+
+    if let Err(e) = write_result {
+        tracing::warn!("Failed to persist optimization history: {}", e);
+    }
+}
+
+/// Merge records converted from another source (e.g. a legacy history file)
+///
+/// Records are ordered by timestamp and the file is rewritten. Returns the
+/// number of records imported.
+pub fn import_records(mut records: Vec<OptimizationRecord>) -> std::io::Result<usize> {
+    if records.is_empty() {
+        return Ok(0);
+    }
+    let imported = records.len();
+
+    let mut history = HISTORY.lock();
+    history.append(&mut records);
+    history.sort_by_key(|r| r.timestamp);
+    if history.len() > MAX_RECORDS {
+        let excess = history.len() - MAX_RECORDS;
+        history.drain(..excess);
+    }
+    rewrite_history(&history)?;
+
+    Ok(imported)
+}
+
+/// Store a purge impact measurement with the run it followed
+///
+/// The run is the oldest one recorded at or after `since` that has no
+/// measurement yet. Returns false when there is no such run.
+pub fn attach_purge_impact(since: u64, impact: PurgeImpact) -> bool {
+    let mut history = HISTORY.lock();
+    let record = match history
+        .iter_mut()
+        .find(|r| r.timestamp >= since && r.purge_impact.is_none())
+    {
+        Some(record) => record,
+        None => return false,
+    };
+    record.purge_impact = Some(impact);
+
+    if let Err(e) = rewrite_history(&history) {
+        tracing::warn!("Failed to persist purge impact: {}", e);
+    }
+    true
+}
+
+/// Run `f` over all records, oldest first, without copying them
+pub(crate) fn with_history<R>(f: impl FnOnce(&[OptimizationRecord]) -> R) -> R {
+    f(&HISTORY.lock())
+}
+
+/// The most recent optimization, `None` if the history is empty
+pub fn last_record() -> Option<OptimizationRecord> {
+    HISTORY.lock().last().cloned()
+}
+
+/// Unix timestamp of the last run in which the area (by display name) was optimized
+///
+/// Skipped areas and areas that failed do not count.
+pub fn last_area_run(name: &str) -> Option<u64> {
+    HISTORY
+        .lock()
+        .iter()
+        .rev()
+        .find(|r| {
+            r.areas
+                .iter()
+                .any(|a| a.name == name && a.error.is_none() && a.skipped.is_none())
+        })
+        .map(|r| r.timestamp)
+}
+
+/// Query the history with filtering and paging
+pub fn query_history(query: &HistoryQuery) -> HistoryPage {
+    filter_records(&HISTORY.lock(), query)
+}
+
+fn filter_records(records: &[OptimizationRecord], query: &HistoryQuery) -> HistoryPage {
+    let profile = query.profile.as_ref().map(|p| p.to_lowercase());
+    let matching: Vec<&OptimizationRecord> = records
+        .iter()
+        .rev()
+        .filter(|r| query.since.map_or(true, |s| r.timestamp >= s))
+        .filter(|r| query.until.map_or(true, |u| r.timestamp <= u))
+        .filter(|r| query.reason.map_or(true, |reason| r.reason == reason))
+        .filter(|r| {
+            profile
+                .as_ref()
+                .map_or(true, |p| r.profile.to_lowercase() == *p)
+        })
+        .collect();
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    HistoryPage {
+        total: matching.len(),
+        offset: query.offset,
+        records: matching
+            .into_iter()
+            .skip(query.offset)
+            .take(limit)
+            .cloned()
+            .collect(),
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(timestamp: u64, reason: Reason, profile: &str) -> OptimizationRecord {
+        OptimizationRecord {
+            timestamp,
+            reason,
+            profile: profile.to_string(),
+            areas: Vec::new(),
+            freed_physical_bytes: 0,
+            freed_commit_bytes: 0,
+            duration_ms: 0,
+            deferred: None,
+            purge_impact: None,
+        }
+    }
+
+    #[test]
+    fn test_filter_newest_first_with_paging() {
+        let records: Vec<_> = (0..10).map(|i| record(i, Reason::Manual, "Balanced")).collect();
+        let page = filter_records(
+            &records,
+            &HistoryQuery {
+                offset: 2,
+                limit: Some(3),
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total, 10);
+        let stamps: Vec<u64> = page.records.iter().map(|r| r.timestamp).collect();
+        assert_eq!(stamps, vec![7, 6, 5]);
+    }
+
+    #[test]
+    fn test_filter_by_reason_and_range() {
+        let records = vec![
+            record(100, Reason::Manual, "Gaming"),
+            record(200, Reason::Schedule, "Gaming"),
+            record(300, Reason::Schedule, "Normal"),
+        ];
+        let page = filter_records(
+            &records,
+            &HistoryQuery {
+                since: Some(150),
+                reason: Some(Reason::Schedule),
+                profile: Some("gaming".to_string()),
+                ..Default::default()
+            },
+        );
+        assert_eq!(page.total, 1);
+        assert_eq!(page.records[0].timestamp, 200);
+    }
+
+    #[test]
+    fn test_deferral_only_marks_matching_reason_once() {
+        defer_next(Reason::Schedule, Deferral::Locked);
+        assert_eq!(take_deferral(Reason::Manual), None);
+        assert_eq!(take_deferral(Reason::Schedule), Some(Deferral::Locked));
+        assert_eq!(take_deferral(Reason::Schedule), None);
+        assert_eq!(Deferral::Locked.to_string(), "deferred (locked)");
+    }
+}
@@ -0,0 +1,16 @@
+/// Persistent optimization statistics
+///
+/// Records every optimization run in the data directory so the UI can
+/// show history and charts of memory freed over time.
+pub mod adaptive;
+pub mod history;
+pub mod multi_user;
+pub mod prometheus;
+pub mod session;
+pub mod timeline;
+pub mod usage;
+
+pub use history::{
+    attach_purge_impact, import_records, last_area_run, last_record, query_history,
+    record_optimization, Deferral, HistoryPage, HistoryQuery, OptimizationRecord,
+};
@@ -0,0 +1,286 @@
+/// Machine-wide history report for administrators
+///
+/// On shared machines every Windows user has their own data directory. This
+/// module walks the user profiles, reads each optimization history it is
+/// allowed to open and aggregates them into a single report. Without
+/// elevation only the current user's history is normally readable; the other
+/// profiles are listed as unreadable instead of failing the whole report.
+use super::history::{parse_history, OptimizationRecord, HISTORY_FILE_NAME};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const APP_DIR_NAME: &str = "TommyMemoryCleaner";
+/// Profile folders that never belong to an interactive user
+const SKIPPED_PROFILES: &[&str] = &["all users", "default", "default user", "public"];
+
+/// Aggregated statistics over a set of history records
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct HistorySummary {
+    pub optimizations: usize,
+    /// Runs started by the auto optimizer (schedule, low memory, prediction)
+    pub automatic: usize,
+    pub freed_physical_bytes: i64,
+    pub freed_commit_bytes: i64,
+    pub average_freed_bytes: i64,
+    pub first_timestamp: Option<u64>,
+    pub last_timestamp: Option<u64>,
+    pub by_reason: BTreeMap<String, usize>,
+    pub by_profile: BTreeMap<String, usize>,
+}
+
+impl HistorySummary {
+    pub fn add(&mut self, record: &OptimizationRecord) {
+        self.optimizations += 1;
+        if record.reason.is_automatic() {
+            self.automatic += 1;
+        }
+        self.freed_physical_bytes += record.freed_physical_bytes;
+        self.freed_commit_bytes += record.freed_commit_bytes;
+        self.average_freed_bytes = self.freed_physical_bytes / self.optimizations as i64;
+        self.first_timestamp = Some(
+            self.first_timestamp
+                .map_or(record.timestamp, |t| t.min(record.timestamp)),
+        );
+        self.last_timestamp = Some(
+            self.last_timestamp
+                .map_or(record.timestamp, |t| t.max(record.timestamp)),
+        );
+        *self.by_reason.entry(record.reason.to_string()).or_default() += 1;
+        *self.by_profile.entry(record.profile.clone()).or_default() += 1;
+    }
+
+    pub fn from_records(records: &[OptimizationRecord]) -> Self {
+        let mut summary = Self::default();
+        for record in records {
+            summary.add(record);
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserStatus {
+    Ok,
+    /// The profile has no history (app never used by this user)
+    NoData,
+    AccessDenied,
+    Error,
+}
+
+/// History of a single user profile
+#[derive(Debug, Clone, Serialize)]
+pub struct UserReport {
+    pub user: String,
+    pub history_path: Option<PathBuf>,
+    pub status: UserStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<HistorySummary>,
+}
+
+/// Report across all user profiles of the machine
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineReport {
+    /// Unix timestamp (seconds)
+    pub generated_at: u64,
+    pub elevated: bool,
+    pub profiles_root: Option<PathBuf>,
+    pub users_scanned: usize,
+    pub users_with_data: usize,
+    pub users_unreadable: usize,
+    pub total: HistorySummary,
+    pub users: Vec<UserReport>,
+}
+
+/// Directory containing the user profiles (e.g. `C:\Users`)
+fn profiles_root() -> Option<PathBuf> {
+    if let Some(root) = dirs::home_dir().and_then(|home| home.parent().map(Path::to_path_buf)) {
+        return Some(root);
+    }
+    std::env::var("SystemDrive")
+        .ok()
+        .map(|drive| PathBuf::from(format!("{}\\", drive)).join("Users"))
+}
+
+/// Possible history locations inside one user profile, in lookup order
+fn history_candidates(profile_dir: &Path) -> Vec<PathBuf> {
+    let app_data = profile_dir.join("AppData");
+    vec![
+        app_data
+            .join("Local")
+            .join(APP_DIR_NAME)
+            .join(HISTORY_FILE_NAME),
+        app_data
+            .join("Roaming")
+            .join(APP_DIR_NAME)
+            .join(HISTORY_FILE_NAME),
+    ]
+}
+
+fn read_user(user: String, profile_dir: &Path) -> UserReport {
+    let mut report = UserReport {
+        user,
+        history_path: None,
+        status: UserStatus::NoData,
+        error: None,
+        summary: None,
+    };
+
+    for path in history_candidates(profile_dir) {
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let records = parse_history(&content);
+                report.summary = Some(HistorySummary::from_records(&records));
+                report.history_path = Some(path);
+                report.status = UserStatus::Ok;
+                report.error = None;
+                return report;
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => {
+                report.status = if e.kind() == io::ErrorKind::PermissionDenied {
+                    UserStatus::AccessDenied
+                } else {
+                    UserStatus::Error
+                };
+                report.error = Some(e.to_string());
+                report.history_path = Some(path);
+            }
+        }
+    }
+
+    // The profile directory itself may be closed to non-admins
+    if report.status == UserStatus::NoData {
+        if let Err(e) = fs::read_dir(profile_dir.join("AppData")) {
+            if e.kind() == io::ErrorKind::PermissionDenied {
+                report.status = UserStatus::AccessDenied;
+                report.error = Some(e.to_string());
+            }
+        }
+    }
+
+    report
+}
+
+/// Aggregate the optimization history of every user profile on the machine
+pub fn build_machine_report() -> MachineReport {
+    let root = profiles_root();
+    let mut users: Vec<UserReport> = Vec::new();
+
+    match root.as_ref().map(fs::read_dir) {
+        Some(Ok(entries)) => {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name().to_string_lossy().to_string();
+                if !path.is_dir() || SKIPPED_PROFILES.contains(&name.to_lowercase().as_str()) {
+                    continue;
+                }
+                users.push(read_user(name, &path));
+            }
+        }
+        Some(Err(e)) => tracing::warn!("Failed to list user profiles: {}", e),
+        None => tracing::warn!("User profiles directory not found"),
+    }
+    users.sort_by(|a, b| a.user.to_lowercase().cmp(&b.user.to_lowercase()));
+
+    let mut total = HistorySummary::default();
+    for user in &users {
+        if let Some(summary) = &user.summary {
+            merge(&mut total, summary);
+        }
+    }
+
+    MachineReport {
+        generated_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        elevated: crate::system::is_app_elevated(),
+        profiles_root: root,
+        users_scanned: users.len(),
+        users_with_data: users
+            .iter()
+            .filter(|u| u.summary.as_ref().is_some_and(|s| s.optimizations > 0))
+            .count(),
+        users_unreadable: users
+            .iter()
+            .filter(|u| matches!(u.status, UserStatus::AccessDenied | UserStatus::Error))
+            .count(),
+        total,
+        users,
+    }
+}
+
+fn merge(total: &mut HistorySummary, other: &HistorySummary) {
+    total.optimizations += other.optimizations;
+    total.automatic += other.automatic;
+    total.freed_physical_bytes += other.freed_physical_bytes;
+    total.freed_commit_bytes += other.freed_commit_bytes;
+    if total.optimizations > 0 {
+        total.average_freed_bytes = total.freed_physical_bytes / total.optimizations as i64;
+    }
+    total.first_timestamp = match (total.first_timestamp, other.first_timestamp) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    total.last_timestamp = match (total.last_timestamp, other.last_timestamp) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    };
+    for (reason, count) in &other.by_reason {
+        *total.by_reason.entry(reason.clone()).or_default() += count;
+    }
+    for (profile, count) in &other.by_profile {
+        *total.by_profile.entry(profile.clone()).or_default() += count;
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::types::Reason;
+
+    fn record(timestamp: u64, reason: Reason, freed: i64) -> OptimizationRecord {
+        OptimizationRecord {
+            timestamp,
+            reason,
+            profile: "Balanced".to_string(),
+            areas: Vec::new(),
+            freed_physical_bytes: freed,
+            freed_commit_bytes: 0,
+            duration_ms: 0,
+            deferred: None,
+            purge_impact: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_matches_single_summary() {
+        let alice = vec![
+            record(100, Reason::Manual, 300),
+            record(400, Reason::Schedule, 100),
+        ];
+        let bob = vec![record(50, Reason::LowMemory, 200)];
+
+        let mut total = HistorySummary::default();
+        merge(&mut total, &HistorySummary::from_records(&alice));
+        merge(&mut total, &HistorySummary::from_records(&bob));
+
+        let all: Vec<_> = alice.into_iter().chain(bob).collect();
+        assert_eq!(total, HistorySummary::from_records(&all));
+        assert_eq!(total.optimizations, 3);
+        assert_eq!(total.automatic, 2);
+        assert_eq!(total.average_freed_bytes, 200);
+        assert_eq!(
+            (total.first_timestamp, total.last_timestamp),
+            (Some(50), Some(400))
+        );
+    }
+}
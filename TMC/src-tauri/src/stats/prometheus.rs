@@ -0,0 +1,290 @@
+/// Prometheus metrics
+///
+/// Counters of the optimizations run since TMC started (runs per reason,
+/// freed bytes overall and per area, area failures), a histogram of their
+/// duration and gauges of the current memory state, rendered in the
+/// Prometheus text format for `GET /metrics` of the HTTP API. Counters only
+/// ever grow and restart from zero with the app, as Prometheus expects; a run
+/// during which memory use grew adds nothing to the freed bytes.
+use crate::engine::OptimizeResult;
+use crate::memory::types::MemoryInfo;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Upper bounds (seconds) of the optimization duration histogram buckets
+const DURATION_BUCKETS: [f64; 9] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+static COUNTERS: Lazy<Mutex<Counters>> = Lazy::new(|| Mutex::new(Counters::default()));
+
+#[derive(Debug, Default)]
+struct Counters {
+    runs: BTreeMap<String, u64>,
+    freed_physical_bytes: u64,
+    freed_commit_bytes: u64,
+    area_freed_bytes: BTreeMap<String, u64>,
+    area_failures: BTreeMap<String, u64>,
+    /// Cumulative count per bucket of `DURATION_BUCKETS`
+    duration_buckets: [u64; DURATION_BUCKETS.len()],
+    duration_sum_secs: f64,
+}
+
+/// Current memory state, read when the metrics are scraped
+#[derive(Debug, Default)]
+pub struct Gauges {
+    pub memory: Option<MemoryInfo>,
+    /// `None` without the privilege to read the memory lists
+    pub standby_bytes: Option<u64>,
+    pub modified_bytes: Option<u64>,
+    pub optimizing: bool,
+}
+
+impl Gauges {
+    pub fn read(engine: &crate::engine::Engine) -> Self {
+        let lists = crate::memory::ops::standby_breakdown().ok();
+        Self {
+            memory: engine.memory().ok(),
+            standby_bytes: lists.map(|l| l.total_bytes),
+            modified_bytes: lists.map(|l| l.modified_bytes),
+            optimizing: crate::is_optimization_running(),
+        }
+    }
+}
+
+impl Counters {
+    fn add(&mut self, result: &OptimizeResult) {
+        *self.runs.entry(format!("{:?}", result.reason)).or_default() += 1;
+        self.freed_physical_bytes += result.freed_physical_bytes.max(0) as u64;
+        self.freed_commit_bytes += result.freed_commit_bytes.max(0) as u64;
+        for area in &result.areas {
+            if area.error.is_some() {
+                *self.area_failures.entry(area.name.clone()).or_default() += 1;
+            } else if let Some(freed) = area.freed_bytes {
+                *self.area_freed_bytes.entry(area.name.clone()).or_default() += freed.max(0) as u64;
+            }
+        }
+
+        let secs = result.duration_ms as f64 / 1000.0;
+        for (count, bound) in self.duration_buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if secs <= bound {
+                *count += 1;
+            }
+        }
+        self.duration_sum_secs += secs;
+    }
+
+    fn run_count(&self) -> u64 {
+        self.runs.values().sum()
+    }
+}
+
+/// Count a completed optimization
+pub fn record(result: &OptimizeResult) {
+    if result.dry_run {
+        return;
+    }
+    COUNTERS.lock().add(result);
+}
+
+/// All metrics in the Prometheus text format
+pub fn render(gauges: &Gauges) -> String {
+    render_metrics(&COUNTERS.lock(), gauges)
+}
+
+fn render_metrics(counters: &Counters, gauges: &Gauges) -> String {
+    let mut out = String::new();
+
+    let by_label = [
+        (
+            "tmc_optimizations_total",
+            "Optimizations run since TMC started",
+            "reason",
+            &counters.runs,
+        ),
+        (
+            "tmc_area_freed_bytes_total",
+            "Free physical memory gained per memory area",
+            "area",
+            &counters.area_freed_bytes,
+        ),
+        (
+            "tmc_area_failures_total",
+            "Memory areas that failed during an optimization",
+            "area",
+            &counters.area_failures,
+        ),
+    ];
+    for (name, help, label, values) in by_label {
+        header(&mut out, name, "counter", help);
+        for (value, count) in values {
+            sample(&mut out, name, &[(label, value)], *count as f64);
+        }
+    }
+
+    let totals = [
+        (
+            "tmc_freed_physical_bytes_total",
+            "Free physical memory gained by optimizations",
+            counters.freed_physical_bytes,
+        ),
+        (
+            "tmc_freed_commit_bytes_total",
+            "Commit charge released by optimizations",
+            counters.freed_commit_bytes,
+        ),
+    ];
+    for (name, help, value) in totals {
+        header(&mut out, name, "counter", help);
+        sample(&mut out, name, &[], value as f64);
+    }
+
+    let name = "tmc_optimization_duration_seconds";
+    header(&mut out, name, "histogram", "Duration of optimizations");
+    let bucket = format!("{}_bucket", name);
+    for (count, bound) in counters.duration_buckets.iter().zip(DURATION_BUCKETS) {
+        sample(
+            &mut out,
+            &bucket,
+            &[("le", &bound.to_string())],
+            *count as f64,
+        );
+    }
+    let count = counters.run_count() as f64;
+    sample(&mut out, &bucket, &[("le", "+Inf")], count);
+    sample(
+        &mut out,
+        &format!("{}_sum", name),
+        &[],
+        counters.duration_sum_secs,
+    );
+    sample(&mut out, &format!("{}_count", name), &[], count);
+
+    let memory = gauges.memory.as_ref();
+    let current = [
+        (
+            "tmc_optimization_running",
+            "1 while an optimization is running",
+            Some(gauges.optimizing as u64),
+        ),
+        (
+            "tmc_physical_free_bytes",
+            "Free physical memory",
+            memory.map(|m| m.physical.free.bytes),
+        ),
+        (
+            "tmc_physical_total_bytes",
+            "Usable physical memory, installed minus hardware reserved",
+            memory.map(|m| m.physical.total.bytes),
+        ),
+        (
+            "tmc_physical_installed_bytes",
+            "Installed physical memory",
+            memory.map(|m| m.installed_bytes),
+        ),
+        (
+            "tmc_commit_used_bytes",
+            "Commit charge",
+            memory.map(|m| m.commit.used.bytes),
+        ),
+        (
+            "tmc_commit_limit_bytes",
+            "Commit limit",
+            memory.map(|m| m.commit.total.bytes),
+        ),
+        (
+            "tmc_memory_load_percent",
+            "Physical memory in use (percent)",
+            memory.map(|m| m.load_percent as u64),
+        ),
+        (
+            "tmc_standby_list_bytes",
+            "Memory on the standby list, all priorities",
+            gauges.standby_bytes,
+        ),
+        (
+            "tmc_modified_list_bytes",
+            "Memory on the modified page list",
+            gauges.modified_bytes,
+        ),
+    ];
+    // Values that could not be read are left out rather than reported as 0
+    for (name, help, value) in current {
+        if let Some(value) = value {
+            header(&mut out, name, "gauge", help);
+            sample(&mut out, name, &[], value as f64);
+        }
+    }
+
+    out
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, &str)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", value);
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counters_render() {
+        let result: OptimizeResult = serde_json::from_value(serde_json::json!({
+            "reason": "Schedule",
+            "duration_ms": 800,
+            "freed_physical_bytes": 4096,
+            "freed_commit_bytes": -10,
+            "areas": [
+                { "name": "Standby List", "duration_ms": 400, "freed_bytes": 4096 },
+                { "name": "Registry \"Cache\"", "duration_ms": 1, "error": "failed" },
+            ],
+        }))
+        .unwrap();
+        let mut counters = Counters::default();
+        counters.add(&result);
+        counters.add(&result);
+
+        let text = render_metrics(
+            &counters,
+            &Gauges {
+                standby_bytes: Some(7),
+                ..Default::default()
+            },
+        );
+        assert!(text.contains("tmc_optimizations_total{reason=\"Schedule\"} 2\n"));
+        assert!(text.contains("tmc_freed_physical_bytes_total 8192\n"));
+        // Memory use that grew is not subtracted from a counter
+        assert!(text.contains("tmc_freed_commit_bytes_total 0\n"));
+        assert!(text.contains("tmc_area_freed_bytes_total{area=\"Standby List\"} 8192\n"));
+        assert!(text.contains("tmc_area_failures_total{area=\"Registry \\\"Cache\\\"\"} 2\n"));
+        assert!(text.contains("tmc_optimization_duration_seconds_bucket{le=\"0.5\"} 0\n"));
+        assert!(text.contains("tmc_optimization_duration_seconds_bucket{le=\"1\"} 2\n"));
+        assert!(text.contains("tmc_optimization_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("tmc_optimization_duration_seconds_sum 1.6\n"));
+        assert!(text.contains("tmc_standby_list_bytes 7\n"));
+        assert!(!text.contains("tmc_physical_free_bytes"));
+    }
+}
@@ -0,0 +1,298 @@
+/// Summary of the current app session
+///
+/// Counts the optimizations and the memory they freed since the app
+/// started, and the highest RAM usage the tray updater saw. When the app
+/// exits (the Exit entry, closing the window without minimizing to the tray,
+/// or Windows ending the session) the summary is written to
+/// `last_session.json` and, with `session_summary_notification`, shown in a
+/// toast. Windows only tells top-level windows that the session ends, so a
+/// hidden one listens for WM_ENDSESSION.
+use crate::engine::OptimizeResult;
+use crate::memory::types::MemoryInfo;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager};
+
+pub const SESSION_FILE_NAME: &str = "last_session.json";
+
+static SESSION: Lazy<Mutex<SessionSummary>> =
+    Lazy::new(|| Mutex::new(SessionSummary::started(unix_now())));
+/// Summary of the session before this one, read once at startup
+static PREVIOUS: Lazy<Option<SessionSummary>> = Lazy::new(load_previous);
+/// The summary of this session was already written
+static FINISHED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSummary {
+    /// Unix timestamp (seconds) of the app start
+    pub started_at: u64,
+    /// Unix timestamp of the exit, `None` while the session runs
+    pub ended_at: Option<u64>,
+    pub optimizations: u32,
+    /// Free physical memory gained over all the optimizations
+    pub freed_bytes: u64,
+    /// Highest RAM usage seen (percent of the usable RAM)
+    pub peak_used_percent: u8,
+    pub peak_used_bytes: u64,
+    /// When the peak was seen, `None` before the first sample
+    pub peak_at: Option<u64>,
+}
+
+/// This session so far and the one before it
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionReport {
+    pub current: SessionSummary,
+    pub previous: Option<SessionSummary>,
+}
+
+impl SessionSummary {
+    fn started(now: u64) -> Self {
+        Self {
+            started_at: now,
+            ..Self::default()
+        }
+    }
+
+    fn add_run(&mut self, result: &OptimizeResult) {
+        self.optimizations = self.optimizations.saturating_add(1);
+        self.freed_bytes = self
+            .freed_bytes
+            .saturating_add(result.freed_physical_bytes.max(0) as u64);
+    }
+
+    fn add_sample(&mut self, used_percent: u8, used_bytes: u64, now: u64) {
+        if self.peak_at.is_none() || used_bytes > self.peak_used_bytes {
+            self.peak_used_percent = used_percent.min(100);
+            self.peak_used_bytes = used_bytes;
+            self.peak_at = Some(now);
+        }
+    }
+
+    /// Seconds the session lasted, up to now while it runs
+    pub fn duration_secs(&self) -> u64 {
+        self.ended_at
+            .unwrap_or_else(unix_now)
+            .saturating_sub(self.started_at)
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn session_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(SESSION_FILE_NAME)
+}
+
+fn load_previous() -> Option<SessionSummary> {
+    let content = fs::read_to_string(session_path()).ok()?;
+    serde_json::from_str(&content)
+        .map_err(|e| tracing::debug!("Ignoring corrupted session summary: {}", e))
+        .ok()
+}
+
+/// Start the session clock, read the previous summary and watch for the
+/// end of the Windows session
+pub fn start(app: &AppHandle) {
+    Lazy::force(&SESSION);
+    Lazy::force(&PREVIOUS);
+    watch_session_end(app.clone());
+}
+
+/// Count a finished optimization
+pub fn record_run(result: &OptimizeResult) {
+    SESSION.lock().add_run(result);
+}
+
+/// Track the RAM usage peak
+pub fn note_memory(mem: &MemoryInfo) {
+    SESSION.lock().add_sample(
+        mem.physical.used.percentage,
+        mem.physical.used.bytes,
+        unix_now(),
+    );
+}
+
+pub fn report() -> SessionReport {
+    SessionReport {
+        current: SESSION.lock().clone(),
+        previous: PREVIOUS.clone(),
+    }
+}
+
+/// Close the session: write the summary and, if `notify` and
+/// `session_summary_notification` are set, show it
+///
+/// Runs once, whichever exit path gets here first.
+pub fn finish(app: &AppHandle, notify: bool) {
+    if FINISHED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let summary = {
+        let mut session = SESSION.lock();
+        session.ended_at = Some(unix_now());
+        session.clone()
+    };
+    tracing::info!(
+        "Session summary: {} optimizations, {:.1} MB freed, peak RAM {}%",
+        summary.optimizations,
+        summary.freed_bytes as f64 / 1024.0 / 1024.0,
+        summary.peak_used_percent
+    );
+    write_summary(&summary);
+
+    let settings = app.try_state::<crate::AppState>().map(|s| {
+        s.cfg
+            .with(|c| (c.session_summary_notification, c.theme.clone()))
+    });
+    if let Some((true, theme)) = settings.filter(|_| notify) {
+        let title = crate::i18n::tr("TMC • Session summary");
+        let body = crate::i18n::tr_args(
+            "%d optimizations, %.1f MB freed\nPeak RAM usage: %d%",
+            &[
+                summary.optimizations.into(),
+                (summary.freed_bytes as f64 / 1024.0 / 1024.0).into(),
+                summary.peak_used_percent.into(),
+            ],
+        );
+        if let Err(e) = crate::notifications::show_windows_notification(app, &title, &body, &theme)
+        {
+            tracing::debug!("Failed to show session summary: {}", e);
+        }
+    }
+}
+
+fn write_summary(summary: &SessionSummary) {
+    let path = session_path();
+    let temp_path = path.with_extension("json.tmp");
+    let written = serde_json::to_string_pretty(summary)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(&temp_path, json))
+        .and_then(|_| fs::rename(&temp_path, &path));
+    if let Err(e) = written {
+        tracing::warn!("Failed to save session summary: {}", e);
+    }
+}
+
+/// App handle for the window procedure of the session end listener
+#[cfg(windows)]
+static SESSION_END_APP: once_cell::sync::OnceCell<AppHandle> = once_cell::sync::OnceCell::new();
+
+/// Hidden top-level window receiving WM_QUERYENDSESSION and WM_ENDSESSION
+///
+/// Message-only windows do not get the session end broadcast.
+#[cfg(windows)]
+fn watch_session_end(app: AppHandle) {
+    use crate::utils::to_wide;
+    use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+    use windows_sys::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, MSG, WM_ENDSESSION, WM_QUERYENDSESSION, WNDCLASSW, WS_OVERLAPPED,
+    };
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        match msg {
+            // Never hold the shutdown back
+            WM_QUERYENDSESSION => 1,
+            WM_ENDSESSION => {
+                // The process may be terminated as soon as this returns
+                if wparam != 0 {
+                    if let Some(app) = SESSION_END_APP.get() {
+                        finish(app, false);
+                    }
+                }
+                0
+            }
+            _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+        }
+    }
+
+    if SESSION_END_APP.set(app).is_err() {
+        return;
+    }
+    let spawned = std::thread::Builder::new()
+        .name("tmc-session-end".into())
+        .spawn(|| unsafe {
+            let class_name = to_wide("TMCSessionEndListener");
+            let instance = GetModuleHandleW(std::ptr::null());
+            let mut class: WNDCLASSW = std::mem::zeroed();
+            class.lpfnWndProc = Some(window_proc);
+            class.hInstance = instance;
+            class.lpszClassName = class_name.as_ptr();
+            if RegisterClassW(&class) == 0 {
+                tracing::warn!(
+                    "Failed to register the session end window: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+            let hwnd = CreateWindowExW(
+                0,
+                class_name.as_ptr(),
+                class_name.as_ptr(),
+                WS_OVERLAPPED,
+                0,
+                0,
+                0,
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                tracing::warn!(
+                    "Failed to create the session end window: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+
+            let mut msg: MSG = std::mem::zeroed();
+            while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+                TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+    if let Err(e) = spawned {
+        tracing::error!("Failed to start session end listener thread: {}", e);
+    }
+}
+
+#[cfg(not(windows))]
+fn watch_session_end(_app: AppHandle) {}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peak_keeps_highest_usage() {
+        let mut session = SessionSummary::started(100);
+        session.add_sample(40, 4_000, 110);
+        session.add_sample(75, 7_500, 120);
+        session.add_sample(60, 6_000, 130);
+
+        assert_eq!(session.peak_used_percent, 75);
+        assert_eq!(session.peak_used_bytes, 7_500);
+        assert_eq!(session.peak_at, Some(120));
+    }
+}
@@ -0,0 +1,270 @@
+/// Memory composition timeline
+///
+/// A background sampler records how physical memory is split (in use,
+/// modified, standby, free, compressed) every `timeline_interval_secs` into
+/// a ring buffer capped at `MAX_BUFFER_BYTES`, so the UI can graph memory
+/// behavior around optimizations. With `timeline_persist` the buffer is
+/// saved to `memory_timeline.json` and the previous session is reloaded at
+/// startup.
+use crate::config::{Config, SharedConfig};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const TIMELINE_FILE_NAME: &str = "memory_timeline.json";
+/// Upper bound for the samples kept in memory (about 43k samples)
+const MAX_BUFFER_BYTES: usize = 2 * 1024 * 1024;
+const MAX_SAMPLES: usize = MAX_BUFFER_BYTES / size_of::<CompositionSample>();
+/// How often the buffer is written to disk while `timeline_persist` is on
+const PERSIST_EVERY: Duration = Duration::from_secs(300);
+const DEFAULT_MAX_POINTS: usize = 600;
+const MAX_POINTS: usize = 5000;
+
+static TIMELINE_RUNNING: AtomicBool = AtomicBool::new(false);
+static TIMELINE: Lazy<Mutex<VecDeque<CompositionSample>>> =
+    Lazy::new(|| Mutex::new(VecDeque::new()));
+
+/// Physical memory split at one point in time, in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompositionSample {
+    /// Unix timestamp (seconds)
+    pub timestamp: u64,
+    /// Used by processes and the system, without the compression store
+    pub in_use: u64,
+    pub modified: u64,
+    pub standby: u64,
+    /// Free and zeroed pages
+    pub free: u64,
+    /// Physical size of the memory compression store
+    pub compressed: u64,
+}
+
+/// Time range and resolution for `query_timeline`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TimelineRange {
+    /// Only samples at or after this unix timestamp
+    pub since: Option<u64>,
+    /// Only samples at or before this unix timestamp
+    pub until: Option<u64>,
+    /// Average neighbouring samples down to at most this many points
+    pub max_points: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MemoryTimeline {
+    pub interval_secs: u32,
+    /// Samples before averaging, in the requested range
+    pub total_samples: usize,
+    pub samples: Vec<CompositionSample>,
+}
+
+fn timeline_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(TIMELINE_FILE_NAME)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Take one sample of the current memory composition
+///
+/// Without the privilege to read the memory lists only in use and free are
+/// known, standby and modified are then reported as 0.
+fn sample() -> anyhow::Result<CompositionSample> {
+    let info = crate::memory::ops::memory_info()?;
+    let total = info.physical.total.bytes;
+    let compressed = crate::memory::ops::compression_info()
+        .map(|c| c.compressed_bytes)
+        .unwrap_or(0);
+
+    let (modified, standby, free) = match crate::memory::ops::standby_breakdown() {
+        Ok(b) => (b.modified_bytes, b.total_bytes, b.free_bytes + b.zero_bytes),
+        Err(_) => (0, 0, info.physical.free.bytes),
+    };
+    let in_use = total
+        .saturating_sub(modified)
+        .saturating_sub(standby)
+        .saturating_sub(free)
+        .saturating_sub(compressed);
+
+    Ok(CompositionSample {
+        timestamp: unix_now(),
+        in_use,
+        modified,
+        standby,
+        free,
+        compressed,
+    })
+}
+
+fn push_sample(buffer: &mut VecDeque<CompositionSample>, sample: CompositionSample) {
+    while buffer.len() >= MAX_SAMPLES {
+        buffer.pop_front();
+    }
+    buffer.push_back(sample);
+}
+
+/// Samples in the range, averaged down to `max_points`
+pub fn query_timeline(range: &TimelineRange, interval_secs: u32) -> MemoryTimeline {
+    let buffer = TIMELINE.lock();
+    let matching: Vec<CompositionSample> = buffer
+        .iter()
+        .filter(|s| range.since.map_or(true, |since| s.timestamp >= since))
+        .filter(|s| range.until.map_or(true, |until| s.timestamp <= until))
+        .copied()
+        .collect();
+    drop(buffer);
+
+    let max_points = range
+        .max_points
+        .unwrap_or(DEFAULT_MAX_POINTS)
+        .clamp(1, MAX_POINTS);
+    MemoryTimeline {
+        interval_secs,
+        total_samples: matching.len(),
+        samples: downsample(&matching, max_points),
+    }
+}
+
+/// Average consecutive samples so that at most `max_points` remain
+fn downsample(samples: &[CompositionSample], max_points: usize) -> Vec<CompositionSample> {
+    if samples.len() <= max_points {
+        return samples.to_vec();
+    }
+    let bucket = samples.len().div_ceil(max_points);
+    samples
+        .chunks(bucket)
+        .map(|chunk| {
+            let n = chunk.len() as u64;
+            let avg = |f: fn(&CompositionSample) -> u64| chunk.iter().map(f).sum::<u64>() / n;
+            CompositionSample {
+                // Bucket start, so markers line up with the first sample
+                timestamp: chunk[0].timestamp,
+                in_use: avg(|s| s.in_use),
+                modified: avg(|s| s.modified),
+                standby: avg(|s| s.standby),
+                free: avg(|s| s.free),
+                compressed: avg(|s| s.compressed),
+            }
+        })
+        .collect()
+}
+
+fn load_session() {
+    let content = match fs::read_to_string(timeline_path()) {
+        Ok(c) => c,
+        Err(_) => return,
+    };
+    match serde_json::from_str::<Vec<CompositionSample>>(&content) {
+        Ok(samples) => {
+            let mut buffer = TIMELINE.lock();
+            for s in samples {
+                push_sample(&mut buffer, s);
+            }
+            tracing::debug!("Loaded {} memory timeline samples", buffer.len());
+        }
+        Err(e) => tracing::debug!("Ignoring corrupted memory timeline: {}", e),
+    }
+}
+
+/// Write the buffer to disk if `timeline_persist` is on
+pub fn save_session(cfg: &Config) {
+    if cfg.timeline_persist {
+        write_session();
+    }
+}
+
+fn write_session() {
+    let samples: Vec<CompositionSample> = TIMELINE.lock().iter().copied().collect();
+    let path = timeline_path();
+    let temp_path = path.with_extension("json.tmp");
+    let written = serde_json::to_string(&samples)
+        .map_err(std::io::Error::from)
+        .and_then(|json| fs::write(&temp_path, json))
+        .and_then(|_| fs::rename(&temp_path, &path));
+    if let Err(e) = written {
+        tracing::debug!("Failed to save memory timeline: {}", e);
+    }
+}
+
+/// Start the sampler if it is not already running
+pub fn start_timeline_sampler(cfg: SharedConfig) {
+    if TIMELINE_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("Memory timeline sampler already running");
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-memory-timeline".into())
+        .spawn(move || {
+            let settings =
+                |cfg: &SharedConfig| cfg.with(|c| (c.timeline_interval_secs, c.timeline_persist));
+            if settings(&cfg).1 {
+                load_session();
+            }
+
+            let mut since_persist = Duration::ZERO;
+            loop {
+                let (interval_secs, persist) = settings(&cfg);
+                match sample() {
+                    Ok(s) => push_sample(&mut TIMELINE.lock(), s),
+                    Err(e) => tracing::debug!("Memory timeline sample failed: {}", e),
+                }
+
+                let interval = Duration::from_secs(interval_secs.max(1) as u64);
+                since_persist += interval;
+                if persist && since_persist >= PERSIST_EVERY {
+                    since_persist = Duration::ZERO;
+                    write_session();
+                }
+                std::thread::sleep(interval);
+            }
+        });
+
+    if let Err(e) = spawned {
+        TIMELINE_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start memory timeline thread: {}", e);
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_at(timestamp: u64, in_use: u64) -> CompositionSample {
+        CompositionSample {
+            timestamp,
+            in_use,
+            modified: 0,
+            standby: 100,
+            free: 50,
+            compressed: 0,
+        }
+    }
+
+    #[test]
+    fn test_downsample_averages_buckets() {
+        let samples: Vec<_> = (0..10).map(|i| sample_at(i * 5, i * 10)).collect();
+        assert_eq!(downsample(&samples, 20), samples);
+
+        let points = downsample(&samples, 4);
+        // Buckets of 3, the last one holds the remaining sample
+        assert_eq!(points.len(), 4);
+        assert_eq!(points[0].timestamp, 0);
+        assert_eq!(points[0].in_use, 10);
+        assert_eq!(points[1].in_use, 40);
+        assert_eq!(points[3], sample_at(45, 90));
+    }
+}
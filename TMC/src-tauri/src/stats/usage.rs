@@ -0,0 +1,301 @@
+/// Local usage metrics
+///
+/// Counts optimizations per reason with the memory they freed, what started
+/// them (app, tray, hotkey, automation) and how often a few features are
+/// used, in `usage_metrics.json` in the data directory. Nothing is ever sent
+/// anywhere: `usage_report` turns the counters into an anonymized summary the
+/// user can export and attach to an issue, to help debugging and tuning the
+/// defaults. Turned off with `usage_metrics` in the config.
+use crate::config::Config;
+use crate::engine::OptimizeResult;
+use crate::memory::types::Reason;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const USAGE_FILE_NAME: &str = "usage_metrics.json";
+/// Bumped when the report layout changes
+const REPORT_VERSION: u32 = 1;
+
+const MB: f64 = 1024.0 * 1024.0;
+
+/// What started an optimization
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// Button in the main window
+    App,
+    Tray,
+    Hotkey,
+    /// Schedules, low memory, prediction
+    Auto,
+    /// SDK pipe
+    External,
+    Cli,
+}
+
+impl Trigger {
+    /// Trigger implied by the reason; the tray is told apart with `mark_next_from_tray`
+    pub fn from_reason(reason: Reason) -> Self {
+        match reason {
+            Reason::Manual => Trigger::App,
+            Reason::Hotkey => Trigger::Hotkey,
+            Reason::External => Trigger::External,
+            Reason::Schedule | Reason::LowMemory | Reason::Predicted | Reason::Emergency => {
+                Trigger::Auto
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    DryRun,
+    /// Working set trim of selected processes
+    TargetedRun,
+    PurgeImpact,
+    ProfileSwitch,
+    /// Auto-optimizer paused or resumed
+    AutomationToggle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasonUsage {
+    pub runs: u64,
+    pub freed_bytes: i64,
+}
+
+/// Counters as stored on disk
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UsageMetrics {
+    /// Unix timestamp of the first counted event
+    pub since: u64,
+    /// Keyed by `Reason`
+    pub runs: BTreeMap<String, ReasonUsage>,
+    pub triggers: BTreeMap<Trigger, u64>,
+    pub features: BTreeMap<Feature, u64>,
+    /// Failures per memory area
+    pub area_errors: BTreeMap<String, u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReasonSummary {
+    pub runs: u64,
+    pub avg_freed_mb: f64,
+}
+
+/// Anonymized summary for bug reports
+///
+/// Holds no timestamps, paths, profile or process names: only the app
+/// version, the Windows build and the counters.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UsageReport {
+    pub report_version: u32,
+    pub app_version: &'static str,
+    pub windows_build: u32,
+    /// Whole days since the first counted event
+    pub days_covered: u64,
+    pub optimizations: BTreeMap<String, ReasonSummary>,
+    pub triggers: BTreeMap<Trigger, u64>,
+    pub features: BTreeMap<Feature, u64>,
+    pub area_errors: BTreeMap<String, u64>,
+}
+
+static METRICS: Lazy<Mutex<UsageMetrics>> = Lazy::new(|| Mutex::new(load_metrics()));
+
+/// Set by the tray right before it starts a run
+static NEXT_FROM_TRAY: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
+
+fn metrics_path() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(USAGE_FILE_NAME)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn load_metrics() -> UsageMetrics {
+    match fs::read_to_string(metrics_path()) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::debug!("Ignoring corrupted usage metrics: {}", e);
+            UsageMetrics::default()
+        }),
+        Err(_) => UsageMetrics::default(),
+    }
+}
+
+fn save_metrics(metrics: &UsageMetrics) {
+    let path = metrics_path();
+    let temp_path = path.with_extension("tmp");
+    let result = serde_json::to_string(metrics)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+        .and_then(|content| fs::write(&temp_path, content))
+        .and_then(|_| fs::rename(&temp_path, &path));
+    if let Err(e) = result {
+        tracing::debug!("Failed to save usage metrics: {}", e);
+    }
+}
+
+fn update(cfg: &Config, change: impl FnOnce(&mut UsageMetrics)) {
+    if !cfg.usage_metrics {
+        return;
+    }
+    let mut metrics = METRICS.lock();
+    if metrics.since == 0 {
+        metrics.since = unix_now();
+    }
+    change(&mut metrics);
+    save_metrics(&metrics);
+}
+
+/// Count the next run as started from the tray menu
+pub fn mark_next_from_tray() {
+    *NEXT_FROM_TRAY.lock() = true;
+}
+
+impl UsageMetrics {
+    fn add_run(&mut self, result: &OptimizeResult, trigger: Trigger) {
+        let usage = self.runs.entry(format!("{:?}", result.reason)).or_default();
+        usage.runs += 1;
+        usage.freed_bytes = usage
+            .freed_bytes
+            .saturating_add(result.freed_physical_bytes);
+        *self.triggers.entry(trigger).or_default() += 1;
+        for area in result.areas.iter().filter(|a| a.error.is_some()) {
+            *self.area_errors.entry(area.name.clone()).or_default() += 1;
+        }
+    }
+
+    fn report(&self, now: u64) -> UsageReport {
+        let optimizations = self
+            .runs
+            .iter()
+            .map(|(reason, usage)| {
+                let avg_mb = if usage.runs > 0 {
+                    usage.freed_bytes as f64 / usage.runs as f64 / MB
+                } else {
+                    0.0
+                };
+                let summary = ReasonSummary {
+                    runs: usage.runs,
+                    avg_freed_mb: (avg_mb * 10.0).round() / 10.0,
+                };
+                (reason.clone(), summary)
+            })
+            .collect();
+        UsageReport {
+            report_version: REPORT_VERSION,
+            app_version: env!("CARGO_PKG_VERSION"),
+            windows_build: crate::os::win_version().version.build,
+            days_covered: if self.since > 0 {
+                now.saturating_sub(self.since) / 86_400
+            } else {
+                0
+            },
+            optimizations,
+            triggers: self.triggers.clone(),
+            features: self.features.clone(),
+            area_errors: self.area_errors.clone(),
+        }
+    }
+}
+
+/// Count a completed optimization; `trigger` overrides the one implied by the reason
+pub fn record_run(cfg: &Config, result: &OptimizeResult, trigger: Option<Trigger>) {
+    if result.dry_run {
+        return;
+    }
+    let from_tray = std::mem::take(&mut *NEXT_FROM_TRAY.lock());
+    let trigger = trigger.unwrap_or(match result.reason {
+        Reason::Manual if from_tray => Trigger::Tray,
+        reason => Trigger::from_reason(reason),
+    });
+    update(cfg, |m| m.add_run(result, trigger));
+}
+
+pub fn record_feature(cfg: &Config, feature: Feature) {
+    update(cfg, |m| *m.features.entry(feature).or_default() += 1);
+}
+
+pub fn usage_report() -> UsageReport {
+    METRICS.lock().report(unix_now())
+}
+
+/// Write the report as pretty JSON, adding the .json extension if missing
+pub fn export_report(path: &Path) -> anyhow::Result<PathBuf> {
+    let path = if path.extension().is_some() {
+        path.to_path_buf()
+    } else {
+        path.with_extension("json")
+    };
+    let content = serde_json::to_string_pretty(&usage_report())?;
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+/// Clear the counters and delete the file
+pub fn reset() {
+    *METRICS.lock() = UsageMetrics::default();
+    let _ = fs::remove_file(metrics_path());
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(reason: Reason, freed_mb: i64, failed_area: Option<&str>) -> OptimizeResult {
+        let areas: Vec<_> = failed_area
+            .map(|name| serde_json::json!({ "name": name, "duration_ms": 1, "error": "failed" }))
+            .into_iter()
+            .collect();
+        let result = serde_json::json!({
+            "reason": reason,
+            "duration_ms": 100,
+            "freed_physical_bytes": freed_mb * 1024 * 1024,
+            "freed_commit_bytes": 0,
+            "areas": areas,
+        });
+        serde_json::from_value(result).unwrap()
+    }
+
+    #[test]
+    fn test_report_averages_and_counts() {
+        let mut metrics = UsageMetrics {
+            since: 1_000,
+            ..Default::default()
+        };
+        metrics.add_run(&result(Reason::Manual, 100, None), Trigger::Tray);
+        metrics.add_run(&result(Reason::Manual, 51, None), Trigger::App);
+        metrics.add_run(
+            &result(Reason::LowMemory, 300, Some("Standby List")),
+            Trigger::Auto,
+        );
+
+        let report = metrics.report(1_000 + 3 * 86_400 + 5);
+        assert_eq!(report.days_covered, 3);
+        assert_eq!(
+            report.optimizations.get("Manual"),
+            Some(&ReasonSummary {
+                runs: 2,
+                avg_freed_mb: 75.5
+            })
+        );
+        assert_eq!(report.triggers.get(&Trigger::Tray), Some(&1));
+        assert_eq!(report.area_errors.get("Standby List"), Some(&1));
+
+        let json = serde_json::to_value(&report).unwrap_or_default();
+        assert_eq!(json["triggers"]["auto"], 1);
+    }
+}
@@ -0,0 +1,239 @@
+/// Size scan and deletion for the temp cleaner locations
+///
+/// A scan walks the same files a cleanup would delete and only adds up their
+/// size. Files that cannot be deleted (in use, access denied) are counted and
+/// skipped; folders are removed afterwards only if they ended up empty.
+use super::locations::{CleanLocation, TempCleanerOptions};
+use crate::ui::bridge::{overall_percent, ProgressEvent};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationReport {
+    pub location: CleanLocation,
+    pub path: Option<String>,
+    /// Files found by a scan or deleted by a cleanup
+    pub files: u64,
+    pub bytes: u64,
+    /// Files that could not be deleted or read
+    pub failed: u64,
+    /// Why the folder was left alone, see `CleanLocation::checked_root`
+    pub skipped: Option<String>,
+}
+
+impl LocationReport {
+    fn new(location: CleanLocation) -> Self {
+        Self {
+            location,
+            path: None,
+            files: 0,
+            bytes: 0,
+            failed: 0,
+            skipped: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageReport {
+    /// Sizes only, nothing was deleted
+    pub dry_run: bool,
+    pub locations: Vec<LocationReport>,
+    pub total_files: u64,
+    pub total_bytes: u64,
+    pub duration_ms: u64,
+}
+
+/// Size of what a cleanup would delete
+pub fn scan(options: &TempCleanerOptions) -> StorageReport {
+    run::<fn(ProgressEvent)>(options, true, None)
+}
+
+/// Delete the files of the enabled locations
+pub fn clean<F>(options: &TempCleanerOptions, progress: Option<F>) -> StorageReport
+where
+    F: FnMut(ProgressEvent),
+{
+    run(options, false, progress)
+}
+
+fn run<F>(options: &TempCleanerOptions, dry_run: bool, mut progress: Option<F>) -> StorageReport
+where
+    F: FnMut(ProgressEvent),
+{
+    let started = Instant::now();
+    let min_age = Duration::from_secs(options.min_age_hours as u64 * 3600);
+    let locations = options.selected();
+    let count = locations.len();
+    let mut walked: Vec<PathBuf> = Vec::new();
+    let mut reports = Vec::with_capacity(locations.len());
+
+    for (idx, location) in locations.into_iter().enumerate() {
+        if let Some(cb) = progress.as_mut() {
+            cb(ProgressEvent::AreaStarted {
+                index: idx as u8 + 1,
+                total: count as u8,
+                area: location.display_name().to_string(),
+            });
+        }
+        let mut report = LocationReport::new(location);
+        match location.path() {
+            Some(path) if path.is_dir() => {
+                report.path = Some(path.display().to_string());
+                match location.checked_root(&path) {
+                    // Running as SYSTEM, the user temp folder is the Windows one
+                    Ok(root) if walked.contains(&root) => {}
+                    Ok(root) => {
+                        walk(&root, min_age, dry_run, &mut report);
+                        walked.push(root);
+                    }
+                    Err(why) => {
+                        tracing::warn!("Not cleaning {:?}: {}", location, why);
+                        report.skipped = Some(why);
+                    }
+                }
+            }
+            _ => tracing::debug!("{:?} not found, nothing to clean", location),
+        }
+        if let Some(cb) = progress.as_mut() {
+            cb(ProgressEvent::AreaFinished {
+                area: location.display_name().to_string(),
+                freed_bytes: Some(report.bytes as i64),
+                skipped: report.path.is_none() || report.skipped.is_some(),
+                failed: report.failed > 0,
+            });
+            cb(ProgressEvent::Overall {
+                percent: overall_percent(idx + 1, count, 0),
+            });
+        }
+        reports.push(report);
+    }
+    if let Some(cb) = progress.as_mut() {
+        cb(ProgressEvent::Completed);
+    }
+
+    let report = StorageReport {
+        dry_run,
+        total_files: reports.iter().map(|r| r.files).sum(),
+        total_bytes: reports.iter().map(|r| r.bytes).sum(),
+        locations: reports,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+    tracing::info!(
+        "Temp cleaner {}: {} files, {} bytes in {}ms",
+        if dry_run { "scan" } else { "cleanup" },
+        report.total_files,
+        report.total_bytes,
+        report.duration_ms
+    );
+    report
+}
+
+/// Walk `root` without following links, deleting files older than `min_age`
+fn walk(root: &Path, min_age: Duration, dry_run: bool, report: &mut LocationReport) {
+    let now = SystemTime::now();
+    let is_old = |meta: &fs::Metadata| {
+        meta.modified()
+            .ok()
+            .and_then(|m| now.duration_since(m).ok())
+            .is_some_and(|age| age >= min_age)
+    };
+
+    let mut pending = vec![root.to_path_buf()];
+    // Old subfolders, parents before their children
+    let mut old_dirs = Vec::new();
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::debug!("Cannot read {}: {}", dir.display(), e);
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            // symlink_metadata does not follow symlinks and junctions
+            let meta = match fs::symlink_metadata(&path) {
+                Ok(meta) => meta,
+                Err(_) => {
+                    report.failed += 1;
+                    continue;
+                }
+            };
+            if is_link(&meta) {
+                continue;
+            }
+            if meta.is_dir() {
+                if is_old(&meta) {
+                    old_dirs.push(path.clone());
+                }
+                pending.push(path);
+                continue;
+            }
+            if !is_old(&meta) {
+                continue;
+            }
+            if dry_run || fs::remove_file(&path).is_ok() {
+                report.files += 1;
+                report.bytes += meta.len();
+            } else {
+                report.failed += 1;
+            }
+        }
+    }
+
+    if !dry_run {
+        // Children first; folders that still hold files are left in place
+        for dir in old_dirs.iter().rev() {
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+#[cfg(windows)]
+fn is_link(meta: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_REPARSE_POINT: u32 = 0x400;
+    meta.file_type().is_symlink() || meta.file_attributes() & FILE_ATTRIBUTE_REPARSE_POINT != 0
+}
+
+#[cfg(not(windows))]
+fn is_link(meta: &fs::Metadata) -> bool {
+    meta.file_type().is_symlink()
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_walk_keeps_recent_files() {
+        let root = std::env::temp_dir().join(format!("tmc-cleaner-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let sub_dir = root.join("sub");
+        fs::create_dir_all(&sub_dir).unwrap();
+        fs::write(sub_dir.join("a.tmp"), [0u8; 100]).unwrap();
+        fs::write(root.join("b.tmp"), [0u8; 10]).unwrap();
+
+        // Everything was just written, an hour of minimum age keeps it all
+        let mut recent = LocationReport::new(CleanLocation::UserTemp);
+        walk(&root, Duration::from_secs(3600), false, &mut recent);
+        assert_eq!((recent.files, recent.bytes), (0, 0));
+
+        let mut scanned = LocationReport::new(CleanLocation::UserTemp);
+        walk(&root, Duration::ZERO, true, &mut scanned);
+        assert_eq!((scanned.files, scanned.bytes), (2, 110));
+        assert!(sub_dir.join("a.tmp").exists());
+
+        let mut cleaned = LocationReport::new(CleanLocation::UserTemp);
+        walk(&root, Duration::ZERO, false, &mut cleaned);
+        assert_eq!((cleaned.files, cleaned.bytes, cleaned.failed), (2, 110, 0));
+        assert!(!sub_dir.exists());
+        assert!(root.exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
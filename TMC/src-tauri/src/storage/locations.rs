@@ -0,0 +1,209 @@
+/// Locations the temp cleaner may empty, and which of them are enabled
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Upper bound for `min_age_hours` (30 days)
+pub const MAX_MIN_AGE_HOURS: u32 = 24 * 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CleanLocation {
+    /// `%SystemRoot%\Temp`, most files there need administrator rights
+    WindowsTemp,
+    /// `%TEMP%` of the current user
+    UserTemp,
+    /// `%LOCALAPPDATA%\D3DSCache`, rebuilt by games on their next start
+    #[serde(rename = "directx_shader_cache")]
+    DirectXShaderCache,
+}
+
+impl CleanLocation {
+    pub const ALL: [CleanLocation; 3] = [
+        CleanLocation::WindowsTemp,
+        CleanLocation::UserTemp,
+        CleanLocation::DirectXShaderCache,
+    ];
+
+    /// Progress step name, translated by the frontend
+    pub fn display_name(self) -> &'static str {
+        match self {
+            CleanLocation::WindowsTemp => "Windows Temp",
+            CleanLocation::UserTemp => "User Temp",
+            CleanLocation::DirectXShaderCache => "DirectX Shader Cache",
+        }
+    }
+
+    /// Folder of the location, `None` if it cannot be resolved
+    pub fn path(self) -> Option<PathBuf> {
+        match self {
+            CleanLocation::WindowsTemp => std::env::var_os("SystemRoot")
+                .or_else(|| std::env::var_os("windir"))
+                .map(|root| PathBuf::from(root).join("Temp")),
+            CleanLocation::UserTemp => Some(std::env::temp_dir()),
+            CleanLocation::DirectXShaderCache => {
+                dirs::data_local_dir().map(|dir| dir.join("D3DSCache"))
+            }
+        }
+    }
+
+    /// Names the folder of the location may have
+    fn folder_names(self) -> &'static [&'static str] {
+        match self {
+            CleanLocation::WindowsTemp | CleanLocation::UserTemp => &["Temp", "Tmp"],
+            CleanLocation::DirectXShaderCache => &["D3DSCache"],
+        }
+    }
+
+    /// Canonical folder to clean, or why `path` must not be cleaned
+    ///
+    /// `%TEMP%` can point anywhere: GetTempPath falls back to the profile or
+    /// the Windows folder when TMP and TEMP are unset, and users sometimes set
+    /// it to a drive root. Everything old under the folder gets deleted, so
+    /// only a folder with the expected name that is not, and does not contain,
+    /// a system folder is accepted.
+    pub fn checked_root(self, path: &Path) -> Result<PathBuf, String> {
+        let root = path
+            .canonicalize()
+            .map_err(|e| format!("Cannot resolve {}: {}", path.display(), e))?;
+
+        if root.parent().is_none() {
+            return Err(format!("{} is a drive root", root.display()));
+        }
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if !self
+            .folder_names()
+            .iter()
+            .any(|expected| name.eq_ignore_ascii_case(expected))
+        {
+            return Err(format!(
+                "{} is not a {} folder",
+                root.display(),
+                self.folder_names().join(" or ")
+            ));
+        }
+
+        let lowered = lowercase(&root);
+        if let Some(folder) = protected_folders()
+            .iter()
+            .find(|folder| lowercase(folder).starts_with(&lowered))
+        {
+            return Err(format!(
+                "{} is or contains the system folder {}",
+                root.display(),
+                folder.display()
+            ));
+        }
+        Ok(root)
+    }
+}
+
+/// Profile, Windows and program folders, canonicalized
+fn protected_folders() -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = dirs::home_dir().into_iter().collect();
+    for var in [
+        "USERPROFILE",
+        "SystemRoot",
+        "windir",
+        "ProgramFiles",
+        "ProgramFiles(x86)",
+        "ProgramW6432",
+    ] {
+        if let Some(value) = std::env::var_os(var) {
+            folders.push(PathBuf::from(value));
+        }
+    }
+    folders
+        .into_iter()
+        .filter_map(|folder| folder.canonicalize().ok())
+        .collect()
+}
+
+/// Paths compare case-insensitively on Windows
+fn lowercase(path: &Path) -> PathBuf {
+    PathBuf::from(path.to_string_lossy().to_lowercase())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TempCleanerOptions {
+    /// Master switch, nothing is scanned or deleted while off
+    pub enabled: bool,
+    pub windows_temp: bool,
+    pub user_temp: bool,
+    pub shader_cache: bool,
+    /// Keep files modified in the last hours
+    pub min_age_hours: u32,
+}
+
+impl Default for TempCleanerOptions {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            windows_temp: true,
+            user_temp: true,
+            shader_cache: true,
+            min_age_hours: 24,
+        }
+    }
+}
+
+impl TempCleanerOptions {
+    pub fn validate(&mut self) {
+        self.min_age_hours = self.min_age_hours.min(MAX_MIN_AGE_HOURS);
+    }
+
+    pub fn is_selected(&self, location: CleanLocation) -> bool {
+        match location {
+            CleanLocation::WindowsTemp => self.windows_temp,
+            CleanLocation::UserTemp => self.user_temp,
+            CleanLocation::DirectXShaderCache => self.shader_cache,
+        }
+    }
+
+    /// Enabled locations, in cleaning order
+    pub fn selected(&self) -> Vec<CleanLocation> {
+        CleanLocation::ALL
+            .into_iter()
+            .filter(|l| self.is_selected(*l))
+            .collect()
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_refuses_roots_outside_temp_folders() {
+        let refused = [
+            Some(PathBuf::from(r"C:\")),
+            std::env::var_os("USERPROFILE").map(PathBuf::from),
+            dirs::home_dir(),
+            std::env::var_os("windir").map(PathBuf::from),
+        ];
+        for path in refused.into_iter().flatten() {
+            for location in CleanLocation::ALL {
+                assert!(
+                    location.checked_root(&path).is_err(),
+                    "{} accepted for {:?}",
+                    path.display(),
+                    location
+                );
+            }
+        }
+
+        let temp = std::env::temp_dir()
+            .join(format!("tmc-root-test-{}", std::process::id()))
+            .join("Temp");
+        std::fs::create_dir_all(&temp).unwrap();
+        assert!(CleanLocation::UserTemp.checked_root(&temp).is_ok());
+        assert!(CleanLocation::DirectXShaderCache
+            .checked_root(&temp)
+            .is_err());
+        std::fs::remove_dir_all(temp.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+/// Temporary file cleaner (opt-in)
+///
+/// Temporary files are not RAM, but they are the next thing people expect a
+/// cleaner sitting in the tray to take care of. The cleaner stays off until
+/// it is enabled in the settings, only works inside a fixed set of well-known
+/// locations and never follows links out of them. Files modified in the last
+/// `min_age_hours` are left alone, installers and running apps may still need
+/// them. Progress goes through the same events as an optimization, so the
+/// progress footer shows it without changes.
+pub mod cleaner;
+pub mod locations;
+
+pub use cleaner::{clean, scan, StorageReport};
+pub use locations::{CleanLocation, TempCleanerOptions};
+
+use tauri::AppHandle;
+
+/// Show the toast for a finished cleanup, if anything was deleted
+pub fn notify_cleaned(app: &AppHandle, report: &StorageReport, theme: &str) {
+    if report.total_files == 0 {
+        return;
+    }
+    let title = crate::i18n::tr("TMC • Temporary files cleaned");
+    let body = crate::i18n::tr_args(
+        "Deleted %s files, %.1f MB freed",
+        &[
+            report.total_files.into(),
+            (report.total_bytes as f64 / 1024.0 / 1024.0).into(),
+        ],
+    );
+    if let Err(e) = crate::notifications::show_windows_notification(app, &title, &body, theme) {
+        tracing::debug!("Failed to show cleanup notification: {}", e);
+    }
+}
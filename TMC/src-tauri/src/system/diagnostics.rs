@@ -0,0 +1,219 @@
+/// Self-test report
+///
+/// Runs every check the optimizer depends on and collects the outcome in one
+/// report the user can copy into a bug report. Unlike the setup wizard probes
+/// each privilege and each memory area is checked on its own, so the report
+/// shows exactly which piece is missing.
+use crate::config::OtherSessionsPolicy;
+use crate::memory::types::Areas;
+use crate::system::sessions::SessionInfo;
+use crate::system::setup_probe::ProbeStatus;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// Privileges acquired by the optimizer, in the order they are checked
+const PRIVILEGES: [&str; 3] = [
+    "SeDebugPrivilege",
+    "SeIncreaseQuotaPrivilege",
+    "SeProfileSingleProcessPrivilege",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticCheck {
+    /// Stable identifier, e.g. `privilege:SeDebugPrivilege` or `area:Standby List`
+    pub name: String,
+    pub status: ProbeStatus,
+    /// What was checked or what went wrong (not translated)
+    pub detail: String,
+}
+
+impl DiagnosticCheck {
+    fn new(name: impl Into<String>, status: ProbeStatus, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status,
+            detail: detail.into(),
+        }
+    }
+
+    fn from_result<E: std::fmt::Display>(
+        name: impl Into<String>,
+        result: Result<(), E>,
+        ok_detail: &str,
+    ) -> Self {
+        match result {
+            Ok(()) => Self::new(name, ProbeStatus::Pass, ok_detail),
+            Err(e) => Self::new(name, ProbeStatus::Fail, e.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsReport {
+    pub app_version: &'static str,
+    pub windows_version: crate::os::version::OsVersion,
+    pub elevated: bool,
+    pub checks: Vec<DiagnosticCheck>,
+    /// All Windows sessions, including those of other logged on users
+    pub sessions: Vec<SessionInfo>,
+}
+
+/// Run all the checks
+pub fn run_diagnostics(app: &AppHandle) -> DiagnosticsReport {
+    let elevated = crate::system::is_app_elevated();
+    let mut checks = vec![check_elevation(elevated)];
+    checks.extend(PRIVILEGES.iter().map(|p| check_privilege(p)));
+    checks.extend(check_areas());
+    checks.push(check_memory_list_command());
+    checks.push(check_advanced_syscalls());
+    checks.push(check_notifications());
+    checks.push(check_tray(app));
+    let sessions = crate::system::sessions::list_sessions();
+    checks.push(check_sessions(app, &sessions));
+
+    let report = DiagnosticsReport {
+        app_version: env!("CARGO_PKG_VERSION"),
+        windows_version: crate::os::win_version().version,
+        elevated,
+        checks,
+        sessions,
+    };
+    for check in &report.checks {
+        tracing::info!(
+            "Diagnostics {}: {:?} ({})",
+            check.name,
+            check.status,
+            check.detail
+        );
+    }
+    report
+}
+
+fn check_elevation(elevated: bool) -> DiagnosticCheck {
+    if elevated {
+        DiagnosticCheck::new("elevation", ProbeStatus::Pass, "Running as administrator")
+    } else {
+        // Only the working sets of the user's own processes can be trimmed
+        DiagnosticCheck::new(
+            "elevation",
+            ProbeStatus::Warning,
+            "Not running as administrator",
+        )
+    }
+}
+
+fn check_privilege(privilege: &str) -> DiagnosticCheck {
+    let result =
+        crate::memory::privileges::ensure_privileges(&[privilege]).map_err(|e| format!("{:#}", e));
+    DiagnosticCheck::from_result(format!("privilege:{}", privilege), result, "Acquired")
+}
+
+fn check_areas() -> Vec<DiagnosticCheck> {
+    let supported = crate::os::supported_areas(Areas::all());
+    Areas::all()
+        .iter()
+        .filter_map(|area| {
+            let name = area.get_names().into_iter().next()?;
+            let check = if supported.contains(area) {
+                DiagnosticCheck::new(format!("area:{}", name), ProbeStatus::Pass, "Available")
+            } else {
+                DiagnosticCheck::new(
+                    format!("area:{}", name),
+                    ProbeStatus::Warning,
+                    "Not supported on this Windows version",
+                )
+            };
+            Some(check)
+        })
+        .collect()
+}
+
+fn check_memory_list_command() -> DiagnosticCheck {
+    let result = crate::memory::ops::probe_memory_list_command().map_err(|e| format!("{:#}", e));
+    DiagnosticCheck::from_result(
+        "nt_set_system_information",
+        result,
+        "Memory list commands reach the kernel",
+    )
+}
+
+fn check_advanced_syscalls() -> DiagnosticCheck {
+    let capability = crate::memory::syscall_probe::probe();
+    if capability.enabled {
+        DiagnosticCheck::new("advanced_syscalls", ProbeStatus::Pass, &capability.detail)
+    } else {
+        // The areas fall back to the documented APIs, nothing is lost but the stealth
+        DiagnosticCheck::new(
+            "advanced_syscalls",
+            ProbeStatus::Warning,
+            format!("Disabled, documented APIs used: {}", capability.detail),
+        )
+    }
+}
+
+fn check_notifications() -> DiagnosticCheck {
+    if let Some(why) = crate::notifications::notifications_blocked() {
+        return DiagnosticCheck::new("notifications", ProbeStatus::Fail, why);
+    }
+    DiagnosticCheck::from_result(
+        "notifications",
+        crate::notifications::probe_toast_notifier(),
+        "Toast notifier available",
+    )
+}
+
+fn check_tray(app: &AppHandle) -> DiagnosticCheck {
+    if crate::ui::tray::find_tray(app).is_none() {
+        return DiagnosticCheck::new("tray", ProbeStatus::Fail, "Tray icon was not created");
+    }
+    if taskbar_present() {
+        DiagnosticCheck::new("tray", ProbeStatus::Pass, "Tray icon created")
+    } else {
+        // The icon is re-added when Explorer restarts and broadcasts TaskbarCreated
+        DiagnosticCheck::new(
+            "tray",
+            ProbeStatus::Warning,
+            "Tray icon created but the Explorer taskbar is not running",
+        )
+    }
+}
+
+fn check_sessions(app: &AppHandle, sessions: &[SessionInfo]) -> DiagnosticCheck {
+    let others = sessions.iter().filter(|s| s.is_other_user()).count();
+    if others == 0 {
+        return DiagnosticCheck::new("sessions", ProbeStatus::Pass, "No other user logged on");
+    }
+
+    let skipped = app
+        .try_state::<crate::AppState>()
+        .map(|state| {
+            state.cfg.read().other_sessions_policy == OtherSessionsPolicy::SkipOtherSessions
+        })
+        .unwrap_or(false);
+    if skipped {
+        return DiagnosticCheck::new(
+            "sessions",
+            ProbeStatus::Pass,
+            format!("{} other user session(s), skipped", others),
+        );
+    }
+    // Their processes page back in while those users work
+    DiagnosticCheck::new(
+        "sessions",
+        ProbeStatus::Warning,
+        format!("{} other user session(s), trimmed too", others),
+    )
+}
+
+#[cfg(windows)]
+fn taskbar_present() -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::FindWindowW;
+
+    let class: Vec<u16> = "Shell_TrayWnd\0".encode_utf16().collect();
+    unsafe { !FindWindowW(class.as_ptr(), std::ptr::null()).is_null() }
+}
+
+#[cfg(not(windows))]
+fn taskbar_present() -> bool {
+    true
+}
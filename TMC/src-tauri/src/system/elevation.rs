@@ -0,0 +1,103 @@
+/// Elevation on demand
+///
+/// TMC starts without administrator rights as well. In that degraded mode
+/// the monitoring, the scheduler and the Working Set trim of the user's own
+/// processes keep working, while the areas in `Areas::ELEVATED` are skipped
+/// and reported as such. The user can relaunch elevated at any time, and
+/// `request_elevation_on_startup` does it on its own at startup.
+use crate::memory::types::Areas;
+#[cfg(windows)]
+use crate::utils::to_wide;
+use anyhow::Result;
+use serde::Serialize;
+
+/// Skip reason of the areas and processes out of reach without elevation
+pub const NOT_ELEVATED_REASON: &str = "Requires administrator";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ElevationStatus {
+    pub elevated: bool,
+    /// Areas skipped until TMC runs elevated, empty when it does
+    pub unavailable_areas: Vec<&'static str>,
+}
+
+/// Areas that cannot run in this process
+pub fn unavailable_areas() -> Areas {
+    if crate::system::is_app_elevated() {
+        Areas::empty()
+    } else {
+        Areas::ELEVATED
+    }
+}
+
+pub fn status() -> ElevationStatus {
+    ElevationStatus {
+        elevated: crate::system::is_app_elevated(),
+        unavailable_areas: unavailable_areas().get_names(),
+    }
+}
+
+/// Start an elevated instance through the UAC prompt and exit this one
+///
+/// The command line is passed on, so `--minimized` survives the relaunch.
+/// Returns an error, and keeps this instance running, if the prompt was
+/// declined or the instance could not be started.
+#[cfg(windows)]
+pub fn relaunch_as_admin() -> Result<()> {
+    use windows_sys::Win32::Foundation::GetLastError;
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    /// The user dismissed the UAC prompt
+    const ERROR_CANCELLED: u32 = 1223;
+
+    let exe = std::env::current_exe()?;
+    let args = std::env::args()
+        .skip(1)
+        .map(|a| quote_arg(&a))
+        .collect::<Vec<_>>()
+        .join(" ");
+    tracing::info!("Relaunching as administrator: {} {}", exe.display(), args);
+
+    let verb = to_wide("runas");
+    let file = to_wide(&exe.to_string_lossy());
+    let params = to_wide(&args);
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            params.as_ptr(),
+            std::ptr::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+
+    // Values up to 32 are errors
+    if result as isize > 32 {
+        std::process::exit(0);
+    }
+    let error = unsafe { GetLastError() };
+    if error == ERROR_CANCELLED {
+        anyhow::bail!("The administrator prompt was declined");
+    }
+    anyhow::bail!(
+        "Failed to relaunch as administrator (code {}, error {})",
+        result as isize,
+        error
+    )
+}
+
+#[cfg(not(windows))]
+pub fn relaunch_as_admin() -> Result<()> {
+    anyhow::bail!("Elevation is only supported on Windows")
+}
+
+/// Quote one argument for the command line of the new instance
+#[cfg(windows)]
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    format!("\"{}\"", arg.replace('"', "\\\""))
+}
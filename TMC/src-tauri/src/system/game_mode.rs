@@ -0,0 +1,69 @@
+/// Windows Game Mode detection
+///
+/// Windows has no public API telling whether Game Mode is engaged for some
+/// other process. It engages for a game in the foreground while the Game
+/// Mode setting is on, so the setting is read from the registry (missing
+/// means on, the default since Windows 10 1903) and the shell is asked
+/// whether a Direct3D app runs full screen. The shell query lives here for
+/// the automatic Gaming profile and the deferred toasts as well.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+#[cfg(windows)]
+use windows_sys::Win32::UI::Shell::QUERY_USER_NOTIFICATION_STATE;
+
+const GAME_BAR_KEY: &str = "Software\\Microsoft\\GameBar";
+const GAME_MODE_VALUE: &str = "AutoGameModeEnabled";
+
+/// `game_mode_active` goes with every memory sample, the checks behind it
+/// run at most this often
+const ACTIVE_TTL: Duration = Duration::from_secs(5);
+
+static ACTIVE: Lazy<Mutex<Option<(Instant, bool)>>> = Lazy::new(|| Mutex::new(None));
+
+/// The Game Mode setting is on
+#[cfg(windows)]
+pub fn game_mode_enabled() -> bool {
+    crate::utils::read_user_dword(GAME_BAR_KEY, GAME_MODE_VALUE).map_or(true, |v| v != 0)
+}
+
+#[cfg(not(windows))]
+pub fn game_mode_enabled() -> bool {
+    false
+}
+
+/// Game Mode is on and a full screen Direct3D game is running
+pub fn game_mode_active() -> bool {
+    let mut cached = ACTIVE.lock();
+    if let Some((checked_at, active)) = *cached {
+        if checked_at.elapsed() < ACTIVE_TTL {
+            return active;
+        }
+    }
+    let active = game_mode_enabled() && fullscreen_d3d_running();
+    *cached = Some((Instant::now(), active));
+    active
+}
+
+/// Notification state of the shell (`QUNS_*`), `None` if it cannot be read
+#[cfg(windows)]
+pub fn notification_state() -> Option<QUERY_USER_NOTIFICATION_STATE> {
+    use windows_sys::Win32::UI::Shell::SHQueryUserNotificationState;
+
+    let mut state = 0;
+    // Any success HRESULT carries a valid state
+    (unsafe { SHQueryUserNotificationState(&mut state) } >= 0).then_some(state)
+}
+
+/// A Direct3D app runs full screen
+#[cfg(windows)]
+pub fn fullscreen_d3d_running() -> bool {
+    use windows_sys::Win32::UI::Shell::QUNS_RUNNING_D3D_FULL_SCREEN;
+
+    notification_state() == Some(QUNS_RUNNING_D3D_FULL_SCREEN)
+}
+
+#[cfg(not(windows))]
+pub fn fullscreen_d3d_running() -> bool {
+    false
+}
@@ -1,8 +1,17 @@
 // src-tauri/src/system/mod.rs
+pub mod power;
 pub mod priority;
+pub mod recommendation;
+pub mod diagnostics;
+pub mod setup_probe;
+pub mod supervisor;
+pub mod tweaks;
 pub mod startup;
 pub mod window;
 pub mod elevated_task;
+pub mod elevation;
+pub mod game_mode;
+pub mod sessions;
 
 /// Verifica se il processo corrente è eseguito con privilegi amministratore
 #[cfg(windows)]
@@ -0,0 +1,72 @@
+/// Power source detection
+///
+/// On laptops the auto optimizer can skip runs or switch to a lighter
+/// profile while the system is running on battery.
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PowerSource {
+    Ac,
+    Battery,
+    /// Desktop without battery or status not reported
+    Unknown,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PowerStatus {
+    pub source: PowerSource,
+    /// Remaining charge, `None` without a battery or when not reported
+    pub battery_percent: Option<u8>,
+    pub battery_saver: bool,
+    /// A system battery is present, i.e. a laptop or tablet
+    pub has_battery: bool,
+}
+
+impl PowerStatus {
+    pub fn on_battery(&self) -> bool {
+        self.source == PowerSource::Battery
+    }
+}
+
+#[cfg(windows)]
+pub fn power_status() -> PowerStatus {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        tracing::debug!("GetSystemPowerStatus failed");
+        return PowerStatus {
+            source: PowerSource::Unknown,
+            battery_percent: None,
+            battery_saver: false,
+            has_battery: false,
+        };
+    }
+
+    // BatteryFlag 128 = no system battery, 255 = unknown
+    let has_battery = status.BatteryFlag & 128 == 0 && status.BatteryFlag != 255;
+    let source = match status.ACLineStatus {
+        0 if has_battery => PowerSource::Battery,
+        1 => PowerSource::Ac,
+        _ => PowerSource::Unknown,
+    };
+
+    PowerStatus {
+        source,
+        battery_percent: (has_battery && status.BatteryLifePercent <= 100)
+            .then_some(status.BatteryLifePercent),
+        battery_saver: status.SystemStatusFlag & 1 != 0,
+        has_battery,
+    }
+}
+
+#[cfg(not(windows))]
+pub fn power_status() -> PowerStatus {
+    PowerStatus {
+        source: PowerSource::Unknown,
+        battery_percent: None,
+        battery_saver: false,
+        has_battery: false,
+    }
+}
@@ -0,0 +1,220 @@
+/// Hardware based settings recommendation for the setup wizard
+///
+/// Looks at the installed memory, whether the PC runs on a battery and
+/// whether it has a dedicated graphics card, and suggests a profile and the
+/// automatic optimization thresholds. A PC with little memory optimizes early
+/// when free memory runs low, one with plenty of memory only on a schedule.
+/// Every choice comes with an i18n key the wizard shows as the reason.
+use crate::config::{Config, Profile};
+use serde::{Deserialize, Serialize};
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HardwareSummary {
+    pub total_ram_bytes: u64,
+    /// A system battery is present
+    pub laptop: bool,
+    /// First dedicated graphics card, `None` with integrated graphics only
+    pub discrete_gpu: Option<String>,
+}
+
+/// Settings the wizard applies when the user accepts the recommendation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendedConfig {
+    pub profile: Profile,
+    /// 0 turns the low memory trigger off
+    pub auto_opt_free_threshold: u8,
+    /// 0 turns the interval off
+    pub auto_opt_interval_hours: u32,
+    pub skip_auto_opt_on_battery: bool,
+}
+
+impl RecommendedConfig {
+    pub fn apply(&self, cfg: &mut Config) {
+        cfg.profile = self.profile;
+        cfg.memory_areas = self.profile.get_memory_areas();
+        cfg.run_priority = self.profile.get_priority();
+        cfg.active_custom_profile = None;
+        cfg.auto_opt_free_threshold = self.auto_opt_free_threshold.min(100);
+        cfg.auto_opt_interval_hours = self.auto_opt_interval_hours.min(24);
+        cfg.skip_auto_opt_on_battery = self.skip_auto_opt_on_battery;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendedSettings {
+    pub hardware: HardwareSummary,
+    pub settings: RecommendedConfig,
+    /// i18n keys explaining the choices, in display order
+    pub rationale: Vec<&'static str>,
+}
+
+/// Detect the hardware and recommend settings for it
+pub fn recommend_for_this_pc() -> RecommendedSettings {
+    let hardware = HardwareSummary {
+        total_ram_bytes: crate::memory::ops::memory_info()
+            .map(|m| m.physical.total.bytes)
+            .unwrap_or(0),
+        laptop: crate::system::power::power_status().has_battery,
+        discrete_gpu: display_adapters().into_iter().find(|n| is_discrete_gpu(n)),
+    };
+    let recommended = recommend(hardware);
+    tracing::info!(
+        "Recommended settings for {} GB RAM, laptop {}, GPU {:?}: {:?}",
+        recommended.hardware.total_ram_bytes / GIB,
+        recommended.hardware.laptop,
+        recommended.hardware.discrete_gpu,
+        recommended.settings
+    );
+    recommended
+}
+
+fn recommend(hardware: HardwareSummary) -> RecommendedSettings {
+    let mut rationale = Vec::new();
+
+    // Windows reserves part of the RAM, an 8 GB PC reports about 7.8 GB
+    let ram_gb = hardware.total_ram_bytes / GIB;
+    let (auto_opt_free_threshold, auto_opt_interval_hours) = if ram_gb < 12 {
+        rationale.push("Little memory: optimize as soon as free memory drops below 35%");
+        (35, 2)
+    } else if ram_gb < 24 {
+        rationale.push("Average memory: optimize when free memory drops below 20%");
+        (20, 4)
+    } else if ram_gb < 48 {
+        rationale.push("Plenty of memory: optimize when free memory drops below 10%");
+        (10, 8)
+    } else {
+        rationale.push("Lots of memory: it rarely runs low, optimize on a schedule only");
+        (0, 12)
+    };
+
+    let profile = if hardware.discrete_gpu.is_some() {
+        rationale.push("Dedicated graphics card: the Gaming profile frees the most before a game");
+        Profile::Gaming
+    } else if hardware.laptop {
+        rationale.push("Laptop: the Normal profile keeps optimizations short");
+        Profile::Normal
+    } else {
+        rationale.push("Desktop: the Balanced profile also clears the file caches");
+        Profile::Balanced
+    };
+
+    if hardware.laptop {
+        rationale.push("Automatic optimizations pause on battery to save power");
+    }
+
+    RecommendedSettings {
+        settings: RecommendedConfig {
+            profile,
+            auto_opt_free_threshold,
+            auto_opt_interval_hours,
+            skip_auto_opt_on_battery: hardware.laptop,
+        },
+        hardware,
+        rationale,
+    }
+}
+
+/// Whether a display adapter name is a dedicated card
+///
+/// Every NVIDIA adapter is, AMD ones are when named Radeon RX or Pro (the
+/// integrated ones are "AMD Radeon(TM) Graphics" or "Radeon(TM) RX Vega"
+/// with a "(TM)" in between), Intel ones when named Arc with a model
+/// number (A770, B580; the integrated Arc has none).
+fn is_discrete_gpu(name: &str) -> bool {
+    let name = name.to_lowercase();
+    if name.contains("nvidia") || name.contains("radeon rx") || name.contains("radeon pro") {
+        return true;
+    }
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty() && *w != "tm")
+        .collect();
+    words.windows(2).any(|pair| {
+        let model = pair[1].as_bytes();
+        pair[0] == "arc"
+            && model.len() > 1
+            && matches!(model[0], b'a' | b'b')
+            && model[1..].iter().all(u8::is_ascii_digit)
+    })
+}
+
+/// Names of the display adapters, without duplicates
+#[cfg(windows)]
+fn display_adapters() -> Vec<String> {
+    use windows_sys::Win32::Graphics::Gdi::{EnumDisplayDevicesW, DISPLAY_DEVICEW};
+
+    let mut names: Vec<String> = Vec::new();
+    for index in 0.. {
+        let mut device: DISPLAY_DEVICEW = unsafe { std::mem::zeroed() };
+        device.cb = std::mem::size_of::<DISPLAY_DEVICEW>() as u32;
+        if unsafe { EnumDisplayDevicesW(std::ptr::null(), index, &mut device, 0) } == 0 {
+            break;
+        }
+        let len = device
+            .DeviceString
+            .iter()
+            .position(|&c| c == 0)
+            .unwrap_or(device.DeviceString.len());
+        let name = String::from_utf16_lossy(&device.DeviceString[..len]);
+        // Each adapter is listed once per output
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(not(windows))]
+fn display_adapters() -> Vec<String> {
+    Vec::new()
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discrete_gpu_names() {
+        assert!(is_discrete_gpu("NVIDIA GeForce RTX 4060 Laptop GPU"));
+        assert!(is_discrete_gpu("AMD Radeon RX 7800 XT"));
+        assert!(is_discrete_gpu("Intel(R) Arc(TM) A770 Graphics"));
+        assert!(!is_discrete_gpu("Intel(R) Arc(TM) Graphics"));
+        assert!(!is_discrete_gpu("Intel(R) Iris(R) Xe Graphics"));
+        assert!(!is_discrete_gpu("AMD Radeon(TM) Graphics"));
+        assert!(!is_discrete_gpu("AMD Radeon(TM) RX Vega 11 Graphics"));
+        assert!(!is_discrete_gpu("Microsoft Basic Display Adapter"));
+    }
+
+    #[test]
+    fn test_recommendation_by_hardware() {
+        let small_laptop = recommend(HardwareSummary {
+            total_ram_bytes: 8 * GIB - 200 * 1024 * 1024,
+            laptop: true,
+            discrete_gpu: None,
+        });
+        assert_eq!(
+            small_laptop.settings,
+            RecommendedConfig {
+                profile: Profile::Normal,
+                auto_opt_free_threshold: 35,
+                auto_opt_interval_hours: 2,
+                skip_auto_opt_on_battery: true,
+            }
+        );
+        assert_eq!(small_laptop.rationale.len(), 3);
+
+        // Schedule only
+        let big_desktop = recommend(HardwareSummary {
+            total_ram_bytes: 64 * GIB,
+            laptop: false,
+            discrete_gpu: Some("NVIDIA GeForce RTX 4090".to_string()),
+        });
+        assert_eq!(big_desktop.settings.profile, Profile::Gaming);
+        assert_eq!(big_desktop.settings.auto_opt_free_threshold, 0);
+        assert_eq!(big_desktop.settings.auto_opt_interval_hours, 12);
+        assert!(!big_desktop.settings.skip_auto_opt_on_battery);
+    }
+}
@@ -0,0 +1,172 @@
+/// Windows sessions on this machine
+///
+/// With fast user switching or Remote Desktop several users can be logged on
+/// at once, each in their own session. A Working Set trim started in one
+/// session reaches the processes of the others as well, and their apps page
+/// back in while they are being used. With `other_sessions_policy` set to
+/// `SkipOtherSessions` the trim leaves the processes of other user sessions
+/// alone. Session 0 holds the services and never counts as a user session.
+use serde::Serialize;
+
+/// Session of the services, never an interactive user
+const SERVICES_SESSION_ID: u32 = 0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    Active,
+    Connected,
+    /// The user is logged on but switched away or closed the RDP window
+    Disconnected,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionInfo {
+    pub id: u32,
+    /// Window station, e.g. `Console` or `RDP-Tcp#3`
+    pub station: String,
+    /// Logged on user, `None` for listener and service sessions
+    pub user: Option<String>,
+    pub state: SessionState,
+    /// The session TMC runs in
+    pub current: bool,
+}
+
+impl SessionInfo {
+    /// Session of another logged on user
+    pub fn is_other_user(&self) -> bool {
+        !self.current && self.id != SERVICES_SESSION_ID && self.user.is_some()
+    }
+}
+
+/// Session TMC runs in
+pub fn current_session_id() -> Option<u32> {
+    process_session_id(std::process::id())
+}
+
+/// Sessions of other logged on users
+pub fn other_user_sessions() -> Vec<SessionInfo> {
+    list_sessions()
+        .into_iter()
+        .filter(SessionInfo::is_other_user)
+        .collect()
+}
+
+/// Session whose processes alone may be trimmed, `None` when all may
+///
+/// Only set when skipping other sessions is requested and another user is
+/// actually logged on, so single-user machines keep the fast trim paths.
+pub fn trim_session_filter(skip_other_sessions: bool) -> Option<u32> {
+    if !skip_other_sessions {
+        return None;
+    }
+    let current = current_session_id()?;
+    if other_user_sessions().is_empty() {
+        return None;
+    }
+    Some(current)
+}
+
+/// Whether `pid` runs in a user session other than `current`
+pub fn in_other_user_session(pid: u32, current: u32) -> bool {
+    matches!(process_session_id(pid), Some(id) if id != current && id != SERVICES_SESSION_ID)
+}
+
+#[cfg(windows)]
+fn process_session_id(pid: u32) -> Option<u32> {
+    use windows_sys::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+
+    let mut session_id = 0u32;
+    let ok = unsafe { ProcessIdToSessionId(pid, &mut session_id) } != 0;
+    ok.then_some(session_id)
+}
+
+#[cfg(not(windows))]
+fn process_session_id(_pid: u32) -> Option<u32> {
+    None
+}
+
+/// All sessions, empty if they cannot be enumerated
+#[cfg(windows)]
+pub fn list_sessions() -> Vec<SessionInfo> {
+    use windows_sys::Win32::System::RemoteDesktop::{
+        WTSActive, WTSConnected, WTSDisconnected, WTSEnumerateSessionsW, WTSFreeMemory,
+        WTS_CURRENT_SERVER_HANDLE, WTS_SESSION_INFOW,
+    };
+
+    let current = current_session_id();
+    let mut buffer: *mut WTS_SESSION_INFOW = std::ptr::null_mut();
+    let mut count = 0u32;
+    unsafe {
+        if WTSEnumerateSessionsW(WTS_CURRENT_SERVER_HANDLE, 0, 1, &mut buffer, &mut count) == 0
+            || buffer.is_null()
+        {
+            tracing::debug!("WTSEnumerateSessionsW failed");
+            return Vec::new();
+        }
+
+        let sessions = std::slice::from_raw_parts(buffer, count as usize)
+            .iter()
+            .map(|s| SessionInfo {
+                id: s.SessionId,
+                station: wide_to_string(s.pWinStationName),
+                user: session_user(s.SessionId),
+                state: match s.State {
+                    WTSActive => SessionState::Active,
+                    WTSConnected => SessionState::Connected,
+                    WTSDisconnected => SessionState::Disconnected,
+                    _ => SessionState::Other,
+                },
+                current: Some(s.SessionId) == current,
+            })
+            .collect();
+        WTSFreeMemory(buffer as _);
+        sessions
+    }
+}
+
+#[cfg(not(windows))]
+pub fn list_sessions() -> Vec<SessionInfo> {
+    Vec::new()
+}
+
+/// User logged on to a session, `None` if nobody is
+#[cfg(windows)]
+fn session_user(session_id: u32) -> Option<String> {
+    use windows_sys::Win32::System::RemoteDesktop::{
+        WTSFreeMemory, WTSQuerySessionInformationW, WTSUserName, WTS_CURRENT_SERVER_HANDLE,
+    };
+
+    let mut buffer: *mut u16 = std::ptr::null_mut();
+    let mut len = 0u32;
+    unsafe {
+        if WTSQuerySessionInformationW(
+            WTS_CURRENT_SERVER_HANDLE,
+            session_id,
+            WTSUserName,
+            &mut buffer,
+            &mut len,
+        ) == 0
+            || buffer.is_null()
+        {
+            return None;
+        }
+        let user = wide_to_string(buffer);
+        WTSFreeMemory(buffer as _);
+        (!user.is_empty()).then_some(user)
+    }
+}
+
+/// Null-terminated UTF-16 string to `String`
+#[cfg(windows)]
+unsafe fn wide_to_string(ptr: *const u16) -> String {
+    if ptr.is_null() {
+        return String::new();
+    }
+    let mut len = 0;
+    while *ptr.add(len) != 0 {
+        len += 1;
+    }
+    String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len))
+}
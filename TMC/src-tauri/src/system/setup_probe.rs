@@ -0,0 +1,201 @@
+/// Setup wizard environment checks
+///
+/// The wizard runs the probes one step at a time and shows the outcome as it
+/// goes. Every probe exercises the real API (privilege adjustment, a memory
+/// list query, a hotkey registration, a file write) instead of guessing from
+/// the Windows version. `remediation` is an i18n key the wizard shows when a
+/// step did not pass.
+use crate::config::Config;
+use crate::memory::types::Areas;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Privileges the memory areas rely on
+const REQUIRED_PRIVILEGES: [&str; 2] = [
+    "SeProfileSingleProcessPrivilege",
+    "SeIncreaseQuotaPrivilege",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupProbeStep {
+    Privileges,
+    Areas,
+    Notifications,
+    Hotkey,
+    Startup,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProbeStatus {
+    Pass,
+    /// Works with limitations
+    Warning,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SetupProbeResult {
+    pub step: SetupProbeStep,
+    pub status: ProbeStatus,
+    /// What was checked or what went wrong (not translated)
+    pub detail: String,
+    /// i18n key of the fix to show, `None` when the step passed
+    pub remediation: Option<&'static str>,
+}
+
+impl SetupProbeResult {
+    fn pass(step: SetupProbeStep, detail: impl Into<String>) -> Self {
+        Self {
+            step,
+            status: ProbeStatus::Pass,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    fn warning(step: SetupProbeStep, detail: impl Into<String>, remediation: &'static str) -> Self {
+        Self {
+            step,
+            status: ProbeStatus::Warning,
+            detail: detail.into(),
+            remediation: Some(remediation),
+        }
+    }
+
+    fn fail(step: SetupProbeStep, detail: impl Into<String>, remediation: &'static str) -> Self {
+        Self {
+            step,
+            status: ProbeStatus::Fail,
+            detail: detail.into(),
+            remediation: Some(remediation),
+        }
+    }
+}
+
+/// Run the probe of one setup step
+pub fn run_probe(app: &AppHandle, cfg: &Config, step: SetupProbeStep) -> SetupProbeResult {
+    tracing::debug!("Running setup probe {:?}", step);
+    let result = match step {
+        SetupProbeStep::Privileges => probe_privileges(),
+        SetupProbeStep::Areas => probe_areas(cfg),
+        SetupProbeStep::Notifications => probe_notifications(),
+        SetupProbeStep::Hotkey => probe_hotkey(app, cfg),
+        SetupProbeStep::Startup => probe_startup(),
+    };
+    tracing::info!(
+        "Setup probe {:?}: {:?} ({})",
+        step,
+        result.status,
+        result.detail
+    );
+    result
+}
+
+fn probe_privileges() -> SetupProbeResult {
+    let step = SetupProbeStep::Privileges;
+    if !crate::system::is_app_elevated() {
+        return SetupProbeResult::fail(
+            step,
+            "Not running as administrator",
+            "Run as administrator to clean all memory areas",
+        );
+    }
+    match crate::memory::privileges::ensure_privileges(&REQUIRED_PRIVILEGES) {
+        Ok(()) => SetupProbeResult::pass(step, "Running as administrator"),
+        Err(e) => SetupProbeResult::fail(
+            step,
+            format!("{:#}", e),
+            "Your account lacks the privileges needed to clean memory",
+        ),
+    }
+}
+
+fn probe_areas(cfg: &Config) -> SetupProbeResult {
+    let step = SetupProbeStep::Areas;
+    // The memory lists cannot be read without the privileges the areas need
+    if let Err(e) = crate::memory::ops::standby_breakdown() {
+        return SetupProbeResult::fail(
+            step,
+            format!("{:#}", e),
+            "Run as administrator to clean all memory areas",
+        );
+    }
+
+    let unsupported = cfg.memory_areas & !crate::os::supported_areas(cfg.memory_areas);
+    if unsupported.is_empty() {
+        let available = crate::os::supported_areas(Areas::all());
+        SetupProbeResult::pass(step, format!("Available: {}", available))
+    } else {
+        SetupProbeResult::warning(
+            step,
+            format!("Not supported on this Windows version: {}", unsupported),
+            "Some memory areas are not supported on this Windows version and will be skipped",
+        )
+    }
+}
+
+fn probe_notifications() -> SetupProbeResult {
+    let step = SetupProbeStep::Notifications;
+    match crate::notifications::notifications_blocked() {
+        Some(why) => SetupProbeResult::fail(
+            step,
+            why,
+            "Turn on notifications for Tommy Memory Cleaner in Windows Settings",
+        ),
+        None => SetupProbeResult::pass(step, "Notifications are allowed"),
+    }
+}
+
+fn probe_hotkey(app: &AppHandle, cfg: &Config) -> SetupProbeResult {
+    let step = SetupProbeStep::Hotkey;
+    match crate::hotkeys::manager::probe_hotkey(app, &cfg.hotkey) {
+        Ok(()) => SetupProbeResult::pass(step, format!("{} is available", cfg.hotkey)),
+        Err(e) => SetupProbeResult::fail(
+            step,
+            format!("{}: {}", cfg.hotkey, e),
+            "The hotkey is used by another application, choose a different one",
+        ),
+    }
+}
+
+fn probe_startup() -> SetupProbeResult {
+    let step = SetupProbeStep::Startup;
+    let data_dir = crate::config::get_portable_detector().data_dir().clone();
+    if let Err(e) = probe_write(&data_dir) {
+        return SetupProbeResult::fail(
+            step,
+            format!("{}: {}", data_dir.display(), e),
+            "The data folder is not writable, settings cannot be saved",
+        );
+    }
+
+    let startup_dir = match dirs::data_dir() {
+        Some(dir) => dir.join(r"Microsoft\Windows\Start Menu\Programs\Startup"),
+        None => {
+            return SetupProbeResult::fail(
+                step,
+                "Startup folder not found",
+                "The Startup folder is not writable, starting with Windows is unavailable",
+            )
+        }
+    };
+    match probe_write(&startup_dir) {
+        Ok(()) => SetupProbeResult::pass(step, "Data and Startup folders are writable"),
+        Err(e) => SetupProbeResult::fail(
+            step,
+            format!("{}: {}", startup_dir.display(), e),
+            "The Startup folder is not writable, starting with Windows is unavailable",
+        ),
+    }
+}
+
+/// Create and delete a file in `dir`
+fn probe_write(dir: &Path) -> std::io::Result<()> {
+    let path = dir.join(".tmc-setup-probe");
+    fs::write(&path, b"probe")?;
+    fs::remove_file(&path)
+}
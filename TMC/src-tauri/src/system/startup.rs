@@ -5,7 +5,7 @@ use std::time::Duration;
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
-use crate::config::get_portable_detector;
+use crate::config::{get_portable_detector, StartupMethod};
 
 // FIX #19: Timeout per comandi di sistema (10 secondi)
 const SYSTEM_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
@@ -57,15 +57,40 @@ fn app_name() -> &'static str {
     "Tommy Memory Cleaner"
 }
 
-pub fn set_run_on_startup(enable: bool) -> Result<()> {
+/// Abilita o disabilita l'avvio automatico con il metodo scelto
+///
+/// Passando da un metodo all'altro la voce del metodo precedente viene rimossa,
+/// così l'app non parte due volte.
+pub fn set_run_on_startup(enable: bool, method: StartupMethod, delay_secs: u32) -> Result<()> {
     let detector = get_portable_detector();
 
+    if enable && method == StartupMethod::ScheduledTask {
+        // Task con privilegi più alti: l'app parte elevata senza prompt UAC
+        let exe = exe_path()?;
+        let exe_str = exe.to_string_lossy();
+        set_task_scheduler_startup(&exe_str, true, delay_secs, true)?;
+
+        // Migrazione: rimuovi Run key o collegamento usati finora
+        let removed = if detector.is_portable() {
+            set_portable_startup(false)
+        } else {
+            set_registry_startup(&exe_str, false)
+        };
+        if let Err(e) = removed {
+            tracing::warn!("Failed to remove the previous startup entry: {}", e);
+        }
+        return Ok(());
+    }
+
     if detector.is_portable() {
         // Versione portable: usa shortcut nella cartella Startup
-        set_portable_startup(enable)
+        set_portable_startup(enable)?;
+        // Migrazione (o disattivazione): il task pianificato non serve più
+        remove_scheduled_task();
+        Ok(())
     } else {
         // Versione installata: usa registro e/o Task Scheduler
-        set_installed_startup(enable)
+        set_installed_startup(enable, delay_secs)
     }
 }
 
@@ -165,7 +190,7 @@ fn set_portable_startup(enable: bool) -> Result<()> {
     Ok(())
 }
 
-fn set_installed_startup(enable: bool) -> Result<()> {
+fn set_installed_startup(enable: bool, delay_secs: u32) -> Result<()> {
     let exe = exe_path()?;
     let exe_str = exe.to_string_lossy();
 
@@ -177,15 +202,17 @@ fn set_installed_startup(enable: bool) -> Result<()> {
     if enable {
         // Prima prova con il registro (non richiede admin)
         if let Ok(()) = set_registry_startup(&exe_str, true) {
+            // Migrazione dal task pianificato
+            remove_scheduled_task();
             return Ok(());
         }
 
         // Fallback a Task Scheduler
-        set_task_scheduler_startup(&exe_str, true)
+        set_task_scheduler_startup(&exe_str, true, delay_secs, false)
     } else {
         // Rimuovi da entrambi
         let _ = set_registry_startup(&exe_str, false);
-        let _ = set_task_scheduler_startup(&exe_str, false);
+        remove_scheduled_task();
         Ok(())
     }
 }
@@ -309,8 +336,25 @@ fn set_registry_startup(exe_path: &str, enable: bool) -> Result<()> {
     Ok(())
 }
 
-fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
+/// Crea o rimuove il task pianificato all'accesso
+///
+/// `highest` lo esegue con i privilegi più alti disponibili (serve essere
+/// amministratori per crearlo), `delay_secs` è l'attesa dopo l'accesso.
+fn set_task_scheduler_startup(
+    exe_path: &str,
+    enable: bool,
+    delay_secs: u32,
+    highest: bool,
+) -> Result<()> {
     if enable {
+        let run_level = if highest {
+            "HighestAvailable"
+        } else {
+            "LeastPrivilege"
+        };
+        let delay = format!("{:04}:{:02}", delay_secs / 60, delay_secs % 60);
+        let rl = if highest { "HIGHEST" } else { "LIMITED" };
+
         // FIX: Usa XML per configurazione più robusta del Task Scheduler
         // Questo evita problemi con delay e privilegi
         let xml_content = format!(
@@ -324,13 +368,13 @@ fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
   <Triggers>
     <LogonTrigger>
       <Enabled>true</Enabled>
-      <Delay>PT30S</Delay>
+      <Delay>PT{}S</Delay>
     </LogonTrigger>
   </Triggers>
   <Principals>
     <Principal id="Author">
       <LogonType>InteractiveToken</LogonType>
-      <RunLevel>LeastPrivilege</RunLevel>
+      <RunLevel>{}</RunLevel>
     </Principal>
   </Principals>
   <Settings>
@@ -358,6 +402,8 @@ fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
     </Exec>
   </Actions>
 </Task>"#,
+            delay_secs,
+            run_level,
             exe_path.replace('\\', "\\\\").replace('"', "&quot;")
         );
 
@@ -415,9 +461,9 @@ fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
                 "/TR",
                 &format!("\"{}\"", exe_path),
                 "/RL",
-                "HIGHEST",
+                rl,
                 "/DELAY",
-                "0000:30",
+                &delay,
             ])
             .creation_flags(0x08000000);
 
@@ -434,9 +480,9 @@ fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
                 "/TR",
                 &format!("\"{}\"", exe_path),
                 "/RL",
-                "HIGHEST",
+                rl,
                 "/DELAY",
-                "0000:30",
+                &delay,
             ]);
 
             let result = run_command_with_timeout(cmd)?;
@@ -447,86 +493,92 @@ fn set_task_scheduler_startup(exe_path: &str, enable: bool) -> Result<()> {
             }
         }
     } else {
-        #[cfg(windows)]
-        let _ = std::process::Command::new("schtasks")
-            .args(["/Delete", "/F", "/TN", task_name()])
-            .creation_flags(0x08000000)
-            .output();
-
-        #[cfg(not(windows))]
-        let _ = std::process::Command::new("schtasks")
-            .args(["/Delete", "/F", "/TN", task_name()])
-            .output();
+        remove_scheduled_task();
     }
 
     Ok(())
 }
 
-pub fn is_startup_enabled() -> bool {
-    let detector = get_portable_detector();
+fn remove_scheduled_task() {
+    #[cfg(windows)]
+    let _ = std::process::Command::new("schtasks")
+        .args(["/Delete", "/F", "/TN", task_name()])
+        .creation_flags(0x08000000)
+        .output();
+
+    #[cfg(not(windows))]
+    let _ = std::process::Command::new("schtasks")
+        .args(["/Delete", "/F", "/TN", task_name()])
+        .output();
+}
 
-    if detector.is_portable() {
-        // Check for shortcut in Startup folder
-        if let Some(data_dir) = dirs::data_dir() {
-            let shortcut_path = data_dir
-                .join(r"Microsoft\Windows\Start Menu\Programs\Startup")
-                .join("TommyMemoryCleaner.lnk");
-            return shortcut_path.exists();
+/// Verifica se l'avvio automatico è attivo con il metodo scelto
+///
+/// Con `ScheduledTask` una Run key (o un collegamento) ancora presente conta
+/// come non attivo, così all'avvio la migrazione viene completata.
+pub fn is_startup_enabled(method: StartupMethod) -> bool {
+    match method {
+        StartupMethod::ScheduledTask => scheduled_task_exists() && !run_entry_exists(),
+        StartupMethod::RunKey => {
+            // L'installazione usa il task pianificato come ripiego della Run key
+            run_entry_exists()
+                || (!get_portable_detector().is_portable() && scheduled_task_exists())
         }
-    } else {
-        // Check registry
-        #[cfg(windows)]
-        {
-            let ps_script = format!(
-                r#"
-                $value = Get-ItemProperty -Path "HKCU:\Software\Microsoft\Windows\CurrentVersion\Run" `
-                    -Name "{}" `
-                    -ErrorAction SilentlyContinue
-                if ($value) {{ exit 0 }} else {{ exit 1 }}
-                "#,
-                app_name()
-            );
+    }
+}
 
-            // FIX #19: Usa timeout per il comando PowerShell
-            #[cfg(windows)]
-            let mut cmd = std::process::Command::new("powershell");
-            #[cfg(windows)]
-            cmd.arg("-NoProfile")
-                .arg("-NonInteractive")
-                .arg("-Command")
-                .arg(&ps_script)
-                .creation_flags(0x08000000);
+/// Run key (installata) o collegamento nella cartella Startup (portable)
+fn run_entry_exists() -> bool {
+    if get_portable_detector().is_portable() {
+        // Check for shortcut in Startup folder
+        return dirs::data_dir()
+            .map(|data_dir| {
+                data_dir
+                    .join(r"Microsoft\Windows\Start Menu\Programs\Startup")
+                    .join("TommyMemoryCleaner.lnk")
+                    .exists()
+            })
+            .unwrap_or(false);
+    }
 
-            #[cfg(not(windows))]
-            let mut cmd = std::process::Command::new("powershell");
-            #[cfg(not(windows))]
-            cmd.arg("-NoProfile")
-                .arg("-NonInteractive")
-                .arg("-Command")
-                .arg(&ps_script);
-
-            if let Ok(result) = run_command_with_timeout(cmd) {
-                if result.status.success() {
-                    return true;
-                }
-            }
+    #[cfg(windows)]
+    {
+        let ps_script = format!(
+            r#"
+            $value = Get-ItemProperty -Path "HKCU:\Software\Microsoft\Windows\CurrentVersion\Run" `
+                -Name "{}" `
+                -ErrorAction SilentlyContinue
+            if ($value) {{ exit 0 }} else {{ exit 1 }}
+            "#,
+            app_name()
+        );
 
-            // Check Task Scheduler
-            // FIX #19: Usa timeout per il comando schtasks
-            #[cfg(windows)]
-            let mut cmd = std::process::Command::new("schtasks");
-            #[cfg(windows)]
-            cmd.args(["/Query", "/TN", task_name()])
-                .creation_flags(0x08000000);
+        // FIX #19: Usa timeout per il comando PowerShell
+        let mut cmd = std::process::Command::new("powershell");
+        cmd.arg("-NoProfile")
+            .arg("-NonInteractive")
+            .arg("-Command")
+            .arg(&ps_script)
+            .creation_flags(0x08000000);
 
-            #[cfg(not(windows))]
-            let mut cmd = std::process::Command::new("schtasks");
-            #[cfg(not(windows))]
-            cmd.args(["/Query", "/TN", task_name()]);
+        if let Ok(result) = run_command_with_timeout(cmd) {
+            return result.status.success();
+        }
+    }
 
-            if let Ok(result) = run_command_with_timeout(cmd) {
-                return result.status.success();
-            }
+    false
+}
+
+fn scheduled_task_exists() -> bool {
+    #[cfg(windows)]
+    {
+        // FIX #19: Usa timeout per il comando schtasks
+        let mut cmd = std::process::Command::new("schtasks");
+        cmd.args(["/Query", "/TN", task_name()])
+            .creation_flags(0x08000000);
+
+        if let Ok(result) = run_command_with_timeout(cmd) {
+            return result.status.success();
         }
     }
 
@@ -0,0 +1,163 @@
+/// Background task supervisor
+///
+/// The tray updater and the auto optimizer are endless async loops. A panic
+/// inside one of them used to end the task silently, leaving the app without
+/// tray updates or automatic optimizations until the next restart. Here each
+/// loop runs as a child task watched by a supervisor task, which logs and
+/// reports the failure and starts the loop again with an exponential backoff.
+use crate::logging::error_bus::{report, Subsystem};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Delay before the first restart, doubled for each failure in a row
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// A task that ran this long before failing starts over from `INITIAL_BACKOFF`
+const STABLE_AFTER: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundTask {
+    TrayUpdater,
+    AutoOptimizer,
+}
+
+impl BackgroundTask {
+    fn subsystem(self) -> Subsystem {
+        match self {
+            BackgroundTask::TrayUpdater => Subsystem::Tray,
+            BackgroundTask::AutoOptimizer => Subsystem::Scheduler,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskState {
+    Running,
+    /// Waiting for the backoff before the next start
+    Restarting,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskHealth {
+    pub task: BackgroundTask,
+    pub state: TaskState,
+    /// Restarts since the app started
+    pub restarts: u32,
+    /// Panic message or reason of the last termination
+    pub last_failure: Option<String>,
+    /// Unix timestamp (seconds) of the last termination
+    pub last_failure_at: Option<u64>,
+}
+
+static TASKS: Lazy<Mutex<BTreeMap<BackgroundTask, TaskHealth>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Wait before restart number `failures_in_a_row` (1 = first failure)
+fn backoff_delay(failures_in_a_row: u32) -> Duration {
+    let factor = 1u32 << failures_in_a_row.saturating_sub(1).min(16);
+    INITIAL_BACKOFF.saturating_mul(factor).min(MAX_BACKOFF)
+}
+
+fn set_state(task: BackgroundTask, state: TaskState) {
+    if let Some(health) = TASKS.lock().get_mut(&task) {
+        health.state = state;
+    }
+}
+
+/// Run the loop built by `make` under supervision
+///
+/// Does nothing if the task is already supervised, so calling the start
+/// functions twice (e.g. at startup and after the setup wizard) is harmless.
+pub fn supervise<F, Fut>(task: BackgroundTask, make: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    {
+        let mut tasks = TASKS.lock();
+        if tasks.contains_key(&task) {
+            tracing::debug!("{:?} already supervised", task);
+            return;
+        }
+        tasks.insert(
+            task,
+            TaskHealth {
+                task,
+                state: TaskState::Running,
+                restarts: 0,
+                last_failure: None,
+                last_failure_at: None,
+            },
+        );
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let mut failures_in_a_row = 0;
+        loop {
+            set_state(task, TaskState::Running);
+            let started = Instant::now();
+            let failure = match tauri::async_runtime::spawn(make()).await {
+                Ok(()) => "the loop ended".to_string(),
+                Err(e) => e.to_string(),
+            };
+
+            if started.elapsed() >= STABLE_AFTER {
+                failures_in_a_row = 0;
+            }
+            failures_in_a_row += 1;
+            let delay = backoff_delay(failures_in_a_row);
+
+            tracing::error!(
+                "{:?} stopped ({}), restarting in {}s",
+                task,
+                failure,
+                delay.as_secs()
+            );
+            report(
+                task.subsystem(),
+                format!("{:?} stopped and was restarted: {}", task, failure),
+            );
+            if let Some(health) = TASKS.lock().get_mut(&task) {
+                health.state = TaskState::Restarting;
+                health.restarts += 1;
+                health.last_failure = Some(failure);
+                health.last_failure_at = Some(unix_now());
+            }
+
+            tokio::time::sleep(delay).await;
+        }
+    });
+}
+
+/// Health of every supervised task
+pub fn task_status() -> Vec<TaskHealth> {
+    TASKS.lock().values().cloned().collect()
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(1));
+        assert_eq!(backoff_delay(2), Duration::from_secs(2));
+        assert_eq!(backoff_delay(4), Duration::from_secs(8));
+        assert_eq!(backoff_delay(7), MAX_BACKOFF);
+        assert_eq!(backoff_delay(u32::MAX), MAX_BACKOFF);
+    }
+}
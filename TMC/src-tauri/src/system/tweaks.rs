@@ -0,0 +1,147 @@
+/// Advanced system tweaks
+///
+/// Windows policies related to memory that live in the registry rather than
+/// in the TMC configuration. The registry stays the only source of truth: the
+/// state is read back after every change, so a value edited with regedit or a
+/// group policy shows up as it is. Each tweak changes how Windows behaves for
+/// every user, needs administrator rights and takes effect at the next
+/// restart, so it comes with a warning the UI shows before changing it.
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(windows)]
+use crate::utils::to_wide;
+
+const MEMORY_MANAGEMENT_KEY: &str =
+    r"SYSTEM\CurrentControlSet\Control\Session Manager\Memory Management";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemTweak {
+    /// Overwrite the pagefile with zeros at shutdown
+    ClearPageFileAtShutdown,
+}
+
+impl SystemTweak {
+    pub const ALL: [SystemTweak; 1] = [SystemTweak::ClearPageFileAtShutdown];
+
+    /// Registry key (under HKLM) and DWORD value of the policy
+    fn registry_value(self) -> (&'static str, &'static str) {
+        match self {
+            SystemTweak::ClearPageFileAtShutdown => {
+                (MEMORY_MANAGEMENT_KEY, "ClearPageFileAtShutdown")
+            }
+        }
+    }
+
+    pub fn warning(self) -> &'static str {
+        match self {
+            SystemTweak::ClearPageFileAtShutdown => {
+                "Shutdown and restart can take several minutes longer with a large pagefile. It only protects data in the pagefile from someone reading the disk while Windows is off."
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TweakStatus {
+    pub tweak: SystemTweak,
+    pub enabled: bool,
+    /// The value exists in the registry (set by TMC, regedit or a policy)
+    pub configured: bool,
+    pub warning: &'static str,
+    /// Changing the tweak needs administrator rights
+    pub can_change: bool,
+}
+
+/// Current state of a tweak as stored in the registry
+pub fn status(tweak: SystemTweak) -> TweakStatus {
+    let (key, value) = tweak.registry_value();
+    let data = read_dword(key, value);
+    TweakStatus {
+        tweak,
+        enabled: data.is_some_and(|d| d != 0),
+        configured: data.is_some(),
+        warning: tweak.warning(),
+        can_change: crate::system::is_app_elevated(),
+    }
+}
+
+/// Enable or disable a tweak and return the state read back from the registry
+pub fn set(tweak: SystemTweak, enable: bool) -> Result<TweakStatus> {
+    if !crate::system::is_app_elevated() {
+        bail!("Administrator rights are required to change {:?}", tweak);
+    }
+    let (key, value) = tweak.registry_value();
+    write_dword(key, value, enable as u32)?;
+
+    let status = status(tweak);
+    if status.enabled != enable {
+        bail!(
+            "{:?} was not changed, a group policy may be enforcing it",
+            tweak
+        );
+    }
+    tracing::info!(
+        "{:?} {} (effective after restart)",
+        tweak,
+        if enable { "enabled" } else { "disabled" }
+    );
+    Ok(status)
+}
+
+#[cfg(windows)]
+fn read_dword(key: &str, value: &str) -> Option<u32> {
+    use windows_sys::Win32::System::Registry::{
+        RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_DWORD,
+    };
+
+    let key_w = to_wide(key);
+    let value_w = to_wide(value);
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            key_w.as_ptr(),
+            value_w.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut data as *mut u32 as *mut _,
+            &mut size,
+        )
+    };
+    (status == 0).then_some(data)
+}
+
+#[cfg(not(windows))]
+fn read_dword(_key: &str, _value: &str) -> Option<u32> {
+    None
+}
+
+#[cfg(windows)]
+fn write_dword(key: &str, value: &str, data: u32) -> Result<()> {
+    use windows_sys::Win32::System::Registry::{RegSetKeyValueW, HKEY_LOCAL_MACHINE, REG_DWORD};
+
+    let key_w = to_wide(key);
+    let value_w = to_wide(value);
+    let status = unsafe {
+        RegSetKeyValueW(
+            HKEY_LOCAL_MACHINE,
+            key_w.as_ptr(),
+            value_w.as_ptr(),
+            REG_DWORD,
+            &data as *const u32 as *const _,
+            std::mem::size_of::<u32>() as u32,
+        )
+    };
+    if status != 0 {
+        bail!("Failed to write {}\\{}: error {}", key, value, status);
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+fn write_dword(_key: &str, _value: &str, _data: u32) -> Result<()> {
+    bail!("System tweaks are only available on Windows")
+}
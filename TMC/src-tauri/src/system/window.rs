@@ -40,8 +40,8 @@ pub fn set_rounded_corners(hwnd: windows_sys::Win32::Foundation::HWND) -> Result
     };
 
     unsafe {
-        // Use centralized version detection (RtlGetVersion-based, more reliable than GetVersionExW)
-        let is_win11 = crate::os::is_windows_11();
+        // Use the cached version service (native DWM corners are a Windows 11 capability)
+        let is_win11 = crate::os::win_version().capabilities.native_rounded_corners;
 
         if is_win11 {
             // Windows 11: Use native DWM rounded corners
@@ -172,8 +172,8 @@ fn apply_win10_rounded_corners(hwnd: windows_sys::Win32::Foundation::HWND) {
 /// Enable window shadow for Windows 11 rounded corners
 #[cfg(windows)]
 pub fn enable_shadow_for_win11(window: &tauri::WebviewWindow) -> Result<(), String> {
-    // Use centralized version detection (RtlGetVersion-based, more reliable than GetVersionExW)
-    let is_win11 = crate::os::is_windows_11();
+    // Use the cached version service (native DWM corners are a Windows 11 capability)
+    let is_win11 = crate::os::win_version().capabilities.native_rounded_corners;
 
     if is_win11 {
         tracing::info!("Enabling shadow for Windows 11 rounded corners");
@@ -0,0 +1,155 @@
+/// Windows accessibility preferences
+///
+/// Reads the high contrast mode and the "Show animations in Windows" switch.
+/// The UI gets them from `cmd_get_accessibility_prefs` (and the
+/// `accessibility-changed` event) to tone down transitions; the tray icon
+/// switches to the colors of the high contrast theme on its own. Each
+/// preference can be forced on or off in `accessibility` in the config.
+use serde::{Deserialize, Serialize};
+
+/// Overrides of the detected preferences, `None` follows Windows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AccessibilityOverrides {
+    pub high_contrast: Option<bool>,
+    pub reduced_motion: Option<bool>,
+}
+
+/// Preferences in effect, and what Windows reported
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct AccessibilityPrefs {
+    pub high_contrast: bool,
+    pub reduced_motion: bool,
+    pub detected_high_contrast: bool,
+    pub detected_reduced_motion: bool,
+}
+
+impl AccessibilityOverrides {
+    pub fn apply(
+        &self,
+        detected_high_contrast: bool,
+        detected_reduced_motion: bool,
+    ) -> AccessibilityPrefs {
+        AccessibilityPrefs {
+            high_contrast: self.high_contrast.unwrap_or(detected_high_contrast),
+            reduced_motion: self.reduced_motion.unwrap_or(detected_reduced_motion),
+            detected_high_contrast,
+            detected_reduced_motion,
+        }
+    }
+}
+
+/// Detected preferences with `overrides` applied
+pub fn accessibility_prefs(overrides: &AccessibilityOverrides) -> AccessibilityPrefs {
+    overrides.apply(high_contrast_on(), !client_animations_on())
+}
+
+/// Badge colors of the high contrast theme in use
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighContrastPalette {
+    pub background: String,
+    pub text: String,
+    /// Warning and danger levels
+    pub alert_background: String,
+    pub alert_text: String,
+}
+
+#[cfg(windows)]
+fn high_contrast_on() -> bool {
+    use windows_sys::Win32::UI::Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{SystemParametersInfoW, SPI_GETHIGHCONTRAST};
+
+    unsafe {
+        let mut hc: HIGHCONTRASTW = std::mem::zeroed();
+        hc.cbSize = std::mem::size_of::<HIGHCONTRASTW>() as u32;
+        let ok = SystemParametersInfoW(
+            SPI_GETHIGHCONTRAST,
+            hc.cbSize,
+            &mut hc as *mut HIGHCONTRASTW as *mut _,
+            0,
+        );
+        ok != 0 && hc.dwFlags & HCF_HIGHCONTRASTON != 0
+    }
+}
+
+/// "Show animations in Windows" (Settings > Accessibility > Visual effects)
+#[cfg(windows)]
+fn client_animations_on() -> bool {
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION,
+    };
+
+    let mut enabled: i32 = 1;
+    let ok = unsafe {
+        SystemParametersInfoW(
+            SPI_GETCLIENTAREAANIMATION,
+            0,
+            &mut enabled as *mut i32 as *mut _,
+            0,
+        )
+    };
+    ok == 0 || enabled != 0
+}
+
+/// Window and highlight colors of the active theme
+#[cfg(windows)]
+pub fn high_contrast_palette() -> HighContrastPalette {
+    use windows_sys::Win32::Graphics::Gdi::{
+        GetSysColor, COLOR_HIGHLIGHT, COLOR_HIGHLIGHTTEXT, COLOR_WINDOW, COLOR_WINDOWTEXT,
+    };
+
+    // COLORREF is 0x00BBGGRR
+    let hex = |index| {
+        let c = unsafe { GetSysColor(index) };
+        format!(
+            "#{:02x}{:02x}{:02x}",
+            c & 0xff,
+            (c >> 8) & 0xff,
+            (c >> 16) & 0xff
+        )
+    };
+    HighContrastPalette {
+        background: hex(COLOR_WINDOW),
+        text: hex(COLOR_WINDOWTEXT),
+        alert_background: hex(COLOR_HIGHLIGHT),
+        alert_text: hex(COLOR_HIGHLIGHTTEXT),
+    }
+}
+
+#[cfg(not(windows))]
+fn high_contrast_on() -> bool {
+    false
+}
+
+#[cfg(not(windows))]
+fn client_animations_on() -> bool {
+    true
+}
+
+#[cfg(not(windows))]
+pub fn high_contrast_palette() -> HighContrastPalette {
+    HighContrastPalette {
+        background: "#000000".to_string(),
+        text: "#ffffff".to_string(),
+        alert_background: "#ffff00".to_string(),
+        alert_text: "#000000".to_string(),
+    }
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_win_over_detection() {
+        let overrides = AccessibilityOverrides {
+            high_contrast: Some(false),
+            reduced_motion: None,
+        };
+        let prefs = overrides.apply(true, true);
+        assert!(!prefs.high_contrast);
+        assert!(prefs.reduced_motion);
+        assert!(prefs.detected_high_contrast);
+    }
+}
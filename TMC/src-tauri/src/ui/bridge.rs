@@ -1,23 +1,56 @@
 use serde::Serialize;
 use tauri::{AppHandle, Emitter};
 
-#[derive(Debug, Clone, Serialize)]
-pub struct ProgressEvent {
-    pub value: u8,
-    pub total: u8,
-    pub step: String,
+/// Progress of an optimization or temp cleanup, tagged by `kind` for the frontend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// Area `index` of `total` begins, counting from 1
+    AreaStarted { index: u8, total: u8, area: String },
+    /// Share of a long area done, e.g. the per-process Working Set loop
+    AreaPercent { area: String, percent: u8 },
+    /// Area done; `freed_bytes` is `None` when skipped, failed or not measured
+    AreaFinished {
+        area: String,
+        freed_bytes: Option<i64>,
+        skipped: bool,
+        failed: bool,
+    },
+    /// Share of the whole run done
+    Overall { percent: u8 },
+    /// Every area has been handled
+    Completed,
+}
+
+/// Overall percent with `done` of `total` areas finished and the next one `area_percent` done
+pub fn overall_percent(done: usize, total: usize, area_percent: u8) -> u8 {
+    if total == 0 {
+        return 100;
+    }
+    let done = done.min(total) * 100 + area_percent.min(100) as usize;
+    (done / total).min(100) as u8
 }
 
 pub const EV_PROGRESS: &str = "tmc://opt_progress";
 pub const EV_DONE: &str = "tmc://opt_done";
+/// Payload: the `OptimizeResult` of a dry run (estimates only)
+pub const EV_DRY_RUN_RESULT: &str = "tmc://dry_run_result";
+/// Payload: the full `OptimizeResult` of every finished optimization
+pub const EV_OPTIMIZATION_RESULT: &str = "optimization-result";
+/// Emitted before `EV_DONE` when a run found nothing to free.
+/// Payload: `{ freed_physical_mb }`
+pub const EV_NOTHING_FREED: &str = "tmc://opt_nothing_freed";
+/// Payload: `Throttled` with the seconds left of `manual_cooldown_secs`
+pub const EV_OPTIMIZATION_THROTTLED: &str = "optimization-throttled";
+/// Payload: the `StorageReport` of a finished temp cleanup
+pub const EV_STORAGE_CLEANED: &str = "tmc://storage_cleaned";
+/// config.json was edited while the running settings had unsaved changes
+pub const EV_CONFIG_CONFLICT: &str = "config-conflict";
+/// Emitted when a window moves to a monitor with a different DPI
+pub const EV_DPI_CHANGED: &str = "tmc://dpi_changed";
+/// Payload: the `AccessibilityPrefs` in effect after a change in Windows or the config
+pub const EV_ACCESSIBILITY_CHANGED: &str = "accessibility-changed";
 
-pub fn emit_progress(app: &AppHandle, value: u8, total: u8, step: &str) {
-    let _ = app.emit(
-        EV_PROGRESS,
-        ProgressEvent {
-            value,
-            total,
-            step: step.to_string(),
-        },
-    );
+pub fn emit_progress(app: &AppHandle, event: ProgressEvent) {
+    let _ = app.emit(EV_PROGRESS, event);
 }
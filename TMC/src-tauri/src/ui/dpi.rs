@@ -0,0 +1,107 @@
+/// Per-monitor DPI helpers
+///
+/// The process is per-monitor DPI aware (see `main`), so Windows does not
+/// stretch our windows or the tray icon: sizes are scaled here for the monitor
+/// they are shown on, and `WM_DPICHANGED` (Tauri's `ScaleFactorChanged`) is
+/// handled for live changes.
+use crate::ui::bridge::EV_DPI_CHANGED;
+use serde::Serialize;
+use tauri::{Emitter, Manager, Window};
+
+/// DPI at 100% scaling
+pub const BASE_DPI: u32 = 96;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DpiChangedEvent {
+    pub label: String,
+    pub scale_factor: f64,
+    pub dpi: u32,
+}
+
+pub fn scale_factor(dpi: u32) -> f64 {
+    dpi.max(1) as f64 / BASE_DPI as f64
+}
+
+/// Logical size to physical pixels for the given DPI
+pub fn scale(logical: f64, dpi: u32) -> u32 {
+    (logical * scale_factor(dpi)).round().max(1.0) as u32
+}
+
+/// Effective DPI of the monitor containing (or nearest to) a screen point
+#[cfg(windows)]
+pub fn dpi_for_point(x: i32, y: i32) -> u32 {
+    use windows_sys::Win32::Foundation::POINT;
+    use windows_sys::Win32::Graphics::Gdi::{MonitorFromPoint, MONITOR_DEFAULTTONEAREST};
+    use windows_sys::Win32::UI::HiDpi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+
+    unsafe {
+        let monitor = MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST);
+        if monitor.is_null() {
+            return BASE_DPI;
+        }
+        let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+        if GetDpiForMonitor(monitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y) == 0 && dpi_x > 0 {
+            dpi_x
+        } else {
+            BASE_DPI
+        }
+    }
+}
+
+#[cfg(not(windows))]
+pub fn dpi_for_point(_x: i32, _y: i32) -> u32 {
+    BASE_DPI
+}
+
+/// DPI of the monitor hosting the taskbar, and therefore the tray icon
+pub fn tray_dpi() -> u32 {
+    match crate::commands::ui::get_taskbar_rect() {
+        Some((left, top, right, bottom)) => dpi_for_point((left + right) / 2, (top + bottom) / 2),
+        None => dpi_for_point(0, 0),
+    }
+}
+
+/// Size in pixels of a small (notification area) icon at the given DPI
+pub fn small_icon_size(dpi: u32) -> u32 {
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::UI::HiDpi::GetSystemMetricsForDpi;
+        use windows_sys::Win32::UI::WindowsAndMessaging::SM_CXSMICON;
+
+        let size = unsafe { GetSystemMetricsForDpi(SM_CXSMICON, dpi) };
+        if size > 0 {
+            return size as u32;
+        }
+    }
+    scale(16.0, dpi)
+}
+
+/// Handle `WM_DPICHANGED` for one of our windows
+pub fn handle_scale_factor_changed(window: &Window, scale_factor: f64) {
+    let label = window.label().to_string();
+    tracing::info!(
+        "DPI changed for '{}' window: scale {:.2}",
+        label,
+        scale_factor
+    );
+
+    // Keep the tray menu at its logical size on the new monitor
+    if label == "tray_menu" {
+        let (width, height) = crate::commands::ui::TRAY_MENU_SIZE;
+        if let Err(e) = window.set_size(tauri::LogicalSize::new(width, height)) {
+            tracing::warn!("Failed to rescale tray menu: {:?}", e);
+        }
+    }
+
+    let _ = window.emit(
+        EV_DPI_CHANGED,
+        DpiChangedEvent {
+            label,
+            scale_factor,
+            dpi: (scale_factor * BASE_DPI as f64).round() as u32,
+        },
+    );
+
+    // The taskbar monitor may have changed scale as well
+    crate::ui::tray::refresh_tray_icon(window.app_handle());
+}
@@ -0,0 +1,144 @@
+/// Executable icons for process lists
+///
+/// The first icon of an executable is extracted once, converted to a PNG data
+/// URL and cached by path, so refreshing a process list does not touch GDI for
+/// processes that were already shown.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Bound for the number of executables kept in memory
+const MAX_CACHED_ICONS: usize = 256;
+
+/// Icon data URL by lowercase path, `None` for executables without an icon
+static CACHE: Lazy<Mutex<HashMap<String, Option<String>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// PNG data URL of the icon of the executable at `path`
+pub fn exe_icon_data_url(path: &str) -> Option<String> {
+    let key = path.to_lowercase();
+    if let Some(cached) = CACHE.lock().get(&key) {
+        return cached.clone();
+    }
+
+    let url = extract_icon_rgba(path).and_then(|(width, height, rgba)| {
+        let img = image::RgbaImage::from_raw(width, height, rgba)?;
+        let mut png = Vec::new();
+        image::DynamicImage::ImageRgba8(img)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .ok()?;
+        Some(crate::ui::icon_cache::png_data_url(&png))
+    });
+
+    let mut cache = CACHE.lock();
+    if cache.len() >= MAX_CACHED_ICONS {
+        cache.clear();
+    }
+    cache.insert(key, url.clone());
+    url
+}
+
+/// First large icon of an executable as (width, height, RGBA pixels)
+#[cfg(windows)]
+fn extract_icon_rgba(path: &str) -> Option<(u32, u32, Vec<u8>)> {
+    use std::ptr;
+    use windows_sys::Win32::Graphics::Gdi::{
+        DeleteObject, GetDC, GetDIBits, GetObjectW, ReleaseDC, BITMAP, BITMAPINFO,
+        BITMAPINFOHEADER, BI_RGB, DIB_RGB_COLORS, HBITMAP,
+    };
+    use windows_sys::Win32::UI::Shell::ExtractIconExW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    let wide = crate::to_wide(path);
+    let mut icon: HICON = ptr::null_mut();
+    unsafe {
+        if ExtractIconExW(wide.as_ptr(), 0, &mut icon, ptr::null_mut(), 1) == 0 || icon.is_null() {
+            return None;
+        }
+    }
+    let _icon_guard = scopeguard::guard(icon, |icon| unsafe {
+        DestroyIcon(icon);
+    });
+
+    let mut info: ICONINFO = unsafe { std::mem::zeroed() };
+    if unsafe { GetIconInfo(icon, &mut info) } == 0 {
+        return None;
+    }
+    let _bitmap_guard = scopeguard::guard((info.hbmColor, info.hbmMask), |(color, mask)| unsafe {
+        if !color.is_null() {
+            DeleteObject(color);
+        }
+        if !mask.is_null() {
+            DeleteObject(mask);
+        }
+    });
+    if info.hbmColor.is_null() {
+        // Monochrome icon, not worth rendering in a process list
+        return None;
+    }
+
+    let mut bitmap: BITMAP = unsafe { std::mem::zeroed() };
+    let got = unsafe {
+        GetObjectW(
+            info.hbmColor,
+            std::mem::size_of::<BITMAP>() as i32,
+            &mut bitmap as *mut BITMAP as _,
+        )
+    };
+    if got == 0 || bitmap.bmWidth <= 0 || bitmap.bmHeight <= 0 {
+        return None;
+    }
+    let (width, height) = (bitmap.bmWidth as u32, bitmap.bmHeight as u32);
+
+    // 32bpp top-down BGRA
+    let read_bits = |hbm: HBITMAP| -> Option<Vec<u8>> {
+        let mut bmi: BITMAPINFO = unsafe { std::mem::zeroed() };
+        bmi.bmiHeader = BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width as i32,
+            biHeight: -(height as i32),
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB,
+            ..unsafe { std::mem::zeroed() }
+        };
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        let lines = unsafe {
+            let hdc = GetDC(ptr::null_mut());
+            let lines = GetDIBits(
+                hdc,
+                hbm,
+                0,
+                height,
+                pixels.as_mut_ptr() as _,
+                &mut bmi,
+                DIB_RGB_COLORS,
+            );
+            ReleaseDC(ptr::null_mut(), hdc);
+            lines
+        };
+        (lines == height as i32).then_some(pixels)
+    };
+
+    let mut pixels = read_bits(info.hbmColor)?;
+    // Icons without an alpha channel carry their transparency in the mask
+    if pixels.chunks_exact(4).all(|px| px[3] == 0) {
+        let mask = read_bits(info.hbmMask);
+        for (i, px) in pixels.chunks_exact_mut(4).enumerate() {
+            let transparent = mask
+                .as_ref()
+                .is_some_and(|m| m[i * 4] != 0 || m[i * 4 + 1] != 0 || m[i * 4 + 2] != 0);
+            px[3] = if transparent { 0 } else { 255 };
+        }
+    }
+    for px in pixels.chunks_exact_mut(4) {
+        px.swap(0, 2);
+    }
+
+    Some((width, height, pixels))
+}
+
+#[cfg(not(windows))]
+fn extract_icon_rgba(_path: &str) -> Option<(u32, u32, Vec<u8>)> {
+    None
+}
@@ -0,0 +1,262 @@
+/// Icon conversion and caching service
+///
+/// Resizing the embedded ICO with Lanczos is too slow for notification and
+/// tray paths, so icons are prepared once by a background task at startup and
+/// stored in `<data_dir>/icons/`. A manifest records the cache version and a
+/// hash of the source icon; the cache is only regenerated when either changes.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bump when the rendering of cached icons changes
+const CACHE_VERSION: u32 = 1;
+const CACHE_DIR_NAME: &str = "icons";
+const MANIFEST_FILE_NAME: &str = "cache.json";
+const NOTIFICATION_ICON_FILE: &str = "notification.png";
+/// Windows Toast looks best with a large PNG
+const NOTIFICATION_ICON_SIZE: u32 = 256;
+
+const EMBEDDED_ICO: &[u8] = include_bytes!("../../icons/icon.ico");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    /// FNV-1a of the source icons, hex encoded
+    source_hash: String,
+}
+
+static READY: AtomicBool = AtomicBool::new(false);
+static STARTED: AtomicBool = AtomicBool::new(false);
+/// Decoded tray icons by pixel size
+static TRAY_ICONS: Lazy<Mutex<HashMap<u32, Vec<u8>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache_dir() -> PathBuf {
+    crate::config::get_portable_detector()
+        .data_dir()
+        .join(CACHE_DIR_NAME)
+}
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |h, b| {
+        (h ^ *b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// High-res PNG shipped next to the executable, preferred over the embedded ICO
+fn shipped_notification_png() -> Option<Vec<u8>> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+    [
+        exe_dir.join("icons").join("128x128.png"),
+        exe_dir.join("128x128.png"),
+        exe_dir.join("icons").join("icon.png"),
+        exe_dir.join("icon.png"),
+    ]
+    .iter()
+    .find_map(|p| fs::read(p).ok())
+}
+
+fn source_hash(shipped_png: Option<&[u8]>) -> String {
+    let mut hash = fnv1a(0xcbf2_9ce4_8422_2325, EMBEDDED_ICO);
+    if let Some(png) = shipped_png {
+        hash = fnv1a(hash, png);
+    }
+    format!("{:016x}", hash)
+}
+
+fn render_rgba(source: &[u8], size: u32) -> Result<image::RgbaImage, String> {
+    let img = image::load_from_memory(source).map_err(|e| format!("Failed to load icon: {}", e))?;
+    let rgba = img.to_rgba8();
+    if rgba.dimensions() == (size, size) {
+        return Ok(rgba);
+    }
+    Ok(image::imageops::resize(
+        &rgba,
+        size,
+        size,
+        image::imageops::FilterType::Lanczos3,
+    ))
+}
+
+fn save_png(img: image::RgbaImage, path: &PathBuf) -> Result<(), String> {
+    image::DynamicImage::ImageRgba8(img)
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+fn read_manifest() -> Option<Manifest> {
+    let content = fs::read_to_string(cache_dir().join(MANIFEST_FILE_NAME)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn expected_manifest(shipped_png: Option<&[u8]>) -> Manifest {
+    Manifest {
+        version: CACHE_VERSION,
+        source_hash: source_hash(shipped_png),
+    }
+}
+
+/// Whether the files on disk were rendered from the current icons
+fn cache_is_current() -> bool {
+    READY.load(Ordering::SeqCst)
+        || read_manifest() == Some(expected_manifest(shipped_notification_png().as_deref()))
+}
+
+/// Bring the cache up to date; returns true when icons were regenerated
+fn prepare() -> Result<bool, String> {
+    let dir = cache_dir();
+    let shipped_png = shipped_notification_png();
+    let expected = expected_manifest(shipped_png.as_deref());
+
+    if read_manifest().as_ref() == Some(&expected) && notification_icon_path().exists() {
+        return Ok(false);
+    }
+
+    // Stale or missing: start from an empty directory
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create icon cache: {}", e))?;
+    TRAY_ICONS.lock().clear();
+
+    let notification_source = shipped_png.as_deref().unwrap_or(EMBEDDED_ICO);
+    save_png(
+        render_rgba(notification_source, NOTIFICATION_ICON_SIZE)?,
+        &notification_icon_path(),
+    )?;
+
+    // Pre-render the tray icon for the current DPI
+    let tray_size = crate::ui::tray::tray_icon_size();
+    save_png(
+        render_rgba(EMBEDDED_ICO, tray_size)?,
+        &tray_icon_path(tray_size),
+    )?;
+
+    let manifest =
+        serde_json::to_string_pretty(&expected).map_err(|e| format!("Invalid manifest: {}", e))?;
+    fs::write(dir.join(MANIFEST_FILE_NAME), manifest)
+        .map_err(|e| format!("Failed to write icon cache manifest: {}", e))?;
+
+    Ok(true)
+}
+
+/// Prepare the icon cache on a background thread (once per process)
+pub fn start_icon_cache() {
+    if STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let spawn_result = std::thread::Builder::new()
+        .name("tmc-icon-cache".to_string())
+        .spawn(|| match prepare() {
+            Ok(regenerated) => {
+                READY.store(true, Ordering::SeqCst);
+                if regenerated {
+                    tracing::info!("✓ Icon cache regenerated in {}", cache_dir().display());
+                    // The notification registration points at the cached PNG
+                    crate::notifications::register_app_for_notifications();
+                } else {
+                    tracing::debug!("Icon cache is up to date");
+                }
+            }
+            Err(e) => tracing::warn!("✗ Failed to prepare icon cache: {}", e),
+        });
+
+    if let Err(e) = spawn_result {
+        STARTED.store(false, Ordering::SeqCst);
+        tracing::warn!("Failed to start icon cache task: {}", e);
+    }
+}
+
+/// Location of the cached notification PNG (may not exist yet)
+pub fn notification_icon_path() -> PathBuf {
+    cache_dir().join(NOTIFICATION_ICON_FILE)
+}
+
+/// Cached notification PNG, `None` until the cache has been generated once
+pub fn notification_icon() -> Option<PathBuf> {
+    let path = notification_icon_path();
+    path.exists().then_some(path)
+}
+
+fn tray_icon_path(size: u32) -> PathBuf {
+    cache_dir().join(format!("tray_{}.png", size))
+}
+
+/// RGBA pixels of the default tray icon at the given size
+///
+/// Served from memory or the disk cache; sizes not rendered at startup (e.g.
+/// after a DPI change) are rendered once and added to the cache.
+pub fn tray_icon_rgba(size: u32) -> Result<Vec<u8>, String> {
+    if let Some(pixels) = TRAY_ICONS.lock().get(&size) {
+        return Ok(pixels.clone());
+    }
+
+    let path = tray_icon_path(size);
+    let cached = if cache_is_current() {
+        image::open(&path)
+            .ok()
+            .map(|img| img.to_rgba8())
+            .filter(|img| img.dimensions() == (size, size))
+    } else {
+        None
+    };
+
+    let img = match cached {
+        Some(img) => img,
+        None => {
+            let img = render_rgba(EMBEDDED_ICO, size)?;
+            if READY.load(Ordering::SeqCst) {
+                if let Err(e) = save_png(img.clone(), &path) {
+                    tracing::debug!("{}", e);
+                }
+            }
+            img
+        }
+    };
+
+    let pixels = img.into_raw();
+    TRAY_ICONS.lock().insert(size, pixels.clone());
+    Ok(pixels)
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |acc, (i, b)| acc | ((*b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Encode PNG bytes as a data URL usable as an image source in the webview
+pub fn png_data_url(png: &[u8]) -> String {
+    format!("data:image/png;base64,{}", base64_encode(png))
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_rfc4648_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+}
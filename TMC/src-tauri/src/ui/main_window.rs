@@ -0,0 +1,66 @@
+/// Hide-to-tray for the main window
+///
+/// A hidden window keeps its WebView2 processes and their memory, which is
+/// a poor look for a memory cleaner sitting idle in the tray. With
+/// `destroy_webview_on_hide` the window is destroyed instead and built again
+/// by `show_or_create_window` the next time it is opened. The UI state the
+/// frontend hands over before hiding (e.g. the active tab) is kept here and
+/// handed to the new webview.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::{AppHandle, Manager};
+
+/// UI state of the last main window, taken by the next one
+static UI_STATE: Lazy<Mutex<Option<serde_json::Value>>> = Lazy::new(|| Mutex::new(None));
+/// The main window was destroyed by a hide and not created again since
+static DESTROYED_ON_HIDE: AtomicBool = AtomicBool::new(false);
+
+/// Hide the main window to the tray, destroying it when configured
+pub fn hide_main_window(app: &AppHandle, ui_state: Option<serde_json::Value>) {
+    let window = match app.get_webview_window("main") {
+        Some(window) => window,
+        None => return,
+    };
+    if ui_state.is_some() {
+        *UI_STATE.lock() = ui_state;
+    }
+
+    let destroy = app
+        .try_state::<crate::AppState>()
+        .map(|state| state.cfg.read().destroy_webview_on_hide)
+        .unwrap_or(false);
+
+    if destroy {
+        DESTROYED_ON_HIDE.store(true, Ordering::SeqCst);
+        match window.destroy() {
+            Ok(()) => {
+                tracing::info!("Main window destroyed on hide, its WebView memory is released");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!("Failed to destroy main window, hiding it instead: {}", e);
+                DESTROYED_ON_HIDE.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+    if let Err(e) = window.hide() {
+        tracing::warn!("Failed to hide window: {}", e);
+    }
+}
+
+/// Call when the main window is created again
+pub fn on_main_window_created() {
+    DESTROYED_ON_HIDE.store(false, Ordering::SeqCst);
+}
+
+/// Whether an exit request without windows left must be ignored, because
+/// the main window was only destroyed to save memory while in the tray
+pub fn keep_running_without_window() -> bool {
+    DESTROYED_ON_HIDE.load(Ordering::SeqCst)
+}
+
+/// UI state saved by the previous main window, `None` once taken
+pub fn take_ui_state() -> Option<serde_json::Value> {
+    UI_STATE.lock().take()
+}
@@ -0,0 +1,170 @@
+/// Theme-aware menu icons
+///
+/// The tray menu and widget draw their icons in the accent color. Instead of
+/// shipping one asset per color, the monochrome glyphs below are rendered on
+/// demand and cached per color as PNG data URLs.
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tiny_skia::{FillRule, LineCap, LineJoin, Paint, PathBuilder, Pixmap, Stroke, Transform};
+
+/// Glyphs are drawn on a 24x24 grid
+const GRID: f32 = 24.0;
+/// Rendered at 2x of the 16px CSS size for high DPI menus
+const MENU_ICON_SIZE: u32 = 32;
+/// Bound for the number of (icon, color) pairs kept in memory
+const MAX_CACHED_ICONS: usize = 64;
+
+pub const MENU_ICON_NAMES: &[&str] = &["open", "optimize", "profile", "pause", "resume", "exit"];
+
+static CACHE: Lazy<Mutex<HashMap<(String, [u8; 4]), String>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+enum Shape {
+    /// Stroked polyline, closed when the flag is set
+    Line(&'static [(f32, f32)], bool),
+    /// Filled polygon
+    Fill(&'static [(f32, f32)]),
+    /// Filled circle (cx, cy, r)
+    Dot(f32, f32, f32),
+}
+
+fn glyph(name: &str) -> Option<&'static [Shape]> {
+    use Shape::*;
+
+    Some(match name {
+        // Window with title bar
+        "open" => &[
+            Line(&[(3.0, 5.0), (21.0, 5.0), (21.0, 19.0), (3.0, 19.0)], true),
+            Line(&[(3.0, 9.0), (21.0, 9.0)], false),
+        ],
+        // Lightning bolt
+        "optimize" => &[Fill(&[
+            (13.0, 2.0),
+            (4.0, 14.0),
+            (11.0, 14.0),
+            (10.0, 22.0),
+            (20.0, 9.0),
+            (13.0, 9.0),
+        ])],
+        // Two sliders
+        "profile" => &[
+            Line(&[(4.0, 7.0), (20.0, 7.0)], false),
+            Line(&[(4.0, 17.0), (20.0, 17.0)], false),
+            Dot(9.0, 7.0, 3.0),
+            Dot(15.0, 17.0, 3.0),
+        ],
+        // Two bars
+        "pause" => &[
+            Fill(&[(6.0, 5.0), (10.0, 5.0), (10.0, 19.0), (6.0, 19.0)]),
+            Fill(&[(14.0, 5.0), (18.0, 5.0), (18.0, 19.0), (14.0, 19.0)]),
+        ],
+        // Play triangle
+        "resume" => &[Fill(&[(7.0, 4.0), (19.0, 12.0), (7.0, 20.0)])],
+        // Arrow leaving a door
+        "exit" => &[
+            Line(&[(10.0, 4.0), (4.0, 4.0), (4.0, 20.0), (10.0, 20.0)], false),
+            Line(&[(9.0, 12.0), (20.0, 12.0)], false),
+            Line(&[(16.0, 8.0), (20.0, 12.0), (16.0, 16.0)], false),
+        ],
+        _ => return None,
+    })
+}
+
+fn polyline(points: &[(f32, f32)], close: bool) -> Option<tiny_skia::Path> {
+    let (first, rest) = points.split_first()?;
+    let mut pb = PathBuilder::new();
+    pb.move_to(first.0, first.1);
+    for (x, y) in rest {
+        pb.line_to(*x, *y);
+    }
+    if close {
+        pb.close();
+    }
+    pb.finish()
+}
+
+fn render_png(shapes: &[Shape], color: [u8; 4], size: u32) -> Result<Vec<u8>, String> {
+    let mut pixmap = Pixmap::new(size, size).ok_or("Invalid icon size")?;
+    let transform = Transform::from_scale(size as f32 / GRID, size as f32 / GRID);
+
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color[0], color[1], color[2], color[3]);
+    paint.anti_alias = true;
+
+    let stroke = Stroke {
+        width: 2.0,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        ..Default::default()
+    };
+
+    for shape in shapes {
+        match shape {
+            Shape::Line(points, close) => {
+                if let Some(path) = polyline(points, *close) {
+                    pixmap.stroke_path(&path, &paint, &stroke, transform, None);
+                }
+            }
+            Shape::Fill(points) => {
+                if let Some(path) = polyline(points, true) {
+                    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+                }
+            }
+            Shape::Dot(cx, cy, r) => {
+                if let Some(path) = PathBuilder::from_circle(*cx, *cy, *r) {
+                    pixmap.fill_path(&path, &paint, FillRule::Winding, transform, None);
+                }
+            }
+        }
+    }
+
+    pixmap
+        .encode_png()
+        .map_err(|e| format!("Failed to encode icon: {}", e))
+}
+
+/// PNG data URL of a menu icon drawn in `color_hex`
+///
+/// Unknown names are an error; invalid colors fall back to gray like the
+/// tray icon does.
+pub fn menu_icon_data_url(name: &str, color_hex: &str) -> Result<String, String> {
+    let shapes = glyph(name).ok_or_else(|| {
+        format!(
+            "Unknown menu icon '{}', expected one of: {}",
+            name,
+            MENU_ICON_NAMES.join(", ")
+        )
+    })?;
+    let color = crate::ui::tray::hex_to_rgba(color_hex);
+    let key = (name.to_string(), color);
+
+    if let Some(url) = CACHE.lock().get(&key) {
+        return Ok(url.clone());
+    }
+
+    let png = render_png(shapes, color, MENU_ICON_SIZE)?;
+    let url = crate::ui::icon_cache::png_data_url(&png);
+
+    let mut cache = CACHE.lock();
+    if cache.len() >= MAX_CACHED_ICONS {
+        // Colors change rarely; starting over is cheaper than tracking usage
+        cache.clear();
+    }
+    cache.insert(key, url.clone());
+    Ok(url)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_listed_icon_has_a_glyph() {
+        for name in MENU_ICON_NAMES {
+            assert!(glyph(name).is_some(), "missing glyph for {}", name);
+        }
+        assert!(glyph("unknown").is_none());
+    }
+}
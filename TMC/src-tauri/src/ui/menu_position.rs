@@ -0,0 +1,249 @@
+/// Tray menu placement
+///
+/// Pure geometry, all in physical pixels: the caller collects the monitor
+/// that contains the tray icon, its work area, DPI and the taskbar state
+/// (see `commands::ui::position_tray_menu`) and this module decides where the
+/// menu goes. The menu opens next to the taskbar edge, centered on the icon
+/// along that edge and kept inside the monitor.
+
+/// Screen rectangle in physical pixels, `right`/`bottom` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+impl Rect {
+    pub fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.right - self.left
+    }
+
+    pub fn height(&self) -> i32 {
+        self.bottom - self.top
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.left && x < self.right && y >= self.top && y < self.bottom
+    }
+
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.left < other.right
+            && other.left < self.right
+            && self.top < other.bottom
+            && other.top < self.bottom
+    }
+}
+
+/// Monitor edge the taskbar is docked to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskbarEdge {
+    Left,
+    Top,
+    Right,
+    Bottom,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Taskbar {
+    pub rect: Rect,
+    pub edge: TaskbarEdge,
+    /// With auto-hide the work area does not exclude the taskbar and the
+    /// reported rectangle can be the thin hidden strip
+    pub auto_hide: bool,
+}
+
+/// The monitor containing the tray icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Monitor {
+    pub bounds: Rect,
+    pub work_area: Rect,
+    pub dpi: u32,
+}
+
+/// Logical gap between the menu and the taskbar or the screen edges
+const MARGIN: f64 = 5.0;
+/// Logical thickness assumed for an auto-hidden taskbar reported as a strip
+const MIN_TASKBAR_THICKNESS: f64 = 40.0;
+
+/// Top-left corner of the menu for an icon at `anchor`
+pub fn place_menu(
+    monitor: &Monitor,
+    taskbar: Option<&Taskbar>,
+    anchor: (i32, i32),
+    menu_size: (i32, i32),
+) -> (i32, i32) {
+    let margin = crate::ui::dpi::scale(MARGIN, monitor.dpi) as i32;
+    let (width, height) = menu_size;
+    let bounds = monitor.bounds;
+
+    // The taskbar only counts if it is on this monitor
+    let taskbar = taskbar.filter(|t| t.rect.intersects(&bounds));
+    let edge = taskbar
+        .map(|t| t.edge)
+        .or_else(|| edge_from_work_area(monitor))
+        .unwrap_or(TaskbarEdge::Bottom);
+    let inner = inner_boundary(monitor, taskbar, edge);
+
+    // Along the taskbar the menu is centered on the icon, across it sits
+    // next to the inner side of the taskbar
+    let (x, y) = match edge {
+        TaskbarEdge::Bottom => (anchor.0 - width / 2, inner - height - margin),
+        TaskbarEdge::Top => (anchor.0 - width / 2, inner + margin),
+        TaskbarEdge::Left => (inner + margin, anchor.1 - height / 2),
+        TaskbarEdge::Right => (inner - width - margin, anchor.1 - height / 2),
+    };
+
+    (
+        clamp_span(x, width, bounds.left + margin, bounds.right - margin),
+        clamp_span(y, height, bounds.top + margin, bounds.bottom - margin),
+    )
+}
+
+/// Coordinate of the taskbar side facing the desktop
+fn inner_boundary(monitor: &Monitor, taskbar: Option<&Taskbar>, edge: TaskbarEdge) -> i32 {
+    let bounds = monitor.bounds;
+    let work = monitor.work_area;
+    let taskbar = match taskbar {
+        Some(t) => t,
+        None => {
+            return match edge {
+                TaskbarEdge::Bottom => work.bottom,
+                TaskbarEdge::Top => work.top,
+                TaskbarEdge::Left => work.left,
+                TaskbarEdge::Right => work.right,
+            }
+        }
+    };
+
+    let thickness = match edge {
+        TaskbarEdge::Top | TaskbarEdge::Bottom => taskbar.rect.height(),
+        TaskbarEdge::Left | TaskbarEdge::Right => taskbar.rect.width(),
+    };
+    let thickness = if taskbar.auto_hide {
+        thickness.max(crate::ui::dpi::scale(MIN_TASKBAR_THICKNESS, monitor.dpi) as i32)
+    } else {
+        thickness
+    };
+
+    match edge {
+        TaskbarEdge::Bottom => bounds.bottom - thickness,
+        TaskbarEdge::Top => bounds.top + thickness,
+        TaskbarEdge::Left => bounds.left + thickness,
+        TaskbarEdge::Right => bounds.right - thickness,
+    }
+}
+
+/// Edge where the work area is inset from the monitor, if any
+fn edge_from_work_area(monitor: &Monitor) -> Option<TaskbarEdge> {
+    let (b, w) = (monitor.bounds, monitor.work_area);
+    [
+        (TaskbarEdge::Bottom, b.bottom - w.bottom),
+        (TaskbarEdge::Top, w.top - b.top),
+        (TaskbarEdge::Left, w.left - b.left),
+        (TaskbarEdge::Right, b.right - w.right),
+    ]
+    .into_iter()
+    .filter(|(_, inset)| *inset > 0)
+    .max_by_key(|(_, inset)| *inset)
+    .map(|(edge, _)| edge)
+}
+
+/// Keep `[start, start + len)` within `[min, max)`, preferring `min` when it does not fit
+fn clamp_span(start: i32, len: i32, min: i32, max: i32) -> i32 {
+    start.min(max - len).max(min)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn monitor(bounds: Rect, work_area: Rect, dpi: u32) -> Monitor {
+        Monitor {
+            bounds,
+            work_area,
+            dpi,
+        }
+    }
+
+    #[test]
+    fn test_bottom_taskbar_at_150_percent() {
+        let m = monitor(
+            Rect::new(0, 0, 2880, 1620),
+            Rect::new(0, 0, 2880, 1548),
+            144,
+        );
+        let taskbar = Taskbar {
+            rect: Rect::new(0, 1548, 2880, 1620),
+            edge: TaskbarEdge::Bottom,
+            auto_hide: false,
+        };
+        // Menu 240x240 (160 logical), margin 8 px at 144 DPI
+        assert_eq!(
+            place_menu(&m, Some(&taskbar), (2700, 1590), (240, 240)),
+            (2580, 1300)
+        );
+        // Icon at the far right: the menu stays on screen
+        assert_eq!(
+            place_menu(&m, Some(&taskbar), (2870, 1590), (240, 240)),
+            (2632, 1300)
+        );
+    }
+
+    #[test]
+    fn test_vertical_taskbar_on_secondary_monitor() {
+        // Secondary monitor left of the primary, taskbar on its right edge
+        let m = monitor(
+            Rect::new(-1920, 0, 0, 1080),
+            Rect::new(-1920, 0, -62, 1080),
+            96,
+        );
+        let taskbar = Taskbar {
+            rect: Rect::new(-62, 0, 0, 1080),
+            edge: TaskbarEdge::Right,
+            auto_hide: false,
+        };
+        assert_eq!(
+            place_menu(&m, Some(&taskbar), (-30, 1050), (160, 160)),
+            (-227, 915)
+        );
+
+        // The primary taskbar is ignored when it is not on this monitor
+        let elsewhere = Taskbar {
+            rect: Rect::new(0, 1040, 1920, 1080),
+            edge: TaskbarEdge::Bottom,
+            auto_hide: false,
+        };
+        assert_eq!(
+            place_menu(&m, Some(&elsewhere), (-30, 1050), (160, 160)),
+            (-227, 915)
+        );
+    }
+
+    #[test]
+    fn test_auto_hide_taskbar_reserves_space() {
+        let m = monitor(Rect::new(0, 0, 1920, 1080), Rect::new(0, 0, 1920, 1080), 96);
+        let hidden = Taskbar {
+            rect: Rect::new(0, 1078, 1920, 1080),
+            edge: TaskbarEdge::Bottom,
+            auto_hide: true,
+        };
+        assert_eq!(
+            place_menu(&m, Some(&hidden), (1800, 1060), (160, 160)),
+            (1720, 875)
+        );
+        // No taskbar information and no inset work area: bottom of the screen
+        assert_eq!(place_menu(&m, None, (1800, 1060), (160, 160)), (1720, 915));
+    }
+}
@@ -1,2 +1,10 @@
+pub mod accessibility;
 pub mod bridge;
+pub mod dpi;
+pub mod exe_icons;
+pub mod icon_cache;
+pub mod main_window;
+pub mod menu_icons;
+pub mod menu_position;
+pub mod system_theme;
 pub mod tray;
@@ -0,0 +1,119 @@
+/// Windows taskbar theme and accent color
+///
+/// With `tray.follow_system` the tray icon takes its colors from the taskbar
+/// instead of the configured ones, so the percentage stays readable when the
+/// user switches between light and dark mode or changes the accent color.
+/// The values come from the per-user personalization keys, read again on
+/// every tray update, so a change at runtime shows within one cycle.
+
+/// Badge background when the accent color cannot be used
+const NEUTRAL_DARK: [u8; 3] = [0x3a, 0x3a, 0x3a];
+const NEUTRAL_LIGHT: [u8; 3] = [0xe0, 0xe0, 0xe0];
+
+/// Default is the Windows default: dark taskbar, no accent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaskbarTheme {
+    /// Light taskbar (`SystemUsesLightTheme`)
+    pub light: bool,
+    /// Accent color, `None` when Windows does not report one
+    pub accent: Option<[u8; 3]>,
+    /// The taskbar itself is painted in the accent color (`ColorPrevalence`)
+    pub accent_on_taskbar: bool,
+}
+
+impl TaskbarTheme {
+    /// Color of the taskbar behind the icon
+    fn taskbar_color(&self) -> [u8; 3] {
+        match self.accent {
+            Some(accent) if self.accent_on_taskbar => accent,
+            _ if self.light => NEUTRAL_LIGHT,
+            _ => NEUTRAL_DARK,
+        }
+    }
+
+    /// Badge background: the accent color, unless the taskbar already uses it
+    pub fn badge_color(&self) -> [u8; 3] {
+        match self.accent {
+            Some(accent) if !self.accent_on_taskbar => accent,
+            _ if self.light => NEUTRAL_DARK,
+            _ => NEUTRAL_LIGHT,
+        }
+    }
+
+    /// Text color for a badge drawn straight on the taskbar (transparent background)
+    pub fn transparent_text_color(&self) -> [u8; 3] {
+        contrasting_text(self.taskbar_color())
+    }
+}
+
+/// Black or white, whichever reads better on `bg`
+pub fn contrasting_text(bg: [u8; 3]) -> [u8; 3] {
+    // Relative luminance (sRGB), 0.179 is where both contrast ratios match
+    let linear = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let luminance = 0.2126 * linear(bg[0]) + 0.7152 * linear(bg[1]) + 0.0722 * linear(bg[2]);
+    if luminance > 0.179 {
+        [0, 0, 0]
+    } else {
+        [0xff, 0xff, 0xff]
+    }
+}
+
+pub fn rgb_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+/// Current taskbar theme, Windows defaults for values that cannot be read
+#[cfg(windows)]
+pub fn taskbar_theme() -> TaskbarTheme {
+    use crate::utils::read_user_dword as read_dword;
+
+    const PERSONALIZE: &str = r"Software\Microsoft\Windows\CurrentVersion\Themes\Personalize";
+
+    TaskbarTheme {
+        light: read_dword(PERSONALIZE, "SystemUsesLightTheme") == Some(1),
+        // Stored as 0xAABBGGRR
+        accent: read_dword(r"Software\Microsoft\Windows\DWM", "AccentColor")
+            .map(|abgr| [abgr as u8, (abgr >> 8) as u8, (abgr >> 16) as u8]),
+        accent_on_taskbar: read_dword(PERSONALIZE, "ColorPrevalence") == Some(1),
+    }
+}
+
+#[cfg(not(windows))]
+pub fn taskbar_theme() -> TaskbarTheme {
+    TaskbarTheme::default()
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_badge_stays_readable() {
+        assert_eq!(contrasting_text([0xff, 0xd7, 0x00]), [0, 0, 0]);
+        assert_eq!(contrasting_text([0x00, 0x5a, 0x9e]), [0xff, 0xff, 0xff]);
+
+        // An accent colored taskbar would swallow an accent badge
+        let theme = TaskbarTheme {
+            light: false,
+            accent: Some([0x6b, 0x2d, 0x8f]),
+            accent_on_taskbar: true,
+        };
+        assert_eq!(theme.badge_color(), NEUTRAL_LIGHT);
+        assert_eq!(theme.transparent_text_color(), [0xff, 0xff, 0xff]);
+
+        let light = TaskbarTheme {
+            light: true,
+            ..Default::default()
+        };
+        assert_eq!(light.transparent_text_color(), [0, 0, 0]);
+        assert_eq!(rgb_hex(light.badge_color()), "#3a3a3a");
+    }
+}
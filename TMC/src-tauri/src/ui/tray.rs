@@ -1,16 +1,43 @@
+use crate::config::{Profile, TrayConfig, TrayDisplayMode};
 use crate::engine::Engine;
+use crate::memory::types::Reason;
+use crate::system::supervisor::BackgroundTask;
 use image::{ImageBuffer, Rgba, RgbaImage};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
 use rusttype::{point, Font, Scale};
-use tauri::{image::Image, tray::TrayIconBuilder, AppHandle, Manager, Runtime};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{image::Image, tray::TrayIconBuilder, AppHandle, Emitter, Manager, Runtime, Wry};
 
+use crate::ui::accessibility::{self, AccessibilityOverrides};
+use crate::ui::system_theme;
 use crate::TRAY_ICON_ID;
 
-const ICON_SIZE: u32 = 32;
-
 // Font embedded nel binario
 const FONT_DATA: &[u8] = include_bytes!("../../fonts/Roboto-Bold.ttf");
 
-fn hex_to_rgba(hex: &str) -> [u8; 4] {
+/// RAM samples kept for the sparkline (3 minutes at one sample every 2 s)
+const SPARKLINE_SAMPLES: usize = 90;
+
+// Storico dell'uso RAM campionato da start_tray_updater
+static RAM_HISTORY: Lazy<Mutex<VecDeque<u8>>> =
+    Lazy::new(|| Mutex::new(VecDeque::with_capacity(SPARKLINE_SAMPLES)));
+
+fn record_ram_sample(percent: u8) {
+    let mut history = RAM_HISTORY.lock();
+    if history.len() == SPARKLINE_SAMPLES {
+        history.pop_front();
+    }
+    history.push_back(percent.min(100));
+}
+
+fn ram_history() -> Vec<u8> {
+    RAM_HISTORY.lock().iter().copied().collect()
+}
+
+pub(crate) fn hex_to_rgba(hex: &str) -> [u8; 4] {
     // FIX #7: Validare il formato hex prima del parsing e usare un default sensato
     let hex = hex.trim_start_matches('#');
 
@@ -27,13 +54,20 @@ fn hex_to_rgba(hex: &str) -> [u8; 4] {
     [128, 128, 128, 255]
 }
 
+/// Pixel size of the tray icon for the DPI of the taskbar monitor
+pub fn tray_icon_size() -> u32 {
+    crate::ui::dpi::small_icon_size(crate::ui::dpi::tray_dpi()).clamp(16, 64)
+}
+
 pub fn create_tray_icon(
     percentage: u8,
     bg_hex: &str,
     text_hex: &str,
     transparent: bool,
+    size: u32,
 ) -> Image<'static> {
-    let render_size = ICON_SIZE * 2;
+    // Render at twice the size and downsample for smooth text
+    let render_size = size * 2;
 
     let bg_color = hex_to_rgba(bg_hex);
     let text_color = hex_to_rgba(text_hex);
@@ -45,7 +79,7 @@ pub fn create_tray_icon(
     };
 
     if !transparent {
-        apply_rounded_corners(&mut img, 12.0, bg_color);
+        apply_rounded_corners(&mut img, render_size as f32 * 0.1875, bg_color);
     }
 
     // Try to load font, but don't crash if it fails - just create icon without text
@@ -105,15 +139,57 @@ pub fn create_tray_icon(
         tracing::warn!("Failed to load embedded font, creating icon without text");
     }
 
-    let final_img = image::imageops::resize(
-        &img,
-        ICON_SIZE,
-        ICON_SIZE,
-        image::imageops::FilterType::Lanczos3,
-    );
+    let final_img =
+        image::imageops::resize(&img, size, size, image::imageops::FilterType::Lanczos3);
 
     let buffer: Vec<u8> = final_img.into_raw();
-    Image::new_owned(buffer, ICON_SIZE, ICON_SIZE)
+    Image::new_owned(buffer, size, size)
+}
+
+/// Bar graph of RAM usage, oldest sample on the left and the newest on the right
+pub fn create_sparkline_icon(
+    samples: &[u8],
+    bg_hex: &str,
+    bar_hex: &str,
+    transparent: bool,
+    size: u32,
+) -> Image<'static> {
+    let render_size = size * 2;
+
+    let bg_color = hex_to_rgba(bg_hex);
+    let bar_color = hex_to_rgba(bar_hex);
+
+    let mut img: RgbaImage = if transparent {
+        ImageBuffer::from_fn(render_size, render_size, |_, _| Rgba([0, 0, 0, 0]))
+    } else {
+        ImageBuffer::from_fn(render_size, render_size, |_, _| Rgba(bg_color))
+    };
+
+    if !transparent {
+        apply_rounded_corners(&mut img, render_size as f32 * 0.1875, bg_color);
+    }
+
+    // Area del grafico con un piccolo margine (nessun margine se lo sfondo è trasparente)
+    let pad = if transparent { 0 } else { render_size / 8 };
+    let graph_w = render_size - pad * 2;
+    let graph_h = render_size - pad * 2;
+    // Allinea a destra finché lo storico non è pieno
+    let missing = SPARKLINE_SAMPLES.saturating_sub(samples.len());
+
+    for x in 0..graph_w {
+        let slot = x as usize * SPARKLINE_SAMPLES / graph_w as usize;
+        if let Some(&percent) = slot.checked_sub(missing).and_then(|i| samples.get(i)) {
+            let bar_h = (percent as u32 * graph_h / 100).max(1);
+            for y in (graph_h - bar_h)..graph_h {
+                img.put_pixel(pad + x, pad + y, Rgba(bar_color));
+            }
+        }
+    }
+
+    let final_img =
+        image::imageops::resize(&img, size, size, image::imageops::FilterType::Lanczos3);
+
+    Image::new_owned(final_img.into_raw(), size, size)
 }
 
 fn apply_rounded_corners(img: &mut RgbaImage, radius: f32, _bg_color: [u8; 4]) {
@@ -175,48 +251,34 @@ fn blend_colors(bg: [u8; 4], fg: [u8; 4], alpha: u8) -> [u8; 4] {
     ]
 }
 
-fn load_default_icon() -> Result<Image<'static>, String> {
-    let ico_data = include_bytes!("../../icons/icon.ico");
-
-    let img =
-        image::load_from_memory(ico_data).map_err(|e| format!("Failed to load ICO: {}", e))?;
-
-    let rgba_img = img.to_rgba8();
-    let (width, height) = rgba_img.dimensions();
-
-    let final_img = if width != ICON_SIZE || height != ICON_SIZE {
-        image::imageops::resize(
-            &rgba_img,
-            ICON_SIZE,
-            ICON_SIZE,
-            image::imageops::FilterType::Lanczos3,
-        )
-    } else {
-        rgba_img
-    };
-
-    let rgba_bytes: Vec<u8> = final_img.into_raw();
-    Ok(Image::new_owned(rgba_bytes, ICON_SIZE, ICON_SIZE))
+fn load_default_icon(size: u32) -> Result<Image<'static>, String> {
+    let rgba_bytes = crate::ui::icon_cache::tray_icon_rgba(size)?;
+    Ok(Image::new_owned(rgba_bytes, size, size))
 }
 
-// Cache per l'icona di default
-use std::sync::OnceLock;
-static DEFAULT_ICON: OnceLock<Image<'static>> = OnceLock::new();
+// Cache per l'icona di default (rigenerata se cambia il DPI)
+static DEFAULT_ICON: Lazy<Mutex<Option<(u32, Image<'static>)>>> = Lazy::new(|| Mutex::new(None));
 
 fn get_default_icon() -> Image<'static> {
-    DEFAULT_ICON
-        .get_or_init(|| {
-            load_default_icon().unwrap_or_else(|e| {
-                tracing::error!("Failed to load default icon: {}", e);
-                // Fallback: crea un'icona vuota
-                Image::new_owned(
-                    vec![0u8; (ICON_SIZE * ICON_SIZE * 4) as usize],
-                    ICON_SIZE,
-                    ICON_SIZE,
-                )
-            })
-        })
-        .clone()
+    let size = tray_icon_size();
+    let mut cache = DEFAULT_ICON.lock();
+    if let Some((cached_size, icon)) = cache.as_ref() {
+        if *cached_size == size {
+            return icon.clone();
+        }
+    }
+
+    let icon = load_default_icon(size).unwrap_or_else(|e| {
+        tracing::error!("Failed to load default icon: {}", e);
+        crate::logging::error_bus::report(
+            crate::logging::error_bus::Subsystem::Tray,
+            format!("Failed to load the tray icon: {}", e),
+        );
+        // Fallback: crea un'icona vuota
+        Image::new_owned(vec![0u8; (size * size * 4) as usize], size, size)
+    });
+    *cache = Some((size, icon.clone()));
+    icon
 }
 
 /// Update tray icon with current theme
@@ -245,9 +307,13 @@ pub fn build<R: Runtime>(_app: &AppHandle<R>) -> tauri::Result<TrayIconBuilder<R
                         .and_then(|t| t.get("show_mem_usage"))
                         .and_then(|v| v.as_bool())
                         .unwrap_or(true);
+                    let display_mode = tray_cfg
+                        .and_then(|t| t.get("display_mode"))
+                        .and_then(|v| serde_json::from_value::<TrayDisplayMode>(v.clone()).ok())
+                        .unwrap_or_default();
                     
                     // Show memory usage only if enabled AND setup is completed
-                    if show_mem && setup_completed {
+                    if show_mem && setup_completed && display_mode != TrayDisplayMode::IconOnly {
                         // Get tray colors from config
                         let bg_hex = tray_cfg
                             .and_then(|t| t.get("background_color_hex"))
@@ -261,10 +327,37 @@ pub fn build<R: Runtime>(_app: &AppHandle<R>) -> tauri::Result<TrayIconBuilder<R
                             .and_then(|t| t.get("transparent_bg"))
                             .and_then(|v| v.as_bool())
                             .unwrap_or(false);
+
+                        // With follow_system or high contrast the system decides the colors
+                        let high_contrast = json
+                            .get("accessibility")
+                            .and_then(|v| {
+                                serde_json::from_value::<AccessibilityOverrides>(v.clone()).ok()
+                            })
+                            .map(|o| accessibility::accessibility_prefs(&o).high_contrast)
+                            .unwrap_or(false);
+                        let system_colors = tray_cfg
+                            .and_then(|t| serde_json::from_value::<TrayConfig>(t.clone()).ok())
+                            .filter(|t| t.follow_system || high_contrast)
+                            .map(|t| badge_colors(&t, 0, high_contrast));
+                        let (bg_hex, text_hex) = match &system_colors {
+                            Some((bg, text)) => (bg.as_str(), text.as_str()),
+                            None => (bg_hex, text_hex),
+                        };
                         
                         tracing::info!("Tray init: theme={}, bg={}", theme, bg_hex);
                         // Create initial icon with 0% (will be updated by tray_updater)
-                        create_tray_icon(0, bg_hex, text_hex, transparent)
+                        if display_mode == TrayDisplayMode::Sparkline {
+                            create_sparkline_icon(
+                                &[],
+                                bg_hex,
+                                text_hex,
+                                transparent,
+                                tray_icon_size(),
+                            )
+                        } else {
+                            create_tray_icon(0, bg_hex, text_hex, transparent, tray_icon_size())
+                        }
                     } else {
                         get_default_icon()
                     }
@@ -287,20 +380,166 @@ fn get_tray_id() -> Option<String> {
     TRAY_ICON_ID.lock().ok().and_then(|g| g.clone())
 }
 
-fn set_tray_icon(app: &AppHandle, icon: Image<'static>, tooltip: &str) {
-    let tray_id = get_tray_id();
+// Unix timestamp dell'ultima ottimizzazione, inizializzato dallo storico
+static LAST_OPTIMIZED_AT: Lazy<Mutex<Option<u64>>> =
+    Lazy::new(|| Mutex::new(crate::stats::last_record().map(|r| r.timestamp)));
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Remember when the engine finished an optimization, for the tooltip
+pub(crate) fn note_optimization() {
+    *LAST_OPTIMIZED_AT.lock() = Some(unix_now());
+}
+
+/// Bytes as GB with one decimal, using the decimal comma where the language expects it
+fn format_gb(bytes: u64) -> String {
+    format!(
+        "{} GB",
+        crate::i18n::decimal(bytes as f64 / 1024.0 / 1024.0 / 1024.0, 1)
+    )
+}
+
+/// Time since the last optimization, e.g. "12 min ago"
+fn elapsed_text(since: u64) -> String {
+    let minutes = unix_now().saturating_sub(since) / 60;
+    let (key, value) = if minutes < 60 {
+        ("%d min", minutes)
+    } else if minutes < 48 * 60 {
+        ("%d h", minutes / 60)
+    } else {
+        ("%d d", minutes / (24 * 60))
+    };
+    let amount = crate::i18n::tr_args(key, &[value.into()]);
+    crate::i18n::tr_args("%s ago", &[amount.into()])
+}
+
+/// Tooltip line summing up the last optimization
+fn last_result_line() -> Option<String> {
+    let elapsed = LAST_OPTIMIZED_AT.lock().map(elapsed_text);
+    let result = match crate::last_optimization_result() {
+        Some(result) => result,
+        None => {
+            // Ottimizzazione di una sessione precedente: solo da quanto tempo
+            return elapsed.map(|e| crate::i18n::tr_args("Last optimization: %s", &[e.into()]));
+        }
+    };
+    let freed_mb = result.freed_physical_bytes.max(0) as f64 / 1024.0 / 1024.0;
+    let mut line = crate::i18n::tr_args(
+        "Last optimization: %s freed",
+        &[format!("{:.0} MB", freed_mb).into()],
+    );
+    if let Some(elapsed) = elapsed {
+        line.push_str(", ");
+        line.push_str(&elapsed);
+    }
+    let failed = result.areas.iter().filter(|a| a.error.is_some()).count();
+    if failed > 0 {
+        line.push_str(", ");
+        line.push_str(&crate::i18n::tr_args("%d areas failed", &[failed.into()]));
+    }
+    Some(line)
+}
+
+/// RAM usage, used/free physical memory and standby list size
+fn memory_tooltip(app: &AppHandle, mem_percent: u8) -> String {
+    let state = app.state::<crate::AppState>();
+    let mut tooltip = crate::i18n::tr_args("RAM: %d%", &[mem_percent.into()]);
+
+    if let Ok(mem) = state.engine.memory() {
+        tooltip.push('\n');
+        tooltip.push_str(&crate::i18n::tr_args(
+            "Used: %s • Free: %s",
+            &[
+                format_gb(mem.physical.used.bytes).into(),
+                format_gb(mem.physical.free.bytes).into(),
+            ],
+        ));
+    }
+    if let Ok(standby) = crate::memory::ops::standby_breakdown() {
+        tooltip.push('\n');
+        tooltip.push_str(&crate::i18n::tr_args(
+            "Standby: %s",
+            &[format_gb(standby.total_bytes).into()],
+        ));
+    }
+    tooltip
+}
 
-    let tray = tray_id
+fn with_last_result(tooltip: &str) -> String {
+    match last_result_line() {
+        Some(line) => format!("{}\n{}", tooltip, line),
+        None => tooltip.to_string(),
+    }
+}
+
+pub(crate) fn find_tray(app: &AppHandle) -> Option<tauri::tray::TrayIcon> {
+    get_tray_id()
         .as_ref()
         .and_then(|id| app.tray_by_id(id))
-        .or_else(|| app.tray_by_id("main"));
+        .or_else(|| app.tray_by_id("main"))
+}
 
-    if let Some(tray) = tray {
+fn set_tray_icon(app: &AppHandle, icon: Image<'static>, tooltip: &str) {
+    if let Some(tray) = find_tray(app) {
         let _ = tray.set_icon(Some(icon));
-        let _ = tray.set_tooltip(Some(tooltip.to_string()));
+        let _ = tray.set_tooltip(Some(with_last_result(tooltip)));
     }
 }
 
+/// Refresh only the tooltip, for cycles where the icon itself does not change
+fn set_tray_tooltip(app: &AppHandle, tooltip: &str) {
+    if let Some(tray) = find_tray(app) {
+        let _ = tray.set_tooltip(Some(with_last_result(tooltip)));
+    }
+}
+
+/// Background and text color of the badge at `mem_percent`
+///
+/// Warning and danger keep their configured background. With `follow_system`
+/// the normal background comes from the taskbar theme and the text is black
+/// or white, whichever reads better on what is behind it. In high contrast
+/// mode the colors of the high contrast theme replace all of them.
+fn badge_colors(tray_cfg: &TrayConfig, mem_percent: u8, high_contrast: bool) -> (String, String) {
+    if high_contrast {
+        let palette = accessibility::high_contrast_palette();
+        let alert = mem_percent >= tray_cfg.warning_level.min(tray_cfg.danger_level);
+        return match (alert, tray_cfg.transparent_bg) {
+            (true, false) => (palette.alert_background, palette.alert_text),
+            _ => (palette.background, palette.text),
+        };
+    }
+
+    let theme = tray_cfg.follow_system.then(system_theme::taskbar_theme);
+
+    let bg = if mem_percent >= tray_cfg.danger_level {
+        tray_cfg.danger_color_hex.clone()
+    } else if mem_percent >= tray_cfg.warning_level {
+        tray_cfg.warning_color_hex.clone()
+    } else {
+        match theme {
+            Some(theme) => system_theme::rgb_hex(theme.badge_color()),
+            None => tray_cfg.background_color_hex.clone(),
+        }
+    };
+
+    let text = match theme {
+        Some(theme) if tray_cfg.transparent_bg => {
+            system_theme::rgb_hex(theme.transparent_text_color())
+        }
+        Some(_) => {
+            let [r, g, b, _] = hex_to_rgba(&bg);
+            system_theme::rgb_hex(system_theme::contrasting_text([r, g, b]))
+        }
+        None => tray_cfg.text_color_hex.clone(),
+    };
+    (bg, text)
+}
+
 pub fn update_tray_icon(app: &AppHandle, mut mem_percent: u8) {
     // CORREZIONE 2: Risolve errore lifetime 'state does not live long enough'
     let state = app.state::<crate::AppState>();
@@ -318,49 +557,40 @@ pub fn update_tray_icon(app: &AppHandle, mut mem_percent: u8) {
         }
     }
 
-    let tray_cfg = match state.cfg.try_lock() {
-        Ok(cfg) => cfg.tray.clone(),
-        Err(_) => {
-            // Lock occupato, riprova dopo
-            tracing::debug!("Config lock busy, skipping update");
-            return;
-        }
-    };
+    let (tray_cfg, overrides) = state.cfg.with(|c| (c.tray.clone(), c.accessibility));
 
-    if !tray_cfg.show_mem_usage {
+    let display_mode = tray_cfg.effective_display_mode();
+    if display_mode == TrayDisplayMode::IconOnly {
         set_tray_icon(app, get_default_icon(), "Memory Cleaner");
         return;
     }
 
-    let bg = if mem_percent >= tray_cfg.danger_level {
-        &tray_cfg.danger_color_hex
-    } else if mem_percent >= tray_cfg.warning_level {
-        &tray_cfg.warning_color_hex
-    } else {
-        &tray_cfg.background_color_hex
-    };
-
-    let icon = create_tray_icon(
-        mem_percent,
-        bg,
-        &tray_cfg.text_color_hex,
-        tray_cfg.transparent_bg,
-    );
+    let high_contrast = accessibility::accessibility_prefs(&overrides).high_contrast;
+    let (bg, text) = badge_colors(&tray_cfg, mem_percent, high_contrast);
 
-    // Try to get translated tooltip
-    let tooltip = {
-        let translated = crate::commands::get_translation(&state.translations, "RAM: %d%");
-
-        // If translation is empty, use English format
-        if translated.is_empty() {
-            format!("RAM: {}%", mem_percent)
-        } else {
-            // Replace placeholder with actual percentage
-            translated.replace("%d%", &mem_percent.to_string())
+    let icon = if display_mode == TrayDisplayMode::Sparkline {
+        let mut samples = ram_history();
+        if samples.is_empty() {
+            samples.push(mem_percent);
         }
+        create_sparkline_icon(
+            &samples,
+            &bg,
+            &text,
+            tray_cfg.transparent_bg,
+            tray_icon_size(),
+        )
+    } else {
+        create_tray_icon(
+            mem_percent,
+            &bg,
+            &text,
+            tray_cfg.transparent_bg,
+            tray_icon_size(),
+        )
     };
 
-    set_tray_icon(app, icon, &tooltip);
+    set_tray_icon(app, icon, &memory_tooltip(app, mem_percent));
 }
 
 /// Forza refresh dell'icona (chiamato quando cambia la config)
@@ -371,9 +601,7 @@ pub fn refresh_tray_icon(app: &AppHandle) {
         // Percentuale 0 come placeholder, verrà aggiornata dal loop se necessario
         let show = state
             .cfg
-            .lock()
-            .map(|c| c.tray.show_mem_usage)
-            .unwrap_or(true);
+            .with(|c| c.tray.effective_display_mode() != TrayDisplayMode::IconOnly);
         (show, 0u8)
     };
 
@@ -385,56 +613,171 @@ pub fn refresh_tray_icon(app: &AppHandle) {
     }
 }
 
-pub fn start_tray_updater(app: AppHandle, engine: Engine) {
-    tauri::async_runtime::spawn(async move {
-        let mut last_percent: f32 = -1.0; // Inizializza a valore impossibile
-
-        loop {
-            // FIX #12: Clona la configurazione del tray PRIMA di chiamare memory() per evitare race conditions
-            // Questo assicura che anche se la config cambia durante l'esecuzione, usiamo valori consistenti
-            let tray_cfg_opt = {
-                let state = app.state::<crate::AppState>();
-                let cfg_result = match state.cfg.try_lock() {
-                    Ok(cfg) => Some(cfg.tray.clone()),
-                    Err(_) => {
-                        // Lock occupato, salta questo ciclo
-                        tracing::debug!("Config lock busy in start_tray_updater, skipping cycle");
-                        None
-                    }
-                };
-                // Se il lock è occupato, aspetta e continua
-                if cfg_result.is_none() {
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                    continue;
-                }
-                cfg_result
-            };
-
-            // Se la configurazione non mostra l'uso della memoria, usa l'icona di default
-            if let Some(ref tray_cfg) = tray_cfg_opt {
-                if !tray_cfg.show_mem_usage {
-                    set_tray_icon(&app, get_default_icon(), "Memory Cleaner");
-                    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
-                    continue;
-                }
+/// Menu item ids of the native tray menu; optimize entries end with the profile
+const MENU_OPEN: &str = "tray_open";
+const MENU_OPTIMIZE_PREFIX: &str = "tray_optimize:";
+const MENU_EXIT: &str = "tray_exit";
+
+/// Built-in profiles offered in the native menu's optimize submenu
+const MENU_PROFILES: [Profile; 3] = [Profile::Normal, Profile::Balanced, Profile::Gaming];
+
+/// Native right-click menu: open, optimize with a given profile, exit
+fn native_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let tr = crate::i18n::tr;
+    let profiles = MENU_PROFILES
+        .iter()
+        .map(|p| {
+            let name = format!("{:?}", p);
+            MenuItem::with_id(
+                app,
+                format!("{}{}", MENU_OPTIMIZE_PREFIX, name),
+                tr(&name),
+                true,
+                None::<&str>,
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let profile_items: Vec<&dyn IsMenuItem<Wry>> =
+        profiles.iter().map(|i| i as &dyn IsMenuItem<Wry>).collect();
+    let optimize = Submenu::with_items(app, tr("Optimize Memory"), true, &profile_items)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, MENU_OPEN, tr("Open TMC"), true, None::<&str>)?,
+            &optimize,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, MENU_EXIT, tr("Exit"), true, None::<&str>)?,
+        ],
+    )
+}
+
+/// Attach or detach the native menu after `tray.native_menu` or the language changed
+pub fn apply_native_menu(app: &AppHandle) {
+    let Some(tray) = find_tray(app) else {
+        return;
+    };
+    let enabled = app
+        .try_state::<crate::AppState>()
+        .is_some_and(|s| s.cfg.with(|c| c.tray.native_menu));
+    let menu = if enabled {
+        match native_menu(app) {
+            Ok(menu) => Some(menu),
+            Err(e) => {
+                tracing::warn!("Failed to build the native tray menu: {}", e);
+                None
             }
+        }
+    } else {
+        None
+    };
+    if let Err(e) = tray.set_menu(menu) {
+        tracing::warn!("Failed to set the native tray menu: {}", e);
+    }
+    let _ = tray.set_show_menu_on_left_click(false);
+}
+
+/// Run a native tray menu entry
+///
+/// An optimize entry runs with its profile's areas and leaves the
+/// configured profile as it is.
+pub fn handle_menu_event(app: &AppHandle, id: &str) {
+    if id == MENU_OPEN {
+        crate::commands::ui::show_or_create_window(app);
+    } else if id == MENU_EXIT {
+        crate::commands::config::cmd_exit(app.clone());
+    } else if let Some(name) = id.strip_prefix(MENU_OPTIMIZE_PREFIX) {
+        let Some(profile) = MENU_PROFILES.iter().find(|p| format!("{:?}", p) == name) else {
+            return;
+        };
+        let Some(state) = app.try_state::<crate::AppState>() else {
+            return;
+        };
+        let (cfg, engine) = (state.cfg.clone(), state.engine.clone());
+        let areas = profile.get_memory_areas();
+        tracing::info!("Tray optimization with the {} profile", name);
+        crate::stats::usage::mark_next_from_tray();
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            crate::perform_optimization(app, engine, cfg, Reason::Manual, true, Some(areas), None)
+                .await;
+        });
+    }
+}
+
+/// Start the tray updater under the background task supervisor
+pub fn start_tray_updater(app: AppHandle, engine: Engine) {
+    crate::system::supervisor::supervise(BackgroundTask::TrayUpdater, move || {
+        tray_updater_loop(app.clone(), engine.clone())
+    });
+}
+
+async fn tray_updater_loop(app: AppHandle, engine: Engine) {
+    let mut last_percent: f32 = -1.0; // Inizializza a valore impossibile
+    let mut last_icon_size = tray_icon_size();
+    let mut last_theme = None;
+    let mut last_prefs = None;
+
+    loop {
+        // FIX #12: Clona la configurazione del tray PRIMA di chiamare memory() per evitare race conditions
+        // Questo assicura che anche se la config cambia durante l'esecuzione, usiamo valori consistenti
+        let (tray_cfg, overrides) = {
+            let state = app.state::<crate::AppState>();
+            let cfg = state.cfg.read();
+            (cfg.tray.clone(), cfg.accessibility)
+        };
+
+        // High contrast or animations switched: tell the UI and redraw the badge
+        let prefs = accessibility::accessibility_prefs(&overrides);
+        if last_prefs.is_some_and(|last| last != prefs) {
+            let _ = app.emit(crate::ui::bridge::EV_ACCESSIBILITY_CHANGED, prefs);
+        }
+        let prefs_changed = last_prefs != Some(prefs);
+        last_prefs = Some(prefs);
 
-            // Ora ottieni la memoria e aggiorna l'icona solo se cambia significativamente
+        // Se la configurazione non mostra l'uso della memoria, usa l'icona di default
+        if tray_cfg.effective_display_mode() == TrayDisplayMode::IconOnly {
             if let Ok(mem) = engine.memory() {
-                // Clamp percentage tra 0-100 (dovrebbe essere già nel range, ma per sicurezza)
-                let current_percent = mem.physical.used.percentage.min(100) as f32;
-
-                // Aggiorna solo se la variazione è > 0.5% o è il primo ciclo
-                if last_percent < 0.0 || (current_percent - last_percent).abs() > 0.5 {
-                    update_tray_icon(&app, current_percent as u8);
-                    last_percent = current_percent;
-                    #[cfg(debug_assertions)]
-                    tracing::debug!("Tray icon updated: {:.1}% (change > 0.5%)", current_percent);
-                } else {
-                    // No update needed - change too small
-                }
+                crate::stats::session::note_memory(&mem);
             }
+            set_tray_icon(&app, get_default_icon(), "Memory Cleaner");
             tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            continue;
         }
-    });
+
+        // Ora ottieni la memoria e aggiorna l'icona solo se cambia significativamente
+        if let Ok(mem) = engine.memory() {
+            // Clamp percentage tra 0-100 (dovrebbe essere già nel range, ma per sicurezza)
+            let current_percent = mem.physical.used.percentage.min(100) as f32;
+            record_ram_sample(current_percent as u8);
+            crate::stats::session::note_memory(&mem);
+            let sparkline = tray_cfg.effective_display_mode() == TrayDisplayMode::Sparkline;
+
+            // Taskbar theme switched at runtime (only watched with follow_system)
+            let theme = tray_cfg.follow_system.then(system_theme::taskbar_theme);
+
+            // Aggiorna solo se la variazione è > 0.5%, è il primo ciclo o è cambiato il DPI
+            // (il grafico scorre a ogni campione, quindi va ridisegnato sempre)
+            let icon_size = tray_icon_size();
+            if sparkline
+                || last_percent < 0.0
+                || (current_percent - last_percent).abs() > 0.5
+                || icon_size != last_icon_size
+                || theme != last_theme
+                || prefs_changed
+            {
+                update_tray_icon(&app, current_percent as u8);
+                last_percent = current_percent;
+                last_icon_size = icon_size;
+                last_theme = theme;
+                #[cfg(debug_assertions)]
+                tracing::debug!("Tray icon updated: {:.1}% (change > 0.5%)", current_percent);
+            } else {
+                // Icona invariata, ma il tooltip mostra dati che cambiano comunque
+                set_tray_tooltip(&app, &memory_tooltip(&app, current_percent as u8));
+            }
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    }
 }
@@ -0,0 +1,465 @@
+/// Update checks against GitHub releases
+///
+/// `check_for_update` reads the latest release, compares its tag with the
+/// running version and picks the asset for the current install: the setup
+/// executable, or the portable zip when running in portable mode. The asset
+/// is only applied after `download_update` verified it against the signed
+/// checksum list and checked the signer of the executable (see `verify`).
+///
+/// While `auto_update` is enabled (off by default) a background thread
+/// checks once a day and tells the UI through the `update-available` event;
+/// updates are never installed without the user asking for it.
+pub mod verify;
+pub mod version;
+
+use crate::config::{app_info, get_portable_detector, SharedConfig};
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use version::Version;
+
+const LATEST_RELEASE_URL: &str =
+    "https://api.github.com/repos/tommy4377/Tommy-Memory-Cleaner/releases/latest";
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+/// Detached PKCS#7 signature of `CHECKSUMS_ASSET`
+const CHECKSUMS_SIGNATURE_ASSET: &str = "SHA256SUMS.p7";
+const UPDATES_DIR_NAME: &str = "updates";
+/// Folder the portable zip is unpacked into before its signature is checked
+const STAGED_DIR_NAME: &str = "staged";
+const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+const MAX_DOWNLOAD_BYTES: u64 = 200 * 1024 * 1024;
+const MAX_CHECKSUMS_BYTES: u64 = 64 * 1024;
+const MAX_SIGNATURE_BYTES: u64 = 64 * 1024;
+/// First background check, after the startup work has settled
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(120);
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+/// How often the background thread re-reads `auto_update`
+const FLAG_POLL_INTERVAL: Duration = Duration::from_secs(60);
+pub const EV_UPDATE_AVAILABLE: &str = "update-available";
+
+static CHECKER_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Release found by the last check, used by `download_update`
+static LAST_RELEASE: Lazy<Mutex<Option<Release>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    html_url: String,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+}
+
+/// What the installed copy needs from a release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    Installer,
+    Portable,
+}
+
+#[derive(Debug, Clone)]
+struct Release {
+    version: Version,
+    package: PackageKind,
+    asset: GitHubAsset,
+    checksums: GitHubAsset,
+    checksums_signature: GitHubAsset,
+}
+
+/// Outcome of an update check, returned to the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateInfo {
+    pub current_version: String,
+    pub latest_version: String,
+    pub update_available: bool,
+    pub package: PackageKind,
+    /// File `cmd_apply_update` downloads, `None` if the release has none for
+    /// this install or no signed checksum list
+    pub asset_name: Option<String>,
+    pub asset_size: u64,
+    pub release_notes: String,
+    pub release_url: String,
+    pub published_at: Option<String>,
+}
+
+/// A verified download ready to be applied
+#[derive(Debug, Clone)]
+pub struct DownloadedUpdate {
+    /// The installer, or the folder the portable zip was unpacked into
+    pub path: PathBuf,
+    pub package: PackageKind,
+    pub version: Version,
+}
+
+fn package_kind() -> PackageKind {
+    if get_portable_detector().is_portable_mode() {
+        PackageKind::Portable
+    } else {
+        PackageKind::Installer
+    }
+}
+
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(timeout)
+        .user_agent(&format!("TommyMemoryCleaner/{}", app_info::VERSION))
+        .build()
+}
+
+fn pick_asset(assets: &[GitHubAsset], package: PackageKind) -> Option<&GitHubAsset> {
+    assets.iter().find(|a| {
+        let name = a.name.to_lowercase();
+        match package {
+            PackageKind::Installer => name.ends_with("-setup.exe"),
+            PackageKind::Portable => name.ends_with(".zip") && name.contains("portable"),
+        }
+    })
+}
+
+/// Check GitHub for a release newer than the running version
+pub fn check_for_update() -> Result<UpdateInfo> {
+    let release: GitHubRelease = agent(HTTP_TIMEOUT)
+        .get(LATEST_RELEASE_URL)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .context("Failed to reach the release server")?
+        .into_json()
+        .context("Invalid release information")?;
+
+    let current = Version::parse(app_info::VERSION)
+        .with_context(|| format!("Invalid app version {}", app_info::VERSION))?;
+    let latest = Version::parse(&release.tag_name)
+        .with_context(|| format!("Release tag '{}' is not a version", release.tag_name))?;
+    let package = package_kind();
+    let find = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|a| a.name.eq_ignore_ascii_case(name))
+            .cloned()
+    };
+    let checksums = find(CHECKSUMS_ASSET);
+    let checksums_signature = find(CHECKSUMS_SIGNATURE_ASSET);
+    // Without the signed checksum list nothing in the release can be verified
+    let asset = match (&checksums, &checksums_signature) {
+        (Some(_), Some(_)) => pick_asset(&release.assets, package).cloned(),
+        _ => None,
+    };
+
+    // The latest endpoint skips drafts and pre-releases, but be defensive
+    let update_available =
+        !release.draft && !release.prerelease && !latest.is_prerelease() && latest > current;
+
+    *LAST_RELEASE.lock() = match (&asset, checksums, checksums_signature) {
+        (Some(asset), Some(checksums), Some(checksums_signature)) if update_available => {
+            Some(Release {
+                version: latest.clone(),
+                package,
+                asset: asset.clone(),
+                checksums,
+                checksums_signature,
+            })
+        }
+        _ => None,
+    };
+
+    Ok(UpdateInfo {
+        current_version: current.to_string(),
+        latest_version: latest.to_string(),
+        update_available,
+        package,
+        asset_name: asset.as_ref().map(|a| a.name.clone()),
+        asset_size: asset.as_ref().map(|a| a.size).unwrap_or(0),
+        release_notes: release.body.unwrap_or_default(),
+        release_url: release.html_url,
+        published_at: release.published_at,
+    })
+}
+
+fn updates_dir() -> PathBuf {
+    get_portable_detector().data_dir().join(UPDATES_DIR_NAME)
+}
+
+/// Download the release found by the last check and verify it
+pub fn download_update() -> Result<DownloadedUpdate> {
+    let release = match LAST_RELEASE.lock().clone() {
+        Some(release) => release,
+        None => bail!("No update available, check for updates first"),
+    };
+
+    let checksums = fetch_bytes(&release.checksums.browser_download_url, MAX_CHECKSUMS_BYTES)?;
+    let signature = fetch_bytes(
+        &release.checksums_signature.browser_download_url,
+        MAX_SIGNATURE_BYTES,
+    )?;
+    verify::check_checksums_signature(&checksums, &signature)?;
+    let checksums = std::str::from_utf8(&checksums).context("Invalid checksum file")?;
+    let expected = match verify::expected_sha256(checksums, &release.asset.name) {
+        Some(hash) => hash,
+        None => bail!(
+            "{} is not listed in {}",
+            release.asset.name,
+            CHECKSUMS_ASSET
+        ),
+    };
+
+    let dir = updates_dir();
+    // Keep only one download around
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).context("Failed to create the updates folder")?;
+    let path = dir.join(safe_file_name(&release.asset.name));
+
+    let actual = download_to(&release.asset.browser_download_url, &path)?;
+    let verified = verify::check_sha256(&actual, &expected).and_then(|_| match release.package {
+        PackageKind::Installer => verify::check_signature(&path).map(|_| path.clone()),
+        PackageKind::Portable => stage_portable(&path, &dir),
+    });
+    let path = match verified {
+        Ok(path) => path,
+        Err(e) => {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+
+    tracing::info!("✓ Update {} downloaded and verified", release.version);
+    Ok(DownloadedUpdate {
+        path,
+        package: release.package,
+        version: release.version,
+    })
+}
+
+/// Asset names come from the network, never let them leave the updates folder
+fn safe_file_name(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_' | ' '))
+        .collect();
+    match cleaned.trim_start_matches('.') {
+        "" => "update.bin".to_string(),
+        rest => rest.to_string(),
+    }
+}
+
+fn fetch_bytes(url: &str, limit: u64) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    agent(HTTP_TIMEOUT)
+        .get(url)
+        .call()
+        .with_context(|| format!("Failed to download {}", url))?
+        .into_reader()
+        .take(limit)
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to read {}", url))?;
+    Ok(data)
+}
+
+/// Stream `url` into `path`, returns the SHA-256 of the data (lowercase hex)
+fn download_to(url: &str, path: &Path) -> Result<String> {
+    let mut reader = agent(DOWNLOAD_TIMEOUT)
+        .get(url)
+        .call()
+        .context("Failed to download the update")?
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1);
+    let mut file =
+        File::create(path).with_context(|| format!("Failed to create {}", path.display()))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut total = 0u64;
+    loop {
+        let n = reader.read(&mut buffer).context("Download interrupted")?;
+        if n == 0 {
+            break;
+        }
+        total += n as u64;
+        if total > MAX_DOWNLOAD_BYTES {
+            drop(file);
+            let _ = fs::remove_file(path);
+            bail!(
+                "Update is larger than {} MB",
+                MAX_DOWNLOAD_BYTES / 1024 / 1024
+            );
+        }
+        hasher.update(&buffer[..n]);
+        file.write_all(&buffer[..n])
+            .context("Failed to write the update")?;
+    }
+    file.sync_all().context("Failed to write the update")?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Unpack the portable zip next to it and check the signer of its executable
+///
+/// Returns the staged folder; nothing is copied over the install until the
+/// executable in it passed `verify::check_signature`.
+fn stage_portable(zip: &Path, dir: &Path) -> Result<PathBuf> {
+    let staged = dir.join(STAGED_DIR_NAME);
+    let script = format!(
+        "$ErrorActionPreference = 'Stop'; \
+         Expand-Archive -LiteralPath '{}' -DestinationPath '{}' -Force",
+        ps_quote(zip),
+        ps_quote(&staged)
+    );
+    let status = powershell(&script)
+        .status()
+        .context("Failed to unpack the update")?;
+    if !status.success() {
+        bail!("Failed to unpack the update");
+    }
+
+    let exe_name = match get_portable_detector().exe_path().file_name() {
+        Some(name) => name.to_owned(),
+        None => bail!("Executable path has no file name"),
+    };
+    let exe = staged.join(&exe_name);
+    if !exe.is_file() {
+        bail!("The update does not contain {}", exe_name.to_string_lossy());
+    }
+    verify::check_signature(&exe)?;
+    Ok(staged)
+}
+
+fn ps_quote(path: &Path) -> String {
+    path.to_string_lossy().replace('\'', "''")
+}
+
+fn powershell(script: &str) -> std::process::Command {
+    let mut cmd = std::process::Command::new("powershell");
+    cmd.args(["-NoProfile", "-NonInteractive", "-Command", script]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    cmd
+}
+
+/// Start the verified update and exit
+///
+/// The installer runs on its own; the staged portable files are copied over
+/// the executable folder by a helper that waits for TMC to exit and restarts it.
+pub fn apply_update(app: &AppHandle, update: &DownloadedUpdate) -> Result<()> {
+    match update.package {
+        PackageKind::Installer => {
+            std::process::Command::new(&update.path)
+                .spawn()
+                .context("Failed to start the installer")?;
+        }
+        PackageKind::Portable => spawn_portable_replace(&update.path)?,
+    }
+    tracing::info!("Applying update {}, exiting", update.version);
+    app.exit(0);
+    Ok(())
+}
+
+fn spawn_portable_replace(staged: &Path) -> Result<()> {
+    let exe = get_portable_detector().exe_path().clone();
+    let exe_dir = match exe.parent() {
+        Some(dir) => dir.to_path_buf(),
+        None => bail!("Executable path has no parent directory"),
+    };
+    let script = format!(
+        "Wait-Process -Id {} -ErrorAction SilentlyContinue; \
+         Get-ChildItem -LiteralPath '{}' | Copy-Item -Destination '{}' -Recurse -Force; \
+         Start-Process -FilePath '{}'",
+        std::process::id(),
+        ps_quote(staged),
+        ps_quote(&exe_dir),
+        ps_quote(&exe)
+    );
+
+    powershell(&script)
+        .spawn()
+        .context("Failed to start the update helper")?;
+    Ok(())
+}
+
+fn auto_update_enabled(cfg: &SharedConfig) -> bool {
+    cfg.with(|c| c.auto_update)
+}
+
+fn notify_update(app: &AppHandle, cfg: &SharedConfig, info: &UpdateInfo) {
+    let (show, theme) = cfg.with(|c| (c.show_opt_notifications, c.theme.clone()));
+    if !show {
+        return;
+    }
+    let title = crate::i18n::tr("TMC • Update available");
+    let body = crate::i18n::tr_args("Version %s is available", &[(&info.latest_version).into()]);
+    if let Err(e) = crate::notifications::show_windows_notification(app, &title, &body, &theme) {
+        tracing::debug!("Failed to show update notification: {}", e);
+    }
+}
+
+/// Start the daily background check if `auto_update` is on and it is not already running
+pub fn start_update_checker(app: AppHandle, cfg: SharedConfig) {
+    if !auto_update_enabled(&cfg) {
+        return;
+    }
+    if CHECKER_RUNNING.swap(true, Ordering::SeqCst) {
+        tracing::debug!("Update checker already running");
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("tmc-update-check".into())
+        .spawn(move || {
+            let mut next_check = Instant::now() + FIRST_CHECK_DELAY;
+            let mut notified: Option<String> = None;
+            // Re-read the flag every poll so disabling auto_update stops the thread
+            while auto_update_enabled(&cfg) {
+                if Instant::now() >= next_check {
+                    next_check = Instant::now() + CHECK_INTERVAL;
+                    match check_for_update() {
+                        Ok(info) if info.update_available && info.asset_name.is_some() => {
+                            if notified.as_deref() != Some(info.latest_version.as_str()) {
+                                tracing::info!("Update {} is available", info.latest_version);
+                                notified = Some(info.latest_version.clone());
+                                let _ = app.emit(EV_UPDATE_AVAILABLE, &info);
+                                notify_update(&app, &cfg, &info);
+                            }
+                        }
+                        Ok(_) => tracing::debug!("No update available"),
+                        // Offline is normal, try again tomorrow
+                        Err(e) => tracing::warn!("Update check failed: {:#}", e),
+                    }
+                }
+                std::thread::sleep(FLAG_POLL_INTERVAL);
+            }
+            CHECKER_RUNNING.store(false, Ordering::SeqCst);
+            tracing::info!("Update checker stopped");
+        });
+
+    if let Err(e) = spawned {
+        CHECKER_RUNNING.store(false, Ordering::SeqCst);
+        tracing::error!("Failed to start update checker thread: {}", e);
+    }
+}
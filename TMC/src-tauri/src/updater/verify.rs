@@ -0,0 +1,281 @@
+/// Download verification
+///
+/// Every release ships a `SHA256SUMS` asset and its detached PKCS#7
+/// signature `SHA256SUMS.p7`; a download whose hash is missing from the
+/// signed list or does not match is deleted. Executables must also carry a
+/// valid Authenticode signature. Both signatures must come from the
+/// certificate pinned by `certificateThumbprint` in tauri.conf.json, so a
+/// build without one cannot install updates.
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use std::path::Path;
+
+/// SHA-1 thumbprint (uppercase hex) of the release signing certificate
+static SIGNING_THUMBPRINT: Lazy<Option<String>> = Lazy::new(|| {
+    let conf: serde_json::Value =
+        serde_json::from_str(include_str!("../../tauri.conf.json")).ok()?;
+    conf.pointer("/bundle/windows/certificateThumbprint")
+        .and_then(|v| v.as_str())
+        .and_then(normalize_thumbprint)
+});
+
+/// Expected SHA-256 (lowercase hex) of `asset_name` in a checksum file
+///
+/// Accepts `sha256sum` output (`<hash>  <name>` or `<hash> *<name>`) and
+/// single-hash `.sha256` files.
+pub fn expected_sha256(checksums: &str, asset_name: &str) -> Option<String> {
+    let is_hash = |h: &str| h.len() == 64 && h.chars().all(|c| c.is_ascii_hexdigit());
+
+    for line in checksums.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let (hash, name) = match line.split_once(char::is_whitespace) {
+            Some((hash, name)) => (hash, name.trim().trim_start_matches('*')),
+            None => (line, ""),
+        };
+        if !is_hash(hash) {
+            continue;
+        }
+        if name.is_empty() || name.eq_ignore_ascii_case(asset_name) {
+            return Some(hash.to_lowercase());
+        }
+    }
+    None
+}
+
+/// Fail unless `actual` matches the expected hash
+pub fn check_sha256(actual: &str, expected: &str) -> Result<()> {
+    if !actual.eq_ignore_ascii_case(expected) {
+        bail!(
+            "Checksum mismatch: expected {}, downloaded file has {}",
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Thumbprint as 40 uppercase hex digits, ignoring spaces and colons
+fn normalize_thumbprint(raw: &str) -> Option<String> {
+    let hex: String = raw
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ':')
+        .collect::<String>()
+        .to_uppercase();
+    (hex.len() == 40 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// Fail unless `thumbprint` is the pinned signing certificate
+fn check_signer(thumbprint: Option<String>) -> Result<()> {
+    let pinned = match SIGNING_THUMBPRINT.as_deref() {
+        Some(pinned) => pinned,
+        None => {
+            bail!("This build has no signing certificate configured, updates cannot be verified")
+        }
+    };
+    match thumbprint {
+        Some(t) if t == pinned => Ok(()),
+        Some(t) => bail!("Signed by an unexpected certificate ({})", t),
+        None => bail!("Could not read the signing certificate"),
+    }
+}
+
+/// SHA-1 thumbprint of a certificate
+#[cfg(windows)]
+unsafe fn cert_thumbprint(
+    cert: *const windows_sys::Win32::Security::Cryptography::CERT_CONTEXT,
+) -> Option<String> {
+    use windows_sys::Win32::Security::Cryptography::{
+        CertGetCertificateContextProperty, CERT_SHA1_HASH_PROP_ID,
+    };
+
+    if cert.is_null() {
+        return None;
+    }
+    let mut hash = [0u8; 20];
+    let mut len = hash.len() as u32;
+    if CertGetCertificateContextProperty(
+        cert,
+        CERT_SHA1_HASH_PROP_ID,
+        hash.as_mut_ptr() as *mut _,
+        &mut len,
+    ) == 0
+    {
+        return None;
+    }
+    Some(
+        hash[..len as usize]
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect(),
+    )
+}
+
+/// Check that `signature` is a detached PKCS#7 signature of `data` made
+/// with the pinned certificate
+#[cfg(windows)]
+pub fn check_checksums_signature(data: &[u8], signature: &[u8]) -> Result<()> {
+    use windows_sys::Win32::Security::Cryptography::{
+        CertFreeCertificateContext, CryptVerifyDetachedMessageSignature, CERT_CONTEXT,
+        CRYPT_VERIFY_MESSAGE_PARA, PKCS_7_ASN_ENCODING, X509_ASN_ENCODING,
+    };
+
+    unsafe {
+        let mut para: CRYPT_VERIFY_MESSAGE_PARA = std::mem::zeroed();
+        para.cbSize = std::mem::size_of::<CRYPT_VERIFY_MESSAGE_PARA>() as u32;
+        para.dwMsgAndCertEncodingType = PKCS_7_ASN_ENCODING | X509_ASN_ENCODING;
+
+        let parts = [data.as_ptr()];
+        let sizes = [data.len() as u32];
+        let mut signer: *mut CERT_CONTEXT = std::ptr::null_mut();
+        let ok = CryptVerifyDetachedMessageSignature(
+            &para,
+            0,
+            signature.as_ptr(),
+            signature.len() as u32,
+            1,
+            parts.as_ptr(),
+            sizes.as_ptr(),
+            &mut signer,
+        );
+        if ok == 0 {
+            if !signer.is_null() {
+                CertFreeCertificateContext(signer);
+            }
+            bail!("Invalid checksum file signature");
+        }
+
+        let thumbprint = cert_thumbprint(signer);
+        CertFreeCertificateContext(signer);
+        check_signer(thumbprint)
+    }
+}
+
+#[cfg(not(windows))]
+pub fn check_checksums_signature(_data: &[u8], _signature: &[u8]) -> Result<()> {
+    check_signer(None)
+}
+
+/// Check the Authenticode signature of an executable
+///
+/// The file must be signed, the signature must verify and the signer must
+/// be the pinned certificate.
+#[cfg(windows)]
+pub fn check_signature(path: &Path) -> Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Foundation::TRUST_E_NOSIGNATURE;
+    use windows_sys::Win32::Security::WinTrust::{
+        WTHelperGetProvCertFromChain, WTHelperGetProvSignerFromChain,
+        WTHelperProvDataFromStateData, WinVerifyTrust, WINTRUST_ACTION_GENERIC_VERIFY_V2,
+        WINTRUST_DATA, WINTRUST_FILE_INFO, WTD_CHOICE_FILE, WTD_REVOKE_NONE, WTD_STATEACTION_CLOSE,
+        WTD_STATEACTION_VERIFY, WTD_UI_NONE,
+    };
+
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    unsafe {
+        let mut file_info: WINTRUST_FILE_INFO = std::mem::zeroed();
+        file_info.cbStruct = std::mem::size_of::<WINTRUST_FILE_INFO>() as u32;
+        file_info.pcwszFilePath = wide.as_ptr();
+
+        let mut data: WINTRUST_DATA = std::mem::zeroed();
+        data.cbStruct = std::mem::size_of::<WINTRUST_DATA>() as u32;
+        data.dwUIChoice = WTD_UI_NONE;
+        data.fdwRevocationChecks = WTD_REVOKE_NONE;
+        data.dwUnionChoice = WTD_CHOICE_FILE;
+        data.Anonymous.pFile = &mut file_info;
+        data.dwStateAction = WTD_STATEACTION_VERIFY;
+
+        let mut action = WINTRUST_ACTION_GENERIC_VERIFY_V2;
+        let status = WinVerifyTrust(
+            std::ptr::null_mut(),
+            &mut action,
+            &mut data as *mut _ as *mut _,
+        );
+
+        // The signer is only available until the state is closed
+        let thumbprint = if status == 0 {
+            let provider = WTHelperProvDataFromStateData(data.hWVTStateData);
+            let signer = if provider.is_null() {
+                std::ptr::null_mut()
+            } else {
+                WTHelperGetProvSignerFromChain(provider, 0, 0, 0)
+            };
+            let cert = if signer.is_null() {
+                std::ptr::null_mut()
+            } else {
+                WTHelperGetProvCertFromChain(signer, 0)
+            };
+            if cert.is_null() {
+                None
+            } else {
+                cert_thumbprint((*cert).pCert)
+            }
+        } else {
+            None
+        };
+
+        data.dwStateAction = WTD_STATEACTION_CLOSE;
+        WinVerifyTrust(
+            std::ptr::null_mut(),
+            &mut action,
+            &mut data as *mut _ as *mut _,
+        );
+
+        match status {
+            0 => check_signer(thumbprint),
+            s if s == TRUST_E_NOSIGNATURE => bail!("{} is not signed", file_name(path)),
+            s => bail!(
+                "Invalid signature on {} (0x{:08X})",
+                file_name(path),
+                s as u32
+            ),
+        }
+    }
+}
+
+#[cfg(windows)]
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+#[cfg(not(windows))]
+pub fn check_signature(_path: &Path) -> Result<()> {
+    check_signer(None)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expected_sha256_formats() {
+        let hash = "a".repeat(64);
+        let sums = format!(
+            "{}  TMC_2.6.0_x64-setup.exe\n{} *TMC_2.6.0_portable.zip\n",
+            hash,
+            "B".repeat(64)
+        );
+        assert_eq!(
+            expected_sha256(&sums, "TMC_2.6.0_x64-setup.exe"),
+            Some(hash.clone())
+        );
+        assert_eq!(
+            expected_sha256(&sums, "TMC_2.6.0_portable.zip"),
+            Some("b".repeat(64))
+        );
+        assert_eq!(expected_sha256(&sums, "other.zip"), None);
+        assert_eq!(expected_sha256(&hash, "anything"), Some(hash));
+    }
+
+    #[test]
+    fn test_normalize_thumbprint() {
+        assert_eq!(
+            normalize_thumbprint("a1 b2 c3 d4 e5 f6 07 18 29 3a 4b 5c 6d 7e 8f 90 01 12 23 34"),
+            Some("A1B2C3D4E5F60718293A4B5C6D7E8F9001122334".to_string())
+        );
+        assert_eq!(normalize_thumbprint("A1:B2"), None);
+        assert_eq!(normalize_thumbprint(&"g".repeat(40)), None);
+    }
+}
@@ -0,0 +1,47 @@
+//! Small helpers shared across modules
+//!
+//! Kept here so every Win32 call site converts strings and reads the
+//! registry the same way instead of carrying its own copy.
+
+/// Convert a UTF-8 string to a NUL-terminated wide string (UTF-16) for the
+/// `W` variants of the Win32 APIs
+pub fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// DWORD value under HKEY_CURRENT_USER, `None` if missing or of another type
+#[cfg(windows)]
+pub fn read_user_dword(key: &str, value: &str) -> Option<u32> {
+    use windows_sys::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
+
+    let key_w = to_wide(key);
+    let value_w = to_wide(value);
+    let mut data: u32 = 0;
+    let mut size = std::mem::size_of::<u32>() as u32;
+    let status = unsafe {
+        RegGetValueW(
+            HKEY_CURRENT_USER,
+            key_w.as_ptr(),
+            value_w.as_ptr(),
+            RRF_RT_REG_DWORD,
+            std::ptr::null_mut(),
+            &mut data as *mut u32 as *mut _,
+            &mut size,
+        )
+    };
+    (status == 0).then_some(data)
+}
+
+// ========== TESTS ==========
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_wide_is_nul_terminated() {
+        assert_eq!(to_wide("TMC"), vec![0x54, 0x4d, 0x43, 0]);
+        // Characters outside the BMP become surrogate pairs
+        assert_eq!(to_wide("é😀"), vec![0xe9, 0xd83d, 0xde00, 0]);
+        assert_eq!(to_wide(""), vec![0]);
+    }
+}
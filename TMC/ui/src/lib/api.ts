@@ -3,7 +3,21 @@
  */
 
 import { invoke } from '@tauri-apps/api/core'
-import type { Areas, MemoryInfo, Reason, Config } from './types'
+import type {
+  AreaToggleResult,
+  Areas,
+  CompressionInfo,
+  FileCacheInfo,
+  MemoryInfo,
+  OptimizeResult,
+  PowerStatus,
+  Profile,
+  Config,
+  ScheduleEntry,
+  StandbyBreakdown,
+  TrimStrategy,
+} from './types'
+import { AreasFlag, Reason } from './types'
 import { areasToString } from './profiles'
 
 /** Get current memory usage information */
@@ -11,6 +25,26 @@ export async function memoryInfo(): Promise<MemoryInfo> {
   return await invoke<MemoryInfo>('cmd_memory_info')
 }
 
+/** Get standby list size per page priority (0-7) */
+export async function standbyBreakdown(): Promise<StandbyBreakdown> {
+  return await invoke<StandbyBreakdown>('cmd_standby_breakdown')
+}
+
+/** Get memory compression store statistics */
+export async function compressionInfo(): Promise<CompressionInfo> {
+  return await invoke<CompressionInfo>('cmd_compression_info')
+}
+
+/** Get the System File Cache size and the limits in effect */
+export async function getFileCacheInfo(): Promise<FileCacheInfo> {
+  return await invoke<FileCacheInfo>('cmd_get_file_cache_info')
+}
+
+/** Get the full result of the last finished optimization, null if none ran yet */
+export async function getLastResult(): Promise<OptimizeResult | null> {
+  return await invoke<OptimizeResult | null>('cmd_get_last_result')
+}
+
 /** Get current application configuration */
 export async function getConfig(): Promise<Config> {
   return await invoke<Config>('cmd_get_config')
@@ -21,20 +55,72 @@ export async function saveConfig(cfg: Partial<Config>): Promise<void> {
   await invoke('cmd_save_config', { cfgJson: cfg })
 }
 
+/** Activate a built-in or custom profile by name, returns the active profile */
+export async function selectProfile(name: string): Promise<string> {
+  return await invoke<string>('cmd_select_profile', { name })
+}
+
+/** Current power source and battery state */
+export async function powerStatus(): Promise<PowerStatus> {
+  return await invoke<PowerStatus>('cmd_power_status')
+}
+
+/** Enable or disable a single area of the area set in use */
+export async function toggleArea(
+  area: keyof typeof AreasFlag,
+  enabled: boolean
+): Promise<AreaToggleResult> {
+  return await invoke<AreaToggleResult>('cmd_toggle_area', { area, enabled })
+}
+
+/** Confirm (or withdraw) an aggressive area for automatic runs, returns the confirmed areas */
+export async function confirmArea(
+  area: 'WORKING_SET' | 'SYSTEM_FILE_CACHE',
+  confirmed = true
+): Promise<Areas> {
+  return await invoke<Areas>('cmd_confirm_area', { area, confirmed })
+}
+
 /** Show native system notification */
 export async function showNotification(title: string, message: string): Promise<void> {
   await invoke('cmd_show_notification', { title, message })
 }
 
+/** Menu icon as PNG data URL, recolored with `color` or the current accent */
+export async function getMenuIcon(
+  name: 'open' | 'optimize' | 'profile' | 'exit',
+  color?: string
+): Promise<string> {
+  return await invoke<string>('cmd_get_menu_icon', { name, color: color ?? null })
+}
+
 /** Register global hotkey */
 export async function registerHotkey(hotkey: string): Promise<void> {
   await invoke('cmd_register_hotkey', { hotkey })
 }
 
-/** Execute memory optimization */
-export async function optimizeAsync(reason: Reason, areas: Areas): Promise<void> {
+/** Execute memory optimization (optionally only on the given PIDs or process names) */
+export async function optimizeAsync(
+  reason: Reason,
+  areas: Areas,
+  targets?: string[],
+  dryRun = false
+): Promise<void> {
   const areasString = areasToString(areas)
-  await invoke('cmd_optimize_async', { reason, areas: areasString })
+  await invoke('cmd_optimize_async', { reason, areas: areasString, targets: targets ?? null, dryRun })
+}
+
+/** Flush the modified page list, the one-click action offered by the commit charge warning */
+export async function flushModifiedList(): Promise<void> {
+  await optimizeAsync(Reason.Manual, AreasFlag.MODIFIED_PAGE_LIST)
+}
+
+/** Payload of the `commit-pressure` event */
+export interface CommitWarning {
+  used_percent: number
+  used_bytes: number
+  limit_bytes: number // RAM + file di paging
+  threshold: number
 }
 
 /** Get list of running process names */
@@ -42,6 +128,42 @@ export async function listProcessNames(): Promise<string[]> {
   return await invoke<string[]>('cmd_list_process_names')
 }
 
+/** Column used to rank processes by memory usage */
+export type ProcessSortKey = 'working_set' | 'private_bytes' | 'commit'
+
+/** Memory usage of a single process */
+export interface ProcessMemoryEntry {
+  pid: number
+  name: string
+  path: string | null
+  working_set_bytes: number
+  private_bytes: number
+  commit_bytes: number
+  icon?: string // PNG data URL, solo se richiesto
+}
+
+/** Get the processes using the most memory (largest first) */
+export async function processMemoryList(
+  sort: ProcessSortKey = 'working_set',
+  limit = 20,
+  icons = false
+): Promise<ProcessMemoryEntry[]> {
+  return await invoke<ProcessMemoryEntry[]>('cmd_process_memory_list', { sort, limit, icons })
+}
+
+/** Working Set of a process around a single-process trim */
+export interface SingleProcessTrim {
+  pid: number
+  name: string
+  before_bytes: number
+  after_bytes: number
+}
+
+/** Trim the working set of one process (refused for critical or excluded processes) */
+export async function trimProcess(pid: number): Promise<SingleProcessTrim> {
+  return await invoke<SingleProcessTrim>('cmd_trim_process', { pid })
+}
+
 /** Get list of critical system processes */
 export async function getCriticalProcesses(): Promise<string[]> {
   return await invoke<string[]>('cmd_get_critical_processes')
@@ -61,3 +183,555 @@ export async function setAlwaysOnTop(on: boolean): Promise<void> {
 export async function setPriority(priority: 'Low' | 'Normal' | 'High'): Promise<void> {
   await invoke('cmd_set_priority', { priority })
 }
+
+/** Result of importing a .tmcprofile file */
+export interface ProfileImportReport {
+  name: string
+  applied_areas: Areas
+  dropped_areas: string[]
+  warnings: string[]
+}
+
+/** Export current profile to a shareable .tmcprofile file */
+export async function exportProfile(path: string, name: string, exclusions?: string[]): Promise<string> {
+  return await invoke<string>('cmd_export_profile', { path, name, exclusions: exclusions ?? null })
+}
+
+/** Import a .tmcprofile file and apply it */
+export async function importProfile(path: string): Promise<ProfileImportReport> {
+  return await invoke<ProfileImportReport>('cmd_import_profile', { path })
+}
+
+/** A preset stored in the data directory */
+export interface PresetSummary {
+  name: string
+  description: string
+  areas: Areas
+  trim_strategy: TrimStrategy | null // null = strategia globale
+  exclusions: number // numero di processi esclusi
+}
+
+/** Result of importing a .tmcpreset file */
+export interface PresetImportReport {
+  name: string
+  applied_areas: Areas
+  dropped_areas: string[]
+  warnings: string[]
+  applied: boolean // il preset è ora il profilo attivo
+}
+
+/** Export a custom profile (or the profile in use) to a shareable .tmcpreset file */
+export async function exportPreset(
+  path: string,
+  name: string,
+  description?: string,
+  profile?: string
+): Promise<string> {
+  return await invoke<string>('cmd_export_preset', {
+    path,
+    name,
+    description: description ?? null,
+    profile: profile ?? null,
+  })
+}
+
+/** Import a .tmcpreset file into the preset folder, optionally applying it */
+export async function importPreset(path: string, apply: boolean): Promise<PresetImportReport> {
+  return await invoke<PresetImportReport>('cmd_import_preset', { path, apply })
+}
+
+/** Presets stored in the data directory */
+export async function listPresets(): Promise<PresetSummary[]> {
+  return await invoke<PresetSummary[]>('cmd_list_presets')
+}
+
+/** Apply a stored preset as the active custom profile */
+export async function applyPreset(name: string): Promise<void> {
+  await invoke('cmd_apply_preset', { name })
+}
+
+/** Delete a stored preset (the custom profile created from it is kept) */
+export async function deletePreset(name: string): Promise<void> {
+  await invoke('cmd_delete_preset', { name })
+}
+
+/** Result of importing a .tmcconfig settings file */
+export interface ConfigImportReport {
+  app_version: string // versione di TMC che ha scritto il file
+  exported_at: number
+  dropped_areas: string[]
+  warnings: string[]
+}
+
+/** Export the whole configuration to a .tmcconfig settings file */
+export async function exportConfig(path: string): Promise<string> {
+  return await invoke<string>('cmd_export_config', { path })
+}
+
+/** Import a .tmcconfig settings file, replacing the current configuration */
+export async function importConfig(path: string): Promise<ConfigImportReport> {
+  return await invoke<ConfigImportReport>('cmd_import_config', { path })
+}
+
+/** Settle a config.json edited while the app had unsaved settings: true applies the file */
+export async function resolveConfigConflict(useFile: boolean): Promise<boolean> {
+  return await invoke<boolean>('cmd_resolve_config_conflict', { useFile })
+}
+
+/** A problem found by the config schema */
+export interface SchemaIssue {
+  path: string // JSON Pointer, vuoto per l'intero documento
+  message: string
+}
+
+/** Result of checking a config file against the config schema */
+export interface ConfigValidationReport {
+  valid: boolean
+  errors: SchemaIssue[]
+  warnings: SchemaIssue[] // impostazioni sconosciute, ignorate dall'app
+}
+
+/** Check a config.json or .tmcconfig file without loading it */
+export async function validateConfigFile(path: string): Promise<ConfigValidationReport> {
+  return await invoke<ConfigValidationReport>('cmd_validate_config_file', { path })
+}
+
+/** Result of an update check (also the payload of the update-available event) */
+export interface UpdateInfo {
+  current_version: string
+  latest_version: string
+  update_available: boolean
+  package: 'installer' | 'portable'
+  asset_name: string | null // null se la release non ha un pacchetto per questa installazione
+  asset_size: number
+  release_notes: string
+  release_url: string
+  published_at: string | null
+}
+
+/** Check GitHub for a newer release */
+export async function checkUpdate(): Promise<UpdateInfo> {
+  return await invoke<UpdateInfo>('cmd_check_update')
+}
+
+/** Download, verify and start the update found by the last check (the app exits) */
+export async function applyUpdate(): Promise<void> {
+  await invoke('cmd_apply_update')
+}
+
+/** A legacy file found at startup */
+export interface MigrationItem {
+  kind: 'config' | 'history' | 'icon'
+  source: string
+  destination: string
+  status: 'migrated' | 'skipped' | 'failed'
+  detail: string | null
+}
+
+/** Last migration of data from older versions */
+export interface MigrationReport {
+  timestamp: number
+  backup_dir: string | null
+  items: MigrationItem[]
+}
+
+/** Report of the last legacy data migration (null if none ever happened) */
+export async function getMigrationReport(): Promise<MigrationReport | null> {
+  return await invoke<MigrationReport | null>('cmd_get_migration_report')
+}
+
+/** Filters for the optimization history */
+export interface HistoryQuery {
+  since?: number
+  until?: number
+  reason?: Reason
+  profile?: string
+  offset?: number
+  limit?: number
+}
+
+/** Get recorded optimizations (newest first) */
+export async function getOptimizationHistory(query?: HistoryQuery): Promise<any> {
+  return await invoke('cmd_get_optimization_history', { query: query ?? null })
+}
+
+/** Physical memory split at one point in time, in bytes */
+export interface CompositionSample {
+  timestamp: number
+  in_use: number // Senza lo store di compressione
+  modified: number
+  standby: number
+  free: number // Pagine libere e azzerate
+  compressed: number
+}
+
+/** Time range and resolution for the memory timeline */
+export interface TimelineRange {
+  since?: number
+  until?: number
+  max_points?: number // Default 600, i campioni vicini vengono mediati
+}
+
+export interface MemoryTimeline {
+  interval_secs: number
+  total_samples: number
+  samples: CompositionSample[]
+}
+
+/** Get the memory composition timeline (oldest first) */
+export async function memoryTimeline(range?: TimelineRange): Promise<MemoryTimeline> {
+  return await invoke<MemoryTimeline>('cmd_memory_timeline', { range: range ?? null })
+}
+
+/** What adaptive area selection learned about one area */
+export interface AreaEffectiveness {
+  name: string
+  recent_runs: number // Esecuzioni misurate considerate (max 5)
+  avg_freed_bytes: number
+  bytes_per_sec: number
+  adaptive_skips: number // Salti consecutivi dall'ultima esecuzione
+  skip_next: boolean
+}
+
+/** Get the learned per-area effectiveness (adaptive_area_selection) */
+export async function getAreaEffectiveness(): Promise<AreaEffectiveness[]> {
+  return await invoke<AreaEffectiveness[]>('cmd_get_area_effectiveness')
+}
+
+export type UsageTrigger = 'app' | 'tray' | 'hotkey' | 'auto' | 'external' | 'cli'
+export type UsageFeature = 'dry_run' | 'targeted_run' | 'purge_impact' | 'profile_switch' | 'automation_toggle'
+
+/** Anonymized summary of the local usage metrics, never sent anywhere */
+export interface UsageReport {
+  report_version: number
+  app_version: string
+  windows_build: number
+  days_covered: number
+  optimizations: Record<string, { runs: number; avg_freed_mb: number }> // Chiave = Reason
+  triggers: Partial<Record<UsageTrigger, number>>
+  features: Partial<Record<UsageFeature, number>>
+  area_errors: Record<string, number> // Errori per area
+}
+
+export async function getUsageReport(): Promise<UsageReport> {
+  return await invoke<UsageReport>('cmd_get_usage_report')
+}
+
+/** Optimizations, memory freed and RAM usage peak of an app session */
+export interface SessionSummary {
+  started_at: number // Timestamp Unix (secondi)
+  ended_at: number | null // null finché la sessione è in corso
+  optimizations: number
+  freed_bytes: number
+  peak_used_percent: number
+  peak_used_bytes: number
+  peak_at: number | null
+}
+
+export interface SessionReport {
+  current: SessionSummary
+  previous: SessionSummary | null // Sessione precedente, da last_session.json
+}
+
+/** Get the summary of this session and of the previous one */
+export async function getSessionSummary(): Promise<SessionReport> {
+  return await invoke<SessionReport>('cmd_get_session_summary')
+}
+
+/** Write the usage report to a JSON file to attach to an issue */
+export async function exportUsageReport(path: string): Promise<string> {
+  return await invoke<string>('cmd_export_usage_report', { path })
+}
+
+export async function resetUsageMetrics(): Promise<void> {
+  return await invoke<void>('cmd_reset_usage_metrics')
+}
+
+/** Refill cost measured after a standby list purge */
+export interface PurgeImpact {
+  window_secs: number
+  standby_before_bytes: number
+  standby_after_purge_bytes: number
+  standby_end_bytes: number
+  refilled_bytes: number // Crescita della standby list nella finestra
+  hard_faults: number
+  hard_faults_per_sec: number
+  read_back_bytes: number // Letti dal disco nella finestra
+  score: number // 0-100, quota dei byte liberati riletta dal disco
+  samples: { secs: number; standby_bytes: number; hard_faults: number }[]
+}
+
+/** Purge the standby list and measure the refill I/O for `durationSecs` (5-300, default 60) */
+export async function measurePurgeImpact(lowPriority?: boolean, durationSecs?: number): Promise<PurgeImpact> {
+  return await invoke<PurgeImpact>('cmd_measure_purge_impact', {
+    lowPriority: lowPriority ?? null,
+    durationSecs: durationSecs ?? null,
+  })
+}
+
+/** Windows accessibility preferences, with the config overrides applied */
+export interface AccessibilityPrefs {
+  high_contrast: boolean
+  reduced_motion: boolean
+  detected_high_contrast: boolean // Quanto riportato da Windows, prima degli override
+  detected_reduced_motion: boolean
+}
+
+/** Get the high contrast and reduced motion preferences in effect */
+export async function getAccessibilityPrefs(): Promise<AccessibilityPrefs> {
+  return await invoke<AccessibilityPrefs>('cmd_get_accessibility_prefs')
+}
+
+/** Whether automatic optimizations run */
+export interface AutoOptimizerStatus {
+  enabled: boolean
+  cooldown_remaining_secs: number // Attesa residua del trigger di memoria bassa, 0 = nessuna
+}
+
+/** Pause (false) or resume (true) all automatic triggers without changing their settings */
+export async function setAutoOptimizerEnabled(enabled: boolean): Promise<AutoOptimizerStatus> {
+  return await invoke<AutoOptimizerStatus>('cmd_set_auto_optimizer_enabled', { enabled })
+}
+
+/** Get the pause state and the remaining low memory cooldown */
+export async function getAutoOptimizerStatus(): Promise<AutoOptimizerStatus> {
+  return await invoke<AutoOptimizerStatus>('cmd_get_auto_optimizer_status')
+}
+
+/** Upcoming run of a schedule rule */
+export interface ScheduledRun {
+  name: string
+  rule: string
+  at: string // local time, YYYY-MM-DDTHH:MM
+  timestamp: number
+  areas: number
+}
+
+/** Preview the next runs of the saved rules, or of `entries` if given */
+export async function getSchedulePreview(entries?: ScheduleEntry[], count?: number): Promise<ScheduledRun[]> {
+  return await invoke<ScheduledRun[]>('cmd_get_schedule_preview', {
+    entries: entries ?? null,
+    count: count ?? null,
+  })
+}
+
+/** Error reported by a background subsystem */
+export interface RecentError {
+  seq: number
+  timestamp: number
+  subsystem: 'hotkeys' | 'tray' | 'engine' | 'scheduler' | 'notifications' | 'ipc' | 'config'
+  message: string
+  count: number // Occorrenze consecutive
+}
+
+export interface RecentErrors {
+  unseen: number
+  errors: RecentError[] // newest first
+}
+
+/** Get recent background errors; by default this clears the unseen badge */
+export async function getRecentErrors(markSeen = true): Promise<RecentErrors> {
+  return await invoke<RecentErrors>('cmd_get_recent_errors', { markSeen })
+}
+
+/** Remove all recorded background errors */
+export async function clearRecentErrors(): Promise<void> {
+  await invoke('cmd_clear_recent_errors')
+}
+
+/** One line of the log file */
+export interface LogEntry {
+  ts: string // ora locale RFC 3339
+  level: 'ERROR' | 'WARN' | 'INFO' | 'DEBUG' | 'TRACE'
+  target: string
+  message: string
+  fields?: Record<string, unknown>
+}
+
+/** Last entries of the log file (newest first), for bug reports */
+export async function getRecentLogs(n?: number): Promise<LogEntry[]> {
+  return await invoke<LogEntry[]>('cmd_get_recent_logs', { n: n ?? null })
+}
+
+/** Setup wizard step checked by `setupProbeStep` */
+export type SetupProbeStep = 'privileges' | 'areas' | 'notifications' | 'hotkey' | 'startup'
+
+/** Outcome of one setup wizard environment check */
+export interface SetupProbeResult {
+  step: SetupProbeStep
+  status: 'pass' | 'warning' | 'fail'
+  detail: string // Dettaglio tecnico, non tradotto
+  remediation: string | null // Chiave i18n del rimedio, null se superato
+}
+
+/** Run the live environment check of one setup wizard step */
+export async function setupProbeStep(step: SetupProbeStep): Promise<SetupProbeResult> {
+  return await invoke<SetupProbeResult>('cmd_setup_probe_step', { step })
+}
+
+/** Settings recommended for the detected hardware */
+export interface RecommendedConfig {
+  profile: Profile
+  auto_opt_free_threshold: number // 0 = trigger disattivato
+  auto_opt_interval_hours: number // 0 = intervallo disattivato
+  skip_auto_opt_on_battery: boolean
+}
+
+/** Hardware recommendation shown by the setup wizard */
+export interface RecommendedSettings {
+  hardware: {
+    total_ram_bytes: number
+    laptop: boolean
+    discrete_gpu: string | null // Scheda grafica dedicata, null se solo integrata
+  }
+  settings: RecommendedConfig
+  rationale: string[] // Chiavi i18n dei motivi
+}
+
+/** Recommend a profile and automatic optimization thresholds for this PC */
+export async function getRecommendedSettings(): Promise<RecommendedSettings> {
+  return await invoke<RecommendedSettings>('cmd_get_recommended_settings')
+}
+
+/** One self-test check */
+export interface DiagnosticCheck {
+  name: string // es. 'elevation', 'privilege:SeDebugPrivilege', 'area:Standby List', 'tray'
+  status: 'pass' | 'warning' | 'fail'
+  detail: string // Dettaglio tecnico, non tradotto
+}
+
+/** One Windows session, from the console or Remote Desktop */
+export interface SessionInfo {
+  id: number
+  station: string // es. 'Console', 'RDP-Tcp#3'
+  user: string | null // null per sessioni senza utente
+  state: 'active' | 'connected' | 'disconnected' | 'other'
+  current: boolean // sessione in cui gira TMC
+}
+
+/** Self-test report, for bug reports */
+export interface DiagnosticsReport {
+  app_version: string
+  windows_version: { major: number; minor: number; build: number }
+  elevated: boolean
+  checks: DiagnosticCheck[]
+  sessions: SessionInfo[]
+}
+
+/** Whether TMC runs as administrator and what is missing if not */
+export interface ElevationStatus {
+  elevated: boolean
+  unavailable_areas: string[] // Aree saltate finché TMC non è amministratore
+}
+
+export async function getElevationStatus(): Promise<ElevationStatus> {
+  return await invoke<ElevationStatus>('cmd_get_elevation_status')
+}
+
+/** Relaunch as administrator through the UAC prompt; this instance exits on success */
+export async function requestElevation(): Promise<void> {
+  return await invoke<void>('cmd_request_elevation')
+}
+
+/** Run the self-test: privileges, memory areas, kernel call, notifications, tray */
+export async function runDiagnostics(): Promise<DiagnosticsReport> {
+  return await invoke<DiagnosticsReport>('cmd_run_diagnostics')
+}
+
+/** Duration of one startup phase */
+export interface PhaseTiming {
+  phase: 'privilege_init' | 'config_load' | 'window_create' | 'tray_build' | 'webview_ready'
+  start_ms: number // dall'avvio del processo
+  duration_ms: number
+}
+
+/** Startup timings of one launch */
+export interface StartupRecord {
+  version: string
+  timestamp: number
+  total_ms: number
+  phases: PhaseTiming[]
+}
+
+/** Startup timings of the last launches (newest first) */
+export async function getStartupTimings(): Promise<StartupRecord[]> {
+  return await invoke<StartupRecord[]>('cmd_get_startup_timings')
+}
+
+export interface BackgroundTaskHealth {
+  task: 'tray_updater' | 'auto_optimizer'
+  state: 'running' | 'restarting' // restarting = in attesa del backoff
+  restarts: number // Riavvii dall'avvio dell'app
+  last_failure: string | null
+  last_failure_at: number | null // Unix timestamp (secondi)
+}
+
+/** Health of the supervised background tasks */
+export async function getBackgroundTaskStatus(): Promise<BackgroundTaskHealth[]> {
+  return await invoke<BackgroundTaskHealth[]>('cmd_get_background_task_status')
+}
+
+export type SystemTweak = 'clear_page_file_at_shutdown'
+
+export interface TweakStatus {
+  tweak: SystemTweak
+  enabled: boolean
+  configured: boolean // Valore presente nel registro (TMC, regedit o criteri di gruppo)
+  warning: string // Testo inglese, usato come chiave di traduzione
+  can_change: boolean // false senza privilegi amministratore
+}
+
+/** Get the advanced system tweaks and their state in the registry */
+export async function getSystemTweaks(): Promise<TweakStatus[]> {
+  return await invoke<TweakStatus[]>('cmd_get_system_tweaks')
+}
+
+/** Enable or disable a system tweak, effective after restart */
+export async function setSystemTweak(tweak: SystemTweak, enable: boolean): Promise<TweakStatus> {
+  return await invoke<TweakStatus>('cmd_set_system_tweak', { tweak, enable })
+}
+
+// Stato UI passato alla finestra ricreata dopo destroy_webview_on_hide
+export interface MainWindowUiState {
+  activeTab?: string
+}
+
+/** Hide the main window to the tray, destroying it when destroy_webview_on_hide is set */
+export async function hideMainWindow(uiState: MainWindowUiState | null): Promise<void> {
+  await invoke('cmd_hide_main_window', { uiState })
+}
+
+/** Take the UI state saved when the previous main window was hidden */
+export async function takeMainWindowState(): Promise<MainWindowUiState | null> {
+  return await invoke<MainWindowUiState | null>('cmd_take_main_window_state')
+}
+
+// Pulizia opzionale dei file temporanei
+export type CleanLocation = 'windows_temp' | 'user_temp' | 'directx_shader_cache'
+
+export interface LocationReport {
+  location: CleanLocation
+  path: string | null // null se la cartella non esiste
+  files: number // Trovati dalla scansione o eliminati dalla pulizia
+  bytes: number
+  failed: number // File in uso o senza permessi
+  skipped: string | null // Motivo se la cartella non è sicura da pulire (es. TEMP impostato su C:\)
+}
+
+export interface StorageReport {
+  dry_run: boolean // true = solo scansione, nulla eliminato
+  locations: LocationReport[]
+  total_files: number
+  total_bytes: number
+  duration_ms: number
+}
+
+/** Measure what a temp cleanup would delete, without deleting anything */
+export async function scanCleanable(): Promise<StorageReport> {
+  return await invoke<StorageReport>('cmd_scan_cleanable')
+}
+
+/** Start deleting temporary files; the report arrives as tmc://storage_cleaned */
+export async function cleanStorage(): Promise<void> {
+  await invoke('cmd_clean_storage')
+}
@@ -18,7 +18,8 @@ export function areasForProfile(profile: Profile): Areas {
         AreasFlag.STANDBY_LIST |
         AreasFlag.SYSTEM_FILE_CACHE |
         AreasFlag.MODIFIED_FILE_CACHE |
-        AreasFlag.STANDBY_LIST_LOW
+        AreasFlag.STANDBY_LIST_LOW |
+        AreasFlag.MEMORY_COMPRESSION
       )
     case 'Gaming':
       // Profilo Gaming: Include Balanced + Modified Page List + Combined Page List
@@ -31,7 +32,8 @@ export function areasForProfile(profile: Profile): Areas {
         AreasFlag.MODIFIED_FILE_CACHE |
         AreasFlag.STANDBY_LIST_LOW |
         AreasFlag.MODIFIED_PAGE_LIST |
-        AreasFlag.COMBINED_PAGE_LIST
+        AreasFlag.COMBINED_PAGE_LIST |
+        AreasFlag.MEMORY_COMPRESSION
       )
     default:
       return AreasFlag.WORKING_SET
@@ -48,6 +50,7 @@ export function areaNamesForAreas(areas: Areas): string[] {
   if (areas & AreasFlag.COMBINED_PAGE_LIST) names.push('Combined Pages')
   if (areas & AreasFlag.MODIFIED_FILE_CACHE) names.push('File Cache')
   if (areas & AreasFlag.REGISTRY_CACHE) names.push('Registry Cache')
+  if (areas & AreasFlag.MEMORY_COMPRESSION) names.push('Memory Compression')
   return names
 }
 
@@ -63,6 +66,7 @@ export function areasToString(areas: Areas): string {
   if (areas & AreasFlag.STANDBY_LIST_LOW) flags.push('STANDBY_LIST_LOW')
   if (areas & AreasFlag.SYSTEM_FILE_CACHE) flags.push('SYSTEM_FILE_CACHE')
   if (areas & AreasFlag.WORKING_SET) flags.push('WORKING_SET')
+  if (areas & AreasFlag.MEMORY_COMPRESSION) flags.push('MEMORY_COMPRESSION')
 
   return flags.join('|')
 }
@@ -98,6 +102,9 @@ export function stringToAreas(flagString: string): Areas {
       case 'WORKING_SET':
         areas |= AreasFlag.WORKING_SET
         break
+      case 'MEMORY_COMPRESSION':
+        areas |= AreasFlag.MEMORY_COMPRESSION
+        break
     }
   }
 
@@ -1,16 +1,19 @@
 import { writable, get } from 'svelte/store'
-import type { Config, MemoryInfo, Profile } from './types'
+import type { Config, MemoryInfo, OptimizeResult, Profile, ProgressEvent } from './types'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { setLanguage } from '../i18n/index'
 import { cacheTranslationsInBackend } from '../lib/translations'
 import { areasForProfile } from '../lib/profiles'
 import type { Language } from '../i18n/index'
+import type { AccessibilityPrefs } from './api'
 
 // ========== TYPES ==========
 interface ProgressState {
   value: number
   total: number
   step: string
+  percent: number // Percentuale complessiva
+  areaPercent: number // Percentuale dell'area in corso, 0 se l'area non la riporta
   running: boolean
 }
 
@@ -19,18 +22,36 @@ interface AppState {
   listeners: {
     progress: UnlistenFn | null
     done: UnlistenFn | null
+    nothingFreed: UnlistenFn | null
+    result: UnlistenFn | null
     optimizeNow: UnlistenFn | null
+    throttled: UnlistenFn | null
+    configChanged: UnlistenFn | null
+    configConflict: UnlistenFn | null
+    accessibility: UnlistenFn | null
   }
   refreshInterval: number | null
+  throttleInterval: number | null
 }
 
 // ========== STORES ==========
 export const config = writable<Config | null>(null)
 export const memory = writable<MemoryInfo | null>(null)
+export const lastResult = writable<OptimizeResult | null>(null)
+// Secondi di attesa prima della prossima ottimizzazione manuale (0 = nessuna)
+export const throttleSeconds = writable<number>(0)
+// config.json modificato a mano mentre c'erano impostazioni non salvate
+export const configConflict = writable<boolean>(false)
+// L'ultima ottimizzazione non aveva niente da liberare (mostra "Già ottimale" invece di "Fatto")
+export const nothingFreed = writable<boolean>(false)
+// Stato della UI (es. tab attivo) passato alla prossima finestra quando questa viene distrutta
+export const mainWindowUiState = writable<{ activeTab?: string }>({})
 export const progress = writable<ProgressState>({
   value: 0,
   total: 1,
   step: '',
+  percent: 0,
+  areaPercent: 0,
   running: false,
 })
 
@@ -40,9 +61,16 @@ const appState: AppState = {
   listeners: {
     progress: null,
     done: null,
+    nothingFreed: null,
+    result: null,
     optimizeNow: null,
+    throttled: null,
+    configChanged: null,
+    configConflict: null,
+    accessibility: null,
   },
   refreshInterval: null,
+  throttleInterval: null,
 }
 
 // ========== CONSTANTS ==========
@@ -191,6 +219,22 @@ export async function initApp(): Promise<void> {
       console.error('Failed to load initial memory info:', error)
     }
 
+    // Result of a run that finished before this window was opened
+    try {
+      const { getLastResult } = await import('./api')
+      lastResult.set(await getLastResult())
+    } catch (error) {
+      console.error('Failed to load last optimization result:', error)
+    }
+
+    // Alto contrasto e animazioni ridotte (Windows o override nella config)
+    try {
+      const { getAccessibilityPrefs } = await import('./api')
+      applyAccessibility(await getAccessibilityPrefs())
+    } catch (error) {
+      console.error('Failed to load accessibility preferences:', error)
+    }
+
     // Setup event listeners
     await setupEventListeners()
 
@@ -206,16 +250,39 @@ export async function initApp(): Promise<void> {
   }
 }
 
+/** Expose the accessibility preferences to CSS as data attributes on <html> */
+function applyAccessibility(prefs: AccessibilityPrefs): void {
+  const root = document.documentElement
+  root.dataset.reducedMotion = String(prefs.reduced_motion)
+  root.dataset.highContrast = String(prefs.high_contrast)
+}
+
 async function setupEventListeners(): Promise<void> {
   try {
     // Progress listener
     appState.listeners.progress = await listen('tmc://opt_progress', (event: any) => {
-      const payload = event.payload as { value: number; total: number; step: string }
-      progress.set({
-        value: payload.value,
-        total: payload.total,
-        step: payload.step,
-        running: true,
+      const payload = event.payload as ProgressEvent
+      progress.update((p) => {
+        switch (payload.kind) {
+          case 'area_started':
+            nothingFreed.set(false)
+            return {
+              ...p,
+              value: payload.index,
+              total: payload.total,
+              step: payload.area,
+              areaPercent: 0,
+              running: true,
+            }
+          case 'area_percent':
+            return { ...p, areaPercent: payload.percent, running: true }
+          case 'overall':
+            return { ...p, percent: payload.percent, running: true }
+          case 'completed':
+            return { ...p, step: 'Completed', percent: 100, running: true }
+          default:
+            return p
+        }
       })
     })
 
@@ -226,14 +293,18 @@ async function setupEventListeners(): Promise<void> {
         step: 'Done',
         running: false,
         value: p.total,
+        percent: 100,
       }))
 
       // Reset after delay
       setTimeout(() => {
+        nothingFreed.set(false)
         progress.set({
           value: 0,
           total: 1,
           step: '',
+          percent: 0,
+          areaPercent: 0,
           running: false,
         })
       }, 2000)
@@ -242,6 +313,55 @@ async function setupEventListeners(): Promise<void> {
       refreshMemoryOnce()
     })
 
+    // Sent right before opt_done when memory was already optimal
+    appState.listeners.nothingFreed = await listen('tmc://opt_nothing_freed', () => {
+      nothingFreed.set(true)
+    })
+
+    // Full result of every finished optimization
+    appState.listeners.result = await listen('optimization-result', (event: any) => {
+      lastResult.set(event.payload as OptimizeResult)
+    })
+
+    // Manual run refused by the cooldown: count the remaining seconds down
+    appState.listeners.throttled = await listen('optimization-throttled', (event: any) => {
+      const payload = event.payload as { remaining_secs: number }
+      throttleSeconds.set(payload.remaining_secs)
+      if (appState.throttleInterval) clearInterval(appState.throttleInterval)
+      appState.throttleInterval = window.setInterval(() => {
+        throttleSeconds.update((s) => Math.max(0, s - 1))
+        if (get(throttleSeconds) === 0 && appState.throttleInterval) {
+          clearInterval(appState.throttleInterval)
+          appState.throttleInterval = null
+        }
+      }, 1000)
+    })
+
+    // Config changed by the backend (tray, hotkeys, config.json edited by hand)
+    appState.listeners.configChanged = await listen('config-changed', async () => {
+      try {
+        const { getConfig } = await import('./api')
+        const cfg = await getConfig()
+        cfg.memory_areas = parseMemoryAreas(cfg.memory_areas)
+        if (cfg.language !== get(config)?.language) {
+          await setLanguage(getSafeLanguage(cfg.language))
+        }
+        config.set(cfg)
+      } catch (error) {
+        console.error('Failed to reload config:', error)
+      }
+    })
+
+    // config.json edited while settings were unsaved: the user picks which to keep
+    appState.listeners.configConflict = await listen('config-conflict', () => {
+      configConflict.set(true)
+    })
+
+    // High contrast or animations switched in Windows or in the config
+    appState.listeners.accessibility = await listen('accessibility-changed', (event: any) => {
+      applyAccessibility(event.payload as AccessibilityPrefs)
+    })
+
     // Optimize now listener
     appState.listeners.optimizeNow = await listen('tmc://optimize_now', async () => {
       try {
@@ -277,11 +397,46 @@ export async function cleanupApp(): Promise<void> {
     appState.listeners.done = null
   }
 
+  if (appState.listeners.nothingFreed) {
+    appState.listeners.nothingFreed()
+    appState.listeners.nothingFreed = null
+  }
+
+  if (appState.listeners.result) {
+    appState.listeners.result()
+    appState.listeners.result = null
+  }
+
   if (appState.listeners.optimizeNow) {
     appState.listeners.optimizeNow()
     appState.listeners.optimizeNow = null
   }
 
+  if (appState.listeners.throttled) {
+    appState.listeners.throttled()
+    appState.listeners.throttled = null
+  }
+
+  if (appState.listeners.configChanged) {
+    appState.listeners.configChanged()
+    appState.listeners.configChanged = null
+  }
+
+  if (appState.listeners.configConflict) {
+    appState.listeners.configConflict()
+    appState.listeners.configConflict = null
+  }
+
+  if (appState.listeners.accessibility) {
+    appState.listeners.accessibility()
+    appState.listeners.accessibility = null
+  }
+
+  if (appState.throttleInterval) {
+    clearInterval(appState.throttleInterval)
+    appState.throttleInterval = null
+  }
+
   // Stop memory refresh
   stopMemoryRefresh()
 
@@ -1,10 +1,10 @@
 import { invoke } from '@tauri-apps/api/core'
-import { dict, lang } from '../i18n/index'
+import { lang } from '../i18n/index'
 import { get } from 'svelte/store'
 
 /**
- * Send current translations to backend for caching
- * This allows backend to use translated strings for notifications
+ * Tell the backend which language to use for notifications and the tray
+ * The backend loads the same language packs on its own
  */
 export async function cacheTranslationsInBackend(): Promise<void> {
   const maxRetries = 5
@@ -13,17 +13,10 @@ export async function cacheTranslationsInBackend(): Promise<void> {
   for (let attempt = 1; attempt <= maxRetries; attempt++) {
     try {
       const currentLang = get(lang)
-      const currentDict = get(dict)
 
-      // Send all translations to backend
-      await invoke('cmd_set_translations', {
-        language: currentLang,
-        translations: currentDict,
-      })
+      await invoke('cmd_set_translations', { language: currentLang })
 
-      console.log(
-        `Cached ${Object.keys(currentDict).length} translations for language: ${currentLang}`,
-      )
+      console.log(`Backend language set to: ${currentLang}`)
       return // Success, exit the function
     } catch (error: any) {
       console.error(`Attempt ${attempt} failed to cache translations in backend:`, error)
@@ -17,12 +17,130 @@ export interface MemoryInfo {
   physical: MemoryStats
   commit: MemoryStats
   load_percent: number
+  installed_bytes: number // RAM installata; physical.total è quella utilizzabile
+  hardware_reserved_bytes: number // Riservata da firmware e periferiche
+  game_mode_active: boolean // Modalità Gioco di Windows attiva
+}
+
+// Standby list per priorità (indice = priorità 0-7)
+export interface StandbyBreakdown {
+  by_priority: number[]
+  total_bytes: number
+  low_priority_bytes: number
+  modified_bytes: number
+  free_bytes: number
+  zero_bytes: number
+  page_size: number
 }
 
 export enum Reason {
   LowMemory = 'LowMemory',
   Manual = 'Manual',
   Schedule = 'Schedule',
+  Hotkey = 'Hotkey',
+  External = 'External',
+  Predicted = 'Predicted',
+  Emergency = 'Emergency',
+}
+
+// Store di compressione della memoria (cmd_compression_info)
+export interface CompressionInfo {
+  compressed_pages: number
+  uncompressed_bytes: number
+  compressed_bytes: number
+  compression_ratio: number // 0 se lo store è vuoto
+  process_id: number // PID del processo Memory Compression
+  working_set_bytes: number
+}
+
+// System File Cache e suoi limiti (cmd_get_file_cache_info)
+export interface FileCacheInfo {
+  current_bytes: number
+  peak_bytes: number
+  min_limit_bytes: number
+  max_limit_bytes: number
+  hard_max: boolean // Il massimo è imposto, non solo un obiettivo
+  hard_min: boolean
+}
+
+// Risultato di una singola area (estimated_bytes solo in dry run)
+export interface OptimizeAreaResult {
+  name: string
+  duration_ms: number
+  error: string | null
+  error_kind?: MemoryOpError // Causa strutturata dell'errore, se nota
+  skipped: string | null
+  estimated_bytes: number | null
+  processes?: ProcessTrimResult[] // Solo Working Set, quando i processi sono svuotati uno a uno
+  api_path: ApiPath | null // Percorso API usato, null se l'area non è stata eseguita
+  trim_strategy?: TrimStrategy // Solo Working Set, strategia usata
+  freed_bytes: number | null // Memoria liberata da quest'area, null se non misurata
+  retries: number // Ripetizioni perché la lista era rimasta invariata nonostante il successo
+}
+
+// Causa di un errore delle operazioni di memoria, per messaggi mirati nella UI
+export type MemoryOpError =
+  | { kind: 'privilege_missing'; privilege: string }
+  | { kind: 'unsupported_os_build'; build: number }
+  | { kind: 'nt_status'; status: number }
+  | { kind: 'access_denied'; pid: number }
+  | { kind: 'timeout'; secs: number }
+  | { kind: 'list_unchanged'; retries: number } // Successo riportato ma lista invariata dopo i tentativi
+
+// gentle = solo priorità memoria, standard = EmptyWorkingSet, aggressive = limite rigido poi ripristino
+export type TrimStrategy = 'gentle' | 'standard' | 'aggressive'
+
+// 'advanced' prova prima il modulo avanzato, con ripiego sulle API documentate
+export type ApiPath = 'documented' | 'advanced'
+
+export interface ProcessTrimResult {
+  pid: number
+  name: string
+  trimmed: boolean
+  skipped?: string // es. "Busy (35% CPU)", "Excluded"
+  cpu_percent?: number
+}
+
+// Risultato di un'ottimizzazione (evento optimization-result) o di un dry run (evento tmc://dry_run_result)
+export interface OptimizeResult {
+  reason: Reason
+  duration_ms: number
+  freed_physical_bytes: number // Differenza grezza su tutta l'ottimizzazione
+  freed_commit_bytes: number
+  attributed_freed_bytes: number // Somma della memoria liberata per area
+  areas: OptimizeAreaResult[]
+  dry_run: boolean
+  benchmark: BenchmarkComparison | null // Solo con benchmark_enabled
+  implicitly_excluded: number // Processi saltati perché avviati da un processo escluso
+  measurement: MeasurementInfo | null // null per le simulazioni
+}
+
+// Come è stata misurata la memoria dopo l'ottimizzazione
+export interface MeasurementInfo {
+  delay_ms: number
+  samples: number // Conta il campione mediano
+  sample_interval_ms: number
+  retries: number
+}
+
+// Avanzamento di un'ottimizzazione o di una pulizia (evento tmc://opt_progress)
+export type ProgressEvent =
+  | { kind: 'area_started'; index: number; total: number; area: string } // index parte da 1
+  | { kind: 'area_percent'; area: string; percent: number } // Solo aree lunghe, es. Working Set
+  | { kind: 'area_finished'; area: string; freed_bytes: number | null; skipped: boolean; failed: boolean }
+  | { kind: 'overall'; percent: number }
+  | { kind: 'completed' }
+
+export interface BenchmarkSample {
+  alloc_latency_us: number // Mediana per blocco da 1 MB
+  page_faults: number
+  page_faults_per_sec: number
+  duration_ms: number
+}
+
+export interface BenchmarkComparison {
+  before: BenchmarkSample
+  after: BenchmarkSample
 }
 
 export enum AreasFlag {
@@ -34,14 +152,38 @@ export enum AreasFlag {
   STANDBY_LIST_LOW = 1 << 5,
   SYSTEM_FILE_CACHE = 1 << 6,
   WORKING_SET = 1 << 7,
+  MEMORY_COMPRESSION = 1 << 8,
 }
 
 export type Areas = number
+
+export interface AreaToggleResult {
+  areas: Areas // Aree risultanti dopo la modifica
+  warnings: string[]
+}
+export interface AreaCooldown {
+  area: Areas | string // Flag numerici o nomi separati da ' | '
+  minutes: number // 1-10080
+}
+export interface FileCacheLimit {
+  profile: string // Profilo predefinito o personalizzato a cui si applica
+  min_mb: number
+  max_mb: number // 64-1048576
+  hard?: boolean // Impone il massimo invece di usarlo come obiettivo di riduzione
+}
+
 export type Priority = 'Low' | 'Normal' | 'High'
+export type LockedSessionPolicy = 'RunWhileLocked' | 'DeferToUnlock' | 'Skip'
+export type OtherSessionsPolicy = 'TrimAll' | 'SkipOtherSessions'
+// RunKey = chiave Run del registro, ScheduledTask = task all'accesso con privilegi più alti (niente UAC)
+export type StartupMethod = 'RunKey' | 'ScheduledTask'
 export type Profile = 'Normal' | 'Balanced' | 'Gaming'
 
+export type TrayDisplayMode = 'percent' | 'sparkline' | 'icon-only'
+
 export interface TrayConfig {
   show_mem_usage: boolean
+  display_mode: TrayDisplayMode // Percentuale, grafico RAM degli ultimi minuti o solo icona
   text_color_hex: string
   background_color_hex: string
   transparent_bg: boolean
@@ -49,16 +191,137 @@ export interface TrayConfig {
   warning_color_hex: string
   danger_level: number
   danger_color_hex: string
+  follow_system: boolean // Testo e sfondo seguono il tema e il colore d'accento della barra di Windows
+  native_menu: boolean // Clic destro: menu nativo con una voce di ottimizzazione per profilo
+}
+
+// Forza alto contrasto e animazioni ridotte, null = rilevati da Windows
+export interface AccessibilityOverrides {
+  high_contrast: boolean | null
+  reduced_motion: boolean | null
+}
+
+export interface FreeRamFloor {
+  enabled: boolean
+  skip_working_set_above_percent: number // 0 = disattivato
+  stop_above_free_gb: number // 0 = disattivato
+}
+
+export interface ToastOptions {
+  duration: 'short' | 'long' // ~7s o ~25s
+  scenario: 'default' | 'reminder' // reminder = resta visibile fino alla chiusura
+  silent: boolean
+  native_digits: boolean // Cifre arabo-indiche nelle notifiche in arabo
+  show_when_busy: boolean // false = rimanda i toast durante Focus Assist o app a schermo intero
+}
+
+// silent = nessun toast, solo il tooltip del tray; summary = solo la memoria liberata
+export type NotificationVerbosity = 'silent' | 'summary' | 'detailed'
+
+// Verbosità per profilo integrato, i profili personalizzati usano 'detailed'
+export interface ProfileNotifications {
+  normal: NotificationVerbosity
+  balanced: NotificationVerbosity
+  gaming: NotificationVerbosity
+}
+
+export interface TempCleanerOptions {
+  enabled: boolean // Disattivato di default, nessuna scansione né eliminazione
+  windows_temp: boolean // %SystemRoot%\Temp (richiede privilegi amministratore)
+  user_temp: boolean // %TEMP% dell'utente
+  shader_cache: boolean // Cache shader DirectX (D3DSCache)
+  min_age_hours: number // Mantiene i file modificati nelle ultime ore (0-720)
+}
+
+export interface PressurePrediction {
+  enabled: boolean
+  sample_interval_secs: number // 5-60
+  window_minutes: number // Orizzonte della previsione (1-60)
+  sensitivity: number // 1 (solo trend stabili) - 10 (reagisce ai picchi)
+}
+
+export interface GameMode {
+  enabled: boolean
+  game_processes: string[] // Nomi in minuscolo senza .exe
+  detect_fullscreen: boolean // Qualsiasi app Direct3D a schermo intero conta come gioco
+  gpu_idle_minutes: number // 0-60, 0 = disattivato
+  gpu_idle_percent: number // Uso 3D della GPU sotto cui è inattiva (1-50)
+}
+
+export interface EmergencyMode {
+  enabled: boolean
+  free_threshold: number // Memoria libera critica in % (1-50)
+  prompt_close_top: boolean // Propone di chiudere il processo che usa più memoria
+}
+
+// Profilo personalizzato (exclusions sostituisce la lista globale se presente)
+export interface CustomProfile {
+  name: string
+  areas: Areas
+  priority: Priority
+  exclusions: string[] | null
+  hotkey: string | null
+  trim_strategy?: TrimStrategy | null // null = strategia globale
+}
+
+// Azione associata a un hotkey globale aggiuntivo
+export type HotkeyAction =
+  | { type: 'optimize'; profile: string | null } // profile null = profilo attivo
+  | { type: 'toggle_auto_optimizer' }
+  | { type: 'toggle_window' }
+  | { type: 'trim_foreground' }
+
+export interface PowerStatus {
+  source: 'ac' | 'battery' | 'unknown'
+  battery_percent: number | null
+  battery_saver: boolean
+  has_battery: boolean // Batteria presente (portatile o tablet)
+}
+
+export interface HotkeyBinding {
+  hotkey: string
+  action: HotkeyAction
+}
+
+export interface ScheduleEntry {
+  name: string
+  enabled: boolean
+  rule: string // es. "weekdays at 09:00", "every 30m between 18:00-23:00"
+  profile: Profile | null
+  areas: Areas | null
 }
 
 export interface Config {
   always_on_top: boolean
   auto_opt_interval_hours: number
   auto_opt_free_threshold: number
+  auto_opt_only_when_idle: boolean // Ottimizzazioni automatiche solo con utente inattivo
+  idle_minutes: number // Minuti di inattività richiesti (1-240)
+  locked_session_policy: LockedSessionPolicy // Cosa fanno le ottimizzazioni automatiche a sessione bloccata
+  other_sessions_policy: OtherSessionsPolicy // Salta il Working Set dei processi di altri utenti connessi
+  busy_process_cpu_percent: number // Salta i processi sopra questa % di CPU (0 = disattivato)
+  allow_system_process_trim: boolean // Solo esperti: riduce anche processi di sistema/protetti
+  exclude_child_processes: boolean // Esclude anche i processi avviati da un processo escluso
+  use_documented_apis_only: boolean // Solo API documentate, niente avvisi da antivirus/EDR
+  area_settle_ms: number // Attesa dopo ogni area prima di misurare la memoria liberata (0-5000)
+  area_verify_retries: number // Ripetizioni di un'area riuscita che non ha svuotato la sua lista (0-3, 0 = mai)
+  measurement_delay_ms: number // Attesa dopo l'ultima area prima di misurare la memoria liberata (0-10000)
+  measurement_samples: number // Campioni di memoria dopo l'ottimizzazione, conta la mediana (1-9)
+  benchmark_enabled: boolean // Micro-benchmark di allocazione prima/dopo l'ottimizzazione
+  adaptive_area_selection: boolean // Salta le aree che non hanno liberato nulla nelle ultime esecuzioni
+  document_editor_processes: string[] // Editor saltati dalla pulizia Working Set se hanno documenti aperti
+  skip_foreground_process: boolean // Salta il processo della finestra in primo piano (giochi)
+  skip_audio_processes: boolean // Salta i processi che stanno riproducendo audio
+  skip_auto_opt_on_battery: boolean // Niente ottimizzazioni automatiche a batteria
+  profile_on_battery: string | null // Profilo usato dalle ottimizzazioni automatiche a batteria
+  area_cooldowns: AreaCooldown[] // Intervallo minimo tra due ottimizzazioni automatiche della stessa area
+  manual_cooldown_secs: number // Attesa tra due ottimizzazioni manuali o da hotkey, 0-3600 (0 = disattivata)
+  file_cache_limits: FileCacheLimit[] // Limiti della System File Cache, applicati mentre il loro profilo è attivo
   auto_update: boolean
 
   close_after_opt: boolean
   minimize_to_tray: boolean
+  destroy_webview_on_hide: boolean // Distrugge la finestra nascosta nel tray per liberare la memoria della WebView
   compact_mode: boolean
 
   font_size: number
@@ -72,16 +335,66 @@ export interface Config {
   memory_areas: Areas
   hotkey: string
   process_exclusion_list: string[]
+  process_include_list: string[] // Se non vuota, il Working Set viene svuotato solo per questi processi
+  strict_nt_errors: boolean // Riporta ogni NTSTATUS fallito come errore dell'area, anche se un fallback è riuscito
+  gentle_maintenance_processes: string[] // Sperimentale: Working Set ridotto gradualmente invece di svuotato
+  gentle_maintenance_interval_secs: number // Secondi tra due passaggi (30-3600)
 
   run_priority: Priority
   run_on_startup: boolean
+  startup_method: StartupMethod
+  startup_delay_secs: number // Attesa dopo l'accesso prima che il task avvii l'app (0-600)
+  start_minimized: boolean // Avvio nell'area di notifica, la finestra viene creata al primo click
 
   show_opt_notifications: boolean
+  notification_verbosity: ProfileNotifications
+  notify_nothing_freed: boolean // Avvisa che la memoria era già ottimale quando si libera meno di 1 MB
+  session_summary_notification: boolean // Riepilogo della sessione (ottimizzazioni, MB liberati, picco RAM) all'uscita
+  toast: ToastOptions
+  custom_profiles: CustomProfile[]
+  active_custom_profile: string | null
+  // Aree aggressive confermate per le ottimizzazioni automatiche
+  confirmed_areas: Areas
+  hotkeys: HotkeyBinding[] // Hotkey aggiuntivi oltre a `hotkey`
+  auto_optimizer_enabled: boolean // false = ottimizzazioni automatiche in pausa
   request_elevation_on_startup: boolean
 
   tray: TrayConfig
+  accessibility: AccessibilityOverrides // null = segue Windows
   
   // Platform detection fields
   platform_detected?: boolean
   is_windows_10?: boolean
+
+  // SDK named pipe (\\.\pipe\tmc)
+  sdk_pipe_enabled: boolean
+  sdk_allowed_clients: string[] // Percorsi completi degli eseguibili autorizzati
+  sdk_rate_limit_per_minute: number
+  widget_feed_enabled: boolean // Aggiorna widget.json per Rainmeter/Zebar
+  widget_feed_interval_secs: number // 1-60
+  http_api_enabled: boolean // API HTTP su 127.0.0.1 per Grafana/Home Assistant
+  http_api_port: number // 1024-65535
+  http_api_token: string // Token Bearer, generato se vuoto
+  timeline_interval_secs: number // Campionamento della composizione della memoria, 1-300 s
+  timeline_persist: boolean // Salva la timeline e la ricarica al prossimo avvio
+  usage_metrics: boolean // Statistiche d'uso solo locali, per il report da allegare alle issue
+  commit_warning_percent: number // Avviso commit charge, 50-99 (0 = disattivato)
+
+  // Soglia RAM libera per le ottimizzazioni automatiche (null = valori del profilo)
+  free_ram_floor: FreeRamFloor | null
+  trim_strategy: TrimStrategy | null // Strategia Working Set, null = predefinita del profilo
+
+  schedules: ScheduleEntry[]
+
+  // Ottimizzazione anticipata in base al trend della memoria
+  pressure_prediction: PressurePrediction
+
+  // Profilo Gaming automatico durante il gioco
+  game_mode: GameMode
+
+  // Pulizia immediata sotto la soglia critica di memoria libera
+  emergency_mode: EmergencyMode
+
+  // Pulizia opzionale dei file temporanei
+  temp_cleaner: TempCleanerOptions
 }
@@ -8,6 +8,8 @@ interface AppInfo {
   company: string;
   copyright: string;
   description: string;
+  dataMode: 'appdata' | 'portable';
+  dataDir: string;
 }
 
 // Create a writable store with default values
@@ -17,7 +19,9 @@ export const appInfo = writable<AppInfo>({
   versionFull: '2.5.0.0',
   company: 'Tommy437',
   copyright: '© 2025 Tommy437. All rights reserved.',
-  description: 'Advanced Memory Optimization Tool for Windows'
+  description: 'Advanced Memory Optimization Tool for Windows',
+  dataMode: 'appdata',
+  dataDir: ''
 });
 
 // Function to load app info from backend
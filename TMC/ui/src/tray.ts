@@ -32,6 +32,57 @@ function updateTrayTranslations() {
   })
 }
 
+const BUILT_IN_PROFILES = ['Normal', 'Balanced', 'Gaming']
+
+/** Names of all selectable profiles, built-in first */
+function profileNames(config: any): string[] {
+  const custom = (config.custom_profiles || []).map((p: any) => p.name)
+  return [...BUILT_IN_PROFILES, ...custom]
+}
+
+function activeProfileName(config: any): string {
+  return config.active_custom_profile || config.profile || 'Balanced'
+}
+
+/** Show the active profile in the profile menu entry */
+function updateProfileItem(config: any) {
+  const item = document.getElementById('profile-item')
+  if (!item) return
+  const translations = get(dict)
+  const name = activeProfileName(config)
+  const label = BUILT_IN_PROFILES.includes(name) ? translations[name] || name : name
+  item.textContent = `${translations['Profile'] || 'Profile'}: ${label}`
+}
+
+/** Show whether automatic optimizations are paused in the automation entry */
+function updateAutoItem(config: any) {
+  const item = document.getElementById('auto-item')
+  const icon = document.getElementById('auto-icon')
+  if (!item || !icon) return
+  const translations = get(dict)
+  const paused = config.auto_optimizer_enabled === false
+  const key = paused ? 'Resume automation' : 'Pause automation'
+  item.textContent = translations[key] || key
+  icon.setAttribute('data-icon', paused ? 'resume' : 'pause')
+}
+
+/** Recolor menu icons with the accent color (the exit icon keeps the danger color) */
+async function updateMenuIcons(theme: string, mainColor: string) {
+  const dangerColor = theme === 'light' ? '#d32f2f' : '#ff6b6b'
+  const icons = document.querySelectorAll<HTMLImageElement>('img[data-icon]')
+  await Promise.all(
+    Array.from(icons).map(async (img) => {
+      const name = img.getAttribute('data-icon')
+      const color = img.closest('.danger') ? dangerColor : mainColor
+      try {
+        img.src = (await invoke('cmd_get_menu_icon', { name, color })) as string
+      } catch (err: any) {
+        console.warn(`Failed to load menu icon "${name}":`, err)
+      }
+    })
+  )
+}
+
 /** Setup event listeners for tray menu */
 async function setupEventListeners() {
   // Listen for language change events from backend
@@ -69,6 +120,8 @@ async function reloadTrayConfig() {
         ? config.main_color_hex_light || '#9a8a72'
         : config.main_color_hex_dark || '#0a84ff'
     document.documentElement.style.setProperty('--main-color', mainColor)
+    updateAutoItem(config)
+    await updateMenuIcons(config.theme, mainColor)
 
     // Set language using i18n system
     await setLanguage(config.language || 'en')
@@ -78,6 +131,8 @@ async function reloadTrayConfig() {
 
     // Update translations immediately
     updateTrayTranslations()
+    updateProfileItem(config)
+    updateAutoItem(config)
   } catch (err: any) {
     console.error('Config reload failed:', err)
   }
@@ -119,14 +174,18 @@ async function handleAction(action: string) {
       try {
         const config = (await invoke('cmd_get_config')) as any
         const profile = config.profile || 'Balanced'
+        const custom = (config.custom_profiles || []).find(
+          (p: any) => p.name === config.active_custom_profile
+        )
 
-        // Use areasForProfile to get correct areas
-        const areas = areasForProfile(profile)
+        // Use areasForProfile to get correct areas (custom profiles carry their own)
+        const areas = custom ? custom.areas : areasForProfile(profile)
         const areasString = areasToString(areas)
 
         await invoke('cmd_optimize_async', {
           reason: 'Manual',
           areas: areasString,
+          fromTray: true,
         })
       } catch (err: any) {
         console.error('Failed to get config for optimization, using default balanced profile:', err)
@@ -136,8 +195,20 @@ async function handleAction(action: string) {
         await invoke('cmd_optimize_async', {
           reason: 'Manual',
           areas: defaultAreasString,
+          fromTray: true,
         })
       }
+    } else if (action === 'profile') {
+      // Cycle to the next built-in or custom profile
+      const config = (await invoke('cmd_get_config')) as any
+      const names = profileNames(config)
+      const current = names.indexOf(activeProfileName(config))
+      const next = names[(current + 1) % names.length]
+      await invoke('cmd_select_profile', { name: next })
+    } else if (action === 'auto') {
+      // Pause or resume every automatic trigger, their settings stay as they are
+      const config = (await invoke('cmd_get_config')) as any
+      await invoke('cmd_set_auto_optimizer_enabled', { enabled: config.auto_optimizer_enabled === false })
     } else if (action === 'exit') {
       await invoke('cmd_exit')
     }